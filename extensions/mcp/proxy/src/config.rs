@@ -0,0 +1,98 @@
+//! Reading the upstream server a proxy instance fronts from the services tree.
+//!
+//! One file lists every proxied server, keyed by the MCP service id the
+//! platform launches the proxy under, so the `mcp_servers` registry entry and
+//! its upstream share one name. The file is read once at startup: the
+//! upstream connection is made from it and lives as long as the process.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+use systemprompt::config::ProfileBootstrap;
+use systemprompt_mcp_shared::ToolCapture;
+
+use crate::error::ProxyError;
+
+/// Relative to the profile's services directory.
+pub const PROXIES_FILE: &str = "mcp/proxies.yaml";
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProxiesFile {
+    #[serde(default)]
+    pub mcp_proxies: BTreeMap<String, UpstreamConfig>,
+}
+
+/// One proxied server: how to reach it, which of its tools only some roles
+/// may see, and how much of each call is kept.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct UpstreamConfig {
+    pub transport: UpstreamTransport,
+    /// Upstream tool name to the roles (or permissions) that may see and call
+    /// it. A tool not listed here is visible to every authenticated caller.
+    #[serde(default)]
+    pub tool_roles: BTreeMap<String, Vec<String>>,
+    #[serde(default)]
+    pub tool_capture: ToolCapture,
+}
+
+impl UpstreamConfig {
+    /// Whether a caller holding `roles` may see and call `tool`.
+    #[must_use]
+    pub fn tool_visible(&self, tool: &str, roles: &[String]) -> bool {
+        self.tool_roles
+            .get(tool)
+            .is_none_or(|allowed| allowed.iter().any(|role| roles.contains(role)))
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum UpstreamTransport {
+    /// A local process speaking MCP over its stdin and stdout.
+    Stdio {
+        command: String,
+        #[serde(default)]
+        args: Vec<String>,
+        /// Set on top of the proxy's own environment.
+        #[serde(default)]
+        env: BTreeMap<String, String>,
+    },
+    /// A remote server speaking streamable HTTP.
+    Http {
+        url: String,
+        /// Name of an environment variable holding the bearer token sent
+        /// upstream; the token itself never goes in the services tree.
+        #[serde(default)]
+        bearer_token_env: Option<String>,
+    },
+}
+
+fn proxies_path() -> Result<PathBuf, ProxyError> {
+    ProfileBootstrap::get()
+        .map(|profile| Path::new(&profile.paths.services).join(PROXIES_FILE))
+        .map_err(|e| ProxyError::Config(format!("Failed to get profile: {e}")))
+}
+
+/// The upstream registered for `service_id` in [`PROXIES_FILE`].
+pub fn load_upstream(service_id: &str) -> Result<UpstreamConfig, ProxyError> {
+    load_upstream_from(&proxies_path()?, service_id)
+}
+
+/// Exposed (behind `#[doc(hidden)]`) so the external test workspace can load
+/// a fixture file without a profile; not part of the public API.
+#[doc(hidden)]
+pub fn load_upstream_from(path: &Path, service_id: &str) -> Result<UpstreamConfig, ProxyError> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| ProxyError::Config(format!("Failed to read {}: {e}", path.display())))?;
+    let mut file: ProxiesFile = serde_yaml::from_str(&text)
+        .map_err(|e| ProxyError::Config(format!("Invalid YAML in {}: {e}", path.display())))?;
+    file.mcp_proxies.remove(service_id).ok_or_else(|| {
+        ProxyError::Config(format!(
+            "No upstream for '{service_id}' under mcp_proxies in {}",
+            path.display()
+        ))
+    })
+}
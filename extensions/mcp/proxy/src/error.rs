@@ -0,0 +1,42 @@
+//! Error type for the governing MCP proxy.
+
+use axum::http::StatusCode;
+use systemprompt::traits::ExtensionError;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ProxyError {
+    #[error("Configuration error: {0}")]
+    Config(String),
+
+    #[error("Upstream error: {0}")]
+    Upstream(String),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Internal error: {0}")]
+    Internal(String),
+}
+
+impl ExtensionError for ProxyError {
+    fn code(&self) -> &'static str {
+        match self {
+            Self::Config(_) => "CONFIG_ERROR",
+            Self::Upstream(_) => "UPSTREAM_ERROR",
+            Self::Io(_) => "IO_ERROR",
+            Self::Internal(_) => "INTERNAL_ERROR",
+        }
+    }
+
+    fn status(&self) -> StatusCode {
+        match self {
+            Self::Upstream(_) => StatusCode::BAD_GATEWAY,
+            Self::Config(_) | Self::Io(_) | Self::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn is_retryable(&self) -> bool {
+        matches!(self, Self::Upstream(_) | Self::Io(_))
+    }
+}
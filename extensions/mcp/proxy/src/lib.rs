@@ -0,0 +1,15 @@
+//! Governing MCP proxy for the systemprompt template.
+//!
+//! Fronts a third-party MCP server registered in [`config::PROXIES_FILE`] so
+//! its tools are reached the same way as the bundled `systemprompt` server's:
+//! behind the platform's OAuth, with per-role tool visibility, every call run
+//! through the governance chain, and `user_activity` rows for each access.
+//! [`upstream`] holds the client side of the connection; [`ProxyServer`] is
+//! the server side the platform's clients talk to.
+
+pub mod config;
+pub mod error;
+pub mod server;
+pub mod upstream;
+
+pub use server::ProxyServer;
@@ -0,0 +1,65 @@
+//! Entry point for the governing MCP proxy binary.
+//!
+//! The platform launches one proxy per proxied server, under that server's
+//! service id; the id selects the upstream in `services/mcp/proxies.yaml`.
+
+use anyhow::{Context, Result};
+use std::env;
+use std::sync::Arc;
+use systemprompt::config::{ProfileBootstrap, SecretsBootstrap, init_config};
+use systemprompt::identifiers::McpServerId;
+use systemprompt::system::AppContext;
+use systemprompt_mcp_proxy::ProxyServer;
+use systemprompt_mcp_proxy::config::load_upstream;
+use tokio::net::TcpListener;
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    systemprompt::logging::init_console_logging();
+
+    ProfileBootstrap::init().context("Failed to initialize profile")?;
+    SecretsBootstrap::init().context("Failed to initialize secrets")?;
+    init_config().context("Failed to initialize configuration")?;
+
+    let ctx = Arc::new(
+        AppContext::new()
+            .await
+            .context("Failed to initialize application context")?,
+    );
+
+    // Why: unlike the bundled server there is no sensible default — the id
+    // is what names the upstream, so a proxy without one has nothing to front.
+    let service_id = McpServerId::new(
+        env::var("MCP_SERVICE_ID").context("MCP_SERVICE_ID must name the proxied server")?,
+    );
+    let port: u16 = env::var("MCP_PORT")
+        .context("MCP_PORT must be set")?
+        .parse()
+        .context("MCP_PORT must be a port number")?;
+
+    let upstream = load_upstream(service_id.as_str())
+        .with_context(|| format!("Failed to load the upstream for '{service_id}'"))?;
+    let server = ProxyServer::new(
+        Arc::clone(ctx.db_pool()),
+        service_id.clone(),
+        Arc::clone(ctx.authz_hook()),
+        upstream,
+    );
+    let router = systemprompt::mcp::create_router(
+        server,
+        Arc::clone(ctx.mcp_session_repository()),
+        systemprompt::mcp::McpHttpConfig::default(),
+    );
+    let addr = format!("0.0.0.0:{port}");
+    let listener = TcpListener::bind(&addr).await?;
+
+    tracing::info!(
+        service_id = %service_id,
+        addr = %addr,
+        "Governing MCP proxy listening"
+    );
+
+    axum::serve(listener, router).await?;
+
+    Ok(())
+}
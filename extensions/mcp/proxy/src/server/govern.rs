@@ -0,0 +1,129 @@
+//! Running a proxied tool call through the governance chain.
+//!
+//! The call is judged exactly as the `PreToolUse` hook judges a tool call a
+//! coding agent is about to make: the same global [`GovernanceEngine`], so the
+//! secret scan, scope check, blocklist and the shared rate-limit buckets all
+//! apply, and the same `governance_decisions` audit row.
+
+use rmcp::model::CallToolRequestParams;
+use systemprompt::database::DbPool;
+use systemprompt::identifiers::{CallId, McpToolName};
+use systemprompt::models::auth::Permission;
+use systemprompt::models::execution::context::RequestContext as SysRequestContext;
+use systemprompt::security::authz::Decision;
+use systemprompt::security::policy::types::AccessScope;
+use systemprompt::security::policy::{
+    AgentScope, AuditOrigin, AuditTarget, ChainEntryOutcome, ChainEntryResult, DecisionAudit,
+    GovernanceEngine, GovernedInput, GovernedTarget, McpToolInput, PolicyContext,
+    PrincipalSnapshot, record_decision,
+};
+
+/// The name the chain sees for `tool` on `service_id`.
+///
+/// It is the `mcp__<server>__<tool>` form a coding agent reports to the hook,
+/// so one set of prefixes and blocklist patterns covers a proxied tool on
+/// either path.
+#[must_use]
+pub fn governed_tool_name(service_id: &str, tool: &str) -> String {
+    format!("mcp__{service_id}__{tool}")
+}
+
+/// The id of the first policy in `chain` that failed the call, if any.
+#[must_use]
+pub fn denying_policy(chain: &[ChainEntryOutcome]) -> Option<String> {
+    chain
+        .iter()
+        .find(|entry| matches!(entry.result, ChainEntryResult::Fail))
+        .map(|entry| entry.policy_id.as_str().to_owned())
+}
+
+fn access_scope(context: &SysRequestContext) -> AccessScope {
+    let permissions = context
+        .user
+        .as_ref()
+        .map_or(&[][..], |user| user.permissions());
+    if permissions.contains(&Permission::Admin) {
+        AccessScope::Admin
+    } else if permissions.contains(&Permission::User) {
+        AccessScope::User
+    } else {
+        AccessScope::Unknown
+    }
+}
+
+pub(super) struct Governed {
+    pub(super) decision: Decision,
+    pub(super) denied_by: Option<String>,
+}
+
+pub(super) async fn govern(
+    db_pool: &DbPool,
+    tool_name: &str,
+    request: &CallToolRequestParams,
+    context: &SysRequestContext,
+) -> Governed {
+    let user_id = context.user_id().clone();
+    let session_id = context.session_id().clone();
+    let scope = access_scope(context);
+    // JSON: MCP-protocol boundary — the upstream tool's arguments, schema-less
+    // by spec, handed to the chain as the hook hands it `tool_input`.
+    let arguments = serde_json::Value::Object(request.arguments.clone().unwrap_or_default());
+    let input = GovernedInput::tool_arguments(McpToolInput::new(arguments));
+    let call_id = CallId::generate();
+    let evaluation = GovernanceEngine::global().evaluate(&PolicyContext {
+        target: GovernedTarget::Tool {
+            tool: McpToolName::new(tool_name),
+        },
+        agent_scope: AgentScope::User {
+            user_id: user_id.clone(),
+        },
+        access_scope: scope,
+        session_id: &session_id,
+        user_id: &user_id,
+        input: &input,
+        call_id: &call_id,
+    });
+    let denied_by = denying_policy(&evaluation.chain);
+
+    let audit = DecisionAudit {
+        id: uuid::Uuid::new_v4().to_string(),
+        call_id: call_id.as_str().to_owned(),
+        origin: AuditOrigin::Governed,
+        decision: evaluation.decision.clone(),
+        principal: PrincipalSnapshot {
+            user_id,
+            session_id: session_id.clone(),
+            agent_session: None,
+            agent_id: None,
+            agent_scope: scope,
+        },
+        target: AuditTarget {
+            tool_name: tool_name.to_owned(),
+            plugin_id: None,
+        },
+        chain: evaluation.chain,
+        approver: None,
+        act_chain: Vec::new(),
+        context_id: None,
+        trace_id: None,
+    };
+    // Why: awaited rather than spawned so the decision row exists before the
+    // upstream sees the call; a failed write is logged, never a refusal.
+    if let Some(pool) = db_pool.pool() {
+        if let Err(e) = record_decision(&pool, &audit).await {
+            tracing::error!(
+                target: "governance.audit.write_failed",
+                error = %e,
+                session_id = %session_id,
+                "governance audit write failed; row dropped",
+            );
+        }
+    } else {
+        tracing::warn!("No PgPool available to record the governance decision");
+    }
+
+    Governed {
+        decision: evaluation.decision,
+        denied_by,
+    }
+}
@@ -0,0 +1,235 @@
+//! The proxy's rmcp `ServerHandler`: the upstream's tools under the
+//! platform's OAuth, filtered per role, with every call governed and audited
+//! before it is forwarded.
+//!
+//! Authentication is the registry's RBAC for this service id, as for the
+//! bundled `systemprompt` server; the governance chain lives in `govern`.
+//! Each forwarded call is captured for the Trace Explorer as the bundled
+//! server's are, under the upstream's `tool_capture`.
+
+mod govern;
+
+pub use govern::{denying_policy, governed_tool_name};
+
+use std::sync::Arc;
+
+use chrono::Utc;
+use rmcp::model::{
+    CallToolRequestParams, CallToolResponse, Implementation, ListToolsResult,
+    PaginatedRequestParams, ProtocolVersion, ServerCapabilities, ServerInfo,
+};
+use rmcp::service::{RequestContext, RoleServer, ServiceError};
+use rmcp::{ErrorData as McpError, ServerHandler};
+use systemprompt::database::DbPool;
+use systemprompt::identifiers::McpServerId;
+use systemprompt::mcp::middleware::enforce_rbac_from_registry;
+use systemprompt::models::execution::context::RequestContext as SysRequestContext;
+use systemprompt::security::authz::{Decision, SharedAuthzHook};
+use systemprompt_mcp_shared::{
+    McpAccess, ToolCall, record_mcp_access, record_mcp_access_rejected, record_mcp_tool_call,
+};
+
+use crate::config::UpstreamConfig;
+use crate::upstream::Upstream;
+use govern::govern;
+
+#[derive(Clone, Debug)]
+pub struct ProxyServer {
+    service_id: McpServerId,
+    db_pool: DbPool,
+    authz_hook: SharedAuthzHook,
+    config: Arc<UpstreamConfig>,
+    upstream: Arc<Upstream>,
+}
+
+impl ProxyServer {
+    #[must_use]
+    pub fn new(
+        db_pool: DbPool,
+        service_id: McpServerId,
+        authz_hook: SharedAuthzHook,
+        config: UpstreamConfig,
+    ) -> Self {
+        let upstream = Arc::new(Upstream::new(config.transport.clone()));
+        Self {
+            service_id,
+            db_pool,
+            authz_hook,
+            config: Arc::new(config),
+            upstream,
+        }
+    }
+
+    async fn authenticate(
+        &self,
+        tool_name: &str,
+        ctx: &RequestContext<RoleServer>,
+    ) -> Result<SysRequestContext, McpError> {
+        let access = McpAccess::new(self.service_id.as_str(), tool_name);
+        let result = enforce_rbac_from_registry(ctx, self.service_id.as_str(), &self.authz_hook)
+            .await
+            .and_then(|result| {
+                result
+                    .expect_authenticated(
+                        "BUG: proxied servers require OAuth but auth was not enforced",
+                    )
+                    .map(|authenticated| authenticated.context)
+            });
+        match result {
+            Ok(context) => {
+                record_mcp_access(&self.db_pool, context.user_id(), access, "authenticated").await;
+                Ok(context)
+            },
+            Err(e) => {
+                record_mcp_access_rejected(&self.db_pool, access, e.message.as_ref()).await;
+                Err(e)
+            },
+        }
+    }
+
+    async fn reject(&self, tool_name: &str, rule: Option<&str>, reason: &str) {
+        let access = McpAccess::new(self.service_id.as_str(), tool_name).with_rule(rule);
+        record_mcp_access_rejected(&self.db_pool, access, reason).await;
+    }
+
+    // Why: a response that is not yet complete (input required, or a task to
+    // poll) has no result to excerpt; the upstream's own follow-up carries it.
+    async fn capture(&self, call: ToolCall<'_>, response: &Result<CallToolResponse, McpError>) {
+        let outcome = match response {
+            Ok(CallToolResponse::Complete(result)) => Ok(result),
+            Ok(_) => return,
+            Err(e) => Err(e),
+        };
+        record_mcp_tool_call(&self.db_pool, &self.config.tool_capture, call, outcome).await;
+    }
+}
+
+// Why: `tool_roles` name either roles or permissions, as the bundled server's
+// command rules do, so both are offered.
+fn caller_roles(context: &SysRequestContext) -> Vec<String> {
+    context.user.as_ref().map_or_else(Vec::new, |user| {
+        user.roles()
+            .iter()
+            .cloned()
+            .chain(user.permissions().iter().map(|p| p.as_str().to_owned()))
+            .collect()
+    })
+}
+
+fn upstream_error(e: &ServiceError) -> McpError {
+    // Why: lint-ok: error-adapt — rmcp's ErrorData is a variant-less wire type
+    McpError::internal_error(format!("Upstream MCP server failed: {e}"), None)
+}
+
+impl ServerHandler for ProxyServer {
+    fn get_info(&self) -> ServerInfo {
+        ServerInfo::new(ServerCapabilities::builder().enable_tools().build())
+            .with_protocol_version(ProtocolVersion::V_2025_06_18)
+            .with_server_info(
+                Implementation::new(
+                    format!("SystemPrompt proxy ({})", self.service_id),
+                    env!("CARGO_PKG_VERSION"),
+                )
+                .with_title("SystemPrompt governed MCP proxy"),
+            )
+            .with_instructions(format!(
+                "Tools of the '{}' MCP server, governed and audited by systemprompt.io.",
+                self.service_id
+            ))
+    }
+
+    async fn list_tools(
+        &self,
+        _request: Option<PaginatedRequestParams>,
+        ctx: RequestContext<RoleServer>,
+    ) -> Result<ListToolsResult, McpError> {
+        let context = self.authenticate("tools/list", &ctx).await?;
+        let roles = caller_roles(&context);
+        let peer = self
+            .upstream
+            .peer()
+            .await
+            // Why: lint-ok: error-adapt — rmcp's ErrorData is a variant-less wire type
+            .map_err(|e| McpError::internal_error(e.to_string(), None))?;
+        let tools = peer
+            .list_all_tools()
+            .await
+            .map_err(|e| upstream_error(&e))?
+            .into_iter()
+            .filter(|tool| self.config.tool_visible(&tool.name, &roles))
+            .collect();
+        Ok(ListToolsResult::with_all_items(tools))
+    }
+
+    async fn call_tool(
+        &self,
+        mut request: CallToolRequestParams,
+        ctx: RequestContext<RoleServer>,
+    ) -> Result<CallToolResponse, McpError> {
+        let tool_name = request.name.to_string();
+        let context = self.authenticate(&tool_name, &ctx).await?;
+
+        // Why: a hidden tool answers exactly as a missing one, so the error
+        // does not tell a caller what it is not allowed to see.
+        if !self
+            .config
+            .tool_visible(&tool_name, &caller_roles(&context))
+        {
+            self.reject(
+                &tool_name,
+                Some("tool_roles"),
+                "Tool hidden from the caller's roles",
+            )
+            .await;
+            return Err(McpError::invalid_params(
+                format!("Unknown tool: '{tool_name}'"),
+                None,
+            ));
+        }
+
+        let governed_name = governed_tool_name(self.service_id.as_str(), &tool_name);
+        let governed = govern(&self.db_pool, &governed_name, &request, &context).await;
+        if let Decision::Deny { reason } = &governed.decision {
+            let reason = format!("[GOVERNANCE] {reason}");
+            self.reject(&tool_name, governed.denied_by.as_deref(), &reason)
+                .await;
+            return Err(McpError::invalid_request(reason, None));
+        }
+
+        record_mcp_access(
+            &self.db_pool,
+            context.user_id(),
+            McpAccess::new(self.service_id.as_str(), &tool_name),
+            "used",
+        )
+        .await;
+
+        let peer = self
+            .upstream
+            .peer()
+            .await
+            // Why: lint-ok: error-adapt — rmcp's ErrorData is a variant-less wire type
+            .map_err(|e| McpError::internal_error(e.to_string(), None))?;
+        // Why: the caller's progress token means nothing to the upstream, and
+        // its notifications are not relayed back.
+        request.meta = None;
+        let arguments = request.arguments.clone();
+        let started_at = Utc::now();
+        let response = tokio::select! {
+            response = peer.call_tool_once(request) => response.map_err(|e| upstream_error(&e)),
+            () = ctx.ct.cancelled() => Err(McpError::internal_error(
+                "Call cancelled by the client",
+                None,
+            )),
+        };
+        let call = ToolCall {
+            server: self.service_id.as_str(),
+            tool: &tool_name,
+            context: &context,
+            arguments: arguments.as_ref(),
+            started_at,
+        };
+        self.capture(call, &response).await;
+        response
+    }
+}
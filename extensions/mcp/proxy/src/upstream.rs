@@ -0,0 +1,127 @@
+//! The connection to the proxied MCP server.
+//!
+//! The proxy is an MCP client of its upstream. A stdio upstream is a child
+//! process owned by the connection and killed with it; an HTTP upstream is a
+//! streamable-HTTP session. A connection whose transport has closed is
+//! replaced on the next request, so an upstream that crashes or restarts
+//! costs the calls in flight rather than the proxy.
+
+use std::process::Stdio;
+use std::time::Duration;
+
+use rmcp::ServiceExt;
+use rmcp::service::{Peer, RoleClient, RunningService};
+use rmcp::transport::StreamableHttpClientTransport;
+use rmcp::transport::streamable_http_client::StreamableHttpClientTransportConfig;
+use tokio::process::{Child, Command};
+use tokio::sync::Mutex;
+
+use crate::config::UpstreamTransport;
+use crate::error::ProxyError;
+
+// Why: an upstream that never answers `initialize` would otherwise hold the
+// connection lock, and every caller queued behind it, indefinitely.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+
+struct Connection {
+    service: RunningService<RoleClient, ()>,
+    // Why: held only so the stdio child lives, and dies, with the session.
+    _child: Option<Child>,
+}
+
+pub struct Upstream {
+    transport: UpstreamTransport,
+    connection: Mutex<Option<Connection>>,
+}
+
+impl std::fmt::Debug for Upstream {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Upstream")
+            .field("transport", &self.transport)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Upstream {
+    #[must_use]
+    pub const fn new(transport: UpstreamTransport) -> Self {
+        Self {
+            transport,
+            connection: Mutex::const_new(None),
+        }
+    }
+
+    /// A peer on a live session, connecting (or reconnecting) first if need be.
+    pub async fn peer(&self) -> Result<Peer<RoleClient>, ProxyError> {
+        let mut connection = self.connection.lock().await;
+        if let Some(live) = connection.as_ref()
+            && !live.service.is_closed()
+            && !live.service.peer().is_transport_closed()
+        {
+            return Ok(live.service.peer().clone());
+        }
+        if connection.take().is_some() {
+            tracing::warn!("Upstream MCP session closed, reconnecting");
+        }
+        let fresh = tokio::time::timeout(CONNECT_TIMEOUT, connect(&self.transport))
+            .await
+            .map_err(|e| {
+                ProxyError::Upstream(format!(
+                    "No initialize response within {}s: {e}",
+                    CONNECT_TIMEOUT.as_secs()
+                ))
+            })??;
+        let peer = fresh.service.peer().clone();
+        *connection = Some(fresh);
+        drop(connection);
+        Ok(peer)
+    }
+}
+
+async fn connect(transport: &UpstreamTransport) -> Result<Connection, ProxyError> {
+    match transport {
+        UpstreamTransport::Stdio { command, args, env } => {
+            let mut child = Command::new(command)
+                .args(args)
+                .envs(env)
+                .stdin(Stdio::piped())
+                .stdout(Stdio::piped())
+                .stderr(Stdio::inherit())
+                .kill_on_drop(true)
+                .spawn()
+                .map_err(|e| ProxyError::Upstream(format!("Failed to start `{command}`: {e}")))?;
+            let (Some(stdout), Some(stdin)) = (child.stdout.take(), child.stdin.take()) else {
+                return Err(ProxyError::Internal(
+                    "Upstream process started without piped stdio".to_owned(),
+                ));
+            };
+            let service = ().serve((stdout, stdin)).await.map_err(|e| {
+                ProxyError::Upstream(format!("`{command}` did not initialize: {e}"))
+            })?;
+            Ok(Connection {
+                service,
+                _child: Some(child),
+            })
+        },
+        UpstreamTransport::Http {
+            url,
+            bearer_token_env,
+        } => {
+            let mut config = StreamableHttpClientTransportConfig::with_uri(url.as_str());
+            if let Some(name) = bearer_token_env {
+                let token = std::env::var(name).map_err(|e| {
+                    ProxyError::Config(format!("Bearer token variable {name}: {e}"))
+                })?;
+                config = config.auth_header(token);
+            }
+            let service = ()
+                .serve(StreamableHttpClientTransport::from_config(config))
+                .await
+                .map_err(|e| ProxyError::Upstream(format!("{url} did not initialize: {e}")))?;
+            Ok(Connection {
+                service,
+                _child: None,
+            })
+        },
+    }
+}
@@ -0,0 +1,162 @@
+//! Capture of what an MCP tool call was asked and answered.
+//!
+//! [`record_mcp_tool_call`] writes one `mcp_tool_captures` row per call a
+//! server ran, keyed on the caller's session and trace so the Trace Explorer
+//! shows it as a tool span beside the session's governance decisions and
+//! model requests. The arguments and an excerpt of the result go through the
+//! credential redactor first and are then cut to the server's limits.
+//!
+//! Each server configures this with a [`ToolCapture`] block in its own
+//! settings file. Opting a server or a tool out keeps the span and drops the
+//! payload. Like the access audit, a failed insert is logged and never fails
+//! the call.
+
+use chrono::{DateTime, Utc};
+use rmcp::ErrorData;
+use rmcp::model::{CallToolResult, JsonObject};
+use serde::Deserialize;
+use systemprompt::database::DbPool;
+use systemprompt::models::execution::context::RequestContext;
+use systemprompt_web_access::redact_text;
+
+use crate::repositories::{McpToolCaptureParams, insert_mcp_tool_capture};
+use crate::truncate_on_char_boundary;
+
+pub const DEFAULT_MAX_ARGUMENT_BYTES: usize = 4096;
+pub const DEFAULT_MAX_RESULT_BYTES: usize = 2048;
+
+/// How much of each tool call a server keeps.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ToolCapture {
+    /// `false` keeps no arguments or results for any of the server's tools.
+    pub enabled: bool,
+    /// Limit on the redacted arguments, serialised as JSON.
+    pub max_argument_bytes: usize,
+    /// Limit on the redacted excerpt of the result.
+    pub max_result_bytes: usize,
+    /// Tools whose arguments and results are never kept, for tools that
+    /// handle material the redactor cannot recognise.
+    pub exclude_tools: Vec<String>,
+}
+
+impl Default for ToolCapture {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_argument_bytes: DEFAULT_MAX_ARGUMENT_BYTES,
+            max_result_bytes: DEFAULT_MAX_RESULT_BYTES,
+            exclude_tools: Vec::new(),
+        }
+    }
+}
+
+/// What is kept of one call's payload.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CapturedPayload {
+    pub arguments: Option<String>,
+    pub result_excerpt: Option<String>,
+    pub truncated: bool,
+    pub redactions: u32,
+}
+
+impl ToolCapture {
+    #[must_use]
+    pub fn captures(&self, tool: &str) -> bool {
+        self.enabled && !self.exclude_tools.iter().any(|excluded| excluded == tool)
+    }
+
+    /// Redact `arguments` and `result`, then cut each to its limit. Redacting
+    /// first means a cut can never leave the head of a credential unmasked.
+    #[must_use]
+    pub fn payload(
+        &self,
+        tool: &str,
+        arguments: Option<&JsonObject>,
+        result: &str,
+    ) -> CapturedPayload {
+        if !self.captures(tool) {
+            return CapturedPayload::default();
+        }
+        let mut payload = CapturedPayload::default();
+        if let Some(json) = arguments.and_then(|arguments| serde_json::to_string(arguments).ok()) {
+            let (text, cut, redactions) = bounded(&json, self.max_argument_bytes);
+            payload.arguments = Some(text);
+            payload.truncated |= cut;
+            payload.redactions = redactions;
+        }
+        let (text, cut, redactions) = bounded(result, self.max_result_bytes);
+        payload.result_excerpt = Some(text);
+        payload.truncated |= cut;
+        payload.redactions = payload.redactions.saturating_add(redactions);
+        payload
+    }
+}
+
+fn bounded(text: &str, max_bytes: usize) -> (String, bool, u32) {
+    let (redacted, count) = redact_text(text);
+    let cut = redacted.len() > max_bytes;
+    (truncate_on_char_boundary(&redacted, max_bytes), cut, count)
+}
+
+/// The text a caller saw: the result's text blocks, or its structured
+/// content when it has none, or the error message.
+#[must_use]
+pub fn outcome_text(outcome: Result<&CallToolResult, &ErrorData>) -> String {
+    match outcome {
+        Ok(result) => {
+            let text: Vec<&str> = result
+                .content
+                .iter()
+                .filter_map(|block| block.as_text().map(|text| text.text.as_str()))
+                .collect();
+            if text.is_empty() {
+                result
+                    .structured_content
+                    .as_ref()
+                    .map(ToString::to_string)
+                    .unwrap_or_default()
+            } else {
+                text.join("\n")
+            }
+        },
+        Err(error) => error.message.to_string(),
+    }
+}
+
+/// One call a server ran, as [`record_mcp_tool_call`] stores it.
+#[derive(Debug, Clone, Copy)]
+pub struct ToolCall<'a> {
+    pub server: &'a str,
+    pub tool: &'a str,
+    pub context: &'a RequestContext,
+    pub arguments: Option<&'a JsonObject>,
+    pub started_at: DateTime<Utc>,
+}
+
+pub async fn record_mcp_tool_call(
+    pool: &DbPool,
+    capture: &ToolCapture,
+    call: ToolCall<'_>,
+    outcome: Result<&CallToolResult, &ErrorData>,
+) {
+    let Some(pg_pool) = pool.pool() else {
+        tracing::warn!("No PgPool available to record MCP tool call");
+        return;
+    };
+    let failed = outcome.is_err() || outcome.is_ok_and(|result| result.is_error == Some(true));
+    let payload = capture.payload(call.tool, call.arguments, &outcome_text(outcome));
+    let params = McpToolCaptureParams {
+        user_id: call.context.user_id(),
+        session_id: call.context.session_id(),
+        trace_id: call.context.trace_id(),
+        server: call.server,
+        tool_name: call.tool,
+        status: if failed { "error" } else { "ok" },
+        payload: &payload,
+        started_at: call.started_at,
+    };
+    if let Err(e) = insert_mcp_tool_capture(pg_pool.as_ref(), &params).await {
+        tracing::warn!(error = %e, "Failed to record MCP tool call (non-fatal)");
+    }
+}
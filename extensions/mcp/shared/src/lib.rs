@@ -1,25 +1,31 @@
 //! Shared persistence helpers for MCP extension crates.
 //!
-//! Both functions exposed here ([`record_mcp_access`] and
-//! [`record_mcp_access_rejected`]) are best-effort: they log a `tracing::warn!`
-//! and return on any DB failure, so an MCP request that has already cleared
-//! authz is never blocked by an audit-row insert. Callers do not need to
-//! propagate errors.
+//! The access audit ([`record_mcp_access`] and [`record_mcp_access_rejected`])
+//! and the call capture ([`record_mcp_tool_call`]) are best-effort: they log a
+//! `tracing::warn!` and return on any DB failure, so an MCP request that has
+//! already cleared authz is never blocked by an audit-row insert. Callers do
+//! not need to propagate errors.
 
 use serde::Serialize;
 use systemprompt::database::DbPool;
 use systemprompt::identifiers::UserId;
 
+pub mod capture;
 mod repositories;
 
+pub use capture::{ToolCall, ToolCapture, record_mcp_tool_call};
+
 /// Audit-row metadata persisted to `user_activity.metadata` for every MCP
-/// access event. `reason` is present only on rejections.
+/// access event. `reason` is present only on rejections, and `rule` only when
+/// a server-side command policy decided the call.
 #[derive(Debug, Serialize)]
 pub struct AuditMetadata {
     pub tool_name: String,
     pub server: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub reason: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rule: Option<String>,
 }
 
 use repositories::McpAccessParams;
@@ -57,13 +63,39 @@ pub fn truncate_on_char_boundary(s: &str, max_bytes: usize) -> String {
     format!("{}...", &s[..end])
 }
 
+/// The call an audit row is about: which tool on which server, and the
+/// command-policy rule that decided it, if one did.
+#[derive(Debug, Clone, Copy)]
+pub struct McpAccess<'a> {
+    pub server: &'a str,
+    pub tool: &'a str,
+    pub rule: Option<&'a str>,
+}
+
+impl<'a> McpAccess<'a> {
+    #[must_use]
+    pub const fn new(server: &'a str, tool: &'a str) -> Self {
+        Self {
+            server,
+            tool,
+            rule: None,
+        }
+    }
+
+    #[must_use]
+    pub const fn with_rule(mut self, rule: Option<&'a str>) -> Self {
+        self.rule = rule;
+        self
+    }
+}
+
 pub async fn record_mcp_access(
     pool: &DbPool,
     user_id: &UserId,
-    server: &str,
-    tool: &str,
+    access: McpAccess<'_>,
     action: &str,
 ) {
+    let McpAccess { server, tool, rule } = access;
     let Some(pg_pool) = pool.pool() else {
         tracing::warn!("No PgPool available to record MCP access event");
         return;
@@ -83,6 +115,7 @@ pub async fn record_mcp_access(
         tool_name: tool.to_owned(),
         server: server.to_owned(),
         reason: None,
+        rule: rule.map(str::to_owned),
     };
 
     let params = McpAccessParams {
@@ -99,7 +132,8 @@ pub async fn record_mcp_access(
     }
 }
 
-pub async fn record_mcp_access_rejected(pool: &DbPool, server: &str, tool: &str, reason: &str) {
+pub async fn record_mcp_access_rejected(pool: &DbPool, access: McpAccess<'_>, reason: &str) {
+    let McpAccess { server, tool, rule } = access;
     let Some(pg_pool) = pool.pool() else {
         tracing::warn!("No PgPool available to record MCP access rejection");
         return;
@@ -110,6 +144,7 @@ pub async fn record_mcp_access_rejected(pool: &DbPool, server: &str, tool: &str,
         tool_name: tool.to_owned(),
         server: server.to_owned(),
         reason: Some(reason.to_owned()),
+        rule: rule.map(str::to_owned),
     };
 
     let anonymous_user_id = match find_anonymous_user_id(pg_pool.as_ref()).await {
@@ -1,10 +1,12 @@
 //! Database access shared by the MCP server extensions.
 
+use chrono::{DateTime, Utc};
 use sqlx::PgPool;
 use sqlx::types::Json;
-use systemprompt::identifiers::UserId;
+use systemprompt::identifiers::{SessionId, TraceId, UserId};
 
 use crate::AuditMetadata;
+use crate::capture::CapturedPayload;
 
 #[derive(Debug)]
 pub(crate) struct McpAccessParams<'a> {
@@ -66,3 +68,40 @@ pub(crate) async fn insert_mcp_access_rejection(
     .await?;
     Ok(())
 }
+
+#[derive(Debug)]
+pub(crate) struct McpToolCaptureParams<'a> {
+    pub user_id: &'a UserId,
+    pub session_id: &'a SessionId,
+    pub trace_id: &'a TraceId,
+    pub server: &'a str,
+    pub tool_name: &'a str,
+    pub status: &'a str,
+    pub payload: &'a CapturedPayload,
+    pub started_at: DateTime<Utc>,
+}
+
+pub(crate) async fn insert_mcp_tool_capture(
+    pool: &PgPool,
+    params: &McpToolCaptureParams<'_>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r"INSERT INTO mcp_tool_captures (id, user_id, session_id, trace_id, server, tool_name, status,
+                                         arguments, result_excerpt, truncated, redactions, started_at, ended_at)
+          VALUES (gen_random_uuid()::TEXT, $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, NOW())",
+        params.user_id.as_str(),
+        params.session_id.as_str(),
+        params.trace_id.as_str(),
+        params.server,
+        params.tool_name,
+        params.status,
+        params.payload.arguments.as_deref(),
+        params.payload.result_excerpt.as_deref(),
+        params.payload.truncated,
+        i32::try_from(params.payload.redactions).unwrap_or(i32::MAX),
+        params.started_at,
+    )
+    .execute(pool)
+    .await?;
+    Ok(())
+}
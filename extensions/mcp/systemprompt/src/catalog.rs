@@ -0,0 +1,231 @@
+//! Skills, agents and marketplaces read from the services tree, the same
+//! sources the `core skills` and `admin agents` CLI commands read.
+
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::path::{Path, PathBuf};
+
+use systemprompt::config::ProfileBootstrap;
+use systemprompt::identifiers::MarketplaceId;
+use systemprompt::loader::ConfigLoader;
+use systemprompt::models::services::{ComponentSource, MarketplaceAccess, MarketplaceConfig};
+use systemprompt::models::{DiskSkillConfig, SKILL_CONFIG_FILENAME, strip_frontmatter};
+
+use crate::error::SystempromptToolError;
+
+#[derive(Debug, Clone)]
+pub struct SkillEntry {
+    pub id: String,
+    pub config: DiskSkillConfig,
+    pub config_path: PathBuf,
+}
+
+#[derive(Debug, Clone)]
+pub struct AgentEntry {
+    pub name: String,
+    pub display_name: String,
+    pub description: String,
+    pub enabled: bool,
+    pub provider: Option<String>,
+    pub model: Option<String>,
+    pub mcp_servers: Vec<String>,
+    pub skills: Vec<String>,
+}
+
+/// The skills one marketplace ships, with the access block its members inherit.
+#[derive(Debug, Clone)]
+pub struct MarketplaceSkills {
+    pub id: MarketplaceId,
+    pub access: MarketplaceAccess,
+    pub skills: Vec<String>,
+}
+
+/// The skills tree and the marketplaces shipping from it, read once for a
+/// call that both lists skills and decides which the caller may see.
+#[derive(Debug, Clone)]
+pub struct SkillCatalog {
+    pub root: PathBuf,
+    pub skills: Vec<SkillEntry>,
+    pub marketplaces: Vec<MarketplaceSkills>,
+}
+
+impl SkillCatalog {
+    /// Every skill under the profile's skills directory, and every enabled
+    /// marketplace's members among them.
+    pub fn load() -> Result<Self, SystempromptToolError> {
+        let root = skills_path()?;
+        let skills = list_skills_in(&root)?;
+        let marketplaces = list_marketplace_skills(&skills)?;
+        Ok(Self {
+            root,
+            skills,
+            marketplaces,
+        })
+    }
+}
+
+fn skills_path() -> Result<PathBuf, SystempromptToolError> {
+    ProfileBootstrap::get()
+        .map(|profile| PathBuf::from(profile.paths.skills()))
+        .map_err(|e| SystempromptToolError::Internal(format!("Failed to get profile: {e}")))
+}
+
+fn read_skill(dir: &Path) -> Result<Option<SkillEntry>, SystempromptToolError> {
+    let config_path = dir.join(SKILL_CONFIG_FILENAME);
+    if !config_path.is_file() {
+        return Ok(None);
+    }
+    let Some(id) = dir.file_name().and_then(|n| n.to_str()) else {
+        return Ok(None);
+    };
+    let text = std::fs::read_to_string(&config_path)?;
+    let config: DiskSkillConfig = serde_yaml::from_str(&text).map_err(|e| {
+        SystempromptToolError::Internal(format!("Invalid YAML in {}: {e}", config_path.display()))
+    })?;
+    Ok(Some(SkillEntry {
+        id: id.to_owned(),
+        config,
+        config_path,
+    }))
+}
+
+/// Every skill directory under `root` with a parseable config, sorted by id.
+/// A skill whose config does not parse is logged and left out rather than
+/// failing the list.
+///
+/// Exposed (behind `#[doc(hidden)]`) so the external test workspace can list
+/// a fixture directory without a profile; not part of the public API.
+#[doc(hidden)]
+pub fn list_skills_in(root: &Path) -> Result<Vec<SkillEntry>, SystempromptToolError> {
+    if !root.exists() {
+        return Ok(Vec::new());
+    }
+    let mut skills = Vec::new();
+    for entry in std::fs::read_dir(root)? {
+        let dir = entry?.path();
+        if !dir.is_dir() {
+            continue;
+        }
+        match read_skill(&dir) {
+            Ok(Some(skill)) => skills.push(skill),
+            Ok(None) => {},
+            Err(e) => {
+                tracing::warn!(path = %dir.display(), error = %e, "Skipping unreadable skill");
+            },
+        }
+    }
+    skills.sort_by(|a, b| a.id.cmp(&b.id));
+    Ok(skills)
+}
+
+/// One skill under `root` and its instruction body with the frontmatter
+/// stripped.
+#[doc(hidden)]
+pub fn find_skill_in(
+    root: &Path,
+    id: &str,
+) -> Result<Option<(SkillEntry, String)>, SystempromptToolError> {
+    // Why: the id becomes a path segment, so anything that could climb out of
+    // the skills directory is treated as not found.
+    if id.is_empty() || id.contains(['/', '\\']) || id.starts_with('.') {
+        return Ok(None);
+    }
+    let dir = root.join(id);
+    let Some(skill) = read_skill(&dir)? else {
+        return Ok(None);
+    };
+    let content_path = dir.join(skill.config.content_file());
+    let instructions = if content_path.is_file() {
+        strip_frontmatter(&std::fs::read_to_string(&content_path)?)
+    } else {
+        String::new()
+    };
+    Ok(Some((skill, instructions)))
+}
+
+/// A hash of every file under the skills directory with its size and
+/// modification time; it changes whenever a skill is added, removed or edited.
+pub fn skills_fingerprint() -> Result<u64, SystempromptToolError> {
+    skills_fingerprint_in(&skills_path()?)
+}
+
+#[doc(hidden)]
+pub fn skills_fingerprint_in(root: &Path) -> Result<u64, SystempromptToolError> {
+    let mut files = Vec::new();
+    if root.exists() {
+        for dir in std::fs::read_dir(root)? {
+            let dir = dir?.path();
+            if !dir.is_dir() {
+                continue;
+            }
+            for file in std::fs::read_dir(&dir)? {
+                let file = file?;
+                let metadata = file.metadata()?;
+                files.push((file.path(), metadata.len(), metadata.modified()?));
+            }
+        }
+    }
+    files.sort();
+    let mut hasher = DefaultHasher::new();
+    Hash::hash_slice(&files, &mut hasher);
+    Ok(hasher.finish())
+}
+
+pub fn list_agents() -> Result<Vec<AgentEntry>, SystempromptToolError> {
+    let config = ConfigLoader::load().map_err(|e| {
+        SystempromptToolError::Internal(format!("Failed to load services configuration: {e}"))
+    })?;
+    let mut agents: Vec<AgentEntry> = config
+        .agents
+        .into_iter()
+        .map(|(name, agent)| AgentEntry {
+            name,
+            display_name: agent.card.display_name,
+            description: agent.card.description,
+            enabled: agent.enabled,
+            provider: agent.metadata.provider,
+            model: agent.metadata.model,
+            mcp_servers: agent.metadata.mcp_servers.include,
+            skills: agent.metadata.skills.include,
+        })
+        .collect();
+    agents.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(agents)
+}
+
+/// Every enabled marketplace and the skills it ships, in id order.
+pub fn list_marketplace_skills(
+    skills: &[SkillEntry],
+) -> Result<Vec<MarketplaceSkills>, SystempromptToolError> {
+    let config = ConfigLoader::load().map_err(|e| {
+        SystempromptToolError::Internal(format!("Failed to load services configuration: {e}"))
+    })?;
+    let ids: Vec<String> = skills.iter().map(|skill| skill.id.clone()).collect();
+    let mut marketplaces: Vec<MarketplaceSkills> = config
+        .marketplaces
+        .into_values()
+        .filter(|marketplace| marketplace.enabled)
+        .map(|marketplace| MarketplaceSkills {
+            skills: marketplace_members(&marketplace, &ids),
+            id: marketplace.id,
+            access: marketplace.access,
+        })
+        .collect();
+    marketplaces.sort_by(|a, b| a.id.as_str().cmp(b.id.as_str()));
+    Ok(marketplaces)
+}
+
+/// The ids among `skills` that `marketplace` ships: its `include` list, or
+/// every skill for an instance-sourced marketplace, less its `exclude` list.
+#[doc(hidden)]
+pub fn marketplace_members(marketplace: &MarketplaceConfig, skills: &[String]) -> Vec<String> {
+    let component = &marketplace.skills;
+    skills
+        .iter()
+        .filter(|id| match component.source {
+            ComponentSource::Instance => true,
+            ComponentSource::Explicit => component.include.contains(id),
+        })
+        .filter(|id| !component.exclude.contains(id))
+        .cloned()
+        .collect()
+}
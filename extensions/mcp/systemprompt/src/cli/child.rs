@@ -0,0 +1,179 @@
+//! The CLI as a child process the server can stop.
+//!
+//! The child leads its own process group, so a timeout or a client
+//! cancellation kills everything it started rather than only the direct
+//! child, and a grandchild holding the output pipes open cannot keep the call
+//! alive. Stderr is read line by line so each line can be forwarded as
+//! progress while the command runs.
+
+use std::process::{ExitStatus, Stdio};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Duration;
+
+use nix::sys::signal::{Signal, killpg};
+use nix::unistd::Pid;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, BufReader};
+use tokio::process::{Child, Command};
+use tokio::sync::mpsc::UnboundedSender;
+use tokio_util::sync::CancellationToken;
+
+// Why: only a process that left the group can hold the pipes open once the
+// group is killed, so draining them gets a short grace and no more.
+const DRAIN_GRACE: Duration = Duration::from_secs(5);
+
+pub(super) enum Finished {
+    Exited {
+        status: ExitStatus,
+        stdout: String,
+        stderr: String,
+    },
+    TimedOut {
+        stderr: String,
+    },
+    Cancelled,
+}
+
+// Why: killing on drop covers the caller abandoning the call future as well
+// as the explicit timeout and cancellation paths; a normal exit disarms it.
+struct GroupKill(Option<Pid>);
+
+impl GroupKill {
+    const fn disarm(&mut self) {
+        self.0 = None;
+    }
+}
+
+impl Drop for GroupKill {
+    fn drop(&mut self) {
+        if let Some(group) = self.0.take()
+            && let Err(e) = killpg(group, Signal::SIGKILL)
+        {
+            tracing::warn!(pgid = %group, error = %e, "Failed to kill CLI process group");
+        }
+    }
+}
+
+async fn read_all(pipe: Option<impl AsyncRead + Unpin>) -> String {
+    let mut bytes = Vec::new();
+    if let Some(mut pipe) = pipe
+        && let Err(e) = pipe.read_to_end(&mut bytes).await
+    {
+        tracing::warn!(error = %e, "Failed to read CLI stdout");
+    }
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+async fn forward_lines(
+    pipe: Option<impl AsyncRead + Unpin>,
+    mut progress: Option<UnboundedSender<String>>,
+    collected: Arc<Mutex<String>>,
+) {
+    let Some(pipe) = pipe else {
+        return;
+    };
+    let mut lines = BufReader::new(pipe).lines();
+    while let Ok(Some(line)) = lines.next_line().await {
+        if let Some(sender) = &progress
+            && sender.send(line.clone()).is_err()
+        {
+            progress = None;
+        }
+        let mut collected = collected.lock().unwrap_or_else(PoisonError::into_inner);
+        collected.push_str(&line);
+        collected.push('\n');
+    }
+}
+
+async fn reap(child: &mut Child) {
+    if let Err(e) = child.wait().await {
+        tracing::warn!(error = %e, "Failed to reap the killed CLI process");
+    }
+}
+
+fn snapshot(collected: &Mutex<String>) -> String {
+    collected
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone()
+}
+
+enum Stop {
+    Exited(std::io::Result<ExitStatus>, String),
+    TimedOut,
+    Cancelled,
+}
+
+pub(super) async fn run(
+    mut command: Command,
+    timeout: Duration,
+    cancel: &CancellationToken,
+    progress: Option<&UnboundedSender<String>>,
+) -> std::io::Result<Finished> {
+    command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .process_group(0)
+        .kill_on_drop(true);
+    let mut child = command.spawn()?;
+    let mut group = GroupKill(
+        child
+            .id()
+            .and_then(|pid| i32::try_from(pid).ok())
+            .map(Pid::from_raw),
+    );
+
+    let mut stdout = tokio::spawn(read_all(child.stdout.take()));
+    let stderr_text = Arc::new(Mutex::new(String::new()));
+    let mut stderr = tokio::spawn(forward_lines(
+        child.stderr.take(),
+        progress.cloned(),
+        Arc::clone(&stderr_text),
+    ));
+
+    // Why: the pipes are drained inside the timed branch, so a descendant that
+    // outlives the child and keeps them open still counts against the limit.
+    let completed = async {
+        let status = child.wait().await;
+        let (out, _) = tokio::join!(&mut stdout, &mut stderr);
+        Stop::Exited(status, out.unwrap_or_default())
+    };
+    let stop = tokio::select! {
+        stop = completed => stop,
+        () = tokio::time::sleep(timeout) => Stop::TimedOut,
+        () = cancel.cancelled() => Stop::Cancelled,
+    };
+
+    match stop {
+        Stop::Exited(status, stdout) => {
+            group.disarm();
+            Ok(Finished::Exited {
+                status: status?,
+                stdout,
+                stderr: snapshot(&stderr_text),
+            })
+        },
+        Stop::TimedOut => {
+            drop(group);
+            reap(&mut child).await;
+            stdout.abort();
+            // Why: a handle the timed branch already drove to completion must
+            // not be polled again, and only an unfinished one has lines left.
+            if !stderr.is_finished()
+                && tokio::time::timeout(DRAIN_GRACE, &mut stderr)
+                    .await
+                    .is_err()
+            {
+                tracing::warn!("CLI stderr still open after its process group was killed");
+            }
+            Ok(Finished::TimedOut {
+                stderr: snapshot(&stderr_text),
+            })
+        },
+        Stop::Cancelled => {
+            drop(group);
+            reap(&mut child).await;
+            Ok(Finished::Cancelled)
+        },
+    }
+}
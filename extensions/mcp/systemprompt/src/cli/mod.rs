@@ -0,0 +1,179 @@
+//! Runs the `systemprompt` CLI on behalf of an MCP tool call.
+//!
+//! Models routinely append flags the CLI does not accept; those are stripped
+//! before exec rather than surfaced as a usage error the model cannot act on.
+//! Every run is bounded by the command's timeout and the caller's
+//! cancellation; see `child` for how the process tree is stopped.
+
+mod child;
+
+use crate::policy::{CommandTimeouts, command_path};
+use crate::tools::CliOutput;
+use child::Finished;
+use rmcp::ErrorData as McpError;
+use std::path::PathBuf;
+use std::time::Duration;
+use systemprompt::config::ProfileBootstrap;
+use systemprompt::models::artifacts::{CliArtifact, TextArtifact};
+use tokio::process::Command;
+use tokio::sync::mpsc::UnboundedSender;
+use tokio_util::sync::CancellationToken;
+
+// Why: the end of stderr is where a stuck command says what it waits on;
+// the whole stream could be megabytes.
+const STDERR_TAIL_LINES: usize = 20;
+
+/// What bounds one tool call beyond its arguments.
+///
+/// That is the limits commands run under, the client's cancellation, and
+/// where stderr lines go as progress. The default never cancels, reports no
+/// progress and uses the default timeouts.
+#[derive(Debug, Clone, Default)]
+pub struct CallControl {
+    pub cancel: CancellationToken,
+    pub progress: Option<UnboundedSender<String>>,
+    pub timeouts: CommandTimeouts,
+}
+
+pub(crate) fn get_cli_path() -> Result<PathBuf, McpError> {
+    if let Ok(path) = std::env::var("SYSTEMPROMPT_CLI_PATH") {
+        return Ok(PathBuf::from(path));
+    }
+
+    let profile = ProfileBootstrap::get()
+        // Why: lint-ok: error-adapt — rmcp's ErrorData is a variant-less wire type
+        .map_err(|e| McpError::internal_error(format!("Failed to get profile: {e}"), None))?;
+
+    Ok(PathBuf::from(&profile.paths.bin).join("systemprompt"))
+}
+
+pub(crate) fn workdir() -> PathBuf {
+    if let Ok(path) = std::env::var("SYSTEMPROMPT_WORKDIR") {
+        return PathBuf::from(path);
+    }
+
+    ProfileBootstrap::get().map_or_else(|_| PathBuf::from("."), |p| PathBuf::from(&p.paths.system))
+}
+
+/// Strip CLI flags that models routinely hallucinate onto `systemprompt`
+/// invocations (output-format toggles the gateway sets itself). Exposed behind
+/// `#[doc(hidden)]` so the external test workspace can assert the filter set;
+/// not part of the public API.
+#[doc(hidden)]
+pub fn filter_hallucinated_args(args: Vec<String>) -> Vec<String> {
+    const HALLUCINATED_ARGS: &[&str] = &["--json", "--output-format", "--format"];
+
+    args.into_iter()
+        .filter(|arg| !HALLUCINATED_ARGS.contains(&arg.as_str()))
+        .collect()
+}
+
+// Why: the command policy judges the vector this returns, so splitting once
+// here keeps what is checked identical to what is executed.
+pub(crate) fn split_command(command: &str) -> Result<Vec<String>, McpError> {
+    // Why: lint-ok: error-adapt — rmcp's ErrorData is a variant-less wire type
+    let args = shell_words::split(command).map_err(|e| {
+        McpError::invalid_params(format!("Failed to parse command arguments: {e}"), None)
+    })?;
+    Ok(filter_hallucinated_args(args))
+}
+
+fn stderr_tail(stderr: &str) -> String {
+    let lines: Vec<&str> = stderr.lines().collect();
+    lines[lines.len().saturating_sub(STDERR_TAIL_LINES)..].join("\n")
+}
+
+fn timed_out(command: &str, limit: Duration, stderr: &str) -> McpError {
+    let message = format!(
+        "Command timed out after {}s and its process tree was stopped: `{command}`",
+        limit.as_secs()
+    );
+    let tail = stderr_tail(stderr);
+    let body = if tail.is_empty() {
+        format!("{message}\n\nThe command wrote nothing to stderr.")
+    } else {
+        format!("{message}\n\nLast stderr output:\n{tail}")
+    };
+    let artifact = CliArtifact::text(TextArtifact::new(&body).with_title("Command timed out"));
+    // JSON: protocol boundary
+    let data = serde_json::to_value(&artifact).ok();
+    // Why: lint-ok: error-adapt — rmcp's ErrorData is a variant-less wire type
+    McpError::internal_error(message, data)
+}
+
+pub(crate) async fn execute(
+    command: &str,
+    auth_token: &str,
+    control: &CallControl,
+) -> Result<CliOutput, McpError> {
+    let cli_path = get_cli_path()?;
+    let workdir = workdir();
+    let args = split_command(command)?;
+    let limit = control.timeouts.for_path(&command_path(&args));
+
+    tracing::info!(
+        cli_path = %cli_path.display(),
+        workdir = %workdir.display(),
+        args = ?args,
+        timeout_secs = limit.as_secs(),
+        "Executing CLI command"
+    );
+
+    let mut process = Command::new(&cli_path);
+    process
+        .args(&args)
+        .env("SYSTEMPROMPT_NON_INTERACTIVE", "1")
+        .env("SYSTEMPROMPT_OUTPUT_FORMAT", "json")
+        .env("SYSTEMPROMPT_AUTH_TOKEN", auth_token)
+        .current_dir(workdir);
+
+    let finished = child::run(process, limit, &control.cancel, control.progress.as_ref())
+        .await
+        // Why: lint-ok: error-adapt — rmcp's ErrorData is a variant-less wire type
+        .map_err(|e| {
+            McpError::internal_error(format!("Failed to execute CLI command: {e}"), None)
+        })?;
+
+    let (status, stdout, stderr) = match finished {
+        Finished::Exited {
+            status,
+            stdout,
+            stderr,
+        } => (status, stdout, stderr),
+        Finished::TimedOut { stderr } => {
+            tracing::warn!(
+                command,
+                timeout_secs = limit.as_secs(),
+                "CLI command timed out"
+            );
+            return Err(timed_out(command, limit, &stderr));
+        },
+        Finished::Cancelled => {
+            tracing::info!(command, "CLI command cancelled by the client");
+            // Why: lint-ok: error-adapt — rmcp's ErrorData is a variant-less wire type
+            return Err(McpError::internal_error(
+                format!(
+                    "Command cancelled by the client and its process tree was stopped: `{command}`"
+                ),
+                None,
+            ));
+        },
+    };
+    let exit_code = status.code().unwrap_or(-1);
+    let success = status.success();
+
+    tracing::info!(
+        exit_code = exit_code,
+        success = success,
+        stdout_len = stdout.len(),
+        stderr_len = stderr.len(),
+        "CLI command completed"
+    );
+
+    Ok(CliOutput {
+        stdout,
+        stderr,
+        exit_code,
+        success,
+    })
+}
@@ -18,6 +18,9 @@ pub enum SystempromptToolError {
     #[error("Serialization error: {0}")]
     Serialization(#[from] serde_json::Error),
 
+    #[error("Unauthorized: {0}")]
+    Unauthorized(String),
+
     #[error("Internal error: {0}")]
     Internal(String),
 }
@@ -29,6 +32,7 @@ impl ExtensionError for SystempromptToolError {
             Self::NotFound(_) => "NOT_FOUND",
             Self::Io(_) => "IO_ERROR",
             Self::Serialization(_) => "SERIALIZATION_ERROR",
+            Self::Unauthorized(_) => "UNAUTHORIZED",
             Self::Internal(_) => "INTERNAL_ERROR",
         }
     }
@@ -37,6 +41,7 @@ impl ExtensionError for SystempromptToolError {
         match self {
             Self::NotFound(_) => StatusCode::NOT_FOUND,
             Self::CommandFailed(_) => StatusCode::BAD_REQUEST,
+            Self::Unauthorized(_) => StatusCode::UNAUTHORIZED,
             Self::Io(_) | Self::Serialization(_) | Self::Internal(_) => {
                 StatusCode::INTERNAL_SERVER_ERROR
             },
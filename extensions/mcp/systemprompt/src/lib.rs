@@ -1,17 +1,24 @@
 //! MCP server crate for the systemprompt template.
 //!
 //! Implements the `systemprompt` MCP server that ships with the demo plugin.
-//! Tools are defined in [`tools`] and exposed through [`SystempromptServer`];
-//! errors normalise on [`error::SystempromptToolError`]. The `main` binary is a
+//! Tools are defined in [`tools`] and exposed through [`SystempromptServer`]:
+//! one that runs any CLI command, and typed tools that read [`catalog`] and
+//! [`repositories`] in-process. Which of them a caller may run is decided by
+//! the command rules in [`policy`]. Errors normalise on
+//! [`error::SystempromptToolError`]. The `main` binary is a
 //! thin `tokio::main` shell that builds a [`SystempromptServer`] and serves it
-//! over stdio.
+//! over streamable HTTP, or over stdio through [`stdio`] with `--stdio`.
 
+pub mod catalog;
 mod cli;
 pub mod error;
+pub mod policy;
 
 #[doc(hidden)]
 pub use cli::filter_hallucinated_args;
+pub mod repositories;
 pub mod server;
+pub mod stdio;
 pub mod tools;
 
 pub use server::SystempromptServer;
@@ -1,21 +1,29 @@
 //! Entry point for the `systemprompt` MCP server binary.
+//!
+//! Serves streamable HTTP on `MCP_PORT` by default. With `--stdio` it serves
+//! one client over stdin/stdout instead, authenticated by the personal access
+//! token in `SYSTEMPROMPT_MCP_TOKEN`, and exits when stdin closes.
 
-use anyhow::{Context, Result};
+use anyhow::{Context, Result, bail};
 use std::env;
 use std::sync::Arc;
 use systemprompt::config::{ProfileBootstrap, SecretsBootstrap, init_config};
 use systemprompt::identifiers::McpServerId;
 use systemprompt::system::AppContext;
 use systemprompt_mcp_agent::SystempromptServer;
+use systemprompt_mcp_agent::stdio::{PatCredential, PatTransport, TOKEN_ENV};
 use tokio::net::TcpListener;
 
 const DEFAULT_SERVICE_ID: &str = "systemprompt";
 const DEFAULT_PORT: u16 = 5010;
+const STDIO_FLAG: &str = "--stdio";
 
 #[tokio::main]
 async fn main() -> Result<()> {
     systemprompt::logging::init_console_logging();
 
+    let stdio = env::args().skip(1).any(|arg| arg == STDIO_FLAG);
+
     ProfileBootstrap::init().context("Failed to initialize profile")?;
     SecretsBootstrap::init().context("Failed to initialize secrets")?;
     init_config().context("Failed to initialize configuration")?;
@@ -37,6 +45,60 @@ async fn main() -> Result<()> {
         McpServerId::new,
     );
 
+    let server = SystempromptServer::new(
+        Arc::clone(ctx.db_pool()),
+        service_id.clone(),
+        Arc::clone(ctx.authz_hook()),
+    )
+    .context("Failed to initialize SystempromptServer")?;
+
+    if stdio {
+        serve_stdio(&ctx, server, service_id).await
+    } else {
+        serve_http(&ctx, server, &service_id).await
+    }
+}
+
+async fn serve_stdio(
+    ctx: &AppContext,
+    server: SystempromptServer,
+    service_id: McpServerId,
+) -> Result<()> {
+    let Ok(token) = env::var(TOKEN_ENV) else {
+        bail!("{STDIO_FLAG} needs a personal access token in {TOKEN_ENV}");
+    };
+    let pool = ctx
+        .db_pool()
+        .pool()
+        .context("No PostgreSQL pool is available")?;
+    let credential = PatCredential::resolve(pool, token, service_id.clone())
+        .await
+        .context("Failed to authenticate the personal access token")?;
+
+    tracing::info!(
+        service_id = %service_id,
+        session_id = %credential.session_id(),
+        "SystemPrompt MCP server serving stdio"
+    );
+
+    let (stdin, stdout) = rmcp::transport::stdio();
+    let transport = PatTransport::new(
+        rmcp::transport::async_rw::AsyncRwTransport::new_server(stdin, stdout),
+        Arc::new(credential),
+    );
+    let running = rmcp::ServiceExt::serve(server, transport)
+        .await
+        .context("Failed to start the stdio session")?;
+    let reason = running.waiting().await?;
+    tracing::info!(?reason, "stdio session ended");
+    Ok(())
+}
+
+async fn serve_http(
+    ctx: &AppContext,
+    server: SystempromptServer,
+    service_id: &McpServerId,
+) -> Result<()> {
     let port = env::var("MCP_PORT").map_or_else(
         |_| {
             tracing::warn!(default = DEFAULT_PORT, "MCP_PORT not set, using default");
@@ -50,12 +112,6 @@ async fn main() -> Result<()> {
         },
     );
 
-    let server = SystempromptServer::new(
-        Arc::clone(ctx.db_pool()),
-        service_id.clone(),
-        Arc::clone(ctx.authz_hook()),
-    )
-    .context("Failed to initialize SystempromptServer")?;
     let router = systemprompt::mcp::create_router(
         server,
         Arc::clone(ctx.mcp_session_repository()),
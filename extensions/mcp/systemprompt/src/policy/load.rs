@@ -0,0 +1,59 @@
+//! Reading the command rules and limits from the services tree.
+//!
+//! The file is read on every call, as the registry entry is, so an edit takes
+//! effect without restarting the server.
+
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+use systemprompt::config::ProfileBootstrap;
+use systemprompt_mcp_shared::ToolCapture;
+
+use super::{CommandPolicy, CommandTimeouts};
+use crate::error::SystempromptToolError;
+
+/// Relative to the profile's services directory.
+pub const POLICY_FILE: &str = "mcp/systemprompt-commands.yaml";
+
+/// Everything [`POLICY_FILE`] configures: who may run which commands, for how
+/// long, and how much of each call is kept.
+#[derive(Debug, Clone, Deserialize)]
+pub struct CommandSettings {
+    pub command_policy: CommandPolicy,
+    #[serde(default)]
+    pub command_timeouts: CommandTimeouts,
+    #[serde(default)]
+    pub tool_capture: ToolCapture,
+}
+
+fn policy_path() -> Result<PathBuf, SystempromptToolError> {
+    ProfileBootstrap::get()
+        .map(|profile| Path::new(&profile.paths.services).join(POLICY_FILE))
+        .map_err(|e| SystempromptToolError::Internal(format!("Failed to get profile: {e}")))
+}
+
+/// The settings from [`POLICY_FILE`].
+///
+/// A missing file means [`CommandPolicy::admin_only`] and the default
+/// timeouts and capture limits. A file that exists but does not parse is an
+/// error: falling back would silently change who can run what.
+pub fn load_settings() -> Result<CommandSettings, SystempromptToolError> {
+    load_settings_from(&policy_path()?)
+}
+
+/// Exposed (behind `#[doc(hidden)]`) so the external test workspace can load
+/// a fixture file without a profile; not part of the public API.
+#[doc(hidden)]
+pub fn load_settings_from(path: &Path) -> Result<CommandSettings, SystempromptToolError> {
+    if !path.exists() {
+        return Ok(CommandSettings {
+            command_policy: CommandPolicy::admin_only(),
+            command_timeouts: CommandTimeouts::default(),
+            tool_capture: ToolCapture::default(),
+        });
+    }
+    let text = std::fs::read_to_string(path)?;
+    serde_yaml::from_str(&text).map_err(|e| {
+        SystempromptToolError::Internal(format!("Invalid YAML in {}: {e}", path.display()))
+    })
+}
@@ -0,0 +1,135 @@
+//! Command-level access policy for the `systemprompt` server.
+//!
+//! The registry's OAuth scope only says who may reach the server at all; this
+//! says which commands each role may run once there. A CLI call is judged on
+//! its subcommand path (the leading words before the first flag) and a typed
+//! tool on its name, so one rule set covers both. The same file sets how long
+//! each CLI command may run.
+
+mod load;
+mod timeouts;
+
+pub use load::{CommandSettings, POLICY_FILE, load_settings, load_settings_from};
+pub use timeouts::{CommandTimeouts, DEFAULT_TIMEOUT_SECS, TimeoutOverride};
+
+use serde::Deserialize;
+
+/// A pattern is space-separated words. `*` matches any one word, and a
+/// trailing `*` matches whatever remains, including nothing: `admin *`
+/// covers `admin` and every command under it.
+#[must_use]
+pub fn pattern_matches(pattern: &str, path: &[String]) -> bool {
+    let words: Vec<&str> = pattern.split_whitespace().collect();
+    let Some((last, init)) = words.split_last() else {
+        return path.is_empty();
+    };
+    if *last == "*" {
+        return path.len() >= init.len() && words_match(init, &path[..init.len()]);
+    }
+    path.len() == words.len() && words_match(&words, path)
+}
+
+fn words_match(words: &[&str], path: &[String]) -> bool {
+    words
+        .iter()
+        .zip(path)
+        .all(|(word, segment)| *word == "*" || *word == segment)
+}
+
+/// The leading words of a CLI command, up to the first flag. Positional
+/// arguments such as a skill id are part of the path, so `core skills show *`
+/// can allow showing any skill.
+#[must_use]
+pub fn command_path(args: &[String]) -> Vec<String> {
+    args.iter()
+        .take_while(|arg| !arg.starts_with('-'))
+        .cloned()
+        .collect()
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CommandRule {
+    pub id: String,
+    pub roles: Vec<String>,
+    #[serde(default)]
+    pub allow: Vec<String>,
+    #[serde(default)]
+    pub deny: Vec<String>,
+}
+
+impl CommandRule {
+    fn applies_to(&self, roles: &[String]) -> bool {
+        self.roles.iter().any(|r| r == "*" || roles.contains(r))
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct CommandPolicy {
+    #[serde(default)]
+    pub rules: Vec<CommandRule>,
+}
+
+/// What the policy decided and which rule decided it. `rule` is `None` only
+/// for the default deny when no rule matched.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PolicyDecision {
+    pub allowed: bool,
+    pub rule: Option<String>,
+    pub pattern: Option<String>,
+}
+
+impl PolicyDecision {
+    /// The audit text: the rule, the pattern it matched, and the path.
+    #[must_use]
+    pub fn describe(&self, path: &[String]) -> String {
+        let verb = if self.allowed { "allows" } else { "denies" };
+        let command = path.join(" ");
+        match (&self.rule, &self.pattern) {
+            (Some(rule), Some(pattern)) => {
+                format!("rule '{rule}' {verb} '{command}' (matched '{pattern}')")
+            },
+            _ => format!("no command rule allows '{command}'"),
+        }
+    }
+}
+
+impl CommandPolicy {
+    /// Admins may run anything; everyone else nothing. This is what the
+    /// server did before command rules existed, and what it falls back to
+    /// when the policy file is missing.
+    #[must_use]
+    pub fn admin_only() -> Self {
+        Self {
+            rules: vec![CommandRule {
+                id: "admin".to_owned(),
+                roles: vec!["admin".to_owned()],
+                allow: vec!["*".to_owned()],
+                deny: Vec::new(),
+            }],
+        }
+    }
+
+    /// Rules are tried in file order and the first that applies to one of
+    /// `roles` and matches `path` decides; within a rule, `deny` is checked
+    /// before `allow`. Nothing matching is a deny.
+    #[must_use]
+    pub fn evaluate(&self, roles: &[String], path: &[String]) -> PolicyDecision {
+        for rule in self.rules.iter().filter(|r| r.applies_to(roles)) {
+            let verdicts = [(false, &rule.deny), (true, &rule.allow)];
+            for (allowed, patterns) in verdicts {
+                if let Some(pattern) = patterns.iter().find(|p| pattern_matches(p, path)) {
+                    return PolicyDecision {
+                        allowed,
+                        rule: Some(rule.id.clone()),
+                        pattern: Some(pattern.clone()),
+                    };
+                }
+            }
+        }
+        PolicyDecision {
+            allowed: false,
+            rule: None,
+            pattern: None,
+        }
+    }
+}
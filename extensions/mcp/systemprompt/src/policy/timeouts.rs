@@ -0,0 +1,54 @@
+//! How long a CLI command may run before the server stops it.
+//!
+//! Most commands finish in seconds, so the default is short enough that a
+//! wedged call frees the agent session; the few that legitimately run long
+//! (builds, log streams) get their own limit by command pattern.
+
+use std::time::Duration;
+
+use serde::Deserialize;
+
+use super::pattern_matches;
+
+pub const DEFAULT_TIMEOUT_SECS: u64 = 300;
+
+const fn default_secs() -> u64 {
+    DEFAULT_TIMEOUT_SECS
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TimeoutOverride {
+    pub pattern: String,
+    pub secs: u64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CommandTimeouts {
+    #[serde(default = "default_secs")]
+    pub default_secs: u64,
+    #[serde(default)]
+    pub overrides: Vec<TimeoutOverride>,
+}
+
+impl Default for CommandTimeouts {
+    fn default() -> Self {
+        Self {
+            default_secs: DEFAULT_TIMEOUT_SECS,
+            overrides: Vec::new(),
+        }
+    }
+}
+
+impl CommandTimeouts {
+    /// The limit for a command path: the first override whose pattern
+    /// matches, otherwise the default. Patterns read as in the command rules.
+    #[must_use]
+    pub fn for_path(&self, path: &[String]) -> Duration {
+        let secs = self
+            .overrides
+            .iter()
+            .find(|o| pattern_matches(&o.pattern, path))
+            .map_or(self.default_secs, |o| o.secs);
+        Duration::from_secs(secs)
+    }
+}
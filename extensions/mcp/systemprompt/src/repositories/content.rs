@@ -0,0 +1,68 @@
+//! Published documentation and blog content, read for the server's resources.
+//!
+//! Only rows that are public and whose publish date has passed are visible:
+//! the same rows the site renders, so an MCP client never reads a draft.
+
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use systemprompt::identifiers::SourceId;
+
+/// The content sources offered as resources.
+pub const RESOURCE_SOURCES: [&str; 2] = ["documentation", "blog"];
+
+#[derive(Debug, Clone)]
+pub struct ContentListingRow {
+    pub source_id: SourceId,
+    pub slug: String,
+    pub title: String,
+    pub description: String,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ContentBodyRow {
+    pub title: String,
+    pub description: String,
+    pub body: String,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Every published item in `sources`, one row per slug (English when the item
+/// exists in several locales), ordered by source then slug.
+pub async fn list_published_content(
+    pool: &PgPool,
+    sources: &[&str],
+) -> Result<Vec<ContentListingRow>, sqlx::Error> {
+    let sources: Vec<String> = sources.iter().map(|&s| s.to_owned()).collect();
+    sqlx::query_as!(
+        ContentListingRow,
+        r#"SELECT DISTINCT ON (source_id, slug)
+                  source_id as "source_id!: SourceId", slug, title, description, updated_at
+           FROM markdown_content
+           WHERE source_id = ANY($1) AND public AND published_at <= NOW()
+           ORDER BY source_id, slug, locale = 'en' DESC, locale"#,
+        &sources,
+    )
+    .fetch_all(pool)
+    .await
+}
+
+/// One published item, or `None` when it does not exist or is not published.
+pub async fn find_published_content(
+    pool: &PgPool,
+    source_id: &SourceId,
+    slug: &str,
+) -> Result<Option<ContentBodyRow>, sqlx::Error> {
+    sqlx::query_as!(
+        ContentBodyRow,
+        r#"SELECT title, description, body, updated_at
+           FROM markdown_content
+           WHERE source_id = $1 AND slug = $2 AND public AND published_at <= NOW()
+           ORDER BY locale = 'en' DESC, locale
+           LIMIT 1"#,
+        source_id.as_str(),
+        slug,
+    )
+    .fetch_optional(pool)
+    .await
+}
@@ -0,0 +1,60 @@
+//! Allow/deny decisions from `governance_decisions`.
+
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use systemprompt::identifiers::{AgentId, SessionId, UserId};
+
+#[derive(Debug, Clone)]
+pub struct DecisionRow {
+    pub id: String,
+    pub user_id: UserId,
+    pub session_id: SessionId,
+    pub tool_name: String,
+    pub agent_id: Option<AgentId>,
+    pub decision: String,
+    pub policy: String,
+    pub reason: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct DecisionFilter<'a> {
+    pub decision: Option<&'a str>,
+    pub policy: Option<&'a str>,
+    pub user_id: Option<&'a UserId>,
+    pub session_id: Option<&'a SessionId>,
+    pub tool_name: Option<&'a str>,
+}
+
+/// Newest first. `tool_name` matches as a case-insensitive substring, since
+/// tool names carry an `mcp__server__` prefix the caller rarely remembers.
+pub async fn list_decisions(
+    pool: &PgPool,
+    filter: &DecisionFilter<'_>,
+    since: DateTime<Utc>,
+    limit: i64,
+) -> Result<Vec<DecisionRow>, sqlx::Error> {
+    sqlx::query_as!(
+        DecisionRow,
+        r#"SELECT id, user_id as "user_id!: UserId", session_id as "session_id!: SessionId",
+                  tool_name, agent_id as "agent_id: AgentId", decision, policy, reason, created_at
+           FROM governance_decisions
+           WHERE created_at >= $1
+             AND ($2::text IS NULL OR decision = $2)
+             AND ($3::text IS NULL OR policy = $3)
+             AND ($4::text IS NULL OR user_id = $4)
+             AND ($5::text IS NULL OR session_id = $5)
+             AND ($6::text IS NULL OR tool_name ILIKE '%' || $6 || '%')
+           ORDER BY created_at DESC
+           LIMIT $7"#,
+        since,
+        filter.decision,
+        filter.policy,
+        filter.user_id.map(UserId::as_str),
+        filter.session_id.map(SessionId::as_str),
+        filter.tool_name,
+        limit,
+    )
+    .fetch_all(pool)
+    .await
+}
@@ -0,0 +1,66 @@
+//! Recent AI gateway requests from `ai_requests`.
+
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use systemprompt::identifiers::{SessionId, TraceId, UserId};
+
+#[derive(Debug, Clone)]
+pub struct InferenceRequestRow {
+    pub id: String,
+    pub user_id: UserId,
+    pub session_id: Option<SessionId>,
+    pub trace_id: Option<TraceId>,
+    pub provider: Option<String>,
+    pub model: Option<String>,
+    pub status: String,
+    pub input_tokens: Option<i32>,
+    pub output_tokens: Option<i32>,
+    pub cost_microdollars: i64,
+    pub latency_ms: Option<i32>,
+    pub error_message: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct InferenceFilter<'a> {
+    pub user_id: Option<&'a UserId>,
+    pub session_id: Option<&'a SessionId>,
+    pub provider: Option<&'a str>,
+    pub model: Option<&'a str>,
+    pub status: Option<&'a str>,
+}
+
+/// Newest first. `model` matches as a case-insensitive substring, so
+/// `claude` finds every Claude model; the other filters are exact.
+pub async fn list_inference_requests(
+    pool: &PgPool,
+    filter: &InferenceFilter<'_>,
+    since: DateTime<Utc>,
+    limit: i64,
+) -> Result<Vec<InferenceRequestRow>, sqlx::Error> {
+    sqlx::query_as!(
+        InferenceRequestRow,
+        r#"SELECT id, user_id as "user_id!: UserId", session_id as "session_id: SessionId",
+                  trace_id as "trace_id: TraceId", provider, model, status,
+                  input_tokens, output_tokens, cost_microdollars, latency_ms,
+                  error_message, created_at
+           FROM ai_requests
+           WHERE created_at >= $1
+             AND ($2::text IS NULL OR user_id = $2)
+             AND ($3::text IS NULL OR session_id = $3)
+             AND ($4::text IS NULL OR provider = $4)
+             AND ($5::text IS NULL OR model ILIKE '%' || $5 || '%')
+             AND ($6::text IS NULL OR status = $6)
+           ORDER BY created_at DESC
+           LIMIT $7"#,
+        since,
+        filter.user_id.map(UserId::as_str),
+        filter.session_id.map(SessionId::as_str),
+        filter.provider,
+        filter.model,
+        filter.status,
+        limit,
+    )
+    .fetch_all(pool)
+    .await
+}
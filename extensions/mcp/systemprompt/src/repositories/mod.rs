@@ -0,0 +1,10 @@
+//! Read-only queries behind the typed tools.
+//!
+//! Each one takes its filters as options and a row cap, so a tool call maps
+//! onto exactly one statement and never streams an unbounded result back to
+//! the model.
+
+pub mod content;
+pub mod governance;
+pub mod inference;
+pub mod sessions;
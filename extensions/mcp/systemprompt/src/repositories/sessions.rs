@@ -0,0 +1,72 @@
+//! One session's footprint across the plugin summary, its analysis, and the
+//! requests and decisions it produced.
+
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use systemprompt::identifiers::{SessionId, UserId};
+
+#[derive(Debug, Clone)]
+pub struct SessionOverviewRow {
+    pub user_id: Option<UserId>,
+    pub plugin_id: Option<String>,
+    pub model: Option<String>,
+    pub status: Option<String>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub ended_at: Option<DateTime<Utc>>,
+    pub prompts: Option<i64>,
+    pub tool_uses: Option<i64>,
+    pub errors: Option<i64>,
+    pub title: Option<String>,
+    pub summary: Option<String>,
+    pub goal_achieved: Option<String>,
+    pub quality_score: Option<i16>,
+    pub ai_requests: i64,
+    pub cost_microdollars: i64,
+    pub decisions_allowed: i64,
+    pub decisions_denied: i64,
+}
+
+/// `None` when the id appears in none of the four tables.
+pub async fn find_session_overview(
+    pool: &PgPool,
+    session_id: &SessionId,
+) -> Result<Option<SessionOverviewRow>, sqlx::Error> {
+    let row = sqlx::query_as!(
+        SessionOverviewRow,
+        r#"WITH summary AS (
+               SELECT user_id, plugin_id, model, status, started_at, ended_at,
+                      prompts, tool_uses, errors
+               FROM plugin_session_summaries WHERE session_id = $1
+               ORDER BY updated_at DESC LIMIT 1
+           ), analysis AS (
+               SELECT user_id, title, summary, goal_achieved, quality_score
+               FROM session_analyses WHERE session_id = $1
+               ORDER BY updated_at DESC LIMIT 1
+           ), requests AS (
+               SELECT MIN(user_id) AS user_id, COUNT(*)::bigint AS n,
+                      COALESCE(SUM(cost_microdollars), 0)::bigint AS cost
+               FROM ai_requests WHERE session_id = $1
+           ), decisions AS (
+               SELECT MIN(user_id) AS user_id,
+                      COUNT(*) FILTER (WHERE decision = 'allow')::bigint AS allowed,
+                      COUNT(*) FILTER (WHERE decision = 'deny')::bigint AS denied
+               FROM governance_decisions WHERE session_id = $1
+           )
+           SELECT COALESCE(s.user_id, a.user_id, r.user_id, d.user_id) as "user_id: UserId",
+                  s.plugin_id as "plugin_id?", s.model as "model?", s.status as "status?",
+                  s.started_at as "started_at?", s.ended_at as "ended_at?",
+                  s.prompts as "prompts?", s.tool_uses as "tool_uses?", s.errors as "errors?",
+                  a.title as "title?", a.summary as "summary?",
+                  a.goal_achieved as "goal_achieved?", a.quality_score as "quality_score?",
+                  r.n as "ai_requests!", r.cost as "cost_microdollars!",
+                  d.allowed as "decisions_allowed!", d.denied as "decisions_denied!"
+           FROM requests r
+           CROSS JOIN decisions d
+           LEFT JOIN summary s ON TRUE
+           LEFT JOIN analysis a ON TRUE"#,
+        session_id.as_str(),
+    )
+    .fetch_one(pool)
+    .await?;
+    Ok(row.user_id.is_some().then_some(row))
+}
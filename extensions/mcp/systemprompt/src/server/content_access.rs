@@ -0,0 +1,84 @@
+//! Which content resources a caller may see, decided by the marketplace
+//! access rules.
+//!
+//! Content names no plugin or skill of its own, so each item is put to
+//! [`TemplateMarketplaceFilter`] as a member of every marketplace the server
+//! ships, the way a skill that declares no rules takes its marketplace's
+//! grant. An item is keyed by its resource URI under the `skill` entity type,
+//! so an admin can open or close one document on its own with rules such as
+//! `entity_type: skill, entity_id: content://documentation/billing`; a member
+//! that declares rules owns its decision outright. Items are judged once more
+//! outside any marketplace, so a URI granted on its own is visible to a user
+//! no marketplace admits.
+//!
+//! [`TemplateMarketplaceFilter`]: systemprompt_web_access::marketplace_filter::TemplateMarketplaceFilter
+
+use std::collections::HashSet;
+
+use rmcp::ErrorData as McpError;
+use sha2::{Digest, Sha256};
+use systemprompt::database::DbPool;
+use systemprompt::identifiers::UserId;
+use systemprompt::marketplace::MarketplaceCandidate;
+use systemprompt::models::bridge::ids::{Sha256Digest, SkillId, SkillName};
+use systemprompt::models::bridge::manifest::SkillEntry as ManifestSkill;
+
+use super::skill_access::{allowed_skill_ids, internal};
+use crate::catalog::MarketplaceSkills;
+
+fn content_entry(uri: &str) -> Result<ManifestSkill, McpError> {
+    // Why: candidates carry a content hash the filter never reads; the URI's
+    // hash keeps the entry well-formed without reading the body.
+    let digest = hex::encode(Sha256::digest(uri.as_bytes()));
+    Ok(ManifestSkill {
+        id: SkillId::try_new(uri).map_err(internal)?,
+        name: SkillName::try_new(uri).map_err(internal)?,
+        description: String::new(),
+        file_path: uri.to_owned(),
+        tags: Vec::new(),
+        sha256: Sha256Digest::try_new(digest).map_err(internal)?,
+        instructions: String::new(),
+    })
+}
+
+fn candidates(
+    uris: &[String],
+    marketplaces: &[MarketplaceSkills],
+) -> Result<Vec<MarketplaceCandidate>, McpError> {
+    let entries = uris
+        .iter()
+        .map(|uri| content_entry(uri))
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut out: Vec<MarketplaceCandidate> = marketplaces
+        .iter()
+        .map(|marketplace| MarketplaceCandidate {
+            skills: entries.clone(),
+            marketplace_id: Some(marketplace.id.clone()),
+            access: Some(marketplace.access.clone()),
+            ..MarketplaceCandidate::default()
+        })
+        .collect();
+    out.push(MarketplaceCandidate {
+        skills: entries,
+        ..MarketplaceCandidate::default()
+    });
+    Ok(out)
+}
+
+/// The content URIs among `uris` that `user_id` may list and read, judged
+/// against `marketplaces`.
+///
+/// Exposed (behind `#[doc(hidden)]`) so the external test workspace can judge
+/// fixture content without a profile; not part of the public API.
+#[doc(hidden)]
+pub async fn visible_content(
+    db_pool: &DbPool,
+    user_id: &UserId,
+    marketplaces: &[MarketplaceSkills],
+    uris: &[String],
+) -> Result<HashSet<String>, McpError> {
+    if uris.is_empty() {
+        return Ok(HashSet::new());
+    }
+    allowed_skill_ids(db_pool, user_id, candidates(uris, marketplaces)?).await
+}
@@ -0,0 +1,54 @@
+//! Per-call control taken from the rmcp request: the client's cancellation
+//! token, and a progress channel when the client sent a progress token.
+//!
+//! CLI stderr lines arrive on the channel and go out as progress
+//! notifications with a running line count, since a CLI command has no known
+//! total.
+
+use rmcp::model::{ProgressNotificationParam, ProgressToken};
+use rmcp::service::{Peer, RequestContext, RoleServer};
+use tokio::sync::mpsc::{UnboundedReceiver, unbounded_channel};
+use tokio::task::JoinHandle;
+
+use super::tool::CallControl;
+use crate::policy::CommandTimeouts;
+
+async fn forward(
+    peer: Peer<RoleServer>,
+    token: ProgressToken,
+    mut lines: UnboundedReceiver<String>,
+) {
+    let mut count = 0.0;
+    while let Some(line) = lines.recv().await {
+        count += 1.0;
+        let param = ProgressNotificationParam::new(token.clone(), count).with_message(line);
+        if let Err(e) = peer.notify_progress(param).await {
+            tracing::debug!(error = %e, "Failed to send progress notification");
+        }
+    }
+}
+
+// Why: the forwarding task ends once every copy of the control is dropped,
+// so awaiting it then keeps progress notifications ahead of the tool result.
+pub(super) fn call_control(
+    ctx: &RequestContext<RoleServer>,
+    timeouts: CommandTimeouts,
+) -> (CallControl, Option<JoinHandle<()>>) {
+    let (progress, forwarder) = ctx
+        .meta
+        .get_progress_token()
+        .map(|token| {
+            let (sender, lines) = unbounded_channel();
+            (
+                sender,
+                tokio::spawn(forward(ctx.peer.clone(), token, lines)),
+            )
+        })
+        .unzip();
+    let control = CallControl {
+        cancel: ctx.ct.clone(),
+        progress,
+        timeouts,
+    };
+    (control, forwarder)
+}
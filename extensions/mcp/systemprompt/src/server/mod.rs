@@ -1,21 +1,39 @@
 //! The `systemprompt` MCP server: struct construction and rmcp `ServerHandler`
-//! surface (info, tool listing, call dispatch, artifact-viewer resources).
+//! surface (info, tool listing, call dispatch, prompts and resources).
 //!
-//! Per-call logic (RBAC, auditing, CLI-to-artifact conversion) lives in
-//! the `tool` submodule.
+//! Per-call logic (RBAC, the command policy, auditing, CLI-to-artifact
+//! conversion) lives in the `tool` submodule, and each call that runs is
+//! captured for the Trace Explorer under the policy file's `tool_capture`;
+//! cancellation and progress for a call in `control`; the typed tools' handlers
+//! live in `typed`. Skills are offered as prompts (`prompts`, filtered per
+//! caller by `skill_access`), published content as resources (`resources`,
+//! filtered per caller by `content_access`), and `notify` tells clients when
+//! either list changes.
 
+#[doc(hidden)]
+pub mod content_access;
+mod control;
+mod notify;
+#[doc(hidden)]
+pub mod prompts;
+#[doc(hidden)]
+pub mod resources;
+#[doc(hidden)]
+pub mod skill_access;
 #[doc(hidden)]
 pub mod tool;
+#[doc(hidden)]
+pub mod typed;
 
 use crate::error::SystempromptToolError;
 use crate::tools::{self, SERVER_NAME};
 use rmcp::model::{
-    CallToolRequestParams, CallToolResponse, Icon, Implementation, InitializeRequestParams,
-    InitializeResult, ListResourcesResult, ListToolsResult, PaginatedRequestParams,
-    ProtocolVersion, ReadResourceRequestParams, ReadResourceResponse, ServerCapabilities,
-    ServerInfo,
+    CallToolRequestParams, CallToolResponse, GetPromptRequestParams, GetPromptResponse, Icon,
+    Implementation, InitializeRequestParams, InitializeResult, ListPromptsResult,
+    ListResourcesResult, ListToolsResult, PaginatedRequestParams, ProtocolVersion,
+    ReadResourceRequestParams, ReadResourceResponse, ServerCapabilities, ServerInfo,
 };
-use rmcp::service::{MaybeSendFuture, RequestContext, RoleServer};
+use rmcp::service::{MaybeSendFuture, NotificationContext, RequestContext, RoleServer};
 use rmcp::{ErrorData as McpError, ServerHandler};
 use std::future::Future;
 use std::sync::Arc;
@@ -26,11 +44,13 @@ use systemprompt::mcp::{
     ArtifactViewerConfig, McpArtifactRepository, McpToolExecutor, WEBSITE_URL,
     build_artifact_viewer_resource, build_extension_capabilities, read_artifact_viewer_resource,
 };
+use systemprompt::models::execution::context::RequestContext as SysRequestContext;
 use systemprompt::security::authz::SharedAuthzHook;
-use systemprompt_mcp_shared::record_mcp_access;
+use systemprompt_mcp_shared::{McpAccess, ToolCall, record_mcp_access, record_mcp_tool_call};
 
+use control::call_control;
 use systemprompt::mcp::client_profile_from_peer;
-use tool::{authenticate_tool_request, dispatch_tool};
+use tool::{authenticate_tool_request, authorize, command_settings, dispatch_tool};
 
 const ARTIFACT_VIEWER_TEMPLATE: &str = include_str!("../../templates/artifact-viewer.html");
 
@@ -65,6 +85,23 @@ impl SystempromptServer {
             authz_hook,
         })
     }
+
+    // Why: prompts and resources go through the same OAuth and access audit
+    // as tools, labelled with the MCP method in place of a tool name.
+    async fn authenticate(
+        &self,
+        method: &str,
+        ctx: &RequestContext<RoleServer>,
+    ) -> Result<(SysRequestContext, String), McpError> {
+        authenticate_tool_request(
+            &self.db_pool,
+            method,
+            self.service_id.as_str(),
+            ctx,
+            &self.authz_hook,
+        )
+        .await
+    }
 }
 
 impl ServerHandler for SystempromptServer {
@@ -72,7 +109,10 @@ impl ServerHandler for SystempromptServer {
         ServerInfo::new(
             ServerCapabilities::builder()
                 .enable_tools()
+                .enable_prompts()
+                .enable_prompts_list_changed()
                 .enable_resources()
+                .enable_resources_list_changed()
                 .enable_extensions_with(build_extension_capabilities())
                 .build(),
         )
@@ -94,9 +134,11 @@ impl ServerHandler for SystempromptServer {
             .with_website_url(WEBSITE_URL),
         )
         .with_instructions(
-            format!("Execute SystemPrompt CLI commands. Skills: 'core skills list' or 'core skills show <id>'. \
-             Content: 'core content list'. Agents: 'admin agents list'. \
-             Discord: 'plugins run discord send \"message\"'. Full documentation: {WEBSITE_URL}/docs"),
+            format!("Typed tools: list_skills, show_skill, list_agents, list_inference_requests, \
+             list_governance_decisions, inspect_session. Anything else: the 'systemprompt' tool \
+             runs a CLI command, e.g. 'core content list' or 'plugins run discord send \"message\"'. \
+             Prompts: one per skill you may use. Resources: published documentation and blog \
+             posts as content://{{source}}/{{slug}}. Full documentation: {WEBSITE_URL}/docs"),
         )
     }
 
@@ -135,36 +177,102 @@ impl ServerHandler for SystempromptServer {
         )
         .await?;
 
+        let settings = command_settings()?;
+        let decision = authorize(
+            &self.db_pool,
+            &server_name,
+            &settings.command_policy,
+            &request,
+            &request_context,
+        )
+        .await?;
+
         record_mcp_access(
             &self.db_pool,
             request_context.user_id(),
-            &server_name,
-            &tool_name,
+            McpAccess::new(&server_name, &tool_name).with_rule(decision.rule.as_deref()),
             "used",
         )
         .await;
 
         let client = client_profile_from_peer(&ctx);
-        dispatch_tool(
+        let (control, forwarder) = call_control(&ctx, settings.command_timeouts);
+        let started_at = chrono::Utc::now();
+        let result = dispatch_tool(
             &tool::Dispatch {
+                db_pool: &self.db_pool,
                 executor: &self.executor,
                 request: &request,
                 request_context: &request_context,
                 client: &client,
+                control: &control,
             },
             &tool_name,
             &auth_token,
         )
-        .await
-        .map(Into::into)
+        .await;
+
+        record_mcp_tool_call(
+            &self.db_pool,
+            &settings.tool_capture,
+            ToolCall {
+                server: &server_name,
+                tool: &tool_name,
+                context: &request_context,
+                arguments: request.arguments.as_ref(),
+                started_at,
+            },
+            result.as_ref(),
+        )
+        .await;
+
+        drop(control);
+        if let Some(forwarder) = forwarder
+            && let Err(e) = forwarder.await
+        {
+            tracing::warn!(error = %e, "Progress forwarder ended abnormally");
+        }
+        result.map(Into::into)
+    }
+
+    fn on_initialized(
+        &self,
+        context: NotificationContext<RoleServer>,
+    ) -> impl Future<Output = ()> + MaybeSendFuture + '_ {
+        match typed::pg_pool(&self.db_pool) {
+            Ok(pool) => notify::forward_to(context.peer, pool),
+            Err(e) => tracing::warn!(error = %e, "No list-changed notifications without a pool"),
+        }
+        std::future::ready(())
     }
 
-    fn list_resources(
+    async fn list_prompts(
         &self,
         _request: Option<PaginatedRequestParams>,
-        _ctx: RequestContext<RoleServer>,
-    ) -> impl Future<Output = Result<ListResourcesResult, McpError>> + MaybeSendFuture + '_ {
-        std::future::ready(Ok(build_artifact_viewer_resource(&ArtifactViewerConfig {
+        ctx: RequestContext<RoleServer>,
+    ) -> Result<ListPromptsResult, McpError> {
+        let (request_context, _) = self.authenticate("prompts/list", &ctx).await?;
+        prompts::list(&self.db_pool, request_context.user_id()).await
+    }
+
+    async fn get_prompt(
+        &self,
+        request: GetPromptRequestParams,
+        ctx: RequestContext<RoleServer>,
+    ) -> Result<GetPromptResponse, McpError> {
+        let (request_context, _) = self.authenticate("prompts/get", &ctx).await?;
+        prompts::get(&self.db_pool, request_context.user_id(), request)
+            .await
+            .map(Into::into)
+    }
+
+    async fn list_resources(
+        &self,
+        _request: Option<PaginatedRequestParams>,
+        ctx: RequestContext<RoleServer>,
+    ) -> Result<ListResourcesResult, McpError> {
+        let (request_context, _) = self.authenticate("resources/list", &ctx).await?;
+        let mut result = build_artifact_viewer_resource(&ArtifactViewerConfig {
             server_name: SERVER_NAME,
             title: "systemprompt.io Artifact Viewer",
             description: "Interactive UI viewer for systemprompt.io artifacts. Renders tables, lists, \
@@ -176,17 +284,25 @@ impl ServerHandler for SystempromptServer {
                     .with_mime_type("image/png")
                     .with_sizes(vec!["32x32".to_owned()]),
             ]),
-        })))
+        });
+        result
+            .resources
+            .extend(resources::list(&self.db_pool, request_context.user_id()).await?);
+        Ok(result)
     }
 
-    fn read_resource(
+    async fn read_resource(
         &self,
         request: ReadResourceRequestParams,
-        _ctx: RequestContext<RoleServer>,
-    ) -> impl Future<Output = Result<ReadResourceResponse, McpError>> + MaybeSendFuture + '_ {
-        std::future::ready(
-            read_artifact_viewer_resource(&request, SERVER_NAME, ARTIFACT_VIEWER_TEMPLATE)
-                .map(Into::into),
-        )
+        ctx: RequestContext<RoleServer>,
+    ) -> Result<ReadResourceResponse, McpError> {
+        let (request_context, _) = self.authenticate("resources/read", &ctx).await?;
+        match resources::parse_content_uri(&request.uri) {
+            Some((source_id, slug)) => {
+                resources::read(&self.db_pool, request_context.user_id(), &source_id, slug).await
+            },
+            None => read_artifact_viewer_resource(&request, SERVER_NAME, ARTIFACT_VIEWER_TEMPLATE),
+        }
+        .map(Into::into)
     }
 }
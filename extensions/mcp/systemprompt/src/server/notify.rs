@@ -0,0 +1,122 @@
+//! List-changed notifications for the prompts and resources surfaces.
+//!
+//! One watcher runs per process, started by the first client to finish
+//! initializing. It LISTENs on the `content_changes` channel, fed by the
+//! `markdown_content` trigger, so each content ingestion run tells clients
+//! their resource list changed; and it polls the skills directory, so an added,
+//! removed or edited skill tells them their prompt list changed. Each session
+//! forwards the watcher's events to its own peer until the transport closes.
+
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
+use rmcp::service::{Peer, RoleServer};
+use sqlx::PgPool;
+use sqlx::postgres::PgListener;
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::catalog;
+
+const CHANNEL_NAME: &str = "content_changes";
+const RETRY_DELAY: Duration = Duration::from_secs(2);
+const SKILLS_POLL_INTERVAL: Duration = Duration::from_secs(30);
+const BROADCAST_CAPACITY: usize = 16;
+
+#[derive(Clone, Copy, Debug)]
+enum Change {
+    Resources,
+    Prompts,
+}
+
+static WATCHER: OnceLock<broadcast::Sender<Change>> = OnceLock::new();
+
+pub(super) fn forward_to(peer: Peer<RoleServer>, pool: Arc<PgPool>) {
+    let mut receiver = WATCHER
+        .get_or_init(|| {
+            let (sender, _) = broadcast::channel(BROADCAST_CAPACITY);
+            spawn_content_listener(pool, sender.clone());
+            spawn_skills_poller(sender.clone());
+            sender
+        })
+        .subscribe();
+    tokio::spawn(async move {
+        loop {
+            let change = match receiver.recv().await {
+                Ok(change) => change,
+                // Why: every event means "list again", so a session that fell
+                // behind only needs the next one.
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => return,
+            };
+            if peer.is_transport_closed() {
+                return;
+            }
+            let sent = match change {
+                Change::Resources => peer.notify_resource_list_changed().await,
+                Change::Prompts => peer.notify_prompt_list_changed().await,
+            };
+            if let Err(e) = sent {
+                tracing::debug!(error = %e, ?change, "Client gone; stopping list-changed forwarding");
+                return;
+            }
+        }
+    });
+}
+
+fn spawn_content_listener(pool: Arc<PgPool>, sender: broadcast::Sender<Change>) {
+    tokio::spawn(async move {
+        loop {
+            match PgListener::connect_with(&pool).await {
+                Ok(mut listener) => {
+                    if let Err(e) = listener.listen(CHANNEL_NAME).await {
+                        tracing::warn!(error = %e, channel = CHANNEL_NAME, "LISTEN failed; retrying");
+                        tokio::time::sleep(RETRY_DELAY).await;
+                        continue;
+                    }
+                    while let Ok(_notification) = listener.recv().await {
+                        // Why: `send` fails only when no session is subscribed,
+                        // which is the normal idle state.
+                        drop(sender.send(Change::Resources));
+                    }
+                    tracing::warn!(
+                        channel = CHANNEL_NAME,
+                        "Content listener dropped; reconnecting"
+                    );
+                },
+                Err(e) => {
+                    tracing::warn!(error = %e, "Content listener failed to connect; retrying");
+                },
+            }
+            tokio::time::sleep(RETRY_DELAY).await;
+        }
+    });
+}
+
+async fn fingerprint() -> Option<u64> {
+    match tokio::task::spawn_blocking(catalog::skills_fingerprint).await {
+        Ok(Ok(fingerprint)) => Some(fingerprint),
+        Ok(Err(e)) => {
+            tracing::warn!(error = %e, "Failed to fingerprint the skills directory");
+            None
+        },
+        Err(e) => {
+            tracing::warn!(error = %e, "Skills fingerprint task failed");
+            None
+        },
+    }
+}
+
+fn spawn_skills_poller(sender: broadcast::Sender<Change>) {
+    tokio::spawn(async move {
+        let mut last = fingerprint().await;
+        loop {
+            tokio::time::sleep(SKILLS_POLL_INTERVAL).await;
+            let current = fingerprint().await;
+            if current.is_some() && current != last {
+                drop(sender.send(Change::Prompts));
+            }
+            last = current.or(last);
+        }
+    });
+}
@@ -0,0 +1,97 @@
+//! Skills offered as MCP prompts.
+//!
+//! Each skill the caller may see becomes one prompt named after the skill id.
+//! Getting it returns the skill's instructions as a user message, followed by
+//! the caller's task when one is given, so a client can start a conversation
+//! from any skill without going through a tool call.
+
+use rmcp::ErrorData as McpError;
+use rmcp::model::{
+    GetPromptRequestParams, GetPromptResult, ListPromptsResult, Prompt, PromptArgument,
+    PromptMessage, Role,
+};
+use systemprompt::database::DbPool;
+use systemprompt::identifiers::UserId;
+
+use super::skill_access::{internal, load_catalog, visible_skills};
+use crate::catalog::{self, SkillEntry};
+
+/// The one argument every skill prompt takes.
+pub const TASK_ARGUMENT: &str = "task";
+
+/// The prompt listed for `skill`.
+#[doc(hidden)]
+pub fn skill_prompt(skill: &SkillEntry) -> Prompt {
+    Prompt::new(
+        &skill.id,
+        Some(skill.config.description.clone()),
+        Some(vec![
+            PromptArgument::new(TASK_ARGUMENT)
+                .with_description("What to do with the skill; appended after its instructions")
+                .with_required(false),
+        ]),
+    )
+    .with_title(&skill.config.name)
+}
+
+/// The messages returned for `skill` with its instruction body and an
+/// optional task.
+#[doc(hidden)]
+pub fn skill_messages(
+    skill: &SkillEntry,
+    instructions: &str,
+    task: Option<&str>,
+) -> GetPromptResult {
+    let mut messages = vec![PromptMessage::new_text(Role::User, instructions.trim())];
+    if let Some(task) = task.map(str::trim).filter(|task| !task.is_empty()) {
+        messages.push(PromptMessage::new_text(Role::User, format!("Task: {task}")));
+    }
+    GetPromptResult::new(messages).with_description(skill.config.description.clone())
+}
+
+pub(super) async fn list(
+    db_pool: &DbPool,
+    user_id: &UserId,
+) -> Result<ListPromptsResult, McpError> {
+    let skills = load_catalog().await?;
+    let prompts = visible_skills(db_pool, user_id, &skills)
+        .await?
+        .iter()
+        .filter(|skill| skill.config.enabled)
+        .map(skill_prompt)
+        .collect();
+    Ok(ListPromptsResult::with_all_items(prompts))
+}
+
+pub(super) async fn get(
+    db_pool: &DbPool,
+    user_id: &UserId,
+    request: GetPromptRequestParams,
+) -> Result<GetPromptResult, McpError> {
+    // Why: a skill the caller may not see answers exactly like one that does
+    // not exist, so the prompt list cannot be probed by name.
+    let unknown = || McpError::invalid_params(format!("Unknown prompt: '{}'", request.name), None);
+    let skills = load_catalog().await?;
+    let visible = visible_skills(db_pool, user_id, &skills).await?;
+    if !visible
+        .iter()
+        .any(|skill| skill.config.enabled && skill.id == request.name)
+    {
+        return Err(unknown());
+    }
+
+    let name = request.name.clone();
+    let root = skills.root;
+    let (skill, instructions) =
+        tokio::task::spawn_blocking(move || catalog::find_skill_in(&root, &name))
+            .await
+            .map_err(internal)?
+            .map_err(internal)?
+            .ok_or_else(unknown)?;
+    let task = request
+        .arguments
+        .as_ref()
+        .and_then(|args| args.get(TASK_ARGUMENT))
+        .and_then(|task| task.as_str());
+    Ok(skill_messages(&skill, &instructions, task))
+}
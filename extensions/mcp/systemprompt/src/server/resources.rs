@@ -0,0 +1,89 @@
+//! Published documentation and blog posts offered as MCP resources.
+//!
+//! Each item is addressed as `content://{source_id}/{slug}` and read as
+//! markdown. The listing sits beside the artifact viewer's `ui://` resource;
+//! any URI that is not a content URI is left to the viewer. Both the listing
+//! and a read are judged per caller by `content_access`, and an item the
+//! caller may not see is answered exactly like one that does not exist.
+
+use rmcp::ErrorData as McpError;
+use rmcp::model::{ReadResourceResult, Resource, ResourceContents};
+use systemprompt::database::DbPool;
+use systemprompt::identifiers::{SourceId, UserId};
+
+use super::content_access::visible_content;
+use super::skill_access::{internal, load_catalog};
+use super::typed::pg_pool;
+use crate::repositories::content::{
+    ContentListingRow, RESOURCE_SOURCES, find_published_content, list_published_content,
+};
+
+/// URI scheme of content resources.
+pub const CONTENT_SCHEME: &str = "content://";
+
+const MARKDOWN: &str = "text/markdown";
+
+#[doc(hidden)]
+pub fn content_uri(source_id: &SourceId, slug: &str) -> String {
+    format!("{CONTENT_SCHEME}{source_id}/{slug}")
+}
+
+/// Split a content URI into its source and slug. `None` for any other URI,
+/// and for sources this server does not offer.
+#[doc(hidden)]
+pub fn parse_content_uri(uri: &str) -> Option<(SourceId, &str)> {
+    let (source_id, slug) = uri.strip_prefix(CONTENT_SCHEME)?.split_once('/')?;
+    (RESOURCE_SOURCES.contains(&source_id) && !slug.is_empty() && !slug.contains('/'))
+        .then(|| (SourceId::new(source_id.to_owned()), slug))
+}
+
+#[doc(hidden)]
+pub fn content_resource(row: &ContentListingRow) -> Resource {
+    Resource::new(content_uri(&row.source_id, &row.slug), row.title.clone())
+        .with_description(row.description.clone())
+        .with_mime_type(MARKDOWN)
+}
+
+pub(super) async fn list(db_pool: &DbPool, user_id: &UserId) -> Result<Vec<Resource>, McpError> {
+    let pool = pg_pool(db_pool)?;
+    let rows = list_published_content(&pool, &RESOURCE_SOURCES)
+        .await
+        .map_err(internal)?;
+    let uris: Vec<String> = rows
+        .iter()
+        .map(|row| content_uri(&row.source_id, &row.slug))
+        .collect();
+    let catalog = load_catalog().await?;
+    let visible = visible_content(db_pool, user_id, &catalog.marketplaces, &uris).await?;
+    Ok(rows
+        .iter()
+        .zip(&uris)
+        .filter(|(_, uri)| visible.contains(*uri))
+        .map(|(row, _)| content_resource(row))
+        .collect())
+}
+
+pub(super) async fn read(
+    db_pool: &DbPool,
+    user_id: &UserId,
+    source_id: &SourceId,
+    slug: &str,
+) -> Result<ReadResourceResult, McpError> {
+    let pool = pg_pool(db_pool)?;
+    let uri = content_uri(source_id, slug);
+    let not_found = || McpError::resource_not_found(format!("Unknown resource: '{uri}'"), None);
+    let catalog = load_catalog().await?;
+    let requested = [uri.clone()];
+    let visible = visible_content(db_pool, user_id, &catalog.marketplaces, &requested).await?;
+    if !visible.contains(&uri) {
+        return Err(not_found());
+    }
+    let row = find_published_content(&pool, source_id, slug)
+        .await
+        .map_err(internal)?
+        .ok_or_else(not_found)?;
+    let text = format!("# {}\n\n{}", row.title, row.body.trim());
+    Ok(ReadResourceResult::new(vec![
+        ResourceContents::text(text, uri).with_mime_type(MARKDOWN),
+    ]))
+}
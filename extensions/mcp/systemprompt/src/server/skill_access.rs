@@ -0,0 +1,131 @@
+//! Which skills a caller may see, decided by the marketplace access rules.
+//!
+//! The rules in `access_control_rules` that decide which skills a user's
+//! bridge manifest carries are applied by [`TemplateMarketplaceFilter`]; the
+//! prompts surface, the `list_skills` and `show_skill` tools and the content
+//! resources (see `content_access`) ask the same filter. It asks once per
+//! marketplace, so each member skill inherits its marketplace's grant exactly
+//! as it does in the manifest, and once more for skills no marketplace ships,
+//! which are judged on their own rules alone.
+
+use std::collections::HashSet;
+
+use rmcp::ErrorData as McpError;
+use sha2::{Digest, Sha256};
+use systemprompt::database::DbPool;
+use systemprompt::identifiers::UserId;
+use systemprompt::marketplace::MarketplaceCandidate;
+use systemprompt::models::bridge::ids::{Sha256Digest, SkillId, SkillName};
+use systemprompt::models::bridge::manifest::SkillEntry as ManifestSkill;
+use systemprompt_web_access::marketplace_filter::TemplateMarketplaceFilter;
+
+use crate::catalog::{MarketplaceSkills, SkillCatalog, SkillEntry};
+use crate::error::SystempromptToolError;
+
+// Why: lint-ok: error-adapt — rmcp's ErrorData is a variant-less wire type
+pub(super) fn internal(e: impl std::fmt::Display) -> McpError {
+    McpError::internal_error(e.to_string(), None)
+}
+
+fn manifest_skill(skill: &SkillEntry) -> Result<ManifestSkill, SystempromptToolError> {
+    // Why: candidates carry a content hash; the filter never reads it, but
+    // hashing the config file keeps the entry an honest one.
+    let config_bytes = std::fs::read(&skill.config_path)?;
+    let digest = hex::encode(Sha256::digest(&config_bytes));
+    let invalid = |e: &dyn std::fmt::Display| {
+        SystempromptToolError::Internal(format!("Skill '{}': {e}", skill.id))
+    };
+    Ok(ManifestSkill {
+        id: SkillId::try_new(skill.id.clone()).map_err(|e| invalid(&e))?,
+        name: SkillName::try_new(skill.config.name.clone()).map_err(|e| invalid(&e))?,
+        description: skill.config.description.clone(),
+        file_path: skill.config_path.display().to_string(),
+        tags: skill.config.tags.clone(),
+        sha256: Sha256Digest::try_new(digest).map_err(|e| invalid(&e))?,
+        instructions: String::new(),
+    })
+}
+
+// Why: one candidate per marketplace plus one for skills none of them ship.
+fn candidates(
+    skills: &[SkillEntry],
+    marketplaces: &[MarketplaceSkills],
+) -> Result<Vec<MarketplaceCandidate>, SystempromptToolError> {
+    let shipped: HashSet<&str> = marketplaces
+        .iter()
+        .flat_map(|marketplace| marketplace.skills.iter().map(String::as_str))
+        .collect();
+    let entries = |keep: &dyn Fn(&SkillEntry) -> bool| {
+        skills
+            .iter()
+            .filter(|skill| keep(skill))
+            .map(manifest_skill)
+            .collect::<Result<Vec<_>, _>>()
+    };
+
+    let mut out = Vec::with_capacity(marketplaces.len() + 1);
+    for marketplace in marketplaces {
+        out.push(MarketplaceCandidate {
+            skills: entries(&|skill| marketplace.skills.contains(&skill.id))?,
+            marketplace_id: Some(marketplace.id.clone()),
+            access: Some(marketplace.access.clone()),
+            ..MarketplaceCandidate::default()
+        });
+    }
+    out.push(MarketplaceCandidate {
+        skills: entries(&|skill| !shipped.contains(skill.id.as_str()))?,
+        ..MarketplaceCandidate::default()
+    });
+    Ok(out)
+}
+
+// Why: a skill is kept when any candidate carrying it is allowed it, so a
+// grant through one marketplace is not undone by another that withholds it.
+pub(super) async fn allowed_skill_ids(
+    db_pool: &DbPool,
+    user_id: &UserId,
+    candidates: Vec<MarketplaceCandidate>,
+) -> Result<HashSet<String>, McpError> {
+    let filter = TemplateMarketplaceFilter::from_db(db_pool).map_err(internal)?;
+    let mut keep = HashSet::new();
+    for candidate in candidates {
+        if candidate.skills.is_empty() {
+            continue;
+        }
+        let allowed = filter.filter(user_id, candidate).await.map_err(internal)?;
+        keep.extend(allowed.skills.into_iter().map(|skill| skill.id.to_string()));
+    }
+    Ok(keep)
+}
+
+pub(super) async fn load_catalog() -> Result<SkillCatalog, McpError> {
+    tokio::task::spawn_blocking(SkillCatalog::load)
+        .await
+        .map_err(internal)?
+        .map_err(internal)
+}
+
+/// The skills in `catalog`, enabled or not, that `user_id` may see.
+///
+/// Exposed (behind `#[doc(hidden)]`) so the external test workspace can judge
+/// a fixture catalog without a profile; not part of the public API.
+#[doc(hidden)]
+pub async fn visible_skills(
+    db_pool: &DbPool,
+    user_id: &UserId,
+    catalog: &SkillCatalog,
+) -> Result<Vec<SkillEntry>, McpError> {
+    let (skills, marketplaces) = (catalog.skills.clone(), catalog.marketplaces.clone());
+    let candidates = tokio::task::spawn_blocking(move || candidates(&skills, &marketplaces))
+        .await
+        .map_err(internal)?
+        .map_err(internal)?;
+
+    let keep = allowed_skill_ids(db_pool, user_id, candidates).await?;
+    Ok(catalog
+        .skills
+        .iter()
+        .filter(|skill| keep.contains(&skill.id))
+        .cloned()
+        .collect())
+}
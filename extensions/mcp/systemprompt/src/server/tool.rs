@@ -2,10 +2,23 @@
 //!
 //! The server in the parent module owns the rmcp `ServerHandler` surface; this
 //! module owns what happens per tool call: RBAC enforcement against the
-//! registry, access auditing, and turning CLI output into a [`CliArtifact`].
+//! registry, the per-command policy, access auditing, routing to the CLI or a
+//! typed handler, and turning CLI output into a [`CliArtifact`].
 
+use std::sync::Arc;
+
+use super::skill_access::load_catalog;
+use super::typed::{
+    GovernanceDecisionsHandler, InferenceRequestsHandler, InspectSessionHandler, ListAgentsHandler,
+    ListSkillsHandler, ShowSkillHandler, pg_pool,
+};
 use crate::cli;
-use crate::tools::CliInput;
+use crate::policy::{CommandPolicy, CommandSettings, PolicyDecision, command_path, load_settings};
+use crate::tools::typed::{
+    INSPECT_SESSION, LIST_AGENTS, LIST_GOVERNANCE_DECISIONS, LIST_INFERENCE_REQUESTS, LIST_SKILLS,
+    SHOW_SKILL,
+};
+use crate::tools::{CliInput, SERVER_NAME};
 use rmcp::ErrorData as McpError;
 use rmcp::model::{CallToolRequestParams, CallToolResult};
 use rmcp::service::{RequestContext, RoleServer};
@@ -16,10 +29,14 @@ use systemprompt::mcp::{ClientProfile, McpToolExecutor, McpToolHandler};
 use systemprompt::models::artifacts::{CliArtifact, TextArtifact};
 use systemprompt::models::execution::context::RequestContext as SysRequestContext;
 use systemprompt::security::authz::SharedAuthzHook;
-use systemprompt_mcp_shared::{record_mcp_access, record_mcp_access_rejected};
+use systemprompt_mcp_shared::{McpAccess, record_mcp_access, record_mcp_access_rejected};
+
+#[doc(hidden)]
+pub use crate::cli::CallControl;
 
 pub(super) struct SystempromptToolHandler {
     pub(super) auth_token: String,
+    pub(super) control: CallControl,
 }
 
 impl McpToolHandler for SystempromptToolHandler {
@@ -40,7 +57,7 @@ impl McpToolHandler for SystempromptToolHandler {
         _ctx: &SysRequestContext,
         _exec_id: &McpExecutionId,
     ) -> Result<(Self::Output, String), McpError> {
-        let output = cli::execute(&input.command, &self.auth_token).await?;
+        let output = cli::execute(&input.command, &self.auth_token, &self.control).await?;
 
         if !output.success {
             return Err(McpError::internal_error(
@@ -79,7 +96,7 @@ pub(super) async fn authenticate_tool_request(
     ctx: &RequestContext<RoleServer>,
     authz_hook: &SharedAuthzHook,
 ) -> Result<(SysRequestContext, String), McpError> {
-    let server_name = service_id;
+    let access = McpAccess::new(service_id, tool_name);
     let rbac_result = enforce_rbac_from_registry(ctx, service_id, authz_hook).await;
 
     match rbac_result {
@@ -91,8 +108,7 @@ pub(super) async fn authenticate_tool_request(
                     record_mcp_access(
                         db_pool,
                         authenticated.context.user_id(),
-                        server_name,
-                        tool_name,
+                        access,
                         "authenticated",
                     )
                     .await;
@@ -100,26 +116,99 @@ pub(super) async fn authenticate_tool_request(
                     Ok((authenticated.context.clone(), token))
                 },
                 Err(e) => {
-                    record_mcp_access_rejected(db_pool, server_name, tool_name, e.message.as_ref())
-                        .await;
+                    record_mcp_access_rejected(db_pool, access, e.message.as_ref()).await;
                     Err(e)
                 },
             }
         },
         Err(e) => {
-            record_mcp_access_rejected(db_pool, server_name, tool_name, &format!("{e}")).await;
+            record_mcp_access_rejected(db_pool, access, &format!("{e}")).await;
             Err(e)
         },
     }
 }
 
+// Why: one rule set covers both kinds of tool, so a typed tool is judged as
+// a one-word path of its own name and the CLI tool on its subcommand words.
+fn requested_path(request: &CallToolRequestParams) -> Result<Vec<String>, McpError> {
+    if request.name != SERVER_NAME {
+        return Ok(vec![request.name.to_string()]);
+    }
+    let command = request
+        .arguments
+        .as_ref()
+        .and_then(|args| args.get("command"))
+        .and_then(|command| command.as_str())
+        .unwrap_or_default();
+    Ok(command_path(&cli::split_command(command)?))
+}
+
+// Why: rules name either roles or permissions (`admin`, `user`), and a token
+// may carry a permission without a matching role, so both are offered.
+fn caller_roles(context: &SysRequestContext) -> Vec<String> {
+    context.user.as_ref().map_or_else(Vec::new, |user| {
+        user.roles()
+            .iter()
+            .cloned()
+            .chain(user.permissions().iter().map(|p| p.as_str().to_owned()))
+            .collect()
+    })
+}
+
+// Why: the settings are re-read on every call so edits apply without a
+// restart, and read once per call so the rules and limits agree.
+pub(super) fn command_settings() -> Result<CommandSettings, McpError> {
+    // Why: lint-ok: error-adapt — rmcp's ErrorData is a variant-less wire type
+    load_settings().map_err(|e| McpError::internal_error(e.to_string(), None))
+}
+
+/// Judge an authenticated call against `policy` before anything runs. A deny
+/// is audited as a rejection naming the rule that decided it; an allow
+/// returns the decision so the caller can put its rule on the `used` row.
+///
+/// Exposed (behind `#[doc(hidden)]`) so the external test workspace can
+/// check the audit rows without a profile to load the policy file from. Not
+/// part of the public API.
+#[doc(hidden)]
+pub async fn authorize(
+    db_pool: &DbPool,
+    server_name: &str,
+    policy: &CommandPolicy,
+    request: &CallToolRequestParams,
+    request_context: &SysRequestContext,
+) -> Result<PolicyDecision, McpError> {
+    let path = requested_path(request)?;
+    let decision = policy.evaluate(&caller_roles(request_context), &path);
+    if decision.allowed {
+        return Ok(decision);
+    }
+    let reason = decision.describe(&path);
+    let access =
+        McpAccess::new(server_name, request.name.as_ref()).with_rule(decision.rule.as_deref());
+    record_mcp_access_rejected(db_pool, access, &reason).await;
+    Err(McpError::invalid_request(
+        format!("Permission denied: {reason}"),
+        None,
+    ))
+}
+
 #[doc(hidden)]
 #[derive(Debug)]
 pub struct Dispatch<'a> {
+    pub db_pool: &'a DbPool,
     pub executor: &'a McpToolExecutor,
     pub request: &'a CallToolRequestParams,
     pub request_context: &'a SysRequestContext,
     pub client: &'a ClientProfile,
+    pub control: &'a CallControl,
+}
+
+impl Dispatch<'_> {
+    async fn run<H: McpToolHandler>(&self, handler: &H) -> Result<CallToolResult, McpError> {
+        self.executor
+            .execute(handler, self.request, self.request_context, self.client)
+            .await
+    }
 }
 
 /// Route one authenticated tool call to its handler.
@@ -137,10 +226,36 @@ pub async fn dispatch_tool(
         "systemprompt" => {
             let handler = SystempromptToolHandler {
                 auth_token: auth_token.to_owned(),
+                control: ctx.control.clone(),
             };
-            ctx.executor
-                .execute(&handler, ctx.request, ctx.request_context, ctx.client)
-                .await
+            ctx.run(&handler).await
+        },
+        LIST_SKILLS => {
+            let handler = ListSkillsHandler {
+                db_pool: Arc::clone(ctx.db_pool),
+                catalog: load_catalog().await?,
+            };
+            ctx.run(&handler).await
+        },
+        SHOW_SKILL => {
+            let handler = ShowSkillHandler {
+                db_pool: Arc::clone(ctx.db_pool),
+                catalog: load_catalog().await?,
+            };
+            ctx.run(&handler).await
+        },
+        LIST_AGENTS => ctx.run(&ListAgentsHandler).await,
+        LIST_INFERENCE_REQUESTS => {
+            let pool = pg_pool(ctx.db_pool)?;
+            ctx.run(&InferenceRequestsHandler { pool }).await
+        },
+        LIST_GOVERNANCE_DECISIONS => {
+            let pool = pg_pool(ctx.db_pool)?;
+            ctx.run(&GovernanceDecisionsHandler { pool }).await
+        },
+        INSPECT_SESSION => {
+            let pool = pg_pool(ctx.db_pool)?;
+            ctx.run(&InspectSessionHandler { pool }).await
         },
         _ => Err(McpError::invalid_params(
             format!(
@@ -0,0 +1,191 @@
+//! Handlers for the typed tools. Each runs one repository query or catalog
+//! read in-process and returns a [`CliArtifact`], the same envelope the CLI
+//! tool produces, so clients render both alike.
+
+mod skills;
+mod tables;
+
+use std::sync::Arc;
+
+use chrono::Utc;
+use rmcp::ErrorData as McpError;
+use sqlx::PgPool;
+use systemprompt::database::DbPool;
+use systemprompt::identifiers::McpExecutionId;
+use systemprompt::mcp::McpToolHandler;
+use systemprompt::models::artifacts::CliArtifact;
+use systemprompt::models::execution::context::RequestContext as SysRequestContext;
+
+use crate::catalog;
+use crate::error::SystempromptToolError;
+use crate::repositories::governance::{DecisionFilter, list_decisions};
+use crate::repositories::inference::{InferenceFilter, list_inference_requests};
+use crate::repositories::sessions::find_session_overview;
+use crate::tools::typed::{
+    DecisionOutcome, GovernanceDecisionsInput, INSPECT_SESSION, InferenceRequestsInput,
+    InspectSessionInput, LIST_AGENTS, LIST_GOVERNANCE_DECISIONS, LIST_INFERENCE_REQUESTS,
+    ListAgentsInput, row_limit, window_start,
+};
+
+#[doc(hidden)]
+pub use skills::{ListSkillsHandler, ShowSkillHandler};
+
+// Why: lint-ok: error-adapt — rmcp's ErrorData is a variant-less wire type
+fn internal(e: impl std::fmt::Display) -> McpError {
+    McpError::internal_error(e.to_string(), None)
+}
+
+pub(super) fn pg_pool(db_pool: &DbPool) -> Result<Arc<PgPool>, McpError> {
+    db_pool
+        .pool()
+        .ok_or_else(|| internal("No PostgreSQL pool is available"))
+}
+
+// Why: the catalog reads YAML and markdown from disk, which must not stall a
+// runtime worker shared with every other in-flight call.
+async fn blocking<T: Send + 'static>(
+    read: impl FnOnce() -> Result<T, SystempromptToolError> + Send + 'static,
+) -> Result<T, McpError> {
+    tokio::task::spawn_blocking(read)
+        .await
+        .map_err(internal)?
+        .map_err(internal)
+}
+
+fn rows_summary(count: usize, noun: &str) -> String {
+    match count {
+        0 => format!("No {noun}s matched."),
+        1 => format!("1 {noun}."),
+        n => format!("{n} {noun}s."),
+    }
+}
+
+pub(super) struct ListAgentsHandler;
+
+impl McpToolHandler for ListAgentsHandler {
+    type Input = ListAgentsInput;
+    type Output = CliArtifact;
+
+    fn tool_name(&self) -> &'static str {
+        LIST_AGENTS
+    }
+
+    async fn handle(
+        &self,
+        input: Self::Input,
+        _ctx: &SysRequestContext,
+        _exec_id: &McpExecutionId,
+    ) -> Result<(Self::Output, String), McpError> {
+        let agents: Vec<_> = blocking(catalog::list_agents)
+            .await?
+            .into_iter()
+            .filter(|a| !input.enabled_only || a.enabled)
+            .collect();
+        let summary = rows_summary(agents.len(), "agent");
+        Ok((CliArtifact::table(tables::agents_table(&agents)), summary))
+    }
+}
+
+pub(super) struct InferenceRequestsHandler {
+    pub(super) pool: Arc<PgPool>,
+}
+
+impl McpToolHandler for InferenceRequestsHandler {
+    type Input = InferenceRequestsInput;
+    type Output = CliArtifact;
+
+    fn tool_name(&self) -> &'static str {
+        LIST_INFERENCE_REQUESTS
+    }
+
+    async fn handle(
+        &self,
+        input: Self::Input,
+        _ctx: &SysRequestContext,
+        _exec_id: &McpExecutionId,
+    ) -> Result<(Self::Output, String), McpError> {
+        let filter = InferenceFilter {
+            user_id: input.user_id.as_ref(),
+            session_id: input.session_id.as_ref(),
+            provider: input.provider.as_deref(),
+            model: input.model.as_deref(),
+            status: input.status.as_deref(),
+        };
+        let since = window_start(input.since_hours, Utc::now());
+        let rows = list_inference_requests(&self.pool, &filter, since, row_limit(input.limit))
+            .await
+            .map_err(internal)?;
+        let summary = rows_summary(rows.len(), "inference request");
+        Ok((CliArtifact::table(tables::inference_table(&rows)), summary))
+    }
+}
+
+pub(super) struct GovernanceDecisionsHandler {
+    pub(super) pool: Arc<PgPool>,
+}
+
+impl McpToolHandler for GovernanceDecisionsHandler {
+    type Input = GovernanceDecisionsInput;
+    type Output = CliArtifact;
+
+    fn tool_name(&self) -> &'static str {
+        LIST_GOVERNANCE_DECISIONS
+    }
+
+    async fn handle(
+        &self,
+        input: Self::Input,
+        _ctx: &SysRequestContext,
+        _exec_id: &McpExecutionId,
+    ) -> Result<(Self::Output, String), McpError> {
+        let filter = DecisionFilter {
+            decision: input.decision.map(DecisionOutcome::as_str),
+            policy: input.policy.as_deref(),
+            user_id: input.user_id.as_ref(),
+            session_id: input.session_id.as_ref(),
+            tool_name: input.tool_name.as_deref(),
+        };
+        let since = window_start(input.since_hours, Utc::now());
+        let rows = list_decisions(&self.pool, &filter, since, row_limit(input.limit))
+            .await
+            .map_err(internal)?;
+        let summary = rows_summary(rows.len(), "governance decision");
+        Ok((CliArtifact::table(tables::decisions_table(&rows)), summary))
+    }
+}
+
+pub(super) struct InspectSessionHandler {
+    pub(super) pool: Arc<PgPool>,
+}
+
+impl McpToolHandler for InspectSessionHandler {
+    type Input = InspectSessionInput;
+    type Output = CliArtifact;
+
+    fn tool_name(&self) -> &'static str {
+        INSPECT_SESSION
+    }
+
+    async fn handle(
+        &self,
+        input: Self::Input,
+        _ctx: &SysRequestContext,
+        _exec_id: &McpExecutionId,
+    ) -> Result<(Self::Output, String), McpError> {
+        let id = input.session_id.as_str();
+        let Some(row) = find_session_overview(&self.pool, &input.session_id)
+            .await
+            .map_err(internal)?
+        else {
+            return Err(McpError::invalid_params(
+                format!("Session '{id}' has no summary, analysis, requests or decisions."),
+                None,
+            ));
+        };
+        let summary = format!(
+            "Session '{id}': {} inference requests, {} denied decisions.",
+            row.ai_requests, row.decisions_denied
+        );
+        Ok((CliArtifact::table(tables::session_table(id, &row)), summary))
+    }
+}
@@ -0,0 +1,103 @@
+//! `list_skills` and `show_skill`. Both answer with only the skills the
+//! caller may see, judged by the same marketplace access rules as the skill
+//! prompts, so a skill withheld from one surface cannot be read through the
+//! other.
+
+use rmcp::ErrorData as McpError;
+use systemprompt::database::DbPool;
+use systemprompt::identifiers::McpExecutionId;
+use systemprompt::mcp::McpToolHandler;
+use systemprompt::models::artifacts::CliArtifact;
+use systemprompt::models::execution::context::RequestContext as SysRequestContext;
+
+use super::{blocking, rows_summary, tables};
+use crate::catalog::{self, SkillCatalog};
+use crate::server::skill_access::visible_skills;
+use crate::tools::typed::{LIST_SKILLS, ListSkillsInput, SHOW_SKILL, ShowSkillInput};
+
+/// Exposed (behind `#[doc(hidden)]`) so the external test workspace can drive
+/// it over a fixture catalog; not part of the public API.
+#[doc(hidden)]
+#[derive(Debug)]
+pub struct ListSkillsHandler {
+    pub db_pool: DbPool,
+    pub catalog: SkillCatalog,
+}
+
+impl McpToolHandler for ListSkillsHandler {
+    type Input = ListSkillsInput;
+    type Output = CliArtifact;
+
+    fn tool_name(&self) -> &'static str {
+        LIST_SKILLS
+    }
+
+    async fn handle(
+        &self,
+        input: Self::Input,
+        ctx: &SysRequestContext,
+        _exec_id: &McpExecutionId,
+    ) -> Result<(Self::Output, String), McpError> {
+        let skills: Vec<_> = visible_skills(&self.db_pool, ctx.user_id(), &self.catalog)
+            .await?
+            .into_iter()
+            .filter(|s| !input.enabled_only || s.config.enabled)
+            .filter(|s| {
+                input
+                    .tag
+                    .as_deref()
+                    .is_none_or(|tag| s.config.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)))
+            })
+            .collect();
+        let summary = rows_summary(skills.len(), "skill");
+        Ok((CliArtifact::table(tables::skills_table(&skills)), summary))
+    }
+}
+
+/// Exposed (behind `#[doc(hidden)]`) so the external test workspace can drive
+/// it over a fixture catalog; not part of the public API.
+#[doc(hidden)]
+#[derive(Debug)]
+pub struct ShowSkillHandler {
+    pub db_pool: DbPool,
+    pub catalog: SkillCatalog,
+}
+
+impl McpToolHandler for ShowSkillHandler {
+    type Input = ShowSkillInput;
+    type Output = CliArtifact;
+
+    fn tool_name(&self) -> &'static str {
+        SHOW_SKILL
+    }
+
+    async fn handle(
+        &self,
+        input: Self::Input,
+        ctx: &SysRequestContext,
+        _exec_id: &McpExecutionId,
+    ) -> Result<(Self::Output, String), McpError> {
+        let id = input.skill_id.as_str().to_owned();
+        // Why: a skill the caller may not see answers exactly like one that
+        // does not exist, so skill ids cannot be probed through this tool.
+        let not_found = || {
+            McpError::invalid_params(
+                format!("Skill '{id}' not found. Call '{LIST_SKILLS}' for the valid ids."),
+                None,
+            )
+        };
+        let visible = visible_skills(&self.db_pool, ctx.user_id(), &self.catalog).await?;
+        if !visible.iter().any(|skill| skill.id == id) {
+            return Err(not_found());
+        }
+        let (root, lookup) = (self.catalog.root.clone(), id.clone());
+        let (skill, instructions) = blocking(move || catalog::find_skill_in(&root, &lookup))
+            .await?
+            .ok_or_else(not_found)?;
+        let summary = format!("Skill '{id}': {}", skill.config.name);
+        Ok((
+            CliArtifact::text(tables::skill_text(&skill, &instructions)),
+            summary,
+        ))
+    }
+}
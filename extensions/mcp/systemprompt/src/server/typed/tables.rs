@@ -0,0 +1,209 @@
+//! Rows to artifacts. Every typed tool answers with a table, except
+//! `show_skill`, whose instruction body is prose and goes out as text.
+
+use chrono::{DateTime, Utc};
+use serde_json::{Value, json};
+use systemprompt::identifiers::{AgentId, SessionId, TraceId};
+use systemprompt::models::artifacts::{Column, ColumnType, TableArtifact, TextArtifact};
+
+use crate::catalog::{AgentEntry, SkillEntry};
+use crate::repositories::governance::DecisionRow;
+use crate::repositories::inference::InferenceRequestRow;
+use crate::repositories::sessions::SessionOverviewRow;
+
+fn columns(spec: &[(&str, ColumnType)]) -> Vec<Column> {
+    spec.iter()
+        .map(|(name, kind)| Column::new(*name, *kind))
+        .collect()
+}
+
+fn time(t: DateTime<Utc>) -> String {
+    t.to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
+}
+
+fn dollars(microdollars: i64) -> f64 {
+    microdollars as f64 / 1_000_000.0
+}
+
+pub(super) fn skills_table(skills: &[SkillEntry]) -> TableArtifact {
+    // JSON: protocol boundary
+    let rows: Vec<Value> = skills
+        .iter()
+        .map(|s| {
+            json!({
+                "skill_id": s.id,
+                "name": s.config.name,
+                "description": s.config.description,
+                "enabled": s.config.enabled,
+                "category": s.config.category,
+                "tags": s.config.tags.join(", "),
+            })
+        })
+        .collect();
+    TableArtifact::new(columns(&[
+        ("skill_id", ColumnType::String),
+        ("name", ColumnType::String),
+        ("description", ColumnType::String),
+        ("enabled", ColumnType::Boolean),
+        ("category", ColumnType::String),
+        ("tags", ColumnType::String),
+    ]))
+    .with_rows(rows)
+}
+
+pub(super) fn skill_text(skill: &SkillEntry, instructions: &str) -> TextArtifact {
+    let c = &skill.config;
+    let body = format!(
+        "{}\n\nid: {}\nenabled: {}\ncategory: {}\ntags: {}\nconfig: {}\n\n{}",
+        c.description,
+        skill.id,
+        c.enabled,
+        c.category.as_deref().unwrap_or("-"),
+        c.tags.join(", "),
+        skill.config_path.display(),
+        instructions.trim(),
+    );
+    TextArtifact::new(&body).with_title(format!("Skill: {}", c.name))
+}
+
+pub(super) fn agents_table(agents: &[AgentEntry]) -> TableArtifact {
+    // JSON: protocol boundary
+    let rows: Vec<Value> = agents
+        .iter()
+        .map(|a| {
+            json!({
+                "name": a.name,
+                "display_name": a.display_name,
+                "description": a.description,
+                "enabled": a.enabled,
+                "provider": a.provider,
+                "model": a.model,
+                "mcp_servers": a.mcp_servers.join(", "),
+                "skills": a.skills.join(", "),
+            })
+        })
+        .collect();
+    TableArtifact::new(columns(&[
+        ("name", ColumnType::String),
+        ("display_name", ColumnType::String),
+        ("description", ColumnType::String),
+        ("enabled", ColumnType::Boolean),
+        ("provider", ColumnType::String),
+        ("model", ColumnType::String),
+        ("mcp_servers", ColumnType::String),
+        ("skills", ColumnType::String),
+    ]))
+    .with_rows(rows)
+}
+
+pub(super) fn inference_table(rows: &[InferenceRequestRow]) -> TableArtifact {
+    // JSON: protocol boundary
+    let items: Vec<Value> = rows
+        .iter()
+        .map(|r| {
+            json!({
+                "request_id": r.id,
+                "created_at": time(r.created_at),
+                "user_id": r.user_id.as_str(),
+                "session_id": r.session_id.as_ref().map(SessionId::as_str),
+                "trace_id": r.trace_id.as_ref().map(TraceId::as_str),
+                "provider": r.provider,
+                "model": r.model,
+                "status": r.status,
+                "input_tokens": r.input_tokens,
+                "output_tokens": r.output_tokens,
+                "cost_usd": dollars(r.cost_microdollars),
+                "latency_ms": r.latency_ms,
+                "error": r.error_message,
+            })
+        })
+        .collect();
+    TableArtifact::new(columns(&[
+        ("request_id", ColumnType::String),
+        ("created_at", ColumnType::Date),
+        ("user_id", ColumnType::String),
+        ("session_id", ColumnType::String),
+        ("trace_id", ColumnType::String),
+        ("provider", ColumnType::String),
+        ("model", ColumnType::String),
+        ("status", ColumnType::String),
+        ("input_tokens", ColumnType::Integer),
+        ("output_tokens", ColumnType::Integer),
+        ("cost_usd", ColumnType::Currency),
+        ("latency_ms", ColumnType::Integer),
+        ("error", ColumnType::String),
+    ]))
+    .with_rows(items)
+}
+
+pub(super) fn decisions_table(rows: &[DecisionRow]) -> TableArtifact {
+    // JSON: protocol boundary
+    let items: Vec<Value> = rows
+        .iter()
+        .map(|r| {
+            json!({
+                "decision_id": r.id,
+                "created_at": time(r.created_at),
+                "decision": r.decision,
+                "policy": r.policy,
+                "reason": r.reason,
+                "tool_name": r.tool_name,
+                "agent_id": r.agent_id.as_ref().map(AgentId::as_str),
+                "user_id": r.user_id.as_str(),
+                "session_id": r.session_id.as_str(),
+            })
+        })
+        .collect();
+    TableArtifact::new(columns(&[
+        ("decision_id", ColumnType::String),
+        ("created_at", ColumnType::Date),
+        ("decision", ColumnType::String),
+        ("policy", ColumnType::String),
+        ("reason", ColumnType::String),
+        ("tool_name", ColumnType::String),
+        ("agent_id", ColumnType::String),
+        ("user_id", ColumnType::String),
+        ("session_id", ColumnType::String),
+    ]))
+    .with_rows(items)
+}
+
+pub(super) fn session_table(session: &str, row: &SessionOverviewRow) -> TableArtifact {
+    let or_dash = |v: Option<String>| v.unwrap_or_else(|| "-".to_owned());
+    let fields = [
+        ("session_id", session.to_owned()),
+        (
+            "user_id",
+            or_dash(row.user_id.as_ref().map(ToString::to_string)),
+        ),
+        ("plugin", or_dash(row.plugin_id.clone())),
+        ("model", or_dash(row.model.clone())),
+        ("status", or_dash(row.status.clone())),
+        ("started_at", or_dash(row.started_at.map(time))),
+        ("ended_at", or_dash(row.ended_at.map(time))),
+        ("prompts", or_dash(row.prompts.map(|n| n.to_string()))),
+        ("tool_uses", or_dash(row.tool_uses.map(|n| n.to_string()))),
+        ("errors", or_dash(row.errors.map(|n| n.to_string()))),
+        ("ai_requests", row.ai_requests.to_string()),
+        ("cost_usd", format!("{:.4}", dollars(row.cost_microdollars))),
+        ("decisions_allowed", row.decisions_allowed.to_string()),
+        ("decisions_denied", row.decisions_denied.to_string()),
+        ("title", or_dash(row.title.clone())),
+        ("goal_achieved", or_dash(row.goal_achieved.clone())),
+        (
+            "quality_score",
+            or_dash(row.quality_score.map(|n| n.to_string())),
+        ),
+        ("summary", or_dash(row.summary.clone())),
+    ];
+    // JSON: protocol boundary
+    let items: Vec<Value> = fields
+        .into_iter()
+        .map(|(field, value)| json!({ "field": field, "value": value }))
+        .collect();
+    TableArtifact::new(columns(&[
+        ("field", ColumnType::String),
+        ("value", ColumnType::String),
+    ]))
+    .with_rows(items)
+}
@@ -0,0 +1,265 @@
+//! Serving the server over stdio, authenticated by a personal access token.
+//!
+//! Over HTTP every request arrives with its `Authorization` header and the
+//! gateway's context headers, and the RBAC middleware reads both from the
+//! request parts rmcp attaches to each message. A stdio client has neither,
+//! so [`PatTransport`] attaches them itself: it resolves the token named by
+//! [`TOKEN_ENV`] to its owner, mints a short-lived JWT for the server's
+//! audience, and puts it on every incoming message. From there the request
+//! takes the HTTP path unchanged: the same OAuth checks, authz hook, command
+//! rules and audit rows.
+//!
+//! The token is re-resolved every few minutes, so revoking it, letting it
+//! expire or deactivating its owner ends the session's access without a
+//! restart; a request made after that is rejected and audited like any
+//! unauthenticated one.
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use axum::http::header::AUTHORIZATION;
+use axum::http::request::Parts;
+use axum::http::{HeaderValue, Request};
+use rmcp::RoleServer;
+use rmcp::model::{ClientJsonRpcMessage, GetExtensions};
+use rmcp::service::{RxJsonRpcMessage, TxJsonRpcMessage};
+use rmcp::transport::Transport;
+use sqlx::PgPool;
+use systemprompt::identifiers::{AgentName, ContextId, McpServerId, SessionId, TraceId, UserId};
+use systemprompt::loader::ConfigLoader;
+use systemprompt::models::Config;
+use systemprompt::models::auth::{AuthenticatedUser, JwtAudience, Permission};
+use systemprompt::models::execution::context::RequestContext as SysRequestContext;
+use systemprompt::oauth::services::{
+    JwtConfig, JwtSigningParams, generate_access_token_jti, generate_jwt,
+};
+use systemprompt_web_access::repositories::api_keys::find_api_key_owner;
+use systemprompt_web_access::repositories::users::find_user_identity;
+use tokio::sync::Mutex;
+
+use crate::error::SystempromptToolError;
+
+/// Environment variable holding the personal access token for `--stdio`.
+pub const TOKEN_ENV: &str = "SYSTEMPROMPT_MCP_TOKEN";
+
+/// The agent name stdio requests are attributed to.
+pub const STDIO_AGENT: &str = "mcp-stdio";
+
+// Why: the re-resolve interval bounds how long a revoked token keeps working;
+// the JWT outlives it comfortably so an in-flight call never holds an expired
+// one.
+const REFRESH_AFTER: Duration = Duration::from_mins(5);
+const JWT_LIFETIME_HOURS: i64 = 1;
+
+struct Minted {
+    jwt: String,
+    at: Instant,
+}
+
+/// A personal access token and the JWT most recently minted from it.
+pub struct PatCredential {
+    pool: Arc<PgPool>,
+    token: String,
+    service_id: McpServerId,
+    session_id: SessionId,
+    minted: Mutex<Option<Minted>>,
+}
+
+// Why: the token and the JWT are credentials and stay out of debug output.
+impl std::fmt::Debug for PatCredential {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PatCredential")
+            .field("service_id", &self.service_id)
+            .field("session_id", &self.session_id)
+            .finish_non_exhaustive()
+    }
+}
+
+impl PatCredential {
+    /// Resolve `token` once, failing when it does not name a live key of an
+    /// active user, so a bad token stops the server before it serves.
+    pub async fn resolve(
+        pool: Arc<PgPool>,
+        token: String,
+        service_id: McpServerId,
+    ) -> Result<Self, SystempromptToolError> {
+        let credential = Self {
+            pool,
+            token,
+            service_id,
+            session_id: SessionId::generate(),
+            minted: Mutex::new(None),
+        };
+        let jwt = credential.mint().await?;
+        *credential.minted.lock().await = Some(Minted {
+            jwt,
+            at: Instant::now(),
+        });
+        Ok(credential)
+    }
+
+    pub const fn session_id(&self) -> &SessionId {
+        &self.session_id
+    }
+
+    async fn mint(&self) -> Result<String, SystempromptToolError> {
+        let owner = find_api_key_owner(&self.pool, &self.token)
+            .await
+            .map_err(|e| SystempromptToolError::Internal(e.to_string()))?
+            .ok_or_else(|| {
+                SystempromptToolError::Unauthorized(format!(
+                    "{TOKEN_ENV} is not a live personal access token of an active user"
+                ))
+            })?;
+        let identity = find_user_identity(&self.pool, &owner)
+            .await
+            .map_err(|e| SystempromptToolError::Internal(e.to_string()))?
+            .ok_or_else(|| {
+                SystempromptToolError::Unauthorized(format!("Token owner {owner} not found"))
+            })?;
+        let user = AuthenticatedUser::new_with_roles(
+            owner_uuid(&owner)?,
+            identity.name,
+            identity.email,
+            permissions(&identity.roles),
+            identity.roles,
+        );
+        let config = Config::get().map_err(|e| SystempromptToolError::Internal(e.to_string()))?;
+        generate_jwt(
+            &user,
+            JwtConfig {
+                permissions: user.permissions.clone(),
+                audience: audience(&self.service_id)?,
+                expires_in_hours: Some(JWT_LIFETIME_HOURS),
+                resource: None,
+                plugin_id: None,
+            },
+            generate_access_token_jti(),
+            &self.session_id,
+            &JwtSigningParams {
+                issuer: &config.jwt_issuer,
+            },
+        )
+        .map_err(|e| SystempromptToolError::Internal(e.to_string()))
+    }
+
+    async fn bearer(&self) -> Option<String> {
+        let mut minted = self.minted.lock().await;
+        if let Some(current) = minted.as_ref()
+            && current.at.elapsed() < REFRESH_AFTER
+        {
+            return Some(current.jwt.clone());
+        }
+        match self.mint().await {
+            Ok(jwt) => {
+                *minted = Some(Minted {
+                    jwt: jwt.clone(),
+                    at: Instant::now(),
+                });
+                Some(jwt)
+            },
+            Err(e) => {
+                tracing::warn!(error = %e, "Personal access token no longer resolves");
+                *minted = None;
+                None
+            },
+        }
+    }
+}
+
+fn owner_uuid(owner: &UserId) -> Result<uuid::Uuid, SystempromptToolError> {
+    owner
+        .as_str()
+        .parse()
+        .map_err(|e| SystempromptToolError::Internal(format!("Token owner id {owner}: {e}")))
+}
+
+/// The scopes a JWT for a user holding `roles` carries: each role that names
+/// a permission.
+#[doc(hidden)]
+pub fn permissions(roles: &[String]) -> Vec<Permission> {
+    roles.iter().filter_map(|role| role.parse().ok()).collect()
+}
+
+// Why: the RBAC check wants the server's own audience on top of the
+// first-party ones the JWT validator accepts.
+fn audience(service_id: &McpServerId) -> Result<Vec<JwtAudience>, SystempromptToolError> {
+    let config = ConfigLoader::load().map_err(|e| {
+        SystempromptToolError::Internal(format!("Failed to load services configuration: {e}"))
+    })?;
+    let mut audience = JwtAudience::standard();
+    if let Some(server) = config.mcp_servers.get(service_id.as_str())
+        && !audience.contains(&server.oauth.audience)
+    {
+        audience.push(server.oauth.audience.clone());
+    }
+    Ok(audience)
+}
+
+/// The request parts an HTTP request would have carried: the bearer, when
+/// there is one, and the request context the gateway would have set.
+#[doc(hidden)]
+pub fn request_parts(bearer: Option<&str>, session_id: &SessionId) -> Parts {
+    let (mut parts, ()) = Request::new(()).into_parts();
+    if let Some(bearer) = bearer {
+        match HeaderValue::from_str(&format!("Bearer {bearer}")) {
+            Ok(value) => {
+                parts.headers.insert(AUTHORIZATION, value);
+            },
+            Err(e) => tracing::warn!(error = %e, "Minted token is not a valid header value"),
+        }
+    }
+    parts.extensions.insert(SysRequestContext::new(
+        session_id.clone(),
+        TraceId::generate(),
+        ContextId::derived_from_session(session_id),
+        AgentName::new(STDIO_AGENT),
+    ));
+    parts
+}
+
+/// A server transport that attaches a [`PatCredential`] to every incoming
+/// request and notification.
+#[derive(Debug)]
+pub struct PatTransport<T> {
+    inner: T,
+    credential: Arc<PatCredential>,
+}
+
+impl<T> PatTransport<T> {
+    pub const fn new(inner: T, credential: Arc<PatCredential>) -> Self {
+        Self { inner, credential }
+    }
+}
+
+impl<T: Transport<RoleServer>> Transport<RoleServer> for PatTransport<T> {
+    type Error = T::Error;
+
+    fn send(
+        &mut self,
+        item: TxJsonRpcMessage<RoleServer>,
+    ) -> impl Future<Output = Result<(), Self::Error>> + Send + 'static {
+        self.inner.send(item)
+    }
+
+    async fn receive(&mut self) -> Option<RxJsonRpcMessage<RoleServer>> {
+        let mut message = self.inner.receive().await?;
+        let extensions = match &mut message {
+            ClientJsonRpcMessage::Request(request) => request.request.extensions_mut(),
+            ClientJsonRpcMessage::Notification(notification) => {
+                notification.notification.extensions_mut()
+            },
+            _ => return Some(message),
+        };
+        let bearer = self.credential.bearer().await;
+        extensions.insert(request_parts(
+            bearer.as_deref(),
+            self.credential.session_id(),
+        ));
+        Some(message)
+    }
+
+    fn close(&mut self) -> impl Future<Output = Result<(), Self::Error>> + Send {
+        self.inner.close()
+    }
+}
@@ -0,0 +1,173 @@
+//! Tool definitions exposed by the `systemprompt` MCP server.
+//!
+//! The `systemprompt` tool runs any CLI command; the typed tools in [`typed`]
+//! cover the common reads with real argument schemas and are listed first so
+//! a model reaches for them before composing a command line.
+
+pub mod typed;
+
+use rmcp::model::{MetaObject, Tool};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use systemprompt::mcp::{McpOutputSchema, WEBSITE_URL, default_tool_visibility, tool_ui_meta};
+use systemprompt::models::artifacts::CliArtifact;
+use typed::{
+    GovernanceDecisionsInput, INSPECT_SESSION, InferenceRequestsInput, InspectSessionInput,
+    LIST_AGENTS, LIST_GOVERNANCE_DECISIONS, LIST_INFERENCE_REQUESTS, LIST_SKILLS, ListAgentsInput,
+    ListSkillsInput, SHOW_SKILL, ShowSkillInput,
+};
+
+pub const SERVER_NAME: &str = "systemprompt";
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CliInput {
+    /// The CLI command to execute (without 'systemprompt' prefix). Examples:
+    /// 'plugins run discord send "message"', 'core skills list'
+    pub command: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CliOutput {
+    pub stdout: String,
+    pub stderr: String,
+    pub exit_code: i32,
+    pub success: bool,
+}
+
+#[must_use]
+// JSON: protocol boundary
+pub fn input_schema() -> serde_json::Value {
+    schemars::schema_for!(CliInput).to_value()
+}
+
+#[must_use]
+// JSON: protocol boundary
+pub fn output_schema() -> serde_json::Value {
+    <CliArtifact as McpOutputSchema>::validated_schema()
+}
+
+struct ToolDef<'a> {
+    server_name: &'a str,
+    name: &'a str,
+    title: &'a str,
+    description: &'a str,
+    // JSON: protocol boundary
+    input_schema: &'a serde_json::Value,
+    // JSON: protocol boundary
+    output_schema: &'a serde_json::Value,
+}
+
+fn create_tool(def: &ToolDef<'_>) -> Tool {
+    let input_obj = def
+        .input_schema
+        .as_object()
+        .cloned()
+        .unwrap_or_else(serde_json::Map::new);
+    let output_obj = def
+        .output_schema
+        .as_object()
+        .cloned()
+        .unwrap_or_else(serde_json::Map::new);
+
+    let mut tool = Tool::default();
+    tool.name = def.name.to_owned().into();
+    tool.title = Some(def.title.to_owned());
+    tool.description = Some(def.description.to_owned().into());
+    tool.input_schema = Arc::new(input_obj);
+    tool.output_schema = Some(Arc::new(output_obj));
+    tool.meta = Some(MetaObject(tool_ui_meta(
+        def.server_name,
+        &default_tool_visibility(),
+    )));
+    tool
+}
+
+// JSON: protocol boundary
+fn schema_of<T: JsonSchema>() -> serde_json::Value {
+    schemars::schema_for!(T).to_value()
+}
+
+fn typed_tools(output: &serde_json::Value) -> Vec<Tool> {
+    let defs = [
+        (
+            LIST_SKILLS,
+            "List skills",
+            "List installed skills with their id, name, description, category and tags.",
+            schema_of::<ListSkillsInput>(),
+        ),
+        (
+            SHOW_SKILL,
+            "Show skill",
+            "Show one skill's config and full instruction body by skill id.",
+            schema_of::<ShowSkillInput>(),
+        ),
+        (
+            LIST_AGENTS,
+            "List agents",
+            "List configured agents with their model, MCP servers and skills.",
+            schema_of::<ListAgentsInput>(),
+        ),
+        (
+            LIST_INFERENCE_REQUESTS,
+            "Query inference requests",
+            "List recent AI gateway requests, newest first, filtered by user, session, provider, \
+             model, status and time window. Returns tokens, cost, latency and errors.",
+            schema_of::<InferenceRequestsInput>(),
+        ),
+        (
+            LIST_GOVERNANCE_DECISIONS,
+            "Look up governance decisions",
+            "List recent allow/deny decisions, newest first, filtered by outcome, policy, user, \
+             session, tool and time window. Returns the policy and reason for each.",
+            schema_of::<GovernanceDecisionsInput>(),
+        ),
+        (
+            INSPECT_SESSION,
+            "Inspect session",
+            "Summarise one session: its plugin, model, activity, analysis, inference requests, \
+             cost and governance decisions.",
+            schema_of::<InspectSessionInput>(),
+        ),
+    ];
+    defs.iter()
+        .map(|(name, title, description, input)| {
+            create_tool(&ToolDef {
+                server_name: SERVER_NAME,
+                name,
+                title,
+                description,
+                input_schema: input,
+                output_schema: output,
+            })
+        })
+        .collect()
+}
+
+#[must_use]
+pub fn list_tools() -> Vec<Tool> {
+    let desc = format!(
+        "Execute SystemPrompt CLI commands. Pass the command WITHOUT the 'systemprompt' prefix. \
+        Prefer the typed tools ({LIST_SKILLS}, {SHOW_SKILL}, {LIST_AGENTS}, \
+        {LIST_INFERENCE_REQUESTS}, {LIST_GOVERNANCE_DECISIONS}, {INSPECT_SESSION}) when one \
+        covers the task.\n\n\
+        Common commands:\n  \
+        - core content list: List markdown content\n  \
+        - plugins run discord send \"message\": Send Discord notification\n  \
+        - plugins run discord send \"message\" --channel <id>: Send to specific channel\n  \
+        - infra logs audit <request-id> --full: Reconstruct one request end to end\n\n\
+        Example: {{\"command\": \"core content list\"}}\n\n\
+        Full documentation: {WEBSITE_URL}/docs"
+    );
+    let output = output_schema();
+    let mut tools = typed_tools(&output);
+    tools.push(create_tool(&ToolDef {
+        server_name: SERVER_NAME,
+        name: "systemprompt",
+        title: "SystemPrompt CLI",
+        description: &desc,
+        input_schema: &input_schema(),
+        output_schema: &output,
+    }));
+    tools
+}
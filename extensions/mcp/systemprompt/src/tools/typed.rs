@@ -0,0 +1,127 @@
+//! Inputs for the typed tools: one struct per tool, so the schema a model
+//! sees is exactly the arguments the query accepts.
+
+use chrono::{DateTime, Duration, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use systemprompt::identifiers::{SessionId, SkillId, UserId};
+
+pub const LIST_SKILLS: &str = "list_skills";
+pub const SHOW_SKILL: &str = "show_skill";
+pub const LIST_INFERENCE_REQUESTS: &str = "list_inference_requests";
+pub const LIST_GOVERNANCE_DECISIONS: &str = "list_governance_decisions";
+pub const INSPECT_SESSION: &str = "inspect_session";
+pub const LIST_AGENTS: &str = "list_agents";
+
+pub const DEFAULT_SINCE_HOURS: u32 = 24;
+/// Thirty days; a wider window belongs in the analytics pages, not a tool
+/// result a model has to read.
+pub const MAX_SINCE_HOURS: u32 = 720;
+pub const DEFAULT_LIMIT: u32 = 25;
+pub const MAX_LIMIT: u32 = 200;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct ListSkillsInput {
+    /// Only return enabled skills.
+    #[serde(default)]
+    pub enabled_only: bool,
+    /// Only return skills carrying this tag, e.g. "governance".
+    #[serde(default)]
+    pub tag: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ShowSkillInput {
+    /// The skill's directory id, as returned by `list_skills`.
+    pub skill_id: SkillId,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum DecisionOutcome {
+    Allow,
+    Deny,
+}
+
+impl DecisionOutcome {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Allow => "allow",
+            Self::Deny => "deny",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct InferenceRequestsInput {
+    #[serde(default)]
+    pub user_id: Option<UserId>,
+    #[serde(default)]
+    pub session_id: Option<SessionId>,
+    /// Exact provider name, e.g. "anthropic".
+    #[serde(default)]
+    pub provider: Option<String>,
+    /// Case-insensitive substring of the model, e.g. "sonnet".
+    #[serde(default)]
+    pub model: Option<String>,
+    /// Exact request status, e.g. "completed" or "failed".
+    #[serde(default)]
+    pub status: Option<String>,
+    /// How many hours back to look. Defaults to 24, at most 720.
+    #[serde(default)]
+    pub since_hours: Option<u32>,
+    /// Maximum rows to return. Defaults to 25, at most 200.
+    #[serde(default)]
+    pub limit: Option<u32>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct GovernanceDecisionsInput {
+    #[serde(default)]
+    pub decision: Option<DecisionOutcome>,
+    /// Exact policy id, e.g. `scope_check`.
+    #[serde(default)]
+    pub policy: Option<String>,
+    #[serde(default)]
+    pub user_id: Option<UserId>,
+    #[serde(default)]
+    pub session_id: Option<SessionId>,
+    /// Case-insensitive substring of the tool name.
+    #[serde(default)]
+    pub tool_name: Option<String>,
+    /// How many hours back to look. Defaults to 24, at most 720.
+    #[serde(default)]
+    pub since_hours: Option<u32>,
+    /// Maximum rows to return. Defaults to 25, at most 200.
+    #[serde(default)]
+    pub limit: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct InspectSessionInput {
+    pub session_id: SessionId,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, JsonSchema)]
+pub struct ListAgentsInput {
+    /// Only return enabled agents.
+    #[serde(default)]
+    pub enabled_only: bool,
+}
+
+/// The start of a `since_hours` window ending at `now`, clamped to
+/// [1, [`MAX_SINCE_HOURS`]].
+#[must_use]
+pub fn window_start(since_hours: Option<u32>, now: DateTime<Utc>) -> DateTime<Utc> {
+    let hours = since_hours
+        .unwrap_or(DEFAULT_SINCE_HOURS)
+        .clamp(1, MAX_SINCE_HOURS);
+    now - Duration::hours(i64::from(hours))
+}
+
+/// A row cap clamped to [1, [`MAX_LIMIT`]].
+#[must_use]
+pub fn row_limit(limit: Option<u32>) -> i64 {
+    i64::from(limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT))
+}
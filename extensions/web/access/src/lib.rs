@@ -0,0 +1,19 @@
+//! Access rules and credential checks shared by the admin extension and the
+//! bundled MCP servers.
+//!
+//! Both sides have to answer the same questions the same way — which
+//! marketplace items a user may see, who a personal access token belongs to,
+//! and what a credential looks like in text headed for display — without the
+//! MCP servers linking the whole admin dashboard to ask them.
+//!
+//! - [`marketplace_filter`] — the template's marketplace filter, registered
+//!   with core, and [`authz`], the subject dimensions it resolves against.
+//! - [`repositories`] — the user and API key lookups behind a principal.
+//! - [`redact_text`] — the credential redactor for transcripts and captures.
+
+pub mod authz;
+pub mod marketplace_filter;
+mod redact;
+pub mod repositories;
+
+pub use redact::redact_text;
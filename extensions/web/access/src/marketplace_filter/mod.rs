@@ -17,8 +17,6 @@
 #[doc(hidden)]
 pub mod keepsets;
 
-pub(crate) use keepsets::entity_ref_for;
-
 use std::sync::Arc;
 
 use sqlx::PgPool;
@@ -32,10 +30,10 @@ use systemprompt_security::authz::{
     ResolveParent, resolve as resolve_access,
 };
 
-use keepsets::{CandidateEntityIds, KeepIdsQuery, KeepSets, apply_keep_sets};
+use keepsets::{CandidateEntityIds, KeepIdsQuery, KeepSets, apply_keep_sets, entity_ref_for};
 
 use crate::authz::{dimensions, subject_attributes_for};
-use crate::repositories::users::queries::find_user_roles_department;
+use crate::repositories::users::find_user_roles_department;
 
 #[derive(Debug)]
 pub struct TemplateMarketplaceFilter {
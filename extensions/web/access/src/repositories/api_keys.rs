@@ -0,0 +1,50 @@
+//! Personal access token lookup: the secret's shape and hash, and the live
+//! key a presented secret belongs to. Issuing and revoking keys stays with
+//! the admin extension.
+
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use systemprompt::identifiers::UserId;
+
+pub const API_KEY_PREFIX: &str = "sp-live-";
+
+/// Resolve a presented secret to the user it belongs to, if the key is live
+/// (unrevoked, unexpired, owner active), and stamp `last_used_at`.
+pub async fn find_api_key_owner(
+    pool: &PgPool,
+    secret: &str,
+) -> Result<Option<UserId>, sqlx::Error> {
+    let Some((key_prefix, _)) = secret
+        .split_once('.')
+        .filter(|(prefix, _)| prefix.starts_with(API_KEY_PREFIX))
+    else {
+        return Ok(None);
+    };
+    let owner = sqlx::query_scalar!(
+        r#"
+        UPDATE user_api_keys
+        SET last_used_at = CURRENT_TIMESTAMP
+        WHERE key_prefix = $1
+          AND key_hash = $2
+          AND revoked_at IS NULL
+          AND (expires_at IS NULL OR expires_at > CURRENT_TIMESTAMP)
+          AND EXISTS (
+              SELECT 1 FROM users u WHERE u.id = user_api_keys.user_id AND u.status = 'active'
+          )
+        RETURNING user_id
+        "#,
+        key_prefix,
+        hash_secret(secret),
+    )
+    .fetch_optional(pool)
+    .await?;
+    Ok(owner.map(UserId::new))
+}
+
+/// The stored form of a secret; only this hash is ever persisted.
+#[must_use]
+pub fn hash_secret(secret: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(secret.as_bytes());
+    hex::encode(hasher.finalize())
+}
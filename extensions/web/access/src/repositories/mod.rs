@@ -0,0 +1,5 @@
+//! The `sqlx` lookups behind a principal: who holds a personal access token,
+//! and the identity, roles and department of a user.
+
+pub mod api_keys;
+pub mod users;
@@ -1,4 +1,6 @@
-//! Lookup of the identity record behind a signed-in user.
+//! The user record behind a principal: the identity a signed-in user or
+//! token owner carries, and the roles and department access rules resolve
+//! against.
 
 use sqlx::PgPool;
 use systemprompt::identifiers::UserId;
@@ -32,3 +34,22 @@ pub async fn find_user_identity(
         roles: r.roles,
     }))
 }
+
+pub async fn find_user_roles_department(
+    pool: &PgPool,
+    user_id: &UserId,
+) -> Result<Option<(Vec<String>, String)>, sqlx::Error> {
+    let row = sqlx::query!(
+        r#"
+        SELECT u.roles, COALESCE(upe.department, 'Default') AS "department!"
+        FROM users u
+        LEFT JOIN user_profile_ext upe ON upe.user_id = u.id
+        WHERE u.id = $1
+        "#,
+        user_id.as_str()
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.map(|r| (r.roles, r.department)))
+}
@@ -17,8 +17,8 @@ use systemprompt::models::bridge::manifest::{
     AgentEntry, ArtifactEntry, HookEntry, ManagedMcpServer, PluginEntry, SkillEntry,
 };
 use systemprompt_security::authz::{EntityKind, EntityRef};
-use systemprompt_web_admin::authz::department::{department_dimension, department_rule_type};
-use systemprompt_web_admin::marketplace_filter::keepsets::{
+use systemprompt_web_access::authz::department::{department_dimension, department_rule_type};
+use systemprompt_web_access::marketplace_filter::keepsets::{
     CandidateEntityIds, KeepSet, KeepSets, apply_keep_sets, entity_ref_for,
 };
 
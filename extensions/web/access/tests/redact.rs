@@ -6,7 +6,7 @@
     reason = "test code: panics are the assertion mechanism and clones keep fixtures readable"
 )]
 
-use systemprompt_web_admin::repositories::analytics::conversations::redact_text;
+use systemprompt_web_access::redact_text;
 
 #[test]
 fn redact_aws_key() {
@@ -33,8 +33,10 @@ fn page_js_assets(storage_js: &Path) -> Vec<AssetDefinition> {
         page_js!(&pages, "admin-access-control-modals.js"),
         page_js!(&pages, "admin-access-control-state.js"),
         page_js!(&pages, "admin-access-tokens.js"),
+        page_js!(&pages, "admin-chargeback.js"),
         page_js!(&pages, "admin-contexts.js"),
         page_js!(&pages, "admin-demo-register.js"),
+        page_js!(&pages, "admin-gateway-history.js"),
         page_js!(&pages, "admin-models.js"),
         page_js!(&pages, "admin-register.js"),
         page_js!(&pages, "admin-register-ui.js"),
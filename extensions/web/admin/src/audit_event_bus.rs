@@ -8,12 +8,21 @@
 //! The listener is started lazily on first subscription and survives for the
 //! lifetime of the process. Reconnects are handled by `PgListener::recv`
 //! itself (it transparently re-subscribes on transient errors).
+//!
+//! Every notification is counted, with its insert-to-delivery lag, in
+//! [`crate::metrics::runtime`]; subscribers that read through [`next_event`]
+//! have the payloads they lagged past counted as dropped.
 
 use std::sync::{Arc, OnceLock};
 
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
 use sqlx::PgPool;
 use sqlx::postgres::PgListener;
 use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::metrics::runtime;
 
 const CHANNEL_NAME: &str = "audit_events";
 const BROADCAST_CAPACITY: usize = 256;
@@ -31,6 +40,38 @@ impl AuditEventBus {
 
 static BUS: OnceLock<AuditEventBus> = OnceLock::new();
 
+#[derive(Deserialize)]
+struct Stamp {
+    created_at: Option<DateTime<Utc>>,
+}
+
+/// SSE streams currently subscribed; zero before the bus first starts.
+#[must_use]
+pub fn subscriber_count() -> usize {
+    BUS.get().map_or(0, |bus| bus.sender.receiver_count())
+}
+
+/// The next payload for `receiver`, or `None` once the bus is gone. A
+/// subscriber that fell behind the channel skips to the oldest payload still
+/// held, and the ones it missed are counted as dropped.
+pub async fn next_event(receiver: &mut broadcast::Receiver<String>) -> Option<String> {
+    loop {
+        match receiver.recv().await {
+            Ok(payload) => return Some(payload),
+            Err(RecvError::Lagged(skipped)) => runtime::record_sse_dropped(skipped),
+            Err(RecvError::Closed) => return None,
+        }
+    }
+}
+
+fn observe(payload: &str) {
+    let lag = serde_json::from_str::<Stamp>(payload)
+        .ok()
+        .and_then(|stamp| stamp.created_at)
+        .and_then(|at| (Utc::now() - at).to_std().ok());
+    runtime::record_audit_notification(lag);
+}
+
 /// Bus accessor that survives a missing `audit_events` channel.
 ///
 /// If the listener fails to start (e.g. the `audit_events` channel doesn't
@@ -64,6 +105,7 @@ fn spawn_listener(pool: Arc<PgPool>, sender: broadcast::Sender<String>) {
                         match listener.recv().await {
                             Ok(notification) => {
                                 let payload = notification.payload().to_owned();
+                                observe(&payload);
                                 // Why: `broadcast::Sender::send` returns Err when there are
                                 // zero subscribers. That's a normal idle state for this bus
                                 // — no SSE clients connected — not a failure to log.
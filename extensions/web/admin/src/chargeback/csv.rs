@@ -0,0 +1,62 @@
+//! Chargeback lines as CSV for finance systems.
+//!
+//! Amounts are exact decimal US dollars derived from integer microdollars,
+//! never through a float, so a column sums to the statement total to the
+//! cent. A text cell that a spreadsheet would read as a formula is prefixed
+//! with `'`: department names and emails come from users and identity
+//! providers, not from finance.
+
+use chrono::NaiveDate;
+
+use crate::repositories::chargeback::ChargebackLine;
+
+const HEADER: &str = "month,department,cost_center,user_id,user_email,provider,model,requests,input_tokens,output_tokens,gross_usd,credit_usd,net_usd";
+
+/// `micros` as dollars with six decimal places.
+#[must_use]
+pub fn usd(micros: i64) -> String {
+    let sign = if micros < 0 { "-" } else { "" };
+    let abs = micros.unsigned_abs();
+    format!("{sign}{}.{:06}", abs / 1_000_000, abs % 1_000_000)
+}
+
+fn cell(value: &str) -> String {
+    let value = if value.starts_with(['=', '+', '-', '@', '\t', '\r']) {
+        format!("'{value}")
+    } else {
+        value.to_owned()
+    };
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+#[must_use]
+pub fn render_csv(month: NaiveDate, lines: &[ChargebackLine]) -> String {
+    let month = month.format("%Y-%m").to_string();
+    let mut out = String::with_capacity(HEADER.len() + 1 + lines.len() * 160);
+    out.push_str(HEADER);
+    out.push_str("\r\n");
+    for l in lines {
+        let row = [
+            cell(&month),
+            cell(&l.department),
+            cell(&l.cost_center),
+            cell(l.user_id.as_str()),
+            cell(l.user_email.as_deref().unwrap_or("")),
+            cell(&l.provider),
+            cell(&l.model),
+            l.requests.to_string(),
+            l.input_tokens.to_string(),
+            l.output_tokens.to_string(),
+            usd(l.gross_microdollars),
+            usd(l.credit_microdollars),
+            usd(l.gross_microdollars - l.credit_microdollars),
+        ];
+        out.push_str(&row.join(","));
+        out.push_str("\r\n");
+    }
+    out
+}
@@ -0,0 +1,114 @@
+//! Monthly chargeback statements per department and cost center.
+//!
+//! Department spend has been visible on the dashboards, but not in a form
+//! finance can invoice against. A statement breaks one department's month
+//! down by cost center, provider, model and user (see [`statement`]), with
+//! eval and judge traffic credited back, and exports as CSV (see [`csv`]) or
+//! as a printable page at `/admin/chargeback/statement`.
+//!
+//! Until a month is closed its statement is computed live from
+//! `ai_requests`, with cost centers from `services/finops/cost_centers.yaml`.
+//! [`close_month`] snapshots those lines into `chargeback_lines`, after which
+//! the statement is read back from the snapshot and the database refuses to
+//! change it. Only a month that has ended can be closed.
+
+pub mod csv;
+pub mod statement;
+
+use chrono::{DateTime, Months, NaiveDate, NaiveTime, Utc};
+use sqlx::PgPool;
+use systemprompt::identifiers::UserId;
+
+use crate::repositories::chargeback::{
+    ChargebackLine, ChargebackPeriod, find_chargeback_period, insert_closed_chargeback_period,
+    list_chargeback_lines, list_chargeback_usage,
+};
+use crate::repositories::config::cost_centers::CostCenters;
+use crate::util::calendar::month_start;
+
+/// A month's lines, and the period record if it has been closed.
+#[derive(Debug, Clone)]
+pub struct MonthLines {
+    pub month_start: NaiveDate,
+    pub lines: Vec<ChargebackLine>,
+    pub period: Option<ChargebackPeriod>,
+}
+
+/// `2026-09` as the first of that month.
+#[must_use]
+pub fn parse_month(value: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(&format!("{}-01", value.trim()), "%Y-%m-%d").ok()
+}
+
+/// `[start, end)` of the month beginning `month_start`, in UTC.
+#[must_use]
+pub fn month_bounds(month_start: NaiveDate) -> (DateTime<Utc>, DateTime<Utc>) {
+    let next = month_start
+        .checked_add_months(Months::new(1))
+        .unwrap_or(month_start);
+    (
+        month_start.and_time(NaiveTime::MIN).and_utc(),
+        next.and_time(NaiveTime::MIN).and_utc(),
+    )
+}
+
+/// Whether the month beginning `month_start` has ended by `today`.
+#[must_use]
+pub fn has_ended(month_start: NaiveDate, today: NaiveDate) -> bool {
+    month_start < self::month_start(today)
+}
+
+/// `count` months ending with the one containing `today`, newest first.
+#[must_use]
+pub fn recent_months(today: NaiveDate, count: u32) -> Vec<NaiveDate> {
+    let current = month_start(today);
+    (0..count)
+        .filter_map(|n| current.checked_sub_months(Months::new(n)))
+        .collect()
+}
+
+/// Assign each line the cost center `centers` gives its user or department.
+pub fn assign_cost_centers(lines: &mut [ChargebackLine], centers: &CostCenters) {
+    for line in lines {
+        line.cost_center = centers
+            .cost_center_for(&line.department, line.user_email.as_deref())
+            .to_owned();
+    }
+}
+
+/// The month's lines: the snapshot if it is closed, live spend otherwise.
+pub async fn load_month(
+    pool: &PgPool,
+    centers: &CostCenters,
+    month_start: NaiveDate,
+) -> Result<MonthLines, sqlx::Error> {
+    if let Some(period) = find_chargeback_period(pool, month_start).await? {
+        return Ok(MonthLines {
+            month_start,
+            lines: list_chargeback_lines(pool, month_start).await?,
+            period: Some(period),
+        });
+    }
+    let (since, until) = month_bounds(month_start);
+    let mut lines = list_chargeback_usage(pool, since, until).await?;
+    assign_cost_centers(&mut lines, centers);
+    Ok(MonthLines {
+        month_start,
+        lines,
+        period: None,
+    })
+}
+
+/// Snapshot and close the month. Returns `false` when it was already closed.
+/// The caller checks the month [`has_ended`].
+pub async fn close_month(
+    pool: &PgPool,
+    centers: &CostCenters,
+    month_start: NaiveDate,
+    closed_by: &UserId,
+) -> Result<bool, sqlx::Error> {
+    let (since, until) = month_bounds(month_start);
+    let mut lines = list_chargeback_usage(pool, since, until).await?;
+    assign_cost_centers(&mut lines, centers);
+    insert_closed_chargeback_period(pool, month_start, closed_by, &lines).await
+}
@@ -0,0 +1,136 @@
+//! Rolling chargeback lines up into per-department totals and statements.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::repositories::chargeback::ChargebackLine;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ChargeTotals {
+    pub requests: i64,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub gross_microdollars: i64,
+    pub credit_microdollars: i64,
+}
+
+impl ChargeTotals {
+    const fn add(&mut self, line: &ChargebackLine) {
+        self.requests += line.requests;
+        self.input_tokens += line.input_tokens;
+        self.output_tokens += line.output_tokens;
+        self.gross_microdollars += line.gross_microdollars;
+        self.credit_microdollars += line.credit_microdollars;
+    }
+
+    /// What the department is charged: spend less credited traffic.
+    #[must_use]
+    pub const fn net_microdollars(&self) -> i64 {
+        self.gross_microdollars - self.credit_microdollars
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChargeRow {
+    pub label: String,
+    pub totals: ChargeTotals,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DepartmentCharge {
+    pub department: String,
+    pub cost_centers: Vec<String>,
+    pub totals: ChargeTotals,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DepartmentStatement {
+    pub charge: DepartmentCharge,
+    pub by_cost_center: Vec<ChargeRow>,
+    pub by_provider: Vec<ChargeRow>,
+    pub by_model: Vec<ChargeRow>,
+    pub by_user: Vec<ChargeRow>,
+}
+
+fn rows<'a>(
+    lines: impl Iterator<Item = &'a ChargebackLine>,
+    label: impl Fn(&ChargebackLine) -> String,
+) -> Vec<ChargeRow> {
+    let mut grouped: BTreeMap<String, ChargeTotals> = BTreeMap::new();
+    for line in lines {
+        grouped.entry(label(line)).or_default().add(line);
+    }
+    let mut out: Vec<ChargeRow> = grouped
+        .into_iter()
+        .map(|(label, totals)| ChargeRow { label, totals })
+        .collect();
+    out.sort_by(|a, b| {
+        b.totals
+            .gross_microdollars
+            .cmp(&a.totals.gross_microdollars)
+            .then_with(|| a.label.cmp(&b.label))
+    });
+    out
+}
+
+fn charge<'a>(
+    department: &str,
+    lines: impl Iterator<Item = &'a ChargebackLine>,
+) -> DepartmentCharge {
+    let mut totals = ChargeTotals::default();
+    let mut centers = BTreeSet::new();
+    for line in lines {
+        totals.add(line);
+        if !line.cost_center.is_empty() {
+            centers.insert(line.cost_center.clone());
+        }
+    }
+    DepartmentCharge {
+        department: department.to_owned(),
+        cost_centers: centers.into_iter().collect(),
+        totals,
+    }
+}
+
+/// One charge per department in `lines`, alphabetically.
+#[must_use]
+pub fn department_charges(lines: &[ChargebackLine]) -> Vec<DepartmentCharge> {
+    let departments: BTreeSet<&str> = lines.iter().map(|l| l.department.as_str()).collect();
+    departments
+        .into_iter()
+        .map(|d| charge(d, lines.iter().filter(|l| l.department == d)))
+        .collect()
+}
+
+/// The statement for `department`, or `None` when it has no lines.
+#[must_use]
+pub fn department_statement(
+    lines: &[ChargebackLine],
+    department: &str,
+) -> Option<DepartmentStatement> {
+    let mine: Vec<&ChargebackLine> = lines
+        .iter()
+        .filter(|l| l.department == department)
+        .collect();
+    if mine.is_empty() {
+        return None;
+    }
+    Some(DepartmentStatement {
+        charge: charge(department, mine.iter().copied()),
+        by_cost_center: rows(mine.iter().copied(), |l| {
+            if l.cost_center.is_empty() {
+                "Unassigned".to_owned()
+            } else {
+                l.cost_center.clone()
+            }
+        }),
+        by_provider: rows(mine.iter().copied(), |l| l.provider.clone()),
+        by_model: rows(mine.iter().copied(), |l| {
+            format!("{} / {}", l.provider, l.model)
+        }),
+        by_user: rows(mine.iter().copied(), |l| {
+            l.user_email
+                .clone()
+                .unwrap_or_else(|| l.user_id.to_string())
+        }),
+    })
+}
@@ -0,0 +1,162 @@
+//! Cohort analytics over session analyses.
+//!
+//! The goal, quality, correction and best-practice outcomes of
+//! `session_analyses`, grouped by department, agent, model, plugin, skill or
+//! category and compared between two date ranges.
+//!
+//! Everything here is pure: the page loads one
+//! [`CohortSessionRow`] per analysed session in each range and this module
+//! groups, buckets and ranks them. A session belongs to exactly one cohort on
+//! every dimension but skills, where it counts once towards each skill it
+//! scored and sessions that used none form their own cohort.
+
+mod range;
+mod stats;
+mod trend;
+
+pub use range::{CohortRange, DEFAULT_RANGE_DAYS, MAX_RANGE_DAYS};
+pub use stats::{
+    ALL_SESSIONS, CohortComparison, CohortStats, CohortSummary, MIN_COMPARABLE_SESSIONS,
+    compare_cohorts, overall, practice_score, summarize,
+};
+pub use trend::{CohortTrend, TrendBucket, cohort_trends, trend_buckets};
+
+use crate::repositories::analytics::cohorts::CohortSessionRow;
+
+/// The cohort of sessions with no value on the dimension.
+pub const UNSET_COHORT: &str = "(none)";
+/// The skill cohort of sessions that scored no skill.
+pub const NO_SKILL_COHORT: &str = "(no skills)";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CohortDimension {
+    #[default]
+    Department,
+    Agent,
+    Model,
+    Plugin,
+    Skill,
+    Category,
+}
+
+impl CohortDimension {
+    pub const ALL: [Self; 6] = [
+        Self::Department,
+        Self::Agent,
+        Self::Model,
+        Self::Plugin,
+        Self::Skill,
+        Self::Category,
+    ];
+
+    #[must_use]
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|d| d.as_str() == value)
+    }
+
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Department => "department",
+            Self::Agent => "agent",
+            Self::Model => "model",
+            Self::Plugin => "plugin",
+            Self::Skill => "skill",
+            Self::Category => "category",
+        }
+    }
+
+    #[must_use]
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Department => "Department",
+            Self::Agent => "Agent",
+            Self::Model => "Model",
+            Self::Plugin => "Plugin",
+            Self::Skill => "Skill",
+            Self::Category => "Category",
+        }
+    }
+}
+
+fn or_unset(value: Option<&str>) -> String {
+    value
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .unwrap_or(UNSET_COHORT)
+        .to_owned()
+}
+
+/// The cohorts `row` belongs to on `dimension`.
+#[must_use]
+pub fn cohort_keys(row: &CohortSessionRow, dimension: CohortDimension) -> Vec<String> {
+    match dimension {
+        CohortDimension::Department => vec![or_unset(Some(&row.department))],
+        CohortDimension::Agent => vec![or_unset(row.agent.as_deref())],
+        CohortDimension::Model => vec![or_unset(row.model.as_deref())],
+        CohortDimension::Plugin => vec![or_unset(row.plugin.as_deref())],
+        CohortDimension::Category => vec![or_unset(Some(&row.category))],
+        CohortDimension::Skill => {
+            let skills: Vec<String> = row
+                .skill_scores
+                .as_ref()
+                .and_then(serde_json::Value::as_object)
+                .map(|scores| {
+                    scores
+                        .keys()
+                        .map(|k| k.trim())
+                        .filter(|k| !k.is_empty())
+                        .map(str::to_owned)
+                        .collect()
+                })
+                .unwrap_or_default();
+            if skills.is_empty() {
+                vec![NO_SKILL_COHORT.to_owned()]
+            } else {
+                skills
+            }
+        },
+    }
+}
+
+// Why: an unscored session (quality 0) is not a bad one; it sorts after
+// every scored session.
+const fn quality_rank(row: &CohortSessionRow) -> i16 {
+    if row.quality_score > 0 {
+        row.quality_score
+    } else {
+        i16::MAX
+    }
+}
+
+const fn goal_rank(goal: &str) -> u8 {
+    match goal.as_bytes() {
+        b"no" => 0,
+        b"partial" => 1,
+        _ => 2,
+    }
+}
+
+/// The worst sessions, optionally only those in `cohort`: goal missed before
+/// goal partly met, then lowest quality, then most corrections.
+#[must_use]
+pub fn worst_sessions<'a>(
+    rows: &'a [CohortSessionRow],
+    dimension: CohortDimension,
+    cohort: Option<&str>,
+    limit: usize,
+) -> Vec<&'a CohortSessionRow> {
+    let mut worst: Vec<&CohortSessionRow> = rows
+        .iter()
+        .filter(|r| cohort.is_none_or(|c| cohort_keys(r, dimension).iter().any(|k| k == c)))
+        .collect();
+    worst.sort_by(|a, b| {
+        goal_rank(&a.goal_achieved)
+            .cmp(&goal_rank(&b.goal_achieved))
+            .then(quality_rank(a).cmp(&quality_rank(b)))
+            .then(b.corrections_count.cmp(&a.corrections_count))
+            .then(b.created_at.cmp(&a.created_at))
+    });
+    worst.truncate(limit);
+    worst
+}
@@ -0,0 +1,87 @@
+//! The selected and baseline date ranges, as whole UTC days.
+//!
+//! Both are inclusive of their last day. The baseline defaults to the same
+//! number of days immediately before the selected range, which is the "did
+//! this month beat the last one" reading; a rollout is compared by giving
+//! the baseline explicitly as the weeks before it.
+
+use chrono::{DateTime, Duration, NaiveDate, NaiveTime, Utc};
+
+/// A range's length when none is given.
+pub const DEFAULT_RANGE_DAYS: i64 = 28;
+/// The longest range a page loads, so a hand-edited URL cannot ask for every
+/// session ever analysed.
+pub const MAX_RANGE_DAYS: i64 = 366;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CohortRange {
+    pub first: NaiveDate,
+    pub last: NaiveDate,
+}
+
+const fn midnight(day: NaiveDate) -> DateTime<Utc> {
+    day.and_time(NaiveTime::MIN).and_utc()
+}
+
+fn parse_day(value: Option<&str>) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(value?.trim(), "%Y-%m-%d").ok()
+}
+
+impl CohortRange {
+    // Why: inverted ranges are swapped, and an over-long one keeps its last
+    // day and pulls the first up to the cap.
+    fn clamped(a: NaiveDate, b: NaiveDate) -> Self {
+        let (first, last) = if a <= b { (a, b) } else { (b, a) };
+        let earliest = last - Duration::days(MAX_RANGE_DAYS - 1);
+        Self {
+            first: first.max(earliest),
+            last,
+        }
+    }
+
+    /// The range `?from=&to=` asks for: a missing `to` is today, a missing
+    /// `from` the [`DEFAULT_RANGE_DAYS`] ending on `to`.
+    #[must_use]
+    pub fn parse(from: Option<&str>, to: Option<&str>, today: NaiveDate) -> Self {
+        let last = parse_day(to).unwrap_or(today);
+        let first =
+            parse_day(from).unwrap_or_else(|| last - Duration::days(DEFAULT_RANGE_DAYS - 1));
+        Self::clamped(first, last)
+    }
+
+    /// The baseline `?baseline_from=&baseline_to=` asks for, or the range
+    /// of the same length just before `selected` unless both are given.
+    #[must_use]
+    pub fn parse_baseline(from: Option<&str>, to: Option<&str>, selected: Self) -> Self {
+        match (parse_day(from), parse_day(to)) {
+            (Some(first), Some(last)) => Self::clamped(first, last),
+            _ => selected.preceding(),
+        }
+    }
+
+    #[must_use]
+    pub fn days(self) -> i64 {
+        (self.last - self.first).num_days() + 1
+    }
+
+    #[must_use]
+    pub fn preceding(self) -> Self {
+        let last = self.first - Duration::days(1);
+        Self {
+            first: last - Duration::days(self.days() - 1),
+            last,
+        }
+    }
+
+    /// Midnight at the start of the first day.
+    #[must_use]
+    pub const fn start(self) -> DateTime<Utc> {
+        midnight(self.first)
+    }
+
+    /// Midnight at the end of the last day, exclusive.
+    #[must_use]
+    pub fn end(self) -> DateTime<Utc> {
+        midnight(self.last + Duration::days(1))
+    }
+}
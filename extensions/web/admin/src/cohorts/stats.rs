@@ -0,0 +1,241 @@
+//! Per-cohort outcome statistics, and a cohort's change between two ranges.
+//!
+//! A goal is counted only when the analysis said `yes`, `partial` or `no`,
+//! and a quality score only when it is 1 to 5, so a session the analyser
+//! could not score dilutes neither rate. The best-practice score is the share
+//! of applicable checklist items a session met, `partial` counting half.
+
+use std::collections::BTreeMap;
+
+use serde_json::Value;
+
+use super::{CohortDimension, cohort_keys};
+use crate::repositories::analytics::cohorts::CohortSessionRow;
+
+/// A cohort's change between ranges is shown only when both ranges hold at
+/// least this many of its sessions; below it one bad afternoon reads as a
+/// trend.
+pub const MIN_COMPARABLE_SESSIONS: i64 = 10;
+/// The name [`overall`] gives the cohort of every session.
+pub const ALL_SESSIONS: &str = "All sessions";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct CohortStats {
+    pub sessions: i64,
+    pub goal_yes: i64,
+    pub goal_partial: i64,
+    pub goal_no: i64,
+    /// Sessions per quality score, 1 to 5.
+    pub quality: [i64; 5],
+    pub corrections: i64,
+    practice_total: f64,
+    practice_sessions: i64,
+}
+
+fn ratio(part: f64, whole: i64) -> Option<f64> {
+    (whole > 0).then(|| part / crate::numeric::to_f64(whole))
+}
+
+/// The share of applicable practices a session's checklist met: `yes` counts
+/// 1, `partial` a half, `no` nothing, and `n/a` or anything else is left out.
+#[must_use]
+pub fn practice_score(checklist: Option<&Value>) -> Option<f64> {
+    let items = checklist?.as_array()?;
+    let (met, applicable) = items
+        .iter()
+        .filter_map(|item| match item.get("score")?.as_str()? {
+            "yes" => Some(1.0),
+            "partial" => Some(0.5),
+            "no" => Some(0.0),
+            _ => None,
+        })
+        .fold((0.0, 0i64), |(met, n), s| (met + s, n + 1));
+    ratio(met, applicable)
+}
+
+impl CohortStats {
+    pub fn add(&mut self, row: &CohortSessionRow) {
+        self.sessions += 1;
+        match row.goal_achieved.as_str() {
+            "yes" => self.goal_yes += 1,
+            "partial" => self.goal_partial += 1,
+            "no" => self.goal_no += 1,
+            _ => {},
+        }
+        if let Ok(score @ 1..=5) = usize::try_from(row.quality_score) {
+            self.quality[score - 1] += 1;
+        }
+        self.corrections += i64::from(row.corrections_count);
+        if let Some(score) = practice_score(row.best_practices_checklist.as_ref()) {
+            self.practice_total += score;
+            self.practice_sessions += 1;
+        }
+    }
+
+    #[must_use]
+    pub const fn goals_assessed(&self) -> i64 {
+        self.goal_yes + self.goal_partial + self.goal_no
+    }
+
+    /// The shares of assessed sessions whose goal was met, partly met and
+    /// missed.
+    #[must_use]
+    pub fn goal_shares(&self) -> Option<[f64; 3]> {
+        let assessed = self.goals_assessed();
+        Some([
+            ratio(crate::numeric::to_f64(self.goal_yes), assessed)?,
+            ratio(crate::numeric::to_f64(self.goal_partial), assessed)?,
+            ratio(crate::numeric::to_f64(self.goal_no), assessed)?,
+        ])
+    }
+
+    #[must_use]
+    pub fn quality_scored(&self) -> i64 {
+        self.quality.iter().sum()
+    }
+
+    #[must_use]
+    pub fn avg_quality(&self) -> Option<f64> {
+        let total: i64 = self
+            .quality
+            .iter()
+            .zip(1i64..)
+            .map(|(n, score)| n * score)
+            .sum();
+        ratio(crate::numeric::to_f64(total), self.quality_scored())
+    }
+
+    #[must_use]
+    pub fn avg_corrections(&self) -> Option<f64> {
+        ratio(crate::numeric::to_f64(self.corrections), self.sessions)
+    }
+
+    #[must_use]
+    pub fn practice_score(&self) -> Option<f64> {
+        ratio(self.practice_total, self.practice_sessions)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CohortSummary {
+    pub cohort: String,
+    pub stats: CohortStats,
+}
+
+/// Every cohort on `dimension`, largest first.
+#[must_use]
+pub fn summarize(rows: &[CohortSessionRow], dimension: CohortDimension) -> Vec<CohortSummary> {
+    let mut cohorts: BTreeMap<String, CohortStats> = BTreeMap::new();
+    for row in rows {
+        for key in cohort_keys(row, dimension) {
+            cohorts.entry(key).or_default().add(row);
+        }
+    }
+    let mut summaries: Vec<CohortSummary> = cohorts
+        .into_iter()
+        .map(|(cohort, stats)| CohortSummary { cohort, stats })
+        .collect();
+    summaries.sort_by_key(|s| std::cmp::Reverse(s.stats.sessions));
+    summaries
+}
+
+/// One cohort in the selected range and the baseline range.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CohortComparison {
+    pub cohort: String,
+    pub current: CohortStats,
+    pub baseline: CohortStats,
+}
+
+fn change(current: Option<f64>, baseline: Option<f64>) -> Option<f64> {
+    Some(current? - baseline?)
+}
+
+impl CohortComparison {
+    #[must_use]
+    pub const fn comparable(&self) -> bool {
+        self.current.sessions >= MIN_COMPARABLE_SESSIONS
+            && self.baseline.sessions >= MIN_COMPARABLE_SESSIONS
+    }
+
+    fn when_comparable(&self, value: Option<f64>) -> Option<f64> {
+        value.filter(|_| self.comparable())
+    }
+
+    #[must_use]
+    pub fn goal_rate_change(&self) -> Option<f64> {
+        let rate = |s: &CohortStats| s.goal_shares().map(|[yes, _, _]| yes);
+        self.when_comparable(change(rate(&self.current), rate(&self.baseline)))
+    }
+
+    #[must_use]
+    pub fn quality_change(&self) -> Option<f64> {
+        self.when_comparable(change(
+            self.current.avg_quality(),
+            self.baseline.avg_quality(),
+        ))
+    }
+
+    #[must_use]
+    pub fn corrections_change(&self) -> Option<f64> {
+        self.when_comparable(change(
+            self.current.avg_corrections(),
+            self.baseline.avg_corrections(),
+        ))
+    }
+
+    #[must_use]
+    pub fn practice_change(&self) -> Option<f64> {
+        self.when_comparable(change(
+            self.current.practice_score(),
+            self.baseline.practice_score(),
+        ))
+    }
+}
+
+/// Pairs each cohort of the selected range with itself in the baseline, in
+/// the selected range's order; cohorts seen only in the baseline follow, so a
+/// cohort that stopped appearing is still on the page.
+#[must_use]
+pub fn compare_cohorts(
+    current: Vec<CohortSummary>,
+    baseline: Vec<CohortSummary>,
+) -> Vec<CohortComparison> {
+    let mut baseline: BTreeMap<String, CohortStats> =
+        baseline.into_iter().map(|s| (s.cohort, s.stats)).collect();
+    let mut compared: Vec<CohortComparison> = current
+        .into_iter()
+        .map(|s| CohortComparison {
+            baseline: baseline.remove(&s.cohort).unwrap_or_default(),
+            cohort: s.cohort,
+            current: s.stats,
+        })
+        .collect();
+    let mut gone: Vec<CohortComparison> = baseline
+        .into_iter()
+        .map(|(cohort, stats)| CohortComparison {
+            cohort,
+            current: CohortStats::default(),
+            baseline: stats,
+        })
+        .collect();
+    gone.sort_by_key(|c| std::cmp::Reverse(c.baseline.sessions));
+    compared.extend(gone);
+    compared
+}
+
+/// Every session in each range, as one cohort.
+#[must_use]
+pub fn overall(current: &[CohortSessionRow], baseline: &[CohortSessionRow]) -> CohortComparison {
+    let total = |rows: &[CohortSessionRow]| {
+        rows.iter().fold(CohortStats::default(), |mut stats, row| {
+            stats.add(row);
+            stats
+        })
+    };
+    CohortComparison {
+        cohort: ALL_SESSIONS.to_owned(),
+        current: total(current),
+        baseline: total(baseline),
+    }
+}
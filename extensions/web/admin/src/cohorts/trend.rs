@@ -0,0 +1,103 @@
+//! Cohort outcomes over time: the range cut into days, or weeks starting
+//! Monday once it is longer than a month.
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc};
+
+use super::{CohortDimension, CohortStats, cohort_keys};
+use crate::repositories::analytics::cohorts::CohortSessionRow;
+
+const DAILY_UP_TO_DAYS: i64 = 31;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrendBucket {
+    Day,
+    Week,
+}
+
+impl TrendBucket {
+    #[must_use]
+    pub fn for_range(from: DateTime<Utc>, to: DateTime<Utc>) -> Self {
+        if to - from <= Duration::days(DAILY_UP_TO_DAYS) {
+            Self::Day
+        } else {
+            Self::Week
+        }
+    }
+
+    /// The first day of the bucket `t` falls in.
+    #[must_use]
+    pub fn start(self, t: DateTime<Utc>) -> NaiveDate {
+        let day = t.date_naive();
+        match self {
+            Self::Day => day,
+            Self::Week => day - Duration::days(i64::from(day.weekday().num_days_from_monday())),
+        }
+    }
+
+    #[must_use]
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Day => "day",
+            Self::Week => "week",
+        }
+    }
+
+    const fn step(self) -> Duration {
+        match self {
+            Self::Day => Duration::days(1),
+            Self::Week => Duration::days(7),
+        }
+    }
+}
+
+/// The start of every bucket that overlaps `[from, to)`, in order.
+#[must_use]
+pub fn trend_buckets(
+    bucket: TrendBucket,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Vec<NaiveDate> {
+    if to <= from {
+        return Vec::new();
+    }
+    let last = bucket.start(to - Duration::microseconds(1));
+    std::iter::successors(Some(bucket.start(from)), |d| Some(*d + bucket.step()))
+        .take_while(|d| *d <= last)
+        .collect()
+}
+
+/// One cohort's statistics per bucket, aligned with the buckets it was
+/// built from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CohortTrend {
+    pub cohort: String,
+    pub buckets: Vec<CohortStats>,
+}
+
+#[must_use]
+pub fn cohort_trends(
+    rows: &[CohortSessionRow],
+    dimension: CohortDimension,
+    cohorts: &[String],
+    bucket: TrendBucket,
+    starts: &[NaiveDate],
+) -> Vec<CohortTrend> {
+    let mut trends: Vec<CohortTrend> = cohorts
+        .iter()
+        .map(|cohort| CohortTrend {
+            cohort: cohort.clone(),
+            buckets: vec![CohortStats::default(); starts.len()],
+        })
+        .collect();
+    for row in rows {
+        let Ok(index) = starts.binary_search(&bucket.start(row.created_at)) else {
+            continue;
+        };
+        for key in cohort_keys(row, dimension) {
+            if let Some(trend) = trends.iter_mut().find(|t| t.cohort == key) {
+                trend.buckets[index].add(row);
+            }
+        }
+    }
+    trends
+}
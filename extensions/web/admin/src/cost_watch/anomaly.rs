@@ -0,0 +1,126 @@
+//! Judging an hour of spend against the same subject's recent hours.
+//!
+//! A subject's norm is the mean and standard deviation of its hourly spend
+//! over the baseline window, counting every hour it spent nothing as a zero.
+//! An agent stuck in a loop shows up as an hour many times that mean and far
+//! outside its usual spread; either test alone is noisy (a quiet user's first
+//! busy hour passes the ratio, a bursty one's routine peak passes the
+//! z-score), so an hour has to fail both, and clear a floor in absolute
+//! dollars, to be flagged.
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::repositories::config::cost_watch::CostWatchConfig;
+use crate::repositories::cost_watch::{HourlySpend, NewCostAnomaly, SpendScope};
+
+const MICROS_PER_USD: f64 = 1_000_000.0;
+
+pub const SEVERITY_WARNING: &str = "warning";
+pub const SEVERITY_CRITICAL: &str = "critical";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Baseline {
+    pub mean: f64,
+    pub stddev: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Verdict {
+    pub ratio: f64,
+    pub severity: &'static str,
+}
+
+/// The norm of `spends`, the non-zero hours of a window `window_hours` long.
+#[must_use]
+pub fn baseline(spends: &[i64], window_hours: u32) -> Baseline {
+    let n = f64::from(window_hours.max(1));
+    let (sum, sum_sq) = spends.iter().fold((0.0f64, 0.0f64), |(s, sq), v| {
+        let v = crate::numeric::to_f64(*v);
+        (s + v, v.mul_add(v, sq))
+    });
+    let mean = sum / n;
+    let variance = mean.mul_add(-mean, sum_sq / n).max(0.0);
+    Baseline {
+        mean,
+        stddev: variance.sqrt(),
+    }
+}
+
+/// Whether `spend` breaks from `norm` under `config`, and how badly.
+#[must_use]
+pub fn judge(spend: i64, norm: Baseline, config: &CostWatchConfig) -> Option<Verdict> {
+    let spend = crate::numeric::to_f64(spend);
+    if spend < config.min_spend_usd * MICROS_PER_USD {
+        return None;
+    }
+    let ratio = spend / norm.mean.max(config.min_baseline_usd * MICROS_PER_USD);
+    if ratio < config.ratio_threshold {
+        return None;
+    }
+    let z = if norm.stddev > 0.0 {
+        (spend - norm.mean) / norm.stddev
+    } else {
+        f64::INFINITY
+    };
+    if z < config.z_threshold {
+        return None;
+    }
+    let severity = if ratio >= config.critical_ratio {
+        SEVERITY_CRITICAL
+    } else {
+        SEVERITY_WARNING
+    };
+    Some(Verdict { ratio, severity })
+}
+
+/// Every hour at or after `evaluate_from` in `rows` that breaks from its
+/// subject's norm. `rows` must reach back a full baseline window before
+/// `evaluate_from`.
+#[must_use]
+pub fn find_anomalies(
+    scope: SpendScope,
+    rows: &[HourlySpend],
+    evaluate_from: DateTime<Utc>,
+    config: &CostWatchConfig,
+) -> Vec<NewCostAnomaly> {
+    let window_hours = config.baseline_days.saturating_mul(24);
+    let window = Duration::hours(i64::from(window_hours));
+    let mut by_subject: BTreeMap<&str, Vec<&HourlySpend>> = BTreeMap::new();
+    for row in rows {
+        by_subject
+            .entry(row.subject.as_str())
+            .or_default()
+            .push(row);
+    }
+
+    let mut found = Vec::new();
+    for hours in by_subject.values() {
+        for hour in hours.iter().filter(|h| h.hour_start >= evaluate_from) {
+            let history: Vec<i64> = hours
+                .iter()
+                .filter(|h| {
+                    h.hour_start < hour.hour_start && h.hour_start >= hour.hour_start - window
+                })
+                .map(|h| h.spend_microdollars)
+                .collect();
+            let norm = baseline(&history, window_hours);
+            let Some(verdict) = judge(hour.spend_microdollars, norm, config) else {
+                continue;
+            };
+            found.push(NewCostAnomaly {
+                scope,
+                subject: hour.subject.clone(),
+                hour_start: hour.hour_start,
+                spend_microdollars: hour.spend_microdollars,
+                requests: hour.requests,
+                baseline_microdollars: crate::numeric::round_to_i64(norm.mean),
+                stddev_microdollars: crate::numeric::round_to_i64(norm.stddev),
+                ratio: verdict.ratio,
+                severity: verdict.severity,
+            });
+        }
+    }
+    found
+}
@@ -0,0 +1,72 @@
+//! Month-end spend projection per department.
+//!
+//! Today is still running, so it is not used as evidence: the month so far
+//! up to yesterday, plus the trailing daily average for every day from today
+//! to the end of the month. A forecast never falls below what has already
+//! been spent.
+
+use std::collections::BTreeMap;
+
+use chrono::{Duration, Months, NaiveDate};
+
+use crate::repositories::cost_watch::{CostForecast, DailySpend};
+use crate::util::calendar::month_start;
+
+/// The first day `daily` must reach back to for [`project_months`] to see
+/// both the whole previous month and the trailing window.
+#[must_use]
+pub fn history_start(today: NaiveDate, trailing_days: u32) -> NaiveDate {
+    let previous = month_start(today)
+        .checked_sub_months(Months::new(1))
+        .unwrap_or(today);
+    previous.min(today - Duration::days(i64::from(trailing_days)))
+}
+
+/// One forecast per department in `daily` for the month containing `today`.
+#[must_use]
+pub fn project_months(
+    daily: &[DailySpend],
+    today: NaiveDate,
+    trailing_days: u32,
+) -> Vec<CostForecast> {
+    let this_month = month_start(today);
+    let previous_month = this_month
+        .checked_sub_months(Months::new(1))
+        .unwrap_or(this_month);
+    let next_month = this_month
+        .checked_add_months(Months::new(1))
+        .unwrap_or(this_month);
+    let trailing_from = today - Duration::days(i64::from(trailing_days));
+    let days_left = (next_month - today).num_days();
+
+    let mut by_department: BTreeMap<&str, Vec<&DailySpend>> = BTreeMap::new();
+    for row in daily {
+        by_department
+            .entry(row.department.as_str())
+            .or_default()
+            .push(row);
+    }
+
+    by_department
+        .into_iter()
+        .map(|(department, days)| {
+            let sum = |from: NaiveDate, to: NaiveDate| -> i64 {
+                days.iter()
+                    .filter(|d| d.day >= from && d.day < to)
+                    .map(|d| d.spend_microdollars)
+                    .sum()
+            };
+            let spent = sum(this_month, next_month);
+            let daily_rate = sum(trailing_from, today) / i64::from(trailing_days.max(1));
+            let forecast = sum(this_month, today) + daily_rate * days_left;
+            CostForecast {
+                department: department.to_owned(),
+                month_start: this_month,
+                spent_microdollars: spent,
+                daily_rate_microdollars: daily_rate,
+                forecast_microdollars: forecast.max(spent),
+                previous_month_microdollars: sum(previous_month, this_month),
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,86 @@
+//! Spend anomalies and month-end forecasts.
+//!
+//! Cost has only been visible after the fact, on the usage pages and the
+//! inference KPIs; a runaway agent loop could burn through a budget overnight
+//! before anyone looked. The `cost_anomaly_scan` job runs this module hourly:
+//! [`scan_anomalies`] judges each recently closed hour of spend per user,
+//! department and model against that subject's own norm (see [`anomaly`]),
+//! and [`refresh_forecasts`] projects each department's month-end spend
+//! (see [`forecast`]).
+//!
+//! Anomalies land in `cost_anomalies`, whose insert trigger publishes them on
+//! the audit bus, so live dashboards and anything subscribed to the bus see a
+//! spike within the hour. Both are shown on `/admin/costs`. Thresholds live
+//! in `services/finops/cost_watch.yaml`.
+
+pub mod anomaly;
+pub mod forecast;
+
+use chrono::{DateTime, Duration, DurationRound, Utc};
+use sqlx::PgPool;
+
+use crate::repositories::config::cost_watch::CostWatchConfig;
+use crate::repositories::cost_watch::{
+    SpendScope, insert_cost_anomaly, list_department_daily_spend, list_hourly_spend,
+    set_cost_forecast,
+};
+
+/// What one scan found.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ScanReport {
+    pub hours_examined: u32,
+    pub flagged: usize,
+    /// Anomalies not already recorded by an earlier, overlapping run.
+    pub recorded: usize,
+}
+
+fn hour_floor(at: DateTime<Utc>) -> DateTime<Utc> {
+    at.duration_trunc(Duration::hours(1)).unwrap_or(at)
+}
+
+/// Judge the last `evaluate_hours` closed hours before `now` in every scope,
+/// recording anything anomalous.
+pub async fn scan_anomalies(
+    pool: &PgPool,
+    config: &CostWatchConfig,
+    now: DateTime<Utc>,
+) -> Result<ScanReport, sqlx::Error> {
+    let until = hour_floor(now);
+    let evaluate_from = until - Duration::hours(i64::from(config.evaluate_hours));
+    let since = evaluate_from - Duration::days(i64::from(config.baseline_days));
+
+    let mut report = ScanReport {
+        hours_examined: config.evaluate_hours,
+        ..ScanReport::default()
+    };
+    for scope in SpendScope::ALL {
+        let rows = list_hourly_spend(pool, scope, since, until).await?;
+        let found = anomaly::find_anomalies(scope, &rows, evaluate_from, config);
+        report.flagged += found.len();
+        for item in &found {
+            if insert_cost_anomaly(pool, item).await? {
+                report.recorded += 1;
+            }
+        }
+    }
+    Ok(report)
+}
+
+/// Recompute this month's forecast for every department with recent spend.
+/// Returns how many departments were forecast.
+pub async fn refresh_forecasts(
+    pool: &PgPool,
+    config: &CostWatchConfig,
+    now: DateTime<Utc>,
+) -> Result<usize, sqlx::Error> {
+    let today = now.date_naive();
+    let since = forecast::history_start(today, config.forecast_trailing_days)
+        .and_time(chrono::NaiveTime::MIN)
+        .and_utc();
+    let daily = list_department_daily_spend(pool, since, now).await?;
+    let forecasts = forecast::project_months(&daily, today, config.forecast_trailing_days);
+    for item in &forecasts {
+        set_cost_forecast(pool, item).await?;
+    }
+    Ok(forecasts.len())
+}
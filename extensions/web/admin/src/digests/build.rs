@@ -0,0 +1,159 @@
+//! Assembling one subscription's [`Digest`] for one period.
+//!
+//! Spend and top models are the Inference Requests page's read models and the
+//! governance counts the Governance page's, each narrowed to the
+//! subscription's [`DigestScope`](super::DigestScope); the rest comes from
+//! `repositories::digests::sections`.
+
+use serde::Serialize;
+use sqlx::PgPool;
+
+use super::DigestPeriod;
+use crate::repositories::analytics::request_stats::get_request_stats;
+use crate::repositories::analytics::requests::{BreakdownRow, list_requests_by_model};
+use crate::repositories::config::digests::DigestSubscription;
+use crate::repositories::digests::sections::{
+    self, DigestEvals, DigestFindingRow, DigestSessionRow,
+};
+use crate::repositories::governance::{PerPolicyCounts, list_per_policy_counts_in_range};
+use crate::util::time_range::{TimeRange, TimeRangePreset};
+
+const TOP_MODELS: usize = 5;
+const TOP_POLICIES: usize = 5;
+const NOTABLE_SESSIONS: i64 = 5;
+
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct DigestSpend {
+    pub requests: i64,
+    pub spend_microdollars: i64,
+    pub users: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DigestModelRow {
+    pub model: String,
+    pub requests: i64,
+    pub spend_microdollars: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DigestPolicyRow {
+    pub policy: String,
+    pub decisions: i64,
+    pub denials: i64,
+}
+
+/// One subscription's report for one period.
+#[derive(Debug, Clone, Serialize)]
+pub struct Digest {
+    pub subscription_id: String,
+    pub title: String,
+    pub scope: String,
+    pub period: DigestPeriod,
+    pub spend: DigestSpend,
+    pub previous_spend_microdollars: i64,
+    pub top_models: Vec<DigestModelRow>,
+    pub decisions: i64,
+    pub denials: i64,
+    /// Policies that denied anything, most denials first.
+    pub denied_policies: Vec<DigestPolicyRow>,
+    pub safety_findings: Vec<DigestFindingRow>,
+    pub evals: DigestEvals,
+    pub notable_sessions: Vec<DigestSessionRow>,
+}
+
+impl Digest {
+    /// Share of judged requests that passed, as a percentage.
+    #[must_use]
+    pub fn eval_pass_rate(&self) -> Option<f64> {
+        (self.evals.scored > 0).then(|| self.evals.pass as f64 * 100.0 / self.evals.scored as f64)
+    }
+}
+
+/// Gather the digest for `subscription` over `period`.
+pub async fn build_digest(
+    pool: &PgPool,
+    subscription: &DigestSubscription,
+    period: DigestPeriod,
+) -> Result<Digest, sqlx::Error> {
+    let scope = subscription.scope();
+    let activity = scope.activity();
+    let range = TimeRange {
+        from: period.start,
+        to: period.end,
+        preset: TimeRangePreset::Custom,
+    };
+    let previous = TimeRange {
+        from: period.start - (period.end - period.start),
+        to: period.start,
+        ..range
+    };
+
+    let stats = get_request_stats(pool, range, activity).await?;
+    let policies = list_per_policy_counts_in_range(pool, range, activity).await?;
+    Ok(Digest {
+        subscription_id: subscription.id.clone(),
+        title: subscription.title().to_owned(),
+        scope: scope.label(),
+        period,
+        spend: DigestSpend {
+            requests: stats.total,
+            spend_microdollars: stats.total_cost_microdollars,
+            users: stats.distinct_users,
+        },
+        previous_spend_microdollars: get_request_stats(pool, previous, activity)
+            .await?
+            .total_cost_microdollars,
+        top_models: top_models(list_requests_by_model(pool, range, activity).await?),
+        decisions: policies.iter().map(|p| p.allowed + p.denied).sum(),
+        denials: policies.iter().map(|p| p.denied).sum(),
+        denied_policies: denied_policies(policies),
+        safety_findings: sections::list_digest_safety_findings(pool, range, activity).await?,
+        evals: sections::get_digest_evals(pool, range, activity).await?,
+        notable_sessions: sections::list_digest_notable_sessions(
+            pool,
+            range,
+            activity,
+            NOTABLE_SESSIONS,
+        )
+        .await?,
+    })
+}
+
+// Why: the Models tab ranks by traffic; a digest reports where the money went.
+fn top_models(mut rows: Vec<BreakdownRow>) -> Vec<DigestModelRow> {
+    rows.sort_by(|a, b| {
+        b.cost_microdollars
+            .cmp(&a.cost_microdollars)
+            .then(b.requests.cmp(&a.requests))
+            .then_with(|| a.key.cmp(&b.key))
+    });
+    rows.into_iter()
+        .take(TOP_MODELS)
+        .map(|row| DigestModelRow {
+            model: row.key,
+            requests: row.requests,
+            spend_microdollars: row.cost_microdollars,
+        })
+        .collect()
+}
+
+fn denied_policies(policies: Vec<PerPolicyCounts>) -> Vec<DigestPolicyRow> {
+    let mut rows: Vec<DigestPolicyRow> = policies
+        .into_iter()
+        .filter(|p| p.denied > 0)
+        .map(|p| DigestPolicyRow {
+            decisions: p.allowed + p.denied,
+            denials: p.denied,
+            policy: p.policy,
+        })
+        .collect();
+    rows.sort_by(|a, b| {
+        b.denials
+            .cmp(&a.denials)
+            .then(b.decisions.cmp(&a.decisions))
+            .then_with(|| a.policy.cmp(&b.policy))
+    });
+    rows.truncate(TOP_POLICIES);
+    rows
+}
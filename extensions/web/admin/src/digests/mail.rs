@@ -0,0 +1,194 @@
+//! Digest email, and the transports that carry it.
+//!
+//! [`MailTransport`] is the seam: the job picks one from the `mail` block of
+//! the digests file. [`SmtpMailTransport`] relays through a mail server;
+//! [`FileMailSink`] writes each message as an `.eml` file, which is how
+//! digests are tried out and tested without one.
+
+use std::path::{Path, PathBuf};
+
+use async_trait::async_trait;
+use base64::Engine as _;
+use base64::engine::general_purpose::STANDARD;
+use chrono::{DateTime, Utc};
+use lettre::message::header::ContentType;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use systemprompt_web_shared::error::MarketplaceError;
+
+use crate::repositories::config::digests::{MailTransportConfig, SmtpTls};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MailMessage {
+    pub from: String,
+    pub to: Vec<String>,
+    pub subject: String,
+    pub html: String,
+}
+
+// Why: header values must be ASCII; anything else goes out as an RFC 2047
+// encoded word.
+fn header_text(value: &str) -> String {
+    if value.is_ascii() {
+        value.to_owned()
+    } else {
+        format!("=?UTF-8?B?{}?=", STANDARD.encode(value))
+    }
+}
+
+impl MailMessage {
+    /// The message as an RFC 5322 document, HTML body base64-encoded.
+    #[must_use]
+    pub fn to_rfc5322(&self, date: DateTime<Utc>) -> String {
+        let body = STANDARD.encode(&self.html);
+        let mut lines = vec![
+            format!("From: {}", self.from),
+            format!("To: {}", self.to.join(", ")),
+            format!("Subject: {}", header_text(&self.subject)),
+            format!("Date: {}", date.to_rfc2822()),
+            "MIME-Version: 1.0".to_owned(),
+            "Content-Type: text/html; charset=utf-8".to_owned(),
+            "Content-Transfer-Encoding: base64".to_owned(),
+            String::new(),
+        ];
+        lines.extend(
+            body.as_bytes()
+                .chunks(76)
+                .map(|c| String::from_utf8_lossy(c).into_owned()),
+        );
+        lines.join("\r\n") + "\r\n"
+    }
+}
+
+#[async_trait]
+pub trait MailTransport: Send + Sync {
+    async fn send(&self, message: &MailMessage) -> Result<(), MarketplaceError>;
+}
+
+#[derive(Debug, Clone)]
+pub struct FileMailSink {
+    dir: PathBuf,
+}
+
+impl FileMailSink {
+    #[must_use]
+    pub const fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+}
+
+#[async_trait]
+impl MailTransport for FileMailSink {
+    async fn send(&self, message: &MailMessage) -> Result<(), MarketplaceError> {
+        let now = Utc::now();
+        tokio::fs::create_dir_all(&self.dir).await?;
+        let name = format!(
+            "{}-{}.eml",
+            now.format("%Y%m%dT%H%M%S"),
+            uuid::Uuid::new_v4()
+        );
+        tokio::fs::write(self.dir.join(name), message.to_rfc5322(now)).await?;
+        Ok(())
+    }
+}
+
+pub struct SmtpMailTransport {
+    inner: AsyncSmtpTransport<Tokio1Executor>,
+}
+
+impl std::fmt::Debug for SmtpMailTransport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SmtpMailTransport").finish_non_exhaustive()
+    }
+}
+
+fn smtp_error(e: impl std::fmt::Display) -> MarketplaceError {
+    MarketplaceError::Internal(format!("smtp: {e}"))
+}
+
+fn env_value(var: Option<&String>) -> Result<Option<String>, MarketplaceError> {
+    var.map(|var| {
+        std::env::var(var)
+            .map_err(|e| MarketplaceError::Internal(format!("smtp credentials read {var}: {e}")))
+    })
+    .transpose()
+}
+
+impl SmtpMailTransport {
+    pub fn new(
+        host: &str,
+        port: u16,
+        tls: SmtpTls,
+        credentials: Option<(String, String)>,
+    ) -> Result<Self, MarketplaceError> {
+        let builder = match tls {
+            SmtpTls::Starttls => {
+                AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host).map_err(smtp_error)?
+            },
+            SmtpTls::Tls => {
+                AsyncSmtpTransport::<Tokio1Executor>::relay(host).map_err(smtp_error)?
+            },
+            SmtpTls::None => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(host),
+        };
+        let builder = builder.port(port);
+        let builder = match credentials {
+            Some((username, password)) => builder.credentials(Credentials::new(username, password)),
+            None => builder,
+        };
+        Ok(Self {
+            inner: builder.build(),
+        })
+    }
+}
+
+#[async_trait]
+impl MailTransport for SmtpMailTransport {
+    async fn send(&self, message: &MailMessage) -> Result<(), MarketplaceError> {
+        let mut builder = Message::builder()
+            .from(message.from.parse().map_err(smtp_error)?)
+            .subject(message.subject.as_str());
+        for to in &message.to {
+            builder = builder.to(to.parse().map_err(smtp_error)?);
+        }
+        let email = builder
+            .header(ContentType::TEXT_HTML)
+            .body(message.html.clone())
+            .map_err(smtp_error)?;
+        self.inner.send(email).await.map_err(smtp_error)?;
+        Ok(())
+    }
+}
+
+/// The transport `config` asks for. A file sink's relative directory
+/// resolves against `system_root`; with none it writes to `default_dir`.
+pub fn mail_transport(
+    config: &MailTransportConfig,
+    system_root: &Path,
+    default_dir: &Path,
+) -> Result<Box<dyn MailTransport>, MarketplaceError> {
+    match config {
+        MailTransportConfig::File { dir } => {
+            let dir = dir
+                .as_ref()
+                .map_or_else(|| default_dir.to_path_buf(), |d| system_root.join(d));
+            Ok(Box::new(FileMailSink::new(dir)))
+        },
+        MailTransportConfig::Smtp {
+            host,
+            port,
+            username_env,
+            password_env,
+            tls,
+        } => {
+            let username = env_value(username_env.as_ref())?;
+            let password = env_value(password_env.as_ref())?;
+            let credentials = username.zip(password);
+            Ok(Box::new(SmtpMailTransport::new(
+                host,
+                *port,
+                *tls,
+                credentials,
+            )?))
+        },
+    }
+}
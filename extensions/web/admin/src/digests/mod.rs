@@ -0,0 +1,242 @@
+//! Scheduled digest reports: a summary of one period's spend, models,
+//! governance denials, safety findings, eval outcomes and troubled sessions,
+//! for the whole organisation, one department or one user.
+//!
+//! Subscriptions are in `services/reports/digests.yaml`. The hourly
+//! `digest_reports` job calls [`send_due_digests`], which works out the
+//! latest closed period of each subscription, builds its [`Digest`] from the
+//! dashboard's request and governance repositories narrowed to the
+//! subscription's scope, plus `repositories::digests::sections`, and sends it
+//! as HTML email through a [`MailTransport`] and as JSON to a webhook. The
+//! `digest_deliveries` ledger makes each (subscription, period, channel) go
+//! out once; a failed delivery is retried on later runs up to
+//! `max_attempts`. A subscription whose digest cannot be built is logged,
+//! counted as failed and retried on the next run; the others still go out.
+//!
+//! Only the latest period is ever sent. A period that closed while the job
+//! was not running at all is skipped rather than delivered stale.
+
+mod build;
+mod mail;
+mod render;
+mod webhook;
+
+use chrono::{DateTime, Datelike, Duration, NaiveTime, Utc, Weekday};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use systemprompt::identifiers::UserId;
+use systemprompt_web_shared::error::MarketplaceError;
+
+pub use build::{Digest, DigestModelRow, DigestPolicyRow, DigestSpend, build_digest};
+pub use mail::{FileMailSink, MailMessage, MailTransport, SmtpMailTransport, mail_transport};
+pub use render::{render_html, summary_line};
+
+use crate::repositories::config::digests::{DigestConfig, DigestSubscription};
+use crate::repositories::digests::{find_digest_delivery, record_digest_attempt};
+use crate::util::activity_scope::ActivityScope;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DigestCadence {
+    Daily,
+    Weekly,
+}
+
+/// Whose activity a digest covers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DigestScope<'a> {
+    Global,
+    Department(&'a str),
+    User(&'a UserId),
+}
+
+impl<'a> DigestScope<'a> {
+    #[must_use]
+    pub fn activity(self) -> ActivityScope<'a> {
+        match self {
+            Self::Global => ActivityScope::default(),
+            Self::Department(department) => ActivityScope {
+                department: Some(department),
+                ..ActivityScope::default()
+            },
+            Self::User(user_id) => ActivityScope {
+                user_id: Some(user_id),
+                ..ActivityScope::default()
+            },
+        }
+    }
+
+    #[must_use]
+    pub fn label(self) -> String {
+        match self {
+            Self::Global => "All departments".to_owned(),
+            Self::Department(department) => format!("Department {department}"),
+            Self::User(user_id) => format!("User {user_id}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct DigestPeriod {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+/// The most recent period of `cadence` that has closed by `now`. Periods end
+/// at `send_hour` UTC, every day or on `weekly_on`.
+#[must_use]
+pub fn latest_period(
+    cadence: DigestCadence,
+    send_hour: u32,
+    weekly_on: Weekday,
+    now: DateTime<Utc>,
+) -> DigestPeriod {
+    let hour = NaiveTime::from_hms_opt(send_hour.min(23), 0, 0).unwrap_or(NaiveTime::MIN);
+    let mut end = now.date_naive().and_time(hour).and_utc();
+    if end > now {
+        end -= Duration::days(1);
+    }
+    let days = match cadence {
+        DigestCadence::Daily => 1,
+        DigestCadence::Weekly => {
+            let back =
+                (7 + end.weekday().num_days_from_monday() - weekly_on.num_days_from_monday()) % 7;
+            end -= Duration::days(i64::from(back));
+            7
+        },
+    };
+    DigestPeriod {
+        start: end - Duration::days(days),
+        end,
+    }
+}
+
+/// What one run sent.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DigestRunReport {
+    pub delivered: usize,
+    pub failed: usize,
+}
+
+const EMAIL: &str = "email";
+const WEBHOOK: &str = "webhook";
+
+async fn pending_channels(
+    pool: &PgPool,
+    config: &DigestConfig,
+    subscription: &DigestSubscription,
+    period: DigestPeriod,
+) -> Result<Vec<&'static str>, sqlx::Error> {
+    let mut wanted = Vec::new();
+    if !subscription.email.is_empty() {
+        wanted.push(EMAIL);
+    }
+    if subscription.webhook.is_some() {
+        wanted.push(WEBHOOK);
+    }
+    let mut pending = Vec::new();
+    for channel in wanted {
+        let done = find_digest_delivery(pool, &subscription.id, period.start, channel)
+            .await?
+            .is_some_and(|d| {
+                d.delivered_at.is_some()
+                    || u32::try_from(d.attempts).unwrap_or(u32::MAX) >= config.max_attempts
+            });
+        if !done {
+            pending.push(channel);
+        }
+    }
+    Ok(pending)
+}
+
+async fn deliver(
+    config: &DigestConfig,
+    transport: &dyn MailTransport,
+    subscription: &DigestSubscription,
+    digest: &Digest,
+    channel: &str,
+) -> Result<(), MarketplaceError> {
+    if channel == EMAIL {
+        let message = MailMessage {
+            from: config.from.clone(),
+            to: subscription.email.clone(),
+            subject: format!("{} — {}", digest.title, summary_line(digest)),
+            html: render_html(digest, &config.admin_url),
+        };
+        transport.send(&message).await
+    } else if let Some(hook) = &subscription.webhook {
+        webhook::post_digest(hook, digest, &config.admin_url).await
+    } else {
+        Ok(())
+    }
+}
+
+/// Send every subscription's latest digest on each channel it has not yet
+/// gone out on.
+///
+/// A subscription that fails — its ledger unreadable, its
+/// digest unbuildable — is logged and counted in `failed`, and the run moves
+/// on to the next.
+pub async fn send_due_digests(
+    pool: &PgPool,
+    config: &DigestConfig,
+    transport: &dyn MailTransport,
+    now: DateTime<Utc>,
+) -> DigestRunReport {
+    let mut report = DigestRunReport::default();
+    for subscription in &config.subscriptions {
+        let period = latest_period(
+            subscription.cadence,
+            config.send_hour_utc,
+            config.weekly_on,
+            now,
+        );
+        let channels = match pending_channels(pool, config, subscription, period).await {
+            Ok(channels) => channels,
+            Err(error) => {
+                tracing::warn!(subscription = %subscription.id, %error, "digest ledger lookup failed");
+                report.failed += 1;
+                continue;
+            },
+        };
+        if channels.is_empty() {
+            continue;
+        }
+        let digest = match build_digest(pool, subscription, period).await {
+            Ok(digest) => Ok(digest),
+            Err(error) => {
+                tracing::warn!(subscription = %subscription.id, %error, "digest build failed");
+                Err(error.to_string())
+            },
+        };
+        for channel in channels {
+            let error = match &digest {
+                Ok(digest) => deliver(config, transport, subscription, digest, channel)
+                    .await
+                    .err()
+                    .map(|error| {
+                        tracing::warn!(subscription = %subscription.id, channel, %error, "digest delivery failed");
+                        error.to_string()
+                    }),
+                Err(error) => Some(error.clone()),
+            };
+            if error.is_some() {
+                report.failed += 1;
+            } else {
+                report.delivered += 1;
+            }
+            if let Err(error) = record_digest_attempt(
+                pool,
+                &subscription.id,
+                period.start,
+                channel,
+                error.as_deref(),
+            )
+            .await
+            {
+                tracing::warn!(subscription = %subscription.id, channel, %error, "digest attempt not recorded");
+            }
+        }
+    }
+    report
+}
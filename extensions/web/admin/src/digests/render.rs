@@ -0,0 +1,207 @@
+//! A digest as an HTML email body, and as the one line that leads its
+//! subject and webhook message.
+//!
+//! Mail clients ignore stylesheets, so styling is inline and the layout is
+//! plain tables. Every value from the database is escaped.
+
+use systemprompt_web_shared::format::format_cost;
+use systemprompt_web_shared::html_escape;
+
+use super::Digest;
+use crate::handlers::ssr::entity_urls::session_detail_url;
+
+const TABLE: &str = r#"style="border-collapse:collapse;width:100%;font-size:14px""#;
+const TH: &str = r#"style="text-align:left;border-bottom:1px solid #ddd;padding:4px 8px""#;
+const TD: &str = r#"style="border-bottom:1px solid #eee;padding:4px 8px""#;
+const TD_NUM: &str = r#"style="border-bottom:1px solid #eee;padding:4px 8px;text-align:right""#;
+const H2: &str = r#"style="font-size:16px;margin:24px 0 8px""#;
+const MUTED: &str = r#"style="color:#666""#;
+
+fn spend_text(microdollars: i64) -> String {
+    if microdollars > 0 {
+        format_cost(microdollars)
+    } else {
+        "$0".to_owned()
+    }
+}
+
+fn change_text(current: i64, previous: i64) -> String {
+    if previous <= 0 {
+        return "no spend in the period before".to_owned();
+    }
+    let change = (current - previous) as f64 * 100.0 / previous as f64;
+    format!("{change:+.0}% on the period before")
+}
+
+/// Spend, denials and findings in one line, e.g. for a subject.
+#[must_use]
+pub fn summary_line(digest: &Digest) -> String {
+    let findings: i64 = digest.safety_findings.iter().map(|f| f.findings).sum();
+    format!(
+        "{} spent, {} denial{}, {} safety finding{} ({} to {})",
+        spend_text(digest.spend.spend_microdollars),
+        digest.denials,
+        if digest.denials == 1 { "" } else { "s" },
+        findings,
+        if findings == 1 { "" } else { "s" },
+        digest.period.start.format("%b %-d"),
+        digest.period.end.format("%b %-d"),
+    )
+}
+
+fn table(out: &mut String, headers: &[&str], rows: &[Vec<(String, bool)>]) {
+    out.push_str(&format!("<table {TABLE}><tr>"));
+    for header in headers {
+        out.push_str(&format!("<th {TH}>{header}</th>"));
+    }
+    out.push_str("</tr>");
+    for row in rows {
+        out.push_str("<tr>");
+        for (cell, numeric) in row {
+            let style = if *numeric { TD_NUM } else { TD };
+            out.push_str(&format!("<td {style}>{cell}</td>"));
+        }
+        out.push_str("</tr>");
+    }
+    out.push_str("</table>");
+}
+
+fn none(out: &mut String, text: &str) {
+    out.push_str(&format!("<p {MUTED}>{text}</p>"));
+}
+
+fn spend_section(out: &mut String, digest: &Digest) {
+    out.push_str(&format!(
+        "<h2 {H2}>Spend</h2><p><strong>{}</strong> across {} requests from {} users, {}.</p>",
+        spend_text(digest.spend.spend_microdollars),
+        digest.spend.requests,
+        digest.spend.users,
+        change_text(
+            digest.spend.spend_microdollars,
+            digest.previous_spend_microdollars
+        ),
+    ));
+    if digest.top_models.is_empty() {
+        return;
+    }
+    let rows: Vec<_> = digest
+        .top_models
+        .iter()
+        .map(|m| {
+            vec![
+                (html_escape(&m.model), false),
+                (m.requests.to_string(), true),
+                (spend_text(m.spend_microdollars), true),
+            ]
+        })
+        .collect();
+    table(out, &["Top models", "Requests", "Spend"], &rows);
+}
+
+fn governance_section(out: &mut String, digest: &Digest) {
+    out.push_str(&format!(
+        "<h2 {H2}>Governance</h2><p>{} denials out of {} policy decisions.</p>",
+        digest.denials, digest.decisions,
+    ));
+    if !digest.denied_policies.is_empty() {
+        let rows: Vec<_> = digest
+            .denied_policies
+            .iter()
+            .map(|p| {
+                vec![
+                    (html_escape(&p.policy), false),
+                    (p.denials.to_string(), true),
+                ]
+            })
+            .collect();
+        table(out, &["Policy", "Denials"], &rows);
+    }
+
+    out.push_str(&format!("<h2 {H2}>Safety findings</h2>"));
+    if digest.safety_findings.is_empty() {
+        none(out, "No safety findings.");
+    } else {
+        let rows: Vec<_> = digest
+            .safety_findings
+            .iter()
+            .map(|f| {
+                vec![
+                    (html_escape(&f.severity), false),
+                    (html_escape(&f.category), false),
+                    (f.findings.to_string(), true),
+                ]
+            })
+            .collect();
+        table(out, &["Severity", "Category", "Findings"], &rows);
+    }
+}
+
+fn quality_section(out: &mut String, digest: &Digest, admin_url: &str) {
+    out.push_str(&format!("<h2 {H2}>Evals</h2>"));
+    match digest.eval_pass_rate() {
+        Some(rate) => {
+            out.push_str(&format!(
+                "<p><strong>{rate:.0}%</strong> of {} judged requests passed ({} partial, {} failed).</p>",
+                digest.evals.scored, digest.evals.partial, digest.evals.fail,
+            ));
+        },
+        None => none(out, "No requests were judged."),
+    }
+
+    out.push_str(&format!("<h2 {H2}>Sessions to look at</h2>"));
+    if digest.notable_sessions.is_empty() {
+        none(
+            out,
+            "No analysed session missed its goal or scored 2 or below.",
+        );
+        return;
+    }
+    let base = admin_url.trim_end_matches('/');
+    let rows: Vec<_> = digest
+        .notable_sessions
+        .iter()
+        .map(|s| {
+            let title = html_escape(if s.title.is_empty() {
+                s.session_id.as_str()
+            } else {
+                &s.title
+            });
+            let title = if base.is_empty() {
+                title
+            } else {
+                let url = format!("{base}{}", session_detail_url(&s.session_id));
+                format!(r#"<a href="{}">{title}</a>"#, html_escape(&url))
+            };
+            vec![
+                (title, false),
+                (html_escape(s.user_id.as_str()), false),
+                (html_escape(&s.goal_achieved), false),
+                (s.quality_score.to_string(), true),
+            ]
+        })
+        .collect();
+    table(out, &["Session", "User", "Goal met", "Quality"], &rows);
+}
+
+/// The HTML body of a digest email. `admin_url` turns sessions into links.
+#[must_use]
+pub fn render_html(digest: &Digest, admin_url: &str) -> String {
+    let mut out = String::with_capacity(4096);
+
+    out.push_str(
+        r#"<!DOCTYPE html><html><body style="font-family:-apple-system,Segoe UI,Helvetica,Arial,sans-serif;color:#222;max-width:640px">"#,
+    );
+
+    out.push_str(&format!(
+        "<h1 style=\"font-size:20px;margin:0 0 4px\">{}</h1><p {MUTED}>{} · {} to {} UTC</p>",
+        html_escape(&digest.title),
+        html_escape(&digest.scope),
+        digest.period.start.format("%Y-%m-%d %H:%M"),
+        digest.period.end.format("%Y-%m-%d %H:%M"),
+    ));
+    spend_section(&mut out, digest);
+    governance_section(&mut out, digest);
+    quality_section(&mut out, digest, admin_url);
+    out.push_str("</body></html>");
+    out
+}
@@ -0,0 +1,63 @@
+//! Digest delivery to a webhook: one JSON POST per digest.
+//!
+//! The body carries a `text` line, which chat tools such as Slack and Teams
+//! incoming webhooks display as is, beside the full digest for anything that
+//! wants the numbers. One attempt per run; the job retries a failure on its
+//! next run.
+
+use std::time::Duration;
+
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use serde_json::json;
+use systemprompt_web_shared::error::MarketplaceError;
+
+use super::{Digest, summary_line};
+use crate::repositories::config::digests::DigestWebhook;
+
+const TIMEOUT: Duration = Duration::from_secs(15);
+
+fn headers(webhook: &DigestWebhook) -> Result<HeaderMap, MarketplaceError> {
+    let mut headers = HeaderMap::new();
+    for (name, var) in &webhook.headers_env {
+        let value = std::env::var(var).map_err(|e| {
+            MarketplaceError::Internal(format!("digest webhook header `{name}` reads {var}: {e}"))
+        })?;
+        let name = HeaderName::try_from(name.as_str()).map_err(|e| {
+            MarketplaceError::BadRequest(format!("digest webhook header `{name}`: {e}"))
+        })?;
+        let value = HeaderValue::try_from(value).map_err(|e| {
+            MarketplaceError::BadRequest(format!("digest webhook header `{name}`: {e}"))
+        })?;
+        headers.insert(name, value);
+    }
+    Ok(headers)
+}
+
+pub(super) async fn post_digest(
+    webhook: &DigestWebhook,
+    digest: &Digest,
+    admin_url: &str,
+) -> Result<(), MarketplaceError> {
+    let mut text = format!("{}: {}", digest.title, summary_line(digest));
+    if !admin_url.is_empty() {
+        text.push_str(&format!(" {}/admin", admin_url.trim_end_matches('/')));
+    }
+    let body = json!({ "text": text, "digest": digest });
+    let response = reqwest::Client::new()
+        .post(&webhook.url)
+        .headers(headers(webhook)?)
+        .timeout(TIMEOUT)
+        .json(&body)
+        .send()
+        .await
+        .map_err(|e| MarketplaceError::Internal(format!("digest webhook unreachable: {e}")))?;
+    let status = response.status();
+    if status.is_success() {
+        return Ok(());
+    }
+    let detail = response.text().await.unwrap_or_default();
+    Err(MarketplaceError::Internal(format!(
+        "digest webhook returned {status}: {}",
+        detail.chars().take(300).collect::<String>()
+    )))
+}
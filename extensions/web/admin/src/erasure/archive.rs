@@ -0,0 +1,111 @@
+//! A minimal ZIP writer for data-subject exports.
+//!
+//! Entries are stored uncompressed: the export is a handful of JSON files
+//! built in memory and fetched once, and every archive tool opens a stored
+//! ZIP. ZIP64 is not written, so an archive is capped at 4 GiB and 65 535
+//! entries; [`write_zip`] refuses anything larger rather than emit a file
+//! that extracts truncated.
+
+use chrono::{Datelike, NaiveDateTime, Timelike};
+
+const LOCAL_HEADER: u32 = 0x0403_4b50;
+const CENTRAL_HEADER: u32 = 0x0201_4b50;
+const END_OF_CENTRAL_DIRECTORY: u32 = 0x0605_4b50;
+const VERSION: u16 = 20;
+// Why: bit 11 marks names as UTF-8, so non-ASCII file names survive.
+const FLAG_UTF8: u16 = 0x0800;
+
+#[derive(Debug, Clone, Copy, thiserror::Error)]
+#[error("export too large for a ZIP without ZIP64: {0}")]
+pub struct ArchiveTooLarge(&'static str);
+
+// Why: a plain ZIP header carries only an MS-DOS date and time, which
+// cannot express years before 1980.
+fn dos_timestamp(at: NaiveDateTime) -> (u16, u16) {
+    let year = u16::try_from(at.year().clamp(1980, 2107) - 1980).unwrap_or(0);
+    let date = (year << 9)
+        | (u16::try_from(at.month()).unwrap_or(1) << 5)
+        | u16::try_from(at.day()).unwrap_or(1);
+    let time = (u16::try_from(at.hour()).unwrap_or(0) << 11)
+        | (u16::try_from(at.minute()).unwrap_or(0) << 5)
+        | u16::try_from(at.second() / 2).unwrap_or(0);
+    (time, date)
+}
+
+fn u32_len(len: usize) -> Result<u32, ArchiveTooLarge> {
+    u32::try_from(len)
+        .ok()
+        .ok_or(ArchiveTooLarge("entry or archive over 4 GiB"))
+}
+
+struct Central {
+    name_len: u16,
+    crc: u32,
+    size: u32,
+    offset: u32,
+}
+
+/// Write `entries` (name, contents) as a stored ZIP, every entry stamped
+/// `modified`.
+pub fn write_zip(
+    entries: &[(String, Vec<u8>)],
+    modified: NaiveDateTime,
+) -> Result<Vec<u8>, ArchiveTooLarge> {
+    let count = u16::try_from(entries.len())
+        .ok()
+        .ok_or(ArchiveTooLarge("more than 65535 entries"))?;
+    let (time, date) = dos_timestamp(modified);
+    let mut out = Vec::new();
+    let mut central = Vec::with_capacity(entries.len());
+
+    for (name, data) in entries {
+        let name_len = u16::try_from(name.len())
+            .ok()
+            .ok_or(ArchiveTooLarge("file name too long"))?;
+        let entry = Central {
+            name_len,
+            crc: crc32fast::hash(data),
+            size: u32_len(data.len())?,
+            offset: u32_len(out.len())?,
+        };
+        out.extend_from_slice(&LOCAL_HEADER.to_le_bytes());
+        for field in [VERSION, FLAG_UTF8, 0, time, date] {
+            out.extend_from_slice(&field.to_le_bytes());
+        }
+        for field in [entry.crc, entry.size, entry.size] {
+            out.extend_from_slice(&field.to_le_bytes());
+        }
+        out.extend_from_slice(&name_len.to_le_bytes());
+        out.extend_from_slice(&0u16.to_le_bytes());
+        out.extend_from_slice(name.as_bytes());
+        out.extend_from_slice(data);
+        central.push(entry);
+    }
+
+    let directory_offset = u32_len(out.len())?;
+    for ((name, _), entry) in entries.iter().zip(&central) {
+        out.extend_from_slice(&CENTRAL_HEADER.to_le_bytes());
+        for field in [VERSION, VERSION, FLAG_UTF8, 0, time, date] {
+            out.extend_from_slice(&field.to_le_bytes());
+        }
+        for field in [entry.crc, entry.size, entry.size] {
+            out.extend_from_slice(&field.to_le_bytes());
+        }
+        for field in [entry.name_len, 0, 0, 0, 0] {
+            out.extend_from_slice(&field.to_le_bytes());
+        }
+        out.extend_from_slice(&0u32.to_le_bytes());
+        out.extend_from_slice(&entry.offset.to_le_bytes());
+        out.extend_from_slice(name.as_bytes());
+    }
+    let directory_size = u32_len(out.len())? - directory_offset;
+
+    out.extend_from_slice(&END_OF_CENTRAL_DIRECTORY.to_le_bytes());
+    for field in [0, 0, count, count] {
+        out.extend_from_slice(&field.to_le_bytes());
+    }
+    out.extend_from_slice(&directory_size.to_le_bytes());
+    out.extend_from_slice(&directory_offset.to_le_bytes());
+    out.extend_from_slice(&0u16.to_le_bytes());
+    Ok(out)
+}
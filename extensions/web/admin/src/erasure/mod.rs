@@ -0,0 +1,140 @@
+//! Right to erasure and data-subject export.
+//!
+//! Deleting a user used to remove the account and leave their requests,
+//! transcripts, hook events and secrets behind. An erasure now goes through
+//! every table holding rows tied to the user id and does one of three things:
+//!
+//! - **deleted** — content and personal data: transcripts, hook events, request
+//!   bodies, session analyses, link clicks, secrets, the account.
+//! - **pseudonymized** — rows kept for cost reporting and audit (gateway
+//!   requests, governance decisions, tool executions, the secret audit log,
+//!   closed chargeback months, cost anomalies, config revisions, erasure
+//!   certificates, master key events): the user id is replaced with
+//!   [`pseudonym`], a keyed hash that is the same every time for the same user,
+//!   and free text is stripped.
+//! - **retained** — kept unchanged. No table is retained now; certificates
+//!   issued before closed chargeback lines could be pseudonymized list them
+//!   this way.
+//!
+//! A committed erasure stores an [`ErasedTable`] list as its certificate. An
+//! export is the inverse: every row we hold about the user, one JSON file per
+//! table in a ZIP ([`export_archive`]).
+
+pub mod archive;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use systemprompt::identifiers::UserId;
+
+use crate::repositories::config::retention::LegalHold;
+use crate::repositories::erasure::{NewErasureCertificate, erase_user_rows};
+use crate::util::hmac::hmac_sha256;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErasureAction {
+    Deleted,
+    Pseudonymized,
+    Retained,
+}
+
+/// One line of an erasure certificate.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ErasedTable {
+    pub table: String,
+    pub action: ErasureAction,
+    pub rows: i64,
+}
+
+/// The stable stand-in for an erased user id. Keyed, so it cannot be
+/// reversed by hashing candidate ids without the platform secret.
+#[must_use]
+pub fn pseudonym(secret: &[u8], user_id: &UserId) -> String {
+    let mac = hmac_sha256(secret, format!("erasure:{user_id}").as_bytes());
+    format!("erased-{}", hex::encode(&mac[..16]))
+}
+
+/// Why `user_id` cannot be erased yet, if a legal hold names them.
+#[must_use]
+pub fn hold_reason<'a>(holds: &'a [LegalHold], user_id: &UserId) -> Option<&'a str> {
+    holds
+        .iter()
+        .find(|h| h.user_id.as_ref() == Some(user_id))
+        .map(|h| h.reason.as_str())
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ErasureReport {
+    /// `None` for a preview, which changed nothing.
+    pub certificate_id: Option<String>,
+    pub subject_hash: String,
+    pub tables: Vec<ErasedTable>,
+}
+
+/// Who is erased, by whom and why.
+#[derive(Debug, Clone, Copy)]
+pub struct ErasureRequest<'a> {
+    pub user_id: &'a UserId,
+    pub requested_by: &'a UserId,
+    pub reason: &'a str,
+}
+
+/// Erase the user, or with `commit` false count what erasing would touch.
+pub async fn erase(
+    pool: &PgPool,
+    secret: &[u8],
+    request: ErasureRequest<'_>,
+    commit: bool,
+) -> Result<ErasureReport, sqlx::Error> {
+    let subject_hash = pseudonym(secret, request.user_id);
+    let certificate_id = commit.then(|| format!("erasure_{}", uuid::Uuid::new_v4().simple()));
+    let certificate = certificate_id.as_deref().map(|id| NewErasureCertificate {
+        id,
+        subject_hash: &subject_hash,
+        requested_by: request.requested_by,
+        reason: request.reason,
+    });
+    let tables = erase_user_rows(pool, request.user_id, &subject_hash, certificate).await?;
+    Ok(ErasureReport {
+        certificate_id,
+        subject_hash,
+        tables,
+    })
+}
+
+/// The ZIP handed to a data subject: `manifest.json` plus one file per key
+/// of `export` (see `repositories::erasure::export`).
+pub fn export_archive(
+    export: &serde_json::Value,
+    user_id: &UserId,
+    generated_at: DateTime<Utc>,
+) -> Result<Vec<u8>, archive::ArchiveTooLarge> {
+    let mut files = serde_json::Map::new();
+    let mut entries = Vec::new();
+    if let Some(tables) = export.as_object() {
+        for (name, rows) in tables {
+            let count = rows
+                .as_array()
+                .map_or_else(|| usize::from(!rows.is_null()), Vec::len);
+            files.insert(format!("{name}.json"), count.into());
+            entries.push((
+                format!("{name}.json"),
+                serde_json::to_vec_pretty(rows).unwrap_or_default(),
+            ));
+        }
+    }
+    let manifest = serde_json::json!({
+        "user_id": user_id.as_str(),
+        "generated_at": generated_at.to_rfc3339(),
+        "files": files,
+    });
+    entries.insert(
+        0,
+        (
+            "manifest.json".to_owned(),
+            serde_json::to_vec_pretty(&manifest).unwrap_or_default(),
+        ),
+    );
+    archive::write_zip(&entries, generated_at.naive_utc())
+}
@@ -13,6 +13,7 @@ use thiserror::Error;
 use crate::handlers::shared::ErrorBody;
 use crate::repositories::access_tokens::AccessTokenRepoError;
 use crate::repositories::secrets::secret_crypto::SecretCryptoError;
+use crate::services::auth::TokenRejection;
 use crate::templates::AdminTemplateError;
 use systemprompt_web_shared::error::MarketplaceError;
 
@@ -38,6 +39,16 @@ pub enum AdminError {
     #[error("Conflict: {0}")]
     Conflict(String),
 
+    /// The caller's `If-Match` no longer names the current state: someone
+    /// else changed the resource since the caller read it.
+    #[error("Precondition failed: {0}")]
+    PreconditionFailed(String),
+
+    /// The endpoint only accepts conditional writes and the caller sent no
+    /// `If-Match`.
+    #[error("Precondition required: {0}")]
+    PreconditionRequired(String),
+
     #[error("Too many requests: {0}")]
     RateLimited(String),
 
@@ -68,6 +79,19 @@ pub enum AdminError {
     Internal(#[source] Box<dyn std::error::Error + Send + Sync>),
 }
 
+impl From<TokenRejection> for AdminError {
+    fn from(value: TokenRejection) -> Self {
+        match value {
+            TokenRejection::Unauthenticated => {
+                Self::Unauthorized("A valid access token is required".to_owned())
+            },
+            TokenRejection::NotAdmin => {
+                Self::Forbidden("The token owner must hold the admin role".to_owned())
+            },
+        }
+    }
+}
+
 impl From<AdminTemplateError> for AdminError {
     fn from(value: AdminTemplateError) -> Self {
         Self::Internal(Box::new(value))
@@ -95,6 +119,8 @@ impl AdminError {
             Self::Unauthorized(_) | Self::Unauthenticated(_) => StatusCode::UNAUTHORIZED,
             Self::Forbidden(_) => StatusCode::FORBIDDEN,
             Self::Conflict(_) => StatusCode::CONFLICT,
+            Self::PreconditionFailed(_) => StatusCode::PRECONDITION_FAILED,
+            Self::PreconditionRequired(_) => StatusCode::PRECONDITION_REQUIRED,
             Self::RateLimited(_) => StatusCode::TOO_MANY_REQUESTS,
             Self::Unavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
             Self::Upstream(_) => StatusCode::BAD_GATEWAY,
@@ -113,6 +139,8 @@ impl AdminError {
             | Self::Unauthorized(msg)
             | Self::Forbidden(msg)
             | Self::Conflict(msg)
+            | Self::PreconditionFailed(msg)
+            | Self::PreconditionRequired(msg)
             | Self::RateLimited(msg)
             | Self::Unavailable(msg)
             | Self::AccessTokenRepo(AccessTokenRepoError::Validation(msg))
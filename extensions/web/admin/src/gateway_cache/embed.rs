@@ -0,0 +1,52 @@
+//! Embeddings for similarity matching, from an `OpenAI`-compatible endpoint.
+//!
+//! The call sits in front of every lookup on a route with a threshold, so it
+//! is bounded by `timeout_ms`; a slow or failed call is a miss, never a
+//! failed request.
+
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use serde::Deserialize;
+
+use crate::repositories::config::gateway::EmbeddingSettings;
+
+#[derive(Deserialize)]
+struct EmbeddingResponse {
+    data: Vec<EmbeddingDatum>,
+}
+
+#[derive(Deserialize)]
+struct EmbeddingDatum {
+    embedding: Vec<f32>,
+}
+
+fn client() -> &'static reqwest::Client {
+    static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+    CLIENT.get_or_init(reqwest::Client::new)
+}
+
+pub(super) async fn embed(settings: &EmbeddingSettings, text: &str) -> Result<Vec<f32>, String> {
+    let mut request = client()
+        .post(&settings.endpoint)
+        .timeout(Duration::from_millis(settings.timeout_ms))
+        .json(&serde_json::json!({ "model": settings.model, "input": text }));
+    if let Some(var) = settings.api_key_env.as_deref() {
+        let key = std::env::var(var).map_err(|e| format!("{var}: {e}"))?;
+        request = request.bearer_auth(key);
+    }
+    let response = request.send().await.map_err(|e| e.to_string())?;
+    if !response.status().is_success() {
+        return Err(format!(
+            "embeddings endpoint returned {}",
+            response.status()
+        ));
+    }
+    let body: EmbeddingResponse = response.json().await.map_err(|e| e.to_string())?;
+    body.data
+        .into_iter()
+        .next()
+        .map(|d| d.embedding)
+        .filter(|e| !e.is_empty())
+        .ok_or_else(|| "embeddings endpoint returned no vector".to_owned())
+}
@@ -0,0 +1,120 @@
+//! Cache keys and similarity, free of I/O so the matching rules can be tested
+//! alone.
+
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+
+// Why: these change how a reply is delivered, not what it says, so a
+// streamed and a buffered request for the same thing share an entry.
+const DELIVERY_FIELDS: &[&str] = &["stream", "stream_options"];
+
+// Why: the conversation sits under a different field on each upstream wire:
+// `messages` for Anthropic and OpenAI Chat, `input` for OpenAI Responses,
+// `contents` for Gemini.
+const CONVERSATION_FIELDS: &[&str] = &["messages", "input", "contents"];
+
+/// What an entry is isolated by besides the request itself.
+#[derive(Debug, Clone, Copy)]
+pub struct CacheScope<'a> {
+    pub provider: &'a str,
+    pub upstream_model: &'a str,
+    /// Set when the route keeps each user's entries to themselves.
+    pub user_id: Option<&'a str>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CacheKeys {
+    /// Digest of the whole request: the exact-match key.
+    pub exact: String,
+    /// Digest of the request without its final message: similarity matching
+    /// only compares entries that agree on everything else.
+    pub shape: String,
+}
+
+fn sorted(value: Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            let mut entries: Vec<(String, Value)> = map.into_iter().collect();
+            entries.sort_by(|a, b| a.0.cmp(&b.0));
+            Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(k, v)| (k, sorted(v)))
+                    .collect::<Map<String, Value>>(),
+            )
+        },
+        Value::Array(items) => Value::Array(items.into_iter().map(sorted).collect()),
+        other => other,
+    }
+}
+
+fn digest(scope: CacheScope<'_>, body: &Value) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(b"v1\n");
+    hasher.update(scope.provider.as_bytes());
+    hasher.update(b"\n");
+    hasher.update(scope.upstream_model.as_bytes());
+    hasher.update(b"\n");
+    hasher.update(scope.user_id.unwrap_or("*").as_bytes());
+    hasher.update(b"\n");
+    hasher.update(body.to_string().as_bytes());
+    hex::encode(hasher.finalize())
+}
+
+/// Derive both keys from the body about to be sent upstream. `None` when the
+/// body is not a JSON object, which no supported upstream sends.
+#[must_use]
+pub fn cache_keys(scope: CacheScope<'_>, prepared_body: &[u8]) -> Option<CacheKeys> {
+    let Value::Object(mut map) = serde_json::from_slice::<Value>(prepared_body).ok()? else {
+        return None;
+    };
+    for field in DELIVERY_FIELDS {
+        map.remove(*field);
+    }
+    let exact = digest(scope, &sorted(Value::Object(map.clone())));
+    for field in CONVERSATION_FIELDS {
+        match map.get_mut(*field) {
+            Some(Value::Array(items)) => {
+                items.pop();
+            },
+            Some(_) => {
+                map.remove(*field);
+            },
+            None => {},
+        }
+    }
+    let shape = digest(scope, &sorted(Value::Object(map)));
+    Some(CacheKeys { exact, shape })
+}
+
+/// Cosine similarity, or `None` when the vectors cannot be compared.
+#[must_use]
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> Option<f32> {
+    if a.len() != b.len() || a.is_empty() {
+        return None;
+    }
+    let (mut dot, mut norm_a, mut norm_b) = (0.0f64, 0.0f64, 0.0f64);
+    for (x, y) in a.iter().zip(b) {
+        let (x, y) = (f64::from(*x), f64::from(*y));
+        dot = x.mul_add(y, dot);
+        norm_a = x.mul_add(x, norm_a);
+        norm_b = y.mul_add(y, norm_b);
+    }
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return None;
+    }
+    Some((dot / (norm_a.sqrt() * norm_b.sqrt())) as f32)
+}
+
+/// The most similar candidate at or above `threshold`, with its similarity.
+pub fn best_match<'a>(
+    query: &[f32],
+    candidates: impl IntoIterator<Item = (i64, &'a [f32])>,
+    threshold: f32,
+) -> Option<(i64, f32)> {
+    candidates
+        .into_iter()
+        .filter_map(|(id, embedding)| cosine_similarity(query, embedding).map(|s| (id, s)))
+        .filter(|(_, similarity)| *similarity >= threshold)
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+}
@@ -0,0 +1,284 @@
+//! One request's trip through the cache: find the request it belongs to,
+//! look for an exact then a similar entry, record the outcome, and on a miss
+//! store whatever the upstream sends back.
+
+use std::sync::{Arc, Mutex, PoisonError};
+
+use futures_util::stream::{self, BoxStream, StreamExt};
+use sqlx::PgPool;
+use systemprompt::api::services::gateway::audit::payload::digest_hex;
+use systemprompt::api::services::gateway::{OutboundCtx, OutboundOutcome};
+use systemprompt::models::wire::canonical::{CanonicalEvent, CanonicalRequest, Role};
+
+use super::embed::embed;
+use super::key::{CacheKeys, CacheScope, best_match, cache_keys};
+use super::recorder::StreamRecorder;
+use super::replay::CachedResponse;
+use crate::repositories::config::gateway::{EmbeddingSettings, RouteCacheSettings};
+use crate::repositories::gateway_cache::{
+    NewCacheEntry, NewCacheLookup, PendingGatewayRequest, delete_expired_cache_entries,
+    find_cache_entry, find_cache_entry_by_id, insert_cache_entry, insert_cache_lookup,
+    list_cache_candidates, list_pending_requests_by_digest, update_cache_entry_hit,
+};
+
+pub(super) struct Lookup {
+    pool: Arc<PgPool>,
+    settings: RouteCacheSettings,
+    embedding: Option<EmbeddingSettings>,
+    pending: PendingGatewayRequest,
+    route_id: String,
+    provider: String,
+    upstream_model: String,
+    keys: CacheKeys,
+    query_embedding: Option<Vec<f32>>,
+}
+
+struct Hit {
+    entry_id: i64,
+    outcome: &'static str,
+    similarity: Option<f32>,
+    response: CachedResponse,
+}
+
+/// The request a body belongs to, when every unclaimed request in flight
+/// with it came from the same user and session.
+///
+/// The adapter cannot see the caller, so identical bodies from two callers
+/// are indistinguishable; guessing would serve one user's `per_user` entry to
+/// the other. Such a request skips the cache instead. Requests from one
+/// caller are interchangeable for scoping and for naming the request a miss
+/// stores, so the oldest is taken. Hits are never marked from this guess.
+#[must_use]
+pub fn sole_pending_request(
+    candidates: Vec<PendingGatewayRequest>,
+) -> Option<PendingGatewayRequest> {
+    let mut candidates = candidates.into_iter();
+    let first = candidates.next()?;
+    candidates
+        .all(|c| c.user_id == first.user_id && c.session == first.session)
+        .then_some(first)
+}
+
+pub(super) async fn begin(
+    pool: Arc<PgPool>,
+    settings: RouteCacheSettings,
+    embedding: Option<EmbeddingSettings>,
+    ctx: &OutboundCtx<'_>,
+    body: &[u8],
+) -> Option<Lookup> {
+    let candidates = match list_pending_requests_by_digest(&pool, &digest_hex(body)).await {
+        Ok(candidates) => candidates,
+        Err(e) => {
+            tracing::warn!(error = %e, "response cache request lookup failed");
+            return None;
+        },
+    };
+    let Some(pending) = sole_pending_request(candidates) else {
+        tracing::debug!(route = %ctx.route.id, "no single caller for body; cache skipped");
+        return None;
+    };
+    let scope = CacheScope {
+        provider: ctx.route.provider.as_str(),
+        upstream_model: ctx.upstream_model,
+        user_id: settings.per_user.then_some(pending.user_id.as_str()),
+    };
+    let keys = cache_keys(scope, body)?;
+    Some(Lookup {
+        pool,
+        settings,
+        embedding,
+        route_id: ctx.route.id.as_str().to_owned(),
+        provider: scope.provider.to_owned(),
+        upstream_model: scope.upstream_model.to_owned(),
+        pending,
+        keys,
+        query_embedding: None,
+    })
+}
+
+impl Lookup {
+    async fn find_exact(&self) -> Option<Hit> {
+        let row = find_cache_entry(&self.pool, &self.keys.exact)
+            .await
+            .map_err(|e| tracing::warn!(error = %e, "response cache read failed"))
+            .ok()??;
+        Some(Hit {
+            entry_id: row.id,
+            outcome: "exact",
+            similarity: None,
+            response: serde_json::from_value(row.response).ok()?,
+        })
+    }
+
+    async fn find_similar(&mut self, request: &CanonicalRequest) -> Option<Hit> {
+        let threshold = self.settings.similarity_threshold?;
+        let settings = self.embedding.as_ref()?;
+        let text = request.latest_message_text(Role::User)?;
+        let query = embed(settings, &text)
+            .await
+            .map_err(|e| tracing::warn!(error = %e, "response cache embedding failed"))
+            .ok()?;
+        let limit = i64::from(settings.max_candidates);
+        let candidates = list_cache_candidates(&self.pool, &self.keys.shape, limit).await;
+        self.query_embedding = Some(query);
+        let candidates = candidates
+            .map_err(|e| tracing::warn!(error = %e, "response cache candidates failed"))
+            .ok()?;
+        let query = self.query_embedding.as_deref()?;
+        let (entry_id, similarity) = best_match(
+            query,
+            candidates.iter().map(|c| (c.id, c.embedding.as_slice())),
+            threshold,
+        )?;
+        let row = find_cache_entry_by_id(&self.pool, entry_id).await.ok()??;
+        Some(Hit {
+            entry_id,
+            outcome: "semantic",
+            similarity: Some(similarity),
+            response: serde_json::from_value(row.response).ok()?,
+        })
+    }
+
+    async fn record_miss(&self) {
+        self.record(NewCacheLookup {
+            ai_request_id: Some(&self.pending.ai_request_id),
+            replay_id: None,
+            route_id: &self.route_id,
+            outcome: "miss",
+            entry_id: None,
+            similarity: None,
+        })
+        .await;
+    }
+
+    async fn record_hit(&self, hit: &Hit, replay_id: &str) {
+        self.record(NewCacheLookup {
+            ai_request_id: None,
+            replay_id: Some(replay_id),
+            route_id: &self.route_id,
+            outcome: hit.outcome,
+            entry_id: Some(hit.entry_id),
+            similarity: hit.similarity,
+        })
+        .await;
+    }
+
+    async fn record(&self, lookup: NewCacheLookup<'_>) {
+        if let Err(e) = insert_cache_lookup(&self.pool, lookup).await {
+            tracing::warn!(error = %e, "response cache lookup insert failed");
+        }
+    }
+
+    // Why: the lookup row is written before the reply is returned, because
+    // the trigger that marks the `ai_requests` row as a hit looks it up by
+    // the reply's id when core stores that reply.
+    pub(super) async fn serve(&mut self, request: &CanonicalRequest) -> Option<OutboundOutcome> {
+        let hit = match self.find_exact().await {
+            Some(hit) => Some(hit),
+            None => self.find_similar(request).await,
+        };
+        let Some(hit) = hit else {
+            self.record_miss().await;
+            return None;
+        };
+        let id = format!("cache_{}", uuid::Uuid::new_v4().simple());
+        self.record_hit(&hit, &id).await;
+        if let Err(e) = update_cache_entry_hit(&self.pool, hit.entry_id).await {
+            tracing::warn!(error = %e, "response cache hit count update failed");
+        }
+        tracing::debug!(route = %self.route_id, entry = hit.entry_id, outcome = hit.outcome, "response cache hit");
+        Some(if request.stream {
+            let events: Vec<Result<CanonicalEvent, String>> =
+                hit.response.to_events(&id).into_iter().map(Ok).collect();
+            OutboundOutcome::Streaming(stream::iter(events).boxed())
+        } else {
+            OutboundOutcome::Buffered(Box::new(hit.response.to_canonical(&id)))
+        })
+    }
+
+    fn store(self, response: CachedResponse) {
+        tokio::spawn(async move {
+            let Ok(value) = serde_json::to_value(&response) else {
+                return;
+            };
+            let ttl = chrono::Duration::seconds(
+                i64::try_from(self.settings.ttl_secs).unwrap_or(i64::MAX),
+            );
+            let entry = NewCacheEntry {
+                cache_key: &self.keys.exact,
+                shape_key: &self.keys.shape,
+                route_id: &self.route_id,
+                scope_user_id: self.settings.per_user.then_some(&self.pending.user_id),
+                provider: &self.provider,
+                upstream_model: &self.upstream_model,
+                embedding: self.query_embedding.as_deref(),
+                response: &value,
+                source_ai_request_id: &self.pending.ai_request_id,
+                expires_at: chrono::Utc::now() + ttl,
+            };
+            if let Err(e) = insert_cache_entry(&self.pool, entry).await {
+                tracing::warn!(error = %e, "response cache insert failed");
+                return;
+            }
+            if let Err(e) = delete_expired_cache_entries(&self.pool).await {
+                tracing::warn!(error = %e, "response cache expiry sweep failed");
+            }
+        });
+    }
+
+    pub(super) fn capture(self, outcome: OutboundOutcome) -> OutboundOutcome {
+        match outcome {
+            OutboundOutcome::Buffered(response) => {
+                if let Some(cached) = CachedResponse::from_canonical(&response) {
+                    self.store(cached);
+                }
+                OutboundOutcome::Buffered(response)
+            },
+            OutboundOutcome::RawBuffered {
+                body,
+                content_type,
+                canonical,
+            } => {
+                if let Some(cached) = CachedResponse::from_canonical(&canonical) {
+                    self.store(cached);
+                }
+                OutboundOutcome::RawBuffered {
+                    body,
+                    content_type,
+                    canonical,
+                }
+            },
+            OutboundOutcome::Streaming(upstream) => {
+                OutboundOutcome::Streaming(self.record_stream(upstream))
+            },
+            raw @ OutboundOutcome::RawStreaming { .. } => raw,
+        }
+    }
+
+    fn record_stream(
+        self,
+        upstream: BoxStream<'static, Result<CanonicalEvent, String>>,
+    ) -> BoxStream<'static, Result<CanonicalEvent, String>> {
+        let recorder = Arc::new(Mutex::new(StreamRecorder::default()));
+        let tap = Arc::clone(&recorder);
+        let tapped = upstream.inspect(move |item| {
+            let mut recorder = tap.lock().unwrap_or_else(PoisonError::into_inner);
+            match item {
+                Ok(event) => recorder.record(event),
+                Err(_) => recorder.fail(),
+            }
+        });
+        // Why: runs only when the upstream stream ends; a client that hangs up
+        // first drops the stream before this, so a partial reply is never
+        // stored.
+        let tail = stream::once(async move {
+            let finished =
+                std::mem::take(&mut *recorder.lock().unwrap_or_else(PoisonError::into_inner));
+            if let Some(cached) = finished.finish() {
+                self.store(cached);
+            }
+        })
+        .filter_map(|()| std::future::ready(None));
+        tapped.chain(tail).boxed()
+    }
+}
@@ -0,0 +1,176 @@
+//! Gateway response cache: repeated requests on an opted-in route are served
+//! without calling the upstream.
+//!
+//! Core has no cache stage, but it lets an extension register an
+//! [`OutboundAdapter`] under a built-in wire tag, replacing the built-in. The
+//! cache registers one [`CachingOutbound`] per wire, each wrapping the target
+//! failover layer around core's own adapter, so every upstream call passes
+//! through it and a hit never reaches an upstream to fail over from. Routes
+//! not listed in `services/gateway/cache.yaml` go straight to the wrapped
+//! adapter.
+//!
+//! On a cached route the adapter keys the request on the body about to be
+//! sent upstream (see [`cache_keys`]), tries an exact match, then, when the
+//! route sets a `similarity_threshold`, the closest entry whose final user
+//! message embeds within it. A hit is replayed with zero usage, buffered or as
+//! a stream to match the request, so core prices it at nothing. Its response
+//! id is a fresh replay id, which core stores with the reply on the request
+//! it served; a trigger on the payload table finds the lookup by that id and
+//! sets `cache_hit` on exactly that row. A miss is sent upstream and the reply
+//! stored once it has completed. A body that two callers have in flight at
+//! once skips the cache, since the adapter cannot tell whose it is (see
+//! [`sole_pending_request`]).
+//!
+//! Cached routes always take the canonical lane, never the raw passthrough:
+//! an entry has to be replayable to a caller on any inbound protocol, and a
+//! raw upstream stream cannot be read back into one.
+//!
+//! Like the targets selector, the adapters are built by `inventory` with no
+//! context, so the pool arrives through [`install`]. Until then, or when the
+//! cache file fails to load, nothing is cached.
+
+mod embed;
+mod key;
+mod lookup;
+mod recorder;
+mod replay;
+
+use std::sync::{Arc, LazyLock, OnceLock, PoisonError, RwLock};
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use async_trait::async_trait;
+use sqlx::PgPool;
+use systemprompt::api::services::gateway::protocol::outbound::PreparedBody;
+use systemprompt::api::services::gateway::protocol::outbound::anthropic::AnthropicOutbound;
+use systemprompt::api::services::gateway::protocol::outbound::gemini::GeminiOutbound;
+use systemprompt::api::services::gateway::protocol::outbound::openai_chat::OpenAiChatOutbound;
+use systemprompt::api::services::gateway::protocol::outbound::openai_responses::OpenAiResponsesOutbound;
+use systemprompt::api::services::gateway::{
+    OutboundAdapter, OutboundAdapterRegistration, OutboundCtx, OutboundOutcome,
+};
+use systemprompt::config::ProfileBootstrap;
+
+pub use key::{CacheKeys, CacheScope, best_match, cache_keys, cosine_similarity};
+pub use lookup::sole_pending_request;
+pub use recorder::StreamRecorder;
+pub use replay::{CachedBlock, CachedResponse};
+
+use crate::gateway_routing::FailoverOutbound;
+use crate::repositories::config::gateway::{
+    ResponseCacheConfig, RouteCacheSettings, load_response_cache_config,
+};
+
+const CONFIG_TTL: Duration = Duration::from_secs(5);
+
+static POOL: OnceLock<Arc<PgPool>> = OnceLock::new();
+type CachedConfig = Option<(Arc<ResponseCacheConfig>, Instant)>;
+
+static CONFIG_CACHE: LazyLock<RwLock<CachedConfig>> = LazyLock::new(|| RwLock::new(None));
+
+/// Give the cache the pool it stores entries in. Later calls are ignored.
+pub fn install(pool: Arc<PgPool>) {
+    POOL.get_or_init(|| pool);
+}
+
+fn load_config() -> Option<ResponseCacheConfig> {
+    let services_path = ProfileBootstrap::get().ok()?.paths.services.clone();
+    load_response_cache_config(services_path.as_ref())
+        .map_err(
+            |e| tracing::warn!(error = %e, "response cache config failed to load; caching nothing"),
+        )
+        .ok()
+}
+
+// Why: `build_body` is synchronous, so the cached config sits behind a std
+// lock; the file read on expiry is a few hundred bytes every few seconds.
+fn config() -> Arc<ResponseCacheConfig> {
+    if let Some((config, at)) = CONFIG_CACHE
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .as_ref()
+        && at.elapsed() < CONFIG_TTL
+    {
+        return Arc::clone(config);
+    }
+    let config = Arc::new(load_config().unwrap_or_default());
+    *CONFIG_CACHE.write().unwrap_or_else(PoisonError::into_inner) =
+        Some((Arc::clone(&config), Instant::now()));
+    config
+}
+
+fn route_settings(ctx: &OutboundCtx<'_>) -> Option<RouteCacheSettings> {
+    POOL.get()?;
+    config().settings_for(ctx.route.id.as_str())
+}
+
+/// A built-in outbound adapter with the response cache in front of it.
+pub struct CachingOutbound {
+    inner: Arc<dyn OutboundAdapter>,
+}
+
+impl std::fmt::Debug for CachingOutbound {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CachingOutbound").finish_non_exhaustive()
+    }
+}
+
+impl CachingOutbound {
+    #[must_use]
+    pub fn new(inner: Arc<dyn OutboundAdapter>) -> Self {
+        Self { inner }
+    }
+}
+
+#[async_trait]
+impl OutboundAdapter for CachingOutbound {
+    fn build_body(&self, ctx: &OutboundCtx<'_>) -> Result<PreparedBody> {
+        if ctx.raw_body.is_none() || route_settings(ctx).is_none() {
+            return self.inner.build_body(ctx);
+        }
+        self.inner.build_body(&OutboundCtx {
+            raw_body: None,
+            ..*ctx
+        })
+    }
+
+    async fn send(&self, ctx: OutboundCtx<'_>, body: &PreparedBody) -> Result<OutboundOutcome> {
+        let (Some(settings), Some(pool)) = (route_settings(&ctx), POOL.get()) else {
+            return self.inner.send(ctx, body).await;
+        };
+        if body.raw_lane {
+            return self.inner.send(ctx, body).await;
+        }
+        let embedding = config().embedding.clone();
+        let Some(mut lookup) =
+            lookup::begin(Arc::clone(pool), settings, embedding, &ctx, &body.bytes).await
+        else {
+            return self.inner.send(ctx, body).await;
+        };
+        if let Some(hit) = lookup.serve(ctx.request).await {
+            return Ok(hit);
+        }
+        let outcome = self.inner.send(ctx, body).await?;
+        Ok(lookup.capture(outcome))
+    }
+}
+
+macro_rules! register_caching_outbound {
+    ($tag:literal, $inner:expr) => {
+        inventory::submit! {
+            OutboundAdapterRegistration {
+                tag: $tag,
+                factory: || {
+                    Arc::new(CachingOutbound::new(Arc::new(FailoverOutbound::new(
+                        Arc::new($inner),
+                    ))))
+                },
+            }
+        }
+    };
+}
+
+register_caching_outbound!("anthropic", AnthropicOutbound);
+register_caching_outbound!("openai-chat", OpenAiChatOutbound);
+register_caching_outbound!("openai-responses", OpenAiResponsesOutbound);
+register_caching_outbound!("gemini", GeminiOutbound);
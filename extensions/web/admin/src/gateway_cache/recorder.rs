@@ -0,0 +1,162 @@
+//! Rebuilds a streamed reply from its events so it can be cached once the
+//! stream ends.
+
+use std::collections::BTreeMap;
+
+use systemprompt::models::wire::canonical::{CanonicalEvent, ContentBlockKind};
+
+use super::replay::{CachedBlock, CachedResponse};
+
+#[derive(Debug)]
+enum PartialBlock {
+    Text(String),
+    Thinking {
+        text: String,
+        signature: Option<String>,
+        id: Option<String>,
+        encrypted_content: Option<String>,
+    },
+    ToolUse {
+        id: String,
+        name: String,
+        signature: Option<String>,
+        json: String,
+    },
+}
+
+impl PartialBlock {
+    fn finish(self) -> Option<CachedBlock> {
+        Some(match self {
+            Self::Text(text) => CachedBlock::Text { text },
+            Self::Thinking {
+                text,
+                signature,
+                id,
+                encrypted_content,
+            } => CachedBlock::Thinking {
+                text,
+                signature,
+                id,
+                encrypted_content,
+            },
+            Self::ToolUse {
+                id,
+                name,
+                signature,
+                json,
+            } => CachedBlock::ToolUse {
+                id,
+                name,
+                signature,
+                input: if json.trim().is_empty() {
+                    serde_json::Value::Object(serde_json::Map::new())
+                } else {
+                    serde_json::from_str(&json).ok()?
+                },
+            },
+        })
+    }
+}
+
+/// Accumulates one stream. Only a stream that reached its stop event without
+/// an error yields a reply; a cut-off or failed stream is never cached.
+#[derive(Debug, Default)]
+pub struct StreamRecorder {
+    model: String,
+    blocks: BTreeMap<u32, PartialBlock>,
+    stopped: bool,
+    stop_reason: Option<String>,
+    failed: bool,
+}
+
+impl StreamRecorder {
+    pub fn record(&mut self, event: &CanonicalEvent) {
+        match event {
+            CanonicalEvent::MessageStart { model, .. } => model.clone_into(&mut self.model),
+            CanonicalEvent::ContentBlockStart { index, block } => {
+                let partial = match block {
+                    ContentBlockKind::Text => PartialBlock::Text(String::new()),
+                    ContentBlockKind::Thinking { id, signature } => PartialBlock::Thinking {
+                        text: String::new(),
+                        signature: signature.clone(),
+                        id: id.clone(),
+                        encrypted_content: None,
+                    },
+                    ContentBlockKind::ToolUse {
+                        id,
+                        name,
+                        signature,
+                    } => PartialBlock::ToolUse {
+                        id: id.clone(),
+                        name: name.clone(),
+                        signature: signature.clone(),
+                        json: String::new(),
+                    },
+                };
+                self.blocks.insert(*index, partial);
+            },
+            CanonicalEvent::TextDelta { index, text } => {
+                if let Some(PartialBlock::Text(buf)) = self.blocks.get_mut(index) {
+                    buf.push_str(text);
+                }
+            },
+            CanonicalEvent::ThinkingDelta { index, text } => {
+                if let Some(PartialBlock::Thinking { text: buf, .. }) = self.blocks.get_mut(index) {
+                    buf.push_str(text);
+                }
+            },
+            CanonicalEvent::SignatureDelta { index, signature } => {
+                if let Some(PartialBlock::Thinking { signature: sig, .. }) =
+                    self.blocks.get_mut(index)
+                {
+                    sig.get_or_insert_with(String::new).push_str(signature);
+                }
+            },
+            CanonicalEvent::EncryptedContentDelta { index, data } => {
+                if let Some(PartialBlock::Thinking {
+                    encrypted_content, ..
+                }) = self.blocks.get_mut(index)
+                {
+                    encrypted_content
+                        .get_or_insert_with(String::new)
+                        .push_str(data);
+                }
+            },
+            CanonicalEvent::ToolUseDelta {
+                index,
+                partial_json,
+            } => {
+                if let Some(PartialBlock::ToolUse { json, .. }) = self.blocks.get_mut(index) {
+                    json.push_str(partial_json);
+                }
+            },
+            CanonicalEvent::MessageStop { stop_reason, .. } => {
+                self.stopped = true;
+                self.stop_reason = stop_reason.map(|r| r.anthropic_str().to_owned());
+            },
+            CanonicalEvent::Error(_) => self.failed = true,
+            CanonicalEvent::ContentBlockStop { .. } | CanonicalEvent::UsageDelta(_) => {},
+        }
+    }
+
+    pub const fn fail(&mut self) {
+        self.failed = true;
+    }
+
+    #[must_use]
+    pub fn finish(self) -> Option<CachedResponse> {
+        if self.failed || !self.stopped {
+            return None;
+        }
+        let content = self
+            .blocks
+            .into_values()
+            .map(PartialBlock::finish)
+            .collect::<Option<Vec<_>>>()?;
+        Some(CachedResponse {
+            model: self.model,
+            content,
+            stop_reason: self.stop_reason,
+        })
+    }
+}
@@ -0,0 +1,212 @@
+//! The stored form of a cached reply, and the conversions in and out of it.
+//!
+//! Core's canonical response types are not serializable, so an entry is kept
+//! as a [`CachedResponse`]: the text, thinking and tool-use blocks a reply is
+//! made of, plus its model and stop reason. Usage is deliberately not kept; a
+//! replayed reply reports zero tokens, which is what makes a hit cost nothing.
+
+use serde::{Deserialize, Serialize};
+use systemprompt::models::wire::canonical::{
+    CanonicalContent, CanonicalEvent, CanonicalResponse, CanonicalStopReason, ContentBlockKind,
+};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum CachedBlock {
+    Text {
+        text: String,
+    },
+    Thinking {
+        text: String,
+        signature: Option<String>,
+        id: Option<String>,
+        encrypted_content: Option<String>,
+    },
+    ToolUse {
+        id: String,
+        name: String,
+        input: serde_json::Value,
+        signature: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CachedResponse {
+    pub model: String,
+    pub content: Vec<CachedBlock>,
+    /// In Anthropic's spelling, which round-trips every canonical reason.
+    pub stop_reason: Option<String>,
+}
+
+impl CachedResponse {
+    /// Capture a reply for the cache. Replies carrying anything the cache
+    /// cannot reproduce (images, grounding, code execution) are not cached.
+    #[must_use]
+    pub fn from_canonical(response: &CanonicalResponse) -> Option<Self> {
+        if response.grounding.is_some() || response.code_execution.is_some() {
+            return None;
+        }
+        let content = response
+            .content
+            .iter()
+            .map(|part| match part {
+                CanonicalContent::Text(text) => Some(CachedBlock::Text { text: text.clone() }),
+                CanonicalContent::Thinking {
+                    text,
+                    signature,
+                    id,
+                    encrypted_content,
+                } => Some(CachedBlock::Thinking {
+                    text: text.clone(),
+                    signature: signature.clone(),
+                    id: id.clone(),
+                    encrypted_content: encrypted_content.clone(),
+                }),
+                CanonicalContent::ToolUse {
+                    id,
+                    name,
+                    input,
+                    signature,
+                } => Some(CachedBlock::ToolUse {
+                    id: id.clone(),
+                    name: name.clone(),
+                    input: input.clone(),
+                    signature: signature.clone(),
+                }),
+                CanonicalContent::Image(_) | CanonicalContent::ToolResult { .. } => None,
+            })
+            .collect::<Option<Vec<_>>>()?;
+        Some(Self {
+            model: response.model.clone(),
+            content,
+            stop_reason: response.stop_reason.map(|r| r.anthropic_str().to_owned()),
+        })
+    }
+
+    fn stop_reason(&self) -> Option<CanonicalStopReason> {
+        self.stop_reason
+            .as_deref()
+            .map(CanonicalStopReason::from_anthropic)
+    }
+
+    /// The reply as a buffered response with zero usage.
+    #[must_use]
+    pub fn to_canonical(&self, id: &str) -> CanonicalResponse {
+        let content = self
+            .content
+            .iter()
+            .map(|block| match block {
+                CachedBlock::Text { text } => CanonicalContent::Text(text.clone()),
+                CachedBlock::Thinking {
+                    text,
+                    signature,
+                    id,
+                    encrypted_content,
+                } => CanonicalContent::Thinking {
+                    text: text.clone(),
+                    signature: signature.clone(),
+                    id: id.clone(),
+                    encrypted_content: encrypted_content.clone(),
+                },
+                CachedBlock::ToolUse {
+                    id,
+                    name,
+                    input,
+                    signature,
+                } => CanonicalContent::ToolUse {
+                    id: id.clone(),
+                    name: name.clone(),
+                    input: input.clone(),
+                    signature: signature.clone(),
+                },
+            })
+            .collect();
+        CanonicalResponse {
+            id: id.to_owned(),
+            model: self.model.clone(),
+            content,
+            stop_reason: self.stop_reason(),
+            ..CanonicalResponse::default()
+        }
+    }
+
+    /// The reply as the event sequence a streaming upstream would have sent,
+    /// one delta per block, with zero usage.
+    #[must_use]
+    pub fn to_events(&self, id: &str) -> Vec<CanonicalEvent> {
+        let mut events = vec![CanonicalEvent::MessageStart {
+            id: id.to_owned(),
+            model: self.model.clone(),
+            usage: systemprompt::models::wire::canonical::CanonicalUsage::default(),
+        }];
+        for (index, block) in (0u32..).zip(&self.content) {
+            match block {
+                CachedBlock::Text { text } => {
+                    events.push(CanonicalEvent::ContentBlockStart {
+                        index,
+                        block: ContentBlockKind::Text,
+                    });
+                    events.push(CanonicalEvent::TextDelta {
+                        index,
+                        text: text.clone(),
+                    });
+                },
+                CachedBlock::Thinking {
+                    text,
+                    signature,
+                    id,
+                    encrypted_content,
+                } => {
+                    events.push(CanonicalEvent::ContentBlockStart {
+                        index,
+                        block: ContentBlockKind::Thinking {
+                            id: id.clone(),
+                            signature: None,
+                        },
+                    });
+                    events.push(CanonicalEvent::ThinkingDelta {
+                        index,
+                        text: text.clone(),
+                    });
+                    if let Some(signature) = signature {
+                        events.push(CanonicalEvent::SignatureDelta {
+                            index,
+                            signature: signature.clone(),
+                        });
+                    }
+                    if let Some(data) = encrypted_content {
+                        events.push(CanonicalEvent::EncryptedContentDelta {
+                            index,
+                            data: data.clone(),
+                        });
+                    }
+                },
+                CachedBlock::ToolUse {
+                    id,
+                    name,
+                    input,
+                    signature,
+                } => {
+                    events.push(CanonicalEvent::ContentBlockStart {
+                        index,
+                        block: ContentBlockKind::ToolUse {
+                            id: id.clone(),
+                            name: name.clone(),
+                            signature: signature.clone(),
+                        },
+                    });
+                    events.push(CanonicalEvent::ToolUseDelta {
+                        index,
+                        partial_json: input.to_string(),
+                    });
+                },
+            }
+            events.push(CanonicalEvent::ContentBlockStop { index });
+        }
+        events.push(CanonicalEvent::MessageStop {
+            id: id.to_owned(),
+            stop_reason: self.stop_reason(),
+        });
+        events
+    }
+}
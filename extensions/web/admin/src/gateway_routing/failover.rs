@@ -0,0 +1,150 @@
+//! Retrying a failed upstream call on the route's next healthy target.
+//!
+//! [`FailoverOutbound`] sits between the response cache and core's adapter
+//! for each wire. A request on a route with targets that comes back with a
+//! 429, a 5xx or a transport error is sent once more, to the first other
+//! healthy target, through core's adapter for that target's wire. Core only
+//! sees the outcome: the reply if the retry succeeded, otherwise the first
+//! target's error, which is the one it attributes to the provider it chose.
+//! The failure core does not see is recorded for target health.
+
+use std::sync::Arc;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use systemprompt::api::services::gateway::protocol::outbound::anthropic::AnthropicOutbound;
+use systemprompt::api::services::gateway::protocol::outbound::gemini::GeminiOutbound;
+use systemprompt::api::services::gateway::protocol::outbound::openai_chat::OpenAiChatOutbound;
+use systemprompt::api::services::gateway::protocol::outbound::openai_responses::OpenAiResponsesOutbound;
+use systemprompt::api::services::gateway::protocol::outbound::{PreparedBody, UpstreamError};
+use systemprompt::api::services::gateway::{OutboundAdapter, OutboundCtx, OutboundOutcome};
+use systemprompt::config::{ProfileBootstrap, SecretsBootstrap};
+use systemprompt::models::profile::{GatewayRoute, WireProtocol};
+use systemprompt::models::wire::canonical::CanonicalRequest;
+
+use super::select::{current_target, failover_target, route_for_target, target_is_healthy};
+use super::served::{ServedBy, record_failure};
+use super::{route_targets, unhealthy_upstreams};
+use crate::types::GatewayRouteTarget;
+
+/// Whether another upstream could have answered where this one failed: a
+/// 429, any 5xx, or a transport failure. Anything else, a 400 included,
+/// would fail the same way on every target.
+#[must_use]
+pub fn is_retryable(err: &anyhow::Error) -> bool {
+    match err.downcast_ref::<UpstreamError>() {
+        Some(UpstreamError::Status { status, .. }) => *status == 429 || *status >= 500,
+        Some(UpstreamError::Transport { .. }) => true,
+        None => false,
+    }
+}
+
+/// A built-in outbound adapter that fails over between a route's targets.
+pub struct FailoverOutbound {
+    inner: Arc<dyn OutboundAdapter>,
+}
+
+impl std::fmt::Debug for FailoverOutbound {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FailoverOutbound").finish_non_exhaustive()
+    }
+}
+
+impl FailoverOutbound {
+    #[must_use]
+    pub fn new(inner: Arc<dyn OutboundAdapter>) -> Self {
+        Self { inner }
+    }
+}
+
+// Why: the retry may cross wires, and the registry's adapter for a tag is
+// this wrapper again; core's own adapter sends it exactly once.
+fn core_adapter(wire: WireProtocol) -> Arc<dyn OutboundAdapter> {
+    match wire {
+        WireProtocol::Anthropic => Arc::new(AnthropicOutbound),
+        WireProtocol::OpenAiChat => Arc::new(OpenAiChatOutbound),
+        WireProtocol::OpenAiResponses => Arc::new(OpenAiResponsesOutbound),
+        WireProtocol::Gemini => Arc::new(GeminiOutbound),
+    }
+}
+
+async fn send_to_target(
+    matched: &GatewayRoute,
+    request: &CanonicalRequest,
+    forward_headers: &[(String, String)],
+    target: &GatewayRouteTarget,
+) -> Result<OutboundOutcome> {
+    let route = route_for_target(matched, target);
+    let profile = ProfileBootstrap::get()?;
+    let provider = route.resolve(&profile.providers).ok_or_else(|| {
+        anyhow::anyhow!(
+            "target '{}' provider '{}' is not declared",
+            target.id,
+            target.provider
+        )
+    })?;
+    let api_key = SecretsBootstrap::get()?
+        .get(provider.api_key_secret.as_str())
+        .ok_or_else(|| anyhow::anyhow!("target '{}' has no API key secret", target.id))?;
+    let upstream_model = route.effective_upstream_model(&request.model).to_owned();
+    let retry = OutboundCtx {
+        route: &route,
+        endpoint: &provider.endpoint,
+        api_key,
+        request,
+        upstream_model: &upstream_model,
+        model_limits: provider.find_model(&upstream_model).map(|m| m.limits),
+        forward_headers,
+        raw_body: None,
+    };
+    let adapter = core_adapter(provider.wire);
+    let body = adapter.build_body(&retry)?;
+    adapter.send(retry, &body).await
+}
+
+#[async_trait]
+impl OutboundAdapter for FailoverOutbound {
+    fn build_body(&self, ctx: &OutboundCtx<'_>) -> Result<PreparedBody> {
+        self.inner.build_body(ctx)
+    }
+
+    async fn send(&self, ctx: OutboundCtx<'_>, body: &PreparedBody) -> Result<OutboundOutcome> {
+        let Ok(config) = route_targets().await else {
+            return self.inner.send(ctx, body).await;
+        };
+        let targets = config.targets_for(ctx.route.id.as_str());
+        let Some(first) = current_target(targets, ctx.route) else {
+            return self.inner.send(ctx, body).await;
+        };
+        let (matched, request, forward_headers) = (ctx.route, ctx.request, ctx.forward_headers);
+        let route_id = matched.id.as_str().to_owned();
+        let requested_model = request.model.as_str();
+        let error = match self.inner.send(ctx, body).await {
+            Ok(outcome) => return Ok(ServedBy::new(route_id, first).record(outcome).await),
+            Err(e) if is_retryable(&e) => e,
+            Err(e) => return Err(e),
+        };
+
+        let unhealthy = unhealthy_upstreams(config.health).await;
+        let Some(next) = failover_target(targets, first, |t| {
+            target_is_healthy(&unhealthy, t, requested_model)
+        }) else {
+            return Err(error);
+        };
+        tracing::info!(route = %route_id, failed = %first.id, target = %next.id, error = %error, "gateway target failed over");
+        match send_to_target(matched, request, forward_headers, next).await {
+            Ok(outcome) => {
+                record_failure(first, requested_model, &error).await;
+                Ok(ServedBy::new(route_id, next).record(outcome).await)
+            },
+            Err(retry_error) => {
+                if is_retryable(&retry_error) {
+                    record_failure(next, requested_model, &retry_error).await;
+                } else {
+                    tracing::warn!(route = %route_id, target = %next.id, error = %retry_error, "gateway target retry failed");
+                }
+                Err(error)
+            },
+        }
+    }
+}
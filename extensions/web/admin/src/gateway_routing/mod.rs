@@ -0,0 +1,177 @@
+//! Gateway [`RouteSelector`] that spreads a route across weighted upstream
+//! targets and fails over between them.
+//!
+//! Core matches a request to one profile route, first match wins. When that
+//! route has targets in `services/gateway/targets.yaml`, [`TargetSelector`]
+//! (registered as `targets`) swaps in the chosen target's provider and
+//! upstream model. Healthy weighted targets split traffic by weight, which is
+//! how a canary takes 5% of a model; when they are all unhealthy the first
+//! healthy target in list order takes over. Core then dispatches to the
+//! returned route and records that provider and model on the `ai_requests`
+//! row, with `route_match = "selector:targets"`.
+//!
+//! A selector cannot retry, so [`FailoverOutbound`], wrapped around core's
+//! adapter for each wire, does: a call to a target that fails with a 429, a
+//! 5xx or a transport error is sent once more to the first other healthy
+//! target. The `ai_requests` row keeps the selector's provider and model, so
+//! the target that actually answered is recorded against the request in
+//! `gateway_request_targets`.
+//!
+//! Health comes from that same table, plus the failures a retry hid from it:
+//! an upstream that returned 429s, 5xxs or timed out `failure_threshold`
+//! times in the window sits out until the cooldown passes without another
+//! failure. The Model Selection page reads health through [`load_unhealthy`]
+//! and [`target_is_healthy`], so it shows what the selector acts on.
+//!
+//! Selectors are built by `inventory` with no context, so the pool arrives
+//! through [`install`] when the web extension builds its router. Until then,
+//! or if the failure query errors, every target counts as healthy. The
+//! targets file and the health view are cached briefly so a request never
+//! pays for a file read and a query.
+
+mod failover;
+mod select;
+mod served;
+
+use std::sync::{Arc, LazyLock, OnceLock};
+use std::time::{Duration, Instant};
+
+use rand::Rng;
+use sqlx::PgPool;
+use systemprompt::ai::{RouteSelector, RouteSelectorError, register_route_selector};
+use systemprompt::config::ProfileBootstrap;
+use systemprompt::models::profile::GatewayRoute;
+use systemprompt::models::wire::canonical::CanonicalRequest;
+use tokio::sync::RwLock;
+
+pub use failover::{FailoverOutbound, is_retryable};
+pub use select::{
+    UnhealthyUpstreams, choose_target, current_target, failover_target, route_for_target,
+    target_is_healthy,
+};
+
+use crate::repositories::analytics::upstream_health::list_upstream_failures;
+use crate::repositories::config::gateway::{
+    RouteTargetsConfig, TargetHealthSettings, load_route_targets,
+};
+
+const SELECTOR_NAME: &str = "targets";
+const TARGETS_TTL: Duration = Duration::from_secs(5);
+const HEALTH_TTL: Duration = Duration::from_secs(10);
+
+type Cached<T> = LazyLock<RwLock<Option<(Arc<T>, Instant)>>>;
+
+static POOL: OnceLock<Arc<PgPool>> = OnceLock::new();
+static TARGETS_CACHE: Cached<RouteTargetsConfig> = LazyLock::new(|| RwLock::new(None));
+static HEALTH_CACHE: Cached<UnhealthyUpstreams> = LazyLock::new(|| RwLock::new(None));
+
+/// Give the selector the pool it reads target health from. Later calls are
+/// ignored.
+pub fn install(pool: Arc<PgPool>) {
+    POOL.get_or_init(|| pool);
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TargetSelector;
+
+impl TargetSelector {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+async fn cached<T: Send + Sync>(cache: &Cached<T>, ttl: Duration) -> Option<Arc<T>> {
+    cache
+        .read()
+        .await
+        .as_ref()
+        .filter(|(_, at)| at.elapsed() < ttl)
+        .map(|(value, _)| Arc::clone(value))
+}
+
+async fn store<T: Send + Sync>(cache: &Cached<T>, value: T) -> Arc<T> {
+    let value = Arc::new(value);
+    *cache.write().await = Some((Arc::clone(&value), Instant::now()));
+    value
+}
+
+fn failed(err: &dyn std::fmt::Display) -> RouteSelectorError {
+    RouteSelectorError::Failed {
+        name: SELECTOR_NAME,
+        message: err.to_string(),
+    }
+}
+
+async fn route_targets() -> Result<Arc<RouteTargetsConfig>, RouteSelectorError> {
+    if let Some(hit) = cached(&TARGETS_CACHE, TARGETS_TTL).await {
+        return Ok(hit);
+    }
+    let services_path = ProfileBootstrap::get()
+        .map_err(|e| failed(&e))?
+        .paths
+        .services
+        .clone();
+    let config = load_route_targets(services_path.as_ref()).map_err(|e| failed(&e))?;
+    Ok(store(&TARGETS_CACHE, config).await)
+}
+
+/// The upstreams out of rotation right now, read fresh. If the failure query
+/// errors every target counts as healthy.
+pub async fn load_unhealthy(pool: &PgPool, settings: TargetHealthSettings) -> UnhealthyUpstreams {
+    let now = chrono::Utc::now();
+    let window = chrono::Duration::seconds(i64::try_from(settings.window_secs).unwrap_or(i64::MAX));
+    match list_upstream_failures(pool, now - window).await {
+        Ok(rows) => UnhealthyUpstreams::from_failures(&rows, settings, now),
+        Err(e) => {
+            tracing::warn!(error = %e, "Failed to read upstream failures; treating targets as healthy");
+            UnhealthyUpstreams::default()
+        },
+    }
+}
+
+async fn unhealthy_upstreams(settings: TargetHealthSettings) -> Arc<UnhealthyUpstreams> {
+    if let Some(hit) = cached(&HEALTH_CACHE, HEALTH_TTL).await {
+        return hit;
+    }
+    let Some(pool) = POOL.get() else {
+        return Arc::new(UnhealthyUpstreams::default());
+    };
+    store(&HEALTH_CACHE, load_unhealthy(pool, settings).await).await
+}
+
+async fn forget_health() {
+    *HEALTH_CACHE.write().await = None;
+}
+
+#[async_trait::async_trait]
+impl RouteSelector for TargetSelector {
+    fn name(&self) -> &'static str {
+        SELECTOR_NAME
+    }
+
+    async fn refine(
+        &self,
+        matched: &GatewayRoute,
+        request: &CanonicalRequest,
+    ) -> Result<Option<GatewayRoute>, RouteSelectorError> {
+        let config = route_targets().await?;
+        let targets = config.targets_for(matched.id.as_str());
+        if targets.is_empty() {
+            return Ok(None);
+        }
+        let unhealthy = unhealthy_upstreams(config.health).await;
+        let roll = rand::rng().random::<u64>();
+        let Some(target) = choose_target(
+            targets,
+            |t| target_is_healthy(&unhealthy, t, &request.model),
+            roll,
+        ) else {
+            return Ok(None);
+        };
+        tracing::debug!(route = %matched.id, target = %target.id, "gateway target chosen");
+        Ok(Some(route_for_target(matched, target)))
+    }
+}
+
+register_route_selector!(TargetSelector::new, name = "targets");
@@ -0,0 +1,137 @@
+//! Target choice and health, free of I/O so the policy can be tested alone.
+
+use std::collections::HashSet;
+
+use chrono::{DateTime, Duration, Utc};
+
+use systemprompt::identifiers::ProviderId;
+use systemprompt::models::profile::GatewayRoute;
+
+use crate::repositories::analytics::upstream_health::UpstreamFailureRow;
+use crate::repositories::config::gateway::TargetHealthSettings;
+use crate::types::GatewayRouteTarget;
+
+/// The `(provider, model)` pairs currently held out of rotation.
+#[derive(Debug, Clone, Default)]
+pub struct UnhealthyUpstreams(HashSet<(String, String)>);
+
+impl UnhealthyUpstreams {
+    /// Mark every upstream that reached `failure_threshold` failures in the
+    /// window and whose latest failure is still inside the cooldown.
+    #[must_use]
+    pub fn from_failures(
+        rows: &[UpstreamFailureRow],
+        settings: TargetHealthSettings,
+        now: DateTime<Utc>,
+    ) -> Self {
+        let cooldown = Duration::seconds(i64::try_from(settings.cooldown_secs).unwrap_or(i64::MAX));
+        Self(
+            rows.iter()
+                .filter(|r| r.failures >= i64::from(settings.failure_threshold.max(1)))
+                .filter(|r| now - r.last_failure_at < cooldown)
+                .map(|r| (r.provider.clone(), r.model.clone()))
+                .collect(),
+        )
+    }
+
+    #[must_use]
+    pub fn is_healthy(&self, provider: &str, model: &str) -> bool {
+        !self.0.contains(&(provider.to_owned(), model.to_owned()))
+    }
+}
+
+/// Whether `target` is in rotation for a request for `requested_model`.
+///
+/// A target without its own upstream model sends the requested one, so that
+/// is the model its health is read under.
+#[must_use]
+pub fn target_is_healthy(
+    unhealthy: &UnhealthyUpstreams,
+    target: &GatewayRouteTarget,
+    requested_model: &str,
+) -> bool {
+    let model = target.upstream_model.as_deref().unwrap_or(requested_model);
+    unhealthy.is_healthy(&target.provider, model)
+}
+
+/// Pick the target for one request.
+///
+/// Healthy weighted targets share traffic in proportion to `weight`, with
+/// `roll` as the random draw. When none is healthy the first healthy target
+/// in list order takes over, zero-weight fallbacks included. When every
+/// target is unhealthy the first listed is used anyway: a degraded upstream
+/// still beats failing the request before it is sent.
+#[must_use]
+pub fn choose_target(
+    targets: &[GatewayRouteTarget],
+    is_healthy: impl Fn(&GatewayRouteTarget) -> bool,
+    roll: u64,
+) -> Option<&GatewayRouteTarget> {
+    let weighted: Vec<&GatewayRouteTarget> = targets
+        .iter()
+        .filter(|t| t.weight > 0 && is_healthy(t))
+        .collect();
+    let total: u64 = weighted.iter().map(|t| u64::from(t.weight)).sum();
+    if total > 0 {
+        let mut point = roll % total;
+        for target in &weighted {
+            let weight = u64::from(target.weight);
+            if point < weight {
+                return Some(target);
+            }
+            point -= weight;
+        }
+    }
+    targets
+        .iter()
+        .find(|t| is_healthy(t))
+        .or_else(|| targets.first())
+}
+
+/// Pick the target to retry a request on after `failed` returned a
+/// retryable error.
+///
+/// That is the first other healthy target in list order, zero-weight
+/// fallbacks included. Unlike [`choose_target`] there is no last resort: with
+/// no healthy target left the original error stands.
+#[must_use]
+pub fn failover_target<'a>(
+    targets: &'a [GatewayRouteTarget],
+    failed: &GatewayRouteTarget,
+    is_healthy: impl Fn(&GatewayRouteTarget) -> bool,
+) -> Option<&'a GatewayRouteTarget> {
+    targets.iter().find(|t| t.id != failed.id && is_healthy(t))
+}
+
+/// The target [`route_for_target`] swapped into `route`.
+///
+/// It is recognised by its provider and upstream model. Ids are not carried
+/// on the route, so two targets with the same provider and model resolve to
+/// the first.
+#[must_use]
+pub fn current_target<'a>(
+    targets: &'a [GatewayRouteTarget],
+    route: &GatewayRoute,
+) -> Option<&'a GatewayRouteTarget> {
+    targets
+        .iter()
+        .find(|t| t.provider == route.provider.as_str() && t.upstream_model == route.upstream_model)
+}
+
+/// `route` sending to `target` instead of its own provider and model.
+#[must_use]
+pub fn route_for_target(route: &GatewayRoute, target: &GatewayRouteTarget) -> GatewayRoute {
+    let mut swapped = route.clone();
+    if swapped.provider.as_str() != target.provider {
+        // Why: headers and pricing on the profile route describe its own
+        // provider; carrying them to another vendor would send stray
+        // headers and mis-cost the request.
+        swapped.extra_headers.clear();
+        swapped.pricing = None;
+    } else if swapped.upstream_model != target.upstream_model {
+        swapped.pricing = None;
+    }
+    swapped.provider = ProviderId::new(target.provider.clone());
+    swapped.upstream_model.clone_from(&target.upstream_model);
+    swapped
+}
@@ -0,0 +1,126 @@
+//! Recording which target served a request, and the failures core never
+//! sees.
+//!
+//! The adapter has the upstream's response id but not the request id, so it
+//! stores the one against the target and the schema binds it to the request
+//! when core stores the reply. A buffered reply is recorded before it is
+//! returned. A stream is recorded from its opening event, and the stream does
+//! not end until that write has finished, since core stores the reply only
+//! once it has. A raw passthrough stream is never parsed and goes unrecorded.
+
+use std::sync::{Arc, Mutex, PoisonError};
+
+use futures_util::stream::{self, BoxStream, StreamExt};
+use systemprompt::api::services::gateway::OutboundOutcome;
+use systemprompt::models::wire::canonical::CanonicalEvent;
+use tokio::task::JoinHandle;
+
+use super::{POOL, forget_health};
+use crate::repositories::gateway_targets::{
+    delete_stale_target_rows, insert_target_failure, insert_target_response,
+};
+use crate::types::GatewayRouteTarget;
+
+pub(super) struct ServedBy {
+    route_id: String,
+    target_id: String,
+}
+
+impl ServedBy {
+    pub(super) fn new(route_id: String, target: &GatewayRouteTarget) -> Self {
+        Self {
+            route_id,
+            target_id: target.id.clone(),
+        }
+    }
+
+    pub(super) async fn record(self, outcome: OutboundOutcome) -> OutboundOutcome {
+        match outcome {
+            OutboundOutcome::Buffered(response) => {
+                self.save(response.id.clone()).await;
+                OutboundOutcome::Buffered(response)
+            },
+            OutboundOutcome::RawBuffered {
+                body,
+                content_type,
+                canonical,
+            } => {
+                self.save(canonical.id.clone()).await;
+                OutboundOutcome::RawBuffered {
+                    body,
+                    content_type,
+                    canonical,
+                }
+            },
+            OutboundOutcome::Streaming(upstream) => {
+                OutboundOutcome::Streaming(self.record_stream(upstream))
+            },
+            raw @ OutboundOutcome::RawStreaming { .. } => raw,
+        }
+    }
+
+    async fn save(self, response_id: String) {
+        let Some(pool) = POOL.get() else {
+            return;
+        };
+        if let Err(e) =
+            insert_target_response(pool, &response_id, &self.route_id, &self.target_id).await
+        {
+            tracing::warn!(error = %e, target = %self.target_id, "gateway target record failed");
+            return;
+        }
+        if let Err(e) = delete_stale_target_rows(pool).await {
+            tracing::warn!(error = %e, "gateway target sweep failed");
+        }
+    }
+
+    fn record_stream(
+        self,
+        upstream: BoxStream<'static, Result<CanonicalEvent, String>>,
+    ) -> BoxStream<'static, Result<CanonicalEvent, String>> {
+        let pending: Arc<Mutex<Option<Self>>> = Arc::new(Mutex::new(Some(self)));
+        let write: Arc<Mutex<Option<JoinHandle<()>>>> = Arc::new(Mutex::new(None));
+        let tap = Arc::clone(&write);
+        let tapped = upstream.inspect(move |item| {
+            let Ok(CanonicalEvent::MessageStart { id, .. }) = item else {
+                return;
+            };
+            let Some(served) = pending
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .take()
+            else {
+                return;
+            };
+            let handle = tokio::spawn(served.save(id.clone()));
+            *tap.lock().unwrap_or_else(PoisonError::into_inner) = Some(handle);
+        });
+        let tail = stream::once(async move {
+            let handle = write.lock().unwrap_or_else(PoisonError::into_inner).take();
+            if let Some(handle) = handle
+                && let Err(e) = handle.await
+            {
+                tracing::warn!(error = %e, "gateway target record task failed");
+            }
+        })
+        .filter_map(|()| std::future::ready(None));
+        tapped.chain(tail).boxed()
+    }
+}
+
+// Why: core puts only the error it is handed on the request row; dropping
+// the cached health view lets the next request count this one too.
+pub(super) async fn record_failure(
+    target: &GatewayRouteTarget,
+    requested_model: &str,
+    error: &anyhow::Error,
+) {
+    let Some(pool) = POOL.get() else {
+        return;
+    };
+    let model = target.upstream_model.as_deref().unwrap_or(requested_model);
+    if let Err(e) = insert_target_failure(pool, &target.provider, model, &error.to_string()).await {
+        tracing::warn!(error = %e, target = %target.id, "gateway target failure record failed");
+    }
+    forget_health().await;
+}
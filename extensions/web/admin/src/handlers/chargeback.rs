@@ -0,0 +1,117 @@
+//! HTTP handlers for chargeback CSV export and closing a month.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use axum::Json;
+use axum::extract::{Extension, Path, Query, State};
+use axum::http::header;
+use axum::response::{IntoResponse, Response};
+use chrono::{NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use systemprompt::config::ProfileBootstrap;
+
+use crate::chargeback::{self, csv};
+use crate::error::{AdminError, AdminResult};
+use crate::repositories::config::cost_centers::{CostCenters, load_cost_centers};
+use crate::types::UserContext;
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct ExportQuery {
+    department: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct CloseResponse {
+    pub month: String,
+    pub lines: usize,
+    pub gross_usd: String,
+    pub credit_usd: String,
+}
+
+pub(crate) fn cost_centers() -> AdminResult<CostCenters> {
+    let services_path = PathBuf::from(&ProfileBootstrap::get()?.paths.services);
+    Ok(load_cost_centers(&services_path)?)
+}
+
+fn month(value: &str) -> AdminResult<NaiveDate> {
+    chargeback::parse_month(value)
+        .ok_or_else(|| AdminError::BadRequest(format!("'{value}' is not a month (YYYY-MM)")))
+}
+
+// Why: department names are free text; keep the download name to characters
+// every browser and file system accepts.
+fn file_name(month: NaiveDate, department: Option<&str>) -> String {
+    let mut name = format!("chargeback-{}", month.format("%Y-%m"));
+    if let Some(d) = department {
+        name.push('-');
+        name.extend(
+            d.chars()
+                .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' }),
+        );
+    }
+    name.push_str(".csv");
+    name
+}
+
+pub(crate) async fn export_chargeback_csv_handler(
+    State(pool): State<Arc<PgPool>>,
+    Path(month_param): Path<String>,
+    Query(query): Query<ExportQuery>,
+) -> AdminResult<Response> {
+    let month_start = month(&month_param)?;
+    let mut loaded = chargeback::load_month(&pool, &cost_centers()?, month_start).await?;
+    let department = query.department.as_deref().filter(|d| !d.is_empty());
+    if let Some(d) = department {
+        loaded.lines.retain(|l| l.department == d);
+    }
+    let body = csv::render_csv(month_start, &loaded.lines);
+    let disposition = format!(
+        "attachment; filename=\"{}\"",
+        file_name(month_start, department)
+    );
+    Ok((
+        [
+            (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_owned()),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        body,
+    )
+        .into_response())
+}
+
+pub(crate) async fn close_chargeback_period_handler(
+    State(pool): State<Arc<PgPool>>,
+    Extension(user_ctx): Extension<UserContext>,
+    Path(month_param): Path<String>,
+) -> AdminResult<Json<CloseResponse>> {
+    let month_start = month(&month_param)?;
+    if !chargeback::has_ended(month_start, Utc::now().date_naive()) {
+        return Err(AdminError::BadRequest(
+            "Only a month that has ended can be closed".to_owned(),
+        ));
+    }
+    let centers = cost_centers()?;
+    if !chargeback::close_month(&pool, &centers, month_start, &user_ctx.user_id).await? {
+        return Err(AdminError::Conflict(format!(
+            "{} is already closed",
+            month_start.format("%Y-%m")
+        )));
+    }
+    let closed = chargeback::load_month(&pool, &centers, month_start).await?;
+    let gross: i64 = closed.lines.iter().map(|l| l.gross_microdollars).sum();
+    let credit: i64 = closed.lines.iter().map(|l| l.credit_microdollars).sum();
+    tracing::info!(
+        month = %month_start.format("%Y-%m"),
+        lines = closed.lines.len(),
+        closed_by = %user_ctx.user_id,
+        "chargeback period closed"
+    );
+    Ok(Json(CloseResponse {
+        month: month_start.format("%Y-%m").to_string(),
+        lines: closed.lines.len(),
+        gross_usd: csv::usd(gross),
+        credit_usd: csv::usd(credit),
+    }))
+}
@@ -0,0 +1,167 @@
+//! HTTP handlers for erasing a user on request, exporting what we hold about
+//! them, and reading back erasure certificates.
+//!
+//! An erasure is refused while a legal hold in
+//! `services/governance/retention.yaml` names the user or one of their
+//! sessions. `dry_run` counts what would be touched and changes nothing.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use axum::Json;
+use axum::extract::{Extension, Path, State};
+use axum::http::header;
+use axum::response::{IntoResponse, Response};
+use chrono::Utc;
+use serde::Deserialize;
+use sqlx::PgPool;
+use systemprompt::config::{ProfileBootstrap, SecretsBootstrap};
+use systemprompt::identifiers::UserId;
+
+use crate::activity::{self, ActivityEntity, NewActivity};
+use crate::erasure::{self, ErasureReport, ErasureRequest};
+use crate::error::{AdminError, AdminResult};
+use crate::repositories::config::retention::load_retention_config;
+use crate::repositories::erasure::export::get_user_export;
+use crate::repositories::erasure::{
+    ErasureCertificate, find_erasure_certificate, find_held_user_session, list_erasure_certificates,
+};
+use crate::types::UserContext;
+use systemprompt_web_access::repositories::users::find_user_identity;
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct EraseUserBody {
+    reason: String,
+    #[serde(default)]
+    dry_run: bool,
+}
+
+async fn ensure_not_held(pool: &PgPool, user_id: &UserId) -> AdminResult<()> {
+    let services_path = PathBuf::from(&ProfileBootstrap::get()?.paths.services);
+    let config = load_retention_config(&services_path)?;
+    if let Some(reason) = erasure::hold_reason(&config.legal_holds, user_id) {
+        return Err(AdminError::Conflict(format!(
+            "User is under legal hold ({reason})"
+        )));
+    }
+    let holds = crate::retention::LegalHolds::from_config(&config.legal_holds);
+    if let Some(session) = find_held_user_session(pool, user_id, &holds.sessions).await? {
+        return Err(AdminError::Conflict(format!(
+            "Session {session} of this user is under legal hold"
+        )));
+    }
+    Ok(())
+}
+
+pub(crate) async fn erase_user_handler(
+    State(pool): State<Arc<PgPool>>,
+    Extension(user_ctx): Extension<UserContext>,
+    Path(user_id_raw): Path<String>,
+    Json(body): Json<EraseUserBody>,
+) -> AdminResult<Json<ErasureReport>> {
+    let user_id = UserId::new(user_id_raw);
+    let reason = body.reason.trim();
+    if reason.is_empty() {
+        return Err(AdminError::BadRequest(
+            "An erasure needs a reason for its certificate".to_owned(),
+        ));
+    }
+    if user_id == user_ctx.user_id {
+        return Err(AdminError::BadRequest(
+            "Admins cannot erase their own account".to_owned(),
+        ));
+    }
+    if find_user_identity(&pool, &user_id).await?.is_none() {
+        return Err(AdminError::NotFound("User not found".to_owned()));
+    }
+    ensure_not_held(&pool, &user_id).await?;
+
+    let secret = SecretsBootstrap::manifest_signing_secret_seed().map_err(AdminError::internal)?;
+    let report = erasure::erase(
+        &pool,
+        &secret,
+        ErasureRequest {
+            user_id: &user_id,
+            requested_by: &user_ctx.user_id,
+            reason,
+        },
+        !body.dry_run,
+    )
+    .await?;
+
+    if let Some(certificate_id) = &report.certificate_id {
+        tracing::info!(
+            certificate_id = %certificate_id,
+            subject_hash = %report.subject_hash,
+            requested_by = %user_ctx.user_id,
+            "user erased"
+        );
+        let p = Arc::clone(&pool);
+        let uid = user_ctx.user_id.clone();
+        let subject = report.subject_hash.clone();
+        tokio::spawn(async move {
+            activity::record(
+                &p,
+                NewActivity::entity_deleted(&uid, ActivityEntity::User, &subject, &subject),
+            )
+            .await;
+        });
+    }
+    Ok(Json(report))
+}
+
+// Why: user ids are free text from identity providers; keep the download
+// name to characters every browser and file system accepts.
+fn file_name(user_id: &UserId) -> String {
+    let safe: String = user_id
+        .as_str()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    format!("data-export-{safe}.zip")
+}
+
+pub(crate) async fn export_user_data_handler(
+    State(pool): State<Arc<PgPool>>,
+    Extension(user_ctx): Extension<UserContext>,
+    Path(user_id_raw): Path<String>,
+) -> AdminResult<Response> {
+    let user_id = UserId::new(user_id_raw);
+    if find_user_identity(&pool, &user_id).await?.is_none() {
+        return Err(AdminError::NotFound("User not found".to_owned()));
+    }
+    let export = get_user_export(&pool, &user_id).await?;
+    let body =
+        erasure::export_archive(&export, &user_id, Utc::now()).map_err(AdminError::internal)?;
+    tracing::info!(
+        user_id = %user_id,
+        requested_by = %user_ctx.user_id,
+        bytes = body.len(),
+        "data subject export generated"
+    );
+    let disposition = format!("attachment; filename=\"{}\"", file_name(&user_id));
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/zip".to_owned()),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        body,
+    )
+        .into_response())
+}
+
+pub(crate) async fn list_erasure_certificates_handler(
+    State(pool): State<Arc<PgPool>>,
+) -> AdminResult<Json<Vec<ErasureCertificate>>> {
+    Ok(Json(list_erasure_certificates(&pool).await?))
+}
+
+pub(crate) async fn get_erasure_certificate_handler(
+    State(pool): State<Arc<PgPool>>,
+    Path(id): Path<String>,
+) -> AdminResult<Json<ErasureCertificate>> {
+    find_erasure_certificate(&pool, &id)
+        .await?
+        .map(Json)
+        .ok_or_else(|| AdminError::NotFound("Erasure certificate not found".to_owned()))
+}
@@ -1,69 +1,205 @@
 //! HTTP handlers for gateway route configuration.
+//!
+//! A route's definition lives in the profile YAML and its upstream targets in
+//! `services/gateway/targets.yaml`; these handlers keep the two in step, so a
+//! route that is renamed or deleted takes its targets with it.
+//!
+//! Every write is versioned through [`gateway_config::apply`]: the response
+//! carries the new `ETag`, and a request whose `If-Match` names an older one
+//! is refused with 412 rather than editing a route that may have moved. One
+//! without `If-Match` is refused with 428.
+
+use std::path::Path as FsPath;
+use std::sync::Arc;
 
 use axum::Json;
-use axum::extract::Path;
-use axum::http::StatusCode;
+use axum::extract::{Extension, Path, State};
+use axum::http::{HeaderMap, HeaderValue, StatusCode, header};
 use axum::response::{IntoResponse, Response};
 use serde::Serialize;
+use sqlx::PgPool;
 
 use crate::error::{AdminError, AdminResult};
 use crate::handlers::shared;
 use crate::repositories;
-use crate::types::{GatewayRouteView, ReorderRoutesRequest, UpdateGatewaySettingsRequest};
+use crate::repositories::config::gateway::{load_route_targets, set_route_targets};
+use crate::services::gateway_config::{self, GatewayChange};
+use crate::types::{
+    GatewayConfigView, GatewayRouteView, ReorderRoutesRequest, UpdateGatewaySettingsRequest,
+    UserContext,
+};
 
 #[derive(Debug, Serialize)]
 pub(crate) struct CreateRouteResponse {
     pub index: usize,
 }
 
+fn with_targets(mut config: GatewayConfigView) -> AdminResult<GatewayConfigView> {
+    let targets = load_route_targets(&shared::get_services_path()?)?;
+    for route in &mut config.routes {
+        route.targets = targets.targets_for(&route.id).to_vec();
+    }
+    Ok(config)
+}
+
+fn route_id_at(profile_path: &FsPath, idx: usize) -> AdminResult<Option<String>> {
+    let config = repositories::config::gateway::get_gateway_config(profile_path)?;
+    Ok(config.routes.into_iter().nth(idx).map(|r| r.id))
+}
+
+fn with_route_id(mut route: GatewayRouteView) -> GatewayRouteView {
+    if route.id.trim().is_empty() {
+        route.id = repositories::config::gateway::synthesize_route_id(
+            &route.model_pattern,
+            &route.provider,
+        );
+    }
+    route
+}
+
+pub(crate) fn if_match(headers: &HeaderMap) -> Option<&str> {
+    headers.get(header::IF_MATCH).and_then(|v| v.to_str().ok())
+}
+
+pub(crate) fn with_etag(mut response: Response, etag: &str) -> Response {
+    if let Ok(value) = HeaderValue::from_str(&format!("\"{etag}\"")) {
+        response.headers_mut().insert(header::ETAG, value);
+    }
+    response
+}
+
 pub(crate) async fn get_gateway_handler() -> AdminResult<Response> {
     let profile_path = shared::get_profile_path()?;
-    let config = repositories::config::gateway::get_gateway_config(&profile_path)
+    let mut config = repositories::config::gateway::get_gateway_config(&profile_path)
         .map_err(AdminError::internal)?;
-    Ok(Json(config).into_response())
+    config.etag = gateway_config::current_etag()?;
+    let etag = config.etag.clone();
+    Ok(with_etag(
+        Json(with_targets(config)?).into_response(),
+        &etag,
+    ))
 }
 
 pub(crate) async fn update_gateway_settings_handler(
+    State(pool): State<Arc<PgPool>>,
+    Extension(user_ctx): Extension<UserContext>,
+    headers: HeaderMap,
     Json(body): Json<UpdateGatewaySettingsRequest>,
 ) -> AdminResult<Response> {
-    let profile_path = shared::get_profile_path()?;
-    let config = repositories::config::gateway::update_gateway_settings(&profile_path, &body)?;
-    Ok(Json(config).into_response())
+    let change = GatewayChange {
+        actor: &user_ctx.user_id,
+        if_match: if_match(&headers),
+        action: "settings",
+        summary: "Updated gateway settings",
+    };
+    let (mut config, etag) = gateway_config::apply(&pool, change, |profile, _| {
+        Ok(repositories::config::gateway::update_gateway_settings(
+            profile, &body,
+        )?)
+    })
+    .await?;
+    config.etag.clone_from(&etag);
+    Ok(with_etag(
+        Json(with_targets(config)?).into_response(),
+        &etag,
+    ))
 }
 
 pub(crate) async fn create_gateway_route_handler(
+    State(pool): State<Arc<PgPool>>,
+    Extension(user_ctx): Extension<UserContext>,
+    headers: HeaderMap,
     Json(body): Json<GatewayRouteView>,
 ) -> AdminResult<Response> {
-    let profile_path = shared::get_profile_path()?;
-    let index = repositories::config::gateway::create_route(&profile_path, &body)?;
-    Ok((StatusCode::CREATED, Json(CreateRouteResponse { index })).into_response())
+    let route = with_route_id(body);
+    let summary = format!("Created route {}", route.id);
+    let change = GatewayChange {
+        actor: &user_ctx.user_id,
+        if_match: if_match(&headers),
+        action: "create_route",
+        summary: &summary,
+    };
+    let (index, etag) = gateway_config::apply(&pool, change, |profile, services| {
+        let index = repositories::config::gateway::create_route(profile, &route)?;
+        set_route_targets(services, &route.id, None, &route.targets)?;
+        Ok(index)
+    })
+    .await?;
+    let response = (StatusCode::CREATED, Json(CreateRouteResponse { index })).into_response();
+    Ok(with_etag(response, &etag))
 }
 
 pub(crate) async fn update_gateway_route_handler(
+    State(pool): State<Arc<PgPool>>,
+    Extension(user_ctx): Extension<UserContext>,
+    headers: HeaderMap,
     Path(idx): Path<usize>,
     Json(body): Json<GatewayRouteView>,
 ) -> AdminResult<Response> {
-    let profile_path = shared::get_profile_path()?;
-    if repositories::config::gateway::update_route(&profile_path, idx, &body)? {
-        Ok(StatusCode::NO_CONTENT.into_response())
-    } else {
-        Err(AdminError::NotFound("Route not found".to_owned()))
-    }
+    let route = with_route_id(body);
+    let summary = format!("Updated route {}", route.id);
+    let change = GatewayChange {
+        actor: &user_ctx.user_id,
+        if_match: if_match(&headers),
+        action: "update_route",
+        summary: &summary,
+    };
+    let ((), etag) = gateway_config::apply(&pool, change, |profile, services| {
+        let previous_id = route_id_at(profile, idx)?;
+        if !repositories::config::gateway::update_route(profile, idx, &route)? {
+            return Err(AdminError::NotFound("Route not found".to_owned()));
+        }
+        set_route_targets(services, &route.id, previous_id.as_deref(), &route.targets)?;
+        Ok(())
+    })
+    .await?;
+    Ok(with_etag(StatusCode::NO_CONTENT.into_response(), &etag))
 }
 
-pub(crate) async fn delete_gateway_route_handler(Path(idx): Path<usize>) -> AdminResult<Response> {
-    let profile_path = shared::get_profile_path()?;
-    if repositories::config::gateway::delete_route(&profile_path, idx)? {
-        Ok(StatusCode::NO_CONTENT.into_response())
-    } else {
-        Err(AdminError::NotFound("Route not found".to_owned()))
-    }
+pub(crate) async fn delete_gateway_route_handler(
+    State(pool): State<Arc<PgPool>>,
+    Extension(user_ctx): Extension<UserContext>,
+    headers: HeaderMap,
+    Path(idx): Path<usize>,
+) -> AdminResult<Response> {
+    let change = GatewayChange {
+        actor: &user_ctx.user_id,
+        if_match: if_match(&headers),
+        action: "delete_route",
+        summary: "Deleted route",
+    };
+    let ((), etag) = gateway_config::apply_described(&pool, change, |profile, services| {
+        let Some(route_id) = route_id_at(profile, idx)? else {
+            return Err(AdminError::NotFound("Route not found".to_owned()));
+        };
+        if !repositories::config::gateway::delete_route(profile, idx)? {
+            return Err(AdminError::NotFound("Route not found".to_owned()));
+        }
+        set_route_targets(services, &route_id, None, &[])?;
+        Ok(((), format!("Deleted route {route_id}")))
+    })
+    .await?;
+    Ok(with_etag(StatusCode::NO_CONTENT.into_response(), &etag))
 }
 
 pub(crate) async fn reorder_gateway_routes_handler(
+    State(pool): State<Arc<PgPool>>,
+    Extension(user_ctx): Extension<UserContext>,
+    headers: HeaderMap,
     Json(body): Json<ReorderRoutesRequest>,
 ) -> AdminResult<Response> {
-    let profile_path = shared::get_profile_path()?;
-    repositories::config::gateway::reorder_routes(&profile_path, &body.order)?;
-    Ok(StatusCode::NO_CONTENT.into_response())
+    let change = GatewayChange {
+        actor: &user_ctx.user_id,
+        if_match: if_match(&headers),
+        action: "reorder_routes",
+        summary: "Reordered routes",
+    };
+    let ((), etag) = gateway_config::apply(&pool, change, |profile, _| {
+        Ok(repositories::config::gateway::reorder_routes(
+            profile,
+            &body.order,
+        )?)
+    })
+    .await?;
+    Ok(with_etag(StatusCode::NO_CONTENT.into_response(), &etag))
 }
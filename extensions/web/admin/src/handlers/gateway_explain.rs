@@ -0,0 +1,53 @@
+//! `GET /gateway/explain` — the route explainer as JSON.
+//!
+//! Takes `model` and either `user_id` or `token` (a personal access token's
+//! prefix, or the whole token).
+
+use std::sync::Arc;
+
+use axum::Json;
+use axum::extract::{Query, State};
+use serde::Deserialize;
+use sqlx::PgPool;
+use systemprompt::identifiers::UserId;
+
+use crate::error::{AdminError, AdminResult};
+use crate::services::route_explain::{self, ExplainFor};
+use crate::types::RouteExplanation;
+
+#[derive(Debug, Default, Deserialize)]
+pub(crate) struct ExplainQuery {
+    #[serde(default)]
+    pub model: String,
+    pub user_id: Option<UserId>,
+    pub token: Option<String>,
+}
+
+impl ExplainQuery {
+    pub(crate) fn subject(&self) -> Option<ExplainFor> {
+        let token = self
+            .token
+            .as_deref()
+            .map(str::trim)
+            .filter(|t| !t.is_empty());
+        match (token, &self.user_id) {
+            (Some(token), _) => Some(ExplainFor::Token(token.to_owned())),
+            (None, Some(user_id)) if !user_id.as_str().is_empty() => {
+                Some(ExplainFor::User(user_id.clone()))
+            },
+            _ => None,
+        }
+    }
+}
+
+pub(crate) async fn explain_handler(
+    State(pool): State<Arc<PgPool>>,
+    Query(query): Query<ExplainQuery>,
+) -> AdminResult<Json<RouteExplanation>> {
+    let who = query
+        .subject()
+        .ok_or_else(|| AdminError::BadRequest("Pass user_id or token".to_owned()))?;
+    Ok(Json(
+        route_explain::explain(&pool, &query.model, who).await?,
+    ))
+}
@@ -0,0 +1,76 @@
+//! HTTP handlers for gateway configuration history and rollback.
+
+use std::sync::Arc;
+
+use axum::Json;
+use axum::extract::{Extension, Path, State};
+use axum::http::HeaderMap;
+use axum::response::{IntoResponse, Response};
+use serde::Serialize;
+use sqlx::PgPool;
+
+use crate::error::{AdminError, AdminResult};
+use crate::handlers::gateway::{if_match, with_etag};
+use crate::repositories::config::gateway_revisions::{
+    find_gateway_revision, find_previous_gateway_revision, list_gateway_revisions,
+};
+use crate::services::gateway_config;
+use crate::types::{GatewayRevision, GatewayRevisionSummary, UserContext};
+use crate::util::line_diff::{DiffRow, side_by_side};
+
+const HISTORY_LIMIT: i64 = 200;
+
+#[derive(Debug, Serialize)]
+pub(crate) struct RevisionDiff {
+    pub revision: GatewayRevision,
+    pub previous_id: Option<i64>,
+    pub gateway: Vec<DiffRow>,
+    pub targets: Vec<DiffRow>,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct RollbackResponse {
+    pub etag: String,
+}
+
+// Why: the first revision has nothing before it, so it is diffed against an
+// empty configuration and reads as all additions.
+pub(crate) async fn load_revision_diff(pool: &PgPool, id: i64) -> AdminResult<RevisionDiff> {
+    let revision = find_gateway_revision(pool, id)
+        .await?
+        .ok_or_else(|| AdminError::NotFound(format!("Revision #{id} not found")))?;
+    let previous = find_previous_gateway_revision(pool, id).await?;
+    let (old_gateway, old_targets) = previous.as_ref().map_or(("", ""), |p| {
+        (p.gateway_yaml.as_str(), p.targets_yaml.as_str())
+    });
+    Ok(RevisionDiff {
+        gateway: side_by_side(old_gateway, &revision.gateway_yaml),
+        targets: side_by_side(old_targets, &revision.targets_yaml),
+        previous_id: previous.map(|p| p.id),
+        revision,
+    })
+}
+
+pub(crate) async fn list_gateway_revisions_handler(
+    State(pool): State<Arc<PgPool>>,
+) -> AdminResult<Json<Vec<GatewayRevisionSummary>>> {
+    Ok(Json(list_gateway_revisions(&pool, HISTORY_LIMIT).await?))
+}
+
+pub(crate) async fn get_gateway_revision_handler(
+    State(pool): State<Arc<PgPool>>,
+    Path(id): Path<i64>,
+) -> AdminResult<Json<RevisionDiff>> {
+    Ok(Json(load_revision_diff(&pool, id).await?))
+}
+
+pub(crate) async fn rollback_gateway_revision_handler(
+    State(pool): State<Arc<PgPool>>,
+    Extension(user_ctx): Extension<UserContext>,
+    headers: HeaderMap,
+    Path(id): Path<i64>,
+) -> AdminResult<Response> {
+    let etag = gateway_config::rollback(&pool, &user_ctx.user_id, if_match(&headers), id).await?;
+    let response = Json(RollbackResponse { etag: etag.clone() }).into_response();
+    Ok(with_etag(response, &etag))
+}
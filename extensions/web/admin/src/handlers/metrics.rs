@@ -0,0 +1,38 @@
+//! `GET /metrics`: the Prometheus scrape target.
+//!
+//! A scraper presents a personal access token as a static bearer, checked by
+//! the same [`authenticate_admin_token`] an identity provider's is for SCIM.
+//! The exposition carries spend and policy outcomes for the whole deployment,
+//! so the token's owner must hold `admin`. A disabled endpoint answers 404
+//! before the token is looked at.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::extract::State;
+use axum::http::{HeaderMap, header};
+use axum::response::{IntoResponse, Response};
+use sqlx::PgPool;
+use systemprompt::config::ProfileBootstrap;
+
+use crate::error::{AdminError, AdminResult};
+use crate::metrics::{self, exposition};
+use crate::repositories;
+use crate::services::auth::authenticate_admin_token;
+
+pub(crate) async fn metrics_handler(
+    State(pool): State<Arc<PgPool>>,
+    headers: HeaderMap,
+) -> AdminResult<Response> {
+    let services_path = PathBuf::from(&ProfileBootstrap::get()?.paths.services);
+    let config = repositories::config::metrics::load_metrics_endpoint_config(&services_path)?;
+    if !config.enabled {
+        return Err(AdminError::NotFound(
+            "The metrics endpoint is not enabled".to_owned(),
+        ));
+    }
+    authenticate_admin_token::<AdminError>(&pool, &headers).await?;
+    let body = metrics::render_metrics(&pool, Duration::from_secs(config.refresh_secs)).await?;
+    Ok(([(header::CONTENT_TYPE, exposition::CONTENT_TYPE)], body).into_response())
+}
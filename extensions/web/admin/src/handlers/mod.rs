@@ -5,20 +5,26 @@
 
 pub(crate) mod access_control;
 pub(crate) mod access_tokens;
+pub(crate) mod chargeback;
 pub(crate) mod demo_register;
 pub(crate) mod departments;
 pub(crate) mod entity_access;
+pub(crate) mod erasure;
 pub(crate) mod gateway;
 pub(crate) mod gateway_access;
 pub(crate) mod gateway_catalog;
+pub(crate) mod gateway_explain;
+pub(crate) mod gateway_revisions;
 pub(crate) mod hooks_track;
 mod jobs;
 pub(crate) mod magic_link;
+pub(crate) mod metrics;
 mod plugins;
 mod plugins_env;
 pub(crate) mod public_register;
 pub(crate) mod resources;
 pub(crate) mod responses;
+pub(crate) mod scim;
 pub(crate) mod secrets;
 pub(crate) mod share;
 pub(crate) mod shared;
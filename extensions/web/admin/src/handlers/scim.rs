@@ -0,0 +1,195 @@
+//! HTTP handlers for SCIM 2.0 provisioning under `/scim/v2`.
+//!
+//! Each handler checks the feature switch and the caller's token before
+//! touching the body, so a disabled integration or a bad token never learns
+//! whether a resource exists.
+
+use std::sync::Arc;
+
+use axum::Json;
+use axum::extract::{Path, Query, State};
+use axum::http::{HeaderMap, HeaderValue, StatusCode, header};
+use axum::response::{IntoResponse, Response};
+use serde::Serialize;
+use sqlx::PgPool;
+use systemprompt::identifiers::UserId;
+
+use crate::repositories::config::scim::ScimConfig;
+use crate::services::auth::authenticate_admin_token;
+use crate::services::scim::resources::ScimPage;
+use crate::services::scim::{ScimError, auth, groups, users};
+use crate::types::scim::{
+    SCHEMA_SERVICE_PROVIDER_CONFIG, SCIM_CONTENT_TYPE, ScimGroupInput, ScimListQuery,
+    ScimPatchRequest, ScimUserInput,
+};
+
+type ScimResult = Result<Response, ScimError>;
+
+fn scim_json<T: Serialize>(status: StatusCode, body: &T) -> Response {
+    let mut response = (status, Json(body)).into_response();
+    response.headers_mut().insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static(SCIM_CONTENT_TYPE),
+    );
+    response
+}
+
+async fn admit(pool: &PgPool, headers: &HeaderMap) -> Result<(ScimConfig, UserId), ScimError> {
+    let config = auth::load_enabled_config()?;
+    let actor = authenticate_admin_token::<ScimError>(pool, headers).await?;
+    Ok((config, actor))
+}
+
+pub(crate) async fn service_provider_config_handler(
+    State(pool): State<Arc<PgPool>>,
+    headers: HeaderMap,
+) -> ScimResult {
+    admit(&pool, &headers).await?;
+    let body = serde_json::json!({
+        "schemas": [SCHEMA_SERVICE_PROVIDER_CONFIG],
+        "patch": { "supported": true },
+        "bulk": { "supported": false, "maxOperations": 0, "maxPayloadSize": 0 },
+        "filter": { "supported": true, "maxResults": 500 },
+        "changePassword": { "supported": false },
+        "sort": { "supported": false },
+        "etag": { "supported": false },
+        "authenticationSchemes": [{
+            "type": "oauthbearertoken",
+            "name": "Personal access token",
+            "description": "Authorization: Bearer sp-live-… issued to an admin account",
+        }],
+    });
+    Ok(scim_json(StatusCode::OK, &body))
+}
+
+pub(crate) async fn list_users_handler(
+    State(pool): State<Arc<PgPool>>,
+    headers: HeaderMap,
+    Query(query): Query<ScimListQuery>,
+) -> ScimResult {
+    admit(&pool, &headers).await?;
+    let page = ScimPage::from_query(&query)?;
+    Ok(scim_json(
+        StatusCode::OK,
+        &users::list_users(&pool, &page).await?,
+    ))
+}
+
+pub(crate) async fn create_user_handler(
+    State(pool): State<Arc<PgPool>>,
+    headers: HeaderMap,
+    Json(body): Json<ScimUserInput>,
+) -> ScimResult {
+    let (config, actor) = admit(&pool, &headers).await?;
+    let user = users::create_user(&pool, &config, &actor, &body).await?;
+    Ok(scim_json(StatusCode::CREATED, &user))
+}
+
+pub(crate) async fn get_user_handler(
+    State(pool): State<Arc<PgPool>>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> ScimResult {
+    admit(&pool, &headers).await?;
+    let user = users::get_user(&pool, &UserId::new(id)).await?;
+    Ok(scim_json(StatusCode::OK, &user))
+}
+
+pub(crate) async fn replace_user_handler(
+    State(pool): State<Arc<PgPool>>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+    Json(body): Json<ScimUserInput>,
+) -> ScimResult {
+    let (_, actor) = admit(&pool, &headers).await?;
+    let user = users::replace_user(&pool, &actor, &UserId::new(id), &body).await?;
+    Ok(scim_json(StatusCode::OK, &user))
+}
+
+pub(crate) async fn patch_user_handler(
+    State(pool): State<Arc<PgPool>>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+    Json(body): Json<ScimPatchRequest>,
+) -> ScimResult {
+    let (_, actor) = admit(&pool, &headers).await?;
+    let user = users::patch_user(&pool, &actor, &UserId::new(id), &body.operations).await?;
+    Ok(scim_json(StatusCode::OK, &user))
+}
+
+pub(crate) async fn delete_user_handler(
+    State(pool): State<Arc<PgPool>>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> ScimResult {
+    let (_, actor) = admit(&pool, &headers).await?;
+    users::deactivate_user(&pool, &actor, &UserId::new(id)).await?;
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
+
+pub(crate) async fn list_groups_handler(
+    State(pool): State<Arc<PgPool>>,
+    headers: HeaderMap,
+    Query(query): Query<ScimListQuery>,
+) -> ScimResult {
+    admit(&pool, &headers).await?;
+    let page = ScimPage::from_query(&query)?;
+    Ok(scim_json(
+        StatusCode::OK,
+        &groups::list_groups(&pool, &page).await?,
+    ))
+}
+
+pub(crate) async fn create_group_handler(
+    State(pool): State<Arc<PgPool>>,
+    headers: HeaderMap,
+    Json(body): Json<ScimGroupInput>,
+) -> ScimResult {
+    let (config, _) = admit(&pool, &headers).await?;
+    let group = groups::create_group(&pool, &config, &body).await?;
+    Ok(scim_json(StatusCode::CREATED, &group))
+}
+
+pub(crate) async fn get_group_handler(
+    State(pool): State<Arc<PgPool>>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> ScimResult {
+    admit(&pool, &headers).await?;
+    Ok(scim_json(
+        StatusCode::OK,
+        &groups::get_group(&pool, &id).await?,
+    ))
+}
+
+pub(crate) async fn replace_group_handler(
+    State(pool): State<Arc<PgPool>>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+    Json(body): Json<ScimGroupInput>,
+) -> ScimResult {
+    let (config, _) = admit(&pool, &headers).await?;
+    let group = groups::replace_group(&pool, &config, &id, &body).await?;
+    Ok(scim_json(StatusCode::OK, &group))
+}
+
+pub(crate) async fn patch_group_handler(
+    State(pool): State<Arc<PgPool>>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+    Json(body): Json<ScimPatchRequest>,
+) -> ScimResult {
+    let (config, _) = admit(&pool, &headers).await?;
+    let group = groups::patch_group(&pool, &config, &id, &body.operations).await?;
+    Ok(scim_json(StatusCode::OK, &group))
+}
+
+pub(crate) async fn delete_group_handler(
+    State(pool): State<Arc<PgPool>>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> ScimResult {
+    let (config, _) = admit(&pool, &headers).await?;
+    groups::delete_group(&pool, &config, &id).await?;
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
@@ -1,5 +1,5 @@
-//! HTTP handlers for secret storage, rotation, and short-lived resolution
-//! tokens.
+//! HTTP handlers for secret storage, rotation, metadata, and short-lived
+//! resolution tokens.
 
 use std::sync::Arc;
 
@@ -7,12 +7,14 @@ use axum::Json;
 use axum::extract::{Path, Query, State};
 use axum::http::HeaderMap;
 use axum::response::{IntoResponse, Response};
+use chrono::{DateTime, Utc};
 use sqlx::PgPool;
 
 use systemprompt::identifiers::UserId;
 
 use crate::error::AdminResult;
 use crate::handlers::users::extract_user_from_cookie;
+use crate::repositories::secrets::secret_lifecycle::SecretMetadata;
 use crate::services::auth::validate_plugin_jwt;
 use crate::services::secret_service;
 
@@ -28,6 +30,14 @@ pub(crate) struct ResolveQuery {
     token: String,
 }
 
+#[derive(serde::Deserialize, Debug)]
+pub(crate) struct SecretMetadataRequest {
+    #[serde(default)]
+    owner_id: Option<String>,
+    #[serde(default)]
+    expires_at: Option<DateTime<Utc>>,
+}
+
 pub(crate) async fn create_resolution_token_handler(
     State(pool): State<Arc<PgPool>>,
     Path(plugin_id): Path<String>,
@@ -82,3 +92,22 @@ pub(crate) async fn rotate_handler(
     secret_service::rotate_user_keys(&pool, &session.user_id, &plugin_id).await?;
     Ok(Json(ResultOkResponse { result: "ok" }).into_response())
 }
+
+pub(crate) async fn update_metadata_handler(
+    State(pool): State<Arc<PgPool>>,
+    Path((plugin_id, var_name)): Path<(String, String)>,
+    headers: HeaderMap,
+    Json(body): Json<SecretMetadataRequest>,
+) -> AdminResult<Response> {
+    let session = extract_user_from_cookie(&headers)?;
+    let metadata = SecretMetadata {
+        owner_id: body
+            .owner_id
+            .map(|owner| owner.trim().to_owned())
+            .filter(|owner| !owner.is_empty()),
+        expires_at: body.expires_at,
+    };
+    secret_service::set_secret_metadata(&pool, &session.user_id, &plugin_id, &var_name, &metadata)
+        .await?;
+    Ok(Json(ResultOkResponse { result: "ok" }).into_response())
+}
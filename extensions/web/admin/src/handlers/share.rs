@@ -16,7 +16,6 @@ use axum::response::{IntoResponse, Response};
 use axum::{Extension, Json};
 use base64::Engine;
 use serde::Serialize;
-use sha2::{Digest, Sha256};
 use sqlx::PgPool;
 use systemprompt::config::SecretsBootstrap;
 use systemprompt::identifiers::UserId;
@@ -25,32 +24,11 @@ use crate::error::{AdminError, AdminResult};
 use crate::handlers::shared;
 use crate::repositories;
 use crate::types::UserContext;
+use crate::util::hmac::hmac_sha256;
 
 fn sign(secret: &[u8], user_id: &UserId, version: i32) -> String {
     let payload = format!("{user_id}:{version}");
-    let mut padded = [0u8; 64];
-    if secret.len() > 64 {
-        let mut h = Sha256::new();
-        h.update(secret);
-        let digest = h.finalize();
-        padded[..32].copy_from_slice(&digest);
-    } else {
-        padded[..secret.len()].copy_from_slice(secret);
-    }
-    let mut ipad = [0x36u8; 64];
-    let mut opad = [0x5cu8; 64];
-    for i in 0..64 {
-        ipad[i] ^= padded[i];
-        opad[i] ^= padded[i];
-    }
-    let mut inner = Sha256::new();
-    inner.update(ipad);
-    inner.update(payload.as_bytes());
-    let inner_digest = inner.finalize();
-    let mut outer = Sha256::new();
-    outer.update(opad);
-    outer.update(inner_digest);
-    let mac = outer.finalize();
+    let mac = hmac_sha256(secret, payload.as_bytes());
 
     let b64 = base64::engine::general_purpose::URL_SAFE_NO_PAD;
     let uid_b64 = b64.encode(user_id.as_str().as_bytes());
@@ -16,6 +16,7 @@ pub(crate) struct CurrentUser<'a> {
     username: &'a str,
     roles: &'a [String],
     is_admin: bool,
+    can_audit: bool,
 }
 
 impl<'a> From<&'a UserContext> for CurrentUser<'a> {
@@ -25,6 +26,7 @@ impl<'a> From<&'a UserContext> for CurrentUser<'a> {
             username: &ctx.username,
             roles: &ctx.roles,
             is_admin: ctx.is_admin,
+            can_audit: ctx.can_audit(),
         }
     }
 }
@@ -19,12 +19,17 @@ mod ssr_access_control;
 mod ssr_add_passkey;
 pub(crate) mod ssr_analytics_requests;
 mod ssr_chain;
+mod ssr_chargeback;
+mod ssr_cohorts;
 mod ssr_context_detail;
 mod ssr_conversations_raw;
+mod ssr_cost_watch;
 mod ssr_demo_help;
 mod ssr_demo_register;
 mod ssr_demo_trace;
 mod ssr_evals;
+mod ssr_gateway_explain;
+mod ssr_gateway_history;
 mod ssr_governance;
 mod ssr_governance_audit_detail;
 mod ssr_governance_decisions;
@@ -36,6 +41,8 @@ mod ssr_models;
 mod ssr_perf_trace_detail;
 mod ssr_perf_traces;
 mod ssr_profile;
+mod ssr_retention;
+mod ssr_search;
 mod ssr_search_resolve;
 mod ssr_session_detail;
 mod ssr_sessions_list;
@@ -49,13 +56,18 @@ pub(crate) use ssr_access_control::access_control_page;
 pub(crate) use ssr_add_passkey::add_passkey_page;
 pub(crate) use ssr_analytics_requests::analytics_requests_page;
 pub(crate) use ssr_chain::chain_envelope;
+pub(crate) use ssr_chargeback::{chargeback_page, chargeback_statement_page};
+pub(crate) use ssr_cohorts::cohorts_page;
 pub(crate) use ssr_context_detail::context_detail_page;
 pub(crate) use ssr_conversations_raw::conversations_raw;
+pub(crate) use ssr_cost_watch::cost_watch_page;
 pub(crate) use ssr_demo_register::demo_register_page;
 pub(crate) use ssr_demo_trace::demo_trace_page;
 pub(crate) use ssr_evals::{
     eval_promote_case_action, eval_run_action, eval_run_detail_page, evals_page,
 };
+pub(crate) use ssr_gateway_explain::gateway_explain_page;
+pub(crate) use ssr_gateway_history::gateway_history_page;
 pub(crate) use ssr_governance::governance_page;
 pub(crate) use ssr_governance_audit_detail::governance_audit_detail_page;
 pub(crate) use ssr_governance_decisions::governance_decisions_page;
@@ -71,6 +83,8 @@ pub(crate) use ssr_models::models_page;
 pub(crate) use ssr_perf_trace_detail::perf_trace_detail_page;
 pub(crate) use ssr_perf_traces::perf_traces_page;
 pub(crate) use ssr_profile::profile_page;
+pub(crate) use ssr_retention::retention_page;
+pub(crate) use ssr_search::search_page;
 pub(crate) use ssr_search_resolve::search_resolve;
 pub(crate) use ssr_session_detail::session_detail_page;
 pub(crate) use ssr_sessions_list::sessions_list_page;
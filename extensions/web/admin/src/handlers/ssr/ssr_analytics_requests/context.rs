@@ -61,6 +61,7 @@ pub(super) struct AnalyticsRequestsPageContext {
     pub histogram: HistogramView,
     pub traffic_chart: ChartView,
     pub cost_chart: ChartView,
+    pub cache: ResponseCacheView,
     pub breakdown: BreakdownView,
     pub rows: Vec<RequestListRowView>,
     pub has_rows: bool,
@@ -133,6 +134,18 @@ pub(super) struct RequestStatsView {
     pub denied_session_rate_pct: String,
 }
 
+#[derive(Debug, Serialize)]
+pub(super) struct ResponseCacheView {
+    pub has_activity: bool,
+    pub lookups: i64,
+    pub hits: i64,
+    pub exact_hits: i64,
+    pub semantic_hits: i64,
+    pub hit_rate_pct: String,
+    pub saved_display: String,
+    pub live_entries: i64,
+}
+
 #[derive(Debug, Serialize)]
 pub(super) struct RequestListRowView {
     pub id: String,
@@ -17,6 +17,8 @@ use crate::repositories::analytics::requests::{
     BreakdownRow, RequestFilter, RequestPage, RequestRow, RequestSortSpec, list_requests_by_model,
     list_requests_by_provider, list_requests_by_status, list_requests_paged,
 };
+use crate::repositories::gateway_cache::{ResponseCacheStats, get_response_cache_stats};
+use crate::util::activity_scope::ActivityScope;
 use crate::util::time_range::{
     TimeRange, TimeRangePreset, TimeRangeQuery, count_requests_in_range, parse_time_range,
     preset_to_range,
@@ -65,6 +67,7 @@ pub(super) struct RequestsData {
     pub stats: RequestStats,
     pub hist: Vec<LatencyBucket>,
     pub series: Vec<TimeBucket>,
+    pub cache: ResponseCacheStats,
     pub breakdown: Vec<BreakdownRow>,
 }
 
@@ -100,7 +103,7 @@ pub(super) async fn load_requests_data(
 
     let (paged, stats_res) = tokio::join!(
         list_requests_paged(pool, filter, range, page),
-        get_request_stats(pool, range),
+        get_request_stats(pool, range, ActivityScope::default()),
     );
 
     let (rows, total_count) = paged.unwrap_or_else(|e| {
@@ -121,16 +124,21 @@ pub(super) async fn load_requests_data(
 
     match tab {
         RequestsTab::Overview => {
-            let (hist_res, series_res) = tokio::join!(
+            let (hist_res, series_res, cache_res) = tokio::join!(
                 list_latency_histogram(pool, range),
                 list_request_timeseries(pool, range),
+                get_response_cache_stats(pool, range),
             );
             data.hist = unwrap_or_empty(hist_res, "list_latency_histogram");
             data.series = unwrap_or_empty(series_res, "list_request_timeseries");
+            data.cache = cache_res.unwrap_or_else(|e| {
+                tracing::warn!(error = %e, "get_response_cache_stats failed");
+                ResponseCacheStats::default()
+            });
         },
         RequestsTab::Models => {
             data.breakdown = unwrap_or_empty(
-                list_requests_by_model(pool, range).await,
+                list_requests_by_model(pool, range, ActivityScope::default()).await,
                 "list_requests_by_model",
             );
         },
@@ -154,6 +154,7 @@ fn page_context(input: PageInput<'_>) -> AnalyticsRequestsPageContext {
         histogram: charts::histogram_view(&fetched.hist, &fetched.stats),
         traffic_chart: charts::traffic_chart(&fetched.series, &range),
         cost_chart: charts::cost_chart(&fetched.series, &range),
+        cache: view::cache_view(&fetched.cache),
         breakdown: view::breakdown_view(tab, &fetched.breakdown, query),
         rows: fetched.rows.iter().map(view::request_row_to_json).collect(),
         has_rows: !fetched.rows.is_empty(),
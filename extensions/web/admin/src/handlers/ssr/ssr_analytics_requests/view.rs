@@ -11,11 +11,12 @@ use crate::repositories::analytics::request_stats::RequestStats;
 use crate::repositories::analytics::requests::{
     BreakdownRow, RequestFilter, RequestRow, RequestSortColumn, RequestSortSpec, SortDir,
 };
+use crate::repositories::gateway_cache::ResponseCacheStats;
 use crate::util::time_range::TimeRange;
 
 use super::context::{
     BreakdownRowView, BreakdownView, RequestListRowView, RequestStatsView, RequestsTab,
-    TimeRangeView,
+    ResponseCacheView, TimeRangeView,
 };
 use super::urls::{log_filter_url, preserved_query_string};
 use super::{BASE_URL, RequestsQuery};
@@ -66,6 +67,25 @@ pub(super) fn stats_to_json(s: &RequestStats) -> RequestStatsView {
     }
 }
 
+pub(super) fn cache_view(s: &ResponseCacheStats) -> ResponseCacheView {
+    let hits = s.exact_hits + s.semantic_hits;
+    let hit_rate = if s.lookups > 0 {
+        hits as f64 / s.lookups as f64 * 100.0
+    } else {
+        0.0
+    };
+    ResponseCacheView {
+        has_activity: s.lookups > 0 || s.live_entries > 0,
+        lookups: s.lookups,
+        hits,
+        exact_hits: s.exact_hits,
+        semantic_hits: s.semantic_hits,
+        hit_rate_pct: format!("{hit_rate:.1}"),
+        saved_display: format_cost(s.saved_microdollars),
+        live_entries: s.live_entries,
+    }
+}
+
 // Why: share_pct is against the busiest row rather than the window total, so
 // the bars use the full width even when one dimension has a long tail. The
 // printed percentage is still share-of-total.
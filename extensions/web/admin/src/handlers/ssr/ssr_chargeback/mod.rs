@@ -0,0 +1,177 @@
+//! `/admin/chargeback` — one month's charges per department, with links to
+//! each department's statement and CSV, and closing the month.
+//!
+//! An open month is provisional and recomputed on every view; a closed one
+//! shows the snapshot it was closed with, and who closed it when.
+
+use std::collections::BTreeSet;
+use std::sync::Arc;
+
+use axum::extract::{Extension, Query, State};
+use axum::response::Response;
+use chrono::{NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+use super::format::{format_cost, local_time};
+use crate::chargeback::statement::{ChargeTotals, department_charges};
+use crate::chargeback::{self, parse_month};
+use crate::error::{AdminError, AdminHtmlResult};
+use crate::handlers::chargeback::cost_centers;
+use crate::repositories::chargeback::list_chargeback_periods;
+use crate::templates::AdminTemplateEngine;
+use crate::types::{MarketplaceContext, UserContext};
+
+mod statement;
+
+pub(crate) use statement::chargeback_statement_page;
+
+const MONTHS_SHOWN: u32 = 12;
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct ChargebackQuery {
+    month: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct MonthOptionView {
+    value: String,
+    label: String,
+    closed: bool,
+    selected: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub(super) struct TotalsView {
+    requests: i64,
+    gross: String,
+    credit: String,
+    net: String,
+}
+
+impl From<&ChargeTotals> for TotalsView {
+    fn from(t: &ChargeTotals) -> Self {
+        Self {
+            requests: t.requests,
+            gross: format_cost(t.gross_microdollars),
+            credit: format_cost(t.credit_microdollars),
+            net: format_cost(t.net_microdollars()),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct DepartmentChargeView {
+    department: String,
+    cost_centers: String,
+    totals: TotalsView,
+    statement_url: String,
+    csv_url: String,
+}
+
+#[derive(Debug, Serialize)]
+struct ChargebackContext {
+    page: &'static str,
+    title: &'static str,
+    hero_title: &'static str,
+    hero_subtitle: &'static str,
+    month: String,
+    month_label: String,
+    months: Vec<MonthOptionView>,
+    closed: bool,
+    closed_at: Option<String>,
+    closed_by: Option<String>,
+    can_close: bool,
+    departments: Vec<DepartmentChargeView>,
+    totals: TotalsView,
+    csv_url: String,
+}
+
+pub(super) fn month_label(month: NaiveDate) -> String {
+    month.format("%B %Y").to_string()
+}
+
+pub(crate) async fn chargeback_page(
+    Extension(user_ctx): Extension<UserContext>,
+    Extension(mkt_ctx): Extension<MarketplaceContext>,
+    Extension(engine): Extension<AdminTemplateEngine>,
+    State(pool): State<Arc<PgPool>>,
+    Query(params): Query<ChargebackQuery>,
+) -> AdminHtmlResult<Response> {
+    if !user_ctx.is_admin {
+        return Err(AdminError::Forbidden("Admin access required.".to_owned()).into());
+    }
+
+    let today = Utc::now().date_naive();
+    let recent = chargeback::recent_months(today, MONTHS_SHOWN);
+    // Why: default to last month, the one finance is usually invoicing.
+    let month_start = params
+        .month
+        .as_deref()
+        .and_then(parse_month)
+        .or_else(|| recent.get(1).copied())
+        .unwrap_or(today);
+    let loaded = chargeback::load_month(&pool, &cost_centers()?, month_start).await?;
+    let closed_months: BTreeSet<NaiveDate> = list_chargeback_periods(&pool)
+        .await?
+        .into_iter()
+        .map(|p| p.month_start)
+        .collect();
+
+    let month = month_start.format("%Y-%m").to_string();
+    let charges = department_charges(&loaded.lines);
+    let mut totals = ChargeTotals::default();
+    for c in &charges {
+        totals.requests += c.totals.requests;
+        totals.gross_microdollars += c.totals.gross_microdollars;
+        totals.credit_microdollars += c.totals.credit_microdollars;
+    }
+    let departments = charges
+        .iter()
+        .map(|c| {
+            let dept = urlencoding::encode(&c.department);
+            DepartmentChargeView {
+                department: c.department.clone(),
+                cost_centers: c.cost_centers.join(", "),
+                totals: TotalsView::from(&c.totals),
+                statement_url: format!(
+                    "/admin/chargeback/statement?month={month}&department={dept}"
+                ),
+                csv_url: format!("/api/public/admin/chargeback/{month}/export?department={dept}"),
+            }
+        })
+        .collect();
+
+    let ctx = ChargebackContext {
+        page: "chargeback",
+        title: "Chargeback",
+        hero_title: "Chargeback",
+        hero_subtitle: "Monthly spend per department and cost center, with eval and judge traffic credited back.",
+        month_label: month_label(month_start),
+        months: recent
+            .iter()
+            .map(|m| MonthOptionView {
+                value: m.format("%Y-%m").to_string(),
+                label: month_label(*m),
+                closed: closed_months.contains(m),
+                selected: *m == month_start,
+            })
+            .collect(),
+        closed: loaded.period.is_some(),
+        closed_at: loaded.period.as_ref().map(|p| local_time(p.closed_at)),
+        closed_by: loaded.period.as_ref().map(|p| p.closed_by.to_string()),
+        can_close: loaded.period.is_none() && chargeback::has_ended(month_start, today),
+        departments,
+        totals: TotalsView::from(&totals),
+        csv_url: format!("/api/public/admin/chargeback/{month}/export"),
+        month,
+    };
+
+    Ok(super::render_typed_page(
+        &engine,
+        "chargeback",
+        &ctx,
+        &user_ctx,
+        &mkt_ctx,
+    ))
+}
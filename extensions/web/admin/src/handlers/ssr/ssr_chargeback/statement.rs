@@ -0,0 +1,120 @@
+//! `/admin/chargeback/statement` — one department's statement for a month,
+//! laid out for printing or saving as PDF rather than inside the admin
+//! shell.
+
+use std::sync::Arc;
+
+use axum::extract::{Extension, Query, State};
+use axum::response::Response;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+use super::super::format::local_time;
+use super::{TotalsView, month_label};
+use crate::chargeback::statement::{ChargeRow, department_statement};
+use crate::chargeback::{self, parse_month};
+use crate::error::{AdminError, AdminHtmlResult};
+use crate::handlers::chargeback::cost_centers;
+use crate::templates::AdminTemplateEngine;
+use crate::types::{MarketplaceContext, UserContext};
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct StatementQuery {
+    month: String,
+    department: String,
+}
+
+#[derive(Debug, Serialize)]
+struct ChargeRowView {
+    label: String,
+    totals: TotalsView,
+}
+
+#[derive(Debug, Serialize)]
+struct StatementContext {
+    page: &'static str,
+    title: String,
+    department: String,
+    month_label: String,
+    cost_centers: String,
+    closed: bool,
+    closed_at: Option<String>,
+    generated_at: String,
+    totals: TotalsView,
+    input_tokens: i64,
+    output_tokens: i64,
+    by_cost_center: Vec<ChargeRowView>,
+    by_provider: Vec<ChargeRowView>,
+    by_model: Vec<ChargeRowView>,
+    by_user: Vec<ChargeRowView>,
+    csv_url: String,
+}
+
+fn view(rows: &[ChargeRow]) -> Vec<ChargeRowView> {
+    rows.iter()
+        .map(|r| ChargeRowView {
+            label: r.label.clone(),
+            totals: TotalsView::from(&r.totals),
+        })
+        .collect()
+}
+
+pub(crate) async fn chargeback_statement_page(
+    Extension(user_ctx): Extension<UserContext>,
+    Extension(mkt_ctx): Extension<MarketplaceContext>,
+    Extension(engine): Extension<AdminTemplateEngine>,
+    State(pool): State<Arc<PgPool>>,
+    Query(params): Query<StatementQuery>,
+) -> AdminHtmlResult<Response> {
+    if !user_ctx.is_admin {
+        return Err(AdminError::Forbidden("Admin access required.".to_owned()).into());
+    }
+    let month_start = parse_month(&params.month).ok_or_else(|| {
+        AdminError::BadRequest(format!("'{}' is not a month (YYYY-MM)", params.month))
+    })?;
+
+    let loaded = chargeback::load_month(&pool, &cost_centers()?, month_start).await?;
+    let statement = department_statement(&loaded.lines, &params.department).ok_or_else(|| {
+        AdminError::NotFound(format!(
+            "No charges for {} in {}",
+            params.department,
+            month_label(month_start)
+        ))
+    })?;
+
+    let month = month_start.format("%Y-%m");
+    let ctx = StatementContext {
+        page: "chargeback-statement",
+        title: format!(
+            "Chargeback statement — {} — {}",
+            statement.charge.department,
+            month_label(month_start)
+        ),
+        month_label: month_label(month_start),
+        cost_centers: statement.charge.cost_centers.join(", "),
+        closed: loaded.period.is_some(),
+        closed_at: loaded.period.as_ref().map(|p| local_time(p.closed_at)),
+        generated_at: local_time(Utc::now()),
+        totals: TotalsView::from(&statement.charge.totals),
+        input_tokens: statement.charge.totals.input_tokens,
+        output_tokens: statement.charge.totals.output_tokens,
+        by_cost_center: view(&statement.by_cost_center),
+        by_provider: view(&statement.by_provider),
+        by_model: view(&statement.by_model),
+        by_user: view(&statement.by_user),
+        csv_url: format!(
+            "/api/public/admin/chargeback/{month}/export?department={}",
+            urlencoding::encode(&statement.charge.department)
+        ),
+        department: statement.charge.department,
+    };
+
+    Ok(super::super::render_typed_page(
+        &engine,
+        "chargeback-statement",
+        &ctx,
+        &user_ctx,
+        &mkt_ctx,
+    ))
+}
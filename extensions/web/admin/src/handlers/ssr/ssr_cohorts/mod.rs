@@ -0,0 +1,238 @@
+//! `/admin/cohorts` — session outcomes compared across departments, agents,
+//! models, plugins, skills or categories, between a selected date range and
+//! a baseline.
+//!
+//! Reads `session_analyses` directly, so the page reflects every analysis
+//! written up to the moment it loads. `?cohort=` narrows the worst-sessions
+//! table to one cohort; the cohort table and trend always show them all.
+
+use std::sync::Arc;
+
+use axum::extract::{Extension, Query, State};
+use axum::response::Response;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+use crate::cohorts::{
+    self, CohortComparison, CohortDimension, CohortRange, MIN_COMPARABLE_SESSIONS, TrendBucket,
+    compare_cohorts, overall, summarize,
+};
+use crate::error::{AdminError, AdminHtmlResult};
+use crate::repositories::analytics::cohorts::{CohortSessionRow, list_cohort_sessions};
+use crate::templates::AdminTemplateEngine;
+use crate::types::{MarketplaceContext, UserContext};
+
+mod view;
+
+use view::{
+    CohortRowView, TrendRowView, WorstSessionView, bucket_label, cohort_row, trend_row,
+    worst_session,
+};
+
+const BASE_URL: &str = "/admin/cohorts";
+const MAX_SESSIONS: i64 = 20_000;
+const TREND_COHORTS: usize = 6;
+const WORST_SESSIONS: usize = 25;
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct CohortsQuery {
+    by: Option<String>,
+    from: Option<String>,
+    to: Option<String>,
+    baseline_from: Option<String>,
+    baseline_to: Option<String>,
+    cohort: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct DimensionOptionView {
+    value: &'static str,
+    label: &'static str,
+    selected: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct TrendView {
+    bucket: &'static str,
+    first: String,
+    last: String,
+    rows: Vec<TrendRowView>,
+}
+
+#[derive(Debug, Serialize)]
+struct CohortsContext {
+    page: &'static str,
+    title: &'static str,
+    hero_title: &'static str,
+    hero_subtitle: &'static str,
+    base_url: &'static str,
+    dimensions: Vec<DimensionOptionView>,
+    by: &'static str,
+    by_label: &'static str,
+    from: String,
+    to: String,
+    baseline_from: String,
+    baseline_to: String,
+    truncated: bool,
+    max_sessions: i64,
+    min_comparable: i64,
+    total: CohortRowView,
+    cohorts: Vec<CohortRowView>,
+    trend: TrendView,
+    cohort: Option<String>,
+    clear_url: String,
+    worst: Vec<WorstSessionView>,
+}
+
+struct Loaded {
+    range: CohortRange,
+    baseline: CohortRange,
+    current: Vec<CohortSessionRow>,
+    previous: Vec<CohortSessionRow>,
+}
+
+fn day(d: chrono::NaiveDate) -> String {
+    d.format("%Y-%m-%d").to_string()
+}
+
+fn trend_view(
+    loaded: &Loaded,
+    dimension: CohortDimension,
+    compared: &[CohortComparison],
+) -> TrendView {
+    let (start, end) = (loaded.range.start(), loaded.range.end());
+    let bucket = TrendBucket::for_range(start, end);
+    let starts = cohorts::trend_buckets(bucket, start, end);
+    let top: Vec<String> = compared
+        .iter()
+        .filter(|c| c.current.sessions > 0)
+        .take(TREND_COHORTS)
+        .map(|c| c.cohort.clone())
+        .collect();
+    TrendView {
+        bucket: bucket.label(),
+        first: starts
+            .first()
+            .map(|d| bucket_label(bucket, *d))
+            .unwrap_or_default(),
+        last: starts
+            .last()
+            .map(|d| bucket_label(bucket, *d))
+            .unwrap_or_default(),
+        rows: cohorts::cohort_trends(&loaded.current, dimension, &top, bucket, &starts)
+            .iter()
+            .map(|t| trend_row(t, &starts, bucket))
+            .collect(),
+    }
+}
+
+fn page_context(
+    loaded: &Loaded,
+    dimension: CohortDimension,
+    cohort: Option<&str>,
+) -> CohortsContext {
+    let (range, baseline) = (loaded.range, loaded.baseline);
+    let query = format!(
+        "{BASE_URL}?by={}&from={}&to={}&baseline_from={}&baseline_to={}",
+        dimension.as_str(),
+        day(range.first),
+        day(range.last),
+        day(baseline.first),
+        day(baseline.last),
+    );
+    let compared = compare_cohorts(
+        summarize(&loaded.current, dimension),
+        summarize(&loaded.previous, dimension),
+    );
+    CohortsContext {
+        page: "cohorts",
+        title: "Cohorts",
+        hero_title: "Cohorts",
+        hero_subtitle: "Session outcomes by department, agent, model, plugin, skill or category, against a baseline range.",
+        base_url: BASE_URL,
+        dimensions: CohortDimension::ALL
+            .into_iter()
+            .map(|d| DimensionOptionView {
+                value: d.as_str(),
+                label: d.label(),
+                selected: d == dimension,
+            })
+            .collect(),
+        by: dimension.as_str(),
+        by_label: dimension.label(),
+        from: day(range.first),
+        to: day(range.last),
+        baseline_from: day(baseline.first),
+        baseline_to: day(baseline.last),
+        truncated: [&loaded.current, &loaded.previous]
+            .iter()
+            .any(|rows| crate::numeric::usize_to_i64(rows.len()) >= MAX_SESSIONS),
+        max_sessions: MAX_SESSIONS,
+        min_comparable: MIN_COMPARABLE_SESSIONS,
+        total: cohort_row(
+            &overall(&loaded.current, &loaded.previous),
+            query.clone(),
+            cohort.is_none(),
+        ),
+        cohorts: compared
+            .iter()
+            .map(|c| {
+                let url = format!("{query}&cohort={}", urlencoding::encode(&c.cohort));
+                cohort_row(c, url, cohort == Some(c.cohort.as_str()))
+            })
+            .collect(),
+        trend: trend_view(loaded, dimension, &compared),
+        worst: cohorts::worst_sessions(&loaded.current, dimension, cohort, WORST_SESSIONS)
+            .into_iter()
+            .map(|r| worst_session(r, dimension))
+            .collect(),
+        cohort: cohort.map(str::to_owned),
+        clear_url: query,
+    }
+}
+
+pub(crate) async fn cohorts_page(
+    Extension(user_ctx): Extension<UserContext>,
+    Extension(mkt_ctx): Extension<MarketplaceContext>,
+    Extension(engine): Extension<AdminTemplateEngine>,
+    State(pool): State<Arc<PgPool>>,
+    Query(params): Query<CohortsQuery>,
+) -> AdminHtmlResult<Response> {
+    if !user_ctx.is_admin {
+        return Err(AdminError::Forbidden("Admin access required.".to_owned()).into());
+    }
+
+    let dimension = params
+        .by
+        .as_deref()
+        .and_then(CohortDimension::parse)
+        .unwrap_or_default();
+    let range = CohortRange::parse(
+        params.from.as_deref(),
+        params.to.as_deref(),
+        Utc::now().date_naive(),
+    );
+    let baseline = CohortRange::parse_baseline(
+        params.baseline_from.as_deref(),
+        params.baseline_to.as_deref(),
+        range,
+    );
+    let loaded = Loaded {
+        current: list_cohort_sessions(&pool, range.start(), range.end(), MAX_SESSIONS).await?,
+        previous: list_cohort_sessions(&pool, baseline.start(), baseline.end(), MAX_SESSIONS)
+            .await?,
+        range,
+        baseline,
+    };
+    let cohort = params
+        .cohort
+        .as_deref()
+        .map(str::trim)
+        .filter(|c| !c.is_empty());
+    let ctx = page_context(&loaded, dimension, cohort);
+
+    Ok(super::render_typed_page(
+        &engine, "cohorts", &ctx, &user_ctx, &mkt_ctx,
+    ))
+}
@@ -0,0 +1,233 @@
+//! View models for `/admin/cohorts`: every number formatted and every bar
+//! width computed here, so the template only lays them out.
+
+use chrono::NaiveDate;
+use serde::Serialize;
+
+use super::super::entity_urls::session_detail_url;
+use super::super::format::local_time;
+use crate::cohorts::{
+    ALL_SESSIONS, CohortComparison, CohortDimension, CohortStats, CohortTrend, TrendBucket,
+    cohort_keys,
+};
+use crate::numeric::round_to_i64;
+use crate::repositories::analytics::cohorts::CohortSessionRow;
+
+#[derive(Debug, Serialize)]
+pub(super) struct ChangeView {
+    text: String,
+    tone: &'static str,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Better {
+    Higher,
+    Lower,
+}
+
+fn percent(share: f64) -> i64 {
+    round_to_i64(share * 100.0)
+}
+
+fn change_view(change: Option<f64>, better: Better, scale: f64, unit: &str) -> ChangeView {
+    let Some(change) = change else {
+        return ChangeView {
+            text: "—".to_owned(),
+            tone: "flat",
+        };
+    };
+    let scaled = change * scale;
+    let text = if unit.is_empty() {
+        format!("{scaled:+.1}")
+    } else {
+        format!("{:+} {unit}", round_to_i64(scaled))
+    };
+    // Why: a change that rounds to nothing at the shown precision is not
+    // coloured either way.
+    let threshold = if unit.is_empty() { 0.05 } else { 0.5 };
+    let tone = if scaled.abs() < threshold {
+        "flat"
+    } else if (scaled > 0.0) == matches!(better, Better::Higher) {
+        "better"
+    } else {
+        "worse"
+    };
+    ChangeView { text, tone }
+}
+
+fn one_decimal(value: Option<f64>) -> String {
+    value.map_or_else(|| "—".to_owned(), |v| format!("{v:.1}"))
+}
+
+fn share_text(value: Option<f64>) -> String {
+    value.map_or_else(|| "—".to_owned(), |v| format!("{}%", percent(v)))
+}
+
+#[derive(Debug, Serialize)]
+pub(super) struct SegmentView {
+    label: String,
+    pct: i64,
+    count: i64,
+    low: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub(super) struct CohortRowView {
+    cohort: String,
+    total: bool,
+    drill_url: String,
+    selected: bool,
+    sessions: i64,
+    baseline_sessions: i64,
+    goal_met: String,
+    goals: Vec<SegmentView>,
+    goal_change: ChangeView,
+    quality: String,
+    quality_change: ChangeView,
+    distribution: Vec<SegmentView>,
+    corrections: String,
+    corrections_change: ChangeView,
+    practice: String,
+    practice_change: ChangeView,
+    comparable: bool,
+}
+
+fn goal_segments(stats: &CohortStats) -> Vec<SegmentView> {
+    let Some(shares) = stats.goal_shares() else {
+        return Vec::new();
+    };
+    let counts = [stats.goal_yes, stats.goal_partial, stats.goal_no];
+    ["met", "partial", "missed"]
+        .into_iter()
+        .zip(shares.into_iter().zip(counts))
+        .map(|(label, (share, count))| SegmentView {
+            label: label.to_owned(),
+            pct: percent(share),
+            count,
+            low: false,
+        })
+        .collect()
+}
+
+fn quality_segments(stats: &CohortStats) -> Vec<SegmentView> {
+    let scored = stats.quality_scored();
+    if scored == 0 {
+        return Vec::new();
+    }
+    stats
+        .quality
+        .iter()
+        .zip(1..)
+        .map(|(&count, score)| SegmentView {
+            label: format!("{score}"),
+            pct: percent(crate::numeric::to_f64(count) / crate::numeric::to_f64(scored)),
+            count,
+            low: score <= 2,
+        })
+        .collect()
+}
+
+pub(super) fn cohort_row(c: &CohortComparison, drill_url: String, selected: bool) -> CohortRowView {
+    let s = &c.current;
+    CohortRowView {
+        cohort: c.cohort.clone(),
+        total: c.cohort == ALL_SESSIONS,
+        drill_url,
+        selected,
+        sessions: s.sessions,
+        baseline_sessions: c.baseline.sessions,
+        goal_met: share_text(s.goal_shares().map(|[met, _, _]| met)),
+        goals: goal_segments(s),
+        goal_change: change_view(c.goal_rate_change(), Better::Higher, 100.0, "pts"),
+        quality: one_decimal(s.avg_quality()),
+        quality_change: change_view(c.quality_change(), Better::Higher, 1.0, ""),
+        distribution: quality_segments(s),
+        corrections: one_decimal(s.avg_corrections()),
+        corrections_change: change_view(c.corrections_change(), Better::Lower, 1.0, ""),
+        practice: share_text(s.practice_score()),
+        practice_change: change_view(c.practice_change(), Better::Higher, 100.0, "pts"),
+        comparable: c.comparable(),
+    }
+}
+
+pub(super) fn bucket_label(bucket: TrendBucket, start: NaiveDate) -> String {
+    match bucket {
+        TrendBucket::Day => start.format("%b %-d").to_string(),
+        TrendBucket::Week => format!("Week of {}", start.format("%b %-d")),
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub(super) struct TrendCellView {
+    pct: i64,
+    has_data: bool,
+    tooltip: String,
+}
+
+#[derive(Debug, Serialize)]
+pub(super) struct TrendRowView {
+    cohort: String,
+    cells: Vec<TrendCellView>,
+}
+
+pub(super) fn trend_row(
+    trend: &CohortTrend,
+    starts: &[NaiveDate],
+    bucket: TrendBucket,
+) -> TrendRowView {
+    let cells = trend
+        .buckets
+        .iter()
+        .zip(starts)
+        .map(|(stats, start)| {
+            let quality = stats.avg_quality();
+            TrendCellView {
+                pct: quality.map_or(0, |q| round_to_i64(q * 20.0)),
+                has_data: quality.is_some(),
+                tooltip: format!(
+                    "{}: {} sessions, quality {}, goal met {}",
+                    bucket_label(bucket, *start),
+                    stats.sessions,
+                    one_decimal(quality),
+                    share_text(stats.goal_shares().map(|[met, _, _]| met)),
+                ),
+            }
+        })
+        .collect();
+    TrendRowView {
+        cohort: trend.cohort.clone(),
+        cells,
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub(super) struct WorstSessionView {
+    title: String,
+    url: String,
+    user: String,
+    analysed_at: String,
+    goal_achieved: String,
+    quality_score: i16,
+    corrections: i32,
+    cohorts: String,
+}
+
+pub(super) fn worst_session(
+    row: &CohortSessionRow,
+    dimension: CohortDimension,
+) -> WorstSessionView {
+    WorstSessionView {
+        title: if row.title.trim().is_empty() {
+            row.session_id.as_str().to_owned()
+        } else {
+            row.title.clone()
+        },
+        url: session_detail_url(&row.session_id),
+        user: row.user_id.to_string(),
+        analysed_at: local_time(row.created_at),
+        goal_achieved: row.goal_achieved.clone(),
+        quality_score: row.quality_score,
+        corrections: row.corrections_count,
+        cohorts: cohort_keys(row, dimension).join(", "),
+    }
+}
@@ -29,12 +29,7 @@ pub(crate) async fn conversations_raw(
     Path(session_id): Path<String>,
 ) -> AdminResult<Response> {
     let session_id = SessionId::new(session_id);
-    let allowed = user_ctx.is_admin
-        || user_ctx
-            .roles
-            .iter()
-            .any(|r| r.eq_ignore_ascii_case("auditor"));
-    if !allowed {
+    if !user_ctx.can_audit() {
         return Err(AdminError::Forbidden(
             "Admin or auditor role required".to_owned(),
         ));
@@ -0,0 +1,131 @@
+//! `/admin/costs` — month-end spend forecasts per department and the spend
+//! anomalies flagged over the last week.
+//!
+//! Both are written by the hourly `cost_anomaly_scan` job; this page only
+//! reads them, so it shows the state as of that job's last run.
+
+use std::sync::Arc;
+
+use axum::extract::{Extension, State};
+use axum::response::Response;
+use chrono::{Duration, Utc};
+use serde::Serialize;
+use sqlx::PgPool;
+
+use super::format::{format_cost, local_time};
+use crate::cost_watch::anomaly::SEVERITY_CRITICAL;
+use crate::error::{AdminError, AdminHtmlResult};
+use crate::repositories::cost_watch::{
+    CostAnomaly, CostForecast, list_cost_anomalies_since, list_cost_forecasts,
+};
+use crate::templates::AdminTemplateEngine;
+use crate::types::{MarketplaceContext, UserContext};
+use crate::util::calendar::month_start;
+
+const ANOMALY_DAYS: i64 = 7;
+const ANOMALY_LIMIT: i64 = 200;
+
+#[derive(Debug, Serialize)]
+struct ForecastRowView {
+    department: String,
+    spent: String,
+    daily_rate: String,
+    forecast: String,
+    previous_month: String,
+    change: Option<String>,
+    rising: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct AnomalyRowView {
+    hour: String,
+    scope: String,
+    subject: String,
+    is_user: bool,
+    spend: String,
+    baseline: String,
+    ratio: String,
+    requests: i64,
+    critical: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct CostWatchContext {
+    page: &'static str,
+    title: &'static str,
+    hero_title: &'static str,
+    hero_subtitle: &'static str,
+    month: String,
+    forecasts: Vec<ForecastRowView>,
+    anomalies: Vec<AnomalyRowView>,
+    critical_count: usize,
+}
+
+fn forecast_row(f: CostForecast) -> ForecastRowView {
+    let change = (f.previous_month_microdollars > 0).then(|| {
+        let pct = crate::numeric::to_f64(f.forecast_microdollars - f.previous_month_microdollars)
+            * 100.0
+            / crate::numeric::to_f64(f.previous_month_microdollars);
+        format!("{pct:+.0}%")
+    });
+    ForecastRowView {
+        rising: f.forecast_microdollars > f.previous_month_microdollars,
+        department: f.department,
+        spent: format_cost(f.spent_microdollars),
+        daily_rate: format_cost(f.daily_rate_microdollars),
+        forecast: format_cost(f.forecast_microdollars),
+        previous_month: format_cost(f.previous_month_microdollars),
+        change,
+    }
+}
+
+fn anomaly_row(a: CostAnomaly) -> AnomalyRowView {
+    AnomalyRowView {
+        hour: local_time(a.hour_start),
+        is_user: a.scope == "user",
+        critical: a.severity == SEVERITY_CRITICAL,
+        scope: a.scope,
+        subject: a.subject,
+        spend: format_cost(a.spend_microdollars),
+        baseline: format_cost(a.baseline_microdollars),
+        ratio: format!("{:.1}×", a.ratio),
+        requests: a.requests,
+    }
+}
+
+pub(crate) async fn cost_watch_page(
+    Extension(user_ctx): Extension<UserContext>,
+    Extension(mkt_ctx): Extension<MarketplaceContext>,
+    Extension(engine): Extension<AdminTemplateEngine>,
+    State(pool): State<Arc<PgPool>>,
+) -> AdminHtmlResult<Response> {
+    if !user_ctx.is_admin {
+        return Err(AdminError::Forbidden("Admin access required.".to_owned()).into());
+    }
+
+    let now = Utc::now();
+    let month = month_start(now.date_naive());
+    let forecasts = list_cost_forecasts(&pool, month).await?;
+    let anomalies =
+        list_cost_anomalies_since(&pool, now - Duration::days(ANOMALY_DAYS), ANOMALY_LIMIT).await?;
+    let anomalies: Vec<AnomalyRowView> = anomalies.into_iter().map(anomaly_row).collect();
+
+    let ctx = CostWatchContext {
+        page: "costs",
+        title: "Costs",
+        hero_title: "Costs",
+        hero_subtitle: "Projected month-end spend per department, and hours where spend broke from its usual pattern.",
+        month: month.format("%B %Y").to_string(),
+        forecasts: forecasts.into_iter().map(forecast_row).collect(),
+        critical_count: anomalies.iter().filter(|a| a.critical).count(),
+        anomalies,
+    };
+
+    Ok(super::render_typed_page(
+        &engine,
+        "cost-watch",
+        &ctx,
+        &user_ctx,
+        &mkt_ctx,
+    ))
+}
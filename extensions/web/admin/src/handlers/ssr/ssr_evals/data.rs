@@ -33,6 +33,7 @@ use crate::repositories::evals::scores::{
     EvalScoreSummary, ModelScoreRow, ModelWinRateRow, get_eval_score_summary, list_model_scores,
     list_model_win_rates,
 };
+use crate::util::activity_scope::ActivityScope;
 use crate::util::time_range::{
     TimeRange, TimeRangePreset, TimeRangeQuery, count_requests_in_range, parse_time_range,
     preset_to_range,
@@ -112,7 +113,7 @@ pub(super) async fn fetch_evals_data(
     filter: &ResultFilter,
 ) -> EvalsData {
     let (stats, scores) = tokio::join!(
-        get_request_stats(pool, range),
+        get_request_stats(pool, range, ActivityScope::default()),
         get_eval_score_summary(pool, range),
     );
 
@@ -0,0 +1,96 @@
+//! `/admin/gateway/explain` — "what happens to model X for user Y".
+//!
+//! A form over the route explainer: pick a model and a user, or paste a
+//! token prefix, and the page shows each stage the gateway would run. A
+//! lookup that fails (unknown user, unknown token) is shown beside the form
+//! rather than as an error page, since it is usually a typo.
+
+use std::sync::Arc;
+
+use axum::extract::{Extension, Query, State};
+use axum::response::Response;
+use serde::Serialize;
+use sqlx::PgPool;
+
+use crate::error::{AdminError, AdminHtmlResult};
+use crate::handlers::gateway_explain::ExplainQuery;
+use crate::repositories;
+use crate::services::route_explain;
+use crate::templates::AdminTemplateEngine;
+use crate::types::{MarketplaceContext, RouteExplanation, UserContext};
+
+use super::ssr_models::{UserOptionView, build_user_options};
+
+#[derive(Debug, Serialize)]
+struct GatewayExplainContext {
+    page: &'static str,
+    title: &'static str,
+    hero_title: &'static str,
+    hero_subtitle: &'static str,
+    model: String,
+    token: String,
+    users: Vec<UserOptionView>,
+    model_patterns: Vec<String>,
+    explanation: Option<RouteExplanation>,
+    error: Option<String>,
+}
+
+pub(crate) async fn gateway_explain_page(
+    Extension(user_ctx): Extension<UserContext>,
+    Extension(mkt_ctx): Extension<MarketplaceContext>,
+    Extension(engine): Extension<AdminTemplateEngine>,
+    State(pool): State<Arc<PgPool>>,
+    Query(query): Query<ExplainQuery>,
+) -> AdminHtmlResult<Response> {
+    if !user_ctx.is_admin {
+        return Err(AdminError::Forbidden("Admin access required.".to_owned()).into());
+    }
+
+    let all_users = repositories::users::queries::list_users(&pool)
+        .await
+        .unwrap_or_else(|e| {
+            tracing::warn!(error = %e, "Failed to list users for the route explainer");
+            vec![]
+        });
+    let selected = query.user_id.as_ref().map(ToString::to_string);
+    let users = build_user_options(&all_users, selected.as_deref());
+    let model_patterns = repositories::config::gateway::get_gateway_config(
+        &crate::handlers::shared::get_profile_path()?,
+    )
+    .map(|c| c.routes.into_iter().map(|r| r.model_pattern).collect())
+    .unwrap_or_default();
+
+    let (explanation, error) = match query.subject() {
+        Some(who) if !query.model.trim().is_empty() => {
+            match route_explain::explain(&pool, &query.model, who).await {
+                Ok(explanation) => (Some(explanation), None),
+                Err(e @ (AdminError::NotFound(_) | AdminError::BadRequest(_))) => {
+                    (None, Some(e.to_string()))
+                },
+                Err(e) => return Err(e.into()),
+            }
+        },
+        _ => (None, None),
+    };
+
+    let ctx = GatewayExplainContext {
+        page: "gateway-explain",
+        title: "Route Explainer",
+        hero_title: "Route Explainer",
+        hero_subtitle: "Which route a model resolves to for one user, whether access control lets it through, and what runs on the request after that.",
+        model: query.model.trim().to_owned(),
+        token: query.token.clone().unwrap_or_default(),
+        users,
+        model_patterns,
+        explanation,
+        error,
+    };
+
+    Ok(super::render_typed_page(
+        &engine,
+        "gateway-explain",
+        &ctx,
+        &user_ctx,
+        &mkt_ctx,
+    ))
+}
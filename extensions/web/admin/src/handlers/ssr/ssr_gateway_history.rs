@@ -0,0 +1,132 @@
+//! `/admin/gateway/history` — every stored state of the gateway config.
+//!
+//! Lists revisions newest first and shows the selected one (the newest by
+//! default) side by side with the revision before it, for both the profile's
+//! `gateway` block and `targets.yaml`. Rolling back is a POST from the page
+//! script, carrying the etag the page was rendered with.
+
+use std::sync::Arc;
+
+use axum::extract::{Extension, Query, State};
+use axum::response::Response;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+use crate::error::{AdminError, AdminHtmlResult};
+use crate::handlers::gateway_revisions::load_revision_diff;
+use crate::repositories::config::gateway_revisions::list_gateway_revisions;
+use crate::services::gateway_config;
+use crate::templates::AdminTemplateEngine;
+use crate::types::{MarketplaceContext, UserContext};
+use crate::util::line_diff::{DiffKind, DiffRow};
+
+const HISTORY_LIMIT: i64 = 100;
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct HistoryQuery {
+    revision: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+struct RevisionRowView {
+    id: i64,
+    created_at: String,
+    action: String,
+    summary: String,
+    actor: String,
+    selected: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct SelectedRevisionView {
+    id: i64,
+    previous_id: Option<i64>,
+    summary: String,
+    is_current: bool,
+    gateway: Vec<DiffRow>,
+    targets: Vec<DiffRow>,
+    gateway_changed: bool,
+    targets_changed: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct GatewayHistoryContext {
+    page: &'static str,
+    title: &'static str,
+    hero_title: &'static str,
+    hero_subtitle: &'static str,
+    etag: String,
+    revisions: Vec<RevisionRowView>,
+    has_revisions: bool,
+    selected: Option<SelectedRevisionView>,
+}
+
+fn changed(rows: &[DiffRow]) -> bool {
+    rows.iter().any(|r| r.kind != DiffKind::Same)
+}
+
+pub(crate) async fn gateway_history_page(
+    Extension(user_ctx): Extension<UserContext>,
+    Extension(mkt_ctx): Extension<MarketplaceContext>,
+    Extension(engine): Extension<AdminTemplateEngine>,
+    State(pool): State<Arc<PgPool>>,
+    Query(params): Query<HistoryQuery>,
+) -> AdminHtmlResult<Response> {
+    if !user_ctx.is_admin {
+        return Err(AdminError::Forbidden("Admin access required.".to_owned()).into());
+    }
+
+    let revisions = list_gateway_revisions(&pool, HISTORY_LIMIT).await?;
+    let etag = gateway_config::current_etag()?;
+    let selected_id = params.revision.or_else(|| revisions.first().map(|r| r.id));
+    let selected = match selected_id {
+        Some(id) => {
+            let diff = load_revision_diff(&pool, id).await?;
+            Some(SelectedRevisionView {
+                id,
+                previous_id: diff.previous_id,
+                summary: diff.revision.summary,
+                is_current: diff.revision.etag == etag,
+                gateway_changed: changed(&diff.gateway),
+                targets_changed: changed(&diff.targets),
+                gateway: diff.gateway,
+                targets: diff.targets,
+            })
+        },
+        None => None,
+    };
+
+    let rows: Vec<RevisionRowView> = revisions
+        .into_iter()
+        .map(|r| RevisionRowView {
+            selected: Some(r.id) == selected_id,
+            id: r.id,
+            created_at: r.created_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+            action: r.action,
+            summary: r.summary,
+            actor: r
+                .actor_email
+                .or_else(|| r.actor_id.map(|u| u.to_string()))
+                .unwrap_or_else(|| "outside the admin UI".to_owned()),
+        })
+        .collect();
+
+    let ctx = GatewayHistoryContext {
+        page: "gateway-history",
+        title: "Gateway History",
+        hero_title: "Gateway History",
+        hero_subtitle: "Every saved state of the gateway routes and targets, with who changed what.",
+        etag,
+        has_revisions: !rows.is_empty(),
+        revisions: rows,
+        selected,
+    };
+
+    Ok(super::render_typed_page(
+        &engine,
+        "gateway-history",
+        &ctx,
+        &user_ctx,
+        &mkt_ctx,
+    ))
+}
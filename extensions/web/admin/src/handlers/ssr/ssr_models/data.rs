@@ -1,23 +1,49 @@
 //! Data loading for the Model Selection page: gateway routes joined with
-//! per-user access rules, and the selected user's `ai_requests` usage.
+//! per-user access rules and their upstream targets' health, and the selected
+//! user's `ai_requests` usage.
 
 use std::sync::Arc;
 
 use sqlx::PgPool;
 use systemprompt_security::authz::{Access, AccessControlRepository, EntityKind, RuleType};
 
+use crate::gateway_routing::{UnhealthyUpstreams, load_unhealthy, target_is_healthy};
 use crate::handlers::shared;
 use crate::repositories;
 use crate::repositories::analytics::requests::{
     RequestFilter, RequestPage, RequestSortSpec, list_requests_paged,
 };
+use crate::repositories::config::gateway::RouteTargetsConfig;
+use crate::types::GatewayRouteTarget;
 use crate::util::time_range::{TimeRangeQuery, parse_time_range};
 
-use super::view::{ModelRowView, UsageRowView, UsageTotalsView};
+use super::view::{ModelRowView, TargetRowView, UsageRowView, UsageTotalsView};
 use crate::handlers::ssr::format::format_cost;
 
 const USAGE_ROWS: i64 = 25;
 
+fn target_rows(
+    targets: &[GatewayRouteTarget],
+    unhealthy: &UnhealthyUpstreams,
+    requested_model: &str,
+) -> Vec<TargetRowView> {
+    let total: u64 = targets.iter().map(|t| u64::from(t.weight)).sum();
+    targets
+        .iter()
+        .map(|t| TargetRowView {
+            healthy: target_is_healthy(unhealthy, t, requested_model),
+            share_label: if t.weight == 0 || total == 0 {
+                "fallback".to_owned()
+            } else {
+                format!("{}%", u64::from(t.weight) * 100 / total)
+            },
+            id: t.id.clone(),
+            provider: t.provider.clone(),
+            upstream_model: t.upstream_model.clone().unwrap_or_default(),
+        })
+        .collect()
+}
+
 pub(super) async fn load_model_rows(
     pool: &PgPool,
     selected_user: Option<&str>,
@@ -26,6 +52,14 @@ pub(super) async fn load_model_rows(
     let cfg = repositories::config::gateway::get_gateway_config(&profile_path)
         .map_err(|e| crate::error::AdminHtmlError::internal(e.to_string()))?;
 
+    let services_path = shared::get_services_path().map_err(crate::error::AdminHtmlError::from)?;
+    let targets =
+        repositories::config::gateway::load_route_targets(&services_path).unwrap_or_else(|e| {
+            tracing::warn!(error = %e, "Failed to load gateway route targets");
+            RouteTargetsConfig::default()
+        });
+    let unhealthy = load_unhealthy(pool, targets.health).await;
+
     let repo = AccessControlRepository::from_pool(Arc::new(pool.clone()));
     let mut rows = Vec::with_capacity(cfg.routes.len());
     for route in cfg.routes {
@@ -44,7 +78,16 @@ pub(super) async fn load_model_rows(
                 }),
         };
         let denied = deny_rule.is_some();
+        // Why: a target without its own model sends whatever the caller
+        // asked for; the route's pattern is the closest the page can name.
+        let route_targets = target_rows(
+            targets.targets_for(&route.id),
+            &unhealthy,
+            &route.model_pattern,
+        );
         rows.push(ModelRowView {
+            has_targets: !route_targets.is_empty(),
+            targets: route_targets,
             upstream_model: route
                 .upstream_model
                 .clone()
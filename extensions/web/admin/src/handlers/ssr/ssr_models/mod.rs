@@ -22,7 +22,8 @@ use super::types::PageStatView;
 mod data;
 mod view;
 
-use view::{ModelsPageData, build_user_options};
+use view::ModelsPageData;
+pub(super) use view::{UserOptionView, build_user_options};
 
 #[derive(Debug, Deserialize)]
 pub(crate) struct ModelsQuery {
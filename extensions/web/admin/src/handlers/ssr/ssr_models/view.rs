@@ -5,7 +5,7 @@ use serde::Serialize;
 use super::super::types::PageStatView;
 
 #[derive(Debug, Serialize)]
-pub(super) struct UserOptionView {
+pub(crate) struct UserOptionView {
     pub id: String,
     pub label: String,
     pub selected: bool,
@@ -20,6 +20,17 @@ pub(super) struct ModelRowView {
     pub denied: bool,
     pub deny_rule_id: String,
     pub status_label: &'static str,
+    pub targets: Vec<TargetRowView>,
+    pub has_targets: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub(super) struct TargetRowView {
+    pub id: String,
+    pub provider: String,
+    pub upstream_model: String,
+    pub share_label: String,
+    pub healthy: bool,
 }
 
 #[derive(Debug, Serialize)]
@@ -61,7 +72,7 @@ pub(super) struct ModelsPageData {
     pub page_stats: Vec<PageStatView>,
 }
 
-pub(super) fn build_user_options(
+pub(crate) fn build_user_options(
     all_users: &[crate::types::UserSummary],
     selected_id: Option<&str>,
 ) -> Vec<UserOptionView> {
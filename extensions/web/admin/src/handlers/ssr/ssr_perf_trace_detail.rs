@@ -3,7 +3,8 @@
 //! `id` may be a `session_id` or a `trace_id`. The handler resolves it to a
 //! session id, fetches all spans, and renders a waterfall whose bars are
 //! color-coded by `kind`. Each bar carries `data-chain-id` so clicks open
-//! the chain-drawer focused on that span.
+//! the chain-drawer focused on that span. Captured MCP tool calls also list
+//! their redacted arguments and result excerpt.
 
 use crate::error::AdminError;
 use std::sync::Arc;
@@ -43,6 +44,8 @@ struct SpanView {
     offset_label: String,
     offset_pct: f64,
     width_pct: f64,
+    arguments: Option<String>,
+    result_excerpt: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -184,11 +187,20 @@ fn build_span_views(spans: &[Span]) -> Vec<SpanView> {
                 offset_label: format!("+{offset_ms} ms"),
                 offset_pct,
                 width_pct,
+                arguments: raw_text(s, "arguments"),
+                result_excerpt: raw_text(s, "result_excerpt"),
             }
         })
         .collect()
 }
 
+fn raw_text(span: &Span, key: &str) -> Option<String> {
+    span.raw
+        .get(key)
+        .and_then(serde_json::Value::as_str)
+        .map(str::to_owned)
+}
+
 fn round2(v: f64) -> f64 {
     (v * 100.0).round() / 100.0
 }
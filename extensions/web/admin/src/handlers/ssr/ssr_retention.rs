@@ -0,0 +1,140 @@
+//! `/admin/governance/retention` — each data class's retention policy, what
+//! the `data_retention` job will purge next, what it last purged, and the
+//! legal holds exempting users and sessions.
+//!
+//! Policies and holds are edited in `services/governance/retention.yaml`;
+//! this page reads that file and counts the backlog on every view.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use axum::extract::{Extension, State};
+use axum::response::Response;
+use chrono::Utc;
+use serde::Serialize;
+use sqlx::PgPool;
+use systemprompt::config::ProfileBootstrap;
+
+use super::format::local_time;
+use crate::error::{AdminError, AdminHtmlResult, AdminResult};
+use crate::repositories::config::retention::{RetentionConfig, load_retention_config};
+use crate::retention::status::{ClassStatus, HORIZON_DAYS, class_statuses};
+use crate::templates::AdminTemplateEngine;
+use crate::types::{MarketplaceContext, UserContext};
+
+#[derive(Debug, Serialize)]
+struct ClassRowView {
+    class: &'static str,
+    description: &'static str,
+    retain_days: Option<u32>,
+    cutoff: Option<String>,
+    due_now: i64,
+    due_soon: i64,
+    held: i64,
+    last_run: Option<String>,
+    last_purged: i64,
+    total_purged: i64,
+    last_error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct HoldView {
+    kind: &'static str,
+    subject: String,
+    is_user: bool,
+    reason: String,
+}
+
+#[derive(Debug, Serialize)]
+struct RetentionContext {
+    page: &'static str,
+    title: &'static str,
+    hero_title: &'static str,
+    hero_subtitle: &'static str,
+    config_path: &'static str,
+    enabled: bool,
+    batch_size: u32,
+    max_batches_per_run: u32,
+    horizon_days: i64,
+    classes: Vec<ClassRowView>,
+    holds: Vec<HoldView>,
+}
+
+fn retention_config() -> AdminResult<RetentionConfig> {
+    let services_path = PathBuf::from(&ProfileBootstrap::get()?.paths.services);
+    Ok(load_retention_config(&services_path)?)
+}
+
+fn class_row(s: ClassStatus) -> ClassRowView {
+    let backlog = s.backlog.unwrap_or_default();
+    ClassRowView {
+        class: s.class.as_str(),
+        description: s.class.description(),
+        retain_days: s.retain_days,
+        cutoff: s.cutoff.map(local_time),
+        due_now: backlog.due_now,
+        due_soon: backlog.due_soon,
+        held: backlog.held,
+        last_run: s.last_run.as_ref().map(|r| local_time(r.last_run_at)),
+        last_purged: s.last_run.as_ref().map_or(0, |r| r.last_purged),
+        total_purged: s.last_run.as_ref().map_or(0, |r| r.total_purged),
+        last_error: s.last_run.and_then(|r| r.last_error),
+    }
+}
+
+pub(crate) async fn retention_page(
+    Extension(user_ctx): Extension<UserContext>,
+    Extension(mkt_ctx): Extension<MarketplaceContext>,
+    Extension(engine): Extension<AdminTemplateEngine>,
+    State(pool): State<Arc<PgPool>>,
+) -> AdminHtmlResult<Response> {
+    if !user_ctx.is_admin {
+        return Err(AdminError::Forbidden("Admin access required.".to_owned()).into());
+    }
+
+    let config = retention_config()?;
+    let statuses = class_statuses(&pool, &config, Utc::now()).await?;
+    let holds = config
+        .legal_holds
+        .iter()
+        .map(|h| match (&h.user_id, &h.session_id) {
+            (Some(user), _) => HoldView {
+                kind: "User",
+                subject: user.to_string(),
+                is_user: true,
+                reason: h.reason.clone(),
+            },
+            (None, session) => HoldView {
+                kind: "Session",
+                subject: session
+                    .as_ref()
+                    .map(ToString::to_string)
+                    .unwrap_or_default(),
+                is_user: false,
+                reason: h.reason.clone(),
+            },
+        })
+        .collect();
+
+    let ctx = RetentionContext {
+        page: "governance-retention",
+        title: "Retention",
+        hero_title: "Data Retention",
+        hero_subtitle: "How long each class of governed data is kept, what the nightly purge removes next, and who is under legal hold.",
+        config_path: "services/governance/retention.yaml",
+        enabled: config.enabled,
+        batch_size: config.batch_size,
+        max_batches_per_run: config.max_batches_per_run,
+        horizon_days: HORIZON_DAYS,
+        classes: statuses.into_iter().map(class_row).collect(),
+        holds,
+    };
+
+    Ok(super::render_typed_page(
+        &engine,
+        "retention",
+        &ctx,
+        &user_ctx,
+        &mkt_ctx,
+    ))
+}
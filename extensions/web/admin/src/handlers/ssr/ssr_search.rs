@@ -0,0 +1,219 @@
+//! `/admin/search` — full-text search over transcripts, gateway messages and
+//! session analysis titles.
+//!
+//! Open to admins and the `auditor` role, the same audience as raw
+//! transcript bodies. Snippets are redacted before they reach the page; the
+//! session and request links lead to the full record.
+
+use std::sync::Arc;
+
+use axum::extract::{Extension, Query, State};
+use axum::response::Response;
+use chrono::{DateTime, Days, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use systemprompt::identifiers::{AiRequestId, SessionId, UserId};
+
+use super::entity_urls::{request_detail_url, session_detail_url};
+use crate::error::{AdminError, AdminHtmlResult};
+use crate::repositories::search::{SearchFilter, SearchHit, list_search_hits};
+use crate::search::{SearchSource, SnippetSegment, snippet_from_headline};
+use crate::templates::AdminTemplateEngine;
+use crate::types::{MarketplaceContext, UserContext};
+
+const PAGE_SIZE: i64 = 50;
+const OUTCOMES: [&str; 2] = ["allow", "deny"];
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub(crate) struct FullTextSearchParams {
+    #[serde(default)]
+    q: String,
+    #[serde(default)]
+    source: String,
+    #[serde(default)]
+    user: String,
+    #[serde(default)]
+    model: String,
+    #[serde(default)]
+    outcome: String,
+    #[serde(default)]
+    from: String,
+    #[serde(default)]
+    to: String,
+    #[serde(default)]
+    page: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+struct FilterOptionView {
+    value: &'static str,
+    label: &'static str,
+    selected: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct SearchHitView {
+    source_label: &'static str,
+    session_id: Option<SessionId>,
+    session_url: Option<String>,
+    request_id: Option<AiRequestId>,
+    request_url: Option<String>,
+    user_id: UserId,
+    model: Option<String>,
+    role: Option<String>,
+    turn: Option<i32>,
+    occurred_at: String,
+    segments: Vec<SnippetSegment>,
+    redacted: u32,
+}
+
+#[derive(Debug, Serialize)]
+struct SearchPageContext<'a> {
+    page: &'static str,
+    title: &'static str,
+    hero_title: &'static str,
+    hero_subtitle: &'static str,
+    params: &'a FullTextSearchParams,
+    sources: Vec<FilterOptionView>,
+    outcomes: Vec<FilterOptionView>,
+    searched: bool,
+    hits: Vec<SearchHitView>,
+    page_number: i64,
+    prev_url: Option<String>,
+    next_url: Option<String>,
+}
+
+fn non_blank(value: &str) -> Option<&str> {
+    Some(value.trim()).filter(|v| !v.is_empty())
+}
+
+fn parse_day(value: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d").ok()
+}
+
+const fn day_start(day: NaiveDate) -> DateTime<Utc> {
+    day.and_time(chrono::NaiveTime::MIN).and_utc()
+}
+
+fn filter_from_params(params: &FullTextSearchParams) -> Result<SearchFilter, AdminError> {
+    let bad_date = |field| AdminError::BadRequest(format!("`{field}` must be a date (YYYY-MM-DD)"));
+    let from = non_blank(&params.from)
+        .map(|v| parse_day(v).ok_or_else(|| bad_date("from")))
+        .transpose()?;
+    // Why: the `to` day is inclusive on the form, so the bound is the next
+    // midnight.
+    let to = non_blank(&params.to)
+        .map(|v| {
+            parse_day(v)
+                .and_then(|d| d.checked_add_days(Days::new(1)))
+                .ok_or_else(|| bad_date("to"))
+        })
+        .transpose()?;
+    Ok(SearchFilter {
+        query: params.q.trim().to_owned(),
+        source: non_blank(&params.source).and_then(SearchSource::parse),
+        user_id: non_blank(&params.user).map(UserId::new),
+        model: non_blank(&params.model).map(str::to_owned),
+        from: from.map(day_start),
+        to: to.map(day_start),
+        outcome: non_blank(&params.outcome)
+            .filter(|o| OUTCOMES.contains(o))
+            .map(str::to_owned),
+    })
+}
+
+fn page_url(params: &FullTextSearchParams, page: i64) -> String {
+    let fields = [
+        ("q", &params.q),
+        ("source", &params.source),
+        ("user", &params.user),
+        ("model", &params.model),
+        ("outcome", &params.outcome),
+        ("from", &params.from),
+        ("to", &params.to),
+    ];
+    let mut url = format!("/admin/search?page={page}");
+    for (name, value) in fields {
+        if !value.trim().is_empty() {
+            url.push_str(&format!("&{name}={}", urlencoding::encode(value.trim())));
+        }
+    }
+    url
+}
+
+fn hit_view(hit: SearchHit) -> SearchHitView {
+    let snippet = snippet_from_headline(&hit.headline);
+    let source = SearchSource::parse(&hit.source).unwrap_or(SearchSource::Transcript);
+    SearchHitView {
+        source_label: source.label(),
+        session_url: hit.session_id.as_ref().map(session_detail_url),
+        session_id: hit.session_id,
+        request_url: hit.ai_request_id.as_ref().map(request_detail_url),
+        request_id: hit.ai_request_id,
+        user_id: hit.user_id,
+        model: hit.model,
+        role: hit.role,
+        turn: hit.ordinal.filter(|_| source != SearchSource::Analysis),
+        occurred_at: hit.occurred_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+        segments: snippet.segments,
+        redacted: snippet.redacted,
+    }
+}
+
+pub(crate) async fn search_page(
+    Extension(user_ctx): Extension<UserContext>,
+    Extension(mkt_ctx): Extension<MarketplaceContext>,
+    Extension(engine): Extension<AdminTemplateEngine>,
+    State(pool): State<Arc<PgPool>>,
+    Query(params): Query<FullTextSearchParams>,
+) -> AdminHtmlResult<Response> {
+    if !user_ctx.can_audit() {
+        return Err(AdminError::Forbidden("Admin or auditor role required".to_owned()).into());
+    }
+
+    let filter = filter_from_params(&params)?;
+    let page_number = params.page.unwrap_or(1).max(1);
+    let searched = !filter.query.is_empty();
+    let mut hits = if searched {
+        list_search_hits(&pool, &filter, PAGE_SIZE + 1, (page_number - 1) * PAGE_SIZE).await?
+    } else {
+        Vec::new()
+    };
+    let has_next = i64::try_from(hits.len()).unwrap_or(i64::MAX) > PAGE_SIZE;
+    hits.truncate(usize::try_from(PAGE_SIZE).unwrap_or(usize::MAX));
+
+    let sources = SearchSource::ALL
+        .into_iter()
+        .map(|s| FilterOptionView {
+            value: s.as_str(),
+            label: s.label(),
+            selected: filter.source == Some(s),
+        })
+        .collect();
+    let outcomes = OUTCOMES
+        .into_iter()
+        .map(|o| FilterOptionView {
+            value: o,
+            label: o,
+            selected: filter.outcome.as_deref() == Some(o),
+        })
+        .collect();
+    let ctx = SearchPageContext {
+        page: "search",
+        title: "Search",
+        hero_title: "Search",
+        hero_subtitle: "Find who sent or received a string: transcript turns, gateway messages and session analyses.",
+        sources,
+        outcomes,
+        searched,
+        hits: hits.into_iter().map(hit_view).collect(),
+        page_number,
+        prev_url: (page_number > 1).then(|| page_url(&params, page_number - 1)),
+        next_url: has_next.then(|| page_url(&params, page_number + 1)),
+        params: &params,
+    };
+
+    Ok(super::render_typed_page(
+        &engine, "search", &ctx, &user_ctx, &mkt_ctx,
+    ))
+}
@@ -41,7 +41,7 @@ static MARKETPLACE_PARENT_CACHE: LazyLock<RwLock<Option<CachedMarketplaceParents
     LazyLock::new(|| RwLock::new(None));
 const MARKETPLACE_PARENT_TTL: Duration = Duration::from_mins(5);
 
-async fn marketplace_parent_entries(
+pub(crate) async fn marketplace_parent_entries(
     repo: &AccessControlRepository,
 ) -> Vec<(EntityRef, Vec<AccessRule>, Option<bool>)> {
     {
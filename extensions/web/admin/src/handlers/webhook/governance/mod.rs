@@ -10,6 +10,6 @@ mod handler;
 mod scope;
 mod types;
 
-pub(crate) use authz::govern_authz;
+pub(crate) use authz::{govern_authz, marketplace_parent_entries};
 pub(crate) use engine::engine;
 pub(crate) use handler::govern_tool_use;
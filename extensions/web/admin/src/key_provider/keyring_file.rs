@@ -0,0 +1,185 @@
+//! The keyring file named by `ENCRYPTION_KEYRING_FILE`: local master keys,
+//! and the providers that held earlier ones.
+
+use std::fmt;
+use std::path::Path;
+use std::sync::Arc;
+
+use serde::Deserialize;
+
+use super::vault_transit::DEFAULT_MOUNT;
+use super::{KeyProvider, Pkcs11Key, VaultTransit, required};
+use crate::repositories::secrets::secret_crypto::{self, SecretCryptoError};
+
+/// The contents of a keyring file.
+pub struct KeyringFile {
+    pub current: Option<[u8; 32]>,
+    pub previous: Vec<[u8; 32]>,
+    pub previous_providers: Vec<ProviderSpec>,
+}
+
+// Why: key material must never reach a log line through `{:?}`.
+impl fmt::Debug for KeyringFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KeyringFile")
+            .field("current", &self.current.is_some())
+            .field("previous", &self.previous.len())
+            .field("previous_providers", &self.previous_providers)
+            .finish()
+    }
+}
+
+/// A provider that held an earlier master key, as listed under `previous`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(tag = "provider", rename_all = "snake_case", deny_unknown_fields)]
+pub enum ProviderSpec {
+    VaultTransit {
+        addr: String,
+        #[serde(default)]
+        mount: Option<String>,
+        key: String,
+        #[serde(default)]
+        namespace: Option<String>,
+        #[serde(default = "default_token_setting")]
+        token_setting: String,
+    },
+    Pkcs11 {
+        module: String,
+        token_label: String,
+        key_label: String,
+        #[serde(default = "default_pin_setting")]
+        pin_setting: String,
+    },
+}
+
+impl ProviderSpec {
+    /// Build the provider, reading its token or PIN with `lookup`.
+    pub fn build(
+        &self,
+        lookup: &dyn Fn(&str) -> Option<String>,
+    ) -> Result<Arc<dyn KeyProvider>, SecretCryptoError> {
+        Ok(match self {
+            Self::VaultTransit {
+                addr,
+                mount,
+                key,
+                namespace,
+                token_setting,
+            } => Arc::new(VaultTransit::new(
+                addr,
+                required(lookup, token_setting)?,
+                mount.as_deref().unwrap_or(DEFAULT_MOUNT),
+                key,
+                namespace.clone(),
+            )?),
+            Self::Pkcs11 {
+                module,
+                token_label,
+                key_label,
+                pin_setting,
+            } => Arc::new(Pkcs11Key::new(
+                module.clone(),
+                token_label.clone(),
+                required(lookup, pin_setting)?,
+                key_label.clone(),
+            )?),
+        })
+    }
+}
+
+fn default_token_setting() -> String {
+    "VAULT_TOKEN".to_owned()
+}
+
+fn default_pin_setting() -> String {
+    "PKCS11_PIN".to_owned()
+}
+
+// Why: `previous` mixes hex keys and provider mappings; parsing each entry on
+// its own names the field at fault instead of "no variant matched".
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawKeyringFile {
+    #[serde(default)]
+    current: Option<String>,
+    #[serde(default)]
+    previous: Vec<serde_yaml::Value>,
+}
+
+/// Read a keyring file. The file must not be readable by group or others.
+///
+/// ```yaml
+/// current: <hex key>            # required by the `file` provider
+/// previous:
+///   - <hex key>
+///   - provider: vault_transit
+///     addr: https://vault-old.internal:8200
+///     key: dek                  # mount, namespace and token_setting optional
+///   - provider: pkcs11
+///     module: /usr/lib/softhsm/libsofthsm2.so
+///     token_label: sp
+///     key_label: sp-master-2025 # pin_setting optional
+/// ```
+///
+/// A previous provider stays available to unwrap the DEKs it wrapped, so
+/// moving off a Transit key or an HSM key is an ordinary rotation. The file
+/// holds no tokens or PINs: `token_setting` and `pin_setting` name the setting
+/// they are read from, `VAULT_TOKEN` and `PKCS11_PIN` by default.
+pub fn load_keyring_file(path: &Path) -> Result<KeyringFile, SecretCryptoError> {
+    let unreadable = |e: &dyn fmt::Display| {
+        SecretCryptoError::KeyProvider(format!("Cannot read keyring file {}: {e}", path.display()))
+    };
+    reject_shared_permissions(path)?;
+    let text = std::fs::read_to_string(path).map_err(|e| unreadable(&e))?;
+    let raw: RawKeyringFile = serde_yaml::from_str(&text).map_err(|e| unreadable(&e))?;
+    let mut file = KeyringFile {
+        current: raw
+            .current
+            .as_deref()
+            .map(secret_crypto::parse_master_key)
+            .transpose()?,
+        previous: Vec::new(),
+        previous_providers: Vec::new(),
+    };
+    for (index, entry) in raw.previous.into_iter().enumerate() {
+        match entry {
+            serde_yaml::Value::String(hex_key) => {
+                file.previous
+                    .push(secret_crypto::parse_master_key(&hex_key)?);
+            },
+            spec => file.previous_providers.push(
+                serde_yaml::from_value(spec)
+                    .map_err(|e| unreadable(&format_args!("previous[{index}]: {e}")))?,
+            ),
+        }
+    }
+    Ok(file)
+}
+
+#[cfg(unix)]
+fn reject_shared_permissions(path: &Path) -> Result<(), SecretCryptoError> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mode = std::fs::metadata(path)
+        .map_err(|e| {
+            SecretCryptoError::KeyProvider(format!(
+                "Cannot read keyring file {}: {e}",
+                path.display()
+            ))
+        })?
+        .permissions()
+        .mode();
+    if mode & 0o077 != 0 {
+        return Err(SecretCryptoError::KeyProvider(format!(
+            "Keyring file {} must not be accessible to group or others (mode {:o})",
+            path.display(),
+            mode & 0o777
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn reject_shared_permissions(_path: &Path) -> Result<(), SecretCryptoError> {
+    Ok(())
+}
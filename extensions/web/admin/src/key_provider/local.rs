@@ -0,0 +1,62 @@
+//! Master keys held in process memory, from the environment or a keyring
+//! file.
+
+use std::fmt;
+
+use super::{KeyProvider, WrappedDek};
+use crate::repositories::secrets::secret_crypto::{self, SecretCryptoError};
+use crate::repositories::secrets::secret_keyring::master_key_id;
+
+/// A master key in memory, wrapping with ChaCha20-Poly1305 under a fresh
+/// nonce per DEK.
+#[derive(Clone)]
+pub struct LocalKey {
+    id: String,
+    key: [u8; 32],
+}
+
+impl LocalKey {
+    #[must_use]
+    pub fn new(key: [u8; 32]) -> Self {
+        Self {
+            id: master_key_id(&key),
+            key,
+        }
+    }
+}
+
+// Why: key material must never reach a log line through `{:?}`.
+impl fmt::Debug for LocalKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LocalKey")
+            .field("id", &self.id)
+            .finish_non_exhaustive()
+    }
+}
+
+#[async_trait::async_trait]
+impl KeyProvider for LocalKey {
+    fn key_id(&self) -> &str {
+        &self.id
+    }
+
+    async fn wrap(&self, dek: &[u8; 32]) -> Result<WrappedDek, SecretCryptoError> {
+        let nonce = secret_crypto::generate_nonce();
+        let ciphertext = secret_crypto::encrypt(&self.key, &nonce, dek)?;
+        Ok(WrappedDek {
+            key_id: self.id.clone(),
+            nonce: nonce.to_vec(),
+            ciphertext,
+        })
+    }
+
+    async fn unwrap(&self, nonce: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>, SecretCryptoError> {
+        let nonce: [u8; 12] = nonce
+            .try_into()
+            .map_err(|e: std::array::TryFromSliceError| {
+                tracing::warn!(error = %e, "Stored DEK nonce is not 12 bytes");
+                SecretCryptoError::InvalidKeyMaterial
+            })?;
+        secret_crypto::decrypt(&self.key, &nonce, ciphertext)
+    }
+}
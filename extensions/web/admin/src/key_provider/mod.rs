@@ -0,0 +1,146 @@
+//! Where master keys live, behind one [`KeyProvider`] interface.
+//!
+//! A provider wraps and unwraps data encryption keys under one master key it
+//! names with a stable id, the id recorded against each DEK it wraps.
+//! `ENCRYPTION_KEY_PROVIDER` chooses the current provider:
+//!
+//! - `env` (the default) — [`LocalKey`] from `ENCRYPTION_MASTER_KEY`.
+//! - `file` — a [`LocalKey`], the `current` key of the keyring file.
+//! - `vault_transit` — [`VaultTransit`], a Vault Transit-compatible HTTP API.
+//! - `pkcs11` — [`Pkcs11Key`], an AES key held in an HSM such as `SoftHSM`.
+//!
+//! The local providers hold the key in process memory. With `vault_transit`
+//! and `pkcs11` the key never leaves the service or token, and only the DEK
+//! crosses the boundary.
+//!
+//! Every setting is read as an environment variable or, failing that, as the
+//! secret of the same name in lower case. Whatever the provider, the keys that
+//! are not current stay available to unwrap, so moving to a new provider is an
+//! ordinary master key rotation. Those are the local keys in
+//! `ENCRYPTION_MASTER_KEY` and `ENCRYPTION_MASTER_KEY_PREVIOUS`, and the local
+//! keys and earlier Transit or PKCS#11 keys listed in the keyring file named
+//! by `ENCRYPTION_KEYRING_FILE` (see [`load_keyring_file`]).
+
+mod keyring_file;
+mod local;
+mod pkcs11;
+mod vault_transit;
+
+use std::fmt;
+use std::sync::Arc;
+
+pub use keyring_file::{KeyringFile, ProviderSpec, load_keyring_file};
+pub use local::LocalKey;
+pub use pkcs11::Pkcs11Key;
+pub use vault_transit::VaultTransit;
+
+use crate::repositories::secrets::secret_crypto::{self, SecretCryptoError};
+
+/// A DEK sealed under a master key, with the id of the key that sealed it.
+/// `nonce` is empty for providers whose ciphertext carries its own.
+#[derive(Debug, Clone)]
+pub struct WrappedDek {
+    pub key_id: String,
+    pub nonce: Vec<u8>,
+    pub ciphertext: Vec<u8>,
+}
+
+#[async_trait::async_trait]
+pub trait KeyProvider: fmt::Debug + Send + Sync {
+    /// The id recorded against every DEK this provider wraps.
+    fn key_id(&self) -> &str;
+
+    async fn wrap(&self, dek: &[u8; 32]) -> Result<WrappedDek, SecretCryptoError>;
+
+    /// The plaintext of a DEK this provider wrapped.
+    async fn unwrap(&self, nonce: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>, SecretCryptoError>;
+}
+
+/// The configured current provider and the earlier ones kept beside it.
+#[derive(Debug)]
+pub struct ConfiguredProviders {
+    pub current: Arc<dyn KeyProvider>,
+    pub previous: Vec<Arc<dyn KeyProvider>>,
+}
+
+/// Build the providers from settings looked up by name with `lookup`.
+pub fn configured_providers(
+    lookup: &dyn Fn(&str) -> Option<String>,
+) -> Result<ConfiguredProviders, SecretCryptoError> {
+    let env_key = lookup("ENCRYPTION_MASTER_KEY")
+        .map(|hex_key| secret_crypto::parse_master_key(&hex_key))
+        .transpose()?;
+    let mut previous = lookup("ENCRYPTION_MASTER_KEY_PREVIOUS")
+        .map(|list| {
+            list.split(',')
+                .map(str::trim)
+                .filter(|hex_key| !hex_key.is_empty())
+                .map(secret_crypto::parse_master_key)
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .transpose()?
+        .unwrap_or_default();
+
+    let file = lookup("ENCRYPTION_KEYRING_FILE")
+        .map(|path| path.trim().to_owned())
+        .filter(|path| !path.is_empty())
+        .map(|path| load_keyring_file(path.as_ref()))
+        .transpose()?;
+    let (file_current, previous_providers) = match file {
+        Some(file) => {
+            previous.splice(0..0, file.previous);
+            (file.current, file.previous_providers)
+        },
+        None => (None, Vec::new()),
+    };
+
+    let provider = lookup("ENCRYPTION_KEY_PROVIDER").unwrap_or_else(|| "env".to_owned());
+    let current: Arc<dyn KeyProvider> = match provider.trim() {
+        "env" => {
+            previous.extend(file_current);
+            Arc::new(LocalKey::new(
+                env_key.ok_or(SecretCryptoError::MasterKeyMissing)?,
+            ))
+        },
+        "file" => {
+            required(lookup, "ENCRYPTION_KEYRING_FILE")?;
+            previous.extend(env_key);
+            Arc::new(LocalKey::new(file_current.ok_or_else(|| {
+                SecretCryptoError::KeyProvider(
+                    "The keyring file has no current key for the file provider".to_owned(),
+                )
+            })?))
+        },
+        "vault_transit" => {
+            previous.extend(file_current.into_iter().chain(env_key));
+            Arc::new(VaultTransit::from_settings(lookup)?)
+        },
+        "pkcs11" => {
+            previous.extend(file_current.into_iter().chain(env_key));
+            Arc::new(Pkcs11Key::from_settings(lookup)?)
+        },
+        other => {
+            return Err(SecretCryptoError::KeyProvider(format!(
+                "Unknown ENCRYPTION_KEY_PROVIDER '{other}'"
+            )));
+        },
+    };
+    let mut previous: Vec<Arc<dyn KeyProvider>> = previous
+        .into_iter()
+        .map(|key| -> Arc<dyn KeyProvider> { Arc::new(LocalKey::new(key)) })
+        .collect();
+    for spec in &previous_providers {
+        previous.push(spec.build(lookup)?);
+    }
+    Ok(ConfiguredProviders { current, previous })
+}
+
+fn required(
+    lookup: &dyn Fn(&str) -> Option<String>,
+    name: &str,
+) -> Result<String, SecretCryptoError> {
+    lookup(name)
+        .map(|value| value.trim().to_owned())
+        .filter(|value| !value.is_empty())
+        .ok_or_else(|| SecretCryptoError::KeyProvider(format!("{name} is not set")))
+}
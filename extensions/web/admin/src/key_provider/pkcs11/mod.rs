@@ -0,0 +1,140 @@
+//! A master key held as an AES key in a PKCS#11 token, such as `SoftHSM` or a
+//! network HSM.
+//!
+//! DEKs are sealed inside the token with AES-GCM under a fresh 96-bit IV,
+//! stored as the DEK's nonce; the key itself cannot leave the token. The key
+//! id carries the key's fingerprint, its check value or else its `CKA_ID`, so
+//! a key replaced under the same label gets a new id. Calls into the module
+//! block, so wraps and unwraps run on the blocking pool; the module is loaded
+//! through `cryptoki` once per process.
+
+mod token;
+
+use std::fmt;
+
+use super::{KeyProvider, WrappedDek, required};
+use crate::repositories::secrets::secret_crypto::{self, SecretCryptoError};
+
+/// An AES key, found by label, in the token with a given label.
+pub struct Pkcs11Key {
+    id: String,
+    fingerprint: String,
+    module_path: String,
+    token_label: String,
+    pin: String,
+    key_label: String,
+}
+
+impl Pkcs11Key {
+    /// Opens the token once to read the key's fingerprint for its id,
+    /// `pkcs11:{token}/{label}#{fingerprint}`.
+    pub fn new(
+        module_path: String,
+        token_label: String,
+        pin: String,
+        key_label: String,
+    ) -> Result<Self, SecretCryptoError> {
+        let fingerprint = token::Token::open(&module_path, &token_label, &pin, &key_label)
+            .and_then(|token| token.fingerprint())
+            .map_err(|e| {
+                SecretCryptoError::KeyProvider(format!(
+                    "PKCS#11 pkcs11:{token_label}/{key_label}: {e}"
+                ))
+            })?;
+        Ok(Self {
+            id: format!("pkcs11:{token_label}/{key_label}#{fingerprint}"),
+            fingerprint,
+            module_path,
+            token_label,
+            pin,
+            key_label,
+        })
+    }
+
+    /// Reads `PKCS11_MODULE` (the module's path), `PKCS11_TOKEN_LABEL`,
+    /// `PKCS11_PIN` and `PKCS11_KEY_LABEL`.
+    pub fn from_settings(
+        lookup: &dyn Fn(&str) -> Option<String>,
+    ) -> Result<Self, SecretCryptoError> {
+        Self::new(
+            required(lookup, "PKCS11_MODULE")?,
+            required(lookup, "PKCS11_TOKEN_LABEL")?,
+            required(lookup, "PKCS11_PIN")?,
+            required(lookup, "PKCS11_KEY_LABEL")?,
+        )
+    }
+
+    async fn run<T: Send + 'static>(
+        &self,
+        op: impl FnOnce(&token::Token) -> Result<T, String> + Send + 'static,
+    ) -> Result<T, SecretCryptoError> {
+        let (module_path, token_label, pin, key_label) = (
+            self.module_path.clone(),
+            self.token_label.clone(),
+            self.pin.clone(),
+            self.key_label.clone(),
+        );
+        let result = tokio::task::spawn_blocking(move || {
+            op(&token::Token::open(
+                &module_path,
+                &token_label,
+                &pin,
+                &key_label,
+            )?)
+        })
+        .await
+        .map_err(|e| SecretCryptoError::KeyProvider(format!("PKCS#11 {}: {e}", self.id)))?;
+        result.map_err(|e| SecretCryptoError::KeyProvider(format!("PKCS#11 {}: {e}", self.id)))
+    }
+}
+
+// Why: the PIN must never reach a log line through `{:?}`.
+impl fmt::Debug for Pkcs11Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Pkcs11Key")
+            .field("id", &self.id)
+            .field("module_path", &self.module_path)
+            .finish_non_exhaustive()
+    }
+}
+
+#[async_trait::async_trait]
+impl KeyProvider for Pkcs11Key {
+    fn key_id(&self) -> &str {
+        &self.id
+    }
+
+    async fn wrap(&self, dek: &[u8; 32]) -> Result<WrappedDek, SecretCryptoError> {
+        let iv = secret_crypto::generate_nonce();
+        let dek = *dek;
+        let fingerprint = self.fingerprint.clone();
+        let ciphertext = self
+            .run(move |token| {
+                // Why: the id stored with the DEK must name the key that
+                // sealed it, and it was fixed when the provider was built.
+                if token.fingerprint()? != fingerprint {
+                    return Err(
+                        "the key under this label was replaced; reload the keyring".to_owned()
+                    );
+                }
+                token.encrypt(&iv, &dek)
+            })
+            .await?;
+        Ok(WrappedDek {
+            key_id: self.id.clone(),
+            nonce: iv.to_vec(),
+            ciphertext,
+        })
+    }
+
+    async fn unwrap(&self, nonce: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>, SecretCryptoError> {
+        let iv: [u8; 12] = nonce
+            .try_into()
+            .map_err(|e: std::array::TryFromSliceError| {
+                tracing::warn!(error = %e, "Stored DEK nonce is not a 12-byte IV");
+                SecretCryptoError::InvalidKeyMaterial
+            })?;
+        let ciphertext = ciphertext.to_vec();
+        self.run(move |token| token.decrypt(&iv, &ciphertext)).await
+    }
+}
@@ -0,0 +1,122 @@
+//! Opening a session on a PKCS#11 token, finding the master key in it, and
+//! AES-GCM in both directions, over the `cryptoki` bindings.
+//!
+//! Each module is loaded and initialised once per process with OS locking, so
+//! concurrent sessions need no lock of ours.
+
+use std::sync::Mutex;
+
+use cryptoki::context::{CInitializeArgs, CInitializeFlags, Pkcs11};
+use cryptoki::error::{Error, RvError};
+use cryptoki::mechanism::Mechanism;
+use cryptoki::mechanism::aead::GcmParams;
+use cryptoki::object::{Attribute, AttributeType, KeyType, ObjectClass, ObjectHandle};
+use cryptoki::session::{Session, UserType};
+use cryptoki::types::AuthPin;
+
+pub(super) struct Token {
+    session: Session,
+    key: ObjectHandle,
+}
+
+impl Token {
+    // Why: login state is per application, not per session, so a second
+    // session finds the user already logged in.
+    pub(super) fn open(
+        module_path: &str,
+        token_label: &str,
+        pin: &str,
+        key_label: &str,
+    ) -> Result<Self, String> {
+        let context = context(module_path)?;
+        let slot = context
+            .get_slots_with_token()
+            .map_err(|e| format!("list slots: {e}"))?
+            .into_iter()
+            .find(|slot| {
+                context
+                    .get_token_info(*slot)
+                    .is_ok_and(|info| info.label().trim_end() == token_label)
+            })
+            .ok_or_else(|| format!("no token labelled '{token_label}'"))?;
+        let session = context
+            .open_ro_session(slot)
+            .map_err(|e| format!("open session: {e}"))?;
+        match session.login(UserType::User, Some(&AuthPin::new(pin.into()))) {
+            Ok(()) | Err(Error::Pkcs11(RvError::UserAlreadyLoggedIn, _)) => {},
+            Err(e) => return Err(format!("log in: {e}")),
+        }
+        let key = session
+            .find_objects(&[
+                Attribute::Class(ObjectClass::SECRET_KEY),
+                Attribute::KeyType(KeyType::AES),
+                Attribute::Label(key_label.as_bytes().to_vec()),
+            ])
+            .map_err(|e| format!("find key: {e}"))?
+            .into_iter()
+            .next()
+            .ok_or_else(|| format!("no AES key labelled '{key_label}'"))?;
+        Ok(Self { session, key })
+    }
+
+    // Why: the check value is a KCV of the key itself; `CKA_ID` stands in on
+    // tokens that keep none, and attributes come back in the order asked.
+    pub(super) fn fingerprint(&self) -> Result<String, String> {
+        self.session
+            .get_attributes(self.key, &[AttributeType::CheckValue, AttributeType::Id])
+            .map_err(|e| format!("read key attributes: {e}"))?
+            .into_iter()
+            .find_map(|attribute| match attribute {
+                Attribute::CheckValue(value) | Attribute::Id(value) if !value.is_empty() => {
+                    Some(hex::encode(value))
+                },
+                _ => None,
+            })
+            .ok_or_else(|| {
+                "the key has neither CKA_CHECK_VALUE nor CKA_ID to fingerprint it by".to_owned()
+            })
+    }
+
+    pub(super) fn encrypt(&self, iv: &[u8; 12], data: &[u8]) -> Result<Vec<u8>, String> {
+        let mut iv = *iv;
+        let mechanism = gcm(&mut iv)?;
+        self.session
+            .encrypt(&mechanism, self.key, data)
+            .map_err(|e| format!("encrypt: {e}"))
+    }
+
+    pub(super) fn decrypt(&self, iv: &[u8; 12], data: &[u8]) -> Result<Vec<u8>, String> {
+        let mut iv = *iv;
+        let mechanism = gcm(&mut iv)?;
+        self.session
+            .decrypt(&mechanism, self.key, data)
+            .map_err(|e| format!("decrypt: {e}"))
+    }
+}
+
+// Why: a 128-bit tag, matching the local provider's AES-GCM.
+fn gcm(iv: &mut [u8; 12]) -> Result<Mechanism<'_>, String> {
+    GcmParams::new(iv, &[], 128.into())
+        .map(Mechanism::AesGcm)
+        .map_err(|e| format!("GCM parameters: {e}"))
+}
+
+// Why: `C_Initialize` may run once per module per process; a context another
+// caller already initialised is still usable.
+fn context(path: &str) -> Result<Pkcs11, String> {
+    static CONTEXTS: Mutex<Vec<(String, Pkcs11)>> = Mutex::new(Vec::new());
+    let mut contexts = CONTEXTS
+        .lock()
+        .map_err(|e| format!("PKCS#11 lock poisoned: {e}"))?;
+    if let Some((_, context)) = contexts.iter().find(|(loaded, _)| loaded == path) {
+        return Ok(context.clone());
+    }
+    let context = Pkcs11::new(path).map_err(|e| format!("load {path}: {e}"))?;
+    match context.initialize(CInitializeArgs::new(CInitializeFlags::OS_LOCKING_OK)) {
+        Ok(()) | Err(Error::Pkcs11(RvError::CryptokiAlreadyInitialized, _)) => {},
+        Err(e) => return Err(format!("initialise {path}: {e}")),
+    }
+    contexts.push((path.to_owned(), context.clone()));
+    drop(contexts);
+    Ok(context)
+}
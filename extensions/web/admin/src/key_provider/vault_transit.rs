@@ -0,0 +1,165 @@
+//! A master key held by a Vault Transit-compatible service.
+//!
+//! DEKs are sent to `POST /v1/{mount}/encrypt/{key}` and the returned
+//! `vault:v…:` ciphertext is stored as is; Transit carries its own nonce and
+//! key version inside it, so the stored nonce is empty. Unwrapping is the
+//! matching `decrypt` call. Rotating the key inside Transit keeps the same key
+//! id here: Transit still opens ciphertext from the older versions.
+
+use std::fmt;
+use std::time::Duration;
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+
+use super::{KeyProvider, WrappedDek, required};
+use crate::repositories::secrets::secret_crypto::SecretCryptoError;
+
+pub(super) const DEFAULT_MOUNT: &str = "transit";
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Deserialize)]
+struct TransitResponse<T> {
+    data: T,
+}
+
+#[derive(Deserialize)]
+struct EncryptData {
+    ciphertext: String,
+}
+
+#[derive(Deserialize)]
+struct DecryptData {
+    plaintext: String,
+}
+
+/// A Transit key reached over HTTP with a token.
+pub struct VaultTransit {
+    id: String,
+    encrypt_url: String,
+    decrypt_url: String,
+    token: String,
+    namespace: Option<String>,
+    client: reqwest::Client,
+}
+
+impl VaultTransit {
+    /// A provider for key `key` under the Transit engine mounted at `mount` on
+    /// the server at `addr`. Its id,
+    /// `vault-transit:{addr}/{namespace/}{mount}/{key}`, names the cluster,
+    /// so equally named keys on two clusters stay apart.
+    pub fn new(
+        addr: &str,
+        token: String,
+        mount: &str,
+        key: &str,
+        namespace: Option<String>,
+    ) -> Result<Self, SecretCryptoError> {
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .map_err(|e| SecretCryptoError::KeyProvider(format!("Vault client: {e}")))?;
+        let (addr, mount) = (addr.trim_end_matches('/'), mount.trim_matches('/'));
+        let base = format!("{addr}/v1/{mount}");
+        let scope = namespace
+            .as_deref()
+            .map(|ns| format!("{}/", ns.trim_matches('/')))
+            .unwrap_or_default();
+        Ok(Self {
+            id: format!("vault-transit:{addr}/{scope}{mount}/{key}"),
+            encrypt_url: format!("{base}/encrypt/{key}"),
+            decrypt_url: format!("{base}/decrypt/{key}"),
+            token,
+            namespace,
+            client,
+        })
+    }
+
+    /// Reads `VAULT_ADDR`, `VAULT_TOKEN`, `VAULT_TRANSIT_KEY`, and the
+    /// optional `VAULT_TRANSIT_MOUNT` and `VAULT_NAMESPACE`.
+    pub fn from_settings(
+        lookup: &dyn Fn(&str) -> Option<String>,
+    ) -> Result<Self, SecretCryptoError> {
+        let mount = lookup("VAULT_TRANSIT_MOUNT").unwrap_or_else(|| DEFAULT_MOUNT.to_owned());
+        Self::new(
+            &required(lookup, "VAULT_ADDR")?,
+            required(lookup, "VAULT_TOKEN")?,
+            mount.trim(),
+            &required(lookup, "VAULT_TRANSIT_KEY")?,
+            lookup("VAULT_NAMESPACE").filter(|ns| !ns.trim().is_empty()),
+        )
+    }
+
+    async fn call<T: DeserializeOwned>(
+        &self,
+        url: &str,
+        body: serde_json::Value,
+    ) -> Result<T, SecretCryptoError> {
+        let failed = |e: &dyn fmt::Display| {
+            SecretCryptoError::KeyProvider(format!("Vault Transit {}: {e}", self.id))
+        };
+        let mut request = self
+            .client
+            .post(url)
+            .header("X-Vault-Token", &self.token)
+            .json(&body);
+        if let Some(namespace) = &self.namespace {
+            request = request.header("X-Vault-Namespace", namespace);
+        }
+        let response = request.send().await.map_err(|e| failed(&e))?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(failed(&format!("returned {status}")));
+        }
+        let body: TransitResponse<T> = response.json().await.map_err(|e| failed(&e))?;
+        Ok(body.data)
+    }
+}
+
+// Why: the token must never reach a log line through `{:?}`.
+impl fmt::Debug for VaultTransit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VaultTransit")
+            .field("id", &self.id)
+            .field("encrypt_url", &self.encrypt_url)
+            .finish_non_exhaustive()
+    }
+}
+
+#[async_trait::async_trait]
+impl KeyProvider for VaultTransit {
+    fn key_id(&self) -> &str {
+        &self.id
+    }
+
+    async fn wrap(&self, dek: &[u8; 32]) -> Result<WrappedDek, SecretCryptoError> {
+        let data: EncryptData = self
+            .call(
+                &self.encrypt_url,
+                serde_json::json!({ "plaintext": STANDARD.encode(dek) }),
+            )
+            .await?;
+        Ok(WrappedDek {
+            key_id: self.id.clone(),
+            nonce: Vec::new(),
+            ciphertext: data.ciphertext.into_bytes(),
+        })
+    }
+
+    async fn unwrap(&self, _nonce: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>, SecretCryptoError> {
+        let ciphertext = std::str::from_utf8(ciphertext).map_err(|e| {
+            SecretCryptoError::DecryptionFailed(format!("not a Transit ciphertext: {e}"))
+        })?;
+        let data: DecryptData = self
+            .call(
+                &self.decrypt_url,
+                serde_json::json!({ "ciphertext": ciphertext }),
+            )
+            .await?;
+        STANDARD
+            .decode(data.plaintext)
+            .map_err(|e| SecretCryptoError::DecryptionFailed(format!("Transit plaintext: {e}")))
+    }
+}
@@ -10,6 +10,9 @@
 //!   statusline/transcript ingest).
 //! - [`secrets_router`], [`share_manifest_router`] — per-plugin secret
 //!   resolution and public manifest sharing.
+//! - [`scim_router`] — SCIM 2.0 user and group provisioning from an identity
+//!   provider.
+//! - [`metrics_router`] — the Prometheus scrape target at `/metrics`.
 //!
 //! [`repositories`] owns every `sqlx` call; handlers/services never touch
 //! the DB directly. Errors normalise on `error::MarketplaceError` via the
@@ -18,28 +21,42 @@
 pub mod activity;
 pub mod assets;
 pub mod audit_event_bus;
-pub mod authz;
+pub mod chargeback;
+pub mod cohorts;
+pub mod cost_watch;
+pub mod digests;
+pub mod erasure;
 pub mod error;
 pub mod event_hub;
+pub mod gateway_cache;
+pub mod gateway_routing;
 pub mod gateway_safety;
 pub(crate) mod handlers;
-pub mod marketplace_filter;
+pub mod key_provider;
+pub mod master_key_rotation;
+pub mod metrics;
 mod middleware;
 pub mod numeric;
+pub mod otlp;
 pub mod repositories;
+pub mod retention;
 mod routes;
+pub mod search;
+pub mod secret_lifecycle;
 pub(crate) mod services;
 pub mod templates;
 pub mod types;
 pub mod util;
+pub mod warehouse;
 
 use std::sync::Arc;
 
-use axum::routing::{get, post};
+use axum::routing::{get, post, put};
 use axum::{Extension, Router, middleware as axum_middleware};
 use sqlx::PgPool;
 
 pub use routes::admin_ssr_router;
+pub use systemprompt_web_access::{authz, marketplace_filter};
 pub use types::{CreateUserRequest, MarketplaceContext, UserContext, UserSummary, UserUsageEvent};
 
 pub mod test_support {
@@ -50,6 +67,12 @@ pub mod test_support {
     };
     pub use crate::handlers::hooks_track::session_summary::GeneratedSessionSummary;
     pub use crate::handlers::resolve_principal;
+    pub use crate::services::scim::error::ScimError;
+    pub use crate::services::scim::filter::{ScimFilter, parse_filter};
+    pub use crate::services::scim::patch::{
+        ScimGroupPatch, ScimUserPatch, interpret_group_patch, interpret_user_patch,
+    };
+    pub use crate::services::secret_service::set_secret_metadata;
 }
 
 pub fn hooks_webhook_router(
@@ -98,6 +121,48 @@ pub fn secrets_router(pool: Arc<PgPool>) -> Router {
             "/admin/api/secrets/{plugin_id}/rotate",
             post(handlers::secrets::rotate_handler),
         )
+        .route(
+            "/admin/api/secrets/{plugin_id}/{var_name}/metadata",
+            put(handlers::secrets::update_metadata_handler),
+        )
+        .with_state(pool)
+}
+
+pub fn scim_router(pool: Arc<PgPool>) -> Router {
+    use handlers::scim;
+    Router::new()
+        .route(
+            "/scim/v2/ServiceProviderConfig",
+            get(scim::service_provider_config_handler),
+        )
+        .route(
+            "/scim/v2/Users",
+            get(scim::list_users_handler).post(scim::create_user_handler),
+        )
+        .route(
+            "/scim/v2/Users/{id}",
+            get(scim::get_user_handler)
+                .put(scim::replace_user_handler)
+                .patch(scim::patch_user_handler)
+                .delete(scim::delete_user_handler),
+        )
+        .route(
+            "/scim/v2/Groups",
+            get(scim::list_groups_handler).post(scim::create_group_handler),
+        )
+        .route(
+            "/scim/v2/Groups/{id}",
+            get(scim::get_group_handler)
+                .put(scim::replace_group_handler)
+                .patch(scim::patch_group_handler)
+                .delete(scim::delete_group_handler),
+        )
+        .with_state(pool)
+}
+
+pub fn metrics_router(pool: Arc<PgPool>) -> Router {
+    Router::new()
+        .route("/metrics", get(handlers::metrics::metrics_handler))
         .with_state(pool)
 }
 
@@ -0,0 +1,267 @@
+//! Master key rotation: moving every data encryption key onto the current
+//! master key, then retiring the keys it replaced.
+//!
+//! An operator rotates by making the new key `ENCRYPTION_MASTER_KEY` and
+//! listing the old one in `ENCRYPTION_MASTER_KEY_PREVIOUS`, or by moving
+//! `ENCRYPTION_KEY_PROVIDER` to a KMS or HSM while the old key stays
+//! configured, locally or as a previous provider in the keyring file. The
+//! hourly `master_key_rotation` job then runs [`rotate`]. It re-wraps the DEKs
+//! not yet on the current key in batches, and counts its progress on the
+//! rotation's `master_key_rotations` row. A run is capped at
+//! [`RotationLimits::max_batches`]. A re-wrapped DEK no longer matches, so the
+//! next run resumes where the last one stopped, including after a crash.
+//!
+//! Once no DEK is left on an old key, the rotation completes and every other
+//! key is retired. A retired key is never used to open a DEK again, even if it
+//! is still configured, and can be removed from the configuration.
+//!
+//! Each step is written to `master_key_events`, which also feeds the
+//! audit-event bus. Master keys are meant to be rotated yearly, so a current
+//! key first seen more than [`ROTATION_INTERVAL_DAYS`] ago is flagged there,
+//! once, as overdue.
+
+use serde_json::json;
+use sqlx::PgPool;
+use systemprompt::identifiers::UserId;
+
+use crate::repositories::secrets::secret_crypto::SecretCryptoError;
+use crate::repositories::secrets::secret_keyring::MasterKeyring;
+use crate::repositories::secrets::secret_keys::rewrap_dek_batch;
+use crate::repositories::secrets::secret_master_keys::{
+    self as master_keys, MasterKeyEvent, MasterKeyRotation,
+};
+
+pub const ROTATION_INTERVAL_DAYS: i32 = 365;
+
+/// How much one run re-wraps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RotationLimits {
+    pub batch_size: i64,
+    pub max_batches: usize,
+}
+
+impl Default for RotationLimits {
+    fn default() -> Self {
+        Self {
+            batch_size: 100,
+            max_batches: 50,
+        }
+    }
+}
+
+/// What one run did.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RotationOutcome {
+    pub rotation_id: Option<String>,
+    pub rewrapped: u64,
+    pub failed: u64,
+    /// DEKs still off the current key when the run ended.
+    pub remaining: u64,
+    pub retired: Vec<String>,
+    pub flagged_overdue: bool,
+}
+
+struct Audit<'a> {
+    pool: &'a PgPool,
+    actor: &'a UserId,
+}
+
+impl Audit<'_> {
+    async fn record(
+        &self,
+        key_id: &str,
+        rotation_id: Option<&str>,
+        event: &str,
+        detail: serde_json::Value,
+    ) -> Result<(), SecretCryptoError> {
+        let event = MasterKeyEvent {
+            key_id,
+            rotation_id,
+            event,
+            detail,
+            actor: self.actor,
+        };
+        master_keys::insert_master_key_event(self.pool, &event).await?;
+        Ok(())
+    }
+}
+
+pub async fn rotate(
+    pool: &PgPool,
+    keyring: MasterKeyring,
+    actor: &UserId,
+    limits: RotationLimits,
+) -> Result<RotationOutcome, SecretCryptoError> {
+    let audit = Audit { pool, actor };
+    let current = keyring.current_id().to_owned();
+    register_keys(&audit, &keyring).await?;
+    let retired = master_keys::list_retired_master_key_ids(pool).await?;
+    let keyring = keyring.without(&retired);
+
+    let pending = count_pending(pool, &current).await?;
+    let mut outcome = RotationOutcome {
+        remaining: pending,
+        ..RotationOutcome::default()
+    };
+    let rotation = match master_keys::find_running_master_key_rotation(pool, &current).await? {
+        Some(rotation) => Some(rotation),
+        None if pending > 0 => Some(start(&audit, &keyring, pending).await?),
+        None => None,
+    };
+
+    if let Some(rotation) = &rotation {
+        outcome.rotation_id = Some(rotation.id.clone());
+        rewrap(&audit, &keyring, rotation, limits, &mut outcome).await?;
+        outcome.remaining = count_pending(pool, &current).await?;
+        if outcome.remaining == 0 {
+            master_keys::set_master_key_rotation_completed(pool, &rotation.id).await?;
+            let detail = json!({
+                "total_deks": rotation.total_deks,
+                "rewrapped": i64::from(rotation.rewrapped) + as_i64(outcome.rewrapped),
+            });
+            audit
+                .record(&current, Some(&rotation.id), "rotation_completed", detail)
+                .await?;
+        }
+    }
+
+    if outcome.remaining == 0 {
+        outcome.retired = master_keys::set_master_keys_retired(pool, &current).await?;
+        for key_id in &outcome.retired {
+            let detail = json!({ "replaced_by": current });
+            audit
+                .record(key_id, outcome.rotation_id.as_deref(), "retired", detail)
+                .await?;
+        }
+    }
+
+    outcome.flagged_overdue = flag_overdue(&audit, &current).await?;
+    Ok(outcome)
+}
+
+async fn register_keys(
+    audit: &Audit<'_>,
+    keyring: &MasterKeyring,
+) -> Result<(), SecretCryptoError> {
+    let current = keyring.current_id();
+    for key_id in std::iter::once(current).chain(keyring.previous_ids()) {
+        if master_keys::insert_master_key(audit.pool, key_id).await? {
+            let detail = json!({ "current": key_id == current });
+            audit.record(key_id, None, "registered", detail).await?;
+        }
+    }
+    Ok(())
+}
+
+async fn count_pending(pool: &PgPool, current: &str) -> Result<u64, SecretCryptoError> {
+    let count = master_keys::count_deks_off_master_key(pool, current).await?;
+    Ok(u64::try_from(count).unwrap_or(0))
+}
+
+async fn start(
+    audit: &Audit<'_>,
+    keyring: &MasterKeyring,
+    pending: u64,
+) -> Result<MasterKeyRotation, SecretCryptoError> {
+    let total = i32::try_from(pending).unwrap_or(i32::MAX);
+    let rotation =
+        master_keys::insert_master_key_rotation(audit.pool, keyring.current_id(), total).await?;
+    let previous: Vec<&str> = keyring.previous_ids().collect();
+    let detail = json!({ "total_deks": total, "previous_keys": previous });
+    audit
+        .record(
+            keyring.current_id(),
+            Some(&rotation.id),
+            "rotation_started",
+            detail,
+        )
+        .await?;
+    tracing::info!(
+        rotation_id = %rotation.id,
+        key_id = %keyring.current_id(),
+        total_deks = total,
+        "Master key rotation started"
+    );
+    Ok(rotation)
+}
+
+// Why: a DEK that fails is skipped for the rest of the run by the cursor, so
+// one unreadable key cannot stall the others; the next run retries it.
+async fn rewrap(
+    audit: &Audit<'_>,
+    keyring: &MasterKeyring,
+    rotation: &MasterKeyRotation,
+    limits: RotationLimits,
+    outcome: &mut RotationOutcome,
+) -> Result<(), SecretCryptoError> {
+    let current = keyring.current_id();
+    let mut cursor = String::new();
+    for _ in 0..limits.max_batches {
+        let batch = rewrap_dek_batch(audit.pool, keyring, &cursor, limits.batch_size).await?;
+        let Some(last_id) = batch.last_id else {
+            break;
+        };
+        cursor = last_id;
+        outcome.rewrapped += batch.rewrapped;
+        outcome.failed += as_u64(batch.failures.len());
+
+        let last_error = batch.failures.last().map(|f| f.error.as_str());
+        master_keys::update_master_key_rotation_progress(
+            audit.pool,
+            &rotation.id,
+            i32::try_from(batch.rewrapped).unwrap_or(i32::MAX),
+            i32::try_from(batch.failures.len()).unwrap_or(i32::MAX),
+            last_error,
+        )
+        .await?;
+        for failure in &batch.failures {
+            tracing::warn!(
+                rotation_id = %rotation.id,
+                dek_id = %failure.dek_id,
+                error = %failure.error,
+                "Failed to re-wrap a data encryption key"
+            );
+            let detail = json!({ "dek_id": failure.dek_id, "error": failure.error });
+            audit
+                .record(current, Some(&rotation.id), "rewrap_failed", detail)
+                .await?;
+        }
+        let detail = json!({
+            "rewrapped": batch.rewrapped,
+            "failed": batch.failures.len(),
+            "skipped": batch.skipped,
+            "rewrapped_total": i64::from(rotation.rewrapped) + as_i64(outcome.rewrapped),
+            "total_deks": rotation.total_deks,
+        });
+        audit
+            .record(current, Some(&rotation.id), "batch_rewrapped", detail)
+            .await?;
+    }
+    Ok(())
+}
+
+async fn flag_overdue(audit: &Audit<'_>, current: &str) -> Result<bool, SecretCryptoError> {
+    let Some(first_seen_at) =
+        master_keys::find_unflagged_overdue_master_key(audit.pool, current, ROTATION_INTERVAL_DAYS)
+            .await?
+    else {
+        return Ok(false);
+    };
+    tracing::warn!(key_id = %current, %first_seen_at, "Master key is due for rotation");
+    let detail = json!({
+        "first_seen_at": first_seen_at,
+        "interval_days": ROTATION_INTERVAL_DAYS,
+    });
+    audit
+        .record(current, None, "rotation_overdue", detail)
+        .await?;
+    Ok(true)
+}
+
+fn as_u64(n: usize) -> u64 {
+    u64::try_from(n).unwrap_or(u64::MAX)
+}
+
+fn as_i64(n: u64) -> i64 {
+    i64::try_from(n).unwrap_or(i64::MAX)
+}
@@ -0,0 +1,113 @@
+//! The Prometheus text exposition format, version 0.0.4.
+//!
+//! Just enough of it for this endpoint: `# HELP` and `# TYPE` once per
+//! family, then its samples, with label values escaped. Families must be
+//! written whole, one after another; the format does not allow a family's
+//! samples to be interleaved with another's.
+
+use crate::numeric::to_f64;
+
+pub const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetricKind {
+    Counter,
+    Gauge,
+    Histogram,
+}
+
+impl MetricKind {
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::Counter => "counter",
+            Self::Gauge => "gauge",
+            Self::Histogram => "histogram",
+        }
+    }
+}
+
+/// One histogram series: cumulative counts per upper bound, in seconds.
+#[derive(Debug, Clone, Copy)]
+pub struct HistogramSample<'a> {
+    pub bounds: &'a [f64],
+    pub cumulative: &'a [i64],
+    pub count: i64,
+    pub sum: f64,
+}
+
+#[derive(Debug, Default)]
+pub struct Exposition {
+    out: String,
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+fn write_labels(out: &mut String, labels: &[(&str, &str)], extra: Option<(&str, &str)>) {
+    let mut all = labels.iter().copied().chain(extra).peekable();
+    if all.peek().is_none() {
+        return;
+    }
+    out.push('{');
+    for (i, (name, value)) in all.enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        out.push_str(&format!("{name}=\"{}\"", escape(value)));
+    }
+    out.push('}');
+}
+
+fn format_bound(bound: f64) -> String {
+    if bound.is_infinite() {
+        "+Inf".to_owned()
+    } else {
+        bound.to_string()
+    }
+}
+
+impl Exposition {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start a family. Its samples follow, before the next family starts.
+    pub fn family(&mut self, name: &str, kind: MetricKind, help: &str) {
+        let help = help.replace('\\', "\\\\").replace('\n', "\\n");
+        self.out.push_str(&format!("# HELP {name} {help}\n"));
+        self.out
+            .push_str(&format!("# TYPE {name} {}\n", kind.as_str()));
+    }
+
+    pub fn sample(&mut self, name: &str, labels: &[(&str, &str)], value: f64) {
+        self.out.push_str(name);
+        write_labels(&mut self.out, labels, None);
+        self.out.push_str(&format!(" {value}\n"));
+    }
+
+    /// The `_bucket`, `_sum` and `_count` lines of one histogram series. The
+    /// `+Inf` bucket is added from `count`.
+    pub fn histogram(&mut self, name: &str, labels: &[(&str, &str)], sample: HistogramSample<'_>) {
+        let bucket = format!("{name}_bucket");
+        for (bound, count) in sample.bounds.iter().zip(sample.cumulative) {
+            self.out.push_str(&bucket);
+            write_labels(&mut self.out, labels, Some(("le", &format_bound(*bound))));
+            self.out.push_str(&format!(" {count}\n"));
+        }
+        self.out.push_str(&bucket);
+        write_labels(&mut self.out, labels, Some(("le", "+Inf")));
+        self.out.push_str(&format!(" {}\n", sample.count));
+        self.sample(&format!("{name}_sum"), labels, sample.sum);
+        self.sample(&format!("{name}_count"), labels, to_f64(sample.count));
+    }
+
+    #[must_use]
+    pub fn finish(self) -> String {
+        self.out
+    }
+}
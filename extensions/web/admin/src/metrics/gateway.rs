@@ -0,0 +1,179 @@
+//! Gateway request series: counts, latency histogram, tokens and cost per
+//! provider, model, route and status, and the requests still in flight.
+
+use std::collections::BTreeMap;
+
+use super::exposition::{Exposition, HistogramSample, MetricKind};
+use crate::numeric::to_f64;
+use crate::repositories::metrics::{GatewayRequestTally, InFlightTally};
+
+/// Upper bounds of the latency histogram, in milliseconds. Model calls run
+/// from a cached hit in tens of milliseconds to long generations in minutes.
+pub const LATENCY_BUCKETS_MS: [i32; 10] = [
+    100, 250, 500, 1_000, 2_500, 5_000, 10_000, 30_000, 60_000, 120_000,
+];
+
+const BUCKETS: usize = LATENCY_BUCKETS_MS.len();
+
+type SeriesKey = (String, String, String, String);
+
+#[derive(Debug, Default)]
+struct Series {
+    requests: i64,
+    latency_count: i64,
+    latency_ms: i64,
+    // Why: one slot per bound plus one for latencies over every bound, which
+    // only the `+Inf` bucket counts.
+    buckets: [i64; BUCKETS + 1],
+    input_tokens: i64,
+    output_tokens: i64,
+    cache_read_tokens: i64,
+    cost_microdollars: i64,
+}
+
+impl Series {
+    fn cumulative(&self) -> [i64; BUCKETS] {
+        let mut out = [0; BUCKETS];
+        let mut running = 0;
+        for (slot, count) in out.iter_mut().zip(self.buckets) {
+            running += count;
+            *slot = running;
+        }
+        out
+    }
+}
+
+fn series(rows: &[GatewayRequestTally]) -> BTreeMap<SeriesKey, Series> {
+    let mut out: BTreeMap<SeriesKey, Series> = BTreeMap::new();
+    for row in rows {
+        let key = (
+            row.provider.clone().unwrap_or_default(),
+            row.model.clone().unwrap_or_default(),
+            row.route.clone().unwrap_or_default(),
+            row.status.clone(),
+        );
+        let s = out.entry(key).or_default();
+        s.requests += row.requests;
+        s.input_tokens += row.input_tokens;
+        s.output_tokens += row.output_tokens;
+        s.cache_read_tokens += row.cache_read_tokens;
+        s.cost_microdollars += row.cost_microdollars;
+        if let Some(bucket) = row.bucket {
+            let slot = usize::try_from(bucket).unwrap_or(0).min(BUCKETS);
+            s.buckets[slot] += row.requests;
+            s.latency_count += row.requests;
+            s.latency_ms += row.latency_ms;
+        }
+    }
+    out
+}
+
+const fn labels(key: &SeriesKey) -> [(&'static str, &str); 4] {
+    [
+        ("provider", key.0.as_str()),
+        ("model", key.1.as_str()),
+        ("route", key.2.as_str()),
+        ("status", key.3.as_str()),
+    ]
+}
+
+pub(super) fn write(
+    out: &mut Exposition,
+    rows: &[GatewayRequestTally],
+    in_flight: &[InFlightTally],
+) {
+    let series = series(rows);
+
+    out.family(
+        "systemprompt_gateway_requests_total",
+        MetricKind::Counter,
+        "Gateway model requests.",
+    );
+    for (key, s) in &series {
+        out.sample(
+            "systemprompt_gateway_requests_total",
+            &labels(key),
+            to_f64(s.requests),
+        );
+    }
+
+    let bounds = LATENCY_BUCKETS_MS.map(|ms| f64::from(ms) / 1_000.0);
+    out.family(
+        "systemprompt_gateway_request_duration_seconds",
+        MetricKind::Histogram,
+        "Gateway model request latency, end to end.",
+    );
+    for (key, s) in series.iter().filter(|(_, s)| s.latency_count > 0) {
+        let cumulative = s.cumulative();
+        let sample = HistogramSample {
+            bounds: &bounds,
+            cumulative: &cumulative,
+            count: s.latency_count,
+            sum: to_f64(s.latency_ms) / 1_000.0,
+        };
+        out.histogram(
+            "systemprompt_gateway_request_duration_seconds",
+            &labels(key),
+            sample,
+        );
+    }
+
+    out.family(
+        "systemprompt_gateway_tokens_total",
+        MetricKind::Counter,
+        "Tokens through the gateway, by type.",
+    );
+    for (key, s) in &series {
+        let [provider, model, route, status] = labels(key);
+        for (kind, tokens) in [
+            ("input", s.input_tokens),
+            ("output", s.output_tokens),
+            ("cache_read", s.cache_read_tokens),
+        ] {
+            out.sample(
+                "systemprompt_gateway_tokens_total",
+                &[provider, model, route, status, ("type", kind)],
+                to_f64(tokens),
+            );
+        }
+    }
+
+    out.family(
+        "systemprompt_gateway_cost_usd_total",
+        MetricKind::Counter,
+        "Priced cost of gateway model requests, in US dollars.",
+    );
+    for (key, s) in &series {
+        out.sample(
+            "systemprompt_gateway_cost_usd_total",
+            &labels(key),
+            to_f64(s.cost_microdollars) / 1_000_000.0,
+        );
+    }
+
+    write_in_flight(out, in_flight);
+}
+
+fn write_in_flight(out: &mut Exposition, rows: &[InFlightTally]) {
+    let mut series: BTreeMap<(String, String, String), i64> = BTreeMap::new();
+    for row in rows {
+        let key = (
+            row.provider.clone().unwrap_or_default(),
+            row.model.clone().unwrap_or_default(),
+            row.route.clone().unwrap_or_default(),
+        );
+        *series.entry(key).or_default() += row.requests;
+    }
+    out.family(
+        "systemprompt_gateway_requests_in_flight",
+        MetricKind::Gauge,
+        "Gateway model requests started and not yet completed or failed.",
+    );
+    for ((provider, model, route), requests) in &series {
+        out.sample(
+            "systemprompt_gateway_requests_in_flight",
+            &[("provider", provider), ("model", model), ("route", route)],
+            to_f64(*requests),
+        );
+    }
+}
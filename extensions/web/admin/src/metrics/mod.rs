@@ -0,0 +1,96 @@
+//! Prometheus metrics for the gateway, governance and the scheduler, served
+//! at `/metrics`.
+//!
+//! Two sources feed one exposition. Gateway, governance, safety and job
+//! figures are tallied from the audit tables at scrape time, so they are the
+//! same whichever replica answers and cover traffic no process saw directly;
+//! aggregate them with `max`, not `sum`. Audit-bus traffic and job durations
+//! come from [`runtime`], which each process records for itself.
+//!
+//! The database tallies are whole-table aggregates plus what retention has
+//! already deleted, so the counters among them never go down; requests still
+//! pending are a separate in-flight gauge. They are reused for
+//! `refresh_secs` (see `services/telemetry/metrics.yaml`) and recomputed by
+//! one scrape at a time, so a burst of scrapers costs one set of queries.
+
+pub mod exposition;
+mod gateway;
+mod platform;
+pub mod runtime;
+
+use std::sync::{Arc, LazyLock};
+use std::time::{Duration, Instant};
+
+use sqlx::PgPool;
+use tokio::sync::Mutex;
+
+pub use gateway::LATENCY_BUCKETS_MS;
+
+use crate::repositories;
+use crate::repositories::metrics::{
+    DecisionTally, GatewayRequestTally, InFlightTally, SafetyFindingTally,
+};
+use crate::types::JobSummary;
+use exposition::Exposition;
+
+/// The database half of a scrape.
+#[derive(Debug, Default)]
+pub struct MetricsSnapshot {
+    pub requests: Vec<GatewayRequestTally>,
+    pub in_flight: Vec<InFlightTally>,
+    pub decisions: Vec<DecisionTally>,
+    pub findings: Vec<SafetyFindingTally>,
+    pub jobs: Vec<JobSummary>,
+}
+
+type CachedSnapshot = Option<(Arc<MetricsSnapshot>, Instant)>;
+
+static SNAPSHOT: LazyLock<Mutex<CachedSnapshot>> = LazyLock::new(|| Mutex::new(None));
+
+async fn query_snapshot(pool: &PgPool) -> Result<MetricsSnapshot, sqlx::Error> {
+    Ok(MetricsSnapshot {
+        requests: repositories::metrics::list_gateway_request_tallies(pool, &LATENCY_BUCKETS_MS)
+            .await?,
+        in_flight: repositories::metrics::list_in_flight_requests(pool).await?,
+        decisions: repositories::metrics::list_decision_tallies(pool).await?,
+        findings: repositories::metrics::list_safety_finding_tallies(pool).await?,
+        jobs: repositories::jobs::list_jobs(pool).await?,
+    })
+}
+
+async fn snapshot(pool: &PgPool, refresh: Duration) -> Result<Arc<MetricsSnapshot>, sqlx::Error> {
+    // Why: the lock is held across the queries on purpose, so scrapes that
+    // arrive while one is recomputing wait for its result instead of each
+    // running their own.
+    let mut cached = SNAPSHOT.lock().await;
+    if let Some((snapshot, at)) = cached.as_ref()
+        && at.elapsed() < refresh
+    {
+        return Ok(Arc::clone(snapshot));
+    }
+    let snapshot = Arc::new(query_snapshot(pool).await?);
+    *cached = Some((Arc::clone(&snapshot), Instant::now()));
+    drop(cached);
+    Ok(snapshot)
+}
+
+/// Render `snapshot` and this process's own observations as one exposition.
+#[must_use]
+pub fn render(snapshot: &MetricsSnapshot) -> String {
+    let mut out = Exposition::new();
+    gateway::write(&mut out, &snapshot.requests, &snapshot.in_flight);
+    platform::write_governance(&mut out, &snapshot.decisions, &snapshot.findings);
+    platform::write_audit_bus(
+        &mut out,
+        runtime::audit_bus_observation(),
+        crate::audit_event_bus::subscriber_count(),
+    );
+    platform::write_jobs(&mut out, &snapshot.jobs, &runtime::job_run_observations());
+    out.finish()
+}
+
+/// The full `/metrics` body, querying the database at most once per
+/// `refresh`.
+pub async fn render_metrics(pool: &PgPool, refresh: Duration) -> Result<String, sqlx::Error> {
+    Ok(render(&*snapshot(pool, refresh).await?))
+}
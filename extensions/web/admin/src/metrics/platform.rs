@@ -0,0 +1,171 @@
+//! Governance, safety, audit-bus and scheduler series.
+
+use std::collections::BTreeMap;
+
+use super::exposition::{Exposition, MetricKind};
+use super::runtime::{AuditBusObservation, JobRunObservation};
+use crate::numeric::to_f64;
+use crate::repositories::metrics::{DecisionTally, SafetyFindingTally};
+use crate::types::JobSummary;
+
+const JOB_FAILED: &str = "failed";
+
+pub(super) fn write_governance(
+    out: &mut Exposition,
+    decisions: &[DecisionTally],
+    findings: &[SafetyFindingTally],
+) {
+    out.family(
+        "systemprompt_governance_decisions_total",
+        MetricKind::Counter,
+        "Governance decisions, by policy and outcome.",
+    );
+    for d in decisions {
+        out.sample(
+            "systemprompt_governance_decisions_total",
+            &[
+                ("policy", d.policy.as_str()),
+                ("decision", d.decision.as_str()),
+            ],
+            to_f64(d.decisions),
+        );
+    }
+
+    out.family(
+        "systemprompt_safety_findings_total",
+        MetricKind::Counter,
+        "Safety scanner findings on gateway traffic.",
+    );
+    for f in findings {
+        out.sample(
+            "systemprompt_safety_findings_total",
+            &[
+                ("scanner", f.scanner.as_str()),
+                ("category", f.category.as_str()),
+                ("severity", f.severity.as_str()),
+                ("phase", f.phase.as_str()),
+            ],
+            to_f64(f.findings),
+        );
+    }
+}
+
+#[expect(
+    clippy::cast_precision_loss,
+    reason = "bus counters stay far below 2^53 for the life of a process"
+)]
+pub(super) fn write_audit_bus(out: &mut Exposition, bus: AuditBusObservation, subscribers: usize) {
+    out.family(
+        "systemprompt_audit_bus_notifications_total",
+        MetricKind::Counter,
+        "Audit events received from Postgres by this instance.",
+    );
+    out.sample(
+        "systemprompt_audit_bus_notifications_total",
+        &[],
+        bus.notifications as f64,
+    );
+    out.family(
+        "systemprompt_audit_bus_lag_seconds",
+        MetricKind::Gauge,
+        "Delay between an audit row being written and its event reaching the bus, for the latest event.",
+    );
+    out.sample(
+        "systemprompt_audit_bus_lag_seconds",
+        &[],
+        bus.last_lag.as_secs_f64(),
+    );
+    out.family(
+        "systemprompt_audit_bus_subscribers",
+        MetricKind::Gauge,
+        "SSE streams subscribed to the audit bus on this instance.",
+    );
+    out.sample(
+        "systemprompt_audit_bus_subscribers",
+        &[],
+        subscribers as f64,
+    );
+    out.family(
+        "systemprompt_audit_sse_dropped_events_total",
+        MetricKind::Counter,
+        "Audit events an SSE stream fell too far behind to receive.",
+    );
+    out.sample(
+        "systemprompt_audit_sse_dropped_events_total",
+        &[],
+        bus.sse_dropped as f64,
+    );
+}
+
+pub(super) fn write_jobs(
+    out: &mut Exposition,
+    jobs: &[JobSummary],
+    runs: &BTreeMap<&'static str, JobRunObservation>,
+) {
+    let name = |j: &JobSummary| j.job_name.as_str().to_owned();
+
+    out.family(
+        "systemprompt_job_enabled",
+        MetricKind::Gauge,
+        "Whether the scheduler runs the job.",
+    );
+    for j in jobs {
+        out.sample(
+            "systemprompt_job_enabled",
+            &[("job", &name(j))],
+            f64::from(u8::from(j.enabled)),
+        );
+    }
+    out.family(
+        "systemprompt_job_runs_total",
+        MetricKind::Counter,
+        "Scheduler runs of the job, across every instance.",
+    );
+    for j in jobs {
+        out.sample(
+            "systemprompt_job_runs_total",
+            &[("job", &name(j))],
+            f64::from(j.run_count),
+        );
+    }
+    out.family(
+        "systemprompt_job_last_run_timestamp_seconds",
+        MetricKind::Gauge,
+        "When the job last ran, as a Unix timestamp.",
+    );
+    for j in jobs {
+        if let Some(at) = j.last_run {
+            out.sample(
+                "systemprompt_job_last_run_timestamp_seconds",
+                &[("job", &name(j))],
+                to_f64(at.timestamp()),
+            );
+        }
+    }
+    out.family(
+        "systemprompt_job_last_run_failed",
+        MetricKind::Gauge,
+        "1 when the job's last run failed, 0 when it succeeded.",
+    );
+    for j in jobs {
+        if let Some(status) = j.last_status.as_deref() {
+            out.sample(
+                "systemprompt_job_last_run_failed",
+                &[("job", &name(j))],
+                f64::from(u8::from(status == JOB_FAILED)),
+            );
+        }
+    }
+    out.family(
+        "systemprompt_job_last_duration_seconds",
+        MetricKind::Gauge,
+        "How long the job's last run on this instance took.",
+    );
+    for (job, run) in runs {
+        out.sample(
+            "systemprompt_job_last_duration_seconds",
+            &[("job", job)],
+            run.duration.as_secs_f64(),
+        );
+    }
+}
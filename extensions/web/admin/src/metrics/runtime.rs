@@ -0,0 +1,78 @@
+//! What this process observed itself rather than read from the database:
+//! audit-bus traffic and the last run of each job it executed.
+//!
+//! These series are per instance. Prometheus scrapes each replica, so they
+//! carry its `instance` label and sum across replicas in the usual way.
+
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{LazyLock, Mutex, PoisonError};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+
+static AUDIT_NOTIFICATIONS: AtomicU64 = AtomicU64::new(0);
+static AUDIT_LAG_MS: AtomicU64 = AtomicU64::new(0);
+static AUDIT_SSE_DROPPED: AtomicU64 = AtomicU64::new(0);
+
+static JOB_RUNS: LazyLock<Mutex<BTreeMap<&'static str, JobRunObservation>>> =
+    LazyLock::new(|| Mutex::new(BTreeMap::new()));
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct JobRunObservation {
+    pub duration: Duration,
+    pub success: bool,
+    pub finished_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct AuditBusObservation {
+    pub notifications: u64,
+    /// Insert-to-delivery delay of the most recent notification.
+    pub last_lag: Duration,
+    /// Payloads SSE subscribers fell too far behind to receive.
+    pub sse_dropped: u64,
+}
+
+/// A notification reached the bus `lag` after its row was written; `None`
+/// when the payload carried no timestamp.
+pub fn record_audit_notification(lag: Option<Duration>) {
+    AUDIT_NOTIFICATIONS.fetch_add(1, Ordering::Relaxed);
+    if let Some(lag) = lag {
+        let ms = u64::try_from(lag.as_millis()).unwrap_or(u64::MAX);
+        AUDIT_LAG_MS.store(ms, Ordering::Relaxed);
+    }
+}
+
+pub fn record_sse_dropped(skipped: u64) {
+    AUDIT_SSE_DROPPED.fetch_add(skipped, Ordering::Relaxed);
+}
+
+pub fn record_job_run(job: &'static str, duration: Duration, success: bool) {
+    let observation = JobRunObservation {
+        duration,
+        success,
+        finished_at: Utc::now(),
+    };
+    JOB_RUNS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .insert(job, observation);
+}
+
+#[must_use]
+pub fn audit_bus_observation() -> AuditBusObservation {
+    AuditBusObservation {
+        notifications: AUDIT_NOTIFICATIONS.load(Ordering::Relaxed),
+        last_lag: Duration::from_millis(AUDIT_LAG_MS.load(Ordering::Relaxed)),
+        sse_dropped: AUDIT_SSE_DROPPED.load(Ordering::Relaxed),
+    }
+}
+
+#[must_use]
+pub fn job_run_observations() -> BTreeMap<&'static str, JobRunObservation> {
+    JOB_RUNS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone()
+}
@@ -83,7 +83,7 @@ pub(crate) async fn non_admin_gate_middleware(request: Request, next: Next) -> R
         return next.run(request).await;
     }
 
-    if is_non_admin_allowed_path(path) {
+    if is_non_admin_allowed_path(path) || (ctx.can_audit() && is_auditor_path(path)) {
         next.run(request).await
     } else {
         axum::response::Redirect::to("/admin/profile").into_response()
@@ -105,3 +105,7 @@ fn is_non_admin_allowed_path(path: &str) -> bool {
         || path == "/admin/"
         || path == "/admin"
 }
+
+fn is_auditor_path(path: &str) -> bool {
+    path == "/admin/search" || path.starts_with("/admin/search/")
+}
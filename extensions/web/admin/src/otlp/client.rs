@@ -0,0 +1,150 @@
+//! Delivery to the collector: spans in batches over OTLP/HTTP JSON, each
+//! batch retried with exponential backoff.
+//!
+//! Retries follow the OTLP spec: a transport failure, 429, 502, 503 or 504 is
+//! retried, honouring `Retry-After` when the collector sends seconds; any
+//! other non-success status means the collector will never take the batch, so
+//! it fails at once.
+
+use std::time::Duration;
+
+use reqwest::StatusCode;
+use reqwest::header::{CONTENT_TYPE, HeaderMap, HeaderName, HeaderValue, RETRY_AFTER};
+use systemprompt_web_shared::error::MarketplaceError;
+
+use super::wire::{
+    ExportTraceRequest, KeyValue, OtlpSpan, Resource, ResourceSpans, Scope, ScopeSpans,
+};
+use crate::repositories::config::otlp::OtlpExportConfig;
+
+const SCOPE_NAME: &str = "systemprompt.governance";
+const BASE_BACKOFF: Duration = Duration::from_millis(500);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Wrap `spans` in one export request, under the configured resource.
+#[must_use]
+pub fn export_request(config: &OtlpExportConfig, spans: Vec<OtlpSpan>) -> ExportTraceRequest {
+    let mut attributes = vec![KeyValue::string(
+        "service.name",
+        config.service_name.as_str(),
+    )];
+    attributes.extend(
+        config
+            .resource_attributes
+            .iter()
+            .map(|(k, v)| KeyValue::string(k, v.as_str())),
+    );
+    ExportTraceRequest {
+        resource_spans: vec![ResourceSpans {
+            resource: Resource { attributes },
+            scope_spans: vec![ScopeSpans {
+                scope: Scope {
+                    name: SCOPE_NAME.to_owned(),
+                    version: env!("CARGO_PKG_VERSION").to_owned(),
+                },
+                spans,
+            }],
+        }],
+    }
+}
+
+fn headers(config: &OtlpExportConfig) -> Result<HeaderMap, MarketplaceError> {
+    let mut headers = HeaderMap::new();
+    headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+    for (name, var) in &config.headers_env {
+        let value = std::env::var(var).map_err(|e| {
+            MarketplaceError::Internal(format!("otlp header `{name}` reads {var}: {e}"))
+        })?;
+        let name = HeaderName::try_from(name.as_str())
+            .map_err(|e| MarketplaceError::BadRequest(format!("otlp header `{name}`: {e}")))?;
+        let value = HeaderValue::try_from(value)
+            .map_err(|e| MarketplaceError::BadRequest(format!("otlp header `{name}`: {e}")))?;
+        headers.insert(name, value);
+    }
+    Ok(headers)
+}
+
+const fn retryable(status: StatusCode) -> bool {
+    matches!(status.as_u16(), 429 | 502 | 503 | 504)
+}
+
+fn backoff(attempt: u32, retry_after: Option<Duration>) -> Duration {
+    retry_after
+        .unwrap_or_else(|| BASE_BACKOFF.saturating_mul(2u32.saturating_pow(attempt)))
+        .min(MAX_BACKOFF)
+}
+
+async fn send_batch(
+    client: &reqwest::Client,
+    config: &OtlpExportConfig,
+    url: &str,
+    body: &[u8],
+) -> Result<(), MarketplaceError> {
+    let mut attempt = 0;
+    loop {
+        let result = client
+            .post(url)
+            .timeout(Duration::from_millis(config.timeout_ms))
+            .body(body.to_vec())
+            .send()
+            .await;
+        let (failure, retry_after) = match result {
+            Ok(response) if response.status().is_success() => return Ok(()),
+            Ok(response) if retryable(response.status()) => {
+                let retry_after = response
+                    .headers()
+                    .get(RETRY_AFTER)
+                    .and_then(|v| v.to_str().ok())
+                    .and_then(|v| v.parse::<u64>().ok())
+                    .map(Duration::from_secs);
+                (
+                    format!("collector returned {}", response.status()),
+                    retry_after,
+                )
+            },
+            Ok(response) => {
+                let status = response.status();
+                let text = response.text().await.unwrap_or_default();
+                return Err(MarketplaceError::Internal(format!(
+                    "collector rejected the batch with {status}: {}",
+                    text.chars().take(300).collect::<String>()
+                )));
+            },
+            Err(e) => (format!("collector unreachable: {e}"), None),
+        };
+        if attempt >= config.max_retries {
+            return Err(MarketplaceError::Internal(format!(
+                "{failure} after {} attempts",
+                attempt + 1
+            )));
+        }
+        let wait = backoff(attempt, retry_after);
+        tracing::warn!(attempt, wait_ms = wait.as_millis(), %failure, "otlp export retrying");
+        tokio::time::sleep(wait).await;
+        attempt += 1;
+    }
+}
+
+/// Send `spans` to the configured collector, `batch_size` at a time, and
+/// return how many were delivered. Stops at the first batch that fails for
+/// good.
+pub async fn send_spans(
+    config: &OtlpExportConfig,
+    spans: Vec<OtlpSpan>,
+) -> Result<usize, MarketplaceError> {
+    let client = reqwest::Client::builder()
+        .default_headers(headers(config)?)
+        .build()
+        .map_err(|e| MarketplaceError::Internal(format!("otlp client: {e}")))?;
+    let url = config.traces_url();
+    let mut sent = 0;
+    let mut spans = spans.into_iter().peekable();
+    while spans.peek().is_some() {
+        let batch: Vec<OtlpSpan> = spans.by_ref().take(config.batch_size).collect();
+        let count = batch.len();
+        let body = serde_json::to_vec(&export_request(config, batch))?;
+        send_batch(&client, config, &url, &body).await?;
+        sent += count;
+    }
+    Ok(sent)
+}
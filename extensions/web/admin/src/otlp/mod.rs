@@ -0,0 +1,91 @@
+//! OpenTelemetry export of governed traces.
+//!
+//! The trace explorer stitches `ai_requests`, `plugin_usage_events` and
+//! `governance_decisions` into per-session traces inside the admin UI. This
+//! module sends the same traces to an OTLP collector, so agent activity shows
+//! up in an existing tracing backend beside service traces: model requests as
+//! `GenAI` client spans with model, tokens and cost, tool calls as tool spans,
+//! and policy decisions as span events (see [`build_spans`]).
+//!
+//! Export runs on a schedule rather than inline, from the `otlp_trace_export`
+//! job, over windows of finished activity. The mark in `export_cursors` only
+//! advances once a window has been delivered in full, so a collector outage
+//! delays export instead of losing it; span ids are derived from source rows,
+//! so a window sent again after a partial failure repeats spans rather than
+//! adding new ones. Settings live in `services/telemetry/otlp.yaml`.
+
+mod client;
+mod rows;
+mod spans;
+pub mod wire;
+
+use chrono::{DateTime, Duration, Utc};
+use sqlx::PgPool;
+use systemprompt_web_shared::error::MarketplaceError;
+
+pub use client::{export_request, send_spans};
+pub use spans::{build_spans, trace_id_for_session};
+
+use crate::repositories::config::otlp::OtlpExportConfig;
+use crate::repositories::export_cursors::{
+    find_export_cursor, set_export_cursor, set_export_error,
+};
+use crate::repositories::traces::get_trace_export_window;
+
+pub const EXPORTER: &str = "otlp_traces";
+
+/// What one run delivered.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ExportReport {
+    pub spans: usize,
+    /// The new mark, or `None` when the run had nothing to do.
+    pub exported_through: Option<DateTime<Utc>>,
+}
+
+fn secs(value: u64) -> Duration {
+    Duration::seconds(i64::try_from(value).unwrap_or(i64::MAX))
+}
+
+/// Export the next window of settled activity, if there is one.
+///
+/// A window starts at the mark and ends `settle_secs` before now, or
+/// `max_window_secs` after the mark if that is sooner. A failure is recorded
+/// on the mark and returned; the next run retries the same window.
+pub async fn export_traces(
+    pool: &PgPool,
+    config: &OtlpExportConfig,
+) -> Result<ExportReport, MarketplaceError> {
+    if !config.enabled {
+        return Ok(ExportReport::default());
+    }
+    let settled = Utc::now() - secs(config.settle_secs);
+    let after = match find_export_cursor(pool, EXPORTER).await? {
+        Some(cursor) => cursor.exported_through,
+        None => settled - secs(config.initial_lookback_secs),
+    };
+    let through = settled.min(after + secs(config.max_window_secs));
+    if through <= after {
+        return Ok(ExportReport::default());
+    }
+
+    let window = get_trace_export_window(pool, after, through).await?;
+    let spans = build_spans(&window, through);
+    let sent = match send_spans(config, spans).await {
+        Ok(sent) => sent,
+        Err(e) => {
+            set_export_error(pool, EXPORTER, after, &e.to_string()).await?;
+            return Err(e);
+        },
+    };
+    set_export_cursor(
+        pool,
+        EXPORTER,
+        through,
+        i64::try_from(sent).unwrap_or(i64::MAX),
+    )
+    .await?;
+    Ok(ExportReport {
+        spans: sent,
+        exported_through: Some(through),
+    })
+}
@@ -0,0 +1,147 @@
+//! One span, or span event, per source row: model requests, tool calls and
+//! governance decisions.
+
+use super::spans::{nanos, span_id, status};
+use super::wire::{KeyValue, OtlpSpan, SPAN_KIND_CLIENT, SPAN_KIND_INTERNAL, SpanEvent};
+use crate::numeric::to_f64;
+use crate::repositories::traces::{ExportDecision, ExportRequest, ExportToolEvent};
+
+const MICRODOLLARS_PER_DOLLAR: f64 = 1_000_000.0;
+
+// Why: a hook reports one tool call twice, `PreToolUse` then `PostToolUse`
+// (or its failure); pairing them gives the call its real duration. An event
+// with nothing to pair with opens and closes its own call.
+pub(super) struct PairedToolCall<'a> {
+    pub(super) first: &'a ExportToolEvent,
+    pub(super) last: &'a ExportToolEvent,
+}
+
+pub(super) fn pair_tool_calls<'a>(events: &[&'a ExportToolEvent]) -> Vec<PairedToolCall<'a>> {
+    let mut calls: Vec<PairedToolCall<'a>> = Vec::with_capacity(events.len());
+    let mut open: Vec<usize> = Vec::new();
+    for &event in events {
+        let closes = event.event_type.starts_with("PostToolUse");
+        let opened = closes
+            .then(|| {
+                open.iter()
+                    .position(|&i| calls[i].first.tool_name == event.tool_name)
+            })
+            .flatten();
+        if let Some(slot) = opened {
+            calls[open.remove(slot)].last = event;
+            continue;
+        }
+        if event.event_type == "PreToolUse" {
+            open.push(calls.len());
+        }
+        calls.push(PairedToolCall {
+            first: event,
+            last: event,
+        });
+    }
+    calls
+}
+
+pub(super) fn request_span(r: &ExportRequest, trace_id: &str, parent: &str) -> OtlpSpan {
+    let model = r.model.as_deref().unwrap_or("unknown");
+    let mut attributes = vec![
+        KeyValue::string("gen_ai.operation.name", "chat"),
+        KeyValue::string("gen_ai.response.model", model),
+        KeyValue::string(
+            "gen_ai.request.model",
+            r.requested_model.as_deref().unwrap_or(model),
+        ),
+        KeyValue::double(
+            "systemprompt.cost.usd",
+            to_f64(r.cost_microdollars) / MICRODOLLARS_PER_DOLLAR,
+        ),
+        KeyValue::int("systemprompt.cost.microdollars", r.cost_microdollars),
+        KeyValue::bool("systemprompt.cache_hit", r.cache_hit),
+        KeyValue::bool("systemprompt.streaming", r.is_streaming),
+        KeyValue::string("systemprompt.ai_request.id", r.id.as_str()),
+        KeyValue::string("user.id", r.user_id.as_str()),
+    ];
+    if let Some(provider) = r.provider.as_deref() {
+        attributes.push(KeyValue::string("gen_ai.provider.name", provider));
+        attributes.push(KeyValue::string("gen_ai.system", provider));
+    }
+    if let Some(id) = r.provider_request_id.as_deref() {
+        attributes.push(KeyValue::string("gen_ai.response.id", id));
+    }
+    let counts = [
+        ("gen_ai.usage.input_tokens", r.input_tokens),
+        ("gen_ai.usage.output_tokens", r.output_tokens),
+        ("gen_ai.usage.cache_read.input_tokens", r.cache_read_tokens),
+        ("gen_ai.request.max_tokens", r.max_tokens),
+    ];
+    for (key, value) in counts {
+        if let Some(value) = value {
+            attributes.push(KeyValue::int(key, i64::from(value)));
+        }
+    }
+    if let Some(t) = r.temperature {
+        attributes.push(KeyValue::double("gen_ai.request.temperature", t));
+    }
+    let failed = !matches!(r.status.as_str(), "ok" | "success" | "completed");
+    if failed {
+        attributes.push(KeyValue::string("error.type", r.status.as_str()));
+    }
+    OtlpSpan {
+        trace_id: trace_id.to_owned(),
+        span_id: span_id("request", &r.id),
+        parent_span_id: parent.to_owned(),
+        name: format!("chat {model}"),
+        kind: SPAN_KIND_CLIENT,
+        start_time_unix_nano: nanos(r.started_at),
+        end_time_unix_nano: nanos(r.ended_at.max(r.started_at)),
+        attributes,
+        events: Vec::new(),
+        status: status(failed.then(|| r.error_message.clone().unwrap_or_else(|| r.status.clone()))),
+    }
+}
+
+pub(super) fn tool_span(call: &PairedToolCall<'_>, trace_id: &str, parent: &str) -> OtlpSpan {
+    let e = call.last;
+    let mut attributes = vec![
+        KeyValue::string("systemprompt.event_type", e.event_type.as_str()),
+        KeyValue::string("user.id", e.user_id.as_str()),
+    ];
+    if let Some(tool) = e.tool_name.as_deref() {
+        attributes.push(KeyValue::string("gen_ai.operation.name", "execute_tool"));
+        attributes.push(KeyValue::string("gen_ai.tool.name", tool));
+    }
+    let name = e.tool_name.as_deref().map_or_else(
+        || e.event_type.clone(),
+        |tool| format!("execute_tool {tool}"),
+    );
+    if let Some(plugin_id) = e.plugin_id.as_deref() {
+        attributes.push(KeyValue::string("systemprompt.plugin.id", plugin_id));
+    }
+    let failed = e.event_type.contains("Failure") || e.event_type.contains("Error");
+    OtlpSpan {
+        trace_id: trace_id.to_owned(),
+        span_id: span_id("event", &call.first.id),
+        parent_span_id: parent.to_owned(),
+        name,
+        kind: SPAN_KIND_INTERNAL,
+        start_time_unix_nano: nanos(call.first.created_at),
+        end_time_unix_nano: nanos(e.created_at),
+        attributes,
+        events: Vec::new(),
+        status: status(failed.then(|| e.event_type.clone())),
+    }
+}
+
+pub(super) fn decision_event(d: &ExportDecision) -> SpanEvent {
+    SpanEvent {
+        time_unix_nano: nanos(d.created_at),
+        name: "governance.decision".to_owned(),
+        attributes: vec![
+            KeyValue::string("systemprompt.governance.decision", d.decision.as_str()),
+            KeyValue::string("systemprompt.governance.policy", d.policy.as_str()),
+            KeyValue::string("systemprompt.governance.reason", d.reason.as_str()),
+            KeyValue::string("systemprompt.governance.decision_id", d.id.as_str()),
+            KeyValue::string("gen_ai.tool.name", d.tool_name.as_str()),
+        ],
+    }
+}
@@ -0,0 +1,179 @@
+//! Turns an export window into OTLP spans, following the OpenTelemetry
+//! `GenAI` semantic conventions where they have a name for the thing.
+//!
+//! Each session is one trace. Within a window it gets an `invoke_agent` root
+//! span covering its activity, with a `chat {model}` client span per model
+//! request and an `execute_tool {tool}` span per tool call beneath it; a
+//! `PreToolUse` hook event and the `PostToolUse` (or failure) that follows it
+//! for the same tool are one call, spanning the time between them. A
+//! governance decision is a span event: on the first tool span at or after it
+//! for the same tool, which is the call it governed, and on the root when the
+//! tool never ran (a deny) or ran in a later window.
+//!
+//! Ids are derived from the source rows, so a window sent twice after a
+//! failed run produces the same spans rather than new ones.
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use sha2::{Digest, Sha256};
+use systemprompt::identifiers::{AgentId, SessionId};
+
+use super::rows::{decision_event, pair_tool_calls, request_span, tool_span};
+use super::wire::{KeyValue, OtlpSpan, OtlpStatus, SPAN_KIND_INTERNAL, STATUS_ERROR, STATUS_UNSET};
+use crate::repositories::traces::{
+    ExportDecision, ExportRequest, ExportToolEvent, TraceExportWindow,
+};
+
+fn digest_hex(parts: &[&str], bytes: usize) -> String {
+    let mut hasher = Sha256::new();
+    for part in parts {
+        hasher.update(part.as_bytes());
+        hasher.update(b"\n");
+    }
+    hex::encode(&hasher.finalize()[..bytes])
+}
+
+/// The 16-byte trace id a session exports under.
+#[must_use]
+pub fn trace_id_for_session(session_id: &SessionId) -> String {
+    digest_hex(&["trace", session_id.as_str()], 16)
+}
+
+pub(super) fn span_id(kind: &str, id: &str) -> String {
+    digest_hex(&["span", kind, id], 8)
+}
+
+pub(super) fn nanos(at: DateTime<Utc>) -> String {
+    at.timestamp_nanos_opt().unwrap_or_default().to_string()
+}
+
+pub(super) fn status(error: Option<String>) -> OtlpStatus {
+    error.map_or_else(
+        || OtlpStatus {
+            code: STATUS_UNSET,
+            message: String::new(),
+        },
+        |message| OtlpStatus {
+            code: STATUS_ERROR,
+            message,
+        },
+    )
+}
+
+#[derive(Default)]
+struct SessionActivity<'a> {
+    requests: Vec<&'a ExportRequest>,
+    tool_events: Vec<&'a ExportToolEvent>,
+    decisions: Vec<&'a ExportDecision>,
+}
+
+/// Every span in `window`, grouped into one trace per session. `window_end`
+/// keys the root spans, so each window's root is its own span.
+#[must_use]
+pub fn build_spans(window: &TraceExportWindow, window_end: DateTime<Utc>) -> Vec<OtlpSpan> {
+    let mut sessions: BTreeMap<&SessionId, SessionActivity<'_>> = BTreeMap::new();
+    for r in &window.requests {
+        sessions.entry(&r.session_id).or_default().requests.push(r);
+    }
+    for e in &window.tool_events {
+        sessions
+            .entry(&e.session_id)
+            .or_default()
+            .tool_events
+            .push(e);
+    }
+    for d in &window.decisions {
+        sessions.entry(&d.session_id).or_default().decisions.push(d);
+    }
+    let window_key = window_end.to_rfc3339();
+    sessions
+        .into_iter()
+        .flat_map(|(session_id, activity)| session_spans(session_id, &activity, &window_key))
+        .collect()
+}
+
+fn session_spans(
+    session_id: &SessionId,
+    activity: &SessionActivity<'_>,
+    window_key: &str,
+) -> Vec<OtlpSpan> {
+    let trace_id = trace_id_for_session(session_id);
+    let root_id = digest_hex(&["session", session_id.as_str(), window_key], 8);
+
+    let mut children: Vec<OtlpSpan> = activity
+        .requests
+        .iter()
+        .map(|r| request_span(r, &trace_id, &root_id))
+        .collect();
+    let calls = pair_tool_calls(&activity.tool_events);
+    let first_tool = children.len();
+    children.extend(calls.iter().map(|c| tool_span(c, &trace_id, &root_id)));
+
+    let mut root_events = Vec::new();
+    for d in &activity.decisions {
+        let governed = calls.iter().position(|c| {
+            c.first.tool_name.as_deref() == Some(d.tool_name.as_str())
+                && c.first.created_at >= d.created_at
+        });
+        let event = decision_event(d);
+        match governed {
+            Some(i) => children[first_tool + i].events.push(event),
+            None => root_events.push(event),
+        }
+    }
+
+    let times: Vec<DateTime<Utc>> = activity
+        .requests
+        .iter()
+        .flat_map(|r| [r.started_at, r.ended_at])
+        .chain(activity.tool_events.iter().map(|e| e.created_at))
+        .chain(activity.decisions.iter().map(|d| d.created_at))
+        .collect();
+    let (Some(&start), Some(&end)) = (times.iter().min(), times.iter().max()) else {
+        return children;
+    };
+
+    let agent_id = activity
+        .decisions
+        .iter()
+        .find_map(|d| d.agent_id.as_ref().map(AgentId::as_str));
+    let user_id = activity
+        .requests
+        .first()
+        .map(|r| r.user_id.as_str())
+        .or_else(|| activity.tool_events.first().map(|e| e.user_id.as_str()))
+        .or_else(|| activity.decisions.first().map(|d| d.user_id.as_str()));
+    let denied = activity.decisions.iter().any(|d| d.decision == "deny");
+    let failed = children.iter().any(|s| s.status.code == STATUS_ERROR);
+
+    let mut attributes = vec![
+        KeyValue::string("gen_ai.operation.name", "invoke_agent"),
+        KeyValue::string("gen_ai.conversation.id", session_id.as_str()),
+        KeyValue::string("session.id", session_id.as_str()),
+        KeyValue::bool("systemprompt.governance.denied", denied),
+    ];
+    if let Some(agent_id) = agent_id {
+        attributes.push(KeyValue::string("gen_ai.agent.id", agent_id));
+    }
+    if let Some(user_id) = user_id {
+        attributes.push(KeyValue::string("user.id", user_id));
+    }
+
+    let root = OtlpSpan {
+        trace_id,
+        span_id: root_id,
+        parent_span_id: String::new(),
+        name: agent_id.map_or_else(
+            || "invoke_agent".to_owned(),
+            |a| format!("invoke_agent {a}"),
+        ),
+        kind: SPAN_KIND_INTERNAL,
+        start_time_unix_nano: nanos(start),
+        end_time_unix_nano: nanos(end),
+        attributes,
+        events: root_events,
+        status: status(failed.then(|| "a span in this session failed".to_owned())),
+    };
+    std::iter::once(root).chain(children).collect()
+}
@@ -0,0 +1,138 @@
+//! The OTLP/HTTP JSON encoding of an `ExportTraceServiceRequest`.
+//!
+//! Only the fields the exporter sets are modelled. Per the OTLP JSON mapping,
+//! ids are lowercase hex, 64-bit integers are strings, and enum fields are
+//! their numeric values.
+
+use serde::{Deserialize, Serialize};
+
+pub const SPAN_KIND_INTERNAL: i32 = 1;
+pub const SPAN_KIND_CLIENT: i32 = 3;
+
+pub const STATUS_UNSET: i32 = 0;
+pub const STATUS_ERROR: i32 = 2;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportTraceRequest {
+    pub resource_spans: Vec<ResourceSpans>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceSpans {
+    pub resource: Resource,
+    pub scope_spans: Vec<ScopeSpans>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Resource {
+    pub attributes: Vec<KeyValue>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScopeSpans {
+    pub scope: Scope,
+    pub spans: Vec<OtlpSpan>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Scope {
+    pub name: String,
+    pub version: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OtlpSpan {
+    pub trace_id: String,
+    pub span_id: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub parent_span_id: String,
+    pub name: String,
+    pub kind: i32,
+    pub start_time_unix_nano: String,
+    pub end_time_unix_nano: String,
+    pub attributes: Vec<KeyValue>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub events: Vec<SpanEvent>,
+    pub status: OtlpStatus,
+}
+
+impl OtlpSpan {
+    #[must_use]
+    pub fn attribute(&self, key: &str) -> Option<&AnyValue> {
+        self.attributes
+            .iter()
+            .find(|kv| kv.key == key)
+            .map(|kv| &kv.value)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpanEvent {
+    pub time_unix_nano: String,
+    pub name: String,
+    pub attributes: Vec<KeyValue>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OtlpStatus {
+    #[serde(default)]
+    pub code: i32,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub message: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KeyValue {
+    pub key: String,
+    pub value: AnyValue,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum AnyValue {
+    #[serde(rename = "stringValue")]
+    String(String),
+    #[serde(rename = "intValue")]
+    Int(String),
+    #[serde(rename = "doubleValue")]
+    Double(f64),
+    #[serde(rename = "boolValue")]
+    Bool(bool),
+}
+
+impl KeyValue {
+    pub fn string(key: &str, value: impl Into<String>) -> Self {
+        Self {
+            key: key.to_owned(),
+            value: AnyValue::String(value.into()),
+        }
+    }
+
+    #[must_use]
+    pub fn int(key: &str, value: i64) -> Self {
+        Self {
+            key: key.to_owned(),
+            value: AnyValue::Int(value.to_string()),
+        }
+    }
+
+    #[must_use]
+    pub fn double(key: &str, value: f64) -> Self {
+        Self {
+            key: key.to_owned(),
+            value: AnyValue::Double(value),
+        }
+    }
+
+    #[must_use]
+    pub fn bool(key: &str, value: bool) -> Self {
+        Self {
+            key: key.to_owned(),
+            value: AnyValue::Bool(value),
+        }
+    }
+}
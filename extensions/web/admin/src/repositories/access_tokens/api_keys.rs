@@ -2,14 +2,13 @@
 
 use chrono::{DateTime, Utc};
 use rand::RngCore;
-use sha2::{Digest, Sha256};
 use sqlx::PgPool;
 use systemprompt::identifiers::UserId;
+use systemprompt_web_access::repositories::api_keys::{API_KEY_PREFIX, hash_secret};
 use uuid::Uuid;
 
 use super::error::{AccessTokenRepoError, Result};
 
-pub const API_KEY_PREFIX: &str = "sp-live-";
 const SECRET_BYTES: usize = 32;
 const PREFIX_ID_BYTES: usize = 6;
 
@@ -106,6 +105,38 @@ pub async fn revoke_api_key(pool: &PgPool, user_id: &UserId, id: &str) -> Result
     Ok(result.rows_affected() > 0)
 }
 
+#[derive(Debug, Clone)]
+pub struct ApiKeyOwner {
+    pub user_id: UserId,
+    pub name: String,
+    pub live: bool,
+}
+
+/// Look a token up by its public prefix or full secret, without counting a use.
+///
+/// Revoked and expired keys are returned with `live = false` so a caller can
+/// say why a token would be refused.
+pub async fn find_api_key_by_prefix(pool: &PgPool, token: &str) -> Result<Option<ApiKeyOwner>> {
+    let key_prefix = token.split_once('.').map_or(token, |(prefix, _)| prefix);
+    let row = sqlx::query!(
+        r#"
+        SELECT user_id, name,
+               (revoked_at IS NULL
+                AND (expires_at IS NULL OR expires_at > CURRENT_TIMESTAMP)) AS "live!"
+        FROM user_api_keys
+        WHERE key_prefix = $1
+        "#,
+        key_prefix.trim(),
+    )
+    .fetch_optional(pool)
+    .await?;
+    Ok(row.map(|r| ApiKeyOwner {
+        user_id: UserId::new(r.user_id),
+        name: r.name,
+        live: r.live,
+    }))
+}
+
 fn generate_secret() -> (String, String, String) {
     let mut raw = [0u8; SECRET_BYTES];
     rand::rng().fill_bytes(&mut raw);
@@ -115,9 +146,3 @@ fn generate_secret() -> (String, String, String) {
     let key_hash = hash_secret(&secret);
     (secret, key_prefix, key_hash)
 }
-
-fn hash_secret(secret: &str) -> String {
-    let mut hasher = Sha256::new();
-    hasher.update(secret.as_bytes());
-    hex::encode(hasher.finalize())
-}
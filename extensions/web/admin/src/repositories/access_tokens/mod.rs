@@ -4,6 +4,8 @@ pub mod api_keys;
 pub mod error;
 
 pub use api_keys::{
-    ApiKeyRow, IssuedApiKey, issue_api_key, list_api_keys_for_user, revoke_api_key,
+    ApiKeyOwner, ApiKeyRow, IssuedApiKey, find_api_key_by_prefix, issue_api_key,
+    list_api_keys_for_user, revoke_api_key,
 };
 pub use error::{AccessTokenRepoError, Result};
+pub use systemprompt_web_access::repositories::api_keys::find_api_key_owner;
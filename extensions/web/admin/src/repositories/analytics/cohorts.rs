@@ -0,0 +1,68 @@
+//! One row per analysed session, with the attributes cohorts group by.
+//!
+//! A session's department is its user's department in `user_profile_ext` at
+//! read time (`Default` when unset), its model and plugin come from the
+//! session summary, and its agent is the agent that handled most of its hook
+//! events. No plugin version is recorded with a session, so plugin cohorts
+//! are per plugin id; comparing two date ranges is how a rollout is read.
+
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use systemprompt::identifiers::{SessionId, UserId};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CohortSessionRow {
+    pub session_id: SessionId,
+    pub user_id: UserId,
+    pub title: String,
+    pub created_at: DateTime<Utc>,
+    pub goal_achieved: String,
+    pub quality_score: i16,
+    pub corrections_count: i32,
+    pub best_practices_checklist: Option<serde_json::Value>,
+    pub skill_scores: Option<serde_json::Value>,
+    pub category: String,
+    pub department: String,
+    pub agent: Option<String>,
+    pub model: Option<String>,
+    pub plugin: Option<String>,
+}
+
+/// Sessions analysed in `[from, to)`, newest first, at most `limit`.
+pub async fn list_cohort_sessions(
+    pool: &PgPool,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    limit: i64,
+) -> Result<Vec<CohortSessionRow>, sqlx::Error> {
+    sqlx::query_as!(
+        CohortSessionRow,
+        r#"SELECT a.session_id AS "session_id: SessionId", a.user_id AS "user_id: UserId",
+                  a.title, a.created_at, a.goal_achieved, a.quality_score,
+                  a.corrections_count, a.best_practices_checklist, a.skill_scores, a.category,
+                  COALESCE(NULLIF(upe.department, ''), 'Default') AS "department!",
+                  ag.agent AS "agent?", ps.model AS "model?", ps.plugin_id AS "plugin?"
+           FROM session_analyses a
+           LEFT JOIN user_profile_ext upe ON upe.user_id = a.user_id
+           LEFT JOIN LATERAL (
+               SELECT s.model, s.plugin_id FROM plugin_session_summaries s
+               WHERE s.session_id = a.session_id
+               ORDER BY s.updated_at DESC LIMIT 1
+           ) ps ON TRUE
+           LEFT JOIN LATERAL (
+               SELECT COALESCE(e.metadata->>'agent_id', e.plugin_id) AS agent
+               FROM plugin_usage_events e
+               WHERE e.session_id = a.session_id
+                 AND (e.metadata->>'agent_id' IS NOT NULL OR e.plugin_id IS NOT NULL)
+               GROUP BY 1 ORDER BY COUNT(*) DESC, 1 LIMIT 1
+           ) ag ON TRUE
+           WHERE a.created_at >= $1 AND a.created_at < $2
+           ORDER BY a.created_at DESC
+           LIMIT $3"#,
+        from,
+        to,
+        limit,
+    )
+    .fetch_all(pool)
+    .await
+}
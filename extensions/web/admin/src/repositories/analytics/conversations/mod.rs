@@ -15,11 +15,10 @@ use serde::Serialize;
 use systemprompt::identifiers::{PluginId, SessionId, TraceId, UserId};
 
 mod detail;
-mod redact;
 mod transcript;
 
 pub use detail::find_raw_turns;
-pub use redact::redact_text;
+pub use systemprompt_web_access::redact_text;
 
 #[derive(Debug, Clone, Serialize)]
 pub struct ConversationListItem {
@@ -1,6 +1,7 @@
 //! Persistence for the analytics pages and their CSV exports.
 
 pub mod agents;
+pub mod cohorts;
 pub mod content_rollup;
 pub mod context_detail;
 pub mod contexts_list;
@@ -11,6 +12,7 @@ pub mod requests;
 pub mod session_detail;
 pub mod sessions_list;
 pub mod tools;
+pub mod upstream_health;
 
 pub use agents::{AgentRow, list_agents};
 pub use conversations::{
@@ -2,13 +2,15 @@
 //!
 //! Three helpers:
 //! - [`get_request_stats`] — overall KPI strip (rate / latency percentiles /
-//!   cost / error rate / pre-flight deny rate over a [`TimeRange`]).
+//!   cost / error rate / pre-flight deny rate over a [`TimeRange`]), for
+//!   everyone or one [`ActivityScope`].
 //! - [`list_latency_histogram`] — bucketed at fixed bin edges.
 //! - [`list_request_timeseries`] — 24-bucket traffic / error / cost series.
 
 use serde::Serialize;
 use sqlx::PgPool;
 
+use crate::util::activity_scope::ActivityScope;
 use crate::util::time_range::TimeRange;
 
 /// Fixed latency-histogram bin edges (ms). The final bin is open-ended.
@@ -24,6 +26,7 @@ pub struct RequestStats {
     pub p95_latency_ms: f64,
     pub p99_latency_ms: f64,
     pub total_cost_microdollars: i64,
+    pub distinct_users: i64,
     pub error_rate: f64,
     pub denied_session_count: i64,
     pub denied_session_rate: f64,
@@ -32,13 +35,19 @@ pub struct RequestStats {
 pub async fn get_request_stats(
     pool: &PgPool,
     range: TimeRange,
+    scope: ActivityScope<'_>,
 ) -> Result<RequestStats, sqlx::Error> {
     let row = sqlx::query!(
         r#"WITH
             requests AS (
-                SELECT id, status, latency_ms, cost_microdollars, session_id, created_at
-                FROM ai_requests
-                WHERE created_at >= $1 AND created_at < $2
+                SELECT r.id, r.status, r.latency_ms, r.cost_microdollars, r.session_id,
+                       r.user_id, r.created_at
+                FROM ai_requests r
+                LEFT JOIN user_profile_ext upe ON upe.user_id = r.user_id
+                WHERE r.created_at >= $1 AND r.created_at < $2
+                  AND ($3::text IS NULL OR r.user_id = $3)
+                  AND ($4::text IS NULL
+                       OR COALESCE(NULLIF(upe.department, ''), 'Default') = $4)
             ),
             with_deny AS (
                 SELECT DISTINCT r.session_id
@@ -59,12 +68,15 @@ pub async fn get_request_stats(
             COALESCE(percentile_cont(0.99) WITHIN GROUP (ORDER BY latency_ms), 0)::float8
                 AS "p99!",
             COALESCE(SUM(cost_microdollars), 0)::bigint AS "total_cost!",
+            COUNT(DISTINCT user_id)::bigint AS "distinct_users!",
             (SELECT COUNT(*) FROM with_deny)::bigint AS "denied_sessions!",
             COUNT(DISTINCT session_id) FILTER (WHERE session_id IS NOT NULL)::bigint
                 AS "distinct_sessions!"
         FROM requests"#,
         range.from,
         range.to,
+        scope.user(),
+        scope.department,
     )
     .fetch_one(pool)
     .await?;
@@ -94,6 +106,7 @@ pub async fn get_request_stats(
         p95_latency_ms: row.p95,
         p99_latency_ms: row.p99,
         total_cost_microdollars: row.total_cost,
+        distinct_users: row.distinct_users,
         error_rate,
         denied_session_count: row.denied_sessions,
         denied_session_rate,
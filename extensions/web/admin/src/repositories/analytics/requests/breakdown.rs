@@ -7,12 +7,16 @@
 //! matches `view::is_error_status`, so a status row's `error_count` and the
 //! table's danger badge can never disagree.
 //!
+//! The Models tab's rollup also takes an [`ActivityScope`], so a digest's top
+//! models are the dashboard's, narrowed to its user or department.
+//!
 //! `sqlx::query_as!` needs static SQL, so the grouping column cannot be a bind
 //! parameter — hence three functions over one shared shape rather than a
 //! `GROUP BY $1`.
 
 use sqlx::PgPool;
 
+use crate::util::activity_scope::ActivityScope;
 use crate::util::time_range::TimeRange;
 
 #[derive(Debug, Clone)]
@@ -30,28 +34,34 @@ pub struct BreakdownRow {
 pub async fn list_requests_by_model(
     pool: &PgPool,
     range: TimeRange,
+    scope: ActivityScope<'_>,
 ) -> Result<Vec<BreakdownRow>, sqlx::Error> {
     sqlx::query_as!(
         BreakdownRow,
         r#"SELECT
-             model AS "key!",
+             r.model AS "key!",
              COUNT(*)::bigint AS "requests!",
-             COUNT(*) FILTER (WHERE status NOT IN ('completed', 'pending', 'streaming'))::bigint
+             COUNT(*) FILTER (WHERE r.status NOT IN ('completed', 'pending', 'streaming'))::bigint
                AS "error_count!",
-             COALESCE(SUM(input_tokens), 0)::bigint AS "input_tokens!",
-             COALESCE(SUM(output_tokens), 0)::bigint AS "output_tokens!",
-             COALESCE(SUM(cost_microdollars), 0)::bigint AS "cost_microdollars!",
-             COALESCE(percentile_cont(0.50) WITHIN GROUP (ORDER BY latency_ms), 0)::float8
+             COALESCE(SUM(r.input_tokens), 0)::bigint AS "input_tokens!",
+             COALESCE(SUM(r.output_tokens), 0)::bigint AS "output_tokens!",
+             COALESCE(SUM(r.cost_microdollars), 0)::bigint AS "cost_microdollars!",
+             COALESCE(percentile_cont(0.50) WITHIN GROUP (ORDER BY r.latency_ms), 0)::float8
                AS "p50_latency_ms!",
-             COALESCE(percentile_cont(0.95) WITHIN GROUP (ORDER BY latency_ms), 0)::float8
+             COALESCE(percentile_cont(0.95) WITHIN GROUP (ORDER BY r.latency_ms), 0)::float8
                AS "p95_latency_ms!"
-           FROM ai_requests
-           WHERE created_at >= $1 AND created_at < $2
-             AND model IS NOT NULL AND model <> ''
-           GROUP BY model
-           ORDER BY COUNT(*) DESC, model"#,
+           FROM ai_requests r
+           LEFT JOIN user_profile_ext upe ON upe.user_id = r.user_id
+           WHERE r.created_at >= $1 AND r.created_at < $2
+             AND r.model IS NOT NULL AND r.model <> ''
+             AND ($3::text IS NULL OR r.user_id = $3)
+             AND ($4::text IS NULL OR COALESCE(NULLIF(upe.department, ''), 'Default') = $4)
+           GROUP BY r.model
+           ORDER BY COUNT(*) DESC, r.model"#,
         range.from,
         range.to,
+        scope.user(),
+        scope.department,
     )
     .fetch_all(pool)
     .await
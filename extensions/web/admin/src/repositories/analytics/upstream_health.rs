@@ -0,0 +1,52 @@
+//! Recent upstream failures per provider and model, the input to gateway
+//! target health.
+//!
+//! Only failures a different upstream could have avoided count: the gateway
+//! records an upstream HTTP error as `"<provider> returned <status>: ..."` and
+//! a transport failure (refused connection, timeout) as
+//! `"<provider> request failed: ..."`, so a 429, any 5xx, or a transport
+//! failure marks the target, while a 400 from a malformed request does not.
+//!
+//! Failures the gateway retried past on another target are not on the
+//! request's row, which carries only the error core was handed; they are read
+//! from `gateway_target_failures`, which holds only such failures.
+
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+
+#[derive(Debug, Clone)]
+pub struct UpstreamFailureRow {
+    pub provider: String,
+    pub model: String,
+    pub failures: i64,
+    pub last_failure_at: DateTime<Utc>,
+}
+
+pub async fn list_upstream_failures(
+    pool: &PgPool,
+    since: DateTime<Utc>,
+) -> Result<Vec<UpstreamFailureRow>, sqlx::Error> {
+    sqlx::query_as!(
+        UpstreamFailureRow,
+        r#"SELECT provider AS "provider!", model AS "model!",
+                  COUNT(*)::bigint AS "failures!",
+                  MAX(failed_at) AS "last_failure_at!"
+           FROM (
+               SELECT provider, model, created_at AS failed_at
+               FROM ai_requests
+               WHERE created_at >= $1
+                 AND status = 'failed'
+                 AND provider IS NOT NULL AND model IS NOT NULL
+                 AND (error_message ~ ' returned (429|5[0-9][0-9]):'
+                      OR error_message ~* '(request failed|timed out|timeout)')
+               UNION ALL
+               SELECT provider, model, failed_at
+               FROM gateway_target_failures
+               WHERE failed_at >= $1
+           ) failures
+           GROUP BY provider, model"#,
+        since,
+    )
+    .fetch_all(pool)
+    .await
+}
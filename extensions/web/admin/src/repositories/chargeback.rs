@@ -0,0 +1,185 @@
+//! Chargeback lines, live for an open month and snapshotted for a closed one,
+//! and the closed-period register.
+//!
+//! A line is one user's spend on one provider and model within a department.
+//! Eval and judge traffic (`actor_kind = 'job'`) is part of the gross and
+//! repeated as a credit, so statements show what the platform spent on a
+//! department's behalf without billing it. Emails are joined at read time
+//! and never snapshotted.
+
+use chrono::{DateTime, NaiveDate, Utc};
+use sqlx::PgPool;
+use systemprompt::identifiers::UserId;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChargebackLine {
+    pub department: String,
+    pub cost_center: String,
+    pub user_id: UserId,
+    pub user_email: Option<String>,
+    pub provider: String,
+    pub model: String,
+    pub requests: i64,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub gross_microdollars: i64,
+    pub credit_microdollars: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChargebackPeriod {
+    pub month_start: NaiveDate,
+    pub closed_at: DateTime<Utc>,
+    pub closed_by: UserId,
+    pub gross_microdollars: i64,
+    pub credit_microdollars: i64,
+}
+
+/// Spend in `[since, until)` aggregated into lines, cost centers left blank
+/// for the caller to assign.
+pub async fn list_chargeback_usage(
+    pool: &PgPool,
+    since: DateTime<Utc>,
+    until: DateTime<Utc>,
+) -> Result<Vec<ChargebackLine>, sqlx::Error> {
+    sqlx::query_as!(
+        ChargebackLine,
+        r#"SELECT
+            COALESCE(NULLIF(upe.department, ''), 'Default')  AS "department!",
+            ''                                               AS "cost_center!",
+            r.user_id                                        AS "user_id!: UserId",
+            MAX(u.email)                                     AS "user_email?",
+            COALESCE(r.provider, 'unknown')                  AS "provider!",
+            COALESCE(r.model, 'unknown')                     AS "model!",
+            COUNT(*)                                         AS "requests!",
+            COALESCE(SUM(r.input_tokens), 0)::bigint         AS "input_tokens!",
+            COALESCE(SUM(r.output_tokens), 0)::bigint        AS "output_tokens!",
+            SUM(r.cost_microdollars)::bigint                 AS "gross_microdollars!",
+            COALESCE(SUM(r.cost_microdollars) FILTER (WHERE r.actor_kind = 'job'), 0)::bigint
+                                                             AS "credit_microdollars!"
+        FROM ai_requests r
+        LEFT JOIN user_profile_ext upe ON upe.user_id = r.user_id
+        LEFT JOIN users u ON u.id = r.user_id
+        WHERE r.created_at >= $1 AND r.created_at < $2
+        GROUP BY 1, 3, 5, 6
+        ORDER BY 1, 3, 5, 6"#,
+        since,
+        until,
+    )
+    .fetch_all(pool)
+    .await
+}
+
+/// The snapshot of a closed month.
+pub async fn list_chargeback_lines(
+    pool: &PgPool,
+    month_start: NaiveDate,
+) -> Result<Vec<ChargebackLine>, sqlx::Error> {
+    sqlx::query_as!(
+        ChargebackLine,
+        r#"SELECT
+            l.department, l.cost_center,
+            l.user_id AS "user_id: UserId",
+            u.email   AS "user_email?",
+            l.provider, l.model, l.requests, l.input_tokens, l.output_tokens,
+            l.gross_microdollars, l.credit_microdollars
+        FROM chargeback_lines l
+        LEFT JOIN users u ON u.id = l.user_id
+        WHERE l.month_start = $1
+        ORDER BY l.department, l.user_id, l.provider, l.model"#,
+        month_start,
+    )
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn find_chargeback_period(
+    pool: &PgPool,
+    month_start: NaiveDate,
+) -> Result<Option<ChargebackPeriod>, sqlx::Error> {
+    sqlx::query_as!(
+        ChargebackPeriod,
+        r#"SELECT month_start, closed_at, closed_by AS "closed_by: UserId",
+                  gross_microdollars, credit_microdollars
+        FROM chargeback_periods
+        WHERE month_start = $1"#,
+        month_start,
+    )
+    .fetch_optional(pool)
+    .await
+}
+
+pub async fn list_chargeback_periods(pool: &PgPool) -> Result<Vec<ChargebackPeriod>, sqlx::Error> {
+    sqlx::query_as!(
+        ChargebackPeriod,
+        r#"SELECT month_start, closed_at, closed_by AS "closed_by: UserId",
+                  gross_microdollars, credit_microdollars
+        FROM chargeback_periods
+        ORDER BY month_start DESC"#,
+    )
+    .fetch_all(pool)
+    .await
+}
+
+/// Snapshot `lines` and close `month_start`, atomically. Returns `false`,
+/// writing nothing, when the month was already closed.
+pub async fn insert_closed_chargeback_period(
+    pool: &PgPool,
+    month_start: NaiveDate,
+    closed_by: &UserId,
+    lines: &[ChargebackLine],
+) -> Result<bool, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    // Why: two admins closing the same month at once would otherwise both
+    // pass the closed check and collide half-way through the lines.
+    sqlx::query!("SELECT pg_advisory_xact_lock(hashtext('chargeback_close'))")
+        .execute(&mut *tx)
+        .await?;
+    let closed = sqlx::query_scalar!(
+        r#"SELECT EXISTS (SELECT 1 FROM chargeback_periods WHERE month_start = $1) AS "closed!""#,
+        month_start,
+    )
+    .fetch_one(&mut *tx)
+    .await?;
+    if closed {
+        return Ok(false);
+    }
+
+    for line in lines {
+        sqlx::query!(
+            r#"INSERT INTO chargeback_lines
+                (month_start, department, cost_center, user_id, provider, model, requests,
+                 input_tokens, output_tokens, gross_microdollars, credit_microdollars)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)"#,
+            month_start,
+            line.department,
+            line.cost_center,
+            line.user_id.as_str(),
+            line.provider,
+            line.model,
+            line.requests,
+            line.input_tokens,
+            line.output_tokens,
+            line.gross_microdollars,
+            line.credit_microdollars,
+        )
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    let gross: i64 = lines.iter().map(|l| l.gross_microdollars).sum();
+    let credit: i64 = lines.iter().map(|l| l.credit_microdollars).sum();
+    sqlx::query!(
+        r#"INSERT INTO chargeback_periods
+            (month_start, closed_by, gross_microdollars, credit_microdollars)
+        VALUES ($1, $2, $3, $4)"#,
+        month_start,
+        closed_by.as_str(),
+        gross,
+        credit,
+    )
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+    Ok(true)
+}
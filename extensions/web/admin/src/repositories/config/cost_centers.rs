@@ -0,0 +1,72 @@
+//! `services/finops/cost_centers.yaml`: the finance cost center each
+//! department, and optionally each user, is charged to.
+//!
+//! A user entry overrides their department's, for the contractor or shared
+//! platform engineer whose spend belongs to another budget. Read whenever a
+//! statement is built; a closed month keeps the cost centers it was closed
+//! with, so an edit here only moves open and future months.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use serde::Deserialize;
+use systemprompt_web_shared::error::MarketplaceError;
+
+const COST_CENTERS_FILE: &str = "finops/cost_centers.yaml";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields, default)]
+pub struct CostCenters {
+    /// Department name to cost center.
+    pub departments: BTreeMap<String, String>,
+    /// User email to cost center, matched case-insensitively.
+    pub users: BTreeMap<String, String>,
+}
+
+impl CostCenters {
+    pub fn validate(&self) -> Result<(), MarketplaceError> {
+        let blank = self
+            .departments
+            .iter()
+            .chain(&self.users)
+            .find(|(key, center)| key.trim().is_empty() || center.trim().is_empty());
+        if let Some((key, _)) = blank {
+            return Err(MarketplaceError::BadRequest(format!(
+                "cost_centers entry '{key}' needs both a name and a cost center"
+            )));
+        }
+        Ok(())
+    }
+
+    /// The cost center for a user in `department`, or `""` when neither the
+    /// user nor the department has one.
+    #[must_use]
+    pub fn cost_center_for(&self, department: &str, email: Option<&str>) -> &str {
+        email
+            .and_then(|e| {
+                self.users
+                    .iter()
+                    .find(|(k, _)| k.eq_ignore_ascii_case(e))
+                    .map(|(_, v)| v.as_str())
+            })
+            .or_else(|| self.departments.get(department).map(String::as_str))
+            .unwrap_or("")
+    }
+}
+
+/// Load the cost-center file. A missing or empty file assigns none; a file
+/// that fails to parse or validate is an error.
+pub fn load_cost_centers(services_path: &Path) -> Result<CostCenters, MarketplaceError> {
+    let path = services_path.join(COST_CENTERS_FILE);
+    let centers: CostCenters = match std::fs::read_to_string(&path) {
+        Ok(s) if s.trim().is_empty() => return Ok(CostCenters::default()),
+        Ok(s) => serde_yaml::from_str(&s)
+            .map_err(|e| MarketplaceError::config_file(path.display().to_string(), e))?,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Ok(CostCenters::default());
+        },
+        Err(e) => return Err(e.into()),
+    };
+    centers.validate()?;
+    Ok(centers)
+}
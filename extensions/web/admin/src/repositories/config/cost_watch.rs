@@ -0,0 +1,107 @@
+//! `services/finops/cost_watch.yaml`: what counts as a spend anomaly, and
+//! how month-end spend is projected.
+//!
+//! Read on every `cost_anomaly_scan` run, so a threshold edit applies from
+//! the next hour. A missing file runs with the defaults below.
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use systemprompt_web_shared::error::MarketplaceError;
+
+const COST_WATCH_FILE: &str = "finops/cost_watch.yaml";
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields, default)]
+pub struct CostWatchConfig {
+    pub enabled: bool,
+    /// Trailing days of hourly spend a subject's norm is drawn from.
+    pub baseline_days: u32,
+    /// Closed hours each run re-examines. Already-flagged hours are skipped,
+    /// so overlap between runs costs nothing but a query.
+    pub evaluate_hours: u32,
+    /// An hour is anomalous only when its spend is at least this multiple of
+    /// the subject's hourly mean...
+    pub ratio_threshold: f64,
+    /// ...and this many standard deviations above it.
+    pub z_threshold: f64,
+    /// Ratio at which an anomaly is `critical` rather than `warning`.
+    pub critical_ratio: f64,
+    /// Hours spending less than this are never flagged, however quiet the
+    /// subject usually is.
+    pub min_spend_usd: f64,
+    /// Floor under the hourly mean, so a subject with almost no history does
+    /// not trip the ratio on its first real hour.
+    pub min_baseline_usd: f64,
+    /// Trailing whole days whose average daily spend projects the rest of
+    /// the month.
+    pub forecast_trailing_days: u32,
+}
+
+impl Default for CostWatchConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            baseline_days: 14,
+            evaluate_hours: 3,
+            ratio_threshold: 10.0,
+            z_threshold: 4.0,
+            critical_ratio: 50.0,
+            min_spend_usd: 1.0,
+            min_baseline_usd: 0.10,
+            forecast_trailing_days: 7,
+        }
+    }
+}
+
+impl CostWatchConfig {
+    pub fn validate(&self) -> Result<(), MarketplaceError> {
+        if self.baseline_days == 0 || self.forecast_trailing_days == 0 {
+            return Err(MarketplaceError::BadRequest(
+                "cost_watch baseline_days and forecast_trailing_days must be above zero".to_owned(),
+            ));
+        }
+        if !(1..=168).contains(&self.evaluate_hours) {
+            return Err(MarketplaceError::BadRequest(
+                "cost_watch evaluate_hours must be between 1 and 168".to_owned(),
+            ));
+        }
+        let positive = [
+            self.ratio_threshold,
+            self.critical_ratio,
+            self.min_baseline_usd,
+        ];
+        if positive.iter().any(|v| !v.is_finite() || *v <= 0.0)
+            || !self.z_threshold.is_finite()
+            || !self.min_spend_usd.is_finite()
+        {
+            return Err(MarketplaceError::BadRequest(
+                "cost_watch thresholds must be finite, and ratios and min_baseline_usd above zero"
+                    .to_owned(),
+            ));
+        }
+        if self.critical_ratio < self.ratio_threshold {
+            return Err(MarketplaceError::BadRequest(
+                "cost_watch critical_ratio must be at least ratio_threshold".to_owned(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Load the cost-watch file. A missing or empty file is the defaults; a file
+/// that fails to parse or validate is an error.
+pub fn load_cost_watch_config(services_path: &Path) -> Result<CostWatchConfig, MarketplaceError> {
+    let path = services_path.join(COST_WATCH_FILE);
+    let config: CostWatchConfig = match std::fs::read_to_string(&path) {
+        Ok(s) if s.trim().is_empty() => return Ok(CostWatchConfig::default()),
+        Ok(s) => serde_yaml::from_str(&s)
+            .map_err(|e| MarketplaceError::config_file(path.display().to_string(), e))?,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Ok(CostWatchConfig::default());
+        },
+        Err(e) => return Err(e.into()),
+    };
+    config.validate()?;
+    Ok(config)
+}
@@ -0,0 +1,227 @@
+//! `services/reports/digests.yaml`: who gets a digest, how often, and how it
+//! is delivered.
+//!
+//! Read on every `digest_reports` run, so a new subscription is picked up
+//! within the hour. A missing file sends nothing.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+use chrono::Weekday;
+use serde::Deserialize;
+use systemprompt::identifiers::UserId;
+use systemprompt_web_shared::error::MarketplaceError;
+
+use crate::digests::{DigestCadence, DigestScope};
+
+const DIGESTS_FILE: &str = "reports/digests.yaml";
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields, default)]
+pub struct DigestConfig {
+    pub enabled: bool,
+    /// `From:` of every digest email.
+    pub from: String,
+    /// Public base URL of the admin UI, for the links in a digest. Links are
+    /// left out when empty.
+    pub admin_url: String,
+    /// Hour (UTC) a period closes and its digest goes out.
+    pub send_hour_utc: u32,
+    /// Day a weekly period closes.
+    pub weekly_on: Weekday,
+    /// Deliveries tried per digest and channel before it is given up on.
+    pub max_attempts: u32,
+    pub mail: MailTransportConfig,
+    pub subscriptions: Vec<DigestSubscription>,
+}
+
+impl Default for DigestConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            from: "systemprompt <digests@localhost>".to_owned(),
+            admin_url: String::new(),
+            send_hour_utc: 7,
+            weekly_on: Weekday::Mon,
+            max_attempts: 5,
+            mail: MailTransportConfig::default(),
+            subscriptions: Vec::new(),
+        }
+    }
+}
+
+/// How digest email leaves the process.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(tag = "transport", rename_all = "snake_case", deny_unknown_fields)]
+pub enum MailTransportConfig {
+    /// Write each message as an `.eml` file instead of sending it. Relative
+    /// directories resolve against the system root; the default is
+    /// `logs/digests`.
+    File {
+        #[serde(default)]
+        dir: Option<PathBuf>,
+    },
+    Smtp {
+        host: String,
+        #[serde(default = "default_smtp_port")]
+        port: u16,
+        /// Environment variables holding the credentials, so they stay out
+        /// of the file. No credentials sends unauthenticated.
+        #[serde(default)]
+        username_env: Option<String>,
+        #[serde(default)]
+        password_env: Option<String>,
+        #[serde(default)]
+        tls: SmtpTls,
+    },
+}
+
+impl Default for MailTransportConfig {
+    fn default() -> Self {
+        Self::File { dir: None }
+    }
+}
+
+const fn default_smtp_port() -> u16 {
+    587
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SmtpTls {
+    #[default]
+    Starttls,
+    Tls,
+    None,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DigestSubscription {
+    pub id: String,
+    /// Heading of the digest; the id when empty.
+    #[serde(default)]
+    pub name: String,
+    pub cadence: DigestCadence,
+    /// Report on one department. Leave this and `user_id` out for the whole
+    /// organisation.
+    #[serde(default)]
+    pub department: Option<String>,
+    /// Report on one user.
+    #[serde(default)]
+    pub user_id: Option<UserId>,
+    #[serde(default)]
+    pub email: Vec<String>,
+    #[serde(default)]
+    pub webhook: Option<DigestWebhook>,
+}
+
+impl DigestSubscription {
+    #[must_use]
+    pub fn scope(&self) -> DigestScope<'_> {
+        match (&self.user_id, &self.department) {
+            (Some(user_id), _) => DigestScope::User(user_id),
+            (None, Some(department)) => DigestScope::Department(department),
+            (None, None) => DigestScope::Global,
+        }
+    }
+
+    #[must_use]
+    pub fn title(&self) -> &str {
+        if self.name.trim().is_empty() {
+            &self.id
+        } else {
+            &self.name
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DigestWebhook {
+    pub url: String,
+    /// Request headers whose values are read from environment variables,
+    /// header name to variable name.
+    #[serde(default)]
+    pub headers_env: BTreeMap<String, String>,
+}
+
+const fn invalid(message: String) -> MarketplaceError {
+    MarketplaceError::BadRequest(message)
+}
+
+impl DigestSubscription {
+    fn validate(&self) -> Result<(), MarketplaceError> {
+        let id = &self.id;
+        if id.is_empty()
+            || !id
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err(invalid(format!(
+                "digest subscription id `{id}` must be letters, digits, `-` or `_`"
+            )));
+        }
+        if self.user_id.is_some() && self.department.is_some() {
+            return Err(invalid(format!(
+                "digest `{id}` names both a user and a department; pick one"
+            )));
+        }
+        if self.email.is_empty() && self.webhook.is_none() {
+            return Err(invalid(format!(
+                "digest `{id}` needs at least one email address or a webhook"
+            )));
+        }
+        if let Some(bad) = self.email.iter().find(|e| !e.contains('@')) {
+            return Err(invalid(format!(
+                "digest `{id}` email `{bad}` is not an address"
+            )));
+        }
+        if let Some(webhook) = &self.webhook
+            && !(webhook.url.starts_with("https://") || webhook.url.starts_with("http://"))
+        {
+            return Err(invalid(format!(
+                "digest `{id}` webhook url must be http(s)"
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl DigestConfig {
+    pub fn validate(&self) -> Result<(), MarketplaceError> {
+        if self.send_hour_utc > 23 || self.max_attempts == 0 {
+            return Err(invalid(
+                "digests send_hour_utc must be 0-23 and max_attempts above zero".to_owned(),
+            ));
+        }
+        let mut seen = BTreeSet::new();
+        for subscription in &self.subscriptions {
+            subscription.validate()?;
+            if !seen.insert(subscription.id.as_str()) {
+                return Err(invalid(format!(
+                    "digest subscription id `{}` is used twice",
+                    subscription.id
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Load the digests file. A missing or empty file is the defaults, which
+/// subscribe no one; a file that fails to parse or validate is an error.
+pub fn load_digest_config(services_path: &Path) -> Result<DigestConfig, MarketplaceError> {
+    let path = services_path.join(DIGESTS_FILE);
+    let config: DigestConfig = match std::fs::read_to_string(&path) {
+        Ok(s) if s.trim().is_empty() => return Ok(DigestConfig::default()),
+        Ok(s) => serde_yaml::from_str(&s)
+            .map_err(|e| MarketplaceError::config_file(path.display().to_string(), e))?,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Ok(DigestConfig::default());
+        },
+        Err(e) => return Err(e.into()),
+    };
+    config.validate()?;
+    Ok(config)
+}
@@ -1,9 +1,11 @@
-//! Gateway top-level settings (enabled flag, auth scheme, path prefix) and the
-//! assembled [`GatewayConfigView`] read view.
+//! Gateway top-level settings (enabled flag, auth scheme, path prefix), the
+//! assembled [`GatewayConfigView`] read view, and the same block resolved into
+//! core's runtime [`GatewayConfig`].
 
 use std::path::Path;
 
 use serde_yaml::Value;
+use systemprompt::models::profile::{GatewayConfig, GatewayConfigSpec, ProviderRegistry};
 use systemprompt_web_shared::error::MarketplaceError;
 
 use crate::types::{GatewayConfigView, UpdateGatewaySettingsRequest};
@@ -45,9 +47,32 @@ pub fn get_gateway_config(profile_path: &Path) -> Result<GatewayConfigView, Mark
         inference_path_prefix,
         routes,
         profile_path: profile_path.display().to_string(),
+        etag: String::new(),
     })
 }
 
+/// The profile's `gateway` block and provider registry as core reads them, so
+/// callers can run core's own route selection over what is on disk now.
+pub fn get_resolved_gateway(
+    profile_path: &Path,
+) -> Result<(GatewayConfig, ProviderRegistry), MarketplaceError> {
+    ensure_route_ids(profile_path)?;
+    let doc = read_profile(profile_path)?;
+    let config = match doc.get("gateway") {
+        Some(gateway) if !gateway.is_null() => {
+            serde_yaml::from_value::<GatewayConfigSpec>(gateway.clone())?.resolve()
+        },
+        _ => GatewayConfig::default(),
+    };
+    let registry = match doc.get("providers") {
+        Some(providers) if !providers.is_null() => {
+            serde_yaml::from_value::<ProviderRegistry>(providers.clone())?
+        },
+        _ => ProviderRegistry::default(),
+    };
+    Ok((config, registry))
+}
+
 pub fn update_gateway_settings(
     profile_path: &Path,
     req: &UpdateGatewaySettingsRequest,
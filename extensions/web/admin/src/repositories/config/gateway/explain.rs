@@ -0,0 +1,66 @@
+//! Pure pieces of the route explainer: the walk over core's candidate routes
+//! and header masking.
+
+use systemprompt::models::profile::{GatewayConfig, ProviderRegistry};
+use systemprompt::models::wire::canonical::CanonicalRequest;
+
+use crate::types::{RouteMatchOutcome, RouteMatchStep};
+
+const VISIBLE_PREFIX: usize = 4;
+
+/// Every route core would try for `request`, in core's order.
+///
+/// That is the profile routes, then the route core synthesizes for
+/// `default_provider`. Each is judged by core's own `matches_request`, so a
+/// route whose `when` conditions reject the request is reported as such
+/// rather than as a match.
+#[must_use]
+pub fn explain_route_match(
+    config: &GatewayConfig,
+    registry: &ProviderRegistry,
+    request: &CanonicalRequest,
+) -> Vec<RouteMatchStep> {
+    let mut found = false;
+    config
+        .candidate_routes(registry)
+        .enumerate()
+        .map(|(index, route)| {
+            let mut route = route.into_owned();
+            route.ensure_id();
+            let outcome = if found {
+                RouteMatchOutcome::Shadowed
+            } else if route.matches_request(request) {
+                found = true;
+                RouteMatchOutcome::Matched
+            } else if route.matches(&request.model) {
+                RouteMatchOutcome::Unmet
+            } else {
+                RouteMatchOutcome::Skipped
+            };
+            RouteMatchStep {
+                index,
+                id: route.id.as_str().to_owned(),
+                model_pattern: route.model_pattern,
+                provider: route.provider.as_str().to_owned(),
+                conditions: route
+                    .when
+                    .map(|when| when.matched_predicates())
+                    .unwrap_or_default(),
+                default_route: index >= config.routes.len(),
+                outcome,
+            }
+        })
+        .collect()
+}
+
+/// Keep enough of a header value to recognise it and hide the rest. Values
+/// short enough that a prefix would give most of them away are hidden whole.
+#[must_use]
+pub fn mask_header_value(value: &str) -> String {
+    let chars = value.chars().count();
+    if chars <= VISIBLE_PREFIX * 3 {
+        return "••••".to_owned();
+    }
+    let prefix: String = value.chars().take(VISIBLE_PREFIX).collect();
+    format!("{prefix}•••• ({chars} chars)")
+}
@@ -3,18 +3,37 @@
 //! The gateway config is not a Postgres table: it lives in the profile YAML's
 //! `gateway` block, which is why it sits here. These functions read,
 //! mutate, and re-serialize that block while keeping every route's stable `id`
-//! synchronized.
+//! synchronized. Each route's weighted and fallback upstreams live beside it
+//! in the `services/gateway/targets.yaml` sidecar, and its response cache
+//! settings in `services/gateway/cache.yaml`. A [`GatewaySnapshot`]
+//! captures the profile block and the targets for revision history and
+//! rollback, and [`StagedGatewayFiles`] lands an edit to both at once.
 
 mod config;
+mod explain;
 mod matching;
+mod response_cache;
 mod routes;
+mod snapshot;
+mod staged;
+mod targets;
 mod yaml_io;
 
-pub use config::{get_gateway_config, update_gateway_settings};
+pub use config::{get_gateway_config, get_resolved_gateway, update_gateway_settings};
+pub use explain::{explain_route_match, mask_header_value};
 pub use matching::{
     find_matching_route, find_matching_route_index, find_route_index_by_id, glob_match,
     slugify_pattern, synthesize_route_id,
 };
+pub use response_cache::{
+    EmbeddingSettings, ResponseCacheConfig, RouteCacheSettings, load_response_cache_config,
+};
 pub use routes::{
     create_route, delete_route, ensure_route_ids, reorder_routes, update_route, validate_route,
 };
+pub use snapshot::{GatewaySnapshot, get_gateway_snapshot, restore_gateway_snapshot};
+pub use staged::StagedGatewayFiles;
+pub use targets::{
+    RouteTargetsConfig, TargetHealthSettings, load_route_targets, set_route_targets,
+    validate_targets,
+};
@@ -0,0 +1,124 @@
+//! `services/gateway/cache.yaml`: which routes serve repeated requests from
+//! the response cache, and how.
+//!
+//! A route without an entry here is never cached. Like the targets sidecar,
+//! the file sits beside the profile because core's `GatewayRoute` rejects
+//! unknown fields, and it is keyed by stable route id.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use systemprompt_web_shared::error::MarketplaceError;
+
+pub(super) const CACHE_FILE: &str = "gateway/cache.yaml";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ResponseCacheConfig {
+    /// The embeddings endpoint similarity matching calls. Required as soon as
+    /// any route sets a `similarity_threshold`.
+    #[serde(default)]
+    pub embedding: Option<EmbeddingSettings>,
+    #[serde(default)]
+    pub routes: BTreeMap<String, RouteCacheSettings>,
+}
+
+/// An `OpenAI`-compatible `/v1/embeddings` endpoint.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EmbeddingSettings {
+    pub endpoint: String,
+    pub model: String,
+    /// Environment variable holding the bearer token, if the endpoint needs
+    /// one.
+    #[serde(default)]
+    pub api_key_env: Option<String>,
+    #[serde(default = "default_timeout_ms")]
+    pub timeout_ms: u64,
+    /// Most recent entries compared per lookup.
+    #[serde(default = "default_max_candidates")]
+    pub max_candidates: u32,
+}
+
+const fn default_timeout_ms() -> u64 {
+    2_000
+}
+
+const fn default_max_candidates() -> u32 {
+    200
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields, default)]
+pub struct RouteCacheSettings {
+    pub ttl_secs: u64,
+    /// Keep each user's entries to themselves. Turning this off lets one
+    /// user's reply serve another user's identical request.
+    pub per_user: bool,
+    /// Cosine similarity, in `(0, 1]`, at which a near-identical final
+    /// message is served from the cache. Unset means exact matches only.
+    pub similarity_threshold: Option<f32>,
+}
+
+impl Default for RouteCacheSettings {
+    fn default() -> Self {
+        Self {
+            ttl_secs: 3_600,
+            per_user: true,
+            similarity_threshold: None,
+        }
+    }
+}
+
+impl ResponseCacheConfig {
+    #[must_use]
+    pub fn settings_for(&self, route_id: &str) -> Option<RouteCacheSettings> {
+        self.routes.get(route_id).copied()
+    }
+
+    /// Reject settings the cache could not honour: a zero TTL, a threshold
+    /// outside `(0, 1]`, or similarity matching with no embeddings endpoint.
+    pub fn validate(&self) -> Result<(), MarketplaceError> {
+        for (route_id, settings) in &self.routes {
+            if settings.ttl_secs == 0 {
+                return Err(MarketplaceError::BadRequest(format!(
+                    "cache for route `{route_id}` needs a ttl_secs above zero"
+                )));
+            }
+            let Some(threshold) = settings.similarity_threshold else {
+                continue;
+            };
+            if !(threshold > 0.0 && threshold <= 1.0) {
+                return Err(MarketplaceError::BadRequest(format!(
+                    "similarity_threshold for route `{route_id}` must be in (0, 1]"
+                )));
+            }
+            if self.embedding.is_none() {
+                return Err(MarketplaceError::BadRequest(format!(
+                    "route `{route_id}` sets a similarity_threshold but no embedding endpoint is configured"
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Load the cache file. A missing or empty file caches nothing; a file that
+/// fails to parse or validate is an error.
+pub fn load_response_cache_config(
+    services_path: &Path,
+) -> Result<ResponseCacheConfig, MarketplaceError> {
+    let path = services_path.join(CACHE_FILE);
+    let config: ResponseCacheConfig = match std::fs::read_to_string(&path) {
+        Ok(s) if s.trim().is_empty() => return Ok(ResponseCacheConfig::default()),
+        Ok(s) => serde_yaml::from_str(&s)
+            .map_err(|e| MarketplaceError::config_file(path.display().to_string(), e))?,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Ok(ResponseCacheConfig::default());
+        },
+        Err(e) => return Err(e.into()),
+    };
+    config.validate()?;
+    Ok(config)
+}
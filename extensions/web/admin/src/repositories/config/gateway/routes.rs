@@ -9,6 +9,7 @@ use systemprompt_web_shared::error::MarketplaceError;
 use crate::types::GatewayRouteView;
 
 use super::matching::synthesize_route_id;
+use super::targets::validate_targets;
 use super::yaml_io::{read_profile, route_to_yaml, routes_seq_mut, write_profile};
 
 pub fn validate_route(route: &GatewayRouteView) -> Result<(), MarketplaceError> {
@@ -20,7 +21,7 @@ pub fn validate_route(route: &GatewayRouteView) -> Result<(), MarketplaceError>
     if route.provider.trim().is_empty() {
         return Err(MarketplaceError::BadRequest("provider is required".into()));
     }
-    Ok(())
+    validate_targets(&route.targets)
 }
 
 /// Ensure every route in the profile has an explicit stable `id`, persisting
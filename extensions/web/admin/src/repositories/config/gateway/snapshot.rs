@@ -0,0 +1,95 @@
+//! The whole gateway configuration as one comparable, restorable value.
+//!
+//! A [`GatewaySnapshot`] is the profile's `gateway` block, re-serialized so
+//! key order and quoting are stable, plus the targets sidecar verbatim. Its
+//! [`etag`](GatewaySnapshot::etag) changes whenever either does, including
+//! edits made outside the admin API.
+
+use std::path::Path;
+
+use serde_yaml::Value;
+use sha2::{Digest, Sha256};
+use systemprompt_web_shared::error::MarketplaceError;
+
+use super::targets::TARGETS_FILE;
+use super::yaml_io::{read_profile, write_profile};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GatewaySnapshot {
+    pub gateway_yaml: String,
+    pub targets_yaml: String,
+}
+
+impl GatewaySnapshot {
+    #[must_use]
+    pub fn etag(&self) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(self.gateway_yaml.as_bytes());
+        hasher.update([0]);
+        hasher.update(self.targets_yaml.as_bytes());
+        hex::encode(&hasher.finalize()[..12])
+    }
+}
+
+pub fn get_gateway_snapshot(
+    profile_path: &Path,
+    services_path: &Path,
+) -> Result<GatewaySnapshot, MarketplaceError> {
+    let doc = read_profile(profile_path)?;
+    let gateway_yaml = match doc.get("gateway") {
+        None | Some(Value::Null) => String::new(),
+        Some(block) => serde_yaml::to_string(block)?,
+    };
+    let targets_yaml = match std::fs::read_to_string(services_path.join(TARGETS_FILE)) {
+        Ok(s) => s,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e.into()),
+    };
+    Ok(GatewaySnapshot {
+        gateway_yaml,
+        targets_yaml,
+    })
+}
+
+/// Put both files back exactly as `snapshot` recorded them. Every other key
+/// in the profile is left alone; an empty side removes the `gateway` block or
+/// the targets file.
+pub fn restore_gateway_snapshot(
+    profile_path: &Path,
+    services_path: &Path,
+    snapshot: &GatewaySnapshot,
+) -> Result<(), MarketplaceError> {
+    let block: Option<Value> = if snapshot.gateway_yaml.trim().is_empty() {
+        None
+    } else {
+        Some(serde_yaml::from_str(&snapshot.gateway_yaml)?)
+    };
+    let mut doc = read_profile(profile_path)?;
+    let root = doc
+        .as_mapping_mut()
+        .ok_or_else(|| MarketplaceError::Internal("profile YAML root is not a mapping".into()))?;
+    match block {
+        Some(block) => {
+            root.insert(Value::from("gateway"), block);
+        },
+        None => {
+            root.remove(Value::from("gateway"));
+        },
+    }
+    write_profile(profile_path, &doc)?;
+
+    let targets_path = services_path.join(TARGETS_FILE);
+    if snapshot.targets_yaml.is_empty() {
+        match std::fs::remove_file(&targets_path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.into()),
+            _ => {},
+        }
+    } else {
+        if let Some(dir) = targets_path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(&targets_path, &snapshot.targets_yaml)
+            .map_err(|e| MarketplaceError::config_file(targets_path.display().to_string(), e))?;
+    }
+    Ok(())
+}
@@ -0,0 +1,102 @@
+//! Gateway edits made on copies and landed together.
+//!
+//! One edit can touch both the profile and the targets file. Written in
+//! place, a failure on the second leaves the first changed on its own, so a
+//! [`StagedGatewayFiles`] copies both next to the live files, the edit runs
+//! against the copies, and [`land`](StagedGatewayFiles::land) renames them
+//! over the live files only once every write has succeeded.
+
+use std::path::{Path, PathBuf};
+
+use systemprompt_web_shared::error::MarketplaceError;
+use tempfile::{NamedTempFile, TempDir, TempPath};
+
+use super::targets::TARGETS_FILE;
+
+#[derive(Debug)]
+pub struct StagedGatewayFiles {
+    profile_path: PathBuf,
+    services_path: PathBuf,
+    profile: TempPath,
+    services: TempDir,
+}
+
+impl StagedGatewayFiles {
+    /// Copy the profile and the targets file for an edit.
+    ///
+    /// The copies sit in the live files' own directories, so landing them is
+    /// a rename on one filesystem, and `fs::copy` keeps the profile's
+    /// permissions.
+    pub fn stage(profile_path: &Path, services_path: &Path) -> Result<Self, MarketplaceError> {
+        let profile_dir = profile_path.parent().unwrap_or_else(|| Path::new("."));
+        let profile =
+            NamedTempFile::with_prefix_in(".profile-staged", profile_dir)?.into_temp_path();
+        std::fs::copy(profile_path, &profile)?;
+
+        let services = tempfile::Builder::new()
+            .prefix(".gateway-staged")
+            .tempdir_in(services_path)?;
+        let live_targets = services_path.join(TARGETS_FILE);
+        if live_targets.exists() {
+            let staged_targets = services.path().join(TARGETS_FILE);
+            if let Some(dir) = staged_targets.parent() {
+                std::fs::create_dir_all(dir)?;
+            }
+            std::fs::copy(&live_targets, &staged_targets)?;
+        }
+        Ok(Self {
+            profile_path: profile_path.to_path_buf(),
+            services_path: services_path.to_path_buf(),
+            profile,
+            services,
+        })
+    }
+
+    #[must_use]
+    pub fn profile_path(&self) -> &Path {
+        &self.profile
+    }
+
+    #[must_use]
+    pub fn services_path(&self) -> &Path {
+        self.services.path()
+    }
+
+    /// Move the edited copies over the live files. A file the edit left
+    /// as it was is not touched; a targets file the edit removed is removed.
+    pub fn land(self) -> Result<(), MarketplaceError> {
+        let staged_targets = self.services.path().join(TARGETS_FILE);
+        let live_targets = self.services_path.join(TARGETS_FILE);
+        let targets = read_optional(&staged_targets)?;
+        let targets_changed = targets != read_optional(&live_targets)?;
+        let profile_changed = std::fs::read(&self.profile)? != std::fs::read(&self.profile_path)?;
+
+        if profile_changed {
+            self.profile.persist(&self.profile_path).map_err(|e| {
+                MarketplaceError::config_file(self.profile_path.display().to_string(), e.error)
+            })?;
+        }
+        if !targets_changed {
+            return Ok(());
+        }
+        if targets.is_some() {
+            if let Some(dir) = live_targets.parent() {
+                std::fs::create_dir_all(dir)?;
+            }
+            std::fs::rename(&staged_targets, &live_targets).map_err(|e| {
+                MarketplaceError::config_file(live_targets.display().to_string(), e)
+            })?;
+        } else {
+            std::fs::remove_file(&live_targets)?;
+        }
+        Ok(())
+    }
+}
+
+fn read_optional(path: &Path) -> Result<Option<Vec<u8>>, MarketplaceError> {
+    match std::fs::read(path) {
+        Ok(bytes) => Ok(Some(bytes)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
@@ -0,0 +1,145 @@
+//! `services/gateway/targets.yaml`: the upstream targets behind each route.
+//!
+//! Core's `GatewayRoute` rejects unknown fields, so a route's weighted and
+//! fallback targets cannot live beside it in the profile YAML. They sit in
+//! this sidecar instead, keyed by stable route id, together with the health
+//! thresholds the selector in `gateway_routing` applies to them.
+
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use systemprompt_web_shared::error::MarketplaceError;
+
+use crate::types::GatewayRouteTarget;
+
+pub(super) const TARGETS_FILE: &str = "gateway/targets.yaml";
+
+const HEADER: &str = "# Weighted and fallback upstream targets per gateway route, keyed by route\n\
+                      # id. Managed from the admin Models page; hand edits are picked up live.\n";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RouteTargetsConfig {
+    #[serde(default)]
+    pub health: TargetHealthSettings,
+    #[serde(default)]
+    pub routes: BTreeMap<String, Vec<GatewayRouteTarget>>,
+}
+
+/// When a target stops receiving traffic. A target is unhealthy once it has
+/// failed `failure_threshold` times within `window_secs`, and stays so until
+/// `cooldown_secs` pass without a further failure.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields, default)]
+pub struct TargetHealthSettings {
+    pub window_secs: u64,
+    pub failure_threshold: u32,
+    pub cooldown_secs: u64,
+}
+
+impl Default for TargetHealthSettings {
+    fn default() -> Self {
+        Self {
+            window_secs: 300,
+            failure_threshold: 3,
+            cooldown_secs: 60,
+        }
+    }
+}
+
+impl RouteTargetsConfig {
+    #[must_use]
+    pub fn targets_for(&self, route_id: &str) -> &[GatewayRouteTarget] {
+        self.routes.get(route_id).map_or(&[], Vec::as_slice)
+    }
+}
+
+/// Reject a target list the selector could not serve.
+///
+/// That is blank or duplicate ids, a missing provider, two targets on the same
+/// upstream (the `ai_requests` row records provider and model, so they would be
+/// indistinguishable), or a non-empty list with no weighted target to take
+/// normal traffic.
+pub fn validate_targets(targets: &[GatewayRouteTarget]) -> Result<(), MarketplaceError> {
+    if targets.is_empty() {
+        return Ok(());
+    }
+    let mut ids = HashSet::new();
+    let mut upstreams = HashSet::new();
+    for target in targets {
+        if target.id.trim().is_empty() {
+            return Err(MarketplaceError::BadRequest("target id is required".into()));
+        }
+        if target.provider.trim().is_empty() {
+            return Err(MarketplaceError::BadRequest(format!(
+                "target `{}` needs a provider",
+                target.id
+            )));
+        }
+        if !ids.insert(target.id.as_str()) {
+            return Err(MarketplaceError::BadRequest(format!(
+                "target id `{}` is listed twice",
+                target.id
+            )));
+        }
+        if !upstreams.insert((target.provider.as_str(), target.upstream_model.as_deref())) {
+            return Err(MarketplaceError::BadRequest(format!(
+                "target `{}` repeats an upstream another target already uses",
+                target.id
+            )));
+        }
+    }
+    if targets.iter().all(|t| t.weight == 0) {
+        return Err(MarketplaceError::BadRequest(
+            "at least one target needs a weight above zero".into(),
+        ));
+    }
+    Ok(())
+}
+
+/// Load the targets file. A missing or empty file means no route has targets;
+/// a file that fails to parse is an error rather than silently sending every
+/// request to the routes' primary upstreams.
+pub fn load_route_targets(services_path: &Path) -> Result<RouteTargetsConfig, MarketplaceError> {
+    let path = services_path.join(TARGETS_FILE);
+    match std::fs::read_to_string(&path) {
+        Ok(s) if s.trim().is_empty() => Ok(RouteTargetsConfig::default()),
+        Ok(s) => serde_yaml::from_str(&s)
+            .map_err(|e| MarketplaceError::config_file(path.display().to_string(), e)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(RouteTargetsConfig::default()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Replace one route's targets, or drop its entry when `targets` is empty.
+/// `previous_id` removes the entry a renamed route was stored under.
+pub fn set_route_targets(
+    services_path: &Path,
+    route_id: &str,
+    previous_id: Option<&str>,
+    targets: &[GatewayRouteTarget],
+) -> Result<(), MarketplaceError> {
+    validate_targets(targets)?;
+    let mut config = load_route_targets(services_path)?;
+    let mut changed = previous_id
+        .filter(|prev| *prev != route_id)
+        .is_some_and(|prev| config.routes.remove(prev).is_some());
+    if targets.is_empty() {
+        changed |= config.routes.remove(route_id).is_some();
+    } else {
+        config.routes.insert(route_id.to_owned(), targets.to_vec());
+        changed = true;
+    }
+    if !changed {
+        return Ok(());
+    }
+    let path = services_path.join(TARGETS_FILE);
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let body = format!("{HEADER}{}", serde_yaml::to_string(&config)?);
+    std::fs::write(&path, body)
+        .map_err(|e| MarketplaceError::config_file(path.display().to_string(), e))?;
+    Ok(())
+}
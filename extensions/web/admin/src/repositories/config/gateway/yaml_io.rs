@@ -56,6 +56,7 @@ pub(super) fn route_from_yaml(val: &Value) -> Option<GatewayRouteView> {
         provider,
         upstream_model,
         extra_headers,
+        targets: Vec::new(),
     })
 }
 
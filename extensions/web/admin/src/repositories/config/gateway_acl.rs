@@ -16,8 +16,10 @@ use std::collections::HashMap;
 use std::sync::Arc;
 
 use sqlx::PgPool;
+use systemprompt::identifiers::UserId;
 use systemprompt_security::authz::{
-    AccessControlRepository, AuthzError, EntityKind, EntityRow, UpsertRuleParams,
+    AccessControlRepository, AuthzError, DenyReason, EntityKind, EntityRow, MatchedBy,
+    UpsertRuleParams,
 };
 
 pub use systemprompt_security::authz::{Access, AccessRule, Decision, RuleType, resolve};
@@ -83,3 +85,34 @@ pub async fn find_entity(pool: &PgPool, route_id: &str) -> Result<Option<EntityR
         .await
         .map_err(|e| map_err(&e))
 }
+
+/// The rule in `rules` that produced `decision`, if a rule produced it at
+/// all. Default inclusion and not-assigned denials have no rule to point at.
+#[must_use]
+pub fn find_deciding_rule<'a>(
+    rules: &'a [AccessRule],
+    decision: &Decision,
+    user_id: &UserId,
+) -> Option<&'a AccessRule> {
+    let (rule_type, value, access) = match decision {
+        Decision::Allow { matched_by } => match matched_by {
+            MatchedBy::UserAllow => (RuleType::USER, user_id.as_str(), Access::Allow),
+            MatchedBy::RoleAllow { role } => (RuleType::ROLE, role.as_str(), Access::Allow),
+            MatchedBy::AttributeAllow { rule_type, value } => {
+                (rule_type.clone(), value.as_str(), Access::Allow)
+            },
+            MatchedBy::DefaultIncluded | MatchedBy::PolicyAllow { .. } => return None,
+        },
+        Decision::Deny { reason } => match reason {
+            DenyReason::UserDeny { .. } => (RuleType::USER, user_id.as_str(), Access::Deny),
+            DenyReason::RoleDeny { role, .. } => (RuleType::ROLE, role.as_str(), Access::Deny),
+            DenyReason::AttributeDeny {
+                rule_type, value, ..
+            } => (rule_type.clone(), value.as_str(), Access::Deny),
+            _ => return None,
+        },
+    };
+    rules
+        .iter()
+        .find(|r| r.rule_type == rule_type && r.rule_value == value && r.access == access)
+}
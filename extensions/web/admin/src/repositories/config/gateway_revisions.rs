@@ -0,0 +1,101 @@
+//! Stored states of the gateway configuration, newest first.
+//!
+//! Rows are only ever inserted; rollback writes a new revision that copies an
+//! old one's bodies, so the history itself is never rewritten.
+
+use sqlx::PgPool;
+use systemprompt::identifiers::UserId;
+
+use super::gateway::GatewaySnapshot;
+use crate::types::{GatewayRevision, GatewayRevisionSummary};
+
+/// What is being recorded: the state after a change, who made it, and why.
+#[derive(Debug, Clone, Copy)]
+pub struct NewGatewayRevision<'a> {
+    pub snapshot: &'a GatewaySnapshot,
+    pub action: &'a str,
+    pub summary: &'a str,
+    pub actor_id: Option<&'a UserId>,
+    pub restored_from: Option<i64>,
+}
+
+pub async fn insert_gateway_revision(
+    pool: &PgPool,
+    revision: NewGatewayRevision<'_>,
+) -> Result<i64, sqlx::Error> {
+    sqlx::query_scalar!(
+        r"INSERT INTO gateway_config_revisions
+              (etag, action, summary, actor_id, gateway_yaml, targets_yaml, restored_from)
+          VALUES ($1, $2, $3, $4, $5, $6, $7)
+          RETURNING id",
+        revision.snapshot.etag(),
+        revision.action,
+        revision.summary,
+        revision.actor_id.map(UserId::as_str),
+        revision.snapshot.gateway_yaml,
+        revision.snapshot.targets_yaml,
+        revision.restored_from,
+    )
+    .fetch_one(pool)
+    .await
+}
+
+pub async fn find_latest_gateway_etag(pool: &PgPool) -> Result<Option<String>, sqlx::Error> {
+    sqlx::query_scalar!("SELECT etag FROM gateway_config_revisions ORDER BY id DESC LIMIT 1")
+        .fetch_optional(pool)
+        .await
+}
+
+pub async fn list_gateway_revisions(
+    pool: &PgPool,
+    limit: i64,
+) -> Result<Vec<GatewayRevisionSummary>, sqlx::Error> {
+    sqlx::query_as!(
+        GatewayRevisionSummary,
+        r#"SELECT r.id, r.etag, r.action, r.summary,
+                  r.actor_id AS "actor_id: UserId", u.email AS "actor_email?",
+                  r.restored_from, r.created_at
+           FROM gateway_config_revisions r
+           LEFT JOIN users u ON u.id = r.actor_id
+           ORDER BY r.id DESC
+           LIMIT $1"#,
+        limit,
+    )
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn find_gateway_revision(
+    pool: &PgPool,
+    id: i64,
+) -> Result<Option<GatewayRevision>, sqlx::Error> {
+    sqlx::query_as!(
+        GatewayRevision,
+        r#"SELECT id, etag, action, summary, actor_id AS "actor_id: UserId",
+                  gateway_yaml, targets_yaml, restored_from, created_at
+           FROM gateway_config_revisions
+           WHERE id = $1"#,
+        id,
+    )
+    .fetch_optional(pool)
+    .await
+}
+
+/// The revision immediately before `id`, which is what `id` is diffed against.
+pub async fn find_previous_gateway_revision(
+    pool: &PgPool,
+    id: i64,
+) -> Result<Option<GatewayRevision>, sqlx::Error> {
+    sqlx::query_as!(
+        GatewayRevision,
+        r#"SELECT id, etag, action, summary, actor_id AS "actor_id: UserId",
+                  gateway_yaml, targets_yaml, restored_from, created_at
+           FROM gateway_config_revisions
+           WHERE id < $1
+           ORDER BY id DESC
+           LIMIT 1"#,
+        id,
+    )
+    .fetch_optional(pool)
+    .await
+}
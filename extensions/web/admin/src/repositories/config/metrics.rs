@@ -0,0 +1,46 @@
+//! `services/telemetry/metrics.yaml`: whether `/metrics` answers, and how
+//! long one scrape's database figures are reused.
+//!
+//! Read on every scrape, so turning the endpoint off takes effect at once. A
+//! missing file, or `enabled: false`, answers 404.
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use systemprompt_web_shared::error::MarketplaceError;
+
+const METRICS_FILE: &str = "telemetry/metrics.yaml";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields, default)]
+pub struct MetricsEndpointConfig {
+    pub enabled: bool,
+    /// How long the database-derived series are served from memory before
+    /// the next scrape recomputes them, so several scrapers, or a short
+    /// scrape interval, do not each rescan the audit tables.
+    pub refresh_secs: u64,
+}
+
+impl Default for MetricsEndpointConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            refresh_secs: 15,
+        }
+    }
+}
+
+/// Load the endpoint file. A missing or empty file is a disabled endpoint; a
+/// file that fails to parse is an error.
+pub fn load_metrics_endpoint_config(
+    services_path: &Path,
+) -> Result<MetricsEndpointConfig, MarketplaceError> {
+    let path = services_path.join(METRICS_FILE);
+    match std::fs::read_to_string(&path) {
+        Ok(s) if s.trim().is_empty() => Ok(MetricsEndpointConfig::default()),
+        Ok(s) => serde_yaml::from_str(&s)
+            .map_err(|e| MarketplaceError::config_file(path.display().to_string(), e)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(MetricsEndpointConfig::default()),
+        Err(e) => Err(e.into()),
+    }
+}
@@ -17,5 +17,15 @@ pub mod acl_yaml_loader;
 pub mod acl_yaml_snapshot;
 pub mod acl_yaml_types;
 pub mod agents;
+pub mod cost_centers;
+pub mod cost_watch;
+pub mod digests;
 pub mod gateway;
 pub mod gateway_acl;
+pub mod gateway_revisions;
+pub mod metrics;
+pub mod otlp;
+pub mod retention;
+pub mod scim;
+pub mod secret_lifecycle;
+pub mod warehouse;
@@ -0,0 +1,106 @@
+//! `services/telemetry/otlp.yaml`: where governed traces are exported, and
+//! how often and how hard the exporter tries.
+//!
+//! The file is read on every export run, so an edit takes effect on the next
+//! one. A missing file, or `enabled: false`, exports nothing.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use systemprompt_web_shared::error::MarketplaceError;
+
+const OTLP_FILE: &str = "telemetry/otlp.yaml";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields, default)]
+pub struct OtlpExportConfig {
+    pub enabled: bool,
+    /// The collector's base URL; `/v1/traces` is appended unless present.
+    pub endpoint: String,
+    /// Request headers whose values are read from environment variables,
+    /// header name to variable name, so credentials stay out of the file.
+    pub headers_env: BTreeMap<String, String>,
+    pub service_name: String,
+    /// Extra resource attributes, e.g. `deployment.environment`.
+    pub resource_attributes: BTreeMap<String, String>,
+    /// Spans per export request.
+    pub batch_size: usize,
+    /// Retries per request after the first attempt, with exponential backoff,
+    /// on a transport failure or a retryable status (429, 502, 503, 504).
+    pub max_retries: u32,
+    pub timeout_ms: u64,
+    /// How far behind now a window ends, so rows still being written are not
+    /// missed.
+    pub settle_secs: u64,
+    /// Longest window one run exports; a backlog drains over several runs.
+    pub max_window_secs: u64,
+    /// Where the first run starts, counted back from now.
+    pub initial_lookback_secs: u64,
+}
+
+impl Default for OtlpExportConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            endpoint: String::new(),
+            headers_env: BTreeMap::new(),
+            service_name: "systemprompt".to_owned(),
+            resource_attributes: BTreeMap::new(),
+            batch_size: 512,
+            max_retries: 3,
+            timeout_ms: 10_000,
+            settle_secs: 120,
+            max_window_secs: 3_600,
+            initial_lookback_secs: 3_600,
+        }
+    }
+}
+
+impl OtlpExportConfig {
+    /// The OTLP/HTTP traces URL for [`Self::endpoint`].
+    #[must_use]
+    pub fn traces_url(&self) -> String {
+        let base = self.endpoint.trim_end_matches('/');
+        if base.ends_with("/v1/traces") {
+            base.to_owned()
+        } else {
+            format!("{base}/v1/traces")
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), MarketplaceError> {
+        if !self.enabled {
+            return Ok(());
+        }
+        if !(self.endpoint.starts_with("http://") || self.endpoint.starts_with("https://")) {
+            return Err(MarketplaceError::BadRequest(format!(
+                "otlp endpoint `{}` must be an http(s) URL",
+                self.endpoint
+            )));
+        }
+        if self.batch_size == 0 || self.max_window_secs == 0 {
+            return Err(MarketplaceError::BadRequest(
+                "otlp batch_size and max_window_secs must be above zero".to_owned(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Load the exporter file. A missing or empty file is a disabled exporter; a
+/// file that fails to parse or validate is an error.
+pub fn load_otlp_export_config(services_path: &Path) -> Result<OtlpExportConfig, MarketplaceError> {
+    let path = services_path.join(OTLP_FILE);
+    let config: OtlpExportConfig = match std::fs::read_to_string(&path) {
+        Ok(s) if s.trim().is_empty() => return Ok(OtlpExportConfig::default()),
+        Ok(s) => serde_yaml::from_str(&s)
+            .map_err(|e| MarketplaceError::config_file(path.display().to_string(), e))?,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Ok(OtlpExportConfig::default());
+        },
+        Err(e) => return Err(e.into()),
+    };
+    config.validate()?;
+    Ok(config)
+}
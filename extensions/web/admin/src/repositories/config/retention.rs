@@ -0,0 +1,107 @@
+//! `services/governance/retention.yaml`: how long each class of governed
+//! data is kept, and who is exempt under legal hold.
+//!
+//! Read on every `data_retention` run and every view of `/admin/retention`,
+//! so a policy or hold edit applies from the next run. A class with no
+//! policy is kept indefinitely; a missing file purges nothing.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use serde::Deserialize;
+use systemprompt::identifiers::{SessionId, UserId};
+use systemprompt_web_shared::error::MarketplaceError;
+
+use crate::retention::DataClass;
+
+const RETENTION_FILE: &str = "governance/retention.yaml";
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields, default)]
+pub struct RetentionConfig {
+    pub enabled: bool,
+    /// Rows removed or stripped per statement, so no single purge holds
+    /// locks on a hot table for long.
+    pub batch_size: u32,
+    /// Batches per class per run. Whatever is left over is picked up by the
+    /// next run, so a first run against years of history spreads out.
+    pub max_batches_per_run: u32,
+    /// Days each class is kept, counted from when the row was written.
+    pub policies: BTreeMap<DataClass, u32>,
+    pub legal_holds: Vec<LegalHold>,
+}
+
+impl Default for RetentionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            batch_size: 1_000,
+            max_batches_per_run: 100,
+            policies: BTreeMap::new(),
+            legal_holds: Vec::new(),
+        }
+    }
+}
+
+/// Exempts every row belonging to one user or one session from purging,
+/// in every class, for as long as the entry stays in the file.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LegalHold {
+    #[serde(default)]
+    pub user_id: Option<UserId>,
+    #[serde(default)]
+    pub session_id: Option<SessionId>,
+    /// Matter or ticket the hold was placed for.
+    pub reason: String,
+}
+
+impl RetentionConfig {
+    pub fn validate(&self) -> Result<(), MarketplaceError> {
+        if !(1..=50_000).contains(&self.batch_size) || self.max_batches_per_run == 0 {
+            return Err(MarketplaceError::BadRequest(
+                "retention batch_size must be between 1 and 50000, and max_batches_per_run above zero"
+                    .to_owned(),
+            ));
+        }
+        for (class, days) in &self.policies {
+            if *days < class.min_days() {
+                return Err(MarketplaceError::BadRequest(format!(
+                    "retention policy for {} must keep at least {} days",
+                    class.as_str(),
+                    class.min_days()
+                )));
+            }
+        }
+        for hold in &self.legal_holds {
+            if hold.user_id.is_some() == hold.session_id.is_some() {
+                return Err(MarketplaceError::BadRequest(
+                    "each legal hold names exactly one of user_id or session_id".to_owned(),
+                ));
+            }
+            if hold.reason.trim().is_empty() {
+                return Err(MarketplaceError::BadRequest(
+                    "each legal hold needs a reason".to_owned(),
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Load the retention file. A missing or empty file is the defaults, which
+/// purge nothing; a file that fails to parse or validate is an error.
+pub fn load_retention_config(services_path: &Path) -> Result<RetentionConfig, MarketplaceError> {
+    let path = services_path.join(RETENTION_FILE);
+    let config: RetentionConfig = match std::fs::read_to_string(&path) {
+        Ok(s) if s.trim().is_empty() => return Ok(RetentionConfig::default()),
+        Ok(s) => serde_yaml::from_str(&s)
+            .map_err(|e| MarketplaceError::config_file(path.display().to_string(), e))?,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Ok(RetentionConfig::default());
+        },
+        Err(e) => return Err(e.into()),
+    };
+    config.validate()?;
+    Ok(config)
+}
@@ -0,0 +1,110 @@
+//! `services/access-control/scim.yaml`: how identity-provider groups map onto
+//! roles and departments.
+//!
+//! Read on every SCIM request rather than cached at boot. Provisioning traffic
+//! is low-volume and bursty, and an operator who adds a mapping expects the
+//! next push from the identity provider to honour it without a restart.
+
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use serde::Deserialize;
+use systemprompt_web_shared::error::MarketplaceError;
+
+use crate::types::departments::DEFAULT_DEPARTMENT;
+
+const SCIM_FILE: &str = "access-control/scim.yaml";
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ScimConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_roles")]
+    pub default_roles: Vec<String>,
+    #[serde(default)]
+    pub group_mappings: Vec<ScimGroupMapping>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ScimGroupMapping {
+    pub group: String,
+    #[serde(default)]
+    pub roles: Vec<String>,
+    #[serde(default)]
+    pub department: Option<String>,
+}
+
+/// What a managed user's groups entitle them to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScimAssignment {
+    pub roles: Vec<String>,
+    pub department: String,
+}
+
+fn default_roles() -> Vec<String> {
+    vec!["user".to_owned()]
+}
+
+impl Default for ScimConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            default_roles: default_roles(),
+            group_mappings: Vec::new(),
+        }
+    }
+}
+
+impl ScimConfig {
+    /// Resolve the roles and department for a member of `groups`.
+    ///
+    /// Roles are the union of `default_roles` and every matching mapping, in a
+    /// stable order. The department comes from the first mapping, in file
+    /// order, that both matches and names one; a user no mapping places lands
+    /// in [`DEFAULT_DEPARTMENT`].
+    #[must_use]
+    pub fn assignment_for(&self, groups: &[String]) -> ScimAssignment {
+        let matched: Vec<&ScimGroupMapping> = self
+            .group_mappings
+            .iter()
+            .filter(|m| groups.iter().any(|g| g == &m.group))
+            .collect();
+
+        let roles: BTreeSet<String> = self
+            .default_roles
+            .iter()
+            .chain(matched.iter().flat_map(|m| m.roles.iter()))
+            .map(|r| r.trim().to_owned())
+            .filter(|r| !r.is_empty())
+            .collect();
+
+        let department = matched
+            .iter()
+            .find_map(|m| m.department.as_deref())
+            .map(str::trim)
+            .filter(|d| !d.is_empty())
+            .unwrap_or(DEFAULT_DEPARTMENT)
+            .to_owned();
+
+        ScimAssignment {
+            roles: roles.into_iter().collect(),
+            department,
+        }
+    }
+}
+
+/// Load the SCIM mapping file. A missing or empty file means SCIM is off;
+/// a file that fails to parse is an error, so a typo cannot silently
+/// disable provisioning or strip roles.
+pub fn load_scim_config(services_path: &Path) -> Result<ScimConfig, MarketplaceError> {
+    let path = services_path.join(SCIM_FILE);
+    match std::fs::read_to_string(&path) {
+        Ok(s) if s.trim().is_empty() => Ok(ScimConfig::default()),
+        Ok(s) => serde_yaml::from_str(&s)
+            .map_err(|e| MarketplaceError::config_file(path.display().to_string(), e)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(ScimConfig::default()),
+        Err(e) => Err(e.into()),
+    }
+}
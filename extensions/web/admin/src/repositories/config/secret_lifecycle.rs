@@ -0,0 +1,54 @@
+//! `services/governance/secret_lifecycle.yaml`: when a plugin secret is
+//! flagged as expiring or unused, and what makes a resolution unusual.
+//!
+//! Read on every `secret_lifecycle` run and on every secret resolution, so a
+//! threshold edit applies from the next one. A missing file runs with the
+//! [`LifecyclePolicy`] defaults.
+
+use std::path::Path;
+
+use systemprompt_web_shared::error::MarketplaceError;
+
+use crate::secret_lifecycle::LifecyclePolicy;
+
+const SECRET_LIFECYCLE_FILE: &str = "governance/secret_lifecycle.yaml";
+
+fn validate(policy: &LifecyclePolicy) -> Result<(), MarketplaceError> {
+    if policy.expiry_warning_days < 0 || policy.unused_after_days <= 0 {
+        return Err(MarketplaceError::BadRequest(
+            "secret_lifecycle expiry_warning_days must not be negative, and unused_after_days \
+             must be above zero"
+                .to_owned(),
+        ));
+    }
+    if policy.min_history < 0 || policy.rate_floor_per_hour < 0 {
+        return Err(MarketplaceError::BadRequest(
+            "secret_lifecycle min_history and rate_floor_per_hour must not be negative".to_owned(),
+        ));
+    }
+    if !policy.rate_multiplier.is_finite() || policy.rate_multiplier < 1.0 {
+        return Err(MarketplaceError::BadRequest(
+            "secret_lifecycle rate_multiplier must be finite and at least 1".to_owned(),
+        ));
+    }
+    Ok(())
+}
+
+/// Load the secret lifecycle file. A missing or empty file is the defaults; a
+/// file that fails to parse or validate is an error.
+pub fn load_secret_lifecycle_policy(
+    services_path: &Path,
+) -> Result<LifecyclePolicy, MarketplaceError> {
+    let path = services_path.join(SECRET_LIFECYCLE_FILE);
+    let policy: LifecyclePolicy = match std::fs::read_to_string(&path) {
+        Ok(s) if s.trim().is_empty() => return Ok(LifecyclePolicy::default()),
+        Ok(s) => serde_yaml::from_str(&s)
+            .map_err(|e| MarketplaceError::config_file(path.display().to_string(), e))?,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Ok(LifecyclePolicy::default());
+        },
+        Err(e) => return Err(e.into()),
+    };
+    validate(&policy)?;
+    Ok(policy)
+}
@@ -0,0 +1,175 @@
+//! `services/telemetry/warehouse.yaml`: which governed tables are exported to
+//! a data warehouse landing area, in what format, and where to.
+//!
+//! Read on every `warehouse_export` run, so an edit takes effect on the next
+//! one. A missing file, or `enabled: false`, exports nothing.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use systemprompt_web_shared::error::MarketplaceError;
+
+use crate::warehouse::WarehouseTable;
+
+const WAREHOUSE_FILE: &str = "telemetry/warehouse.yaml";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields, default)]
+pub struct WarehouseExportConfig {
+    pub enabled: bool,
+    pub format: WarehouseFormat,
+    pub destination: WarehouseDestination,
+    /// Key prefix every file is written under, e.g. `systemprompt/prod`.
+    pub prefix: String,
+    pub tables: Vec<WarehouseTable>,
+    pub bodies: BodyPolicy,
+    /// How far behind now a window ends, so rows still being written are not
+    /// missed.
+    pub settle_secs: u64,
+    /// Longest window one file covers. Windows also end at midnight UTC, so
+    /// every file belongs to one `dt=` partition.
+    pub max_window_secs: u64,
+    /// Windows each table exports per run; a backlog drains over several.
+    pub max_windows_per_run: u32,
+    /// Where a table's first export starts, counted back from now. Set it
+    /// past the oldest row to backfill everything.
+    pub initial_lookback_secs: u64,
+}
+
+impl Default for WarehouseExportConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            format: WarehouseFormat::default(),
+            destination: WarehouseDestination::default(),
+            prefix: String::new(),
+            tables: WarehouseTable::ALL.to_vec(),
+            bodies: BodyPolicy::default(),
+            settle_secs: 300,
+            max_window_secs: 3_600,
+            max_windows_per_run: 24,
+            initial_lookback_secs: 7 * 86_400,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WarehouseFormat {
+    #[default]
+    Parquet,
+    Jsonl,
+}
+
+impl WarehouseFormat {
+    #[must_use]
+    pub const fn extension(self) -> &'static str {
+        match self {
+            Self::Parquet => "parquet",
+            Self::Jsonl => "jsonl",
+        }
+    }
+}
+
+/// What happens to free-text columns that can carry prompt, transcript or
+/// tool content.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BodyPolicy {
+    /// Left out of the files altogether.
+    #[default]
+    Exclude,
+    /// Exported with credential-shaped tokens masked, as on the session page.
+    Redact,
+    Include,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum WarehouseDestination {
+    /// A directory on this host; relative paths resolve against the system
+    /// root.
+    Local { dir: PathBuf },
+    /// An S3 bucket, or any store that speaks the S3 API (`MinIO`, R2, ...)
+    /// when `endpoint` is set.
+    S3 {
+        bucket: String,
+        #[serde(default)]
+        region: Option<String>,
+        #[serde(default)]
+        endpoint: Option<String>,
+        /// Environment variables holding the credentials, so they stay out of
+        /// the file.
+        access_key_env: String,
+        secret_key_env: String,
+    },
+}
+
+impl Default for WarehouseDestination {
+    fn default() -> Self {
+        Self::Local {
+            dir: PathBuf::from("exports/warehouse"),
+        }
+    }
+}
+
+fn invalid(message: &str) -> MarketplaceError {
+    MarketplaceError::BadRequest(format!("warehouse export: {message}"))
+}
+
+impl WarehouseExportConfig {
+    pub fn validate(&self) -> Result<(), MarketplaceError> {
+        if !self.enabled {
+            return Ok(());
+        }
+        if self.max_window_secs == 0 || self.max_windows_per_run == 0 {
+            return Err(invalid(
+                "max_window_secs and max_windows_per_run must be above zero",
+            ));
+        }
+        if self.tables.is_empty() {
+            return Err(invalid("list at least one table"));
+        }
+        if self.prefix.starts_with('/') || self.prefix.split('/').any(|s| s == "..") {
+            return Err(invalid("prefix must be relative and must not contain `..`"));
+        }
+        match &self.destination {
+            WarehouseDestination::Local { dir } if dir.as_os_str().is_empty() => {
+                Err(invalid("local destination needs a dir"))
+            },
+            WarehouseDestination::S3 {
+                bucket, endpoint, ..
+            } => {
+                if bucket.is_empty() {
+                    return Err(invalid("s3 destination needs a bucket"));
+                }
+                if let Some(endpoint) = endpoint
+                    && !(endpoint.starts_with("http://") || endpoint.starts_with("https://"))
+                {
+                    return Err(invalid("s3 endpoint must be an http(s) URL"));
+                }
+                Ok(())
+            },
+            WarehouseDestination::Local { .. } => Ok(()),
+        }
+    }
+}
+
+/// Load the export file. A missing or empty file is a disabled exporter; a
+/// file that fails to parse or validate is an error.
+pub fn load_warehouse_export_config(
+    services_path: &Path,
+) -> Result<WarehouseExportConfig, MarketplaceError> {
+    let path = services_path.join(WAREHOUSE_FILE);
+    let config: WarehouseExportConfig = match std::fs::read_to_string(&path) {
+        Ok(s) if s.trim().is_empty() => return Ok(WarehouseExportConfig::default()),
+        Ok(s) => serde_yaml::from_str(&s)
+            .map_err(|e| MarketplaceError::config_file(path.display().to_string(), e))?,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Ok(WarehouseExportConfig::default());
+        },
+        Err(e) => return Err(e.into()),
+    };
+    config.validate()?;
+    Ok(config)
+}
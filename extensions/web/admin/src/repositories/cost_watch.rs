@@ -0,0 +1,259 @@
+//! Hourly and daily spend for the cost watch, and the anomalies and
+//! forecasts it records.
+//!
+//! A user's department is read from `user_profile_ext` at query time, so
+//! spend follows a user who moves department, and a user without a profile
+//! row (or an empty department) counts under `Default`, as everywhere else
+//! departments are shown.
+
+use chrono::{DateTime, NaiveDate, Utc};
+use sqlx::PgPool;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SpendScope {
+    User,
+    Department,
+    Model,
+}
+
+impl SpendScope {
+    pub const ALL: [Self; 3] = [Self::User, Self::Department, Self::Model];
+
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::User => "user",
+            Self::Department => "department",
+            Self::Model => "model",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HourlySpend {
+    pub subject: String,
+    pub hour_start: DateTime<Utc>,
+    pub spend_microdollars: i64,
+    pub requests: i64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct NewCostAnomaly {
+    pub scope: SpendScope,
+    pub subject: String,
+    pub hour_start: DateTime<Utc>,
+    pub spend_microdollars: i64,
+    pub requests: i64,
+    pub baseline_microdollars: i64,
+    pub stddev_microdollars: i64,
+    pub ratio: f64,
+    pub severity: &'static str,
+}
+
+#[derive(Debug, Clone)]
+pub struct CostAnomaly {
+    pub id: String,
+    pub scope: String,
+    pub subject: String,
+    pub hour_start: DateTime<Utc>,
+    pub spend_microdollars: i64,
+    pub requests: i64,
+    pub baseline_microdollars: i64,
+    pub ratio: f64,
+    pub severity: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DailySpend {
+    pub department: String,
+    pub day: NaiveDate,
+    pub spend_microdollars: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CostForecast {
+    pub department: String,
+    pub month_start: NaiveDate,
+    pub spent_microdollars: i64,
+    pub daily_rate_microdollars: i64,
+    pub forecast_microdollars: i64,
+    pub previous_month_microdollars: i64,
+}
+
+/// Spend per subject per hour in `[since, until)`, hours with no spend
+/// omitted.
+pub async fn list_hourly_spend(
+    pool: &PgPool,
+    scope: SpendScope,
+    since: DateTime<Utc>,
+    until: DateTime<Utc>,
+) -> Result<Vec<HourlySpend>, sqlx::Error> {
+    match scope {
+        SpendScope::User => {
+            sqlx::query_as!(
+                HourlySpend,
+                r#"SELECT
+                    user_id::text                          AS "subject!",
+                    date_trunc('hour', created_at)         AS "hour_start!",
+                    SUM(cost_microdollars)::bigint         AS "spend_microdollars!",
+                    COUNT(*)                               AS "requests!"
+                FROM ai_requests
+                WHERE created_at >= $1 AND created_at < $2 AND cost_microdollars > 0
+                GROUP BY 1, 2"#,
+                since,
+                until,
+            )
+            .fetch_all(pool)
+            .await
+        },
+        SpendScope::Department => {
+            sqlx::query_as!(
+                HourlySpend,
+                r#"SELECT
+                    COALESCE(NULLIF(upe.department, ''), 'Default') AS "subject!",
+                    date_trunc('hour', r.created_at)       AS "hour_start!",
+                    SUM(r.cost_microdollars)::bigint       AS "spend_microdollars!",
+                    COUNT(*)                               AS "requests!"
+                FROM ai_requests r
+                LEFT JOIN user_profile_ext upe ON upe.user_id = r.user_id
+                WHERE r.created_at >= $1 AND r.created_at < $2 AND r.cost_microdollars > 0
+                GROUP BY 1, 2"#,
+                since,
+                until,
+            )
+            .fetch_all(pool)
+            .await
+        },
+        SpendScope::Model => {
+            sqlx::query_as!(
+                HourlySpend,
+                r#"SELECT
+                    COALESCE(model, 'unknown')             AS "subject!",
+                    date_trunc('hour', created_at)         AS "hour_start!",
+                    SUM(cost_microdollars)::bigint         AS "spend_microdollars!",
+                    COUNT(*)                               AS "requests!"
+                FROM ai_requests
+                WHERE created_at >= $1 AND created_at < $2 AND cost_microdollars > 0
+                GROUP BY 1, 2"#,
+                since,
+                until,
+            )
+            .fetch_all(pool)
+            .await
+        },
+    }
+}
+
+/// Record an anomaly unless its subject and hour are already flagged.
+/// Returns whether a row was written.
+pub async fn insert_cost_anomaly(
+    pool: &PgPool,
+    anomaly: &NewCostAnomaly,
+) -> Result<bool, sqlx::Error> {
+    let id = uuid::Uuid::new_v4().to_string();
+    let result = sqlx::query!(
+        r#"INSERT INTO cost_anomalies
+            (id, scope, subject, hour_start, spend_microdollars, requests,
+             baseline_microdollars, stddev_microdollars, ratio, severity)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+        ON CONFLICT (scope, subject, hour_start) DO NOTHING"#,
+        id,
+        anomaly.scope.as_str(),
+        anomaly.subject,
+        anomaly.hour_start,
+        anomaly.spend_microdollars,
+        anomaly.requests,
+        anomaly.baseline_microdollars,
+        anomaly.stddev_microdollars,
+        anomaly.ratio,
+        anomaly.severity,
+    )
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn list_cost_anomalies_since(
+    pool: &PgPool,
+    since: DateTime<Utc>,
+    limit: i64,
+) -> Result<Vec<CostAnomaly>, sqlx::Error> {
+    sqlx::query_as!(
+        CostAnomaly,
+        r#"SELECT id, scope, subject, hour_start, spend_microdollars, requests,
+                  baseline_microdollars, ratio, severity
+        FROM cost_anomalies
+        WHERE hour_start >= $1
+        ORDER BY hour_start DESC, ratio DESC
+        LIMIT $2"#,
+        since,
+        limit,
+    )
+    .fetch_all(pool)
+    .await
+}
+
+/// Spend per department per UTC day in `[since, until)`.
+pub async fn list_department_daily_spend(
+    pool: &PgPool,
+    since: DateTime<Utc>,
+    until: DateTime<Utc>,
+) -> Result<Vec<DailySpend>, sqlx::Error> {
+    sqlx::query_as!(
+        DailySpend,
+        r#"SELECT
+            COALESCE(NULLIF(upe.department, ''), 'Default')     AS "department!",
+            (r.created_at AT TIME ZONE 'UTC')::date             AS "day!",
+            SUM(r.cost_microdollars)::bigint                    AS "spend_microdollars!"
+        FROM ai_requests r
+        LEFT JOIN user_profile_ext upe ON upe.user_id = r.user_id
+        WHERE r.created_at >= $1 AND r.created_at < $2 AND r.cost_microdollars > 0
+        GROUP BY 1, 2
+        ORDER BY 1, 2"#,
+        since,
+        until,
+    )
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn set_cost_forecast(pool: &PgPool, forecast: &CostForecast) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"INSERT INTO cost_forecasts
+            (department, month_start, spent_microdollars, daily_rate_microdollars,
+             forecast_microdollars, previous_month_microdollars, computed_at)
+        VALUES ($1, $2, $3, $4, $5, $6, NOW())
+        ON CONFLICT (department, month_start) DO UPDATE SET
+            spent_microdollars = EXCLUDED.spent_microdollars,
+            daily_rate_microdollars = EXCLUDED.daily_rate_microdollars,
+            forecast_microdollars = EXCLUDED.forecast_microdollars,
+            previous_month_microdollars = EXCLUDED.previous_month_microdollars,
+            computed_at = NOW()"#,
+        forecast.department,
+        forecast.month_start,
+        forecast.spent_microdollars,
+        forecast.daily_rate_microdollars,
+        forecast.forecast_microdollars,
+        forecast.previous_month_microdollars,
+    )
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+pub async fn list_cost_forecasts(
+    pool: &PgPool,
+    month_start: NaiveDate,
+) -> Result<Vec<CostForecast>, sqlx::Error> {
+    sqlx::query_as!(
+        CostForecast,
+        r#"SELECT department, month_start, spent_microdollars, daily_rate_microdollars,
+                  forecast_microdollars, previous_month_microdollars
+        FROM cost_forecasts
+        WHERE month_start = $1
+        ORDER BY forecast_microdollars DESC"#,
+        month_start,
+    )
+    .fetch_all(pool)
+    .await
+}
@@ -0,0 +1,59 @@
+//! Digest delivery ledger, and the digest sections the dashboard repositories
+//! do not cover ([`sections`]).
+
+pub mod sections;
+
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+
+#[derive(Debug, Clone, Copy)]
+pub struct DigestDelivery {
+    pub attempts: i32,
+    pub delivered_at: Option<DateTime<Utc>>,
+}
+
+pub async fn find_digest_delivery(
+    pool: &PgPool,
+    subscription_id: &str,
+    period_start: DateTime<Utc>,
+    channel: &str,
+) -> Result<Option<DigestDelivery>, sqlx::Error> {
+    sqlx::query_as!(
+        DigestDelivery,
+        r"SELECT attempts, delivered_at
+          FROM digest_deliveries
+          WHERE subscription_id = $1 AND period_start = $2 AND channel = $3",
+        subscription_id,
+        period_start,
+        channel,
+    )
+    .fetch_optional(pool)
+    .await
+}
+
+/// Count one delivery attempt; `error` is `None` when it went through.
+pub async fn record_digest_attempt(
+    pool: &PgPool,
+    subscription_id: &str,
+    period_start: DateTime<Utc>,
+    channel: &str,
+    error: Option<&str>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r"INSERT INTO digest_deliveries
+              (subscription_id, period_start, channel, attempts, delivered_at, last_error)
+          VALUES ($1, $2, $3, 1, CASE WHEN $4::text IS NULL THEN NOW() END, $4)
+          ON CONFLICT (subscription_id, period_start, channel) DO UPDATE SET
+              attempts = digest_deliveries.attempts + 1,
+              delivered_at = EXCLUDED.delivered_at,
+              last_error = EXCLUDED.last_error,
+              updated_at = NOW()",
+        subscription_id,
+        period_start,
+        channel,
+        error,
+    )
+    .execute(pool)
+    .await?;
+    Ok(())
+}
@@ -0,0 +1,119 @@
+//! The digest sections the dashboard has no scoped read model for: safety
+//! findings, eval outcomes and troubled sessions, for one period and one
+//! [`ActivityScope`].
+//!
+//! Spend, top models and governance decisions come from the dashboard's own
+//! repositories, so a digest and the page it links to count the same way.
+
+use serde::Serialize;
+use sqlx::PgPool;
+use systemprompt::identifiers::{SessionId, UserId};
+
+use crate::util::activity_scope::ActivityScope;
+use crate::util::time_range::TimeRange;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DigestFindingRow {
+    pub severity: String,
+    pub category: String,
+    pub findings: i64,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct DigestEvals {
+    pub scored: i64,
+    pub pass: i64,
+    pub partial: i64,
+    pub fail: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DigestSessionRow {
+    pub session_id: SessionId,
+    pub user_id: UserId,
+    pub title: String,
+    pub quality_score: i16,
+    pub goal_achieved: String,
+}
+
+pub async fn list_digest_safety_findings(
+    pool: &PgPool,
+    range: TimeRange,
+    scope: ActivityScope<'_>,
+) -> Result<Vec<DigestFindingRow>, sqlx::Error> {
+    sqlx::query_as!(
+        DigestFindingRow,
+        r#"SELECT f.severity::text AS "severity!", f.category::text AS "category!",
+                  COUNT(*) AS "findings!"
+           FROM ai_safety_findings f
+           JOIN ai_requests r ON r.id = f.ai_request_id
+           LEFT JOIN user_profile_ext upe ON upe.user_id = r.user_id
+           WHERE f.created_at >= $1 AND f.created_at < $2
+             AND ($3::text IS NULL OR r.user_id = $3)
+             AND ($4::text IS NULL OR COALESCE(NULLIF(upe.department, ''), 'Default') = $4)
+           GROUP BY 1, 2
+           ORDER BY 3 DESC, 1, 2"#,
+        range.from,
+        range.to,
+        scope.user(),
+        scope.department,
+    )
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn get_digest_evals(
+    pool: &PgPool,
+    range: TimeRange,
+    scope: ActivityScope<'_>,
+) -> Result<DigestEvals, sqlx::Error> {
+    sqlx::query_as!(
+        DigestEvals,
+        r#"SELECT COUNT(*) AS "scored!",
+                  COUNT(*) FILTER (WHERE e.verdict = 'pass') AS "pass!",
+                  COUNT(*) FILTER (WHERE e.verdict = 'partial') AS "partial!",
+                  COUNT(*) FILTER (WHERE e.verdict = 'fail') AS "fail!"
+           FROM eval_results e
+           LEFT JOIN user_profile_ext upe ON upe.user_id = e.user_id
+           WHERE e.created_at >= $1 AND e.created_at < $2
+             AND ($3::text IS NULL OR e.user_id = $3)
+             AND ($4::text IS NULL OR COALESCE(NULLIF(upe.department, ''), 'Default') = $4)"#,
+        range.from,
+        range.to,
+        scope.user(),
+        scope.department,
+    )
+    .fetch_one(pool)
+    .await
+}
+
+/// Analysed sessions that went worst: goal missed or only partly met, then
+/// lowest quality score.
+pub async fn list_digest_notable_sessions(
+    pool: &PgPool,
+    range: TimeRange,
+    scope: ActivityScope<'_>,
+    limit: i64,
+) -> Result<Vec<DigestSessionRow>, sqlx::Error> {
+    sqlx::query_as!(
+        DigestSessionRow,
+        r#"SELECT a.session_id AS "session_id: SessionId", a.user_id AS "user_id: UserId",
+                  a.title, a.quality_score, a.goal_achieved
+           FROM session_analyses a
+           LEFT JOIN user_profile_ext upe ON upe.user_id = a.user_id
+           WHERE a.created_at >= $1 AND a.created_at < $2
+             AND (a.goal_achieved IN ('no', 'partial') OR a.quality_score <= 2)
+             AND ($3::text IS NULL OR a.user_id = $3)
+             AND ($4::text IS NULL OR COALESCE(NULLIF(upe.department, ''), 'Default') = $4)
+           ORDER BY CASE a.goal_achieved WHEN 'no' THEN 0 WHEN 'partial' THEN 1 ELSE 2 END,
+                    a.quality_score, a.created_at DESC
+           LIMIT $5"#,
+        range.from,
+        range.to,
+        scope.user(),
+        scope.department,
+        limit,
+    )
+    .fetch_all(pool)
+    .await
+}
@@ -0,0 +1,62 @@
+//! Step 3 of an erasure (see [`super::statements`]): the records that name the
+//! user as the one who acted or was flagged, rather than as the subject of a
+//! row, take the pseudonym in their place — closed chargeback periods, cost
+//! anomalies on the user, gateway config revisions, erasure certificates they
+//! requested, and master key events.
+
+use sqlx::PgConnection;
+use systemprompt::identifiers::UserId;
+
+use super::statements::table;
+use crate::erasure::{ErasedTable, ErasureAction};
+
+pub(super) async fn pseudonymize_actor_records(
+    conn: &mut PgConnection,
+    user_id: &UserId,
+    pseudonym: &str,
+) -> Result<Vec<ErasedTable>, sqlx::Error> {
+    // Why: `closed_by` changes under the chargeback guard, which reads the
+    // pseudonym `pseudonymize_audit_rows` named for this transaction.
+    let row = sqlx::query!(
+        r#"WITH periods AS (
+               UPDATE chargeback_periods SET closed_by = $2 WHERE closed_by = $1 RETURNING 1
+           ),
+           anomalies AS (
+               UPDATE cost_anomalies SET subject = $2
+               WHERE scope = 'user' AND subject = $1 RETURNING 1
+           ),
+           revisions AS (
+               UPDATE gateway_config_revisions SET actor_id = $2 WHERE actor_id = $1 RETURNING 1
+           ),
+           certificates AS (
+               UPDATE erasure_certificates SET requested_by = $2
+               WHERE requested_by = $1 RETURNING 1
+           ),
+           key_events AS (
+               UPDATE master_key_events SET
+                   actor_id = CASE WHEN actor_id = $1 THEN $2 ELSE actor_id END,
+                   detail = CASE WHEN detail->>'user_id' = $1
+                       THEN jsonb_set(detail, '{user_id}', to_jsonb($2::text))
+                       ELSE detail END
+               WHERE actor_id = $1 OR detail->>'user_id' = $1 RETURNING 1
+           )
+           SELECT
+               (SELECT COUNT(*) FROM periods) AS "periods!",
+               (SELECT COUNT(*) FROM anomalies) AS "anomalies!",
+               (SELECT COUNT(*) FROM revisions) AS "revisions!",
+               (SELECT COUNT(*) FROM certificates) AS "certificates!",
+               (SELECT COUNT(*) FROM key_events) AS "key_events!""#,
+        user_id.as_str(),
+        pseudonym,
+    )
+    .fetch_one(conn)
+    .await?;
+    let pseudonymized = |name, rows| table(name, ErasureAction::Pseudonymized, rows);
+    Ok(vec![
+        pseudonymized("chargeback_periods", row.periods),
+        pseudonymized("cost_anomalies", row.anomalies),
+        pseudonymized("gateway_config_revisions", row.revisions),
+        pseudonymized("erasure_certificates", row.certificates),
+        pseudonymized("master_key_events", row.key_events),
+    ])
+}
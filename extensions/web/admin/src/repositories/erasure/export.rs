@@ -0,0 +1,61 @@
+//! Everything held about one user, as JSON, for a data-subject export.
+//!
+//! One statement builds an object keyed by file name; each value is the
+//! user's rows of one table, oldest first. Secret values are left out: the
+//! export says which secrets exist, not what they are.
+
+use sqlx::PgPool;
+use systemprompt::identifiers::UserId;
+
+pub async fn get_user_export(
+    pool: &PgPool,
+    user_id: &UserId,
+) -> Result<serde_json::Value, sqlx::Error> {
+    sqlx::query_scalar!(
+        r#"SELECT jsonb_build_object(
+            'account', (SELECT to_jsonb(u) FROM users u WHERE u.id = $1),
+            'settings', (SELECT to_jsonb(s) FROM user_settings s WHERE s.user_id = $1),
+            'sessions', (SELECT COALESCE(jsonb_agg(to_jsonb(t) ORDER BY t.started_at), '[]')
+                FROM user_sessions t WHERE t.user_id = $1),
+            'ai_requests', (SELECT COALESCE(jsonb_agg(to_jsonb(t) ORDER BY t.created_at), '[]')
+                FROM ai_requests t WHERE t.user_id = $1),
+            'ai_request_messages', (SELECT COALESCE(jsonb_agg(to_jsonb(m) ORDER BY r.created_at, m.sequence_number), '[]')
+                FROM ai_request_messages m JOIN ai_requests r ON r.id = m.request_id
+                WHERE r.user_id = $1),
+            'governance_decisions', (SELECT COALESCE(jsonb_agg(to_jsonb(t) ORDER BY t.created_at), '[]')
+                FROM governance_decisions t WHERE t.user_id = $1),
+            'hook_events', (SELECT COALESCE(jsonb_agg(to_jsonb(t) ORDER BY t.created_at), '[]')
+                FROM plugin_usage_events t WHERE t.user_id = $1),
+            'transcripts', (SELECT COALESCE(jsonb_agg(to_jsonb(t) ORDER BY t.captured_at), '[]')
+                FROM session_transcripts t WHERE t.user_id = $1),
+            'session_analyses', (SELECT COALESCE(jsonb_agg(to_jsonb(t) ORDER BY t.created_at), '[]')
+                FROM session_analyses t WHERE t.user_id = $1),
+            'ratings', (SELECT COALESCE(jsonb_agg(to_jsonb(t) ORDER BY t.created_at), '[]')
+                FROM session_ratings t WHERE t.user_id = $1),
+            'skill_ratings', (SELECT COALESCE(jsonb_agg(to_jsonb(t) ORDER BY t.created_at), '[]')
+                FROM skill_ratings t WHERE t.user_id = $1),
+            'link_clicks', (SELECT COALESCE(jsonb_agg(to_jsonb(t) ORDER BY t.clicked_at), '[]')
+                FROM link_clicks t WHERE t.user_id = $1),
+            'mcp_tool_executions', (SELECT COALESCE(jsonb_agg(to_jsonb(t) ORDER BY t.started_at), '[]')
+                FROM mcp_tool_executions t WHERE t.user_id = $1),
+            'mcp_tool_captures', (SELECT COALESCE(jsonb_agg(to_jsonb(t) ORDER BY t.started_at), '[]')
+                FROM mcp_tool_captures t WHERE t.user_id = $1),
+            'secrets', (SELECT COALESCE(jsonb_agg(
+                    to_jsonb(t) - 'encrypted_value' - 'value_nonce'
+                        || CASE WHEN t.is_secret THEN jsonb_build_object('var_value', NULL) ELSE '{}' END
+                    ORDER BY t.plugin_id, t.var_name), '[]')
+                FROM plugin_env_vars t WHERE t.user_id = $1),
+            'secret_audit_log', (SELECT COALESCE(jsonb_agg(to_jsonb(t) ORDER BY t.created_at), '[]')
+                FROM secret_audit_log t WHERE t.user_id = $1),
+            'secret_alerts', (SELECT COALESCE(jsonb_agg(to_jsonb(t) ORDER BY t.created_at), '[]')
+                FROM secret_alerts t WHERE t.user_id = $1),
+            'activity', (SELECT COALESCE(jsonb_agg(to_jsonb(t) ORDER BY t.created_at), '[]')
+                FROM user_activity t WHERE t.user_id = $1),
+            'chargeback_lines', (SELECT COALESCE(jsonb_agg(to_jsonb(t) ORDER BY t.month_start), '[]')
+                FROM chargeback_lines t WHERE t.user_id = $1)
+        ) AS "export!""#,
+        user_id.as_str(),
+    )
+    .fetch_one(pool)
+    .await
+}
@@ -0,0 +1,126 @@
+//! Erasing a user's rows, and the certificates that record each erasure.
+//!
+//! [`erase_user_rows`] runs the [`statements`] in one transaction and either
+//! commits them with their certificate or rolls everything back, which is
+//! how a preview counts exactly what an erasure would touch. [`export`]
+//! gathers what the data subject can ask to see.
+
+mod actor_records;
+pub mod export;
+mod statements;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::PgPool;
+use sqlx::types::Json;
+use systemprompt::identifiers::UserId;
+
+use crate::erasure::ErasedTable;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ErasureCertificate {
+    pub id: String,
+    pub subject_hash: String,
+    pub requested_by: String,
+    pub reason: String,
+    pub tables: Json<Vec<ErasedTable>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// The certificate to store when the erasure is committed.
+#[derive(Debug)]
+pub struct NewErasureCertificate<'a> {
+    pub id: &'a str,
+    pub subject_hash: &'a str,
+    pub requested_by: &'a UserId,
+    pub reason: &'a str,
+}
+
+/// Erase `user_id`, replacing it with `subject_hash` where rows are kept.
+///
+/// With `certificate` set the erasure commits together with the certificate;
+/// with `None` it is rolled back and only the counts remain.
+pub async fn erase_user_rows(
+    pool: &PgPool,
+    user_id: &UserId,
+    subject_hash: &str,
+    certificate: Option<NewErasureCertificate<'_>>,
+) -> Result<Vec<ErasedTable>, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    let mut tables = statements::delete_request_bodies(&mut tx, user_id).await?;
+    tables.extend(statements::pseudonymize_audit_rows(&mut tx, user_id, subject_hash).await?);
+    tables.extend(actor_records::pseudonymize_actor_records(&mut tx, user_id, subject_hash).await?);
+    tables.extend(statements::delete_session_rows(&mut tx, user_id).await?);
+    tables.extend(statements::delete_account_rows(&mut tx, user_id).await?);
+
+    let Some(certificate) = certificate else {
+        tx.rollback().await?;
+        return Ok(tables);
+    };
+    sqlx::query!(
+        r"INSERT INTO erasure_certificates (id, subject_hash, requested_by, reason, tables)
+          VALUES ($1, $2, $3, $4, $5)",
+        certificate.id,
+        certificate.subject_hash,
+        certificate.requested_by.as_str(),
+        certificate.reason,
+        Json(&tables) as _,
+    )
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+    Ok(tables)
+}
+
+pub async fn list_erasure_certificates(
+    pool: &PgPool,
+) -> Result<Vec<ErasureCertificate>, sqlx::Error> {
+    sqlx::query_as!(
+        ErasureCertificate,
+        r#"SELECT id, subject_hash, requested_by, reason,
+                  tables AS "tables!: Json<Vec<ErasedTable>>", created_at
+           FROM erasure_certificates
+           ORDER BY created_at DESC"#,
+    )
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn find_erasure_certificate(
+    pool: &PgPool,
+    id: &str,
+) -> Result<Option<ErasureCertificate>, sqlx::Error> {
+    sqlx::query_as!(
+        ErasureCertificate,
+        r#"SELECT id, subject_hash, requested_by, reason,
+                  tables AS "tables!: Json<Vec<ErasedTable>>", created_at
+           FROM erasure_certificates
+           WHERE id = $1"#,
+        id,
+    )
+    .fetch_optional(pool)
+    .await
+}
+
+/// The first of `sessions` (held sessions) that belongs to `user_id`.
+pub async fn find_held_user_session(
+    pool: &PgPool,
+    user_id: &UserId,
+    sessions: &[String],
+) -> Result<Option<String>, sqlx::Error> {
+    sqlx::query_scalar!(
+        r#"SELECT session_id AS "session_id!" FROM (
+               SELECT session_id FROM user_sessions WHERE user_id = $1
+               UNION SELECT session_id FROM ai_requests WHERE user_id = $1
+               UNION SELECT session_id FROM plugin_usage_events WHERE user_id = $1
+               UNION SELECT session_id FROM session_transcripts WHERE user_id = $1
+           ) s
+           WHERE session_id = ANY($2)
+           ORDER BY session_id
+           LIMIT 1"#,
+        user_id.as_str(),
+        sessions,
+    )
+    .fetch_optional(pool)
+    .await
+}
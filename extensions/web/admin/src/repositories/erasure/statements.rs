@@ -0,0 +1,265 @@
+//! The statements an erasure runs, in order, inside one transaction.
+//!
+//! Each is a single statement of data-modifying CTEs so every table it
+//! touches is counted from the same snapshot:
+//!
+//! 1. `delete_request_bodies` — payloads, messages, tool call arguments and
+//!    safety finding excerpts of the user's gateway requests, plus every cached
+//!    response scoped to the user or replayed from their requests.
+//! 2. `pseudonymize_audit_rows` — the user id becomes the pseudonym on rows
+//!    kept for cost reporting and audit, and their free text is stripped. Other
+//!    users' secrets and secret alerts naming them as owner keep the pseudonym
+//!    as owner. Closed chargeback lines take the pseudonym too, through the one
+//!    update their guard allows: the transaction names the pseudonym in
+//!    `systemprompt.erasure_pseudonym` first, and only the user id changes.
+//! 3. [`super::actor_records`] — the records naming the user as the one who
+//!    acted or was flagged take the pseudonym in their place.
+//! 4. `delete_session_rows` — sessions and everything recorded per session.
+//! 5. `delete_account_rows` — every other row tied to the user, then the user.
+//!    Tables that cascade from `users` are deleted explicitly so they are
+//!    counted.
+
+use sqlx::PgConnection;
+use systemprompt::identifiers::UserId;
+
+use crate::erasure::{ErasedTable, ErasureAction};
+
+pub(super) fn table(name: &'static str, action: ErasureAction, rows: i64) -> ErasedTable {
+    ErasedTable {
+        table: name.to_owned(),
+        action,
+        rows,
+    }
+}
+
+pub(super) async fn delete_request_bodies(
+    conn: &mut PgConnection,
+    user_id: &UserId,
+) -> Result<Vec<ErasedTable>, sqlx::Error> {
+    let row = sqlx::query!(
+        r#"WITH mine AS (SELECT id FROM ai_requests WHERE user_id = $1),
+           payloads AS (
+               DELETE FROM ai_request_payloads p USING mine
+               WHERE p.ai_request_id = mine.id RETURNING 1
+           ),
+           messages AS (
+               DELETE FROM ai_request_messages m USING mine
+               WHERE m.request_id = mine.id RETURNING 1
+           ),
+           tool_calls AS (
+               UPDATE ai_request_tool_calls t
+               SET tool_input = '', tool_result_payload = NULL, updated_at = NOW()
+               FROM mine
+               WHERE t.request_id = mine.id RETURNING 1
+           ),
+           findings AS (
+               UPDATE ai_safety_findings f SET excerpt = NULL
+               FROM mine
+               WHERE f.ai_request_id = mine.id AND f.excerpt IS NOT NULL RETURNING 1
+           ),
+           cache AS (
+               DELETE FROM gateway_cache_entries
+               WHERE scope_user_id = $1 OR source_ai_request_id IN (SELECT id FROM mine)
+               RETURNING 1
+           )
+           SELECT
+               (SELECT COUNT(*) FROM payloads) AS "payloads!",
+               (SELECT COUNT(*) FROM messages) AS "messages!",
+               (SELECT COUNT(*) FROM tool_calls) AS "tool_calls!",
+               (SELECT COUNT(*) FROM findings) AS "findings!",
+               (SELECT COUNT(*) FROM cache) AS "cache!""#,
+        user_id.as_str(),
+    )
+    .fetch_one(conn)
+    .await?;
+    Ok(vec![
+        table("ai_request_payloads", ErasureAction::Deleted, row.payloads),
+        table("ai_request_messages", ErasureAction::Deleted, row.messages),
+        table(
+            "ai_request_tool_calls",
+            ErasureAction::Pseudonymized,
+            row.tool_calls,
+        ),
+        table(
+            "ai_safety_findings",
+            ErasureAction::Pseudonymized,
+            row.findings,
+        ),
+        table("gateway_cache_entries", ErasureAction::Deleted, row.cache),
+    ])
+}
+
+pub(super) async fn pseudonymize_audit_rows(
+    conn: &mut PgConnection,
+    user_id: &UserId,
+    pseudonym: &str,
+) -> Result<Vec<ErasedTable>, sqlx::Error> {
+    sqlx::query!(
+        "SELECT set_config('systemprompt.erasure_pseudonym', $1, true)",
+        pseudonym,
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+    let row = sqlx::query!(
+        r#"WITH requests AS (
+               UPDATE ai_requests SET
+                   user_id = CASE WHEN user_id = $1 THEN $2 ELSE user_id END,
+                   actor_id = CASE WHEN actor_id = $1 THEN $2 ELSE actor_id END,
+                   system_prompt_override = NULL,
+                   error_message = NULL
+               WHERE user_id = $1 OR actor_id = $1 RETURNING 1
+           ),
+           decisions AS (
+               UPDATE governance_decisions SET
+                   user_id = CASE WHEN user_id = $1 THEN $2 ELSE user_id END,
+                   actor_id = CASE WHEN actor_id = $1 THEN $2 ELSE actor_id END
+               WHERE user_id = $1 OR actor_id = $1 RETURNING 1
+           ),
+           executions AS (
+               UPDATE mcp_tool_executions SET user_id = $2, input = '', output = NULL
+               WHERE user_id = $1 RETURNING 1
+           ),
+           secret_audit AS (
+               UPDATE secret_audit_log SET
+                   user_id = CASE WHEN user_id = $1 THEN $2 ELSE user_id END,
+                   actor_id = CASE WHEN actor_id = $1 THEN $2 ELSE actor_id END,
+                   ip_address = ''
+               WHERE user_id = $1 OR actor_id = $1 RETURNING 1
+           ),
+           secret_owners AS (
+               UPDATE plugin_env_vars SET owner_id = $2
+               WHERE owner_id = $1 AND user_id <> $1 RETURNING 1
+           ),
+           alert_owners AS (
+               UPDATE secret_alerts SET owner_id = $2
+               WHERE owner_id = $1 AND user_id <> $1 RETURNING 1
+           ),
+           chargeback AS (
+               UPDATE chargeback_lines SET user_id = $2 WHERE user_id = $1 RETURNING 1
+           )
+           SELECT
+               (SELECT COUNT(*) FROM requests) AS "requests!",
+               (SELECT COUNT(*) FROM decisions) AS "decisions!",
+               (SELECT COUNT(*) FROM executions) AS "executions!",
+               (SELECT COUNT(*) FROM secret_audit) AS "secret_audit!",
+               (SELECT COUNT(*) FROM secret_owners) AS "secret_owners!",
+               (SELECT COUNT(*) FROM alert_owners) AS "alert_owners!",
+               (SELECT COUNT(*) FROM chargeback) AS "chargeback!""#,
+        user_id.as_str(),
+        pseudonym,
+    )
+    .fetch_one(conn)
+    .await?;
+    let pseudonymized = |name, rows| table(name, ErasureAction::Pseudonymized, rows);
+    Ok(vec![
+        pseudonymized("ai_requests", row.requests),
+        pseudonymized("governance_decisions", row.decisions),
+        pseudonymized("mcp_tool_executions", row.executions),
+        pseudonymized("secret_audit_log", row.secret_audit),
+        pseudonymized("plugin_env_vars", row.secret_owners),
+        pseudonymized("secret_alerts", row.alert_owners),
+        pseudonymized("chargeback_lines", row.chargeback),
+    ])
+}
+
+pub(super) async fn delete_session_rows(
+    conn: &mut PgConnection,
+    user_id: &UserId,
+) -> Result<Vec<ErasedTable>, sqlx::Error> {
+    let row = sqlx::query!(
+        r#"WITH sessions AS (DELETE FROM user_sessions WHERE user_id = $1 RETURNING 1),
+           transcripts AS (DELETE FROM session_transcripts WHERE user_id = $1 RETURNING 1),
+           hook_events AS (DELETE FROM plugin_usage_events WHERE user_id = $1 RETURNING 1),
+           mcp_captures AS (DELETE FROM mcp_tool_captures WHERE user_id = $1 RETURNING 1),
+           usage_daily AS (DELETE FROM plugin_usage_daily WHERE user_id = $1 RETURNING 1),
+           summaries AS (DELETE FROM plugin_session_summaries WHERE user_id = $1 RETURNING 1),
+           analyses AS (DELETE FROM session_analyses WHERE user_id = $1 RETURNING 1),
+           entity_links AS (DELETE FROM session_entity_links WHERE user_id = $1 RETURNING 1),
+           session_ratings AS (DELETE FROM session_ratings WHERE user_id = $1 RETURNING 1),
+           skill_ratings AS (DELETE FROM skill_ratings WHERE user_id = $1 RETURNING 1),
+           daily AS (DELETE FROM daily_summaries WHERE user_id = $1 RETURNING 1),
+           reports AS (DELETE FROM user_profile_reports WHERE user_id = $1 RETURNING 1)
+           SELECT
+               (SELECT COUNT(*) FROM sessions) AS "sessions!",
+               (SELECT COUNT(*) FROM transcripts) AS "transcripts!",
+               (SELECT COUNT(*) FROM hook_events) AS "hook_events!",
+               (SELECT COUNT(*) FROM mcp_captures) AS "mcp_captures!",
+               (SELECT COUNT(*) FROM usage_daily) AS "usage_daily!",
+               (SELECT COUNT(*) FROM summaries) AS "summaries!",
+               (SELECT COUNT(*) FROM analyses) AS "analyses!",
+               (SELECT COUNT(*) FROM entity_links) AS "entity_links!",
+               (SELECT COUNT(*) FROM session_ratings) AS "session_ratings!",
+               (SELECT COUNT(*) FROM skill_ratings) AS "skill_ratings!",
+               (SELECT COUNT(*) FROM daily) AS "daily!",
+               (SELECT COUNT(*) FROM reports) AS "reports!""#,
+        user_id.as_str(),
+    )
+    .fetch_one(conn)
+    .await?;
+    let deleted = |name, rows| table(name, ErasureAction::Deleted, rows);
+    Ok(vec![
+        deleted("user_sessions", row.sessions),
+        deleted("session_transcripts", row.transcripts),
+        deleted("plugin_usage_events", row.hook_events),
+        deleted("mcp_tool_captures", row.mcp_captures),
+        deleted("plugin_usage_daily", row.usage_daily),
+        deleted("plugin_session_summaries", row.summaries),
+        deleted("session_analyses", row.analyses),
+        deleted("session_entity_links", row.entity_links),
+        deleted("session_ratings", row.session_ratings),
+        deleted("skill_ratings", row.skill_ratings),
+        deleted("daily_summaries", row.daily),
+        deleted("user_profile_reports", row.reports),
+    ])
+}
+
+pub(super) async fn delete_account_rows(
+    conn: &mut PgConnection,
+    user_id: &UserId,
+) -> Result<Vec<ErasedTable>, sqlx::Error> {
+    let row = sqlx::query!(
+        r#"WITH link_clicks AS (DELETE FROM link_clicks WHERE user_id = $1 RETURNING 1),
+           analytics AS (DELETE FROM analytics_events WHERE user_id = $1 RETURNING 1),
+           engagement AS (DELETE FROM engagement_events WHERE user_id = $1 RETURNING 1),
+           tenant AS (DELETE FROM tenant_activity WHERE user_id = $1 RETURNING 1),
+           env_vars AS (DELETE FROM plugin_env_vars WHERE user_id = $1 RETURNING 1),
+           keys AS (DELETE FROM user_encryption_keys WHERE user_id = $1 RETURNING 1),
+           tokens AS (DELETE FROM secret_resolution_tokens WHERE user_id = $1 RETURNING 1),
+           alerts AS (DELETE FROM secret_alerts WHERE user_id = $1 RETURNING 1),
+           settings AS (DELETE FROM user_settings WHERE user_id = $1 RETURNING 1),
+           activity AS (DELETE FROM user_activity WHERE user_id = $1 RETURNING 1),
+           api_keys AS (DELETE FROM user_api_keys WHERE user_id = $1 RETURNING 1),
+           account AS (DELETE FROM users WHERE id = $1 RETURNING 1)
+           SELECT
+               (SELECT COUNT(*) FROM link_clicks) AS "link_clicks!",
+               (SELECT COUNT(*) FROM analytics) AS "analytics!",
+               (SELECT COUNT(*) FROM engagement) AS "engagement!",
+               (SELECT COUNT(*) FROM tenant) AS "tenant!",
+               (SELECT COUNT(*) FROM env_vars) AS "env_vars!",
+               (SELECT COUNT(*) FROM keys) AS "keys!",
+               (SELECT COUNT(*) FROM tokens) AS "tokens!",
+               (SELECT COUNT(*) FROM alerts) AS "alerts!",
+               (SELECT COUNT(*) FROM settings) AS "settings!",
+               (SELECT COUNT(*) FROM activity) AS "activity!",
+               (SELECT COUNT(*) FROM api_keys) AS "api_keys!",
+               (SELECT COUNT(*) FROM account) AS "account!""#,
+        user_id.as_str(),
+    )
+    .fetch_one(conn)
+    .await?;
+    let deleted = |name, rows| table(name, ErasureAction::Deleted, rows);
+    Ok(vec![
+        deleted("link_clicks", row.link_clicks),
+        deleted("analytics_events", row.analytics),
+        deleted("engagement_events", row.engagement),
+        deleted("tenant_activity", row.tenant),
+        deleted("plugin_env_vars", row.env_vars),
+        deleted("user_encryption_keys", row.keys),
+        deleted("secret_resolution_tokens", row.tokens),
+        deleted("secret_alerts", row.alerts),
+        deleted("user_settings", row.settings),
+        deleted("user_activity", row.activity),
+        deleted("user_api_keys", row.api_keys),
+        deleted("users", row.account),
+    ])
+}
@@ -0,0 +1,79 @@
+//! High-water marks for scheduled exporters, one row per exporter.
+
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+
+#[derive(Debug, Clone)]
+pub struct ExportCursor {
+    pub exported_through: DateTime<Utc>,
+    pub items_exported: i64,
+    pub last_run_at: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+}
+
+pub async fn find_export_cursor(
+    pool: &PgPool,
+    exporter: &str,
+) -> Result<Option<ExportCursor>, sqlx::Error> {
+    sqlx::query_as!(
+        ExportCursor,
+        r#"SELECT exported_through, items_exported, last_run_at, last_error
+           FROM export_cursors
+           WHERE exporter = $1"#,
+        exporter,
+    )
+    .fetch_optional(pool)
+    .await
+}
+
+/// Advance the mark after a window was delivered in full, clearing any
+/// earlier failure.
+pub async fn set_export_cursor(
+    pool: &PgPool,
+    exporter: &str,
+    exported_through: DateTime<Utc>,
+    items: i64,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r"INSERT INTO export_cursors
+              (exporter, exported_through, items_exported, last_run_at, last_error, updated_at)
+          VALUES ($1, $2, $3, NOW(), NULL, NOW())
+          ON CONFLICT (exporter) DO UPDATE SET
+              exported_through = EXCLUDED.exported_through,
+              items_exported = export_cursors.items_exported + EXCLUDED.items_exported,
+              last_run_at = NOW(),
+              last_error = NULL,
+              updated_at = NOW()",
+        exporter,
+        exported_through,
+        items,
+    )
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Record a failed run without moving the mark. `start_at` seeds the mark
+/// for an exporter that has never delivered anything.
+pub async fn set_export_error(
+    pool: &PgPool,
+    exporter: &str,
+    start_at: DateTime<Utc>,
+    error: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r"INSERT INTO export_cursors
+              (exporter, exported_through, last_run_at, last_error, updated_at)
+          VALUES ($1, $2, NOW(), $3, NOW())
+          ON CONFLICT (exporter) DO UPDATE SET
+              last_run_at = NOW(),
+              last_error = EXCLUDED.last_error,
+              updated_at = NOW()",
+        exporter,
+        start_at,
+        error,
+    )
+    .execute(pool)
+    .await?;
+    Ok(())
+}
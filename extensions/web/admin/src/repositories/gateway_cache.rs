@@ -0,0 +1,248 @@
+//! Gateway response cache entries and the lookup log behind its hit rate.
+//!
+//! The cache runs inside an outbound adapter, which sees the exact bytes
+//! about to go upstream but not the request id or the caller. Core has
+//! already stored the digest of those bytes on the request's payload row by
+//! then, so [`list_pending_requests_by_digest`] recovers the requests that
+//! could be in flight with them; the caller decides whether that is one.
+//!
+//! That guess names the caller and the request a miss fills the cache from.
+//! It never marks a hit: a hit's lookup carries the id it was replayed under,
+//! and the schema ties it to the request whose stored reply carries that id.
+
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use systemprompt::identifiers::{AiRequestId, SessionId, UserId};
+
+use crate::util::time_range::TimeRange;
+
+#[derive(Debug, Clone)]
+pub struct PendingGatewayRequest {
+    pub ai_request_id: AiRequestId,
+    pub user_id: UserId,
+    pub session: Option<SessionId>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CacheEntryRow {
+    pub id: i64,
+    pub response: serde_json::Value,
+}
+
+#[derive(Debug, Clone)]
+pub struct CacheCandidate {
+    pub id: i64,
+    pub embedding: Vec<f32>,
+}
+
+#[derive(Debug, Clone)]
+pub struct NewCacheEntry<'a> {
+    pub cache_key: &'a str,
+    pub shape_key: &'a str,
+    pub route_id: &'a str,
+    pub scope_user_id: Option<&'a UserId>,
+    pub provider: &'a str,
+    pub upstream_model: &'a str,
+    pub embedding: Option<&'a [f32]>,
+    pub response: &'a serde_json::Value,
+    pub source_ai_request_id: &'a AiRequestId,
+    pub expires_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct NewCacheLookup<'a> {
+    pub ai_request_id: Option<&'a AiRequestId>,
+    pub replay_id: Option<&'a str>,
+    pub route_id: &'a str,
+    pub outcome: &'a str,
+    pub entry_id: Option<i64>,
+    pub similarity: Option<f32>,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ResponseCacheStats {
+    pub lookups: i64,
+    pub exact_hits: i64,
+    pub semantic_hits: i64,
+    pub saved_microdollars: i64,
+    pub live_entries: i64,
+}
+
+/// The still-pending requests whose prepared body has this digest and that
+/// no cache lookup has claimed yet, oldest first.
+pub async fn list_pending_requests_by_digest(
+    pool: &PgPool,
+    prepared_sha256: &str,
+) -> Result<Vec<PendingGatewayRequest>, sqlx::Error> {
+    sqlx::query_as!(
+        PendingGatewayRequest,
+        r#"SELECT r.id AS "ai_request_id!: AiRequestId", r.user_id AS "user_id!: UserId",
+                  r.session_id AS "session?: SessionId"
+           FROM ai_request_payloads p
+           JOIN ai_requests r ON r.id = p.ai_request_id
+           WHERE p.prepared_body_sha256 = $1
+             AND p.created_at > NOW() - INTERVAL '10 minutes'
+             AND r.status = 'pending'
+             AND NOT EXISTS (
+                 SELECT 1 FROM gateway_cache_lookups l WHERE l.ai_request_id = r.id
+             )
+           ORDER BY r.created_at ASC
+           LIMIT 20"#,
+        prepared_sha256,
+    )
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn find_cache_entry(
+    pool: &PgPool,
+    cache_key: &str,
+) -> Result<Option<CacheEntryRow>, sqlx::Error> {
+    sqlx::query_as!(
+        CacheEntryRow,
+        r"SELECT id, response FROM gateway_cache_entries
+          WHERE cache_key = $1 AND expires_at > NOW()",
+        cache_key,
+    )
+    .fetch_optional(pool)
+    .await
+}
+
+pub async fn find_cache_entry_by_id(
+    pool: &PgPool,
+    id: i64,
+) -> Result<Option<CacheEntryRow>, sqlx::Error> {
+    sqlx::query_as!(
+        CacheEntryRow,
+        r"SELECT id, response FROM gateway_cache_entries
+          WHERE id = $1 AND expires_at > NOW()",
+        id,
+    )
+    .fetch_optional(pool)
+    .await
+}
+
+pub async fn list_cache_candidates(
+    pool: &PgPool,
+    shape_key: &str,
+    limit: i64,
+) -> Result<Vec<CacheCandidate>, sqlx::Error> {
+    sqlx::query_as!(
+        CacheCandidate,
+        r#"SELECT id, embedding AS "embedding!" FROM gateway_cache_entries
+           WHERE shape_key = $1 AND expires_at > NOW() AND embedding IS NOT NULL
+           ORDER BY created_at DESC
+           LIMIT $2"#,
+        shape_key,
+        limit,
+    )
+    .fetch_all(pool)
+    .await
+}
+
+/// Store a reply. An entry already under the key, live or expired, is
+/// replaced and its hit count starts over.
+pub async fn insert_cache_entry(
+    pool: &PgPool,
+    entry: NewCacheEntry<'_>,
+) -> Result<i64, sqlx::Error> {
+    sqlx::query_scalar!(
+        r"INSERT INTO gateway_cache_entries
+              (cache_key, shape_key, route_id, scope_user_id, provider, upstream_model,
+               embedding, response, source_ai_request_id, expires_at)
+          VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+          ON CONFLICT (cache_key) DO UPDATE SET
+              shape_key = EXCLUDED.shape_key,
+              embedding = EXCLUDED.embedding,
+              response = EXCLUDED.response,
+              source_ai_request_id = EXCLUDED.source_ai_request_id,
+              expires_at = EXCLUDED.expires_at,
+              hit_count = 0,
+              last_hit_at = NULL,
+              created_at = NOW()
+          RETURNING id",
+        entry.cache_key,
+        entry.shape_key,
+        entry.route_id,
+        entry.scope_user_id.map(UserId::as_str),
+        entry.provider,
+        entry.upstream_model,
+        entry.embedding,
+        entry.response,
+        entry.source_ai_request_id.as_str(),
+        entry.expires_at,
+    )
+    .fetch_one(pool)
+    .await
+}
+
+pub async fn delete_expired_cache_entries(pool: &PgPool) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query!("DELETE FROM gateway_cache_entries WHERE expires_at <= NOW()")
+        .execute(pool)
+        .await?;
+    Ok(result.rows_affected())
+}
+
+pub async fn update_cache_entry_hit(pool: &PgPool, id: i64) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        "UPDATE gateway_cache_entries SET hit_count = hit_count + 1, last_hit_at = NOW()
+         WHERE id = $1",
+        id,
+    )
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Record one lookup.
+///
+/// A miss names its request; a hit names the id it was replayed under
+/// instead, and is credited with what the entry's original request cost,
+/// which is what serving it from the cache saved.
+pub async fn insert_cache_lookup(
+    pool: &PgPool,
+    lookup: NewCacheLookup<'_>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r"INSERT INTO gateway_cache_lookups
+              (ai_request_id, replay_id, route_id, outcome, entry_id, similarity,
+               saved_microdollars)
+          VALUES ($1, $2, $3, $4, $5, $6,
+                  CASE WHEN $4 = 'miss' THEN 0 ELSE COALESCE((
+                      SELECT r.cost_microdollars FROM gateway_cache_entries e
+                      JOIN ai_requests r ON r.id = e.source_ai_request_id
+                      WHERE e.id = $5), 0) END)
+          ON CONFLICT (ai_request_id) WHERE outcome = 'miss' DO NOTHING",
+        lookup.ai_request_id.map(AiRequestId::as_str),
+        lookup.replay_id,
+        lookup.route_id,
+        lookup.outcome,
+        lookup.entry_id,
+        lookup.similarity,
+    )
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+pub async fn get_response_cache_stats(
+    pool: &PgPool,
+    range: TimeRange,
+) -> Result<ResponseCacheStats, sqlx::Error> {
+    sqlx::query_as!(
+        ResponseCacheStats,
+        r#"SELECT
+               COUNT(*)::bigint AS "lookups!",
+               COUNT(*) FILTER (WHERE outcome = 'exact')::bigint AS "exact_hits!",
+               COUNT(*) FILTER (WHERE outcome = 'semantic')::bigint AS "semantic_hits!",
+               COALESCE(SUM(saved_microdollars), 0)::bigint AS "saved_microdollars!",
+               (SELECT COUNT(*) FROM gateway_cache_entries
+                WHERE expires_at > NOW())::bigint AS "live_entries!"
+           FROM gateway_cache_lookups
+           WHERE created_at >= $1 AND created_at < $2"#,
+        range.from,
+        range.to,
+    )
+    .fetch_one(pool)
+    .await
+}
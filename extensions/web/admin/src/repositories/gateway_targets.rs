@@ -0,0 +1,59 @@
+//! Which gateway target served a request, and the failures it retried past.
+//!
+//! Target failover runs inside the outbound adapter, which never learns the
+//! request id. It stores the upstream's response id against the target
+//! instead, and once core stores the reply the schema binds that id to the
+//! request in `gateway_request_targets` (see `31_gateway_request_targets.sql`).
+
+use sqlx::PgPool;
+
+pub async fn insert_target_response(
+    pool: &PgPool,
+    response_id: &str,
+    route_id: &str,
+    target_id: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        "INSERT INTO gateway_target_responses (response_id, route_id, target_id)
+         VALUES ($1, $2, $3)
+         ON CONFLICT (response_id) DO NOTHING",
+        response_id,
+        route_id,
+        target_id,
+    )
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+pub async fn insert_target_failure(
+    pool: &PgPool,
+    provider: &str,
+    model: &str,
+    error_message: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        "INSERT INTO gateway_target_failures (provider, model, error_message)
+         VALUES ($1, $2, $3)",
+        provider,
+        model,
+        error_message,
+    )
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Sweep response ids no stored reply claimed within an hour and failures
+/// older than any health window.
+pub async fn delete_stale_target_rows(pool: &PgPool) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        "DELETE FROM gateway_target_responses WHERE created_at < NOW() - INTERVAL '1 hour'"
+    )
+    .execute(pool)
+    .await?;
+    sqlx::query!("DELETE FROM gateway_target_failures WHERE failed_at < NOW() - INTERVAL '1 day'")
+        .execute(pool)
+        .await?;
+    Ok(())
+}
@@ -1,8 +1,11 @@
-//! Allow/deny/secret rollups: overall and per-policy, lifetime and windowed.
+//! Allow/deny/secret rollups: overall and per-policy, lifetime, windowed, and
+//! per-policy over a fixed range for one [`ActivityScope`].
 
 use sqlx::PgPool;
 
 use super::{GovernanceCounts, PerPolicyCounts};
+use crate::util::activity_scope::ActivityScope;
+use crate::util::time_range::TimeRange;
 
 // Why: lint-ok: unused-pub — live upstream via the ssr_governance handlers,
 // which this fork does not ship; kept so shared repository files stay
@@ -111,3 +114,41 @@ pub async fn list_per_policy_counts_windowed(
         })
         .collect())
 }
+
+/// Per-policy allow/deny counts over `[range.from, range.to)`, narrowed to
+/// `scope`; the digest's governance section.
+pub async fn list_per_policy_counts_in_range(
+    pool: &PgPool,
+    range: TimeRange,
+    scope: ActivityScope<'_>,
+) -> Result<Vec<PerPolicyCounts>, sqlx::Error> {
+    let rows = sqlx::query!(
+        r#"SELECT
+            d.policy,
+            COUNT(*) FILTER (WHERE d.decision = 'allow')::bigint AS "allowed!",
+            COUNT(*) FILTER (WHERE d.decision = 'deny')::bigint AS "denied!",
+            MAX(d.created_at) AS last_at
+        FROM governance_decisions d
+        LEFT JOIN user_profile_ext upe ON upe.user_id = d.user_id
+        WHERE d.created_at >= $1 AND d.created_at < $2
+          AND ($3::text IS NULL OR d.user_id = $3)
+          AND ($4::text IS NULL OR COALESCE(NULLIF(upe.department, ''), 'Default') = $4)
+        GROUP BY d.policy
+        ORDER BY d.policy"#,
+        range.from,
+        range.to,
+        scope.user(),
+        scope.department,
+    )
+    .fetch_all(pool)
+    .await?;
+    Ok(rows
+        .into_iter()
+        .map(|r| PerPolicyCounts {
+            policy: r.policy,
+            allowed: r.allowed,
+            denied: r.denied,
+            last_at: r.last_at,
+        })
+        .collect())
+}
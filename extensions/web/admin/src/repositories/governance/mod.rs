@@ -19,7 +19,7 @@ pub mod resolve;
 
 pub use counts::{
     get_governance_counts, get_governance_counts_windowed, list_per_policy_counts,
-    list_per_policy_counts_windowed,
+    list_per_policy_counts_in_range, list_per_policy_counts_windowed,
 };
 pub use decisions::list_decisions_for_policy;
 pub use rankings::{list_top_actors, list_top_policies};
@@ -0,0 +1,183 @@
+//! Cumulative tallies behind the Prometheus `/metrics` endpoint.
+//!
+//! The tallies are counts over the whole audit table, not a window, plus what
+//! retention has already deleted from it (see `30_retired_metric_tallies.sql`),
+//! so they only ever grow and Prometheus can treat them as counters. Gateway
+//! requests are counted once they leave `pending`; until then they are in
+//! flight, a gauge of its own, because their status label is still to change.
+
+use sqlx::PgPool;
+
+/// Gateway requests sharing provider, model, route, status and latency
+/// bucket.
+#[derive(Debug, Clone)]
+pub struct GatewayRequestTally {
+    pub provider: Option<String>,
+    pub model: Option<String>,
+    pub route: Option<String>,
+    pub status: String,
+    /// Index into the bucket bounds passed to the query of the first bound
+    /// the latency is at or under; one past the last bound when it is over
+    /// them all, and `None` when no latency was recorded.
+    pub bucket: Option<i32>,
+    pub requests: i64,
+    pub latency_ms: i64,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub cache_read_tokens: i64,
+    pub cost_microdollars: i64,
+}
+
+/// Gateway requests still `pending`, by provider, model and route.
+#[derive(Debug, Clone)]
+pub struct InFlightTally {
+    pub provider: Option<String>,
+    pub model: Option<String>,
+    pub route: Option<String>,
+    pub requests: i64,
+}
+
+#[derive(Debug, Clone)]
+pub struct DecisionTally {
+    pub policy: String,
+    pub decision: String,
+    pub decisions: i64,
+}
+
+#[derive(Debug, Clone)]
+pub struct SafetyFindingTally {
+    pub scanner: String,
+    pub category: String,
+    pub severity: String,
+    pub phase: String,
+    pub findings: i64,
+}
+
+/// Finished gateway requests, live and retired. `bounds_ms` must be
+/// ascending and the same bounds the retention purge tallies with.
+pub async fn list_gateway_request_tallies(
+    pool: &PgPool,
+    bounds_ms: &[i32],
+) -> Result<Vec<GatewayRequestTally>, sqlx::Error> {
+    // Why: `width_bucket` over a threshold array counts the thresholds at or
+    // below its operand. Feeding it `latency_ms - 1` counts the thresholds
+    // strictly below the latency, which is the index of the first bound the
+    // latency does not exceed: Prometheus's inclusive `le`.
+    sqlx::query_as!(
+        GatewayRequestTally,
+        r#"SELECT
+            provider,
+            model,
+            route,
+            status                                        AS "status!",
+            bucket,
+            SUM(requests)::bigint                         AS "requests!",
+            SUM(latency_ms)::bigint                       AS "latency_ms!",
+            SUM(input_tokens)::bigint                     AS "input_tokens!",
+            SUM(output_tokens)::bigint                    AS "output_tokens!",
+            SUM(cache_read_tokens)::bigint                AS "cache_read_tokens!",
+            SUM(cost_microdollars)::bigint                AS "cost_microdollars!"
+        FROM (
+            SELECT
+                provider,
+                model,
+                route_match                                   AS route,
+                status::text                                  AS status,
+                width_bucket(latency_ms - 1, $1::int4[])      AS bucket,
+                COUNT(*)                                      AS requests,
+                COALESCE(SUM(latency_ms), 0)::bigint          AS latency_ms,
+                COALESCE(SUM(input_tokens), 0)::bigint        AS input_tokens,
+                COALESCE(SUM(output_tokens), 0)::bigint       AS output_tokens,
+                COALESCE(SUM(cache_read_tokens), 0)::bigint   AS cache_read_tokens,
+                COALESCE(SUM(cost_microdollars), 0)::bigint   AS cost_microdollars
+            FROM ai_requests
+            WHERE status <> 'pending'
+            GROUP BY 1, 2, 3, 4, 5
+            UNION ALL
+            SELECT
+                NULLIF(provider, ''),
+                NULLIF(model, ''),
+                NULLIF(route, ''),
+                status,
+                NULLIF(bucket, -1),
+                requests,
+                latency_ms,
+                input_tokens,
+                output_tokens,
+                cache_read_tokens,
+                cost_microdollars
+            FROM retired_gateway_request_tallies
+        ) t
+        GROUP BY 1, 2, 3, 4, 5
+        ORDER BY 1, 2, 3, 4, 5"#,
+        bounds_ms,
+    )
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn list_in_flight_requests(pool: &PgPool) -> Result<Vec<InFlightTally>, sqlx::Error> {
+    sqlx::query_as!(
+        InFlightTally,
+        r#"SELECT
+            provider,
+            model,
+            route_match     AS route,
+            COUNT(*)        AS "requests!"
+        FROM ai_requests
+        WHERE status = 'pending'
+        GROUP BY 1, 2, 3
+        ORDER BY 1, 2, 3"#,
+    )
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn list_decision_tallies(pool: &PgPool) -> Result<Vec<DecisionTally>, sqlx::Error> {
+    sqlx::query_as!(
+        DecisionTally,
+        r#"SELECT
+            policy          AS "policy!",
+            decision        AS "decision!",
+            SUM(decisions)::bigint AS "decisions!"
+        FROM (
+            SELECT policy, decision, COUNT(*) AS decisions
+            FROM governance_decisions
+            GROUP BY policy, decision
+            UNION ALL
+            SELECT policy, decision, decisions
+            FROM retired_governance_decision_tallies
+        ) t
+        GROUP BY policy, decision
+        ORDER BY policy, decision"#,
+    )
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn list_safety_finding_tallies(
+    pool: &PgPool,
+) -> Result<Vec<SafetyFindingTally>, sqlx::Error> {
+    sqlx::query_as!(
+        SafetyFindingTally,
+        r#"SELECT
+            scanner         AS "scanner!",
+            category        AS "category!",
+            severity        AS "severity!",
+            phase           AS "phase!",
+            SUM(findings)::bigint AS "findings!"
+        FROM (
+            SELECT scanner::text, category::text, severity::text, phase::text,
+                   COUNT(*) AS findings
+            FROM ai_safety_findings
+            GROUP BY 1, 2, 3, 4
+            UNION ALL
+            SELECT scanner, category, severity, phase, findings
+            FROM retired_safety_finding_tallies
+        ) t
+        GROUP BY 1, 2, 3, 4
+        ORDER BY 1, 2, 3, 4"#,
+    )
+    .fetch_all(pool)
+    .await
+}
@@ -6,14 +6,26 @@
 
 pub mod access_tokens;
 pub mod analytics;
+pub mod chargeback;
 pub mod config;
+pub mod cost_watch;
 pub mod dashboard;
 pub mod departments;
+pub mod digests;
+pub mod erasure;
 pub mod evals;
+pub mod export_cursors;
+pub mod gateway_cache;
+pub mod gateway_targets;
 pub mod governance;
 pub mod jobs;
 pub mod marketplace;
 pub mod mcp;
+pub mod metrics;
+pub mod retention;
+pub mod scim;
+pub mod search;
 pub mod secrets;
 pub mod traces;
 pub mod users;
+pub mod warehouse;
@@ -0,0 +1,250 @@
+//! How much each data class has waiting for the retention job.
+//!
+//! Every count is over rows written before `horizon`, the cutoff a few days
+//! on: `due_now` are past the policy and will go on the next run,
+//! `due_soon` will be by the horizon, and `held` are past it but kept under
+//! legal hold.
+
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+
+use crate::retention::LegalHolds;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RetentionBacklog {
+    pub due_now: i64,
+    pub due_soon: i64,
+    pub held: i64,
+}
+
+/// Gateway requests that still carry a body.
+pub async fn get_ai_request_bodies_backlog(
+    pool: &PgPool,
+    cutoff: DateTime<Utc>,
+    horizon: DateTime<Utc>,
+    holds: &LegalHolds,
+) -> Result<RetentionBacklog, sqlx::Error> {
+    sqlx::query_as!(
+        RetentionBacklog,
+        r#"WITH aged AS (
+               SELECT r.created_at AS at,
+                      (r.user_id = ANY($3::text[])
+                       OR COALESCE(r.session_id, '') = ANY($4::text[])) AS held
+               FROM ai_requests r
+               WHERE r.created_at < $2
+                 AND (EXISTS (SELECT 1 FROM ai_request_payloads p WHERE p.ai_request_id = r.id)
+                   OR EXISTS (SELECT 1 FROM ai_request_messages m WHERE m.request_id = r.id)
+                   OR EXISTS (SELECT 1 FROM ai_request_tool_calls t
+                              WHERE t.request_id = r.id
+                                AND (t.tool_input <> '' OR t.tool_result_payload IS NOT NULL)))
+           )
+           SELECT COUNT(*) FILTER (WHERE at < $1 AND NOT held) AS "due_now!",
+                  COUNT(*) FILTER (WHERE at >= $1 AND NOT held) AS "due_soon!",
+                  COUNT(*) FILTER (WHERE at < $1 AND held) AS "held!"
+           FROM aged"#,
+        cutoff,
+        horizon,
+        &holds.users,
+        &holds.sessions,
+    )
+    .fetch_one(pool)
+    .await
+}
+
+pub async fn get_ai_requests_backlog(
+    pool: &PgPool,
+    cutoff: DateTime<Utc>,
+    horizon: DateTime<Utc>,
+    holds: &LegalHolds,
+) -> Result<RetentionBacklog, sqlx::Error> {
+    sqlx::query_as!(
+        RetentionBacklog,
+        r#"WITH aged AS (
+               SELECT created_at AS at,
+                      (user_id = ANY($3::text[])
+                       OR COALESCE(session_id, '') = ANY($4::text[])) AS held
+               FROM ai_requests
+               WHERE created_at < $2
+           )
+           SELECT COUNT(*) FILTER (WHERE at < $1 AND NOT held) AS "due_now!",
+                  COUNT(*) FILTER (WHERE at >= $1 AND NOT held) AS "due_soon!",
+                  COUNT(*) FILTER (WHERE at < $1 AND held) AS "held!"
+           FROM aged"#,
+        cutoff,
+        horizon,
+        &holds.users,
+        &holds.sessions,
+    )
+    .fetch_one(pool)
+    .await
+}
+
+/// Hook events that still carry details.
+pub async fn get_hook_event_details_backlog(
+    pool: &PgPool,
+    cutoff: DateTime<Utc>,
+    horizon: DateTime<Utc>,
+    holds: &LegalHolds,
+) -> Result<RetentionBacklog, sqlx::Error> {
+    sqlx::query_as!(
+        RetentionBacklog,
+        r#"WITH aged AS (
+               SELECT created_at AS at,
+                      (user_id = ANY($3::text[])
+                       OR COALESCE(session_id, '') = ANY($4::text[])) AS held
+               FROM plugin_usage_events
+               WHERE created_at < $2
+                 AND (prompt_preview IS NOT NULL OR description IS NOT NULL
+                      OR cwd IS NOT NULL OR metadata IS NOT NULL)
+           )
+           SELECT COUNT(*) FILTER (WHERE at < $1 AND NOT held) AS "due_now!",
+                  COUNT(*) FILTER (WHERE at >= $1 AND NOT held) AS "due_soon!",
+                  COUNT(*) FILTER (WHERE at < $1 AND held) AS "held!"
+           FROM aged"#,
+        cutoff,
+        horizon,
+        &holds.users,
+        &holds.sessions,
+    )
+    .fetch_one(pool)
+    .await
+}
+
+pub async fn get_hook_events_backlog(
+    pool: &PgPool,
+    cutoff: DateTime<Utc>,
+    horizon: DateTime<Utc>,
+    holds: &LegalHolds,
+) -> Result<RetentionBacklog, sqlx::Error> {
+    sqlx::query_as!(
+        RetentionBacklog,
+        r#"WITH aged AS (
+               SELECT created_at AS at,
+                      (user_id = ANY($3::text[])
+                       OR COALESCE(session_id, '') = ANY($4::text[])) AS held
+               FROM plugin_usage_events
+               WHERE created_at < $2
+           )
+           SELECT COUNT(*) FILTER (WHERE at < $1 AND NOT held) AS "due_now!",
+                  COUNT(*) FILTER (WHERE at >= $1 AND NOT held) AS "due_soon!",
+                  COUNT(*) FILTER (WHERE at < $1 AND held) AS "held!"
+           FROM aged"#,
+        cutoff,
+        horizon,
+        &holds.users,
+        &holds.sessions,
+    )
+    .fetch_one(pool)
+    .await
+}
+
+pub async fn get_transcripts_backlog(
+    pool: &PgPool,
+    cutoff: DateTime<Utc>,
+    horizon: DateTime<Utc>,
+    holds: &LegalHolds,
+) -> Result<RetentionBacklog, sqlx::Error> {
+    sqlx::query_as!(
+        RetentionBacklog,
+        r#"WITH aged AS (
+               SELECT captured_at AS at,
+                      (user_id = ANY($3::text[])
+                       OR COALESCE(session_id, '') = ANY($4::text[])) AS held
+               FROM session_transcripts
+               WHERE captured_at < $2
+           )
+           SELECT COUNT(*) FILTER (WHERE at < $1 AND NOT held) AS "due_now!",
+                  COUNT(*) FILTER (WHERE at >= $1 AND NOT held) AS "due_soon!",
+                  COUNT(*) FILTER (WHERE at < $1 AND held) AS "held!"
+           FROM aged"#,
+        cutoff,
+        horizon,
+        &holds.users,
+        &holds.sessions,
+    )
+    .fetch_one(pool)
+    .await
+}
+
+pub async fn get_governance_decisions_backlog(
+    pool: &PgPool,
+    cutoff: DateTime<Utc>,
+    horizon: DateTime<Utc>,
+    holds: &LegalHolds,
+) -> Result<RetentionBacklog, sqlx::Error> {
+    sqlx::query_as!(
+        RetentionBacklog,
+        r#"WITH aged AS (
+               SELECT created_at AS at,
+                      (user_id = ANY($3::text[])
+                       OR COALESCE(session_id, '') = ANY($4::text[])) AS held
+               FROM governance_decisions
+               WHERE created_at < $2
+           )
+           SELECT COUNT(*) FILTER (WHERE at < $1 AND NOT held) AS "due_now!",
+                  COUNT(*) FILTER (WHERE at >= $1 AND NOT held) AS "due_soon!",
+                  COUNT(*) FILTER (WHERE at < $1 AND held) AS "held!"
+           FROM aged"#,
+        cutoff,
+        horizon,
+        &holds.users,
+        &holds.sessions,
+    )
+    .fetch_one(pool)
+    .await
+}
+
+pub async fn get_link_clicks_backlog(
+    pool: &PgPool,
+    cutoff: DateTime<Utc>,
+    horizon: DateTime<Utc>,
+    holds: &LegalHolds,
+) -> Result<RetentionBacklog, sqlx::Error> {
+    sqlx::query_as!(
+        RetentionBacklog,
+        r#"WITH aged AS (
+               SELECT clicked_at AS at,
+                      (COALESCE(user_id, '') = ANY($3::text[])
+                       OR COALESCE(session_id, '') = ANY($4::text[])) AS held
+               FROM link_clicks
+               WHERE clicked_at < $2
+           )
+           SELECT COUNT(*) FILTER (WHERE at < $1 AND NOT held) AS "due_now!",
+                  COUNT(*) FILTER (WHERE at >= $1 AND NOT held) AS "due_soon!",
+                  COUNT(*) FILTER (WHERE at < $1 AND held) AS "held!"
+           FROM aged"#,
+        cutoff,
+        horizon,
+        &holds.users,
+        &holds.sessions,
+    )
+    .fetch_one(pool)
+    .await
+}
+
+pub async fn get_mcp_tool_captures_backlog(
+    pool: &PgPool,
+    cutoff: DateTime<Utc>,
+    horizon: DateTime<Utc>,
+    holds: &LegalHolds,
+) -> Result<RetentionBacklog, sqlx::Error> {
+    sqlx::query_as!(
+        RetentionBacklog,
+        r#"WITH aged AS (
+               SELECT ended_at AS at,
+                      (user_id = ANY($3::text[]) OR session_id = ANY($4::text[])) AS held
+               FROM mcp_tool_captures
+               WHERE ended_at < $2
+           )
+           SELECT COUNT(*) FILTER (WHERE at < $1 AND NOT held) AS "due_now!",
+                  COUNT(*) FILTER (WHERE at >= $1 AND NOT held) AS "due_soon!",
+                  COUNT(*) FILTER (WHERE at < $1 AND held) AS "held!"
+           FROM aged"#,
+        cutoff,
+        horizon,
+        &holds.users,
+        &holds.sessions,
+    )
+    .fetch_one(pool)
+    .await
+}
@@ -0,0 +1,86 @@
+//! Retention bookkeeping and the statements that enforce it.
+//!
+//! [`purge`] removes or strips one batch of expired rows per data class;
+//! [`backlog`] counts what each class has waiting. `retention_purges` keeps
+//! what the last run of each class did.
+
+pub mod backlog;
+pub mod purge;
+
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+
+#[derive(Debug, Clone)]
+pub struct RetentionPurge {
+    pub data_class: String,
+    pub last_run_at: DateTime<Utc>,
+    pub last_cutoff: DateTime<Utc>,
+    pub last_purged: i64,
+    pub total_purged: i64,
+    pub last_error: Option<String>,
+}
+
+pub async fn list_retention_purges(pool: &PgPool) -> Result<Vec<RetentionPurge>, sqlx::Error> {
+    sqlx::query_as!(
+        RetentionPurge,
+        r"SELECT data_class, last_run_at, last_cutoff, last_purged, total_purged, last_error
+          FROM retention_purges
+          ORDER BY data_class",
+    )
+    .fetch_all(pool)
+    .await
+}
+
+/// Record a run of `data_class` that finished, clearing any earlier failure.
+pub async fn set_retention_purge(
+    pool: &PgPool,
+    data_class: &str,
+    cutoff: DateTime<Utc>,
+    purged: i64,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r"INSERT INTO retention_purges
+              (data_class, last_run_at, last_cutoff, last_purged, total_purged, last_error)
+          VALUES ($1, NOW(), $2, $3, $3, NULL)
+          ON CONFLICT (data_class) DO UPDATE SET
+              last_run_at = NOW(),
+              last_cutoff = EXCLUDED.last_cutoff,
+              last_purged = EXCLUDED.last_purged,
+              total_purged = retention_purges.total_purged + EXCLUDED.last_purged,
+              last_error = NULL",
+        data_class,
+        cutoff,
+        purged,
+    )
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Record a run of `data_class` that failed after purging `purged` rows.
+pub async fn set_retention_error(
+    pool: &PgPool,
+    data_class: &str,
+    cutoff: DateTime<Utc>,
+    purged: i64,
+    error: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r"INSERT INTO retention_purges
+              (data_class, last_run_at, last_cutoff, last_purged, total_purged, last_error)
+          VALUES ($1, NOW(), $2, $3, $3, $4)
+          ON CONFLICT (data_class) DO UPDATE SET
+              last_run_at = NOW(),
+              last_cutoff = EXCLUDED.last_cutoff,
+              last_purged = EXCLUDED.last_purged,
+              total_purged = retention_purges.total_purged + EXCLUDED.last_purged,
+              last_error = EXCLUDED.last_error",
+        data_class,
+        cutoff,
+        purged,
+        error,
+    )
+    .execute(pool)
+    .await?;
+    Ok(())
+}
@@ -0,0 +1,186 @@
+//! One batch of retention purging per data class.
+//!
+//! Each statement takes the oldest `limit` expired rows not under legal hold
+//! and returns how many it removed or stripped; the caller repeats until a
+//! batch comes back short. A hold matches on the row's user or session, so a
+//! row with neither is never held; a row without a session is judged on its
+//! user alone.
+
+mod requests;
+
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+
+use crate::retention::LegalHolds;
+
+pub use requests::{delete_expired_ai_request_bodies, delete_expired_ai_requests};
+
+/// Blank the free-text and payload fields of expired hook events, keeping
+/// the event itself for usage counts.
+pub async fn delete_expired_hook_event_details(
+    pool: &PgPool,
+    cutoff: DateTime<Utc>,
+    holds: &LegalHolds,
+    limit: i64,
+) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query!(
+        r"UPDATE plugin_usage_events
+          SET prompt_preview = NULL, description = NULL, cwd = NULL, metadata = NULL
+          WHERE id IN (
+              SELECT id FROM plugin_usage_events
+              WHERE created_at < $1
+                AND user_id <> ALL($2::text[])
+                AND (session_id IS NULL OR session_id <> ALL($3::text[]))
+                AND (prompt_preview IS NOT NULL OR description IS NOT NULL
+                     OR cwd IS NOT NULL OR metadata IS NOT NULL)
+              ORDER BY created_at
+              LIMIT $4
+          )",
+        cutoff,
+        &holds.users,
+        &holds.sessions,
+        limit,
+    )
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected())
+}
+
+pub async fn delete_expired_hook_events(
+    pool: &PgPool,
+    cutoff: DateTime<Utc>,
+    holds: &LegalHolds,
+    limit: i64,
+) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query!(
+        r"DELETE FROM plugin_usage_events WHERE id IN (
+              SELECT id FROM plugin_usage_events
+              WHERE created_at < $1
+                AND user_id <> ALL($2::text[])
+                AND (session_id IS NULL OR session_id <> ALL($3::text[]))
+              ORDER BY created_at
+              LIMIT $4
+          )",
+        cutoff,
+        &holds.users,
+        &holds.sessions,
+        limit,
+    )
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected())
+}
+
+pub async fn delete_expired_transcripts(
+    pool: &PgPool,
+    cutoff: DateTime<Utc>,
+    holds: &LegalHolds,
+    limit: i64,
+) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query!(
+        r"DELETE FROM session_transcripts WHERE id IN (
+              SELECT id FROM session_transcripts
+              WHERE captured_at < $1
+                AND user_id <> ALL($2::text[])
+                AND (session_id IS NULL OR session_id <> ALL($3::text[]))
+              ORDER BY captured_at
+              LIMIT $4
+          )",
+        cutoff,
+        &holds.users,
+        &holds.sessions,
+        limit,
+    )
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected())
+}
+
+/// Delete expired governance decisions, adding them to the retired metric
+/// tallies first.
+pub async fn delete_expired_governance_decisions(
+    pool: &PgPool,
+    cutoff: DateTime<Utc>,
+    holds: &LegalHolds,
+    limit: i64,
+) -> Result<u64, sqlx::Error> {
+    let row = sqlx::query!(
+        r#"WITH deleted AS (
+               DELETE FROM governance_decisions WHERE id IN (
+                   SELECT id FROM governance_decisions
+                   WHERE created_at < $1
+                     AND user_id <> ALL($2::text[])
+                     AND (session_id IS NULL OR session_id <> ALL($3::text[]))
+                   ORDER BY created_at
+                   LIMIT $4
+               )
+               RETURNING policy, decision
+           ),
+           tallied AS (
+               INSERT INTO retired_governance_decision_tallies (policy, decision, decisions)
+               SELECT policy, decision, COUNT(*) FROM deleted
+               GROUP BY 1, 2
+               ON CONFLICT (policy, decision) DO UPDATE
+               SET decisions = retired_governance_decision_tallies.decisions + EXCLUDED.decisions
+               RETURNING 1
+           )
+           SELECT COUNT(*)::BIGINT AS "decisions!" FROM deleted"#,
+        cutoff,
+        &holds.users,
+        &holds.sessions,
+        limit,
+    )
+    .fetch_one(pool)
+    .await?;
+    Ok(u64::try_from(row.decisions).unwrap_or(0))
+}
+
+pub async fn delete_expired_link_clicks(
+    pool: &PgPool,
+    cutoff: DateTime<Utc>,
+    holds: &LegalHolds,
+    limit: i64,
+) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query!(
+        r"DELETE FROM link_clicks WHERE id IN (
+              SELECT id FROM link_clicks
+              WHERE clicked_at < $1
+                AND COALESCE(user_id, '') <> ALL($2::text[])
+                AND (session_id IS NULL OR session_id <> ALL($3::text[]))
+              ORDER BY clicked_at
+              LIMIT $4
+          )",
+        cutoff,
+        &holds.users,
+        &holds.sessions,
+        limit,
+    )
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected())
+}
+
+pub async fn delete_expired_mcp_tool_captures(
+    pool: &PgPool,
+    cutoff: DateTime<Utc>,
+    holds: &LegalHolds,
+    limit: i64,
+) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query!(
+        r"DELETE FROM mcp_tool_captures WHERE id IN (
+              SELECT id FROM mcp_tool_captures
+              WHERE ended_at < $1
+                AND user_id <> ALL($2::text[])
+                AND session_id <> ALL($3::text[])
+              ORDER BY ended_at
+              LIMIT $4
+          )",
+        cutoff,
+        &holds.users,
+        &holds.sessions,
+        limit,
+    )
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected())
+}
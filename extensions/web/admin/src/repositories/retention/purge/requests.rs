@@ -0,0 +1,136 @@
+//! Purging gateway requests: stripping their bodies, and deleting the
+//! requests with everything recorded against them.
+
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+
+use crate::metrics::LATENCY_BUCKETS_MS;
+use crate::retention::LegalHolds;
+
+/// Strip bodies from expired gateway requests: payloads and messages are
+/// deleted, tool call arguments and results blanked. Counts requests.
+pub async fn delete_expired_ai_request_bodies(
+    pool: &PgPool,
+    cutoff: DateTime<Utc>,
+    holds: &LegalHolds,
+    limit: i64,
+) -> Result<u64, sqlx::Error> {
+    let row = sqlx::query!(
+        r#"WITH doomed AS (
+               SELECT r.id FROM ai_requests r
+               WHERE r.created_at < $1
+                 AND r.user_id <> ALL($2::text[])
+                 AND COALESCE(r.session_id, '') <> ALL($3::text[])
+                 AND (EXISTS (SELECT 1 FROM ai_request_payloads p WHERE p.ai_request_id = r.id)
+                   OR EXISTS (SELECT 1 FROM ai_request_messages m WHERE m.request_id = r.id)
+                   OR EXISTS (SELECT 1 FROM ai_request_tool_calls t
+                              WHERE t.request_id = r.id
+                                AND (t.tool_input <> '' OR t.tool_result_payload IS NOT NULL)))
+               ORDER BY r.created_at
+               LIMIT $4
+           ),
+           payloads AS (
+               DELETE FROM ai_request_payloads p USING doomed d
+               WHERE p.ai_request_id = d.id RETURNING 1
+           ),
+           messages AS (
+               DELETE FROM ai_request_messages m USING doomed d
+               WHERE m.request_id = d.id RETURNING 1
+           ),
+           tool_calls AS (
+               UPDATE ai_request_tool_calls t
+               SET tool_input = '', tool_result_payload = NULL, updated_at = NOW()
+               FROM doomed d
+               WHERE t.request_id = d.id RETURNING 1
+           )
+           SELECT COUNT(*)::BIGINT AS "requests!" FROM doomed"#,
+        cutoff,
+        &holds.users,
+        &holds.sessions,
+        limit,
+    )
+    .fetch_one(pool)
+    .await?;
+    Ok(u64::try_from(row.requests).unwrap_or(0))
+}
+
+/// Delete expired gateway requests. Payloads, messages, tool calls, safety
+/// findings and cache lookups go with them by cascade.
+///
+/// The requests and findings deleted are first added to the retired metric
+/// tallies, so the `/metrics` counters do not drop.
+pub async fn delete_expired_ai_requests(
+    pool: &PgPool,
+    cutoff: DateTime<Utc>,
+    holds: &LegalHolds,
+    limit: i64,
+) -> Result<u64, sqlx::Error> {
+    // Why: every part of one statement reads the same snapshot, so the
+    // findings tally still sees the rows the cascade is removing. Pending
+    // requests are left out of the tally as they are out of the counters.
+    let row = sqlx::query!(
+        r#"WITH doomed AS (
+               SELECT id FROM ai_requests
+               WHERE created_at < $1
+                 AND user_id <> ALL($2::text[])
+                 AND COALESCE(session_id, '') <> ALL($3::text[])
+               ORDER BY created_at
+               LIMIT $4
+           ),
+           findings AS (
+               INSERT INTO retired_safety_finding_tallies (scanner, category, severity, phase, findings)
+               SELECT f.scanner, f.category, f.severity, f.phase, COUNT(*)
+               FROM ai_safety_findings f JOIN doomed d ON d.id = f.ai_request_id
+               GROUP BY 1, 2, 3, 4
+               ON CONFLICT (scanner, category, severity, phase) DO UPDATE
+               SET findings = retired_safety_finding_tallies.findings + EXCLUDED.findings
+               RETURNING 1
+           ),
+           deleted AS (
+               DELETE FROM ai_requests r USING doomed d
+               WHERE r.id = d.id
+               RETURNING r.provider, r.model, r.route_match, r.status, r.latency_ms,
+                         r.input_tokens, r.output_tokens, r.cache_read_tokens,
+                         r.cost_microdollars
+           ),
+           requests AS (
+               INSERT INTO retired_gateway_request_tallies
+                   (provider, model, route, status, bucket, requests, latency_ms,
+                    input_tokens, output_tokens, cache_read_tokens, cost_microdollars)
+               SELECT
+                   COALESCE(provider, ''),
+                   COALESCE(model, ''),
+                   COALESCE(route_match, ''),
+                   status::text,
+                   COALESCE(width_bucket(latency_ms - 1, $5::int4[]), -1),
+                   COUNT(*),
+                   COALESCE(SUM(latency_ms), 0),
+                   COALESCE(SUM(input_tokens), 0),
+                   COALESCE(SUM(output_tokens), 0),
+                   COALESCE(SUM(cache_read_tokens), 0),
+                   COALESCE(SUM(cost_microdollars), 0)
+               FROM deleted
+               WHERE status <> 'pending'
+               GROUP BY 1, 2, 3, 4, 5
+               ON CONFLICT (provider, model, route, status, bucket) DO UPDATE SET
+                   requests = retired_gateway_request_tallies.requests + EXCLUDED.requests,
+                   latency_ms = retired_gateway_request_tallies.latency_ms + EXCLUDED.latency_ms,
+                   input_tokens = retired_gateway_request_tallies.input_tokens + EXCLUDED.input_tokens,
+                   output_tokens = retired_gateway_request_tallies.output_tokens + EXCLUDED.output_tokens,
+                   cache_read_tokens = retired_gateway_request_tallies.cache_read_tokens
+                       + EXCLUDED.cache_read_tokens,
+                   cost_microdollars = retired_gateway_request_tallies.cost_microdollars
+                       + EXCLUDED.cost_microdollars
+               RETURNING 1
+           )
+           SELECT COUNT(*)::BIGINT AS "requests!" FROM deleted"#,
+        cutoff,
+        &holds.users,
+        &holds.sessions,
+        limit,
+        &LATENCY_BUCKETS_MS[..],
+    )
+    .fetch_one(pool)
+    .await?;
+    Ok(u64::try_from(row.requests).unwrap_or(0))
+}
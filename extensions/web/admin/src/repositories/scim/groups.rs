@@ -0,0 +1,189 @@
+//! identity provider groups and their membership.
+//!
+//! Only SCIM-managed users can be members: a member reference to an account
+//! with no `scim_users` row is dropped, so pushing a group can never rewrite
+//! the roles of a user the identity provider does not own.
+
+use sqlx::PgPool;
+use systemprompt::identifiers::UserId;
+
+use crate::types::scim::{ScimGroupRow, ScimMemberChange, ScimMemberRow};
+
+pub async fn find_scim_group(pool: &PgPool, id: &str) -> Result<Option<ScimGroupRow>, sqlx::Error> {
+    sqlx::query_as!(
+        ScimGroupRow,
+        r"SELECT id, display_name, external_id, created_at, updated_at
+          FROM scim_groups WHERE id = $1",
+        id,
+    )
+    .fetch_optional(pool)
+    .await
+}
+
+pub async fn list_scim_groups(
+    pool: &PgPool,
+    display_name: Option<&str>,
+    external_id: Option<&str>,
+    offset: i64,
+    limit: i64,
+) -> Result<Vec<ScimGroupRow>, sqlx::Error> {
+    sqlx::query_as!(
+        ScimGroupRow,
+        r"SELECT id, display_name, external_id, created_at, updated_at
+          FROM scim_groups
+          WHERE ($1::TEXT IS NULL OR display_name = $1)
+            AND ($2::TEXT IS NULL OR external_id = $2)
+          ORDER BY created_at, id
+          OFFSET $3 LIMIT $4",
+        display_name,
+        external_id,
+        offset,
+        limit,
+    )
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn get_scim_group_count(
+    pool: &PgPool,
+    display_name: Option<&str>,
+    external_id: Option<&str>,
+) -> Result<i64, sqlx::Error> {
+    sqlx::query_scalar!(
+        r#"SELECT COUNT(*) AS "count!" FROM scim_groups
+           WHERE ($1::TEXT IS NULL OR display_name = $1)
+             AND ($2::TEXT IS NULL OR external_id = $2)"#,
+        display_name,
+        external_id,
+    )
+    .fetch_one(pool)
+    .await
+}
+
+pub async fn create_scim_group(
+    pool: &PgPool,
+    id: &str,
+    display_name: &str,
+    external_id: Option<&str>,
+) -> Result<ScimGroupRow, sqlx::Error> {
+    sqlx::query_as!(
+        ScimGroupRow,
+        r"INSERT INTO scim_groups (id, display_name, external_id)
+          VALUES ($1, $2, $3)
+          RETURNING id, display_name, external_id, created_at, updated_at",
+        id,
+        display_name,
+        external_id,
+    )
+    .fetch_one(pool)
+    .await
+}
+
+pub async fn update_scim_group(
+    pool: &PgPool,
+    id: &str,
+    display_name: Option<&str>,
+    external_id: Option<&str>,
+) -> Result<Option<ScimGroupRow>, sqlx::Error> {
+    sqlx::query_as!(
+        ScimGroupRow,
+        r"UPDATE scim_groups SET
+              display_name = COALESCE($2, display_name),
+              external_id = COALESCE($3, external_id),
+              updated_at = NOW()
+          WHERE id = $1
+          RETURNING id, display_name, external_id, created_at, updated_at",
+        id,
+        display_name,
+        external_id,
+    )
+    .fetch_optional(pool)
+    .await
+}
+
+pub async fn delete_scim_group(pool: &PgPool, id: &str) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query!("DELETE FROM scim_groups WHERE id = $1", id)
+        .execute(pool)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn list_group_members(
+    pool: &PgPool,
+    group_id: &str,
+) -> Result<Vec<ScimMemberRow>, sqlx::Error> {
+    sqlx::query_as!(
+        ScimMemberRow,
+        r#"SELECT u.id AS "user_id!", u.email AS "email!"
+           FROM scim_group_members m JOIN users u ON u.id = m.user_id
+           WHERE m.group_id = $1
+           ORDER BY u.email"#,
+        group_id,
+    )
+    .fetch_all(pool)
+    .await
+}
+
+/// Apply membership changes in request order inside one transaction and
+/// return every user whose membership may have moved, so the caller can
+/// recompute their roles.
+pub async fn apply_member_changes(
+    pool: &PgPool,
+    group_id: &str,
+    changes: &[ScimMemberChange],
+) -> Result<Vec<UserId>, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    let mut touched: Vec<String> = Vec::new();
+    for change in changes {
+        match change {
+            ScimMemberChange::Add(ids) => {
+                sqlx::query!(
+                    r"INSERT INTO scim_group_members (group_id, user_id)
+                      SELECT $1, s.user_id FROM scim_users s WHERE s.user_id = ANY($2)
+                      ON CONFLICT DO NOTHING",
+                    group_id,
+                    ids,
+                )
+                .execute(&mut *tx)
+                .await?;
+                touched.extend(ids.iter().cloned());
+            },
+            ScimMemberChange::Remove(ids) => {
+                sqlx::query!(
+                    "DELETE FROM scim_group_members WHERE group_id = $1 AND user_id = ANY($2)",
+                    group_id,
+                    ids,
+                )
+                .execute(&mut *tx)
+                .await?;
+                touched.extend(ids.iter().cloned());
+            },
+            ScimMemberChange::Replace(ids) => {
+                let removed = sqlx::query_scalar!(
+                    r"DELETE FROM scim_group_members
+                      WHERE group_id = $1 AND NOT (user_id = ANY($2))
+                      RETURNING user_id",
+                    group_id,
+                    ids,
+                )
+                .fetch_all(&mut *tx)
+                .await?;
+                sqlx::query!(
+                    r"INSERT INTO scim_group_members (group_id, user_id)
+                      SELECT $1, s.user_id FROM scim_users s WHERE s.user_id = ANY($2)
+                      ON CONFLICT DO NOTHING",
+                    group_id,
+                    ids,
+                )
+                .execute(&mut *tx)
+                .await?;
+                touched.extend(removed);
+                touched.extend(ids.iter().cloned());
+            },
+        }
+    }
+    tx.commit().await?;
+    touched.sort();
+    touched.dedup();
+    Ok(touched.into_iter().map(UserId::new).collect())
+}
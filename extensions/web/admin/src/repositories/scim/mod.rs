@@ -0,0 +1,5 @@
+//! Persistence for SCIM 2.0 provisioning: SCIM-managed users and identity
+//! provider groups.
+
+pub mod groups;
+pub mod users;
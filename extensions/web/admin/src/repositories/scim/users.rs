@@ -0,0 +1,236 @@
+//! SCIM-managed users: lookup, creation, attribute updates, the derived
+//! role/department write, and deactivation.
+
+use sqlx::PgPool;
+use systemprompt::identifiers::UserId;
+
+use crate::types::scim::{ScimMembershipRow, ScimUserRow};
+
+/// Attributes an identity provider controls on a managed user.
+#[derive(Debug, Clone)]
+pub struct ScimUserWrite<'a> {
+    pub email: &'a str,
+    pub display_name: Option<&'a str>,
+    pub external_id: Option<&'a str>,
+    pub active: bool,
+}
+
+pub async fn find_scim_user(
+    pool: &PgPool,
+    user_id: &UserId,
+) -> Result<Option<ScimUserRow>, sqlx::Error> {
+    sqlx::query_as!(
+        ScimUserRow,
+        r#"SELECT u.id AS "user_id!", u.email AS "email!", u.display_name, u.status,
+                  s.external_id, s.created_at, GREATEST(s.updated_at, u.updated_at) AS "updated_at!"
+           FROM scim_users s JOIN users u ON u.id = s.user_id
+           WHERE s.user_id = $1"#,
+        user_id.as_str(),
+    )
+    .fetch_optional(pool)
+    .await
+}
+
+/// Page through managed users, optionally narrowed to one `userName` (email)
+/// or `externalId`.
+pub async fn list_scim_users(
+    pool: &PgPool,
+    email: Option<&str>,
+    external_id: Option<&str>,
+    offset: i64,
+    limit: i64,
+) -> Result<Vec<ScimUserRow>, sqlx::Error> {
+    sqlx::query_as!(
+        ScimUserRow,
+        r#"SELECT u.id AS "user_id!", u.email AS "email!", u.display_name, u.status,
+                  s.external_id, s.created_at, GREATEST(s.updated_at, u.updated_at) AS "updated_at!"
+           FROM scim_users s JOIN users u ON u.id = s.user_id
+           WHERE ($1::TEXT IS NULL OR u.email = $1)
+             AND ($2::TEXT IS NULL OR s.external_id = $2)
+           ORDER BY s.created_at, u.id
+           OFFSET $3 LIMIT $4"#,
+        email,
+        external_id,
+        offset,
+        limit,
+    )
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn get_scim_user_count(
+    pool: &PgPool,
+    email: Option<&str>,
+    external_id: Option<&str>,
+) -> Result<i64, sqlx::Error> {
+    sqlx::query_scalar!(
+        r#"SELECT COUNT(*) AS "count!"
+           FROM scim_users s JOIN users u ON u.id = s.user_id
+           WHERE ($1::TEXT IS NULL OR u.email = $1)
+             AND ($2::TEXT IS NULL OR s.external_id = $2)"#,
+        email,
+        external_id,
+    )
+    .fetch_one(pool)
+    .await
+}
+
+/// The existing account for `email`, and whether the identity provider already
+/// manages it.
+pub async fn find_user_by_email(
+    pool: &PgPool,
+    email: &str,
+) -> Result<Option<(UserId, bool)>, sqlx::Error> {
+    let row = sqlx::query!(
+        r#"SELECT u.id, (s.user_id IS NOT NULL) AS "managed!"
+           FROM users u LEFT JOIN scim_users s ON s.user_id = u.id
+           WHERE u.email = $1"#,
+        email,
+    )
+    .fetch_optional(pool)
+    .await?;
+    Ok(row.map(|r| (UserId::new(r.id), r.managed)))
+}
+
+/// Create the account and mark it SCIM-managed. An existing account with the
+/// same email is a unique violation, never adopted. Roles are left to
+/// [`set_scim_user_assignment`].
+pub async fn create_scim_user(
+    pool: &PgPool,
+    user_id: &UserId,
+    write: &ScimUserWrite<'_>,
+) -> Result<UserId, sqlx::Error> {
+    let status = if write.active { "active" } else { "inactive" };
+    let mut tx = pool.begin().await?;
+    let id = sqlx::query_scalar!(
+        r#"INSERT INTO users (id, name, email, display_name, status, email_verified)
+           VALUES ($1, $2, $2, $3, $4, true)
+           RETURNING id"#,
+        user_id.as_str(),
+        write.email,
+        write.display_name,
+        status,
+    )
+    .fetch_one(&mut *tx)
+    .await?;
+    sqlx::query!(
+        "INSERT INTO scim_users (user_id, external_id) VALUES ($1, $2)",
+        id,
+        write.external_id,
+    )
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+    Ok(UserId::new(id))
+}
+
+/// Overwrite the attributes the identity provider controls. Status only
+/// moves between `active` and `inactive`; an account an operator suspended
+/// stays suspended until they lift it.
+pub async fn update_scim_user(
+    pool: &PgPool,
+    user_id: &UserId,
+    write: &ScimUserWrite<'_>,
+) -> Result<bool, sqlx::Error> {
+    let status = if write.active { "active" } else { "inactive" };
+    let mut tx = pool.begin().await?;
+    let updated = sqlx::query!(
+        r#"UPDATE users SET
+               email = $2,
+               name = $2,
+               display_name = COALESCE($3, display_name),
+               status = CASE WHEN status IN ('active', 'inactive') THEN $4 ELSE status END,
+               updated_at = NOW()
+           WHERE id = $1"#,
+        user_id.as_str(),
+        write.email,
+        write.display_name,
+        status,
+    )
+    .execute(&mut *tx)
+    .await?
+    .rows_affected()
+        > 0;
+    sqlx::query!(
+        "UPDATE scim_users SET external_id = $2, updated_at = NOW() WHERE user_id = $1",
+        user_id.as_str(),
+        write.external_id,
+    )
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+    Ok(updated)
+}
+
+/// Set `status = 'inactive'` and revoke every live access token, in one
+/// transaction so a deprovisioned user cannot keep calling the gateway.
+pub async fn deactivate_scim_user(pool: &PgPool, user_id: &UserId) -> Result<bool, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    let updated = sqlx::query!(
+        "UPDATE users SET status = 'inactive', updated_at = NOW() WHERE id = $1",
+        user_id.as_str(),
+    )
+    .execute(&mut *tx)
+    .await?
+    .rows_affected()
+        > 0;
+    sqlx::query!(
+        r"UPDATE user_api_keys SET revoked_at = CURRENT_TIMESTAMP
+          WHERE user_id = $1 AND revoked_at IS NULL",
+        user_id.as_str(),
+    )
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+    Ok(updated)
+}
+
+/// Write the roles and department derived from group membership.
+pub async fn set_scim_user_assignment(
+    pool: &PgPool,
+    user_id: &UserId,
+    roles: &[String],
+    department: &str,
+) -> Result<(), sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    sqlx::query!(
+        "UPDATE users SET roles = $2, updated_at = NOW() WHERE id = $1",
+        user_id.as_str(),
+        roles,
+    )
+    .execute(&mut *tx)
+    .await?;
+    sqlx::query!(
+        r"INSERT INTO departments (name) VALUES ($1) ON CONFLICT (name) DO NOTHING",
+        department,
+    )
+    .execute(&mut *tx)
+    .await?;
+    sqlx::query!(
+        r"INSERT INTO user_profile_ext (user_id, department)
+          VALUES ($1, $2)
+          ON CONFLICT (user_id) DO UPDATE SET department = EXCLUDED.department",
+        user_id.as_str(),
+        department,
+    )
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+    Ok(())
+}
+
+pub async fn list_user_memberships(
+    pool: &PgPool,
+    user_id: &UserId,
+) -> Result<Vec<ScimMembershipRow>, sqlx::Error> {
+    sqlx::query_as!(
+        ScimMembershipRow,
+        r#"SELECT g.id AS group_id, g.display_name
+           FROM scim_group_members m JOIN scim_groups g ON g.id = m.group_id
+           WHERE m.user_id = $1
+           ORDER BY g.display_name"#,
+        user_id.as_str(),
+    )
+    .fetch_all(pool)
+    .await
+}
@@ -0,0 +1,123 @@
+//! Full-text search across transcript turns, gateway request messages and
+//! session analysis titles.
+//!
+//! The query is `websearch_to_tsquery` syntax: `"quoted phrases"`, `or`, and
+//! `-term` to exclude. Transcripts are picked by the whole-transcript index
+//! and then narrowed to the turns that match on their own, so an excluded
+//! term rules out a transcript only when it appears anywhere in it. Only the
+//! first 100,000 characters of a message, transcript or turn are searched; see
+//! `schema/23_search.sql`. Headlines are cut from the same prefix, since
+//! `ts_headline` re-parses the whole text it is given for every hit.
+//!
+//! Headlines mark matches with [`crate::search::HIT_START`] and
+//! [`crate::search::HIT_END`]; they are raw text and must go through
+//! [`crate::search::snippet_from_headline`] before display.
+
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use systemprompt::identifiers::{AiRequestId, SessionId, UserId};
+
+use crate::search::SearchSource;
+
+#[derive(Debug, Clone, Default)]
+pub struct SearchFilter {
+    pub query: String,
+    pub source: Option<SearchSource>,
+    pub user_id: Option<UserId>,
+    pub model: Option<String>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    /// Only sessions with at least one governance decision of this kind.
+    pub outcome: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SearchHit {
+    pub source: String,
+    pub session_id: Option<SessionId>,
+    pub ai_request_id: Option<AiRequestId>,
+    pub user_id: UserId,
+    pub model: Option<String>,
+    pub ordinal: Option<i32>,
+    pub role: Option<String>,
+    pub occurred_at: DateTime<Utc>,
+    pub headline: String,
+}
+
+pub async fn list_search_hits(
+    pool: &PgPool,
+    filter: &SearchFilter,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<SearchHit>, sqlx::Error> {
+    sqlx::query_as!(
+        SearchHit,
+        r#"WITH opts AS (
+               SELECT websearch_to_tsquery('simple', $1) AS query,
+                      'StartSel=' || chr(2) || ', StopSel=' || chr(3)
+                      || ', MaxWords=30, MinWords=10, ShortWord=0, MaxFragments=2, FragmentDelimiter=" … "'
+                      AS value
+           ),
+           hits AS (
+               SELECT 'transcript' AS source, t.session_id, NULL::text AS ai_request_id,
+                      t.user_id, t.model, (turn.ord - 1)::int AS ordinal, turn.role,
+                      t.captured_at AS occurred_at, turn.body AS body
+               FROM session_transcripts t
+               CROSS JOIN LATERAL (
+                   SELECT e.ord, e.entry->>'role' AS role,
+                          CASE jsonb_typeof(e.entry->'content')
+                              WHEN 'string' THEN e.entry->>'content'
+                              WHEN 'array' THEN (SELECT string_agg(b->>'text', E'\n')
+                                                 FROM jsonb_array_elements(e.entry->'content') b)
+                              ELSE e.entry->>'text'
+                          END AS body
+                   FROM jsonb_array_elements(t.transcript) WITH ORDINALITY e(entry, ord)
+               ) turn
+               WHERE search_tsvector(transcript_search_text(t.transcript))
+                     @@ websearch_to_tsquery('simple', $1)
+                 AND search_tsvector(turn.body) @@ websearch_to_tsquery('simple', $1)
+               UNION ALL
+               SELECT 'request', r.session_id, r.id, r.user_id, r.model, m.sequence_number, m.role,
+                      r.created_at, m.content
+               FROM ai_request_messages m
+               JOIN ai_requests r ON r.id = m.request_id
+               WHERE search_tsvector(m.content) @@ websearch_to_tsquery('simple', $1)
+               UNION ALL
+               SELECT 'analysis', a.session_id, NULL, a.user_id, NULL, NULL, NULL,
+                      a.created_at, a.title
+               FROM session_analyses a
+               WHERE to_tsvector('simple', a.title) @@ websearch_to_tsquery('simple', $1)
+           )
+           SELECT h.source AS "source!",
+                  h.session_id AS "session_id: SessionId",
+                  h.ai_request_id AS "ai_request_id: AiRequestId",
+                  h.user_id AS "user_id!: UserId",
+                  h.model, h.ordinal, h.role,
+                  h.occurred_at AS "occurred_at!",
+                  ts_headline('simple', left(h.body, 100000), opts.query, opts.value)
+                      AS "headline!"
+           FROM hits h
+           CROSS JOIN opts
+           WHERE ($2::text IS NULL OR h.source = $2)
+             AND ($3::text IS NULL OR h.user_id = $3)
+             AND ($4::text IS NULL OR h.model = $4)
+             AND ($5::timestamptz IS NULL OR h.occurred_at >= $5)
+             AND ($6::timestamptz IS NULL OR h.occurred_at < $6)
+             AND ($7::text IS NULL OR EXISTS (
+                 SELECT 1 FROM governance_decisions d
+                 WHERE d.session_id = h.session_id AND d.decision = $7))
+           ORDER BY h.occurred_at DESC, h.session_id, h.ordinal
+           LIMIT $8 OFFSET $9"#,
+        filter.query,
+        filter.source.map(SearchSource::as_str),
+        filter.user_id.as_ref().map(UserId::as_str),
+        filter.model,
+        filter.from,
+        filter.to,
+        filter.outcome,
+        limit,
+        offset,
+    )
+    .fetch_all(pool)
+    .await
+}
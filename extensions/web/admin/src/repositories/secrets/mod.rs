@@ -2,9 +2,17 @@
 //!
 //! Secrets are sealed with a per-user data encryption key, which is itself
 //! sealed under the instance master key. Plaintext never reaches the database.
+//! Master keys are versioned by fingerprint (`secret_keyring`), and each DEK
+//! records the key that wrapped it so a rotation can move it to the next. The
+//! master key itself may live in memory, a KMS, or an HSM
+//! ([`crate::key_provider`]). Each secret also carries an owner, an expiry and
+//! its last resolution, which drive the alerts in `secret_lifecycle`.
 
 pub mod secret_audit;
 pub mod secret_crypto;
+pub mod secret_keyring;
 pub mod secret_keys;
+pub mod secret_lifecycle;
+pub mod secret_master_keys;
 pub mod secret_migration;
 pub mod secret_resolve;
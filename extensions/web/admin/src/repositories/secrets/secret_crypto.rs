@@ -1,4 +1,4 @@
-//! AES-GCM primitives and master key loading for secret storage.
+//! AES-GCM primitives and master key parsing for secret storage.
 
 use chacha20poly1305::aead::rand_core::RngCore;
 use chacha20poly1305::aead::{Aead, KeyInit, OsRng};
@@ -12,12 +12,22 @@ pub enum SecretCryptoError {
     DecryptionFailed(String),
     #[error("Master key not configured")]
     MasterKeyMissing,
+    #[error("Master key {0} is not configured or has been retired")]
+    MasterKeyUnknown(String),
+    #[error("Key provider error: {0}")]
+    KeyProvider(String),
     #[error("Invalid key material")]
     InvalidKeyMaterial,
     #[error("Database error: {0}")]
     Database(String),
 }
 
+impl From<sqlx::Error> for SecretCryptoError {
+    fn from(e: sqlx::Error) -> Self {
+        Self::Database(e.to_string())
+    }
+}
+
 #[must_use]
 pub fn generate_dek() -> [u8; 32] {
     let mut key = [0u8; 32];
@@ -56,18 +66,18 @@ pub fn decrypt(
         .map_err(|e| SecretCryptoError::DecryptionFailed(e.to_string()))
 }
 
-pub fn load_master_key() -> Result<[u8; 32], SecretCryptoError> {
-    // Why: both `.ok()` carve-outs are missing-is-normal; an exhausted chain
-    // becomes `MasterKeyMissing` rather than surfacing either lookup error.
-    let hex_key = std::env::var("ENCRYPTION_MASTER_KEY")
-        .ok()
-        .or_else(|| {
-            systemprompt::config::SecretsBootstrap::get()
-                .ok()
-                .and_then(|s| s.get("encryption_master_key").cloned())
-        })
-        .ok_or(SecretCryptoError::MasterKeyMissing)?;
+// Why: both `.ok()` carve-outs are missing-is-normal; an exhausted chain is
+// `None` and the caller decides whether that is an error. The secret has the
+// setting's name in lower case.
+pub(crate) fn configured_value(name: &str) -> Option<String> {
+    std::env::var(name).ok().or_else(|| {
+        systemprompt::config::SecretsBootstrap::get()
+            .ok()
+            .and_then(|s| s.get(&name.to_ascii_lowercase()).cloned())
+    })
+}
 
+pub(crate) fn parse_master_key(hex_key: &str) -> Result<[u8; 32], SecretCryptoError> {
     let bytes = hex::decode(hex_key.trim()).map_err(|e| {
         tracing::warn!(error = %e, "Master key is not valid hex");
         SecretCryptoError::InvalidKeyMaterial
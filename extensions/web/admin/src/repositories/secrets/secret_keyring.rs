@@ -0,0 +1,144 @@
+//! The instance's master keys: the current one, which wraps every new data
+//! encryption key, and the ones it replaced, held only while the DEKs they
+//! wrapped are re-wrapped.
+//!
+//! Each key sits behind a [`KeyProvider`], which may hold it in memory or
+//! reach it in a KMS or HSM; see [`key_provider`] for how the current
+//! provider is chosen. A local key is identified by a fingerprint of its
+//! material, not by a configured version number, so the id a DEK records can
+//! never name the wrong key. DEKs wrapped before keys were versioned record no
+//! id and are opened by trying each key in turn.
+//!
+//! [`key_provider`]: crate::key_provider
+
+use std::sync::Arc;
+
+use sha2::{Digest, Sha256};
+
+use crate::key_provider::{self, KeyProvider, LocalKey, WrappedDek};
+use crate::repositories::secrets::secret_crypto::{self, SecretCryptoError};
+
+const KEY_ID_CONTEXT: &[u8] = b"systemprompt master key id v1";
+
+/// The id recorded against every DEK a local key wraps: a truncated hash of
+/// the key, so it identifies the key without revealing anything about it.
+#[must_use]
+pub fn master_key_id(key: &[u8; 32]) -> String {
+    let digest = Sha256::new()
+        .chain_update(KEY_ID_CONTEXT)
+        .chain_update(key)
+        .finalize();
+    format!("mk-{}", hex::encode(&digest[..8]))
+}
+
+#[derive(Debug, Clone)]
+pub struct MasterKeyring {
+    current: Arc<dyn KeyProvider>,
+    previous: Vec<Arc<dyn KeyProvider>>,
+}
+
+impl MasterKeyring {
+    /// A keyring of local keys, wrapping under `current`.
+    #[must_use]
+    pub fn new(current: [u8; 32], previous: impl IntoIterator<Item = [u8; 32]>) -> Self {
+        Self::from_providers(
+            Arc::new(LocalKey::new(current)),
+            previous
+                .into_iter()
+                .map(|key| -> Arc<dyn KeyProvider> { Arc::new(LocalKey::new(key)) }),
+        )
+    }
+
+    /// A keyring wrapping under `current`. Previous keys with its id, or with
+    /// one already seen, are dropped.
+    #[must_use]
+    pub fn from_providers(
+        current: Arc<dyn KeyProvider>,
+        previous: impl IntoIterator<Item = Arc<dyn KeyProvider>>,
+    ) -> Self {
+        let mut keys: Vec<Arc<dyn KeyProvider>> = Vec::new();
+        for key in previous {
+            if key.key_id() != current.key_id() && !keys.iter().any(|k| k.key_id() == key.key_id())
+            {
+                keys.push(key);
+            }
+        }
+        Self {
+            current,
+            previous: keys,
+        }
+    }
+
+    #[must_use]
+    pub fn current_id(&self) -> &str {
+        self.current.key_id()
+    }
+
+    pub fn previous_ids(&self) -> impl Iterator<Item = &str> {
+        self.previous.iter().map(|k| k.key_id())
+    }
+
+    /// The keyring without the previous keys named in `retired`. The current
+    /// key is always kept.
+    #[must_use]
+    pub fn without(mut self, retired: &[String]) -> Self {
+        self.previous
+            .retain(|k| !retired.iter().any(|id| id == k.key_id()));
+        self
+    }
+
+    pub async fn wrap_dek(&self, dek: &[u8; 32]) -> Result<WrappedDek, SecretCryptoError> {
+        self.current.wrap(dek).await
+    }
+
+    /// Open a DEK wrapped under the key named `key_id`, or, when it is `None`,
+    /// under whichever configured key opens it.
+    pub async fn unwrap_dek(
+        &self,
+        key_id: Option<&str>,
+        nonce: &[u8],
+        ciphertext: &[u8],
+    ) -> Result<[u8; 32], SecretCryptoError> {
+        let plaintext = match key_id {
+            Some(id) => {
+                let key = self
+                    .keys()
+                    .find(|k| k.key_id() == id)
+                    .ok_or_else(|| SecretCryptoError::MasterKeyUnknown(id.to_owned()))?;
+                key.unwrap(nonce, ciphertext).await?
+            },
+            None => self.unwrap_with_any(nonce, ciphertext).await?,
+        };
+        plaintext.try_into().map_err(|v: Vec<u8>| {
+            tracing::warn!(len = v.len(), "Decrypted DEK is not 32 bytes");
+            SecretCryptoError::InvalidKeyMaterial
+        })
+    }
+
+    async fn unwrap_with_any(
+        &self,
+        nonce: &[u8],
+        ciphertext: &[u8],
+    ) -> Result<Vec<u8>, SecretCryptoError> {
+        for key in self.keys() {
+            if let Ok(plaintext) = key.unwrap(nonce, ciphertext).await {
+                return Ok(plaintext);
+            }
+        }
+        Err(SecretCryptoError::DecryptionFailed(
+            "no configured master key opens this DEK".to_owned(),
+        ))
+    }
+
+    fn keys(&self) -> impl Iterator<Item = &Arc<dyn KeyProvider>> {
+        std::iter::once(&self.current).chain(&self.previous)
+    }
+}
+
+pub fn load_master_keyring() -> Result<MasterKeyring, SecretCryptoError> {
+    let providers = key_provider::configured_providers(&secret_crypto::configured_value)?;
+    Ok(MasterKeyring::from_providers(
+        providers.current,
+        providers.previous,
+    ))
+}
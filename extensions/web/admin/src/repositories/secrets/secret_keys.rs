@@ -1,17 +1,20 @@
-//! Per-user data encryption key issue and rotation.
+//! Per-user data encryption key issue and rotation, and the batch re-wrap
+//! that moves DEKs onto a new master key.
 
 use sqlx::PgPool;
 use systemprompt::identifiers::UserId;
 
 use crate::repositories::secrets::secret_crypto::{self, SecretCryptoError};
+use crate::repositories::secrets::secret_keyring::MasterKeyring;
 
 pub async fn get_or_create_user_dek(
     pool: &PgPool,
     user_id: &UserId,
-    master_key: &[u8; 32],
+    keyring: &MasterKeyring,
 ) -> Result<[u8; 32], SecretCryptoError> {
     let row = sqlx::query!(
-        "SELECT encrypted_dek, dek_nonce FROM user_encryption_keys WHERE user_id = $1",
+        "SELECT encrypted_dek, dek_nonce, master_key_id FROM user_encryption_keys \
+         WHERE user_id = $1",
         user_id.as_str(),
     )
     .fetch_optional(pool)
@@ -19,30 +22,27 @@ pub async fn get_or_create_user_dek(
     .map_err(|e| SecretCryptoError::Database(e.to_string()))?;
 
     if let Some(row) = row {
-        let nonce: [u8; 12] = row.dek_nonce.try_into().map_err(|v: Vec<u8>| {
-            tracing::warn!(len = v.len(), "Stored DEK nonce is not 12 bytes");
-            SecretCryptoError::InvalidKeyMaterial
-        })?;
-        let plaintext = secret_crypto::decrypt(master_key, &nonce, &row.encrypted_dek)?;
-        let dek: [u8; 32] = plaintext.try_into().map_err(|v: Vec<u8>| {
-            tracing::warn!(len = v.len(), "Decrypted DEK is not 32 bytes");
-            SecretCryptoError::InvalidKeyMaterial
-        })?;
-        return Ok(dek);
+        return keyring
+            .unwrap_dek(
+                row.master_key_id.as_deref(),
+                &row.dek_nonce,
+                &row.encrypted_dek,
+            )
+            .await;
     }
 
     let dek = secret_crypto::generate_dek();
-    let nonce = secret_crypto::generate_nonce();
-    let encrypted_dek = secret_crypto::encrypt(master_key, &nonce, &dek)?;
+    let wrapped = keyring.wrap_dek(&dek).await?;
     let id = uuid::Uuid::new_v4().to_string();
 
     sqlx::query!(
-        "INSERT INTO user_encryption_keys (id, user_id, encrypted_dek, dek_nonce) \
-         VALUES ($1, $2, $3, $4)",
+        "INSERT INTO user_encryption_keys (id, user_id, encrypted_dek, dek_nonce, master_key_id) \
+         VALUES ($1, $2, $3, $4, $5)",
         id,
         user_id.as_str(),
-        encrypted_dek.as_slice(),
-        nonce.as_slice(),
+        wrapped.ciphertext.as_slice(),
+        wrapped.nonce.as_slice(),
+        wrapped.key_id,
     )
     .execute(pool)
     .await
@@ -56,13 +56,12 @@ pub async fn get_or_create_user_dek(
 pub async fn rotate_user_dek(
     pool: &PgPool,
     user_id: &UserId,
-    master_key: &[u8; 32],
+    keyring: &MasterKeyring,
 ) -> Result<(), SecretCryptoError> {
-    let old_dek = get_or_create_user_dek(pool, user_id, master_key).await?;
+    let old_dek = get_or_create_user_dek(pool, user_id, keyring).await?;
 
     let new_dek = secret_crypto::generate_dek();
-    let new_dek_nonce = secret_crypto::generate_nonce();
-    let encrypted_new_dek = secret_crypto::encrypt(master_key, &new_dek_nonce, &new_dek)?;
+    let wrapped = keyring.wrap_dek(&new_dek).await?;
 
     let mut tx = pool
         .begin()
@@ -107,10 +106,11 @@ pub async fn rotate_user_dek(
     }
 
     sqlx::query!(
-        "UPDATE user_encryption_keys SET encrypted_dek = $1, dek_nonce = $2, \
-         key_version = key_version + 1, rotated_at = NOW() WHERE user_id = $3",
-        encrypted_new_dek.as_slice(),
-        new_dek_nonce.as_slice(),
+        "UPDATE user_encryption_keys SET encrypted_dek = $1, dek_nonce = $2, master_key_id = $3, \
+         key_version = key_version + 1, rotated_at = NOW() WHERE user_id = $4",
+        wrapped.ciphertext.as_slice(),
+        wrapped.nonce.as_slice(),
+        wrapped.key_id,
         user_id.as_str(),
     )
     .execute(&mut *tx)
@@ -129,3 +129,96 @@ pub async fn rotate_user_dek(
 
     Ok(())
 }
+
+#[derive(Debug, Default)]
+pub struct DekRewrapBatch {
+    /// The highest DEK id the batch looked at; `None` when there was nothing
+    /// left past the cursor.
+    pub last_id: Option<String>,
+    pub rewrapped: u64,
+    /// DEKs rotated or re-wrapped by someone else while this batch held them.
+    pub skipped: u64,
+    pub failures: Vec<DekRewrapFailure>,
+}
+
+#[derive(Debug)]
+pub struct DekRewrapFailure {
+    /// The `user_encryption_keys` row, not its user: failures are recorded in
+    /// the audit trail, which should not carry user ids.
+    pub dek_id: String,
+    pub error: String,
+}
+
+/// Re-wrap up to `limit` DEKs after `after_id` that are not on the current
+/// master key.
+///
+/// DEKs are taken in id order. The DEKs themselves do not change, so nothing
+/// sealed under them is touched. Unwrapping and wrapping may be KMS or HSM
+/// calls, so no row is locked while they run: each DEK is written back only if
+/// its `key_version` and master key are still the ones read, and a DEK that
+/// changed in between is skipped and picked up by a later run.
+pub async fn rewrap_dek_batch(
+    pool: &PgPool,
+    keyring: &MasterKeyring,
+    after_id: &str,
+    limit: i64,
+) -> Result<DekRewrapBatch, SecretCryptoError> {
+    let rows = sqlx::query!(
+        "SELECT id, encrypted_dek, dek_nonce, master_key_id, key_version \
+         FROM user_encryption_keys \
+         WHERE master_key_id IS DISTINCT FROM $1 AND id > $2 \
+         ORDER BY id LIMIT $3",
+        keyring.current_id(),
+        after_id,
+        limit,
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut batch = DekRewrapBatch {
+        last_id: rows.last().map(|row| row.id.clone()),
+        ..DekRewrapBatch::default()
+    };
+    for row in rows {
+        let rewrapped = match keyring
+            .unwrap_dek(
+                row.master_key_id.as_deref(),
+                &row.dek_nonce,
+                &row.encrypted_dek,
+            )
+            .await
+        {
+            Ok(dek) => keyring.wrap_dek(&dek).await,
+            Err(e) => Err(e),
+        };
+        let wrapped = match rewrapped {
+            Ok(wrapped) => wrapped,
+            Err(e) => {
+                batch.failures.push(DekRewrapFailure {
+                    dek_id: row.id,
+                    error: e.to_string(),
+                });
+                continue;
+            },
+        };
+        let swapped = sqlx::query!(
+            "UPDATE user_encryption_keys SET encrypted_dek = $1, dek_nonce = $2, \
+             master_key_id = $3 \
+             WHERE id = $4 AND key_version = $5 AND master_key_id IS NOT DISTINCT FROM $6",
+            wrapped.ciphertext.as_slice(),
+            wrapped.nonce.as_slice(),
+            wrapped.key_id,
+            row.id,
+            row.key_version,
+            row.master_key_id,
+        )
+        .execute(pool)
+        .await?;
+        if swapped.rows_affected() == 1 {
+            batch.rewrapped += 1;
+        } else {
+            batch.skipped += 1;
+        }
+    }
+    Ok(batch)
+}
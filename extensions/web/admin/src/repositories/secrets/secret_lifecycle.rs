@@ -0,0 +1,218 @@
+//! Secret lifecycle metadata and the alerts raised from it.
+
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use systemprompt::identifiers::UserId;
+
+/// What a secret alert is about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SecretAlertKind {
+    Expiring,
+    Expired,
+    Unused,
+    UnusualPlugin,
+    UnusualRate,
+}
+
+impl SecretAlertKind {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Expiring => "expiring",
+            Self::Expired => "expired",
+            Self::Unused => "unused",
+            Self::UnusualPlugin => "unusual_plugin",
+            Self::UnusualRate => "unusual_rate",
+        }
+    }
+}
+
+/// The owner and expiry a user sets on one secret.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SecretMetadata {
+    pub owner_id: Option<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// A secret that has expired, expires before a cutoff, or has not been
+/// resolved since one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SecretAttentionRow {
+    pub id: String,
+    pub user_id: UserId,
+    pub plugin_id: String,
+    pub var_name: String,
+    pub owner_id: Option<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+    /// The last resolution, or when the secret was stored if it never was.
+    pub last_used_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct NewSecretAlert<'a> {
+    pub user_id: &'a UserId,
+    pub plugin_id: &'a str,
+    pub var_name: &'a str,
+    pub owner_id: Option<&'a str>,
+    pub kind: SecretAlertKind,
+    pub dedupe_key: String,
+    pub detail: serde_json::Value,
+}
+
+#[derive(Debug, Clone)]
+pub struct SecretAlertRow {
+    pub plugin_id: String,
+    pub var_name: String,
+    pub kind: String,
+    pub detail: serde_json::Value,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Counts of a user's past resolutions, from the secret audit log, that a new
+/// resolution from one plugin is judged against.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ResolutionBaseline {
+    /// Resolutions by the user from any plugin in the last 30 days.
+    pub user_30d: i64,
+    /// Resolutions by the user from this plugin in the last 30 days.
+    pub plugin_30d: i64,
+    /// Resolutions from this plugin in the last hour.
+    pub plugin_last_hour: i64,
+    /// Resolutions from this plugin in the seven days before the last hour.
+    pub plugin_prior_7d: i64,
+}
+
+/// Set a secret's owner and expiry, recording the change in the secret audit
+/// log. Returns false when the user has no such secret.
+pub async fn set_secret_metadata(
+    pool: &PgPool,
+    user_id: &UserId,
+    plugin_id: &str,
+    var_name: &str,
+    metadata: &SecretMetadata,
+) -> Result<bool, sqlx::Error> {
+    let audit_id = uuid::Uuid::new_v4().to_string();
+    let updated = sqlx::query_scalar!(
+        r#"WITH updated AS (
+               UPDATE plugin_env_vars SET owner_id = $4, expires_at = $5, updated_at = NOW()
+               WHERE user_id = $1 AND plugin_id = $2 AND var_name = $3 AND is_secret
+               RETURNING var_name
+           ),
+           audit AS (
+               INSERT INTO secret_audit_log (id, user_id, plugin_id, var_name, action, actor_id)
+               SELECT $6, $1, $2, var_name, 'updated', $1 FROM updated
+               RETURNING 1
+           )
+           SELECT COUNT(*) AS "updated!" FROM updated"#,
+        user_id.as_str(),
+        plugin_id,
+        var_name,
+        metadata.owner_id,
+        metadata.expires_at,
+        audit_id,
+    )
+    .fetch_one(pool)
+    .await?;
+    Ok(updated > 0)
+}
+
+/// Secrets, of one user or of everyone, that expire before `expiring_before`
+/// or were last used before `unused_before`.
+pub async fn list_secrets_needing_attention(
+    pool: &PgPool,
+    user_id: Option<&UserId>,
+    expiring_before: DateTime<Utc>,
+    unused_before: DateTime<Utc>,
+) -> Result<Vec<SecretAttentionRow>, sqlx::Error> {
+    sqlx::query_as!(
+        SecretAttentionRow,
+        r#"SELECT id, user_id AS "user_id: UserId", plugin_id, var_name, owner_id, expires_at,
+                  COALESCE(last_resolved_at, created_at) AS "last_used_at!"
+        FROM plugin_env_vars
+        WHERE is_secret
+          AND ($1::text IS NULL OR user_id = $1)
+          AND (expires_at < $2 OR COALESCE(last_resolved_at, created_at) < $3)
+        ORDER BY expires_at ASC NULLS LAST, user_id, plugin_id, var_name"#,
+        user_id.map(UserId::as_str),
+        expiring_before,
+        unused_before,
+    )
+    .fetch_all(pool)
+    .await
+}
+
+/// Record an alert unless one with the same dedupe key exists. Returns
+/// whether it was new.
+pub async fn insert_secret_alert(
+    pool: &PgPool,
+    alert: &NewSecretAlert<'_>,
+) -> Result<bool, sqlx::Error> {
+    let id = uuid::Uuid::new_v4().to_string();
+    let result = sqlx::query!(
+        r#"INSERT INTO secret_alerts
+            (id, user_id, plugin_id, var_name, owner_id, kind, dedupe_key, detail)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        ON CONFLICT (dedupe_key) DO NOTHING"#,
+        id,
+        alert.user_id.as_str(),
+        alert.plugin_id,
+        alert.var_name,
+        alert.owner_id,
+        alert.kind.as_str(),
+        alert.dedupe_key,
+        alert.detail,
+    )
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// A user's resolution anomalies raised since `since`, newest first.
+pub async fn list_recent_resolution_alerts(
+    pool: &PgPool,
+    user_id: &UserId,
+    since: DateTime<Utc>,
+) -> Result<Vec<SecretAlertRow>, sqlx::Error> {
+    sqlx::query_as!(
+        SecretAlertRow,
+        r#"SELECT plugin_id, var_name, kind, detail, created_at
+        FROM secret_alerts
+        WHERE user_id = $1 AND created_at >= $2
+          AND kind IN ('unusual_plugin', 'unusual_rate')
+        ORDER BY created_at DESC
+        LIMIT 20"#,
+        user_id.as_str(),
+        since,
+    )
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn get_resolution_baseline(
+    pool: &PgPool,
+    user_id: &UserId,
+    plugin_id: &str,
+    now: DateTime<Utc>,
+) -> Result<ResolutionBaseline, sqlx::Error> {
+    sqlx::query_as!(
+        ResolutionBaseline,
+        r#"SELECT
+               COUNT(*) AS "user_30d!",
+               COUNT(*) FILTER (WHERE plugin_id = $2) AS "plugin_30d!",
+               COUNT(*) FILTER (
+                   WHERE plugin_id = $2 AND created_at >= $3::timestamptz - INTERVAL '1 hour'
+               ) AS "plugin_last_hour!",
+               COUNT(*) FILTER (
+                   WHERE plugin_id = $2 AND created_at < $3::timestamptz - INTERVAL '1 hour'
+                     AND created_at >= $3::timestamptz - INTERVAL '7 days 1 hour'
+               ) AS "plugin_prior_7d!"
+        FROM secret_audit_log
+        WHERE user_id = $1 AND action = 'accessed'
+          AND created_at >= $3::timestamptz - INTERVAL '30 days' AND created_at <= $3"#,
+        user_id.as_str(),
+        plugin_id,
+        now,
+    )
+    .fetch_one(pool)
+    .await
+}
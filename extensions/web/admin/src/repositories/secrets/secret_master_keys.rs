@@ -0,0 +1,172 @@
+//! Master key registry, rotation progress and the rotation audit trail.
+
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use sqlx::types::Json;
+use systemprompt::identifiers::UserId;
+
+#[derive(Debug, Clone)]
+pub struct MasterKeyRotation {
+    pub id: String,
+    pub target_key_id: String,
+    pub total_deks: i32,
+    pub rewrapped: i32,
+    pub failed: i32,
+}
+
+#[derive(Debug)]
+pub struct MasterKeyEvent<'a> {
+    pub key_id: &'a str,
+    pub rotation_id: Option<&'a str>,
+    pub event: &'a str,
+    pub detail: serde_json::Value,
+    pub actor: &'a UserId,
+}
+
+/// Record `key_id` as a known key. Returns whether it was new.
+pub async fn insert_master_key(pool: &PgPool, key_id: &str) -> Result<bool, sqlx::Error> {
+    let inserted = sqlx::query_scalar!(
+        "INSERT INTO master_keys (key_id) VALUES ($1) ON CONFLICT (key_id) DO NOTHING \
+         RETURNING key_id",
+        key_id,
+    )
+    .fetch_optional(pool)
+    .await?;
+    Ok(inserted.is_some())
+}
+
+pub async fn list_retired_master_key_ids(pool: &PgPool) -> Result<Vec<String>, sqlx::Error> {
+    sqlx::query_scalar!("SELECT key_id FROM master_keys WHERE status = 'retired'")
+        .fetch_all(pool)
+        .await
+}
+
+/// DEKs not wrapped under `key_id`, including those wrapped before keys were
+/// versioned.
+pub async fn count_deks_off_master_key(pool: &PgPool, key_id: &str) -> Result<i64, sqlx::Error> {
+    let count = sqlx::query_scalar!(
+        r#"SELECT COUNT(*) AS "count!" FROM user_encryption_keys
+           WHERE master_key_id IS DISTINCT FROM $1"#,
+        key_id,
+    )
+    .fetch_one(pool)
+    .await?;
+    Ok(count)
+}
+
+pub async fn find_running_master_key_rotation(
+    pool: &PgPool,
+    target_key_id: &str,
+) -> Result<Option<MasterKeyRotation>, sqlx::Error> {
+    sqlx::query_as!(
+        MasterKeyRotation,
+        "SELECT id, target_key_id, total_deks, rewrapped, failed FROM master_key_rotations \
+         WHERE target_key_id = $1 AND status = 'running'",
+        target_key_id,
+    )
+    .fetch_optional(pool)
+    .await
+}
+
+pub async fn insert_master_key_rotation(
+    pool: &PgPool,
+    target_key_id: &str,
+    total_deks: i32,
+) -> Result<MasterKeyRotation, sqlx::Error> {
+    sqlx::query_as!(
+        MasterKeyRotation,
+        "INSERT INTO master_key_rotations (id, target_key_id, total_deks) \
+         VALUES (gen_random_uuid()::TEXT, $1, $2) \
+         RETURNING id, target_key_id, total_deks, rewrapped, failed",
+        target_key_id,
+        total_deks,
+    )
+    .fetch_one(pool)
+    .await
+}
+
+pub async fn update_master_key_rotation_progress(
+    pool: &PgPool,
+    rotation_id: &str,
+    rewrapped: i32,
+    failed: i32,
+    last_error: Option<&str>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        "UPDATE master_key_rotations SET rewrapped = rewrapped + $2, failed = failed + $3, \
+         last_error = COALESCE($4, last_error), updated_at = NOW() WHERE id = $1",
+        rotation_id,
+        rewrapped,
+        failed,
+        last_error,
+    )
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+pub async fn set_master_key_rotation_completed(
+    pool: &PgPool,
+    rotation_id: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        "UPDATE master_key_rotations SET status = 'completed', completed_at = NOW(), \
+         updated_at = NOW() WHERE id = $1",
+        rotation_id,
+    )
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Retire every active key but `current_key_id`, returning the ids retired.
+pub async fn set_master_keys_retired(
+    pool: &PgPool,
+    current_key_id: &str,
+) -> Result<Vec<String>, sqlx::Error> {
+    sqlx::query_scalar!(
+        "UPDATE master_keys SET status = 'retired', retired_at = NOW() \
+         WHERE status = 'active' AND key_id <> $1 RETURNING key_id",
+        current_key_id,
+    )
+    .fetch_all(pool)
+    .await
+}
+
+/// When `key_id` was first seen, if that is more than `interval_days` ago and
+/// it has not already been flagged as overdue.
+pub async fn find_unflagged_overdue_master_key(
+    pool: &PgPool,
+    key_id: &str,
+    interval_days: i32,
+) -> Result<Option<DateTime<Utc>>, sqlx::Error> {
+    sqlx::query_scalar!(
+        "SELECT first_seen_at FROM master_keys \
+         WHERE key_id = $1 AND status = 'active' \
+           AND first_seen_at < NOW() - make_interval(days => $2) \
+           AND NOT EXISTS (SELECT 1 FROM master_key_events \
+                           WHERE key_id = $1 AND event = 'rotation_overdue')",
+        key_id,
+        interval_days,
+    )
+    .fetch_optional(pool)
+    .await
+}
+
+pub async fn insert_master_key_event(
+    pool: &PgPool,
+    event: &MasterKeyEvent<'_>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        "INSERT INTO master_key_events (id, key_id, rotation_id, event, detail, actor_id) \
+         VALUES (gen_random_uuid()::TEXT, $1, $2, $3, $4, $5)",
+        event.key_id,
+        event.rotation_id,
+        event.event,
+        Json(&event.detail) as _,
+        event.actor.as_str(),
+    )
+    .execute(pool)
+    .await?;
+    Ok(())
+}
@@ -8,6 +8,7 @@ use sqlx::PgPool;
 use systemprompt::identifiers::UserId;
 
 use crate::repositories::secrets::secret_crypto;
+use crate::repositories::secrets::secret_keyring::MasterKeyring;
 
 use crate::repositories::secrets::secret_keys;
 
@@ -67,9 +68,9 @@ pub async fn resolve_secrets_for_plugin(
     pool: &PgPool,
     user_id: &UserId,
     plugin_id: &str,
-    master_key: &[u8; 32],
+    keyring: &MasterKeyring,
 ) -> Result<HashMap<String, String>, MarketplaceError> {
-    let dek = secret_keys::get_or_create_user_dek(pool, user_id, master_key)
+    let dek = secret_keys::get_or_create_user_dek(pool, user_id, keyring)
         .await
         .map_err(|e| MarketplaceError::Crypto(e.to_string()))?;
 
@@ -106,6 +107,16 @@ pub async fn resolve_secrets_for_plugin(
         secrets.insert(row.var_name.clone(), value);
     }
 
+    sqlx::query!(
+        "UPDATE plugin_env_vars SET last_resolved_at = NOW() \
+         WHERE user_id = $1 AND plugin_id = $2 AND is_secret = true \
+         AND encrypted_value IS NOT NULL AND key_version > 0",
+        user_id.as_str(),
+        plugin_id,
+    )
+    .execute(pool)
+    .await?;
+
     let audit_id = uuid::Uuid::new_v4().to_string();
     sqlx::query!(
         "INSERT INTO secret_audit_log (id, user_id, plugin_id, var_name, action, actor_id) \
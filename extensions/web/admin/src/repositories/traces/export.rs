@@ -0,0 +1,177 @@
+//! Everything the trace explorer draws for a window of time, across every
+//! session at once, in the detail the OTLP exporter needs.
+//!
+//! A row belongs to the window its activity finished in: a model request by
+//! `completed_at`, a decision or a tool event by `created_at`. Requests still
+//! pending are left for a later window. Governance decisions keyed by trace id
+//! are folded onto the session that trace belongs to, as the trace list does.
+
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use systemprompt::identifiers::{AgentId, SessionId, UserId};
+
+#[derive(Debug, Clone)]
+pub struct ExportRequest {
+    pub id: String,
+    pub session_id: SessionId,
+    pub user_id: UserId,
+    pub provider: Option<String>,
+    pub model: Option<String>,
+    pub requested_model: Option<String>,
+    pub provider_request_id: Option<String>,
+    pub status: String,
+    pub error_message: Option<String>,
+    pub input_tokens: Option<i32>,
+    pub output_tokens: Option<i32>,
+    pub cache_read_tokens: Option<i32>,
+    pub cost_microdollars: i64,
+    pub cache_hit: bool,
+    pub is_streaming: bool,
+    pub max_tokens: Option<i32>,
+    pub temperature: Option<f64>,
+    pub started_at: DateTime<Utc>,
+    pub ended_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ExportToolEvent {
+    pub id: String,
+    pub session_id: SessionId,
+    pub user_id: UserId,
+    pub event_type: String,
+    pub tool_name: Option<String>,
+    pub plugin_id: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ExportDecision {
+    pub id: String,
+    pub session_id: SessionId,
+    pub user_id: UserId,
+    pub tool_name: String,
+    pub agent_id: Option<AgentId>,
+    pub decision: String,
+    pub policy: String,
+    pub reason: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct TraceExportWindow {
+    pub requests: Vec<ExportRequest>,
+    pub tool_events: Vec<ExportToolEvent>,
+    pub decisions: Vec<ExportDecision>,
+}
+
+async fn list_requests(
+    pool: &PgPool,
+    after: DateTime<Utc>,
+    through: DateTime<Utc>,
+) -> Result<Vec<ExportRequest>, sqlx::Error> {
+    sqlx::query_as!(
+        ExportRequest,
+        r#"SELECT
+            id                  AS "id!",
+            session_id          AS "session_id!: SessionId",
+            user_id             AS "user_id!: UserId",
+            provider,
+            model,
+            requested_model,
+            provider_request_id,
+            status::text        AS "status!",
+            error_message,
+            input_tokens,
+            output_tokens,
+            cache_read_tokens,
+            cost_microdollars   AS "cost_microdollars!",
+            cache_hit           AS "cache_hit!",
+            is_streaming        AS "is_streaming!",
+            max_tokens,
+            temperature,
+            created_at          AS "started_at!",
+            COALESCE(completed_at, updated_at) AS "ended_at!"
+        FROM ai_requests
+        WHERE session_id IS NOT NULL
+          AND status <> 'pending'
+          AND COALESCE(completed_at, updated_at) > $1
+          AND COALESCE(completed_at, updated_at) <= $2
+        ORDER BY created_at ASC"#,
+        after,
+        through,
+    )
+    .fetch_all(pool)
+    .await
+}
+
+async fn list_tool_events(
+    pool: &PgPool,
+    after: DateTime<Utc>,
+    through: DateTime<Utc>,
+) -> Result<Vec<ExportToolEvent>, sqlx::Error> {
+    sqlx::query_as!(
+        ExportToolEvent,
+        r#"SELECT
+            id              AS "id!",
+            session_id      AS "session_id!: SessionId",
+            user_id         AS "user_id!: UserId",
+            event_type      AS "event_type!",
+            tool_name,
+            plugin_id,
+            created_at      AS "created_at!"
+        FROM plugin_usage_events
+        WHERE created_at > $1 AND created_at <= $2
+        ORDER BY created_at ASC"#,
+        after,
+        through,
+    )
+    .fetch_all(pool)
+    .await
+}
+
+async fn list_decisions(
+    pool: &PgPool,
+    after: DateTime<Utc>,
+    through: DateTime<Utc>,
+) -> Result<Vec<ExportDecision>, sqlx::Error> {
+    sqlx::query_as!(
+        ExportDecision,
+        r#"SELECT
+            g.id            AS "id!",
+            COALESCE(
+                (SELECT r.session_id FROM ai_requests r
+                 WHERE r.trace_id = g.trace_id AND r.session_id IS NOT NULL
+                 LIMIT 1),
+                NULLIF(g.session_id, ''),
+                g.trace_id
+            )               AS "session_id!: SessionId",
+            g.user_id       AS "user_id!: UserId",
+            g.tool_name     AS "tool_name!",
+            g.agent_id      AS "agent_id: AgentId",
+            g.decision      AS "decision!",
+            g.policy        AS "policy!",
+            g.reason        AS "reason!",
+            g.created_at    AS "created_at!"
+        FROM governance_decisions g
+        WHERE g.created_at > $1 AND g.created_at <= $2
+          AND (NULLIF(g.session_id, '') IS NOT NULL OR g.trace_id IS NOT NULL)
+        ORDER BY g.created_at ASC"#,
+        after,
+        through,
+    )
+    .fetch_all(pool)
+    .await
+}
+
+/// Rows that finished in `(after, through]`.
+pub async fn get_trace_export_window(
+    pool: &PgPool,
+    after: DateTime<Utc>,
+    through: DateTime<Utc>,
+) -> Result<TraceExportWindow, sqlx::Error> {
+    Ok(TraceExportWindow {
+        requests: list_requests(pool, after, through).await?,
+        tool_events: list_tool_events(pool, after, through).await?,
+        decisions: list_decisions(pool, after, through).await?,
+    })
+}
@@ -68,6 +68,13 @@ pub async fn list_traces(
             FROM plugin_usage_events
             WHERE created_at >= $1 AND created_at < $2
               AND session_id IS NOT NULL
+            UNION ALL
+            SELECT COALESCE(t.session_id, c.session_id) AS session_id, c.user_id,
+                   NULL::text AS agent_id, NULL::text AS agent_scope,
+                   c.started_at AS created_at, NULL::text AS decision, 'mcp'::text AS source
+            FROM mcp_tool_captures c
+            LEFT JOIN trace_to_session t ON t.trace_id = c.trace_id
+            WHERE c.started_at >= $1 AND c.started_at < $2
         ),
         per_session AS (
             SELECT
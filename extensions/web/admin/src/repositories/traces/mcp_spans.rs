@@ -0,0 +1,80 @@
+//! Waterfall spans for MCP tool calls captured by the bundled and proxied MCP
+//! servers, carrying the redacted arguments and result excerpt for the span
+//! inspector.
+
+use serde::Serialize;
+use sqlx::PgPool;
+use systemprompt::identifiers::SessionId;
+
+use super::spans::format_identity;
+use super::{Span, SpanKind, SpanStatus};
+
+#[derive(Debug, Serialize)]
+struct McpCallSpanRaw<'a> {
+    server: &'a str,
+    tool_name: &'a str,
+    status: &'a str,
+    arguments: Option<&'a str>,
+    result_excerpt: Option<&'a str>,
+    truncated: bool,
+    redactions: i32,
+}
+
+// Why: an agent's MCP calls carry the gateway's trace id while the session
+// key may differ, so calls are matched on either, as governance rows are.
+pub(super) async fn list_mcp_call_spans(
+    pool: &PgPool,
+    session_id: &SessionId,
+) -> Result<Vec<Span>, sqlx::Error> {
+    let calls = sqlx::query!(
+        r#"SELECT
+            id              AS "id!",
+            server          AS "server!",
+            tool_name       AS "tool_name!",
+            status          AS "status!",
+            arguments,
+            result_excerpt,
+            truncated       AS "truncated!",
+            redactions      AS "redactions!",
+            user_id         AS "user_id!",
+            started_at      AS "started_at!",
+            ended_at        AS "ended_at!"
+        FROM mcp_tool_captures
+        WHERE session_id = $1
+           OR trace_id IN (
+               SELECT DISTINCT trace_id FROM ai_requests
+               WHERE session_id = $1 AND trace_id IS NOT NULL)
+        ORDER BY started_at ASC"#,
+        session_id.as_str(),
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(calls
+        .into_iter()
+        .map(|c| Span {
+            id: c.id.clone(),
+            kind: SpanKind::Tool,
+            name: format!("mcp__{}__{}", c.server, c.tool_name),
+            started_at: c.started_at,
+            ended_at: c.ended_at,
+            duration_ms: (c.ended_at - c.started_at).num_milliseconds().max(0),
+            status: if c.status == "error" {
+                SpanStatus::Error
+            } else {
+                SpanStatus::Ok
+            },
+            identity_label: Some(format_identity(Some(c.user_id.as_str()), None, None)),
+            raw: serde_json::to_value(McpCallSpanRaw {
+                server: &c.server,
+                tool_name: &c.tool_name,
+                status: &c.status,
+                arguments: c.arguments.as_deref(),
+                result_excerpt: c.result_excerpt.as_deref(),
+                truncated: c.truncated,
+                redactions: c.redactions,
+            })
+            .unwrap_or_default(),
+        })
+        .collect())
+}
@@ -1,20 +1,27 @@
 //! Trace explorer queries.
 //!
-//! A "trace" here is keyed on `session_id` (which all four governance / gateway
-//! / events tables share) and surfaces the `trace_id` from `ai_requests` when
-//! one exists. [`list_traces`] returns one summary row per session in the
-//! window; [`list_trace_spans`] returns the union of per-table rows for a
-//! single session, normalised into a [`Span`] shape and ordered by start time.
+//! A "trace" here is keyed on `session_id` (which the governance, gateway,
+//! events and MCP tool-call capture tables share) and surfaces the `trace_id`
+//! from `ai_requests` when one exists. [`list_traces`] returns one summary row
+//! per session in the window; [`list_trace_spans`] returns the union of
+//! per-table rows for a single session, normalised into a [`Span`] shape and
+//! ordered by start time. [`get_trace_export_window`] reads the first three of
+//! those sources across every session for the OTLP exporter.
 
 use chrono::{DateTime, Utc};
 use serde::Serialize;
 use systemprompt::identifiers::{AgentId, SessionId, TraceId, UserId};
 
+mod export;
 mod list;
 mod list_row;
+mod mcp_spans;
 mod spans;
 mod stats;
 
+pub use export::{
+    ExportDecision, ExportRequest, ExportToolEvent, TraceExportWindow, get_trace_export_window,
+};
 pub use list::{TracePage, list_traces};
 pub use spans::{list_trace_spans, resolve_trace_session};
 pub use stats::get_trace_stats;
@@ -130,7 +137,7 @@ pub struct TraceStats {
     pub total_tokens: i64,
 }
 
-/// One waterfall span — normalised across the four span sources.
+/// One waterfall span — normalised across the span sources.
 #[derive(Debug, Clone, Serialize)]
 pub struct Span {
     pub id: String,
@@ -141,8 +148,7 @@ pub struct Span {
     pub duration_ms: i64,
     pub status: SpanStatus,
     pub identity_label: Option<String>,
-    // JSON: source row rendered for the span inspector; the four span sources have no common
-    // shape.
+    // JSON: source row rendered for the span inspector; the span sources have no common shape.
     pub raw: serde_json::Value,
 }
 
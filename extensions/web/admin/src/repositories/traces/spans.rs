@@ -1,10 +1,11 @@
 //! Per-session waterfall spans, unioned and normalised across the governance,
-//! gateway-request, and tool-event tables.
+//! gateway-request, tool-event and MCP tool-call capture tables.
 
 use serde::Serialize;
 use sqlx::PgPool;
 use systemprompt::identifiers::SessionId;
 
+use super::mcp_spans::list_mcp_call_spans;
 use super::{Span, SpanKind, SpanStatus};
 
 #[derive(Debug, Serialize)]
@@ -89,6 +90,19 @@ pub async fn resolve_trace_session(
         return Ok(Some(row.session_id));
     }
 
+    if let Some(row) = sqlx::query!(
+        r#"SELECT session_id AS "session_id!: SessionId"
+           FROM mcp_tool_captures
+           WHERE session_id = $1 OR trace_id = $1
+           LIMIT 1"#,
+        id,
+    )
+    .fetch_optional(pool)
+    .await?
+    {
+        return Ok(Some(row.session_id));
+    }
+
     Ok(None)
 }
 
@@ -267,11 +281,16 @@ pub async fn list_trace_spans(
     let mut spans = list_governance_spans(pool, session_id).await?;
     spans.extend(list_request_spans(pool, session_id).await?);
     spans.extend(list_event_spans(pool, session_id).await?);
+    spans.extend(list_mcp_call_spans(pool, session_id).await?);
     spans.sort_by_key(|s| s.started_at);
     Ok(spans)
 }
 
-fn format_identity(user: Option<&str>, agent: Option<&str>, scope: Option<&str>) -> String {
+pub(super) fn format_identity(
+    user: Option<&str>,
+    agent: Option<&str>,
+    scope: Option<&str>,
+) -> String {
     let user_part = user.unwrap_or("?");
     match (agent, scope) {
         (Some(a), Some(s)) => format!("{user_part} · {a} ({s})"),
@@ -5,6 +5,10 @@ use sqlx::PgPool;
 use super::TraceStats;
 use crate::util::time_range::TimeRange;
 
+#[expect(
+    clippy::too_many_lines,
+    reason = "body is one irreducible compile-time-checked query! SQL literal"
+)]
 pub async fn get_trace_stats(pool: &PgPool, range: TimeRange) -> Result<TraceStats, sqlx::Error> {
     let row = sqlx::query!(
         r#"WITH trace_to_session AS (
@@ -28,6 +32,11 @@ pub async fn get_trace_stats(pool: &PgPool, range: TimeRange) -> Result<TraceSta
             SELECT session_id, created_at, NULL::text, status::text
             FROM ai_requests
             WHERE created_at >= $1 AND created_at < $2 AND session_id IS NOT NULL
+            UNION ALL
+            SELECT COALESCE(t.session_id, c.session_id), c.started_at, NULL::text, NULL::text
+            FROM mcp_tool_captures c
+            LEFT JOIN trace_to_session t ON t.trace_id = c.trace_id
+            WHERE c.started_at >= $1 AND c.started_at < $2
         ),
         per_session AS (
             SELECT
@@ -23,8 +23,8 @@ use systemprompt_security::authz::{
 
 use super::rules::list_all_rules;
 use crate::authz::{dimensions, subject_attributes_for};
-use crate::marketplace_filter::entity_ref_for;
 use crate::types::access_control::{AccessControlRule, AccessDecision};
+use systemprompt_web_access::marketplace_filter::keepsets::entity_ref_for;
 
 #[derive(Debug, Serialize)]
 pub struct UserMatrix {
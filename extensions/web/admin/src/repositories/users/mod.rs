@@ -4,7 +4,6 @@ pub mod access_control;
 pub mod access_tokens;
 pub mod access_tree;
 pub mod activity;
-pub mod identity;
 pub mod magic_links;
 pub mod mutations;
 pub mod queries;
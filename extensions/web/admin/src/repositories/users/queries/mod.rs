@@ -3,7 +3,6 @@
 mod detail;
 mod events;
 mod listing;
-mod role;
 mod runtime;
 
 pub use detail::{
@@ -11,7 +10,7 @@ pub use detail::{
 };
 pub use events::list_user_usage;
 pub use listing::{list_distinct_roles, list_users};
-pub use role::find_user_roles_department;
 pub use runtime::{
     UserRuntimeAggregate, UserRuntimeDetail, get_user_runtime_detail, list_user_runtime_aggregates,
 };
+pub use systemprompt_web_access::repositories::users::find_user_roles_department;
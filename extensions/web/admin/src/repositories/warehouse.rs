@@ -0,0 +1,79 @@
+//! Rows for the warehouse export: one table's rows that reached their final
+//! or latest state in a window, as JSON objects, oldest first.
+//!
+//! Append-only tables are windowed on `created_at`. `ai_requests` is windowed
+//! on `completed_at`, so a request is read once it has finished and never
+//! while pending. `session_analyses` is re-analysed in place, so it is
+//! windowed on `updated_at` and a changed row is read again in the window of
+//! its change. Whole rows are read; `warehouse::tables` decides which columns
+//! reach the files.
+
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+
+use crate::warehouse::WarehouseTable;
+
+pub async fn list_warehouse_rows(
+    pool: &PgPool,
+    table: WarehouseTable,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Result<Vec<serde_json::Value>, sqlx::Error> {
+    match table {
+        WarehouseTable::AiRequests => {
+            sqlx::query_scalar!(
+                r#"SELECT to_jsonb(t) AS "row!" FROM ai_requests t
+                   WHERE t.completed_at >= $1 AND t.completed_at < $2
+                   ORDER BY t.completed_at, t.id"#,
+                from,
+                to,
+            )
+            .fetch_all(pool)
+            .await
+        },
+        WarehouseTable::GovernanceDecisions => {
+            sqlx::query_scalar!(
+                r#"SELECT to_jsonb(t) AS "row!" FROM governance_decisions t
+                   WHERE t.created_at >= $1 AND t.created_at < $2
+                   ORDER BY t.created_at, t.id"#,
+                from,
+                to,
+            )
+            .fetch_all(pool)
+            .await
+        },
+        WarehouseTable::HookEvents => {
+            sqlx::query_scalar!(
+                r#"SELECT to_jsonb(t) AS "row!" FROM plugin_usage_events t
+                   WHERE t.created_at >= $1 AND t.created_at < $2
+                   ORDER BY t.created_at, t.id"#,
+                from,
+                to,
+            )
+            .fetch_all(pool)
+            .await
+        },
+        WarehouseTable::SessionAnalyses => {
+            sqlx::query_scalar!(
+                r#"SELECT to_jsonb(t) AS "row!" FROM session_analyses t
+                   WHERE t.updated_at >= $1 AND t.updated_at < $2
+                   ORDER BY t.updated_at, t.session_id"#,
+                from,
+                to,
+            )
+            .fetch_all(pool)
+            .await
+        },
+        WarehouseTable::EvalResults => {
+            sqlx::query_scalar!(
+                r#"SELECT to_jsonb(t) AS "row!" FROM eval_results t
+                   WHERE t.created_at >= $1 AND t.created_at < $2
+                   ORDER BY t.created_at, t.id"#,
+                from,
+                to,
+            )
+            .fetch_all(pool)
+            .await
+        },
+    }
+}
@@ -0,0 +1,235 @@
+//! Data retention: purging governed data once it outlives its policy.
+//!
+//! Everything the gateway and hooks record was kept forever. Policies in
+//! `services/governance/retention.yaml` now give each [`DataClass`] a
+//! lifetime in days; the daily `data_retention` job runs [`enforce`], which
+//! removes or strips each class's expired rows in bounded batches and
+//! records the outcome in `retention_purges`. Rows belonging to a user or
+//! session under legal hold are skipped in every class.
+//!
+//! Some classes are a field class of a table rather than the table: request
+//! bodies can go after a month while the request's metadata stays for cost
+//! reporting. `/admin/governance/retention` shows each policy and what it will
+//! purge next (see [`status`]).
+
+pub mod status;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::Deserialize;
+use sqlx::PgPool;
+
+use crate::repositories::config::retention::{LegalHold, RetentionConfig};
+use crate::repositories::retention::{purge, set_retention_error, set_retention_purge};
+
+/// A class of data with its own retention policy. Keys of `policies` in the
+/// retention file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DataClass {
+    AiRequestBodies,
+    AiRequests,
+    HookEventDetails,
+    HookEvents,
+    Transcripts,
+    GovernanceDecisions,
+    LinkClicks,
+    McpToolCaptures,
+}
+
+impl DataClass {
+    pub const ALL: [Self; 8] = [
+        Self::AiRequestBodies,
+        Self::AiRequests,
+        Self::HookEventDetails,
+        Self::HookEvents,
+        Self::Transcripts,
+        Self::GovernanceDecisions,
+        Self::LinkClicks,
+        Self::McpToolCaptures,
+    ];
+
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::AiRequestBodies => "ai_request_bodies",
+            Self::AiRequests => "ai_requests",
+            Self::HookEventDetails => "hook_event_details",
+            Self::HookEvents => "hook_events",
+            Self::Transcripts => "transcripts",
+            Self::GovernanceDecisions => "governance_decisions",
+            Self::LinkClicks => "link_clicks",
+            Self::McpToolCaptures => "mcp_tool_captures",
+        }
+    }
+
+    /// What expiring the class removes, for the retention page.
+    #[must_use]
+    pub const fn description(self) -> &'static str {
+        match self {
+            Self::AiRequestBodies => {
+                "Request and response bodies, messages and tool call arguments of gateway requests; the request row stays"
+            },
+            Self::AiRequests => "Gateway request rows, with everything recorded against them",
+            Self::HookEventDetails => {
+                "Prompt previews, descriptions, working directories and payloads of hook events; the event stays"
+            },
+            Self::HookEvents => "Hook event rows",
+            Self::Transcripts => "Captured session transcripts",
+            Self::GovernanceDecisions => "Allow and deny decisions with the rules evaluated",
+            Self::LinkClicks => "Tracked link clicks with referrer, user agent and IP address",
+            Self::McpToolCaptures => {
+                "MCP tool calls with their redacted arguments and result excerpts"
+            },
+        }
+    }
+
+    /// Shortest policy allowed. Request rows feed chargeback and the cost
+    /// baselines, so they must outlive the month a finance team closes last.
+    #[must_use]
+    pub const fn min_days(self) -> u32 {
+        match self {
+            Self::AiRequests => 90,
+            _ => 1,
+        }
+    }
+}
+
+/// Legal holds flattened into the id lists each purge statement excludes.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LegalHolds {
+    pub users: Vec<String>,
+    pub sessions: Vec<String>,
+}
+
+impl LegalHolds {
+    #[must_use]
+    pub fn from_config(holds: &[LegalHold]) -> Self {
+        let mut out = Self::default();
+        for hold in holds {
+            if let Some(user) = &hold.user_id {
+                out.users.push(user.to_string());
+            }
+            if let Some(session) = &hold.session_id {
+                out.sessions.push(session.to_string());
+            }
+        }
+        out.users.sort();
+        out.users.dedup();
+        out.sessions.sort();
+        out.sessions.dedup();
+        out
+    }
+}
+
+/// Rows written before this are past a `days`-day policy.
+#[must_use]
+pub fn cutoff(now: DateTime<Utc>, days: u32) -> DateTime<Utc> {
+    now - Duration::days(i64::from(days))
+}
+
+/// What one run did to one class.
+#[derive(Debug, Clone)]
+pub struct PurgeOutcome {
+    pub class: DataClass,
+    pub cutoff: DateTime<Utc>,
+    pub purged: u64,
+    /// Set when a batch failed; the rows purged before it stay purged.
+    pub error: Option<String>,
+}
+
+/// Whether another batch is worth running after one that purged `purged`
+/// rows, with `done` batches behind it.
+#[must_use]
+pub fn wants_another_batch(purged: u64, batch_size: u32, done: u32, max: u32) -> bool {
+    purged >= u64::from(batch_size) && done < max
+}
+
+async fn purge_batch(
+    pool: &PgPool,
+    class: DataClass,
+    cutoff: DateTime<Utc>,
+    holds: &LegalHolds,
+    limit: i64,
+) -> Result<u64, sqlx::Error> {
+    match class {
+        DataClass::AiRequestBodies => {
+            purge::delete_expired_ai_request_bodies(pool, cutoff, holds, limit).await
+        },
+        DataClass::AiRequests => {
+            purge::delete_expired_ai_requests(pool, cutoff, holds, limit).await
+        },
+        DataClass::HookEventDetails => {
+            purge::delete_expired_hook_event_details(pool, cutoff, holds, limit).await
+        },
+        DataClass::HookEvents => {
+            purge::delete_expired_hook_events(pool, cutoff, holds, limit).await
+        },
+        DataClass::Transcripts => {
+            purge::delete_expired_transcripts(pool, cutoff, holds, limit).await
+        },
+        DataClass::GovernanceDecisions => {
+            purge::delete_expired_governance_decisions(pool, cutoff, holds, limit).await
+        },
+        DataClass::LinkClicks => {
+            purge::delete_expired_link_clicks(pool, cutoff, holds, limit).await
+        },
+        DataClass::McpToolCaptures => {
+            purge::delete_expired_mcp_tool_captures(pool, cutoff, holds, limit).await
+        },
+    }
+}
+
+async fn enforce_class(
+    pool: &PgPool,
+    config: &RetentionConfig,
+    holds: &LegalHolds,
+    class: DataClass,
+    cutoff: DateTime<Utc>,
+) -> PurgeOutcome {
+    let mut outcome = PurgeOutcome {
+        class,
+        cutoff,
+        purged: 0,
+        error: None,
+    };
+    let mut batches = 0;
+    loop {
+        match purge_batch(pool, class, cutoff, holds, i64::from(config.batch_size)).await {
+            Ok(n) => {
+                outcome.purged += n;
+                batches += 1;
+                if !wants_another_batch(n, config.batch_size, batches, config.max_batches_per_run) {
+                    break;
+                }
+            },
+            Err(e) => {
+                outcome.error = Some(e.to_string());
+                break;
+            },
+        }
+    }
+    outcome
+}
+
+/// Purge every class with a policy, oldest rows first. A class that fails
+/// is recorded and the rest still run.
+pub async fn enforce(
+    pool: &PgPool,
+    config: &RetentionConfig,
+    now: DateTime<Utc>,
+) -> Result<Vec<PurgeOutcome>, sqlx::Error> {
+    let holds = LegalHolds::from_config(&config.legal_holds);
+    let mut outcomes = Vec::with_capacity(config.policies.len());
+    for (class, days) in &config.policies {
+        let outcome = enforce_class(pool, config, &holds, *class, cutoff(now, *days)).await;
+        let purged = i64::try_from(outcome.purged).unwrap_or(i64::MAX);
+        match &outcome.error {
+            None => set_retention_purge(pool, class.as_str(), outcome.cutoff, purged).await?,
+            Some(error) => {
+                set_retention_error(pool, class.as_str(), outcome.cutoff, purged, error).await?;
+            },
+        }
+        outcomes.push(outcome);
+    }
+    Ok(outcomes)
+}
@@ -0,0 +1,89 @@
+//! Where each data class stands against its policy, for `/admin/retention`.
+
+use chrono::{DateTime, Duration, Utc};
+use sqlx::PgPool;
+
+use super::{DataClass, LegalHolds, cutoff};
+use crate::repositories::config::retention::RetentionConfig;
+use crate::repositories::retention::backlog::{self, RetentionBacklog};
+use crate::repositories::retention::{RetentionPurge, list_retention_purges};
+
+/// How far ahead "due soon" looks.
+pub const HORIZON_DAYS: i64 = 7;
+
+#[derive(Debug, Clone)]
+pub struct ClassStatus {
+    pub class: DataClass,
+    /// `None` when the class has no policy and is kept indefinitely.
+    pub retain_days: Option<u32>,
+    pub cutoff: Option<DateTime<Utc>>,
+    pub backlog: Option<RetentionBacklog>,
+    pub last_run: Option<RetentionPurge>,
+}
+
+async fn class_backlog(
+    pool: &PgPool,
+    class: DataClass,
+    cutoff: DateTime<Utc>,
+    holds: &LegalHolds,
+) -> Result<RetentionBacklog, sqlx::Error> {
+    let horizon = cutoff + Duration::days(HORIZON_DAYS);
+    match class {
+        DataClass::AiRequestBodies => {
+            backlog::get_ai_request_bodies_backlog(pool, cutoff, horizon, holds).await
+        },
+        DataClass::AiRequests => {
+            backlog::get_ai_requests_backlog(pool, cutoff, horizon, holds).await
+        },
+        DataClass::HookEventDetails => {
+            backlog::get_hook_event_details_backlog(pool, cutoff, horizon, holds).await
+        },
+        DataClass::HookEvents => {
+            backlog::get_hook_events_backlog(pool, cutoff, horizon, holds).await
+        },
+        DataClass::Transcripts => {
+            backlog::get_transcripts_backlog(pool, cutoff, horizon, holds).await
+        },
+        DataClass::GovernanceDecisions => {
+            backlog::get_governance_decisions_backlog(pool, cutoff, horizon, holds).await
+        },
+        DataClass::LinkClicks => {
+            backlog::get_link_clicks_backlog(pool, cutoff, horizon, holds).await
+        },
+        DataClass::McpToolCaptures => {
+            backlog::get_mcp_tool_captures_backlog(pool, cutoff, horizon, holds).await
+        },
+    }
+}
+
+/// Every class, in [`DataClass::ALL`] order, with its backlog counted only
+/// when it has a policy.
+pub async fn class_statuses(
+    pool: &PgPool,
+    config: &RetentionConfig,
+    now: DateTime<Utc>,
+) -> Result<Vec<ClassStatus>, sqlx::Error> {
+    let holds = LegalHolds::from_config(&config.legal_holds);
+    let mut runs = list_retention_purges(pool).await?;
+    let mut out = Vec::with_capacity(DataClass::ALL.len());
+    for class in DataClass::ALL {
+        let retain_days = config.policies.get(&class).copied();
+        let class_cutoff = retain_days.map(|days| cutoff(now, days));
+        let backlog = match class_cutoff {
+            Some(at) => Some(class_backlog(pool, class, at, &holds).await?),
+            None => None,
+        };
+        let last_run = runs
+            .iter()
+            .position(|r| r.data_class == class.as_str())
+            .map(|i| runs.swap_remove(i));
+        out.push(ClassStatus {
+            class,
+            retain_days,
+            cutoff: class_cutoff,
+            backlog,
+            last_run,
+        });
+    }
+    Ok(out)
+}
@@ -13,16 +13,32 @@ use super::super::{handlers, middleware};
 
 pub(crate) fn build_admin_only_routes(read_pool: &Arc<PgPool>, write_pool: &Arc<PgPool>) -> Router {
     let reads = build_admin_read_routes_inner(read_pool);
+    let data_subject = build_data_subject_read_routes(read_pool);
     let writes = build_admin_write_routes(write_pool);
 
-    reads.merge(writes).layer(axum_middleware::from_fn(
-        middleware::require_admin_middleware,
-    ))
+    reads
+        .merge(data_subject)
+        .merge(writes)
+        .layer(axum_middleware::from_fn(
+            middleware::require_admin_middleware,
+        ))
 }
 
 fn build_admin_read_routes_inner(read_pool: &Arc<PgPool>) -> Router {
     Router::new()
         .route("/gateway", get(handlers::get_gateway_handler))
+        .route(
+            "/gateway/explain",
+            get(handlers::gateway_explain::explain_handler),
+        )
+        .route(
+            "/gateway/revisions",
+            get(handlers::gateway_revisions::list_gateway_revisions_handler),
+        )
+        .route(
+            "/gateway/revisions/{id}",
+            get(handlers::gateway_revisions::get_gateway_revision_handler),
+        )
         .route(
             "/gateway/catalog/for-user/{user_id}",
             get(handlers::gateway_catalog::for_user_handler),
@@ -75,6 +91,27 @@ fn build_admin_read_routes_inner(read_pool: &Arc<PgPool>) -> Router {
             "/management/departments",
             get(handlers::departments::list_departments_handler),
         )
+        .route(
+            "/chargeback/{month}/export",
+            get(handlers::chargeback::export_chargeback_csv_handler),
+        )
+        .with_state(Arc::clone(read_pool))
+}
+
+fn build_data_subject_read_routes(read_pool: &Arc<PgPool>) -> Router {
+    Router::new()
+        .route(
+            "/users/{user_id}/export",
+            get(handlers::erasure::export_user_data_handler),
+        )
+        .route(
+            "/erasures",
+            get(handlers::erasure::list_erasure_certificates_handler),
+        )
+        .route(
+            "/erasures/{id}",
+            get(handlers::erasure::get_erasure_certificate_handler),
+        )
         .with_state(Arc::clone(read_pool))
 }
 
@@ -94,11 +131,19 @@ fn build_admin_write_routes(write_pool: &Arc<PgPool>) -> Router {
             "/gateway/routes/reorder",
             post(handlers::reorder_gateway_routes_handler),
         )
+        .route(
+            "/gateway/revisions/{id}/rollback",
+            post(handlers::gateway_revisions::rollback_gateway_revision_handler),
+        )
         .route("/users", post(handlers::create_user_handler))
         .route(
             "/users/{user_id}",
             put(handlers::update_user_handler).delete(handlers::delete_user_handler),
         )
+        .route(
+            "/users/{user_id}/erasure",
+            post(handlers::erasure::erase_user_handler),
+        )
         .route(
             "/users/{user_id}/share-token",
             post(handlers::share::issue_share_token_handler),
@@ -135,6 +180,12 @@ fn build_admin_write_routes(write_pool: &Arc<PgPool>) -> Router {
             "/access-control/bulk-template",
             post(handlers::entity_access::apply_template_handler),
         )
+        .merge(build_department_write_routes())
+        .with_state(Arc::clone(write_pool))
+}
+
+fn build_department_write_routes() -> Router<Arc<PgPool>> {
+    Router::new()
         .route(
             "/management/departments",
             post(handlers::departments::create_department_handler),
@@ -148,7 +199,10 @@ fn build_admin_write_routes(write_pool: &Arc<PgPool>) -> Router {
             "/management/users/{user_id}/department",
             put(handlers::departments::assign_user_to_department_handler),
         )
-        .with_state(Arc::clone(write_pool))
+        .route(
+            "/chargeback/{month}/close",
+            post(handlers::chargeback::close_chargeback_period_handler),
+        )
 }
 
 pub(crate) fn build_auth_read_routes(read_pool: &Arc<PgPool>) -> Router {
@@ -122,7 +122,17 @@ fn governance_routes() -> Router<Arc<PgPool>> {
             "/governance/hooks",
             get(handlers::ssr::governance_hooks_page),
         )
+        .route("/governance/retention", get(handlers::ssr::retention_page))
         .route("/models", get(handlers::ssr::models_page))
+        .route("/gateway/explain", get(handlers::ssr::gateway_explain_page))
+        .route("/gateway/history", get(handlers::ssr::gateway_history_page))
+        .route("/costs", get(handlers::ssr::cost_watch_page))
+        .route("/chargeback", get(handlers::ssr::chargeback_page))
+        .route(
+            "/chargeback/statement",
+            get(handlers::ssr::chargeback_statement_page),
+        )
+        .route("/cohorts", get(handlers::ssr::cohorts_page))
         .route("/demo/trace", get(handlers::ssr::demo_trace_page))
 }
 
@@ -142,6 +152,7 @@ fn entity_routes() -> Router<Arc<PgPool>> {
             get(handlers::ssr::session_detail_page),
         )
         .route("/entities/traces", get(handlers::ssr::perf_traces_page))
+        .route("/search", get(handlers::ssr::search_page))
         .route(
             "/entities/traces/{trace_id}",
             get(handlers::ssr::perf_trace_detail_page),
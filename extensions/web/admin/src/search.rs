@@ -0,0 +1,113 @@
+//! Full-text search over what users sent and received.
+//!
+//! The indexes are in `schema/23_search.sql` and the query in
+//! `repositories::search`. This module holds the parts that need no
+//! database: which sources can be searched, and turning a Postgres
+//! `ts_headline` into a snippet that is safe to show.
+//!
+//! Snippets are redacted before display, exactly like transcripts on the
+//! session page. When redaction changed the snippet its highlights no longer
+//! line up with the text, so the snippet is shown unhighlighted instead.
+
+use serde::Serialize;
+
+use crate::repositories::analytics::conversations::redact_text;
+
+/// Marks the start of a match in a headline; `StartSel` of the query.
+pub const HIT_START: char = '\u{2}';
+/// Marks the end of a match in a headline; `StopSel` of the query.
+pub const HIT_END: char = '\u{3}';
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchSource {
+    Transcript,
+    Request,
+    Analysis,
+}
+
+impl SearchSource {
+    pub const ALL: [Self; 3] = [Self::Transcript, Self::Request, Self::Analysis];
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|s| s.as_str() == value)
+    }
+
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Transcript => "transcript",
+            Self::Request => "request",
+            Self::Analysis => "analysis",
+        }
+    }
+
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Transcript => "Transcript turn",
+            Self::Request => "Gateway message",
+            Self::Analysis => "Session analysis",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SnippetSegment {
+    pub text: String,
+    pub hit: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Snippet {
+    pub segments: Vec<SnippetSegment>,
+    /// How many secrets were masked; highlights are dropped when non-zero.
+    pub redacted: u32,
+}
+
+fn push_segment(segments: &mut Vec<SnippetSegment>, text: &mut String, hit: bool) {
+    if !text.is_empty() {
+        segments.push(SnippetSegment {
+            text: std::mem::take(text),
+            hit,
+        });
+    }
+}
+
+/// Split a `ts_headline` into plain and matched segments, redacting secrets.
+pub fn snippet_from_headline(headline: &str) -> Snippet {
+    let plain: String = headline
+        .chars()
+        .filter(|c| *c != HIT_START && *c != HIT_END)
+        .collect();
+    let (redacted_text, redacted) = redact_text(&plain);
+    if redacted > 0 {
+        return Snippet {
+            segments: vec![SnippetSegment {
+                text: redacted_text,
+                hit: false,
+            }],
+            redacted,
+        };
+    }
+
+    let mut segments = Vec::new();
+    let mut current = String::new();
+    let mut in_hit = false;
+    for c in headline.chars() {
+        match c {
+            HIT_START => {
+                push_segment(&mut segments, &mut current, in_hit);
+                in_hit = true;
+            },
+            HIT_END => {
+                push_segment(&mut segments, &mut current, in_hit);
+                in_hit = false;
+            },
+            _ => current.push(c),
+        }
+    }
+    push_segment(&mut segments, &mut current, in_hit);
+    Snippet {
+        segments,
+        redacted: 0,
+    }
+}
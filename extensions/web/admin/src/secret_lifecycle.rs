@@ -0,0 +1,289 @@
+//! Secret lifecycle: expiry, disuse and unusual resolutions.
+//!
+//! Each plugin secret may carry an owner and an expiry date, set through
+//! `PUT /admin/api/secrets/{plugin_id}/{var_name}/metadata`, and records when a
+//! plugin last resolved it. The hourly `secret_lifecycle` job runs [`scan`]:
+//! a secret within [`LifecyclePolicy::expiry_warning_days`] of its expiry is
+//! flagged as expiring, one past it as expired, and one no plugin has resolved
+//! for [`LifecyclePolicy::unused_after_days`] as unused, a candidate for
+//! removal. The thresholds are read from
+//! `services/governance/secret_lifecycle.yaml`.
+//!
+//! Every resolution is also judged as it happens ([`check_resolution`])
+//! against the user's history in the secret audit log: a plugin the user has
+//! not resolved from in 30 days, once they have an established history, or a
+//! plugin resolving far above its usual hourly rate, is flagged as unusual.
+//! Flagging never blocks the resolution.
+//!
+//! Alerts land in `secret_alerts`, whose insert trigger publishes them on the
+//! audit bus. Each finding is recorded once, so rescans are free; a secret
+//! given a new expiry, or resolved again and then left unused, can be flagged
+//! anew. The user's profile page lists what needs attention
+//! ([`attention_for_user`]).
+
+use chrono::{DateTime, Duration, DurationRound, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sqlx::PgPool;
+use systemprompt::identifiers::UserId;
+
+use crate::numeric::to_f64;
+use crate::repositories::secrets::secret_lifecycle::{
+    NewSecretAlert, ResolutionBaseline, SecretAlertKind, SecretAlertRow, SecretAttentionRow,
+    get_resolution_baseline, insert_secret_alert, list_recent_resolution_alerts,
+    list_secrets_needing_attention,
+};
+
+/// When a secret is flagged, and what counts as unusual.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields, default)]
+pub struct LifecyclePolicy {
+    pub expiry_warning_days: i64,
+    pub unused_after_days: i64,
+    /// Resolutions the user needs in the last 30 days before a plugin they
+    /// have not used in that time counts as unusual.
+    pub min_history: i64,
+    /// Resolutions from one plugin within an hour below which the rate is
+    /// never unusual.
+    pub rate_floor_per_hour: i64,
+    /// How many times its average hourly rate over the prior week a plugin
+    /// must reach to be unusual.
+    pub rate_multiplier: f64,
+}
+
+impl Default for LifecyclePolicy {
+    fn default() -> Self {
+        Self {
+            expiry_warning_days: 14,
+            unused_after_days: 90,
+            min_history: 5,
+            rate_floor_per_hour: 20,
+            rate_multiplier: 5.0,
+        }
+    }
+}
+
+/// What one scan found.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LifecycleScanReport {
+    pub examined: usize,
+    pub flagged: usize,
+    /// Alerts not already recorded by an earlier run.
+    pub recorded: usize,
+}
+
+/// One secret needing attention, as shown on the profile page.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SecretAttention {
+    pub plugin_id: String,
+    pub var_name: String,
+    pub owner_id: Option<String>,
+    pub kind: SecretAlertKind,
+    pub detail: String,
+}
+
+/// The lifecycle findings for one secret at `now`: expired or expiring, and
+/// unused.
+#[must_use]
+pub fn classify(
+    row: &SecretAttentionRow,
+    now: DateTime<Utc>,
+    policy: &LifecyclePolicy,
+) -> Vec<SecretAlertKind> {
+    let mut kinds = Vec::new();
+    if let Some(expires_at) = row.expires_at {
+        if expires_at <= now {
+            kinds.push(SecretAlertKind::Expired);
+        } else if expires_at <= now + Duration::days(policy.expiry_warning_days) {
+            kinds.push(SecretAlertKind::Expiring);
+        }
+    }
+    if row.last_used_at <= now - Duration::days(policy.unused_after_days) {
+        kinds.push(SecretAlertKind::Unused);
+    }
+    kinds
+}
+
+/// Whether a resolution about to be made from a plugin is unusual, given the
+/// user's history before it.
+#[must_use]
+pub fn detect_anomalies(
+    baseline: &ResolutionBaseline,
+    policy: &LifecyclePolicy,
+) -> Vec<SecretAlertKind> {
+    let mut kinds = Vec::new();
+    if baseline.plugin_30d == 0 && baseline.user_30d >= policy.min_history {
+        kinds.push(SecretAlertKind::UnusualPlugin);
+    }
+    let this_hour = baseline.plugin_last_hour + 1;
+    let usual_hourly = to_f64(baseline.plugin_prior_7d) / (7.0 * 24.0);
+    let unusual_rate = this_hour >= policy.rate_floor_per_hour
+        && to_f64(this_hour) > usual_hourly * policy.rate_multiplier;
+    if unusual_rate {
+        kinds.push(SecretAlertKind::UnusualRate);
+    }
+    kinds
+}
+
+/// A short, human description of a finding.
+#[must_use]
+pub fn describe(row: &SecretAttentionRow, kind: SecretAlertKind, now: DateTime<Utc>) -> String {
+    let days = |at: DateTime<Utc>| (at - now).num_days().abs();
+    match (kind, row.expires_at) {
+        (SecretAlertKind::Expired, Some(at)) => format!("Expired {} days ago", days(at)),
+        (SecretAlertKind::Expiring, Some(at)) => format!("Expires in {} days", days(at)),
+        _ => format!("Not resolved for {} days", days(row.last_used_at)),
+    }
+}
+
+fn lifecycle_alert(
+    row: &SecretAttentionRow,
+    kind: SecretAlertKind,
+    now: DateTime<Utc>,
+) -> NewSecretAlert<'_> {
+    // Why: expiry alerts are keyed on the expiry date, so setting a new one
+    // re-arms the warning; unused alerts on the last use, so a secret that is
+    // used again and then left idle is flagged again.
+    let (dedupe_key, detail) = if kind == SecretAlertKind::Unused {
+        (
+            format!("unused:{}:{}", row.id, row.last_used_at.timestamp()),
+            json!({ "last_used_at": row.last_used_at }),
+        )
+    } else {
+        let expires_at = row.expires_at.unwrap_or(now);
+        (
+            format!("{}:{}:{}", kind.as_str(), row.id, expires_at.timestamp()),
+            json!({ "expires_at": expires_at }),
+        )
+    };
+    NewSecretAlert {
+        user_id: &row.user_id,
+        plugin_id: &row.plugin_id,
+        var_name: &row.var_name,
+        owner_id: row.owner_id.as_deref(),
+        kind,
+        dedupe_key,
+        detail,
+    }
+}
+
+fn attention_window(
+    now: DateTime<Utc>,
+    policy: &LifecyclePolicy,
+) -> (DateTime<Utc>, DateTime<Utc>) {
+    (
+        now + Duration::days(policy.expiry_warning_days),
+        now - Duration::days(policy.unused_after_days),
+    )
+}
+
+/// Flag every secret that has expired, is about to, or has gone unused.
+pub async fn scan(
+    pool: &PgPool,
+    policy: &LifecyclePolicy,
+    now: DateTime<Utc>,
+) -> Result<LifecycleScanReport, sqlx::Error> {
+    let (expiring_before, unused_before) = attention_window(now, policy);
+    let rows = list_secrets_needing_attention(pool, None, expiring_before, unused_before).await?;
+    let mut report = LifecycleScanReport {
+        examined: rows.len(),
+        ..LifecycleScanReport::default()
+    };
+    for row in &rows {
+        for kind in classify(row, now, policy) {
+            report.flagged += 1;
+            if insert_secret_alert(pool, &lifecycle_alert(row, kind, now)).await? {
+                report.recorded += 1;
+            }
+        }
+    }
+    Ok(report)
+}
+
+/// Judge a resolution about to be made by `user_id` from `plugin_id`,
+/// recording any anomaly once per plugin and day (unusual plugin) or hour
+/// (unusual rate).
+pub async fn check_resolution(
+    pool: &PgPool,
+    user_id: &UserId,
+    plugin_id: &str,
+    policy: &LifecyclePolicy,
+    now: DateTime<Utc>,
+) -> Result<Vec<SecretAlertKind>, sqlx::Error> {
+    let baseline = get_resolution_baseline(pool, user_id, plugin_id, now).await?;
+    let kinds = detect_anomalies(&baseline, policy);
+    let hour = now.duration_trunc(Duration::hours(1)).unwrap_or(now);
+    for &kind in &kinds {
+        let dedupe_key = if kind == SecretAlertKind::UnusualPlugin {
+            format!("unusual_plugin:{user_id}:{plugin_id}:{}", now.date_naive())
+        } else {
+            format!("unusual_rate:{user_id}:{plugin_id}:{}", hour.timestamp())
+        };
+        let alert = NewSecretAlert {
+            user_id,
+            plugin_id,
+            var_name: "*",
+            owner_id: None,
+            kind,
+            dedupe_key,
+            detail: json!({
+                "last_hour": baseline.plugin_last_hour + 1,
+                "prior_7d": baseline.plugin_prior_7d,
+                "plugin_30d": baseline.plugin_30d,
+                "user_30d": baseline.user_30d,
+            }),
+        };
+        insert_secret_alert(pool, &alert).await?;
+    }
+    Ok(kinds)
+}
+
+fn describe_alert(alert: &SecretAlertRow) -> String {
+    if alert.kind == SecretAlertKind::UnusualRate.as_str() {
+        let count = alert.detail["last_hour"].as_i64().unwrap_or_default();
+        format!("Resolved {count} times within an hour, well above its usual rate")
+    } else {
+        "Resolved from a plugin not used in the last 30 days".to_owned()
+    }
+}
+
+/// The user's secrets needing attention at `now`, followed by the unusual
+/// resolutions of the last week.
+pub async fn attention_for_user(
+    pool: &PgPool,
+    user_id: &UserId,
+    policy: &LifecyclePolicy,
+    now: DateTime<Utc>,
+) -> Result<Vec<SecretAttention>, sqlx::Error> {
+    let (expiring_before, unused_before) = attention_window(now, policy);
+    let rows =
+        list_secrets_needing_attention(pool, Some(user_id), expiring_before, unused_before).await?;
+    let mut items: Vec<SecretAttention> = rows
+        .iter()
+        .flat_map(|row| {
+            classify(row, now, policy)
+                .into_iter()
+                .map(|kind| SecretAttention {
+                    plugin_id: row.plugin_id.clone(),
+                    var_name: row.var_name.clone(),
+                    owner_id: row.owner_id.clone(),
+                    kind,
+                    detail: describe(row, kind, now),
+                })
+        })
+        .collect();
+
+    let alerts = list_recent_resolution_alerts(pool, user_id, now - Duration::days(7)).await?;
+    items.extend(alerts.iter().map(|alert| SecretAttention {
+        plugin_id: alert.plugin_id.clone(),
+        var_name: alert.var_name.clone(),
+        owner_id: None,
+        kind: if alert.kind == SecretAlertKind::UnusualRate.as_str() {
+            SecretAlertKind::UnusualRate
+        } else {
+            SecretAlertKind::UnusualPlugin
+        },
+        detail: describe_alert(alert),
+    }));
+    Ok(items)
+}
@@ -1,11 +1,22 @@
-//! Plugin JWT validation for the admin handlers.
+//! Bearer checks for the admin handlers: plugin JWTs, and the admin-owned
+//! personal access tokens that machine clients present.
+//!
+//! An identity provider provisioning over SCIM and a Prometheus scraper both
+//! authenticate with a long-lived static bearer, so their credential is a
+//! personal access token rather than a session JWT, and its owner must hold
+//! `admin`. [`authenticate_admin_token`] is generic over the error type each
+//! endpoint answers with.
 
 use axum::http::HeaderMap;
+use sqlx::PgPool;
+use systemprompt::identifiers::UserId;
 use systemprompt::models::Config;
 use systemprompt::models::auth::JwtAudience;
 use systemprompt::oauth::validate_jwt_token;
+use systemprompt_web_access::repositories::users::find_user_identity;
 
 use crate::error::{AdminError, AdminResult};
+use crate::repositories;
 
 pub(crate) fn validate_plugin_jwt(headers: &HeaderMap) -> AdminResult<String> {
     let token = headers
@@ -34,3 +45,38 @@ pub(crate) fn validate_plugin_jwt(headers: &HeaderMap) -> AdminResult<String> {
 
     Ok(claims.sub)
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TokenRejection {
+    Unauthenticated,
+    NotAdmin,
+}
+
+fn bearer(headers: &HeaderMap) -> Option<&str> {
+    let value = headers.get("authorization")?.to_str().ok()?;
+    value
+        .strip_prefix("Bearer ")
+        .or_else(|| value.strip_prefix("bearer "))
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+}
+
+pub(crate) async fn authenticate_admin_token<E>(
+    pool: &PgPool,
+    headers: &HeaderMap,
+) -> Result<UserId, E>
+where
+    E: From<sqlx::Error> + From<TokenRejection>,
+{
+    let secret = bearer(headers).ok_or(TokenRejection::Unauthenticated)?;
+    let owner = repositories::access_tokens::find_api_key_owner(pool, secret)
+        .await?
+        .ok_or(TokenRejection::Unauthenticated)?;
+    let identity = find_user_identity(pool, &owner)
+        .await?
+        .ok_or(TokenRejection::Unauthenticated)?;
+    if !identity.roles.iter().any(|r| r == "admin") {
+        return Err(TokenRejection::NotAdmin.into());
+    }
+    Ok(owner)
+}
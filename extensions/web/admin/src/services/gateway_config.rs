@@ -0,0 +1,167 @@
+//! Versioned writes to the gateway configuration.
+//!
+//! Every change made through the admin API goes through [`apply`]: it requires
+//! the caller's `If-Match` and checks it against the current
+//! [`GatewaySnapshot`] etag, runs
+//! the edit under a process-wide lock, and stores the resulting state as a
+//! revision. Routes are addressed by array index, so a stale etag is the only
+//! thing standing between two admins and an edit landing on the wrong route.
+//! The edit runs on staged copies of the profile and the targets file, which
+//! replace the live files only once it has succeeded, so a failed write
+//! never leaves one file changed without the other.
+//!
+//! Edits made by hand to the profile or the targets file are not lost either:
+//! when the state on disk no longer matches the newest revision, it is stored
+//! as an `external` revision before the new change is applied on top.
+
+use std::path::Path;
+
+use sqlx::PgPool;
+use systemprompt::identifiers::UserId;
+use tokio::sync::Mutex;
+
+use crate::error::{AdminError, AdminResult};
+use crate::handlers::shared;
+use crate::repositories::config::gateway::{
+    GatewaySnapshot, StagedGatewayFiles, get_gateway_snapshot, restore_gateway_snapshot,
+};
+use crate::repositories::config::gateway_revisions::{
+    NewGatewayRevision, find_gateway_revision, find_latest_gateway_etag, insert_gateway_revision,
+};
+
+static WRITE_LOCK: Mutex<()> = Mutex::const_new(());
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct GatewayChange<'a> {
+    pub actor: &'a UserId,
+    pub if_match: Option<&'a str>,
+    pub action: &'a str,
+    pub summary: &'a str,
+}
+
+pub(crate) fn current_etag() -> AdminResult<String> {
+    let snapshot =
+        get_gateway_snapshot(&shared::get_profile_path()?, &shared::get_services_path()?)?;
+    Ok(snapshot.etag())
+}
+
+fn check_if_match(if_match: Option<&str>, current: &str) -> AdminResult<()> {
+    let Some(expected) = if_match.map(str::trim) else {
+        return Err(AdminError::PreconditionRequired(
+            "Send the configuration's ETag in If-Match to change it".to_owned(),
+        ));
+    };
+    if expected == "*" || expected.trim_start_matches("W/").trim_matches('"') == current {
+        return Ok(());
+    }
+    Err(AdminError::PreconditionFailed(
+        "The gateway configuration changed since it was loaded; reload and try again".to_owned(),
+    ))
+}
+
+async fn record_external_edits(pool: &PgPool, current: &GatewaySnapshot) -> AdminResult<()> {
+    let etag = current.etag();
+    if find_latest_gateway_etag(pool).await?.as_deref() == Some(etag.as_str()) {
+        return Ok(());
+    }
+    insert_gateway_revision(
+        pool,
+        NewGatewayRevision {
+            snapshot: current,
+            action: "external",
+            summary: "Configuration as found on disk",
+            actor_id: None,
+            restored_from: None,
+        },
+    )
+    .await?;
+    Ok(())
+}
+
+async fn apply_inner<R>(
+    pool: &PgPool,
+    change: GatewayChange<'_>,
+    restored_from: Option<i64>,
+    edit: impl FnOnce(&Path, &Path) -> AdminResult<(R, Option<String>)>,
+) -> AdminResult<(R, String)> {
+    let profile_path = shared::get_profile_path()?;
+    let services_path = shared::get_services_path()?;
+    let _guard = WRITE_LOCK.lock().await;
+
+    let before = get_gateway_snapshot(&profile_path, &services_path)?;
+    check_if_match(change.if_match, &before.etag())?;
+    record_external_edits(pool, &before).await?;
+
+    let staged = StagedGatewayFiles::stage(&profile_path, &services_path)?;
+    let (result, summary) = edit(staged.profile_path(), staged.services_path())?;
+    let after = get_gateway_snapshot(staged.profile_path(), staged.services_path())?;
+    staged.land()?;
+    if after != before {
+        insert_gateway_revision(
+            pool,
+            NewGatewayRevision {
+                snapshot: &after,
+                action: change.action,
+                summary: summary.as_deref().unwrap_or(change.summary),
+                actor_id: Some(change.actor),
+                restored_from,
+            },
+        )
+        .await?;
+    }
+    Ok((result, after.etag()))
+}
+
+pub(crate) async fn apply<R>(
+    pool: &PgPool,
+    change: GatewayChange<'_>,
+    edit: impl FnOnce(&Path, &Path) -> AdminResult<R>,
+) -> AdminResult<(R, String)> {
+    apply_inner(pool, change, None, |profile, services| {
+        edit(profile, services).map(|result| (result, None))
+    })
+    .await
+}
+
+// Why: a summary that names what the edit found on disk has to be read under
+// the same lock as the edit, so `edit` returns it and `change.summary` is only
+// the fallback.
+pub(crate) async fn apply_described<R>(
+    pool: &PgPool,
+    change: GatewayChange<'_>,
+    edit: impl FnOnce(&Path, &Path) -> AdminResult<(R, String)>,
+) -> AdminResult<(R, String)> {
+    apply_inner(pool, change, None, |profile, services| {
+        edit(profile, services).map(|(result, summary)| (result, Some(summary)))
+    })
+    .await
+}
+
+pub(crate) async fn rollback(
+    pool: &PgPool,
+    actor: &UserId,
+    if_match: Option<&str>,
+    id: i64,
+) -> AdminResult<String> {
+    let revision = find_gateway_revision(pool, id)
+        .await?
+        .ok_or_else(|| AdminError::NotFound(format!("Revision #{id} not found")))?;
+    let snapshot = GatewaySnapshot {
+        gateway_yaml: revision.gateway_yaml,
+        targets_yaml: revision.targets_yaml,
+    };
+    let summary = format!("Rolled back to revision #{id}");
+    let change = GatewayChange {
+        actor,
+        if_match,
+        action: "rollback",
+        summary: &summary,
+    };
+    let ((), etag) = apply_inner(pool, change, Some(id), |profile, services| {
+        restore_gateway_snapshot(profile, services, &snapshot)
+            .map(|()| ((), None))
+            .map_err(AdminError::from)
+    })
+    .await?;
+    Ok(etag)
+}
@@ -3,7 +3,10 @@
 pub(crate) mod access_token_service;
 pub(crate) mod auth;
 pub(crate) mod evals;
+pub(crate) mod gateway_config;
 pub(crate) mod jobs_service;
 pub(crate) mod marketplaces;
+pub(crate) mod route_explain;
+pub(crate) mod scim;
 pub(crate) mod secret_service;
 pub(crate) mod user_profile;
@@ -0,0 +1,273 @@
+//! "What happens to model X for user Y": the route explainer.
+//!
+//! Walks the stages a gateway request passes through, in the order the
+//! gateway runs them, using the live configuration: core's route selection
+//! over the profile routes and the `default_provider` fallback, the upstream
+//! rewrite and targets, the pre-dispatch access decision (same resolver, rules
+//! and marketplace parents as `/govern/authz`), then the quota windows,
+//! governance chain and safety scanners from the policy core resolves. Nothing
+//! is sent upstream and nothing is audited.
+//!
+//! Routes are judged against a plain request for the model: no tools, no
+//! extended thinking, not streamed. A route whose `when` conditions need a
+//! different request shape shows as unmet, with the conditions it sets.
+//!
+//! Roles come from the `users` row; a token minted before a role change
+//! carries the old roles until it is refreshed, so the live answer can lag
+//! this one by one token lifetime.
+
+use std::sync::Arc;
+
+use sqlx::PgPool;
+use systemprompt::ai::GatewayPolicySpec;
+use systemprompt::ai::repository::AiGatewayPolicyRepository;
+use systemprompt::api::services::gateway::policy::PolicyResolver;
+use systemprompt::database::{Database, DbPool};
+use systemprompt::identifiers::{RouteId, UserId};
+use systemprompt::models::profile::GatewayRoute;
+use systemprompt::models::wire::canonical::CanonicalRequest;
+use systemprompt_security::authz::{
+    AccessControlRepository, Decision, EntityRef, MatchedBy, ResolveInput, ResolveParent,
+};
+
+use crate::authz::{dimensions, subject_attributes_for};
+use crate::error::{AdminError, AdminResult};
+use crate::handlers::shared;
+use crate::handlers::webhook::governance::{engine, marketplace_parent_entries};
+use crate::repositories;
+use crate::repositories::config::gateway::{
+    explain_route_match, get_resolved_gateway, load_route_targets, mask_header_value,
+};
+use crate::repositories::config::gateway_acl::{self, find_deciding_rule};
+use crate::types::{
+    AuthzExplanation, DecidingRule, ExplainSubject, GovernanceStep, MaskedHeader,
+    MatchedRouteExplanation, RouteExplanation, SafetyExplanation,
+};
+
+#[derive(Debug, Clone)]
+pub(crate) enum ExplainFor {
+    User(UserId),
+    /// A personal access token prefix, or the whole token.
+    Token(String),
+}
+
+async fn resolve_subject(pool: &PgPool, who: ExplainFor) -> AdminResult<ExplainSubject> {
+    let (user_id, token_name, token_live) = match who {
+        ExplainFor::User(user_id) => (user_id, None, None),
+        ExplainFor::Token(token) => {
+            let owner = repositories::access_tokens::find_api_key_by_prefix(pool, &token)
+                .await?
+                .ok_or_else(|| {
+                    AdminError::NotFound("No access token with that prefix".to_owned())
+                })?;
+            (owner.user_id, Some(owner.name), Some(owner.live))
+        },
+    };
+    let (roles, department) =
+        repositories::users::queries::find_user_roles_department(pool, &user_id)
+            .await?
+            .ok_or_else(|| AdminError::NotFound("User not found".to_owned()))?;
+    Ok(ExplainSubject {
+        user_id,
+        roles,
+        department,
+        token_name,
+        token_live,
+    })
+}
+
+fn describe(decision: &Decision) -> String {
+    match decision {
+        Decision::Allow { matched_by } => match matched_by {
+            MatchedBy::UserAllow => "Allowed by a rule naming this user".to_owned(),
+            MatchedBy::RoleAllow { role } => format!("Allowed by role {role}"),
+            MatchedBy::AttributeAllow { rule_type, value } => {
+                format!("Allowed by {rule_type} {value}")
+            },
+            MatchedBy::DefaultIncluded => {
+                "Allowed: no rule matched and the route is included by default".to_owned()
+            },
+            MatchedBy::PolicyAllow { detail, .. } => detail.to_string(),
+        },
+        Decision::Deny { reason } => reason.to_string(),
+    }
+}
+
+async fn explain_authz(
+    pool: &Arc<PgPool>,
+    route_id: &str,
+    subject: &ExplainSubject,
+) -> AdminResult<AuthzExplanation> {
+    let rules = gateway_acl::list_rules_for_route(pool, route_id).await?;
+    let default_included = gateway_acl::find_entity(pool, route_id)
+        .await?
+        .map(|e| e.default_included);
+    let parent_entries =
+        marketplace_parent_entries(&AccessControlRepository::from_pool(Arc::clone(pool))).await;
+    let parents: Vec<ResolveParent<'_>> = parent_entries
+        .iter()
+        .map(|(entity, rules, default_included)| ResolveParent {
+            entity,
+            rules,
+            default_included: *default_included,
+        })
+        .collect();
+    let attributes = subject_attributes_for(pool, &subject.user_id).await;
+    let entity = EntityRef::GatewayRoute(RouteId::new(route_id.to_owned()));
+    let decision = gateway_acl::resolve(ResolveInput {
+        entity: &entity,
+        rules: &rules,
+        user_id: &subject.user_id,
+        user_roles: &subject.roles,
+        default_included,
+        parents: &parents,
+        attributes: &attributes,
+        dimensions: dimensions(pool),
+    });
+
+    let deciding_rule = find_deciding_rule(&rules, &decision, &subject.user_id)
+        .map(|rule| (entity.to_string(), rule))
+        .or_else(|| {
+            // Why: the resolver only consults marketplace parents when the
+            // route has no rules of its own.
+            if !rules.is_empty() {
+                return None;
+            }
+            parent_entries.iter().find_map(|(parent, parent_rules, _)| {
+                find_deciding_rule(parent_rules, &decision, &subject.user_id)
+                    .map(|rule| (parent.to_string(), rule))
+            })
+        })
+        .map(|(entity, rule)| DecidingRule {
+            entity,
+            rule: rule.clone(),
+        });
+
+    let (allowed, matched_by) = match &decision {
+        Decision::Allow { matched_by } => (true, Some(matched_by.clone())),
+        Decision::Deny { .. } => (false, None),
+    };
+    Ok(AuthzExplanation {
+        allowed,
+        reason: describe(&decision),
+        matched_by,
+        deciding_rule,
+        route_rules: rules,
+        default_included,
+    })
+}
+
+fn explain_route(route: &GatewayRoute, model: &str) -> AdminResult<MatchedRouteExplanation> {
+    let targets = load_route_targets(&shared::get_services_path()?)?;
+    let upstream_model = route.effective_upstream_model(model).to_owned();
+    let mut extra_headers: Vec<MaskedHeader> = route
+        .extra_headers
+        .iter()
+        .map(|(name, value)| MaskedHeader {
+            name: name.clone(),
+            value: mask_header_value(value),
+        })
+        .collect();
+    extra_headers.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(MatchedRouteExplanation {
+        id: route.id.as_str().to_owned(),
+        provider: route.provider.as_str().to_owned(),
+        rewritten: upstream_model != model,
+        upstream_model,
+        extra_headers,
+        targets: targets.targets_for(route.id.as_str()).to_vec(),
+    })
+}
+
+async fn effective_policy(pool: &Arc<PgPool>) -> AdminResult<(GatewayPolicySpec, Vec<String>)> {
+    let db: DbPool = Arc::new(Database::from_pools(Arc::clone(pool), None));
+    let repository =
+        || AiGatewayPolicyRepository::new(&db).map_err(|e| AdminError::Internal(Box::new(e)));
+    let names = repository()?
+        .list_for_global()
+        .await
+        .map_err(|e| AdminError::Internal(Box::new(e)))?
+        .into_iter()
+        .map(|row| row.name)
+        .collect();
+    // Why: the resolver runs core's merge, so quota and safety here are what
+    // the gateway enforces, including its fallback to permissive on error.
+    let spec = PolicyResolver::from_repository(repository()?)
+        .resolve()
+        .await;
+    Ok((spec, names))
+}
+
+fn governance_chain() -> Vec<GovernanceStep> {
+    engine()
+        .policies()
+        .map(|(cfg, policy)| GovernanceStep {
+            id: policy.id().as_str().to_owned(),
+            name: policy.name().to_owned(),
+            enabled: cfg.enabled,
+        })
+        .collect()
+}
+
+pub(crate) async fn explain(
+    pool: &Arc<PgPool>,
+    model: &str,
+    who: ExplainFor,
+) -> AdminResult<RouteExplanation> {
+    let model = model.trim();
+    if model.is_empty() {
+        return Err(AdminError::BadRequest("A model id is required".to_owned()));
+    }
+    let subject = resolve_subject(pool, who).await?;
+    let (config, registry) = get_resolved_gateway(&shared::get_profile_path()?)?;
+    let request = CanonicalRequest {
+        model: model.to_owned(),
+        ..CanonicalRequest::default()
+    };
+    let route_steps = explain_route_match(&config, &registry, &request);
+
+    let (route, authz) = match config.resolve_route(&registry, &request) {
+        Some(r) => {
+            let mut r = r.into_owned();
+            r.ensure_id();
+            (
+                Some(explain_route(&r, model)?),
+                Some(explain_authz(pool, r.id.as_str(), &subject).await?),
+            )
+        },
+        None => (None, None),
+    };
+
+    let (policy, policy_names) = effective_policy(pool).await?;
+    let verdict = match (&route, &authz) {
+        _ if !config.enabled => "The gateway is disabled in this profile.".to_owned(),
+        (None, _) => format!(
+            "No route matches {model} and the profile has no usable default provider; the gateway answers 404."
+        ),
+        (Some(_), Some(a)) if !a.allowed => {
+            format!("Refused with 403 before dispatch: {}", a.reason)
+        },
+        (Some(r), _) => format!(
+            "Dispatched to {} as {}, subject to quota, governance and safety on each request.",
+            r.provider, r.upstream_model
+        ),
+    };
+
+    Ok(RouteExplanation {
+        model: model.to_owned(),
+        subject,
+        route_steps,
+        route,
+        authz,
+        quota_windows: policy.quota_windows,
+        policy_names,
+        safety: SafetyExplanation {
+            scanners: policy.safety.scanners,
+            block_categories: policy.safety.block_categories,
+            block_response_categories: policy.safety.block_response_categories,
+            history: policy.safety.history,
+        },
+        governance: governance_chain(),
+        verdict,
+    })
+}
@@ -0,0 +1,24 @@
+//! The feature switch in `scim.yaml`, checked before every SCIM request.
+//!
+//! The identity provider's bearer token is checked by
+//! [`crate::services::auth::authenticate_admin_token`]: provisioning can grant
+//! any role, so its owner must hold `admin`.
+
+use std::path::PathBuf;
+
+use systemprompt::config::ProfileBootstrap;
+
+use super::ScimError;
+use crate::repositories;
+use crate::repositories::config::scim::ScimConfig;
+
+pub(crate) fn load_enabled_config() -> Result<ScimConfig, ScimError> {
+    let services_path = ProfileBootstrap::get()
+        .map(|p| PathBuf::from(&p.paths.services))
+        .map_err(|e| ScimError::internal(&e))?;
+    let config = repositories::config::scim::load_scim_config(&services_path)?;
+    if !config.enabled {
+        return Err(ScimError::not_found("SCIM provisioning is not enabled"));
+    }
+    Ok(config)
+}
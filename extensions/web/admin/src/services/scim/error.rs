@@ -0,0 +1,155 @@
+//! The SCIM endpoints' error type.
+//!
+//! identity providers parse the RFC 7644 §3.12 error body and key retries and
+//! conflict handling off `scimType`, so these endpoints answer in that shape
+//! rather than with [`crate::error::AdminError`]'s `{"error": ...}`.
+//! Server-side failures are logged here, once, and reach the wire as a bare
+//! 500.
+
+use axum::Json;
+use axum::http::{HeaderValue, StatusCode, header};
+use axum::response::{IntoResponse, Response};
+use systemprompt_web_shared::error::MarketplaceError;
+
+use super::filter::ScimFilterError;
+use super::patch::ScimPatchError;
+use crate::repositories::access_tokens::AccessTokenRepoError;
+use crate::services::auth::TokenRejection;
+use crate::types::scim::{SCHEMA_ERROR, SCIM_CONTENT_TYPE, ScimErrorBody};
+
+const PG_UNIQUE_VIOLATION: &str = "23505";
+
+#[derive(Debug)]
+pub struct ScimError {
+    status: StatusCode,
+    scim_type: Option<&'static str>,
+    detail: String,
+}
+
+impl ScimError {
+    #[must_use]
+    pub(crate) fn not_found(detail: impl Into<String>) -> Self {
+        Self {
+            status: StatusCode::NOT_FOUND,
+            scim_type: None,
+            detail: detail.into(),
+        }
+    }
+
+    #[must_use]
+    pub(crate) fn invalid_value(detail: impl Into<String>) -> Self {
+        Self {
+            status: StatusCode::BAD_REQUEST,
+            scim_type: Some("invalidValue"),
+            detail: detail.into(),
+        }
+    }
+
+    #[must_use]
+    pub(crate) fn uniqueness(detail: impl Into<String>) -> Self {
+        Self {
+            status: StatusCode::CONFLICT,
+            scim_type: Some("uniqueness"),
+            detail: detail.into(),
+        }
+    }
+
+    #[must_use]
+    pub(crate) fn unauthorized() -> Self {
+        Self {
+            status: StatusCode::UNAUTHORIZED,
+            scim_type: None,
+            detail: "A live personal access token is required".to_owned(),
+        }
+    }
+
+    #[must_use]
+    pub(crate) fn forbidden(detail: impl Into<String>) -> Self {
+        Self {
+            status: StatusCode::FORBIDDEN,
+            scim_type: None,
+            detail: detail.into(),
+        }
+    }
+
+    #[must_use]
+    pub(crate) fn internal(err: &dyn std::error::Error) -> Self {
+        tracing::error!(error = %err, "SCIM request failed");
+        Self {
+            status: StatusCode::INTERNAL_SERVER_ERROR,
+            scim_type: None,
+            detail: "Internal server error".to_owned(),
+        }
+    }
+}
+
+impl From<sqlx::Error> for ScimError {
+    fn from(value: sqlx::Error) -> Self {
+        match &value {
+            sqlx::Error::Database(db) if db.code().as_deref() == Some(PG_UNIQUE_VIOLATION) => {
+                Self::uniqueness("A resource with that identifier already exists")
+            },
+            _ => Self::internal(&value),
+        }
+    }
+}
+
+impl From<MarketplaceError> for ScimError {
+    fn from(value: MarketplaceError) -> Self {
+        Self::internal(&value)
+    }
+}
+
+impl From<AccessTokenRepoError> for ScimError {
+    fn from(value: AccessTokenRepoError) -> Self {
+        Self::internal(&value)
+    }
+}
+
+impl From<TokenRejection> for ScimError {
+    fn from(value: TokenRejection) -> Self {
+        match value {
+            TokenRejection::Unauthenticated => Self::unauthorized(),
+            TokenRejection::NotAdmin => Self::forbidden("The token owner must hold the admin role"),
+        }
+    }
+}
+
+impl From<ScimFilterError> for ScimError {
+    fn from(value: ScimFilterError) -> Self {
+        Self {
+            status: StatusCode::BAD_REQUEST,
+            scim_type: Some("invalidFilter"),
+            detail: value.to_string(),
+        }
+    }
+}
+
+impl From<ScimPatchError> for ScimError {
+    fn from(value: ScimPatchError) -> Self {
+        Self::invalid_value(value.0)
+    }
+}
+
+impl IntoResponse for ScimError {
+    fn into_response(self) -> Response {
+        if self.status.is_client_error() {
+            tracing::warn!(status = %self.status, detail = %self.detail, "SCIM request rejected");
+        }
+        let body = ScimErrorBody {
+            schemas: [SCHEMA_ERROR],
+            status: self.status.as_u16().to_string(),
+            scim_type: self.scim_type,
+            detail: self.detail,
+        };
+        (
+            self.status,
+            [(
+                header::CONTENT_TYPE,
+                HeaderValue::from_static(SCIM_CONTENT_TYPE),
+            )],
+            Json(body),
+        )
+            .into_response()
+    }
+}
@@ -0,0 +1,47 @@
+//! The slice of the RFC 7644 §3.4.2.2 filter grammar identity providers use
+//! to look a resource up before creating it: a single `<attr> eq "<value>"`.
+//!
+//! Okta, Entra ID, and `OneLogin` all probe with exactly this form
+//! (`userName eq "a@b.com"`, `displayName eq "Engineering"`). Anything richer
+//! is answered with `invalidFilter` rather than being half-evaluated, so an
+//! identity provider never mistakes an unsupported query for "no such resource"
+//! and creates a duplicate.
+
+/// A parsed equality filter. `attribute` is lower-cased: SCIM attribute names
+/// are case-insensitive.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScimFilter {
+    pub attribute: String,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("unsupported filter: {0}")]
+pub struct ScimFilterError(pub String);
+
+pub fn parse_filter(raw: &str) -> Result<ScimFilter, ScimFilterError> {
+    let unsupported = || ScimFilterError(raw.to_owned());
+    let trimmed = raw.trim();
+    let (attribute, rest) = trimmed
+        .split_once(char::is_whitespace)
+        .ok_or_else(unsupported)?;
+    let (op, operand) = rest
+        .trim_start()
+        .split_once(char::is_whitespace)
+        .ok_or_else(unsupported)?;
+    if !op.eq_ignore_ascii_case("eq") {
+        return Err(unsupported());
+    }
+    let value = operand
+        .trim()
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .ok_or_else(unsupported)?;
+    if value.contains('"') || attribute.is_empty() {
+        return Err(unsupported());
+    }
+    Ok(ScimFilter {
+        attribute: attribute.to_ascii_lowercase(),
+        value: value.to_owned(),
+    })
+}
@@ -0,0 +1,146 @@
+//! Group provisioning. Every membership change ends by recomputing the roles
+//! and department of each user it touched.
+//!
+//! `PUT` carries the whole group, so membership is replaced outright. A
+//! rename re-resolves every member too, since mappings key on the name, and
+//! deleting a group recomputes its former members without it.
+
+use sqlx::PgPool;
+use systemprompt::identifiers::UserId;
+
+use super::ScimError;
+use super::patch::interpret_group_patch;
+use super::resources::{ScimPage, group_resource};
+use super::users::recompute_assignment;
+use crate::repositories::config::scim::ScimConfig;
+use crate::repositories::scim::groups;
+use crate::types::scim::{
+    ScimGroupInput, ScimGroupResource, ScimGroupRow, ScimListResponse, ScimMemberChange,
+    ScimPatchOperation,
+};
+
+async fn require_group(pool: &PgPool, id: &str) -> Result<ScimGroupRow, ScimError> {
+    groups::find_scim_group(pool, id)
+        .await?
+        .ok_or_else(|| ScimError::not_found("Group not found"))
+}
+
+async fn recompute_all(
+    pool: &PgPool,
+    config: &ScimConfig,
+    touched: &[UserId],
+) -> Result<(), ScimError> {
+    for user_id in touched {
+        recompute_assignment(pool, config, user_id).await?;
+    }
+    Ok(())
+}
+
+fn display_name_of(raw: &str) -> Result<&str, ScimError> {
+    let trimmed = raw.trim();
+    if trimmed.is_empty() {
+        return Err(ScimError::invalid_value("displayName must not be empty"));
+    }
+    Ok(trimmed)
+}
+
+fn member_ids(input: &ScimGroupInput) -> Vec<String> {
+    input.members.iter().map(|m| m.value.clone()).collect()
+}
+
+pub(crate) async fn list_groups(
+    pool: &PgPool,
+    page: &ScimPage,
+) -> Result<ScimListResponse<ScimGroupResource>, ScimError> {
+    let supported = ["displayname", "externalid"];
+    let display_name = page.filter_on("displayname", &supported)?;
+    let external_id = page.filter_on("externalid", &supported)?;
+    let total = groups::get_scim_group_count(pool, display_name, external_id).await?;
+    let rows = groups::list_scim_groups(pool, display_name, external_id, page.offset(), page.count)
+        .await?;
+    let mut resources = Vec::with_capacity(rows.len());
+    for row in rows {
+        resources.push(group_resource(pool, row).await?);
+    }
+    Ok(page.respond(total, resources))
+}
+
+pub(crate) async fn get_group(pool: &PgPool, id: &str) -> Result<ScimGroupResource, ScimError> {
+    let row = require_group(pool, id).await?;
+    group_resource(pool, row).await
+}
+
+pub(crate) async fn create_group(
+    pool: &PgPool,
+    config: &ScimConfig,
+    input: &ScimGroupInput,
+) -> Result<ScimGroupResource, ScimError> {
+    let display_name = display_name_of(&input.display_name)?;
+    let id = uuid::Uuid::new_v4().to_string();
+    groups::create_scim_group(pool, &id, display_name, input.external_id.as_deref()).await?;
+    let changes = [ScimMemberChange::Add(member_ids(input))];
+    let touched = groups::apply_member_changes(pool, &id, &changes).await?;
+    recompute_all(pool, config, &touched).await?;
+    get_group(pool, &id).await
+}
+
+pub(crate) async fn replace_group(
+    pool: &PgPool,
+    config: &ScimConfig,
+    id: &str,
+    input: &ScimGroupInput,
+) -> Result<ScimGroupResource, ScimError> {
+    let display_name = display_name_of(&input.display_name)?;
+    let before = groups::list_group_members(pool, id).await?;
+    groups::update_scim_group(pool, id, Some(display_name), input.external_id.as_deref())
+        .await?
+        .ok_or_else(|| ScimError::not_found("Group not found"))?;
+    let changes = [ScimMemberChange::Replace(member_ids(input))];
+    let mut touched = groups::apply_member_changes(pool, id, &changes).await?;
+    touched.extend(before.into_iter().map(|m| m.user_id));
+    recompute_all(pool, config, &touched).await?;
+    get_group(pool, id).await
+}
+
+pub(crate) async fn patch_group(
+    pool: &PgPool,
+    config: &ScimConfig,
+    id: &str,
+    ops: &[ScimPatchOperation],
+) -> Result<ScimGroupResource, ScimError> {
+    let patch = interpret_group_patch(ops)?;
+    let current = require_group(pool, id).await?;
+    let renamed = patch
+        .display_name
+        .as_deref()
+        .map(display_name_of)
+        .transpose()?
+        .filter(|name| *name != current.display_name);
+    if renamed.is_some() || patch.external_id.is_some() {
+        groups::update_scim_group(pool, id, renamed, patch.external_id.as_deref()).await?;
+    }
+    let mut touched = groups::apply_member_changes(pool, id, &patch.members).await?;
+    if renamed.is_some() {
+        touched.extend(
+            groups::list_group_members(pool, id)
+                .await?
+                .into_iter()
+                .map(|m| m.user_id),
+        );
+    }
+    recompute_all(pool, config, &touched).await?;
+    get_group(pool, id).await
+}
+
+pub(crate) async fn delete_group(
+    pool: &PgPool,
+    config: &ScimConfig,
+    id: &str,
+) -> Result<(), ScimError> {
+    let members = groups::list_group_members(pool, id).await?;
+    if !groups::delete_scim_group(pool, id).await? {
+        return Err(ScimError::not_found("Group not found"));
+    }
+    let touched: Vec<UserId> = members.into_iter().map(|m| m.user_id).collect();
+    recompute_all(pool, config, &touched).await
+}
@@ -0,0 +1,17 @@
+//! SCIM 2.0 provisioning (RFC 7643/7644) for `/scim/v2/Users` and
+//! `/scim/v2/Groups`.
+//!
+//! The identity provider owns every user it provisions. Group membership is the
+//! only input to a managed user's roles and department; `scim.yaml` maps one to
+//! the other, and [`users::recompute_assignment`] re-applies it after any
+//! change.
+
+pub(crate) mod auth;
+pub(crate) mod error;
+pub(crate) mod filter;
+pub(crate) mod groups;
+pub(crate) mod patch;
+pub(crate) mod resources;
+pub(crate) mod users;
+
+pub(crate) use error::ScimError;
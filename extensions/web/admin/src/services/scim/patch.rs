@@ -0,0 +1,202 @@
+//! Interpretation of RFC 7644 §3.5.2 `PatchOp` bodies into typed changes.
+//!
+//! identity providers disagree on the shape of an otherwise identical change:
+//! Entra ID sends `{"op":"Replace","path":"active","value":"False"}`, Okta
+//! sends `{"op":"replace","value":{"active":false}}`. Both collapse to the same
+//! [`ScimUserPatch`] here, so the repository layer sees one form.
+//!
+//! Attributes this platform does not store are dropped, matching how `POST`
+//! and `PUT` treat them; an operation this platform cannot carry out at all
+//! is an error.
+
+use serde_json::Value;
+
+use crate::types::scim::{ScimMemberChange, ScimPatchOperation};
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("{0}")]
+pub struct ScimPatchError(pub String);
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScimUserPatch {
+    pub active: Option<bool>,
+    pub display_name: Option<String>,
+    pub user_name: Option<String>,
+    pub external_id: Option<String>,
+    pub email: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScimGroupPatch {
+    pub display_name: Option<String>,
+    pub external_id: Option<String>,
+    pub members: Vec<ScimMemberChange>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Add,
+    Replace,
+    Remove,
+}
+
+fn op_of(raw: &str) -> Result<Op, ScimPatchError> {
+    match raw.to_ascii_lowercase().as_str() {
+        "add" => Ok(Op::Add),
+        "replace" => Ok(Op::Replace),
+        "remove" => Ok(Op::Remove),
+        other => Err(ScimPatchError(format!("unsupported op '{other}'"))),
+    }
+}
+
+fn as_text(value: &Value) -> Option<String> {
+    value.as_str().map(str::to_owned)
+}
+
+fn as_bool(value: &Value) -> Option<bool> {
+    value.as_bool().or_else(|| {
+        value
+            .as_str()
+            .and_then(|s| s.trim().to_ascii_lowercase().parse().ok())
+    })
+}
+
+fn email_from(value: &Value) -> Option<String> {
+    let emails = value.as_array()?;
+    emails
+        .iter()
+        .find(|e| e.get("primary").and_then(as_bool) == Some(true))
+        .or_else(|| emails.first())
+        .and_then(|e| e.get("value"))
+        .and_then(as_text)
+}
+
+fn apply_user_attr(patch: &mut ScimUserPatch, attr: &str, value: &Value) {
+    match attr.to_ascii_lowercase().as_str() {
+        "active" => patch.active = as_bool(value).or(patch.active),
+        "displayname" | "name.formatted" => patch.display_name = as_text(value),
+        "username" => patch.user_name = as_text(value),
+        "externalid" => patch.external_id = as_text(value),
+        "emails" => patch.email = email_from(value).or_else(|| patch.email.take()),
+        p if p.starts_with("emails[") && p.ends_with("].value") => patch.email = as_text(value),
+        "name" => {
+            if let Some(formatted) = value.get("formatted").and_then(as_text) {
+                patch.display_name = Some(formatted);
+            }
+        },
+        _ => {},
+    }
+}
+
+pub fn interpret_user_patch(ops: &[ScimPatchOperation]) -> Result<ScimUserPatch, ScimPatchError> {
+    let mut patch = ScimUserPatch::default();
+    for operation in ops {
+        if op_of(&operation.op)? == Op::Remove {
+            return Err(ScimPatchError(
+                "remove is not supported on user attributes; send active=false to deprovision"
+                    .to_owned(),
+            ));
+        }
+        let value = operation
+            .value
+            .as_ref()
+            .ok_or_else(|| ScimPatchError("add/replace requires a value".to_owned()))?;
+        match (operation.path.as_deref(), value.as_object()) {
+            (Some(attr), _) => apply_user_attr(&mut patch, attr, value),
+            (None, Some(attrs)) => {
+                for (key, v) in attrs {
+                    apply_user_attr(&mut patch, key, v);
+                }
+            },
+            (None, None) => {
+                return Err(ScimPatchError(
+                    "a patch without a path needs an object value".to_owned(),
+                ));
+            },
+        }
+    }
+    Ok(patch)
+}
+
+fn member_ids(value: &Value) -> Vec<String> {
+    value
+        .as_array()
+        .map(|members| {
+            members
+                .iter()
+                .filter_map(|m| m.get("value").and_then(as_text))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn filtered_member_id(path: &str) -> Option<String> {
+    let inner = path
+        .strip_prefix("members[")
+        .or_else(|| path.strip_prefix("Members["))?
+        .strip_suffix(']')?;
+    let filter = super::filter::parse_filter(inner).ok()?;
+    (filter.attribute == "value").then_some(filter.value)
+}
+
+fn apply_group_attr(
+    patch: &mut ScimGroupPatch,
+    op: Op,
+    attr: &str,
+    value: Option<&Value>,
+) -> Result<(), ScimPatchError> {
+    if let Some(id) = filtered_member_id(attr) {
+        return match op {
+            Op::Remove => {
+                patch.members.push(ScimMemberChange::Remove(vec![id]));
+                Ok(())
+            },
+            Op::Add | Op::Replace => Err(ScimPatchError(
+                "a member filter is only valid with remove".to_owned(),
+            )),
+        };
+    }
+    match (attr.to_ascii_lowercase().as_str(), op) {
+        ("members", Op::Add) => {
+            let ids = value.map(member_ids).unwrap_or_default();
+            patch.members.push(ScimMemberChange::Add(ids));
+        },
+        ("members", Op::Replace) => {
+            let ids = value.map(member_ids).unwrap_or_default();
+            patch.members.push(ScimMemberChange::Replace(ids));
+        },
+        ("members", Op::Remove) => patch.members.push(value.map_or_else(
+            || ScimMemberChange::Replace(Vec::new()),
+            |v| ScimMemberChange::Remove(member_ids(v)),
+        )),
+        ("displayname", Op::Add | Op::Replace) => {
+            patch.display_name = value.and_then(as_text);
+        },
+        ("externalid", Op::Add | Op::Replace) => {
+            patch.external_id = value.and_then(as_text);
+        },
+        _ => {},
+    }
+    Ok(())
+}
+
+pub fn interpret_group_patch(ops: &[ScimPatchOperation]) -> Result<ScimGroupPatch, ScimPatchError> {
+    let mut patch = ScimGroupPatch::default();
+    for operation in ops {
+        let op = op_of(&operation.op)?;
+        match (operation.path.as_deref(), operation.value.as_ref()) {
+            (Some(attr), value) => apply_group_attr(&mut patch, op, attr, value)?,
+            (None, Some(Value::Object(attrs))) => {
+                for (key, v) in attrs {
+                    apply_group_attr(&mut patch, op, key, Some(v))?;
+                }
+            },
+            (None, _) => {
+                return Err(ScimPatchError(
+                    "a patch without a path needs an object value".to_owned(),
+                ));
+            },
+        }
+    }
+    Ok(patch)
+}
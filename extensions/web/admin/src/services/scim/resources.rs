@@ -0,0 +1,141 @@
+//! Rendering stored rows as SCIM resources, and list paging.
+
+use sqlx::PgPool;
+use systemprompt::models::Config;
+
+use super::ScimError;
+use super::filter::{ScimFilter, parse_filter};
+use crate::repositories::scim::{groups, users};
+use crate::types::scim::{
+    SCHEMA_GROUP, SCHEMA_LIST_RESPONSE, SCHEMA_USER, ScimEmail, ScimGroupResource, ScimGroupRow,
+    ScimListQuery, ScimListResponse, ScimMemberRef, ScimMeta, ScimName, ScimUserResource,
+    ScimUserRow,
+};
+
+const DEFAULT_PAGE_SIZE: i64 = 100;
+const MAX_PAGE_SIZE: i64 = 500;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ScimPage {
+    pub(crate) filter: Option<ScimFilter>,
+    pub(crate) start_index: i64,
+    pub(crate) count: i64,
+}
+
+impl ScimPage {
+    pub(crate) fn from_query(query: &ScimListQuery) -> Result<Self, ScimError> {
+        let filter = query.filter.as_deref().map(parse_filter).transpose()?;
+        Ok(Self {
+            filter,
+            start_index: query.start_index.unwrap_or(1).max(1),
+            count: query
+                .count
+                .unwrap_or(DEFAULT_PAGE_SIZE)
+                .clamp(0, MAX_PAGE_SIZE),
+        })
+    }
+
+    #[must_use]
+    pub(crate) const fn offset(&self) -> i64 {
+        self.start_index - 1
+    }
+
+    pub(crate) fn filter_on<'a>(
+        &'a self,
+        attribute: &str,
+        supported: &[&str],
+    ) -> Result<Option<&'a str>, ScimError> {
+        let Some(filter) = &self.filter else {
+            return Ok(None);
+        };
+        if !supported.contains(&filter.attribute.as_str()) {
+            return Err(super::filter::ScimFilterError(filter.attribute.clone()).into());
+        }
+        Ok((filter.attribute == attribute).then_some(filter.value.as_str()))
+    }
+
+    #[must_use]
+    pub(crate) fn respond<T>(&self, total: i64, resources: Vec<T>) -> ScimListResponse<T> {
+        ScimListResponse {
+            schemas: [SCHEMA_LIST_RESPONSE],
+            total_results: total,
+            start_index: self.start_index,
+            items_per_page: i64::try_from(resources.len()).unwrap_or(i64::MAX),
+            resources,
+        }
+    }
+}
+
+fn location(kind: &str, id: &str) -> String {
+    let base = Config::get().map_or_else(
+        |_| String::new(),
+        |c| c.api_external_url.trim_end_matches('/').to_owned(),
+    );
+    format!("{base}/scim/v2/{kind}/{id}")
+}
+
+pub(crate) async fn user_resource(
+    pool: &PgPool,
+    row: ScimUserRow,
+) -> Result<ScimUserResource, ScimError> {
+    let groups = users::list_user_memberships(pool, &row.user_id)
+        .await?
+        .into_iter()
+        .map(|g| ScimMemberRef {
+            value: g.group_id,
+            display: Some(g.display_name),
+        })
+        .collect();
+    let id = row.user_id.as_str().to_owned();
+    Ok(ScimUserResource {
+        schemas: [SCHEMA_USER],
+        meta: ScimMeta {
+            resource_type: "User",
+            created: row.created_at,
+            last_modified: row.updated_at,
+            location: location("Users", &id),
+        },
+        id,
+        external_id: row.external_id,
+        name: row.display_name.clone().map(|formatted| ScimName {
+            formatted: Some(formatted),
+            ..ScimName::default()
+        }),
+        display_name: row.display_name,
+        emails: vec![ScimEmail {
+            value: row.email.clone(),
+            kind: Some("work".to_owned()),
+            primary: true,
+        }],
+        user_name: row.email,
+        active: row.status == "active",
+        groups,
+    })
+}
+
+pub(crate) async fn group_resource(
+    pool: &PgPool,
+    row: ScimGroupRow,
+) -> Result<ScimGroupResource, ScimError> {
+    let members = groups::list_group_members(pool, &row.id)
+        .await?
+        .into_iter()
+        .map(|m| ScimMemberRef {
+            value: m.user_id.as_str().to_owned(),
+            display: Some(m.email),
+        })
+        .collect();
+    Ok(ScimGroupResource {
+        schemas: [SCHEMA_GROUP],
+        meta: ScimMeta {
+            resource_type: "Group",
+            created: row.created_at,
+            last_modified: row.updated_at,
+            location: location("Groups", &row.id),
+        },
+        id: row.id,
+        external_id: row.external_id,
+        display_name: row.display_name,
+        members,
+    })
+}
@@ -0,0 +1,212 @@
+//! User provisioning: create, replace, patch, deactivate, and the
+//! role/department recompute every membership change ends in.
+//!
+//! `POST` never takes over an existing account. One with the same email is a
+//! `uniqueness` conflict whether or not the identity provider manages it:
+//! adopting it would overwrite its status and reactivate a user an operator
+//! suspended. `DELETE` deactivates and never deletes.
+
+use std::sync::Arc;
+
+use sqlx::PgPool;
+use systemprompt::identifiers::UserId;
+
+use super::ScimError;
+use super::patch::interpret_user_patch;
+use super::resources::{ScimPage, user_resource};
+use crate::activity::{self, ActivityEntity, NewActivity};
+use crate::repositories::config::scim::ScimConfig;
+use crate::repositories::scim::users::{self, ScimUserWrite};
+use crate::types::scim::{
+    ScimListResponse, ScimPatchOperation, ScimUserInput, ScimUserResource, ScimUserRow,
+};
+
+pub(crate) async fn recompute_assignment(
+    pool: &PgPool,
+    config: &ScimConfig,
+    user_id: &UserId,
+) -> Result<(), ScimError> {
+    let groups: Vec<String> = users::list_user_memberships(pool, user_id)
+        .await?
+        .into_iter()
+        .map(|m| m.display_name)
+        .collect();
+    let assignment = config.assignment_for(&groups);
+    users::set_scim_user_assignment(pool, user_id, &assignment.roles, &assignment.department)
+        .await?;
+    Ok(())
+}
+
+async fn require_user(pool: &PgPool, user_id: &UserId) -> Result<ScimUserRow, ScimError> {
+    users::find_scim_user(pool, user_id)
+        .await?
+        .ok_or_else(|| ScimError::not_found("User not found"))
+}
+
+fn record(pool: &Arc<PgPool>, activity: NewActivity) {
+    let p = Arc::clone(pool);
+    tokio::spawn(async move { activity::record(&p, activity).await });
+}
+
+fn validated_email(email: String) -> Result<String, ScimError> {
+    if email.is_empty() || !email.contains('@') {
+        return Err(ScimError::invalid_value(
+            "userName or emails must carry an email address",
+        ));
+    }
+    Ok(email)
+}
+
+pub(crate) async fn list_users(
+    pool: &PgPool,
+    page: &ScimPage,
+) -> Result<ScimListResponse<ScimUserResource>, ScimError> {
+    let supported = ["username", "externalid", "emails.value", "emails"];
+    let email = page
+        .filter_on("username", &supported)?
+        .or(page.filter_on("emails.value", &supported)?)
+        .or(page.filter_on("emails", &supported)?)
+        .map(str::to_lowercase);
+    let external_id = page.filter_on("externalid", &supported)?;
+    let total = users::get_scim_user_count(pool, email.as_deref(), external_id).await?;
+    let rows = users::list_scim_users(
+        pool,
+        email.as_deref(),
+        external_id,
+        page.offset(),
+        page.count,
+    )
+    .await?;
+    let mut resources = Vec::with_capacity(rows.len());
+    for row in rows {
+        resources.push(user_resource(pool, row).await?);
+    }
+    Ok(page.respond(total, resources))
+}
+
+pub(crate) async fn get_user(
+    pool: &PgPool,
+    user_id: &UserId,
+) -> Result<ScimUserResource, ScimError> {
+    let row = require_user(pool, user_id).await?;
+    user_resource(pool, row).await
+}
+
+pub(crate) async fn create_user(
+    pool: &Arc<PgPool>,
+    config: &ScimConfig,
+    actor: &UserId,
+    input: &ScimUserInput,
+) -> Result<ScimUserResource, ScimError> {
+    let email = validated_email(input.email())?;
+    match users::find_user_by_email(pool, &email).await? {
+        Some((_, true)) => return Err(ScimError::uniqueness("User is already provisioned")),
+        Some((_, false)) => {
+            return Err(ScimError::uniqueness(
+                "An account with that email already exists",
+            ));
+        },
+        None => {},
+    }
+    let display_name = input.resolved_display_name();
+    let write = ScimUserWrite {
+        email: &email,
+        display_name: display_name.as_deref(),
+        external_id: input.external_id.as_deref(),
+        active: input.active,
+    };
+    let candidate = UserId::new(uuid::Uuid::new_v4().to_string());
+    let user_id = users::create_scim_user(pool, &candidate, &write).await?;
+    if !input.active {
+        users::deactivate_scim_user(pool, &user_id).await?;
+    }
+    recompute_assignment(pool, config, &user_id).await?;
+    record(
+        pool,
+        NewActivity::entity_created(actor, ActivityEntity::User, user_id.as_str(), &email),
+    );
+    get_user(pool, &user_id).await
+}
+
+async fn apply_write(
+    pool: &Arc<PgPool>,
+    actor: &UserId,
+    user_id: &UserId,
+    write: &ScimUserWrite<'_>,
+    was_active: bool,
+) -> Result<ScimUserResource, ScimError> {
+    users::update_scim_user(pool, user_id, write).await?;
+    if was_active && !write.active {
+        users::deactivate_scim_user(pool, user_id).await?;
+    }
+    record(
+        pool,
+        NewActivity::entity_updated(actor, ActivityEntity::User, user_id.as_str(), write.email),
+    );
+    get_user(pool, user_id).await
+}
+
+pub(crate) async fn replace_user(
+    pool: &Arc<PgPool>,
+    actor: &UserId,
+    user_id: &UserId,
+    input: &ScimUserInput,
+) -> Result<ScimUserResource, ScimError> {
+    let current = require_user(pool, user_id).await?;
+    let email = validated_email(input.email())?;
+    let display_name = input.resolved_display_name();
+    let write = ScimUserWrite {
+        email: &email,
+        display_name: display_name.as_deref(),
+        external_id: input.external_id.as_deref(),
+        active: input.active,
+    };
+    apply_write(pool, actor, user_id, &write, current.status == "active").await
+}
+
+pub(crate) async fn patch_user(
+    pool: &Arc<PgPool>,
+    actor: &UserId,
+    user_id: &UserId,
+    ops: &[ScimPatchOperation],
+) -> Result<ScimUserResource, ScimError> {
+    let patch = interpret_user_patch(ops)?;
+    let current = require_user(pool, user_id).await?;
+    let email = validated_email(
+        patch
+            .email
+            .or(patch.user_name)
+            .map_or(current.email, |e| e.trim().to_lowercase()),
+    )?;
+    let display_name = patch.display_name.or(current.display_name);
+    let was_active = current.status == "active";
+    let write = ScimUserWrite {
+        email: &email,
+        display_name: display_name.as_deref(),
+        external_id: patch
+            .external_id
+            .as_deref()
+            .or(current.external_id.as_deref()),
+        active: patch.active.unwrap_or(was_active),
+    };
+    apply_write(pool, actor, user_id, &write, was_active).await
+}
+
+pub(crate) async fn deactivate_user(
+    pool: &Arc<PgPool>,
+    actor: &UserId,
+    user_id: &UserId,
+) -> Result<(), ScimError> {
+    let current = require_user(pool, user_id).await?;
+    users::deactivate_scim_user(pool, user_id).await?;
+    record(
+        pool,
+        NewActivity::entity_updated(
+            actor,
+            ActivityEntity::User,
+            user_id.as_str(),
+            &current.email,
+        ),
+    );
+    Ok(())
+}
@@ -1,13 +1,32 @@
-//! Secret resolution, auditing, and key rotation for the admin handlers.
+//! Secret resolution, auditing, metadata, and key rotation for the admin
+//! handlers.
 
 use std::collections::HashMap;
+use std::path::PathBuf;
 
 use sqlx::PgPool;
+use systemprompt::config::ProfileBootstrap;
 use systemprompt::identifiers::UserId;
+use systemprompt_web_access::repositories::users::find_user_identity;
 
 use crate::error::{AdminError, AdminResult};
+use crate::repositories::config::secret_lifecycle::load_secret_lifecycle_policy;
 use crate::repositories::secrets::secret_audit::{self, AuditLogRow};
-use crate::repositories::secrets::{secret_crypto, secret_keys, secret_resolve};
+use crate::repositories::secrets::secret_lifecycle::{self, SecretMetadata};
+use crate::repositories::secrets::{secret_keyring, secret_keys, secret_resolve};
+use crate::secret_lifecycle::{LifecyclePolicy, check_resolution};
+
+// Why: flagging is advisory, so an unreadable profile or policy file falls
+// back to the defaults rather than failing the request it runs in.
+pub(crate) fn lifecycle_policy() -> LifecyclePolicy {
+    let Ok(profile) = ProfileBootstrap::get() else {
+        return LifecyclePolicy::default();
+    };
+    load_secret_lifecycle_policy(&PathBuf::from(&profile.paths.services)).unwrap_or_else(|e| {
+        tracing::warn!(error = %e, "secret lifecycle policy failed to load; using defaults");
+        LifecyclePolicy::default()
+    })
+}
 
 pub(crate) async fn create_resolution_token(
     pool: &PgPool,
@@ -35,11 +54,27 @@ pub(crate) async fn resolve_secrets(
         return Err(AdminError::Forbidden("Token plugin mismatch".to_owned()));
     }
 
-    let master_key = secret_crypto::load_master_key()?;
+    let keyring = secret_keyring::load_master_keyring()?;
     let user_id = UserId::new(&user_id_str);
 
+    // Why: judged before this resolution is audited, so it is not part of its
+    // own baseline; a failed check never blocks the plugin.
+    let policy = lifecycle_policy();
+    match check_resolution(pool, &user_id, plugin_id, &policy, chrono::Utc::now()).await {
+        Ok(kinds) if !kinds.is_empty() => {
+            tracing::warn!(
+                user_id = %user_id,
+                plugin_id = %plugin_id,
+                ?kinds,
+                "Unusual secret resolution"
+            );
+        },
+        Ok(_) => {},
+        Err(e) => tracing::warn!(error = %e, "Secret resolution anomaly check failed"),
+    }
+
     let secrets =
-        secret_resolve::resolve_secrets_for_plugin(pool, &user_id, plugin_id, &master_key).await?;
+        secret_resolve::resolve_secrets_for_plugin(pool, &user_id, plugin_id, &keyring).await?;
     Ok(secrets)
 }
 
@@ -52,13 +87,41 @@ pub(crate) async fn list_audit_log(
     Ok(rows)
 }
 
+/// Set a secret's owner and expiry. The owner, when given, must be an
+/// existing user.
+pub async fn set_secret_metadata(
+    pool: &PgPool,
+    user_id: &UserId,
+    plugin_id: &str,
+    var_name: &str,
+    metadata: &SecretMetadata,
+) -> AdminResult<()> {
+    if let Some(owner) = &metadata.owner_id
+        && find_user_identity(pool, &UserId::new(owner))
+            .await?
+            .is_none()
+    {
+        return Err(AdminError::BadRequest(format!(
+            "Owner {owner} is not a known user"
+        )));
+    }
+    let updated =
+        secret_lifecycle::set_secret_metadata(pool, user_id, plugin_id, var_name, metadata).await?;
+    if !updated {
+        return Err(AdminError::NotFound(format!(
+            "No secret {var_name} for plugin {plugin_id}"
+        )));
+    }
+    Ok(())
+}
+
 pub(crate) async fn rotate_user_keys(
     pool: &PgPool,
     user_id: &UserId,
     plugin_id: &str,
 ) -> AdminResult<()> {
-    let master_key = secret_crypto::load_master_key()?;
-    secret_keys::rotate_user_dek(pool, user_id, &master_key).await?;
+    let keyring = secret_keyring::load_master_keyring()?;
+    secret_keys::rotate_user_dek(pool, user_id, &keyring).await?;
 
     if let Err(e) = secret_audit::insert_audit_entry(pool, user_id, plugin_id, "rotated").await {
         tracing::warn!(error = %e, "Failed to insert secret audit log");
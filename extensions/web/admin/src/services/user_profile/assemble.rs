@@ -2,8 +2,9 @@
 //!
 //! Each function owns one card on the profile pane: the concurrent usage
 //! fan-out, the usage view-model, config/identity strings, the gateway access
-//! block, and the agents block. Falls back to empty defaults on failure so a
-//! missing section renders as an empty card rather than a page-level error.
+//! block, the agents block, and the secrets block. Falls back to empty defaults
+//! on failure so a missing section renders as an empty card rather than a
+//! page-level error.
 
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -14,10 +15,15 @@ use systemprompt::identifiers::{TenantId, UserId};
 use systemprompt::models::Config;
 use uuid::Uuid;
 
-use crate::repositories::users::identity::{SignedInUserRow, find_user_identity};
+use crate::repositories::secrets::secret_lifecycle::SecretAlertKind;
 use crate::repositories::users::usage as usage_repo;
+use crate::secret_lifecycle::attention_for_user;
+use crate::services::secret_service::lifecycle_policy;
+use systemprompt_web_access::repositories::users::{SignedInUserRow, find_user_identity};
 
-use super::{AgentItem, AgentsBlock, GatewayAccessBlock, ProfileUsage};
+use super::{
+    AgentItem, AgentsBlock, GatewayAccessBlock, ProfileUsage, SecretAttentionItem, SecretsBlock,
+};
 
 pub(super) struct UsageSections {
     pub(super) d1: usage_repo::UsageWindow,
@@ -200,3 +206,34 @@ pub(super) fn build_agents_block() -> AgentsBlock {
         items,
     }
 }
+
+pub(super) async fn build_secrets_block(pool: &PgPool, user_id: &UserId) -> SecretsBlock {
+    let policy = lifecycle_policy();
+    let Ok(found) = attention_for_user(pool, user_id, &policy, chrono::Utc::now())
+        .await
+        .inspect_err(|e| {
+            tracing::warn!(error = %e, user_id = %user_id, "user_profile: secret attention failed");
+        })
+    else {
+        return SecretsBlock::default();
+    };
+    let items: Vec<SecretAttentionItem> = found
+        .into_iter()
+        .map(|item| SecretAttentionItem {
+            plugin_id: item.plugin_id,
+            var_name: item.var_name,
+            owner_id: item.owner_id,
+            kind: item.kind.as_str(),
+            tone: match item.kind {
+                SecretAlertKind::Expired => "error",
+                SecretAlertKind::Unused => "off",
+                _ => "warn",
+            },
+            detail: item.detail,
+        })
+        .collect();
+    SecretsBlock {
+        total: items.len(),
+        items,
+    }
+}
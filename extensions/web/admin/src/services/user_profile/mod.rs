@@ -1,7 +1,8 @@
 //! Aggregator for the profile pane.
 //!
-//! Assembles the signed-in user's identity, gateway access, usage rollups, and
-//! visible agents into the payload the SSR profile page renders.
+//! Assembles the signed-in user's identity, gateway access, usage rollups,
+//! visible agents, and secrets needing attention into the payload the SSR
+//! profile page renders.
 
 mod assemble;
 
@@ -14,8 +15,8 @@ use systemprompt::identifiers::{TenantId, UserId};
 use crate::types::UserContext;
 
 use assemble::{
-    build_agents_block, build_gateway_access_block, build_usage, fetch_usage_sections,
-    read_config_strings, read_tenant_id,
+    build_agents_block, build_gateway_access_block, build_secrets_block, build_usage,
+    fetch_usage_sections, read_config_strings, read_tenant_id,
 };
 
 #[derive(Debug, Clone, Serialize)]
@@ -66,6 +67,22 @@ pub(crate) struct AgentsBlock {
     pub items: Vec<AgentItem>,
 }
 
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct SecretAttentionItem {
+    pub plugin_id: String,
+    pub var_name: String,
+    pub owner_id: Option<String>,
+    pub kind: &'static str,
+    pub tone: &'static str,
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub(crate) struct SecretsBlock {
+    pub total: usize,
+    pub items: Vec<SecretAttentionItem>,
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub(crate) struct ProfilePageData {
     pub page: &'static str,
@@ -74,6 +91,7 @@ pub(crate) struct ProfilePageData {
     pub gateway_access: Option<GatewayAccessBlock>,
     pub usage: ProfileUsage,
     pub agents: AgentsBlock,
+    pub secrets: SecretsBlock,
 }
 
 // Why: falls back gracefully when individual sections fail, so missing data
@@ -107,6 +125,7 @@ pub(crate) async fn build_profile_data(
 
     let usage = build_usage(sections);
     let agents = build_agents_block();
+    let secrets = build_secrets_block(&pool, &user_id).await;
 
     ProfilePageData {
         page: "profile",
@@ -115,5 +134,6 @@ pub(crate) async fn build_profile_data(
         gateway_access,
         usage,
         agents,
+        secrets,
     }
 }
@@ -2,7 +2,9 @@
 
 use std::collections::BTreeMap;
 
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use systemprompt::identifiers::UserId;
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct GatewayRouteView {
@@ -14,6 +16,26 @@ pub struct GatewayRouteView {
     pub upstream_model: Option<String>,
     #[serde(default)]
     pub extra_headers: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub targets: Vec<GatewayRouteTarget>,
+}
+
+/// One upstream a route can send traffic to.
+///
+/// An empty target list means the
+/// route's own `provider`/`upstream_model` serve every request; otherwise the
+/// targets replace it. `weight` sets the share of normal traffic, and a
+/// zero-weight target only receives requests when every weighted target is
+/// unhealthy. List order is the fallback order.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct GatewayRouteTarget {
+    pub id: String,
+    pub provider: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub upstream_model: Option<String>,
+    #[serde(default)]
+    pub weight: u32,
 }
 
 #[derive(Debug, Clone, Serialize, Default)]
@@ -23,6 +45,9 @@ pub struct GatewayConfigView {
     pub inference_path_prefix: String,
     pub routes: Vec<GatewayRouteView>,
     pub profile_path: String,
+    /// Digest of the current configuration; send it back as `If-Match`.
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub etag: String,
 }
 
 #[derive(Debug, Deserialize)]
@@ -36,3 +61,31 @@ pub struct UpdateGatewaySettingsRequest {
 pub struct ReorderRoutesRequest {
     pub order: Vec<usize>,
 }
+
+/// One stored gateway configuration state, without its YAML bodies.
+#[derive(Debug, Clone, Serialize)]
+pub struct GatewayRevisionSummary {
+    pub id: i64,
+    pub etag: String,
+    pub action: String,
+    pub summary: String,
+    pub actor_id: Option<UserId>,
+    pub actor_email: Option<String>,
+    pub restored_from: Option<i64>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A stored gateway configuration state: the profile's `gateway` block and
+/// the targets sidecar exactly as they stood after the change.
+#[derive(Debug, Clone, Serialize)]
+pub struct GatewayRevision {
+    pub id: i64,
+    pub etag: String,
+    pub action: String,
+    pub summary: String,
+    pub actor_id: Option<UserId>,
+    pub gateway_yaml: String,
+    pub targets_yaml: String,
+    pub restored_from: Option<i64>,
+    pub created_at: DateTime<Utc>,
+}
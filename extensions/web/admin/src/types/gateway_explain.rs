@@ -0,0 +1,114 @@
+//! What the gateway would do with one model for one user, stage by stage.
+//!
+//! Built by the route explainer from the same inputs the gateway reads at
+//! request time: profile routes and providers, the targets sidecar,
+//! `access_control_rules`, the gateway policy core resolves from enabled
+//! `ai_gateway_policies` rows, and the governance engine.
+
+use serde::Serialize;
+use systemprompt::ai::{QuotaWindow, SafetyHistoryMode};
+use systemprompt::identifiers::UserId;
+use systemprompt_security::authz::{AccessRule, MatchedBy};
+
+use super::GatewayRouteTarget;
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum RouteMatchOutcome {
+    /// Checked before the winner and its pattern did not match.
+    Skipped,
+    Matched,
+    /// The pattern matched but the route's `when` conditions reject the
+    /// request being explained.
+    Unmet,
+    /// Listed after the winner; never consulted for this model.
+    Shadowed,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct RouteMatchStep {
+    pub index: usize,
+    pub id: String,
+    pub model_pattern: String,
+    pub provider: String,
+    /// Request-shape predicates the route's `when` block sets, by name.
+    pub conditions: Vec<&'static str>,
+    /// The catch-all route core adds for the profile's `default_provider`.
+    pub default_route: bool,
+    pub outcome: RouteMatchOutcome,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct MaskedHeader {
+    pub name: String,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MatchedRouteExplanation {
+    pub id: String,
+    pub provider: String,
+    pub upstream_model: String,
+    pub rewritten: bool,
+    pub extra_headers: Vec<MaskedHeader>,
+    pub targets: Vec<GatewayRouteTarget>,
+}
+
+/// The access rule that settled the decision, and the entity it sits on: the
+/// route itself or a marketplace it inherits from.
+#[derive(Debug, Clone, Serialize)]
+pub struct DecidingRule {
+    pub entity: String,
+    pub rule: AccessRule,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AuthzExplanation {
+    pub allowed: bool,
+    pub reason: String,
+    pub matched_by: Option<MatchedBy>,
+    pub deciding_rule: Option<DecidingRule>,
+    pub route_rules: Vec<AccessRule>,
+    pub default_included: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SafetyExplanation {
+    pub scanners: Vec<String>,
+    pub block_categories: Vec<String>,
+    pub block_response_categories: Vec<String>,
+    pub history: SafetyHistoryMode,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct GovernanceStep {
+    pub id: String,
+    pub name: String,
+    pub enabled: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ExplainSubject {
+    pub user_id: UserId,
+    pub roles: Vec<String>,
+    pub department: String,
+    /// Name of the personal access token the subject was resolved from.
+    pub token_name: Option<String>,
+    pub token_live: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RouteExplanation {
+    pub model: String,
+    pub subject: ExplainSubject,
+    pub route_steps: Vec<RouteMatchStep>,
+    pub route: Option<MatchedRouteExplanation>,
+    pub authz: Option<AuthzExplanation>,
+    /// Quota windows from the effective gateway policy, checked per user.
+    pub quota_windows: Vec<QuotaWindow>,
+    /// Enabled `ai_gateway_policies` rows, in the order core merges them.
+    pub policy_names: Vec<String>,
+    pub safety: SafetyExplanation,
+    pub governance: Vec<GovernanceStep>,
+    pub verdict: String,
+}
@@ -7,11 +7,13 @@ mod dashboard;
 mod dashboard_enterprise;
 pub mod departments;
 pub mod gateway;
+pub mod gateway_explain;
 pub mod hooks_export;
 mod jobs;
 mod plugins;
 mod plugins_config;
 mod plugins_requests;
+pub mod scim;
 pub mod session_analysis;
 mod traffic;
 mod user_context;
@@ -31,7 +33,13 @@ pub use dashboard::{
     UserGamificationProfile, WindowedCounts,
 };
 pub use gateway::{
-    GatewayConfigView, GatewayRouteView, ReorderRoutesRequest, UpdateGatewaySettingsRequest,
+    GatewayConfigView, GatewayRevision, GatewayRevisionSummary, GatewayRouteTarget,
+    GatewayRouteView, ReorderRoutesRequest, UpdateGatewaySettingsRequest,
+};
+pub use gateway_explain::{
+    AuthzExplanation, DecidingRule, ExplainSubject, GovernanceStep, MaskedHeader,
+    MatchedRouteExplanation, RouteExplanation, RouteMatchOutcome, RouteMatchStep,
+    SafetyExplanation,
 };
 pub use hooks_export::{HookEventType, HookHandler, HooksFile, HttpHook, MatcherGroup};
 pub use jobs::JobSummary;
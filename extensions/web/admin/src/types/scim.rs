@@ -0,0 +1,250 @@
+//! SCIM 2.0 wire types (RFC 7643 resources, RFC 7644 messages) for the
+//! provisioning endpoints.
+//!
+//! Only the attributes this platform stores are modelled. Anything else an
+//! identity provider sends is accepted and dropped rather than rejected, which
+//! is what RFC 7644 §3.3 asks of a service provider that does not support an
+//! attribute.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use systemprompt::identifiers::UserId;
+
+pub const SCHEMA_USER: &str = "urn:ietf:params:scim:schemas:core:2.0:User";
+pub const SCHEMA_GROUP: &str = "urn:ietf:params:scim:schemas:core:2.0:Group";
+pub const SCHEMA_LIST_RESPONSE: &str = "urn:ietf:params:scim:api:messages:2.0:ListResponse";
+pub const SCHEMA_ERROR: &str = "urn:ietf:params:scim:api:messages:2.0:Error";
+pub const SCHEMA_SERVICE_PROVIDER_CONFIG: &str =
+    "urn:ietf:params:scim:schemas:core:2.0:ServiceProviderConfig";
+/// The media type of every SCIM response body, errors included (RFC 7644 §3.1).
+pub const SCIM_CONTENT_TYPE: &str = "application/scim+json";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimName {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub formatted: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub given_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub family_name: Option<String>,
+}
+
+impl ScimName {
+    /// The best single-line rendering the identity provider gave us.
+    #[must_use]
+    pub fn display(&self) -> Option<String> {
+        self.formatted.clone().or_else(|| {
+            let parts: Vec<&str> = [self.given_name.as_deref(), self.family_name.as_deref()]
+                .into_iter()
+                .flatten()
+                .filter(|p| !p.trim().is_empty())
+                .collect();
+            (!parts.is_empty()).then(|| parts.join(" "))
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScimEmail {
+    pub value: String,
+    #[serde(default, rename = "type", skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+    #[serde(default)]
+    pub primary: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScimMemberRef {
+    pub value: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimMeta {
+    pub resource_type: &'static str,
+    pub created: DateTime<Utc>,
+    pub last_modified: DateTime<Utc>,
+    pub location: String,
+}
+
+/// A user as an identity provider sends it on `POST`/`PUT`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimUserInput {
+    pub user_name: String,
+    #[serde(default)]
+    pub external_id: Option<String>,
+    #[serde(default)]
+    pub name: Option<ScimName>,
+    #[serde(default)]
+    pub display_name: Option<String>,
+    #[serde(default)]
+    pub emails: Vec<ScimEmail>,
+    #[serde(default = "active_by_default")]
+    pub active: bool,
+}
+
+const fn active_by_default() -> bool {
+    true
+}
+
+impl ScimUserInput {
+    /// The address the account is keyed on: the primary email if one is
+    /// flagged, else the first, else `userName` (which most identity providers
+    /// set to the UPN / email anyway). Normalised the way `users.email`
+    /// requires.
+    #[must_use]
+    pub fn email(&self) -> String {
+        self.emails
+            .iter()
+            .find(|e| e.primary)
+            .or_else(|| self.emails.first())
+            .map_or(self.user_name.as_str(), |e| e.value.as_str())
+            .trim()
+            .to_lowercase()
+    }
+
+    #[must_use]
+    pub fn resolved_display_name(&self) -> Option<String> {
+        self.display_name
+            .clone()
+            .filter(|d| !d.trim().is_empty())
+            .or_else(|| self.name.as_ref().and_then(ScimName::display))
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimUserResource {
+    pub schemas: [&'static str; 1],
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub external_id: Option<String>,
+    pub user_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<ScimName>,
+    pub emails: Vec<ScimEmail>,
+    pub active: bool,
+    pub groups: Vec<ScimMemberRef>,
+    pub meta: ScimMeta,
+}
+
+/// A group as an identity provider sends it on `POST`/`PUT`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimGroupInput {
+    pub display_name: String,
+    #[serde(default)]
+    pub external_id: Option<String>,
+    #[serde(default)]
+    pub members: Vec<ScimMemberRef>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimGroupResource {
+    pub schemas: [&'static str; 1],
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub external_id: Option<String>,
+    pub display_name: String,
+    pub members: Vec<ScimMemberRef>,
+    pub meta: ScimMeta,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimListResponse<T> {
+    pub schemas: [&'static str; 1],
+    pub total_results: i64,
+    pub start_index: i64,
+    pub items_per_page: i64,
+    #[serde(rename = "Resources")]
+    pub resources: Vec<T>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimListQuery {
+    #[serde(default)]
+    pub filter: Option<String>,
+    #[serde(default)]
+    pub start_index: Option<i64>,
+    #[serde(default)]
+    pub count: Option<i64>,
+}
+
+/// One operation of an RFC 7644 §3.5.2 `PatchOp` request.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ScimPatchOperation {
+    pub op: String,
+    #[serde(default)]
+    pub path: Option<String>,
+    // JSON: protocol boundary — a patch value takes the shape of its target path
+    #[serde(default)]
+    pub value: Option<serde_json::Value>,
+}
+
+/// A membership change, kept in request order: a `replace` followed by an
+/// `add` must not collapse into the `replace` alone.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScimMemberChange {
+    Add(Vec<String>),
+    Remove(Vec<String>),
+    Replace(Vec<String>),
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ScimPatchRequest {
+    #[serde(default)]
+    pub operations: Vec<ScimPatchOperation>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimErrorBody {
+    pub schemas: [&'static str; 1],
+    pub status: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scim_type: Option<&'static str>,
+    pub detail: String,
+}
+
+/// An SCIM-managed user as stored: the `users` row joined to `scim_users`.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct ScimUserRow {
+    pub user_id: UserId,
+    pub email: String,
+    pub display_name: Option<String>,
+    pub status: String,
+    pub external_id: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct ScimGroupRow {
+    pub id: String,
+    pub display_name: String,
+    pub external_id: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct ScimMemberRow {
+    pub user_id: UserId,
+    pub email: String,
+}
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct ScimMembershipRow {
+    pub group_id: String,
+    pub display_name: String,
+}
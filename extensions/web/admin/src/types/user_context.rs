@@ -16,3 +16,12 @@ pub struct UserContext {
     /// for tokens minted without one.
     pub session_id: Option<SessionId>,
 }
+
+impl UserContext {
+    /// Admins and holders of the `auditor` role, who may read what users
+    /// sent and received: raw transcript bodies and content search.
+    #[must_use]
+    pub fn can_audit(&self) -> bool {
+        self.is_admin || self.roles.iter().any(|r| r.eq_ignore_ascii_case("auditor"))
+    }
+}
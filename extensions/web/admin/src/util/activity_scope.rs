@@ -0,0 +1,21 @@
+//! Whose activity a dashboard or digest query counts: everyone, one user, or
+//! one department.
+//!
+//! A department is the user's department in `user_profile_ext` at read time,
+//! `Default` when unset, as on the cost and chargeback pages.
+
+use systemprompt::identifiers::UserId;
+
+/// At most one of a user or a department; both `None` counts everyone.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ActivityScope<'a> {
+    pub user_id: Option<&'a UserId>,
+    pub department: Option<&'a str>,
+}
+
+impl ActivityScope<'_> {
+    #[must_use]
+    pub fn user(&self) -> Option<&str> {
+        self.user_id.map(UserId::as_str)
+    }
+}
@@ -0,0 +1,10 @@
+//! Calendar-month arithmetic shared by the cost forecast and chargeback
+//! statements, which both reckon spend by month.
+
+use chrono::{Datelike, NaiveDate};
+
+/// The first day of the month containing `day`.
+#[must_use]
+pub fn month_start(day: NaiveDate) -> NaiveDate {
+    day.with_day(1).unwrap_or(day)
+}
@@ -0,0 +1,130 @@
+//! Side-by-side line diff for the gateway history page.
+//!
+//! A plain longest-common-subsequence walk: the inputs are a few hundred
+//! lines of YAML, so the quadratic table is cheap. Past [`MAX_CELLS`] the
+//! table is skipped and every line is shown as replaced rather than risk a
+//! slow page.
+
+use serde::Serialize;
+
+/// Largest `old × new` table the diff will build.
+pub const MAX_CELLS: usize = 4_000_000;
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffKind {
+    Same,
+    Removed,
+    Added,
+    Changed,
+}
+
+/// One row of the two-column view. A side is `None` where the other side
+/// has a line with no counterpart.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct DiffRow {
+    pub kind: DiffKind,
+    pub old_no: Option<usize>,
+    pub old: Option<String>,
+    pub new_no: Option<usize>,
+    pub new: Option<String>,
+}
+
+enum Op {
+    Same(usize, usize),
+    Removed(usize),
+    Added(usize),
+}
+
+fn ops(old: &[&str], new: &[&str]) -> Vec<Op> {
+    if old.len().saturating_mul(new.len()) > MAX_CELLS {
+        return (0..old.len())
+            .map(Op::Removed)
+            .chain((0..new.len()).map(Op::Added))
+            .collect();
+    }
+    let width = new.len() + 1;
+    let mut lcs = vec![0usize; (old.len() + 1) * width];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i * width + j] = if old[i] == new[j] {
+                lcs[(i + 1) * width + j + 1] + 1
+            } else {
+                lcs[(i + 1) * width + j].max(lcs[i * width + j + 1])
+            };
+        }
+    }
+    let (mut i, mut j) = (0, 0);
+    let mut out = Vec::with_capacity(old.len() + new.len());
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            out.push(Op::Same(i, j));
+            i += 1;
+            j += 1;
+        } else if j < new.len()
+            && (i == old.len() || lcs[i * width + j + 1] >= lcs[(i + 1) * width + j])
+        {
+            out.push(Op::Added(j));
+            j += 1;
+        } else {
+            out.push(Op::Removed(i));
+            i += 1;
+        }
+    }
+    out
+}
+
+fn flush(
+    rows: &mut Vec<DiffRow>,
+    removed: &mut Vec<(usize, &str)>,
+    added: &mut Vec<(usize, &str)>,
+) {
+    let pairs = removed.len().max(added.len());
+    for k in 0..pairs {
+        let old = removed.get(k);
+        let new = added.get(k);
+        let kind = match (old, new) {
+            (Some(_), Some(_)) => DiffKind::Changed,
+            (Some(_), None) => DiffKind::Removed,
+            _ => DiffKind::Added,
+        };
+        rows.push(DiffRow {
+            kind,
+            old_no: old.map(|(n, _)| n + 1),
+            old: old.map(|(_, l)| (*l).to_owned()),
+            new_no: new.map(|(n, _)| n + 1),
+            new: new.map(|(_, l)| (*l).to_owned()),
+        });
+    }
+    removed.clear();
+    added.clear();
+}
+
+/// Align `old` and `new` line by line. Runs of removals and additions that
+/// sit between the same unchanged lines are paired up as `Changed` rows.
+#[must_use]
+pub fn side_by_side(old: &str, new: &str) -> Vec<DiffRow> {
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+    let mut rows = Vec::new();
+    let mut removed = Vec::new();
+    let mut added = Vec::new();
+    for op in ops(&old_lines, &new_lines) {
+        match op {
+            Op::Removed(i) => removed.push((i, old_lines[i])),
+            Op::Added(j) => added.push((j, new_lines[j])),
+            Op::Same(i, j) => {
+                flush(&mut rows, &mut removed, &mut added);
+                rows.push(DiffRow {
+                    kind: DiffKind::Same,
+                    old_no: Some(i + 1),
+                    old: Some(old_lines[i].to_owned()),
+                    new_no: Some(j + 1),
+                    new: Some(new_lines[j].to_owned()),
+                });
+            },
+        }
+    }
+    flush(&mut rows, &mut removed, &mut added);
+    rows
+}
@@ -1,4 +1,8 @@
 //! Helpers shared across handlers and repositories that belong to no single
 //! domain.
 
+pub mod activity_scope;
+pub mod calendar;
+pub mod hmac;
+pub mod line_diff;
 pub mod time_range;
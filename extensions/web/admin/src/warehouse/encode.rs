@@ -0,0 +1,184 @@
+//! Rows to file bytes: the body policy, then JSON Lines or Parquet.
+//!
+//! A row arrives as the database's own JSON rendering of it. Projection keeps
+//! the listed columns in their listed order, so every file of a table has the
+//! same shape, and applies the body policy to free-text columns.
+
+use std::sync::Arc;
+
+use arrow_array::builder::{
+    BooleanBuilder, Float64Builder, Int64Builder, StringBuilder, TimestampMicrosecondBuilder,
+};
+use arrow_array::{ArrayRef, RecordBatch};
+use arrow_schema::{DataType, Field, Schema, TimeUnit};
+use chrono::DateTime;
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::file::properties::WriterProperties;
+use serde_json::{Map, Value};
+use systemprompt_web_shared::error::MarketplaceError;
+
+use super::tables::{ColumnKind, WarehouseColumn, WarehouseTable};
+use crate::repositories::analytics::conversations::redact_text;
+use crate::repositories::config::warehouse::BodyPolicy;
+
+/// The columns a table's files carry under `bodies`.
+#[must_use]
+pub fn export_columns(table: WarehouseTable, bodies: BodyPolicy) -> Vec<WarehouseColumn> {
+    table
+        .columns()
+        .iter()
+        .filter(|c| !(c.body && bodies == BodyPolicy::Exclude))
+        .copied()
+        .collect()
+}
+
+fn redact_value(value: Value) -> Value {
+    match value {
+        Value::String(s) => Value::String(redact_text(&s).0),
+        Value::Null => Value::Null,
+        other => {
+            let (text, count) = redact_text(&other.to_string());
+            if count == 0 {
+                other
+            } else {
+                serde_json::from_str(&text).unwrap_or(Value::String(text))
+            }
+        },
+    }
+}
+
+/// One database row cut down to `columns`, bodies redacted when asked.
+#[must_use]
+pub fn project_row(
+    columns: &[WarehouseColumn],
+    row: &Value,
+    bodies: BodyPolicy,
+) -> Map<String, Value> {
+    columns
+        .iter()
+        .map(|column| {
+            let value = row.get(column.name).cloned().unwrap_or(Value::Null);
+            let value = if column.body && bodies == BodyPolicy::Redact {
+                redact_value(value)
+            } else {
+                value
+            };
+            (column.name.to_owned(), value)
+        })
+        .collect()
+}
+
+/// One JSON object per line.
+#[must_use]
+pub fn encode_jsonl(rows: &[Map<String, Value>]) -> Vec<u8> {
+    let mut out = Vec::new();
+    for row in rows {
+        out.extend_from_slice(Value::Object(row.clone()).to_string().as_bytes());
+        out.push(b'\n');
+    }
+    out
+}
+
+fn data_type(kind: ColumnKind) -> DataType {
+    match kind {
+        ColumnKind::Text | ColumnKind::Json => DataType::Utf8,
+        ColumnKind::Int => DataType::Int64,
+        ColumnKind::Float => DataType::Float64,
+        ColumnKind::Bool => DataType::Boolean,
+        ColumnKind::Timestamp => DataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into())),
+    }
+}
+
+fn text(value: &Value) -> Option<String> {
+    match value {
+        Value::Null => None,
+        Value::String(s) => Some(s.clone()),
+        other => Some(other.to_string()),
+    }
+}
+
+// Why: a timestamp that does not parse would otherwise be written as null,
+// indistinguishable from a row that never had one.
+fn timestamp_micros(column: &str, value: &Value) -> Result<Option<i64>, MarketplaceError> {
+    match value {
+        Value::Null => Ok(None),
+        Value::String(s) => DateTime::parse_from_rfc3339(s)
+            .map(|t| Some(t.timestamp_micros()))
+            .map_err(|e| parquet_error(format!("column {column}: timestamp {s:?}: {e}"))),
+        other => Err(parquet_error(format!(
+            "column {column}: expected a timestamp, got {other}"
+        ))),
+    }
+}
+
+fn column_array(
+    column: &WarehouseColumn,
+    rows: &[Map<String, Value>],
+) -> Result<ArrayRef, MarketplaceError> {
+    let mut values = rows
+        .iter()
+        .map(|row| row.get(column.name).unwrap_or(&Value::Null));
+    Ok(match column.kind {
+        ColumnKind::Text | ColumnKind::Json => {
+            let mut b = StringBuilder::new();
+            values.for_each(|v| b.append_option(text(v)));
+            Arc::new(b.finish())
+        },
+        ColumnKind::Int => {
+            let mut b = Int64Builder::new();
+            values.for_each(|v| b.append_option(v.as_i64()));
+            Arc::new(b.finish())
+        },
+        ColumnKind::Float => {
+            let mut b = Float64Builder::new();
+            values.for_each(|v| b.append_option(v.as_f64()));
+            Arc::new(b.finish())
+        },
+        ColumnKind::Bool => {
+            let mut b = BooleanBuilder::new();
+            values.for_each(|v| b.append_option(v.as_bool()));
+            Arc::new(b.finish())
+        },
+        ColumnKind::Timestamp => {
+            let mut b = TimestampMicrosecondBuilder::new().with_timezone("UTC");
+            values.try_for_each(|v| {
+                b.append_option(timestamp_micros(column.name, v)?);
+                Ok::<_, MarketplaceError>(())
+            })?;
+            Arc::new(b.finish())
+        },
+    })
+}
+
+fn parquet_error(e: impl std::fmt::Display) -> MarketplaceError {
+    MarketplaceError::Internal(format!("parquet encode: {e}"))
+}
+
+/// A Snappy-compressed Parquet file with one row group.
+///
+/// Every column is nullable; JSON columns are stored as their text. A
+/// timestamp that is not RFC 3339 fails the file rather than becoming a null.
+pub fn encode_parquet(
+    columns: &[WarehouseColumn],
+    rows: &[Map<String, Value>],
+) -> Result<Vec<u8>, MarketplaceError> {
+    let schema = Arc::new(Schema::new(
+        columns
+            .iter()
+            .map(|c| Field::new(c.name, data_type(c.kind), true))
+            .collect::<Vec<_>>(),
+    ));
+    let arrays = columns
+        .iter()
+        .map(|c| column_array(c, rows))
+        .collect::<Result<_, _>>()?;
+    let batch = RecordBatch::try_new(Arc::clone(&schema), arrays).map_err(parquet_error)?;
+    let properties = WriterProperties::builder()
+        .set_compression(Compression::SNAPPY)
+        .build();
+    let mut writer =
+        ArrowWriter::try_new(Vec::new(), schema, Some(properties)).map_err(parquet_error)?;
+    writer.write(&batch).map_err(parquet_error)?;
+    writer.into_inner().map_err(parquet_error)
+}
@@ -0,0 +1,172 @@
+//! Bulk export of governed tables to a data warehouse landing area.
+//!
+//! The analytics pages export CSV summaries; this exports the raw rows of
+//! `ai_requests`, `governance_decisions`, hook events, `session_analyses` and
+//! eval results as Parquet or JSON Lines files, partitioned by day, to a
+//! local directory or an S3-compatible bucket (see [`object_key`]).
+//!
+//! Export is incremental and runs from the `warehouse_export` job. Each
+//! table has its own mark in `export_cursors` (exporter
+//! `warehouse.<table>`); a run exports windows of settled rows after it and
+//! moves it past each window once that window's file is written. A failed
+//! write leaves the mark where it was, so the next run writes the same file
+//! again, and one table failing does not hold up the others.
+//!
+//! A window holds the rows that settled in it (see `repositories::warehouse`).
+//! Append-only tables settle when created. A request settles when it
+//! completes, so a pending request is exported once, finished. A session
+//! analysis is rewritten when the session is analysed again, so it settles on
+//! every write and lands in the window of each: its files are upserts, and a
+//! reader keeps the newest `updated_at` per `session_id`. Free-text columns
+//! follow the configured [`BodyPolicy`]. Settings live in
+//! `services/telemetry/warehouse.yaml`.
+
+mod encode;
+mod store;
+mod tables;
+
+use chrono::{DateTime, Duration, NaiveTime, Utc};
+use object_store::ObjectStore;
+use sqlx::PgPool;
+use systemprompt_web_shared::error::MarketplaceError;
+
+pub use encode::{encode_jsonl, encode_parquet, export_columns, project_row};
+pub use store::{S3Target, object_key, s3_store, warehouse_store};
+pub use tables::{ColumnKind, WarehouseColumn, WarehouseTable};
+
+use crate::repositories::config::warehouse::{BodyPolicy, WarehouseExportConfig, WarehouseFormat};
+use crate::repositories::export_cursors::{
+    find_export_cursor, set_export_cursor, set_export_error,
+};
+use crate::repositories::warehouse::list_warehouse_rows;
+
+/// What one run exported.
+#[derive(Debug, Clone, Default)]
+pub struct WarehouseReport {
+    pub rows: usize,
+    pub files: usize,
+    /// Tables whose export failed this run; each error is on its mark.
+    pub failed: Vec<&'static str>,
+}
+
+fn secs(value: u64) -> Duration {
+    Duration::seconds(i64::try_from(value).unwrap_or(i64::MAX))
+}
+
+/// The end of the window that starts at `after`: `max_window` later, but no
+/// later than `settled` or the next midnight UTC.
+#[must_use]
+pub fn window_end(
+    after: DateTime<Utc>,
+    settled: DateTime<Utc>,
+    max_window: Duration,
+) -> DateTime<Utc> {
+    let midnight = (after.date_naive() + Duration::days(1))
+        .and_time(NaiveTime::MIN)
+        .and_utc();
+    settled.min(after + max_window).min(midnight)
+}
+
+/// The file for one window's rows, or `None` for an empty window.
+pub fn encode_window(
+    table: WarehouseTable,
+    format: WarehouseFormat,
+    bodies: BodyPolicy,
+    rows: &[serde_json::Value],
+) -> Result<Option<Vec<u8>>, MarketplaceError> {
+    if rows.is_empty() {
+        return Ok(None);
+    }
+    let columns = export_columns(table, bodies);
+    let projected: Vec<_> = rows
+        .iter()
+        .map(|row| project_row(&columns, row, bodies))
+        .collect();
+    match format {
+        WarehouseFormat::Jsonl => Ok(Some(encode_jsonl(&projected))),
+        WarehouseFormat::Parquet => encode_parquet(&columns, &projected).map(Some),
+    }
+}
+
+fn exporter(table: WarehouseTable) -> String {
+    format!("warehouse.{}", table.name())
+}
+
+async fn write_window(
+    pool: &PgPool,
+    config: &WarehouseExportConfig,
+    store: &dyn ObjectStore,
+    table: WarehouseTable,
+    (after, through): (DateTime<Utc>, DateTime<Utc>),
+) -> Result<(usize, bool), MarketplaceError> {
+    let rows = list_warehouse_rows(pool, table, after, through).await?;
+    let Some(file) = encode_window(table, config.format, config.bodies, &rows)? else {
+        return Ok((0, false));
+    };
+    let key = object_key(&config.prefix, table, config.format, after);
+    store
+        .put(&key, file.into())
+        .await
+        .map_err(|e| MarketplaceError::Internal(format!("warehouse write {key}: {e}")))?;
+    Ok((rows.len(), true))
+}
+
+async fn export_table(
+    pool: &PgPool,
+    config: &WarehouseExportConfig,
+    store: &dyn ObjectStore,
+    table: WarehouseTable,
+    report: &mut WarehouseReport,
+) -> Result<(), MarketplaceError> {
+    let exporter = exporter(table);
+    let settled = Utc::now() - secs(config.settle_secs);
+    let mut after = match find_export_cursor(pool, &exporter).await? {
+        Some(cursor) => cursor.exported_through,
+        None => settled - secs(config.initial_lookback_secs),
+    };
+    for _ in 0..config.max_windows_per_run {
+        let through = window_end(after, settled, secs(config.max_window_secs));
+        if through <= after {
+            break;
+        }
+        let (rows, written) = match write_window(pool, config, store, table, (after, through)).await
+        {
+            Ok(done) => done,
+            Err(e) => {
+                set_export_error(pool, &exporter, after, &e.to_string()).await?;
+                return Err(e);
+            },
+        };
+        set_export_cursor(
+            pool,
+            &exporter,
+            through,
+            i64::try_from(rows).unwrap_or(i64::MAX),
+        )
+        .await?;
+        report.rows += rows;
+        report.files += usize::from(written);
+        after = through;
+    }
+    Ok(())
+}
+
+/// Export every configured table's settled windows, up to
+/// `max_windows_per_run` each.
+pub async fn export_warehouse(
+    pool: &PgPool,
+    config: &WarehouseExportConfig,
+    store: &dyn ObjectStore,
+) -> Result<WarehouseReport, MarketplaceError> {
+    let mut report = WarehouseReport::default();
+    if !config.enabled {
+        return Ok(report);
+    }
+    for &table in &config.tables {
+        if let Err(e) = export_table(pool, config, store, table, &mut report).await {
+            tracing::warn!(table = table.name(), error = %e, "warehouse export failed");
+            report.failed.push(table.name());
+        }
+    }
+    Ok(report)
+}
@@ -0,0 +1,110 @@
+//! Where export files land: a local directory or an S3-compatible bucket,
+//! both behind `object_store`, and the key each file is written under.
+
+use std::path::Path;
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use object_store::ObjectStore;
+use object_store::aws::AmazonS3Builder;
+use object_store::local::LocalFileSystem;
+use object_store::path::Path as ObjectPath;
+use systemprompt_web_shared::error::MarketplaceError;
+
+use super::tables::WarehouseTable;
+use crate::repositories::config::warehouse::{WarehouseDestination, WarehouseFormat};
+
+fn store_error(e: impl std::fmt::Display) -> MarketplaceError {
+    MarketplaceError::Internal(format!("warehouse store: {e}"))
+}
+
+/// Settings for an S3 destination once its credentials have been read.
+#[derive(Debug, Clone, Copy)]
+pub struct S3Target<'a> {
+    pub bucket: &'a str,
+    pub region: Option<&'a str>,
+    pub endpoint: Option<&'a str>,
+    pub access_key: &'a str,
+    pub secret_key: &'a str,
+}
+
+/// A bucket client. With an `endpoint` it addresses a self-hosted store by
+/// path, over plain HTTP if the endpoint says so.
+pub fn s3_store(target: S3Target<'_>) -> Result<Arc<dyn ObjectStore>, MarketplaceError> {
+    let mut builder = AmazonS3Builder::new()
+        .with_bucket_name(target.bucket)
+        .with_region(target.region.unwrap_or("us-east-1"))
+        .with_access_key_id(target.access_key)
+        .with_secret_access_key(target.secret_key);
+    if let Some(endpoint) = target.endpoint {
+        builder = builder
+            .with_endpoint(endpoint)
+            .with_virtual_hosted_style_request(false)
+            .with_allow_http(endpoint.starts_with("http://"));
+    }
+    Ok(Arc::new(builder.build().map_err(store_error)?))
+}
+
+fn env_value(var: &str) -> Result<String, MarketplaceError> {
+    std::env::var(var)
+        .map_err(|e| MarketplaceError::Internal(format!("warehouse credentials read {var}: {e}")))
+}
+
+/// The store `destination` names. A relative local directory resolves
+/// against `system_root` and is created if missing.
+pub fn warehouse_store(
+    destination: &WarehouseDestination,
+    system_root: &Path,
+) -> Result<Arc<dyn ObjectStore>, MarketplaceError> {
+    match destination {
+        WarehouseDestination::Local { dir } => {
+            let dir = system_root.join(dir);
+            std::fs::create_dir_all(&dir)?;
+            Ok(Arc::new(
+                LocalFileSystem::new_with_prefix(&dir).map_err(store_error)?,
+            ))
+        },
+        WarehouseDestination::S3 {
+            bucket,
+            region,
+            endpoint,
+            access_key_env,
+            secret_key_env,
+        } => {
+            let access_key = env_value(access_key_env)?;
+            let secret_key = env_value(secret_key_env)?;
+            s3_store(S3Target {
+                bucket,
+                region: region.as_deref(),
+                endpoint: endpoint.as_deref(),
+                access_key: &access_key,
+                secret_key: &secret_key,
+            })
+        },
+    }
+}
+
+/// `{prefix}/{table}/dt={day}/{table}-{start}.{ext}`, Hive-style so query
+/// engines prune by day. Keyed by window start, so a window exported again
+/// after a failure overwrites its earlier file.
+#[must_use]
+pub fn object_key(
+    prefix: &str,
+    table: WarehouseTable,
+    format: WarehouseFormat,
+    window_start: DateTime<Utc>,
+) -> ObjectPath {
+    let name = table.name();
+    let file = format!(
+        "{name}/dt={}/{name}-{}.{}",
+        window_start.format("%Y-%m-%d"),
+        window_start.format("%Y%m%dT%H%M%SZ"),
+        format.extension(),
+    );
+    let prefix = prefix.trim_matches('/');
+    if prefix.is_empty() {
+        ObjectPath::from(file)
+    } else {
+        ObjectPath::from(format!("{prefix}/{file}"))
+    }
+}
@@ -0,0 +1,206 @@
+//! The exported tables and their file schemas.
+//!
+//! Columns are listed rather than copied from the database, so a migration
+//! that adds a column does not change a warehouse schema under its readers;
+//! a column joins the export when it is added here.
+
+use serde::{Deserialize, Serialize};
+
+use ColumnKind::{Bool, Float, Int, Json, Text, Timestamp};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WarehouseTable {
+    AiRequests,
+    GovernanceDecisions,
+    HookEvents,
+    SessionAnalyses,
+    EvalResults,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnKind {
+    Text,
+    Int,
+    Float,
+    Bool,
+    Timestamp,
+    /// A JSON document or array, written as its JSON text in Parquet.
+    Json,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WarehouseColumn {
+    pub name: &'static str,
+    pub kind: ColumnKind,
+    /// Free text that can carry prompt, transcript or tool content; subject
+    /// to the configured body policy.
+    pub body: bool,
+}
+
+const fn col(name: &'static str, kind: ColumnKind) -> WarehouseColumn {
+    WarehouseColumn {
+        name,
+        kind,
+        body: false,
+    }
+}
+
+const fn body(name: &'static str, kind: ColumnKind) -> WarehouseColumn {
+    WarehouseColumn {
+        name,
+        kind,
+        body: true,
+    }
+}
+
+const AI_REQUESTS: &[WarehouseColumn] = &[
+    col("id", Text),
+    col("request_id", Text),
+    col("user_id", Text),
+    col("session_id", Text),
+    col("context_id", Text),
+    col("trace_id", Text),
+    col("provider", Text),
+    col("model", Text),
+    col("requested_model", Text),
+    col("route_match", Text),
+    col("temperature", Float),
+    col("max_tokens", Int),
+    col("input_tokens", Int),
+    col("output_tokens", Int),
+    col("tokens_used", Int),
+    col("cache_read_tokens", Int),
+    col("cache_creation_tokens", Int),
+    col("cost_microdollars", Int),
+    col("latency_ms", Int),
+    col("cache_hit", Bool),
+    col("is_streaming", Bool),
+    col("status", Text),
+    col("actor_kind", Text),
+    col("actor_id", Text),
+    col("synthetic", Bool),
+    body("system_prompt_override", Text),
+    body("error_message", Text),
+    col("created_at", Timestamp),
+    col("completed_at", Timestamp),
+];
+
+const GOVERNANCE_DECISIONS: &[WarehouseColumn] = &[
+    col("id", Text),
+    col("user_id", Text),
+    col("session_id", Text),
+    col("tool_name", Text),
+    col("agent_id", Text),
+    col("agent_scope", Text),
+    col("decision", Text),
+    col("policy", Text),
+    body("reason", Text),
+    col("evaluated_rules", Json),
+    col("plugin_id", Text),
+    col("actor_kind", Text),
+    col("actor_id", Text),
+    col("context_id", Text),
+    col("trace_id", Text),
+    col("created_at", Timestamp),
+];
+
+const HOOK_EVENTS: &[WarehouseColumn] = &[
+    col("id", Text),
+    col("user_id", Text),
+    col("session_id", Text),
+    col("event_type", Text),
+    col("tool_name", Text),
+    col("plugin_id", Text),
+    body("metadata", Json),
+    body("prompt_preview", Text),
+    body("description", Text),
+    body("cwd", Text),
+    col("content_input_bytes", Int),
+    col("content_output_bytes", Int),
+    col("created_at", Timestamp),
+];
+
+const SESSION_ANALYSES: &[WarehouseColumn] = &[
+    col("session_id", Text),
+    col("user_id", Text),
+    body("title", Text),
+    body("description", Text),
+    body("summary", Text),
+    col("tags", Text),
+    col("goal_achieved", Text),
+    col("quality_score", Int),
+    col("outcome", Text),
+    body("error_analysis", Text),
+    body("skill_assessment", Text),
+    body("recommendations", Text),
+    col("skill_scores", Json),
+    col("category", Text),
+    col("efficiency_metrics", Json),
+    body("improvement_hints", Text),
+    col("corrections_count", Int),
+    col("session_duration_minutes", Int),
+    col("total_turns", Int),
+    col("automation_ratio", Float),
+    col("plan_mode_used", Bool),
+    col("client_surface", Text),
+    col("created_at", Timestamp),
+    col("updated_at", Timestamp),
+];
+
+const EVAL_RESULTS: &[WarehouseColumn] = &[
+    col("id", Text),
+    col("run_id", Text),
+    col("ai_request_id", Text),
+    col("case_id", Text),
+    col("user_id", Text),
+    col("session_id", Text),
+    col("provider", Text),
+    col("model", Text),
+    col("overall_score", Int),
+    col("dimension_scores", Json),
+    col("verdict", Text),
+    body("rationale", Text),
+    body("repair_hint", Text),
+    col("flags", Json),
+    body("prompt_excerpt", Text),
+    body("response_excerpt", Text),
+    col("latency_ms", Int),
+    col("cost_microdollars", Int),
+    col("judge_cost_microdollars", Int),
+    col("repaired", Bool),
+    col("created_at", Timestamp),
+];
+
+impl WarehouseTable {
+    pub const ALL: [Self; 5] = [
+        Self::AiRequests,
+        Self::GovernanceDecisions,
+        Self::HookEvents,
+        Self::SessionAnalyses,
+        Self::EvalResults,
+    ];
+
+    /// The name of the table in the warehouse, and of its key prefix.
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::AiRequests => "ai_requests",
+            Self::GovernanceDecisions => "governance_decisions",
+            Self::HookEvents => "hook_events",
+            Self::SessionAnalyses => "session_analyses",
+            Self::EvalResults => "eval_results",
+        }
+    }
+
+    #[must_use]
+    pub const fn columns(self) -> &'static [WarehouseColumn] {
+        match self {
+            Self::AiRequests => AI_REQUESTS,
+            Self::GovernanceDecisions => GOVERNANCE_DECISIONS,
+            Self::HookEvents => HOOK_EVENTS,
+            Self::SessionAnalyses => SESSION_ANALYSES,
+            Self::EvalResults => EVAL_RESULTS,
+        }
+    }
+}
@@ -0,0 +1,173 @@
+//! Chargeback statements: department rollups with credited job traffic, cost
+//! center assignment, the CSV finance imports, and month arithmetic.
+
+#![allow(
+    clippy::expect_used,
+    clippy::panic,
+    clippy::needless_pass_by_value,
+    clippy::redundant_clone,
+    reason = "test code: panics are the assertion mechanism and clones keep fixtures readable"
+)]
+
+use chrono::NaiveDate;
+use systemprompt::identifiers::UserId;
+use systemprompt_web_admin::chargeback::csv::{render_csv, usd};
+use systemprompt_web_admin::chargeback::statement::{department_charges, department_statement};
+use systemprompt_web_admin::chargeback::{
+    assign_cost_centers, has_ended, month_bounds, parse_month, recent_months,
+};
+use systemprompt_web_admin::repositories::chargeback::ChargebackLine;
+use systemprompt_web_admin::repositories::config::cost_centers::{CostCenters, load_cost_centers};
+
+fn line(department: &str, user: &str, model: &str, gross: i64, credit: i64) -> ChargebackLine {
+    ChargebackLine {
+        department: department.to_owned(),
+        cost_center: String::new(),
+        user_id: UserId::new(user),
+        user_email: Some(format!("{user}@example.com")),
+        provider: "anthropic".to_owned(),
+        model: model.to_owned(),
+        requests: 10,
+        input_tokens: 1_000,
+        output_tokens: 100,
+        gross_microdollars: gross,
+        credit_microdollars: credit,
+    }
+}
+
+const fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(y, m, d).expect("valid date")
+}
+
+fn lines() -> Vec<ChargebackLine> {
+    vec![
+        line("Eng", "alice", "claude-sonnet", 4_000_000, 0),
+        line("Eng", "alice", "claude-haiku", 500_000, 0),
+        line("Eng", "evals", "claude-sonnet", 1_000_000, 1_000_000),
+        line("Sales", "bob", "claude-haiku", 250_000, 0),
+    ]
+}
+
+#[test]
+fn department_charges_net_out_credited_job_traffic() {
+    let charges = department_charges(&lines());
+    assert_eq!(
+        charges
+            .iter()
+            .map(|c| c.department.as_str())
+            .collect::<Vec<_>>(),
+        ["Eng", "Sales"]
+    );
+    let eng = &charges[0].totals;
+    assert_eq!(eng.requests, 30);
+    assert_eq!(eng.gross_microdollars, 5_500_000);
+    assert_eq!(eng.credit_microdollars, 1_000_000);
+    assert_eq!(eng.net_microdollars(), 4_500_000);
+}
+
+#[test]
+fn statement_breaks_a_department_down_biggest_first() {
+    let mut lines = lines();
+    let centers: CostCenters = serde_yaml::from_str(
+        "departments:\n  Eng: CC-4100\nusers:\n  EVALS@example.com: CC-4900\n",
+    )
+    .expect("yaml");
+    assign_cost_centers(&mut lines, &centers);
+
+    let statement = department_statement(&lines, "Eng").expect("eng has lines");
+    assert_eq!(statement.charge.cost_centers, ["CC-4100", "CC-4900"]);
+    let labels = |rows: &[systemprompt_web_admin::chargeback::statement::ChargeRow]| {
+        rows.iter().map(|r| r.label.clone()).collect::<Vec<_>>()
+    };
+    assert_eq!(labels(&statement.by_cost_center), ["CC-4100", "CC-4900"]);
+    assert_eq!(
+        labels(&statement.by_model),
+        ["anthropic / claude-sonnet", "anthropic / claude-haiku"]
+    );
+    assert_eq!(
+        labels(&statement.by_user),
+        ["alice@example.com", "evals@example.com"]
+    );
+    assert_eq!(
+        statement.by_provider[0].totals.net_microdollars(),
+        4_500_000
+    );
+    assert!(department_statement(&lines, "Legal").is_none());
+
+    let sales = department_statement(&lines, "Sales").expect("sales");
+    assert!(sales.charge.cost_centers.is_empty());
+    assert_eq!(labels(&sales.by_cost_center), ["Unassigned"]);
+}
+
+#[test]
+fn csv_amounts_are_exact_and_cells_are_safe_to_open() {
+    assert_eq!(usd(1_234_567), "1.234567");
+    assert_eq!(usd(5), "0.000005");
+    assert_eq!(usd(-2_500_000), "-2.500000");
+
+    let mut hostile = line("=HYPERLINK(\"x\")", "carol", "m,1", 3_000_000, 1_000_000);
+    hostile.user_email = None;
+    let body = render_csv(date(2026, 9, 1), &[hostile]);
+    let mut rows = body.split("\r\n");
+    assert!(
+        rows.next()
+            .expect("header")
+            .starts_with("month,department,cost_center,user_id")
+    );
+    assert_eq!(
+        rows.next().expect("row"),
+        "2026-09,\"'=HYPERLINK(\"\"x\"\")\",,carol,,anthropic,\"m,1\",10,1000,100,3.000000,1.000000,2.000000"
+    );
+    assert_eq!(rows.next(), Some(""));
+}
+
+#[test]
+fn months_parse_bound_and_end() {
+    let september = parse_month("2026-09").expect("month");
+    assert_eq!(september, date(2026, 9, 1));
+    assert!(parse_month("2026-13").is_none());
+    assert!(parse_month("September").is_none());
+
+    let (start, end) = month_bounds(date(2026, 12, 1));
+    assert_eq!(start.to_rfc3339(), "2026-12-01T00:00:00+00:00");
+    assert_eq!(end.to_rfc3339(), "2027-01-01T00:00:00+00:00");
+
+    assert!(has_ended(september, date(2026, 10, 1)));
+    assert!(!has_ended(date(2026, 10, 1), date(2026, 10, 31)));
+    assert_eq!(
+        recent_months(date(2026, 2, 14), 3),
+        [date(2026, 2, 1), date(2026, 1, 1), date(2025, 12, 1)]
+    );
+}
+
+#[test]
+fn cost_centers_file_is_optional_and_users_override_departments() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    assert_eq!(
+        load_cost_centers(dir.path()).expect("missing file"),
+        CostCenters::default()
+    );
+
+    std::fs::create_dir_all(dir.path().join("finops")).expect("mkdir");
+    let file = dir.path().join("finops/cost_centers.yaml");
+    std::fs::write(
+        &file,
+        "departments:\n  Eng: CC-1\nusers:\n  dana@example.com: CC-2\n",
+    )
+    .expect("write");
+    let centers = load_cost_centers(dir.path()).expect("valid file");
+    assert_eq!(
+        centers.cost_center_for("Eng", Some("Dana@Example.com")),
+        "CC-2"
+    );
+    assert_eq!(
+        centers.cost_center_for("Eng", Some("erin@example.com")),
+        "CC-1"
+    );
+    assert_eq!(centers.cost_center_for("Ops", None), "");
+
+    std::fs::write(&file, "departments:\n  Eng: ''\n").expect("write");
+    assert!(load_cost_centers(dir.path()).is_err());
+    std::fs::write(&file, "teams: {}\n").expect("write");
+    assert!(load_cost_centers(dir.path()).is_err());
+}
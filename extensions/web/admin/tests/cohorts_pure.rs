@@ -0,0 +1,226 @@
+//! Cohort analytics: which cohorts a session falls in, how outcomes are
+//! counted and compared between ranges, the trend buckets, the worst-session
+//! ranking, and how the date ranges resolve.
+
+#![allow(
+    clippy::expect_used,
+    clippy::panic,
+    reason = "test code: panics are the assertion mechanism"
+)]
+
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+use serde_json::json;
+use systemprompt::identifiers::{SessionId, UserId};
+use systemprompt_web_admin::cohorts::{
+    ALL_SESSIONS, CohortDimension, CohortRange, MAX_RANGE_DAYS, NO_SKILL_COHORT, TrendBucket,
+    UNSET_COHORT, cohort_keys, cohort_trends, compare_cohorts, overall, practice_score, summarize,
+    trend_buckets, worst_sessions,
+};
+use systemprompt_web_admin::repositories::analytics::cohorts::CohortSessionRow;
+
+fn at(day: u32, hour: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2026, 10, day, hour, 0, 0)
+        .single()
+        .expect("time")
+}
+
+const fn date(day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2026, 10, day).expect("date")
+}
+
+fn session(id: &str, goal: &str, quality: i16) -> CohortSessionRow {
+    CohortSessionRow {
+        session_id: SessionId::new(id.to_owned()),
+        user_id: UserId::new("alice"),
+        title: format!("session {id}"),
+        created_at: at(15, 9),
+        goal_achieved: goal.to_owned(),
+        quality_score: quality,
+        corrections_count: 1,
+        best_practices_checklist: None,
+        skill_scores: None,
+        category: "feature".to_owned(),
+        department: "Engineering".to_owned(),
+        agent: None,
+        model: Some("claude".to_owned()),
+        plugin: None,
+    }
+}
+
+fn many(n: usize, goal: &str, quality: i16) -> Vec<CohortSessionRow> {
+    (0..n)
+        .map(|i| session(&format!("s{i}"), goal, quality))
+        .collect()
+}
+
+#[test]
+fn skills_split_a_session_and_missing_values_get_a_cohort() {
+    let mut row = session("s1", "yes", 5);
+    row.skill_scores = Some(json!({"pdf": 4, "review": 5}));
+    assert_eq!(cohort_keys(&row, CohortDimension::Skill), ["pdf", "review"]);
+    assert_eq!(cohort_keys(&row, CohortDimension::Agent), [UNSET_COHORT]);
+    assert_eq!(cohort_keys(&row, CohortDimension::Model), ["claude"]);
+
+    row.skill_scores = Some(json!({}));
+    assert_eq!(cohort_keys(&row, CohortDimension::Skill), [NO_SKILL_COHORT]);
+    assert_eq!(
+        CohortDimension::parse("plugin"),
+        Some(CohortDimension::Plugin)
+    );
+    assert_eq!(CohortDimension::parse("version"), None);
+}
+
+#[test]
+fn outcomes_ignore_unassessed_goals_and_unscored_quality() {
+    let mut rows = vec![
+        session("a", "yes", 5),
+        session("b", "partial", 3),
+        session("c", "no", 1),
+        session("d", "", 0),
+    ];
+    rows[0].best_practices_checklist = Some(json!([
+        {"practice": "p1", "score": "yes"},
+        {"practice": "p2", "score": "partial"},
+        {"practice": "p3", "score": "n/a"},
+    ]));
+    let stats = overall(&rows, &[]).current;
+    assert_eq!(stats.sessions, 4);
+    assert_eq!(stats.goals_assessed(), 3);
+    assert_eq!(stats.quality, [1, 0, 1, 0, 1]);
+    assert_eq!(stats.avg_quality(), Some(3.0));
+    assert_eq!(stats.avg_corrections(), Some(1.0));
+    assert_eq!(stats.practice_score(), Some(0.75));
+    assert_eq!(practice_score(Some(&json!([{"score": "n/a"}]))), None);
+}
+
+#[test]
+fn changes_need_enough_sessions_on_both_sides() {
+    let mut current = many(12, "yes", 4);
+    current.extend(many(2, "no", 2).into_iter().map(|mut r| {
+        r.department = "Sales".to_owned();
+        r
+    }));
+    let baseline = many(10, "partial", 3);
+
+    let compared = compare_cohorts(
+        summarize(&current, CohortDimension::Department),
+        summarize(&baseline, CohortDimension::Department),
+    );
+    assert_eq!(compared[0].cohort, "Engineering");
+    assert_eq!(compared[0].quality_change(), Some(1.0));
+    assert_eq!(compared[0].goal_rate_change(), Some(1.0));
+    assert_eq!(compared[1].cohort, "Sales");
+    assert_eq!(compared[1].quality_change(), None);
+
+    let all = overall(&current, &baseline);
+    assert_eq!(all.cohort, ALL_SESSIONS);
+    assert!(all.comparable());
+}
+
+#[test]
+fn cohorts_only_in_the_baseline_are_kept() {
+    let current = many(3, "yes", 5);
+    let mut gone = session("old", "no", 1);
+    gone.model = Some("retired".to_owned());
+    let compared = compare_cohorts(
+        summarize(&current, CohortDimension::Model),
+        summarize(&[gone], CohortDimension::Model),
+    );
+    assert_eq!(compared.len(), 2);
+    assert_eq!(compared[1].cohort, "retired");
+    assert_eq!(compared[1].current.sessions, 0);
+    assert_eq!(compared[1].baseline.sessions, 1);
+}
+
+#[test]
+fn worst_sessions_rank_missed_goals_then_low_quality() {
+    let mut rows = vec![
+        session("fine", "yes", 5),
+        session("unscored", "", 0),
+        session("partial", "partial", 2),
+        session("missed", "no", 4),
+        session("missed-low", "no", 1),
+    ];
+    rows[2].department = "Sales".to_owned();
+    let ids = |ranked: Vec<&CohortSessionRow>| {
+        ranked
+            .into_iter()
+            .map(|r| r.session_id.as_str().to_owned())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        ids(worst_sessions(&rows, CohortDimension::Department, None, 3)),
+        ["missed-low", "missed", "partial"]
+    );
+    assert_eq!(
+        ids(worst_sessions(
+            &rows,
+            CohortDimension::Department,
+            Some("Sales"),
+            10
+        )),
+        ["partial"]
+    );
+}
+
+#[test]
+fn trend_buckets_are_days_or_monday_weeks() {
+    assert_eq!(
+        trend_buckets(TrendBucket::Day, at(14, 0), at(16, 0)),
+        [date(14), date(15)]
+    );
+    let range = CohortRange {
+        first: date(1),
+        last: date(31),
+    };
+    let long = CohortRange {
+        first: date(1) - chrono::Duration::days(40),
+        last: date(31),
+    };
+    assert_eq!(
+        TrendBucket::for_range(range.start(), range.end()),
+        TrendBucket::Day
+    );
+    assert_eq!(
+        TrendBucket::for_range(long.start(), long.end()),
+        TrendBucket::Week
+    );
+    // Why: 2026-10-15 is a Thursday; its week starts Monday the 12th.
+    assert_eq!(TrendBucket::Week.start(at(15, 9)), date(12));
+    let weeks = trend_buckets(TrendBucket::Week, at(14, 0), at(20, 0));
+    assert_eq!(weeks, [date(12), date(19)]);
+
+    let mut rows = many(2, "yes", 4);
+    rows[1].created_at = at(19, 8);
+    rows[1].quality_score = 2;
+    let trends = cohort_trends(
+        &rows,
+        CohortDimension::Department,
+        &["Engineering".to_owned()],
+        TrendBucket::Week,
+        &weeks,
+    );
+    assert_eq!(trends[0].buckets[0].avg_quality(), Some(4.0));
+    assert_eq!(trends[0].buckets[1].avg_quality(), Some(2.0));
+}
+
+#[test]
+fn ranges_default_clamp_and_precede() {
+    let today = date(28);
+    let range = CohortRange::parse(None, None, today);
+    assert_eq!((range.first, range.last), (date(1), date(28)));
+    assert_eq!(range.end(), at(29, 0));
+
+    let baseline = CohortRange::parse_baseline(None, Some("2026-01-01"), range);
+    assert_eq!(baseline.last, date(1) - chrono::Duration::days(1));
+    assert_eq!(baseline.days(), 28);
+
+    let swapped = CohortRange::parse(Some("2026-10-20"), Some("2026-10-10"), today);
+    assert_eq!((swapped.first, swapped.last), (date(10), date(20)));
+    let wide = CohortRange::parse(Some("2020-01-01"), Some("bad"), today);
+    assert_eq!(wide.last, today);
+    assert_eq!(wide.days(), MAX_RANGE_DAYS);
+
+    let explicit = CohortRange::parse_baseline(Some("2026-09-01"), Some("2026-09-07"), range);
+    assert_eq!(explicit.days(), 7);
+}
@@ -9,8 +9,8 @@
 use std::path::{Path, PathBuf};
 
 use systemprompt_web_admin::repositories::config::gateway::{
-    create_route, delete_route, get_gateway_config, glob_match, reorder_routes, slugify_pattern,
-    synthesize_route_id, update_gateway_settings, update_route,
+    create_route, delete_route, get_gateway_config, get_resolved_gateway, glob_match,
+    reorder_routes, slugify_pattern, synthesize_route_id, update_gateway_settings, update_route,
 };
 use systemprompt_web_admin::types::{GatewayRouteView, UpdateGatewaySettingsRequest};
 use systemprompt_web_shared::error::MarketplaceError;
@@ -138,6 +138,28 @@ fn routes_missing_provider_are_dropped_from_the_read_view() -> anyhow::Result<()
     Ok(())
 }
 
+#[test]
+fn the_resolved_view_carries_conditions_and_the_default_provider() -> anyhow::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let path = profile(
+        dir.path(),
+        "providers:\n  - name: anthropic\n    wire: anthropic\n    surface: anthropic\n    endpoint: https://api.anthropic.com/v1\n    api_key_secret: anthropic\n    models: []\ngateway:\n  default_provider: anthropic\n  routes:\n    - model_pattern: claude-*\n      provider: anthropic\n      when:\n        stream: true\n",
+    );
+    let (config, registry) = get_resolved_gateway(&path)?;
+    assert!(
+        config.routes[0]
+            .when
+            .is_some_and(|w| w.stream == Some(true))
+    );
+    assert!(!config.routes[0].id.as_str().is_empty());
+    assert_eq!(config.candidate_routes(&registry).count(), 2);
+
+    let (bare, registry) = get_resolved_gateway(&profile(dir.path(), "{}\n"))?;
+    assert!(bare.routes.is_empty() && !bare.enabled);
+    assert!(registry.providers.is_empty());
+    Ok(())
+}
+
 #[test]
 fn update_route_replaces_in_place_and_reports_out_of_range() -> anyhow::Result<()> {
     let dir = tempfile::tempdir()?;
@@ -0,0 +1,166 @@
+//! Cost watch judgement: when an hour of spend is anomalous for its subject,
+//! how month-end spend is projected, and how the thresholds file loads.
+
+#![allow(
+    clippy::expect_used,
+    clippy::panic,
+    clippy::needless_pass_by_value,
+    clippy::redundant_clone,
+    reason = "test code: panics are the assertion mechanism and clones keep fixtures readable"
+)]
+
+use chrono::{DateTime, Duration, NaiveDate, TimeZone, Utc};
+use systemprompt_web_admin::cost_watch::anomaly::{
+    SEVERITY_CRITICAL, SEVERITY_WARNING, baseline, find_anomalies, judge,
+};
+use systemprompt_web_admin::cost_watch::forecast::{history_start, project_months};
+use systemprompt_web_admin::repositories::config::cost_watch::{
+    CostWatchConfig, load_cost_watch_config,
+};
+use systemprompt_web_admin::repositories::cost_watch::{DailySpend, HourlySpend, SpendScope};
+
+const USD: i64 = 1_000_000;
+
+fn hour(h: i64) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2026, 10, 15, 0, 0, 0)
+        .single()
+        .expect("valid")
+        + Duration::hours(h)
+}
+
+fn spend(subject: &str, h: i64, micros: i64) -> HourlySpend {
+    HourlySpend {
+        subject: subject.to_owned(),
+        hour_start: hour(h),
+        spend_microdollars: micros,
+        requests: 10,
+    }
+}
+
+const fn day(d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2026, 10, d).expect("valid")
+}
+
+fn daily(department: &str, day: NaiveDate, usd: i64) -> DailySpend {
+    DailySpend {
+        department: department.to_owned(),
+        day,
+        spend_microdollars: usd * USD,
+    }
+}
+
+#[test]
+fn baseline_counts_silent_hours_as_zero() {
+    let norm = baseline(&[4 * USD, 4 * USD], 4);
+    assert!((norm.mean - 2e6).abs() < 1e-6);
+    assert!((norm.stddev - 2e6).abs() < 1e-6);
+}
+
+#[test]
+fn an_hour_must_break_ratio_spread_and_floor_to_be_flagged() {
+    let config = CostWatchConfig::default();
+    let steady = baseline(&vec![USD; 336], 336);
+
+    let verdict = judge(20 * USD, steady, &config).expect("20x a flat norm");
+    assert_eq!(verdict.severity, SEVERITY_WARNING);
+    assert!((verdict.ratio - 20.0).abs() < 1e-9);
+    assert_eq!(
+        judge(60 * USD, steady, &config).map(|v| v.severity),
+        Some(SEVERITY_CRITICAL)
+    );
+    assert!(judge(5 * USD, steady, &config).is_none(), "under the ratio");
+
+    let quiet = baseline(&[], 336);
+    assert!(
+        judge(USD / 2, quiet, &config).is_none(),
+        "under min_spend_usd however quiet the subject"
+    );
+    assert!(
+        judge(2 * USD, quiet, &config).is_some(),
+        "mean floored at min_baseline_usd"
+    );
+
+    let bursty = baseline(&[30 * USD; 33], 330);
+    assert!(
+        judge(30 * USD, bursty, &config).is_none(),
+        "a routine peak sits inside the spread"
+    );
+}
+
+#[test]
+fn find_anomalies_judges_only_the_evaluated_hours_per_subject() {
+    let config = CostWatchConfig::default();
+    let mut rows: Vec<HourlySpend> = (0..336).map(|h| spend("alice", h, USD / 5)).collect();
+    rows.extend((0..336).map(|h| spend("bob", h, 3 * USD)));
+    rows.push(spend("alice", 340, 40 * USD));
+    rows.push(spend("bob", 340, 4 * USD));
+    rows.push(spend("alice", 300, 40 * USD / 3));
+
+    let found = find_anomalies(SpendScope::User, &rows, hour(338), &config);
+    assert_eq!(found.len(), 1, "{found:?}");
+    let a = &found[0];
+    assert_eq!(a.subject, "alice");
+    assert_eq!(a.hour_start, hour(340));
+    assert_eq!(a.scope, SpendScope::User);
+    assert_eq!(a.severity, SEVERITY_CRITICAL);
+    assert!(a.baseline_microdollars > USD / 5, "{a:?}");
+}
+
+#[test]
+fn forecast_projects_the_trailing_rate_over_the_rest_of_the_month() {
+    let mut rows: Vec<DailySpend> = (1..=14).map(|d| daily("Eng", day(d), 10)).collect();
+    rows.push(daily("Eng", day(15), 3));
+    rows.push(daily(
+        "Eng",
+        NaiveDate::from_ymd_opt(2026, 9, 20).expect("valid"),
+        250,
+    ));
+    rows.push(daily("Sales", day(2), 70));
+
+    let forecasts = project_months(&rows, day(15), 7);
+    let eng = forecasts
+        .iter()
+        .find(|f| f.department == "Eng")
+        .expect("eng");
+    assert_eq!(eng.month_start, day(1));
+    assert_eq!(eng.spent_microdollars, 143 * USD);
+    assert_eq!(eng.daily_rate_microdollars, 10 * USD);
+    assert_eq!(eng.forecast_microdollars, (140 + 17 * 10) * USD);
+    assert_eq!(eng.previous_month_microdollars, 250 * USD);
+
+    let sales = forecasts
+        .iter()
+        .find(|f| f.department == "Sales")
+        .expect("sales");
+    assert_eq!(sales.daily_rate_microdollars, 0);
+    assert_eq!(sales.forecast_microdollars, 70 * USD, "never below spent");
+
+    assert_eq!(
+        history_start(day(3), 7),
+        NaiveDate::from_ymd_opt(2026, 9, 1).expect("valid")
+    );
+}
+
+#[test]
+fn thresholds_file_is_optional_but_validated() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    assert_eq!(
+        load_cost_watch_config(dir.path()).expect("missing file"),
+        CostWatchConfig::default()
+    );
+
+    std::fs::create_dir_all(dir.path().join("finops")).expect("mkdir");
+    let file = dir.path().join("finops/cost_watch.yaml");
+    std::fs::write(&file, "ratio_threshold: 5\nbaseline_days: 7\n").expect("write");
+    let config = load_cost_watch_config(dir.path()).expect("valid file");
+    assert!((config.ratio_threshold - 5.0).abs() < f64::EPSILON);
+    assert_eq!(config.baseline_days, 7);
+
+    std::fs::write(&file, "ratio_threshold: 60\n").expect("write");
+    assert!(
+        load_cost_watch_config(dir.path()).is_err(),
+        "critical below warning"
+    );
+    std::fs::write(&file, "hourly: true\n").expect("write");
+    assert!(load_cost_watch_config(dir.path()).is_err());
+}
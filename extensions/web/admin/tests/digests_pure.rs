@@ -0,0 +1,163 @@
+//! Digest reports: which period a digest covers, how the subscriptions file
+//! loads, what the email body escapes, and what the file mail sink writes.
+
+#![allow(
+    clippy::expect_used,
+    clippy::panic,
+    reason = "test code: panics are the assertion mechanism"
+)]
+
+use chrono::{DateTime, TimeZone, Utc, Weekday};
+use systemprompt::identifiers::{SessionId, UserId};
+use systemprompt_web_admin::digests::{
+    Digest, DigestCadence, DigestModelRow, DigestPeriod, DigestSpend, FileMailSink, MailMessage,
+    MailTransport, latest_period, render_html, summary_line,
+};
+use systemprompt_web_admin::repositories::config::digests::{DigestConfig, load_digest_config};
+use systemprompt_web_admin::repositories::digests::sections::{
+    DigestEvals, DigestFindingRow, DigestSessionRow,
+};
+
+fn at(day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2026, 10, day, hour, minute, 0)
+        .single()
+        .expect("valid")
+}
+
+fn digest() -> Digest {
+    Digest {
+        subscription_id: "eng".to_owned(),
+        title: "Engineering <daily>".to_owned(),
+        scope: "Department R&D".to_owned(),
+        period: DigestPeriod {
+            start: at(14, 7, 0),
+            end: at(15, 7, 0),
+        },
+        spend: DigestSpend {
+            requests: 40,
+            spend_microdollars: 12_500_000,
+            users: 3,
+        },
+        previous_spend_microdollars: 10_000_000,
+        top_models: vec![DigestModelRow {
+            model: "<script>alert(1)</script>".to_owned(),
+            requests: 40,
+            spend_microdollars: 12_500_000,
+        }],
+        decisions: 10,
+        denials: 1,
+        denied_policies: Vec::new(),
+        safety_findings: vec![DigestFindingRow {
+            severity: "high".to_owned(),
+            category: "secret".to_owned(),
+            findings: 2,
+        }],
+        evals: DigestEvals {
+            scored: 4,
+            pass: 3,
+            partial: 1,
+            fail: 0,
+        },
+        notable_sessions: vec![DigestSessionRow {
+            session_id: SessionId::new("sess-1".to_owned()),
+            user_id: UserId::new("alice"),
+            title: "Fix \"quotes\" & things".to_owned(),
+            quality_score: 2,
+            goal_achieved: "no".to_owned(),
+        }],
+    }
+}
+
+#[test]
+fn daily_period_ends_at_the_latest_send_hour() {
+    let period = latest_period(DigestCadence::Daily, 7, Weekday::Mon, at(15, 9, 30));
+    assert_eq!((period.start, period.end), (at(14, 7, 0), at(15, 7, 0)));
+
+    let early = latest_period(DigestCadence::Daily, 7, Weekday::Mon, at(15, 6, 59));
+    assert_eq!((early.start, early.end), (at(13, 7, 0), at(14, 7, 0)));
+}
+
+#[test]
+fn weekly_period_ends_on_the_configured_day() {
+    // Why: 2026-10-12 is a Monday; on Thursday the 15th the last closed week
+    // ended that Monday.
+    let period = latest_period(DigestCadence::Weekly, 7, Weekday::Mon, at(15, 9, 0));
+    assert_eq!((period.start, period.end), (at(5, 7, 0), at(12, 7, 0)));
+
+    let on_the_day = latest_period(DigestCadence::Weekly, 7, Weekday::Thu, at(15, 7, 0));
+    assert_eq!(on_the_day.end, at(15, 7, 0));
+}
+
+#[test]
+fn subscriptions_file_loads_and_validates() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    assert_eq!(
+        load_digest_config(dir.path()).expect("missing file"),
+        DigestConfig::default()
+    );
+
+    std::fs::create_dir_all(dir.path().join("reports")).expect("mkdir");
+    let file = dir.path().join("reports/digests.yaml");
+    std::fs::write(
+        &file,
+        "weekly_on: fri\nsubscriptions:\n  - id: eng\n    cadence: weekly\n    department: Engineering\n    email: [eng@example.com]\n",
+    )
+    .expect("write");
+    let config = load_digest_config(dir.path()).expect("valid file");
+    assert_eq!(config.weekly_on, Weekday::Fri);
+    assert_eq!(config.subscriptions[0].title(), "eng");
+
+    for bad in [
+        "subscriptions:\n  - id: eng\n    cadence: daily\n",
+        "subscriptions:\n  - id: eng\n    cadence: daily\n    email: [nobody]\n",
+        "subscriptions:\n  - id: eng\n    cadence: daily\n    user_id: a\n    department: b\n    email: [a@b.c]\n",
+        "subscriptions:\n  - id: e g\n    cadence: daily\n    email: [a@b.c]\n",
+        "send_hour_utc: 24\n",
+    ] {
+        std::fs::write(&file, bad).expect("write");
+        assert!(load_digest_config(dir.path()).is_err(), "{bad}");
+    }
+}
+
+#[test]
+fn html_body_escapes_every_value() {
+    let html = render_html(&digest(), "https://admin.example.com/");
+    assert!(!html.contains("<script>"));
+    assert!(html.contains("&lt;script&gt;"));
+    assert!(html.contains("Engineering &lt;daily&gt;"));
+    assert!(html.contains("R&amp;D"));
+    assert!(html.contains("https://admin.example.com/admin/"));
+    assert!(html.contains("+25% on the period before"));
+    assert!(html.contains("75%"));
+}
+
+#[test]
+fn summary_line_counts_denials_and_findings() {
+    let line = summary_line(&digest());
+    assert!(line.contains("1 denial,"), "{line}");
+    assert!(line.contains("2 safety findings"), "{line}");
+    assert!(line.ends_with("(Oct 14 to Oct 15)"), "{line}");
+}
+
+#[tokio::test]
+async fn file_sink_writes_an_eml_per_message() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let sink = FileMailSink::new(dir.path().join("out"));
+    let message = MailMessage {
+        from: "digests@example.com".to_owned(),
+        to: vec!["a@example.com".to_owned(), "b@example.com".to_owned()],
+        subject: "Engineering — $12.50 spent".to_owned(),
+        html: "<p>hi</p>".to_owned(),
+    };
+    sink.send(&message).await.expect("send");
+
+    let files: Vec<_> = std::fs::read_dir(dir.path().join("out"))
+        .expect("dir")
+        .map(|e| e.expect("entry").path())
+        .collect();
+    assert_eq!(files.len(), 1);
+    let eml = std::fs::read_to_string(&files[0]).expect("read");
+    assert!(eml.contains("To: a@example.com, b@example.com\r\n"));
+    assert!(eml.contains("Subject: =?UTF-8?B?"));
+    assert!(eml.contains("Content-Type: text/html; charset=utf-8\r\n"));
+}
@@ -0,0 +1,113 @@
+//! Right to erasure: pseudonyms, legal holds and the export archive.
+
+#![allow(
+    clippy::expect_used,
+    clippy::panic,
+    reason = "test code: panics are the assertion mechanism"
+)]
+
+use chrono::{DateTime, Utc};
+use systemprompt::identifiers::UserId;
+use systemprompt_web_admin::erasure::archive::write_zip;
+use systemprompt_web_admin::erasure::{export_archive, hold_reason, pseudonym};
+use systemprompt_web_admin::repositories::config::retention::RetentionConfig;
+
+fn u16_at(bytes: &[u8], at: usize) -> u16 {
+    u16::from_le_bytes([bytes[at], bytes[at + 1]])
+}
+
+fn u32_at(bytes: &[u8], at: usize) -> u32 {
+    u32::from_le_bytes([bytes[at], bytes[at + 1], bytes[at + 2], bytes[at + 3]])
+}
+
+/// (name, contents) of every entry, read through the central directory the
+/// way an unzip tool does, checking each CRC.
+fn read_zip(bytes: &[u8]) -> Vec<(String, Vec<u8>)> {
+    let end = bytes.len() - 22;
+    assert_eq!(u32_at(bytes, end), 0x0605_4b50, "end of central directory");
+    let count = usize::from(u16_at(bytes, end + 10));
+    let mut at = u32_at(bytes, end + 16) as usize;
+    let mut out = Vec::new();
+    for _ in 0..count {
+        assert_eq!(u32_at(bytes, at), 0x0201_4b50, "central header");
+        let crc = u32_at(bytes, at + 16);
+        let size = u32_at(bytes, at + 20) as usize;
+        let name_len = usize::from(u16_at(bytes, at + 28));
+        let local = u32_at(bytes, at + 42) as usize;
+        let name = String::from_utf8(bytes[at + 46..at + 46 + name_len].to_vec()).expect("name");
+        assert_eq!(u32_at(bytes, local), 0x0403_4b50, "local header");
+        let data_at = local + 30 + usize::from(u16_at(bytes, local + 26));
+        let data = bytes[data_at..data_at + size].to_vec();
+        assert_eq!(crc32fast::hash(&data), crc, "crc of {name}");
+        out.push((name, data));
+        at += 46 + name_len;
+    }
+    out
+}
+
+#[test]
+fn pseudonym_is_stable_per_user_and_keyed() {
+    let alice = UserId::new("alice");
+    let first = pseudonym(b"secret-one", &alice);
+    assert_eq!(first, pseudonym(b"secret-one", &alice));
+    assert!(first.starts_with("erased-"));
+    assert_eq!(first.len(), "erased-".len() + 32);
+    assert!(!first.contains("alice"));
+    assert_ne!(first, pseudonym(b"secret-one", &UserId::new("bob")));
+    assert_ne!(first, pseudonym(b"secret-two", &alice));
+}
+
+#[test]
+fn legal_hold_on_the_user_blocks_erasure() {
+    let config: RetentionConfig = serde_yaml::from_str(
+        "legal_holds:\n  - user_id: alice\n    reason: litigation 2026-14\n  - session_id: s1\n    reason: incident\n",
+    )
+    .expect("yaml");
+    assert_eq!(
+        hold_reason(&config.legal_holds, &UserId::new("alice")),
+        Some("litigation 2026-14")
+    );
+    assert_eq!(hold_reason(&config.legal_holds, &UserId::new("bob")), None);
+}
+
+#[test]
+fn zip_round_trips_names_and_contents() {
+    let at = "2026-10-19T14:05:30Z".parse::<DateTime<Utc>>().expect("ts");
+    let entries = vec![
+        ("a.json".to_owned(), b"[]".to_vec()),
+        ("données.json".to_owned(), br#"{"k":"v"}"#.to_vec()),
+        ("empty.json".to_owned(), Vec::new()),
+    ];
+    let bytes = write_zip(&entries, at.naive_utc()).expect("zip");
+    assert_eq!(read_zip(&bytes), entries);
+}
+
+#[test]
+fn export_archive_writes_a_manifest_and_one_file_per_table() {
+    let at = "2026-10-19T14:05:30Z".parse::<DateTime<Utc>>().expect("ts");
+    let export = serde_json::json!({
+        "account": {"id": "alice", "email": "alice@example.com"},
+        "ai_requests": [{"id": "r1"}, {"id": "r2"}],
+        "settings": null,
+    });
+    let bytes = export_archive(&export, &UserId::new("alice"), at).expect("archive");
+    let files = read_zip(&bytes);
+    let names: Vec<&str> = files.iter().map(|(n, _)| n.as_str()).collect();
+    assert_eq!(
+        names,
+        [
+            "manifest.json",
+            "account.json",
+            "ai_requests.json",
+            "settings.json"
+        ]
+    );
+
+    let manifest: serde_json::Value = serde_json::from_slice(&files[0].1).expect("manifest");
+    assert_eq!(manifest["user_id"], "alice");
+    assert_eq!(manifest["files"]["ai_requests.json"], 2);
+    assert_eq!(manifest["files"]["account.json"], 1);
+    assert_eq!(manifest["files"]["settings.json"], 0);
+    let requests: serde_json::Value = serde_json::from_slice(&files[2].1).expect("requests");
+    assert_eq!(requests[1]["id"], "r2");
+}
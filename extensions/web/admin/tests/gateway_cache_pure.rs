@@ -0,0 +1,228 @@
+//! Gateway response cache without a gateway: key derivation, similarity,
+//! replaying a stored reply, rebuilding one from a stream, and the
+//! `cache.yaml` sidecar.
+
+#![allow(
+    clippy::expect_used,
+    clippy::panic,
+    clippy::needless_pass_by_value,
+    clippy::redundant_clone,
+    reason = "test code: panics are the assertion mechanism and clones keep fixtures readable"
+)]
+
+use serde_json::json;
+use systemprompt::models::wire::canonical::{
+    CanonicalContent, CanonicalEvent, CanonicalResponse, CanonicalStopReason, ImageSource,
+};
+use systemprompt_web_admin::gateway_cache::{
+    CacheScope, CachedBlock, CachedResponse, StreamRecorder, best_match, cache_keys,
+    cosine_similarity,
+};
+use systemprompt_web_admin::repositories::config::gateway::load_response_cache_config;
+
+const SCOPE: CacheScope<'static> = CacheScope {
+    provider: "anthropic",
+    upstream_model: "claude-sonnet",
+    user_id: Some("user-1"),
+};
+
+fn body(value: serde_json::Value) -> Vec<u8> {
+    serde_json::to_vec(&value).expect("json")
+}
+
+fn reply() -> CachedResponse {
+    CachedResponse {
+        model: "claude-sonnet".to_owned(),
+        content: vec![
+            CachedBlock::Thinking {
+                text: "consider".to_owned(),
+                signature: Some("sig".to_owned()),
+                id: None,
+                encrypted_content: None,
+            },
+            CachedBlock::Text {
+                text: "Paris".to_owned(),
+            },
+            CachedBlock::ToolUse {
+                id: "toolu_1".to_owned(),
+                name: "lookup".to_owned(),
+                input: json!({"city": "Paris"}),
+                signature: None,
+            },
+        ],
+        stop_reason: Some("tool_use".to_owned()),
+    }
+}
+
+#[test]
+fn keys_ignore_delivery_and_field_order_but_not_content_or_scope() {
+    let a = cache_keys(
+        SCOPE,
+        &body(
+            json!({"model": "m", "stream": true, "messages": [{"role": "user", "content": "hi"}]}),
+        ),
+    )
+    .expect("keys");
+    let b = cache_keys(
+        SCOPE,
+        &body(json!({"messages": [{"content": "hi", "role": "user"}], "model": "m"})),
+    )
+    .expect("keys");
+    assert_eq!(a, b);
+
+    let other_text = cache_keys(
+        SCOPE,
+        &body(json!({"model": "m", "messages": [{"role": "user", "content": "bye"}]})),
+    )
+    .expect("keys");
+    assert_ne!(a.exact, other_text.exact);
+    assert_eq!(a.shape, other_text.shape, "only the final message differs");
+
+    let shared = CacheScope {
+        user_id: None,
+        ..SCOPE
+    };
+    let unscoped = cache_keys(
+        shared,
+        &body(json!({"model": "m", "messages": [{"role": "user", "content": "hi"}]})),
+    )
+    .expect("keys");
+    assert_ne!(a.exact, unscoped.exact);
+
+    assert!(cache_keys(SCOPE, b"[1, 2]").is_none());
+}
+
+#[test]
+fn shape_keeps_earlier_turns_and_system() {
+    let keys = |system: &str, first: &str| {
+        cache_keys(
+            SCOPE,
+            &body(json!({
+                "system": system,
+                "messages": [
+                    {"role": "user", "content": first},
+                    {"role": "assistant", "content": "ok"},
+                    {"role": "user", "content": "last"},
+                ],
+            })),
+        )
+        .expect("keys")
+        .shape
+    };
+    assert_eq!(keys("s", "a"), keys("s", "a"));
+    assert_ne!(keys("s", "a"), keys("s", "b"));
+    assert_ne!(keys("s", "a"), keys("t", "a"));
+}
+
+#[test]
+fn similarity_picks_the_closest_candidate_over_the_threshold() {
+    assert!((cosine_similarity(&[1.0, 0.0], &[1.0, 0.0]).expect("sim") - 1.0).abs() < 1e-6);
+    assert!(
+        cosine_similarity(&[1.0, 0.0], &[0.0, 1.0])
+            .expect("sim")
+            .abs()
+            < 1e-6
+    );
+    assert_eq!(cosine_similarity(&[1.0], &[1.0, 0.0]), None);
+    assert_eq!(cosine_similarity(&[0.0, 0.0], &[1.0, 0.0]), None);
+
+    let near = [0.9f32, 0.1];
+    let nearer = [0.99f32, 0.01];
+    let far = [0.0f32, 1.0];
+    let candidates = [(1, &near[..]), (2, &nearer[..]), (3, &far[..])];
+    let (id, similarity) = best_match(&[1.0, 0.0], candidates, 0.95).expect("match");
+    assert_eq!(id, 2);
+    assert!(similarity > 0.99);
+    assert!(best_match(&[0.0, 1.0], [(1, &near[..])], 0.95).is_none());
+}
+
+#[test]
+fn replay_has_zero_usage_and_round_trips_through_a_stream() {
+    let cached = reply();
+    let canonical = cached.to_canonical("cache_1");
+    assert_eq!(canonical.id, "cache_1");
+    assert_eq!(
+        canonical.usage.input_tokens + canonical.usage.output_tokens,
+        0
+    );
+    assert!(matches!(
+        canonical.stop_reason,
+        Some(CanonicalStopReason::ToolUse)
+    ));
+    assert_eq!(
+        CachedResponse::from_canonical(&canonical),
+        Some(cached.clone())
+    );
+
+    let mut recorder = StreamRecorder::default();
+    for event in cached.to_events("cache_2") {
+        recorder.record(&event);
+    }
+    assert_eq!(recorder.finish(), Some(cached));
+}
+
+#[test]
+fn incomplete_or_failed_streams_and_unreplayable_replies_are_not_cached() {
+    let events = reply().to_events("cache_3");
+    let mut cut_off = StreamRecorder::default();
+    for event in &events[..events.len() - 1] {
+        cut_off.record(event);
+    }
+    assert_eq!(cut_off.finish(), None);
+
+    let mut failed = StreamRecorder::default();
+    for event in &events {
+        failed.record(event);
+    }
+    failed.record(&CanonicalEvent::Error("upstream reset".to_owned()));
+    assert_eq!(failed.finish(), None);
+
+    let with_image = CanonicalResponse {
+        content: vec![CanonicalContent::Image(ImageSource::Url {
+            url: "https://example.com/a.png".to_owned(),
+            detail: None,
+        })],
+        ..CanonicalResponse::default()
+    };
+    assert_eq!(CachedResponse::from_canonical(&with_image), None);
+}
+
+#[test]
+fn cache_file_defaults_and_validation() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    assert!(
+        load_response_cache_config(dir.path())
+            .expect("load")
+            .routes
+            .is_empty()
+    );
+
+    let write = |yaml: &str| {
+        std::fs::create_dir_all(dir.path().join("gateway")).expect("mkdir");
+        std::fs::write(dir.path().join("gateway/cache.yaml"), yaml).expect("write");
+        load_response_cache_config(dir.path())
+    };
+
+    let config = write("routes:\n  r1: {}\n").expect("load");
+    let settings = config.settings_for("r1").expect("r1 cached");
+    assert_eq!(settings.ttl_secs, 3_600);
+    assert!(settings.per_user);
+    assert!(config.settings_for("r2").is_none());
+
+    assert!(write("routes:\n  r1: { similarity_threshold: 0.9 }\n").is_err());
+    assert!(write("routes:\n  r1: { ttl_secs: 0 }\n").is_err());
+    assert!(
+        write(
+            "embedding: { endpoint: http://localhost/v1/embeddings, model: e }\n\
+             routes:\n  r1: { similarity_threshold: 1.5 }\n"
+        )
+        .is_err()
+    );
+    assert!(
+        write(
+            "embedding: { endpoint: http://localhost/v1/embeddings, model: e }\n\
+             routes:\n  r1: { similarity_threshold: 0.95, per_user: false }\n"
+        )
+        .is_ok()
+    );
+}
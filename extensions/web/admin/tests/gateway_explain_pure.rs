@@ -0,0 +1,180 @@
+//! Route explainer pieces that need no database: the walk over core's
+//! candidate routes, header masking, and finding the deciding rule.
+
+#![allow(
+    clippy::expect_used,
+    clippy::panic,
+    clippy::needless_pass_by_value,
+    clippy::redundant_clone,
+    reason = "test code: panics are the assertion mechanism and clones keep fixtures readable"
+)]
+
+use systemprompt::identifiers::{RouteId, RuleId, UserId};
+use systemprompt::models::profile::{GatewayConfig, GatewayConfigSpec, ProviderRegistry};
+use systemprompt::models::wire::canonical::CanonicalRequest;
+use systemprompt_security::authz::{
+    Access, AccessRule, Decision, DenyReason, EntityRef, MatchedBy, RuleType,
+};
+use systemprompt_web_admin::repositories::config::gateway::{
+    explain_route_match, mask_header_value,
+};
+use systemprompt_web_admin::repositories::config::gateway_acl::find_deciding_rule;
+use systemprompt_web_admin::types::RouteMatchOutcome;
+
+const PROVIDERS: &str = r"
+- name: anthropic
+  wire: anthropic
+  surface: anthropic
+  endpoint: https://api.anthropic.com/v1
+  api_key_secret: anthropic
+  models: []
+";
+
+fn registry() -> ProviderRegistry {
+    serde_yaml::from_str(PROVIDERS).expect("provider registry")
+}
+
+fn gateway(yaml: &str) -> GatewayConfig {
+    serde_yaml::from_str::<GatewayConfigSpec>(yaml)
+        .expect("gateway spec")
+        .resolve()
+}
+
+fn plain(model: &str) -> CanonicalRequest {
+    CanonicalRequest {
+        model: model.to_owned(),
+        ..CanonicalRequest::default()
+    }
+}
+
+fn outcomes(config: &GatewayConfig, model: &str) -> Vec<RouteMatchOutcome> {
+    explain_route_match(config, &registry(), &plain(model))
+        .into_iter()
+        .map(|s| s.outcome)
+        .collect()
+}
+
+fn rule(rule_type: RuleType, value: &str, access: Access) -> AccessRule {
+    AccessRule {
+        id: RuleId::new(format!("{rule_type}-{value}")),
+        rule_type,
+        rule_value: value.to_owned(),
+        access,
+        justification: None,
+    }
+}
+
+#[test]
+fn first_match_wins_and_later_routes_are_shadowed() {
+    let config = gateway(
+        r#"
+routes:
+- { id: opus, model_pattern: "claude-opus-*", provider: anthropic }
+- { id: sonnet, model_pattern: "claude-sonnet-*", provider: anthropic }
+- { id: catch-all, model_pattern: "*", provider: anthropic }
+"#,
+    );
+    assert_eq!(
+        outcomes(&config, "claude-sonnet-4"),
+        vec![
+            RouteMatchOutcome::Skipped,
+            RouteMatchOutcome::Matched,
+            RouteMatchOutcome::Shadowed
+        ]
+    );
+    let narrow = gateway(
+        r#"
+routes:
+- { id: opus, model_pattern: "claude-opus-*", provider: anthropic }
+"#,
+    );
+    assert_eq!(
+        outcomes(&narrow, "gpt-4o"),
+        vec![RouteMatchOutcome::Skipped]
+    );
+}
+
+#[test]
+fn the_default_provider_route_catches_what_the_profile_routes_miss() {
+    let config = gateway(
+        r#"
+default_provider: anthropic
+routes:
+- { id: opus, model_pattern: "claude-opus-*", provider: anthropic }
+"#,
+    );
+    let steps = explain_route_match(&config, &registry(), &plain("gpt-4o"));
+    assert_eq!(steps.len(), 2);
+    assert!(!steps[0].default_route);
+    assert!(steps[1].default_route);
+    assert_eq!(steps[1].model_pattern, "*");
+    assert_eq!(steps[1].outcome, RouteMatchOutcome::Matched);
+    assert!(!steps[1].id.is_empty());
+
+    let unknown = gateway("default_provider: nobody\nroutes: []\n");
+    assert!(explain_route_match(&unknown, &registry(), &plain("gpt-4o")).is_empty());
+}
+
+#[test]
+fn when_conditions_are_checked_against_the_request() {
+    let config = gateway(
+        r#"
+routes:
+- id: thinking
+  model_pattern: "claude-*"
+  provider: anthropic
+  when: { thinking: true }
+- { id: plain, model_pattern: "claude-*", provider: anthropic }
+"#,
+    );
+    let steps = explain_route_match(&config, &registry(), &plain("claude-sonnet-4"));
+    assert_eq!(steps[0].outcome, RouteMatchOutcome::Unmet);
+    assert_eq!(steps[0].conditions, vec!["thinking"]);
+    assert_eq!(steps[1].outcome, RouteMatchOutcome::Matched);
+    assert!(steps[1].conditions.is_empty());
+}
+
+#[test]
+fn masking_keeps_a_short_prefix_of_long_values_only() {
+    assert_eq!(mask_header_value("short"), "••••");
+    let masked = mask_header_value("sk-ant-REDACTED");
+    assert!(masked.starts_with("sk-a••••"));
+    assert!(!masked.contains("abcdef"));
+}
+
+#[test]
+fn deciding_rule_follows_the_decision_variant() {
+    let user = UserId::new("u-1");
+    let entity = EntityRef::GatewayRoute(RouteId::new("sonnet".to_owned()));
+    let rules = [
+        rule(RuleType::ROLE, "engineer", Access::Allow),
+        rule(RuleType::USER, "u-1", Access::Deny),
+    ];
+
+    let by_role = Decision::Allow {
+        matched_by: MatchedBy::RoleAllow {
+            role: "engineer".to_owned(),
+        },
+    };
+    let found = find_deciding_rule(&rules, &by_role, &user).expect("role rule");
+    assert_eq!(found.rule_value, "engineer");
+
+    let user_deny = Decision::Deny {
+        reason: DenyReason::UserDeny {
+            entity: entity.clone(),
+            user_id: user.clone(),
+            justification: None,
+        },
+    };
+    let found = find_deciding_rule(&rules, &user_deny, &user).expect("user rule");
+    assert_eq!(found.access, Access::Deny);
+
+    let default = Decision::Allow {
+        matched_by: MatchedBy::DefaultIncluded,
+    };
+    assert!(find_deciding_rule(&rules, &default, &user).is_none());
+    let unknown = Decision::Deny {
+        reason: DenyReason::UnknownEntity { entity },
+    };
+    assert!(find_deciding_rule(&rules, &unknown, &user).is_none());
+}
@@ -0,0 +1,176 @@
+//! Gateway config versioning without a database: snapshot etags, restoring a
+//! snapshot over later edits, staged edits that land both files together, and
+//! the side-by-side diff the history page shows.
+
+#![allow(
+    clippy::expect_used,
+    clippy::panic,
+    clippy::needless_pass_by_value,
+    clippy::redundant_clone,
+    reason = "test code: panics are the assertion mechanism and clones keep fixtures readable"
+)]
+
+use systemprompt_web_admin::repositories::config::gateway::{
+    StagedGatewayFiles, create_route, get_gateway_snapshot, restore_gateway_snapshot,
+    set_route_targets,
+};
+use systemprompt_web_admin::types::{GatewayRouteTarget, GatewayRouteView};
+use systemprompt_web_admin::util::line_diff::{DiffKind, side_by_side};
+
+const PROFILE: &str =
+    "name: test\nserver:\n  port: 8080\ngateway:\n  enabled: true\n  routes: []\n";
+
+fn route(id: &str) -> GatewayRouteView {
+    GatewayRouteView {
+        id: id.to_owned(),
+        model_pattern: format!("{id}-*"),
+        provider: "anthropic".to_owned(),
+        ..Default::default()
+    }
+}
+
+fn kinds(old: &str, new: &str) -> Vec<DiffKind> {
+    side_by_side(old, new).into_iter().map(|r| r.kind).collect()
+}
+
+#[test]
+fn etag_tracks_both_files_and_ignores_the_rest_of_the_profile() -> anyhow::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let profile = dir.path().join("profile.yaml");
+    std::fs::write(&profile, PROFILE)?;
+
+    let first = get_gateway_snapshot(&profile, dir.path())?;
+    assert_eq!(
+        first.etag(),
+        get_gateway_snapshot(&profile, dir.path())?.etag()
+    );
+    assert!(first.targets_yaml.is_empty());
+
+    std::fs::write(&profile, PROFILE.replace("8080", "9090"))?;
+    assert_eq!(
+        first.etag(),
+        get_gateway_snapshot(&profile, dir.path())?.etag()
+    );
+
+    create_route(&profile, &route("sonnet"))?;
+    let with_route = get_gateway_snapshot(&profile, dir.path())?;
+    assert_ne!(first.etag(), with_route.etag());
+
+    let target = GatewayRouteTarget {
+        id: "primary".to_owned(),
+        provider: "anthropic".to_owned(),
+        upstream_model: None,
+        weight: 1,
+    };
+    set_route_targets(dir.path(), "sonnet", None, &[target])?;
+    assert_ne!(
+        with_route.etag(),
+        get_gateway_snapshot(&profile, dir.path())?.etag()
+    );
+    Ok(())
+}
+
+#[test]
+fn restore_undoes_later_edits_and_keeps_other_keys() -> anyhow::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let profile = dir.path().join("profile.yaml");
+    std::fs::write(&profile, PROFILE)?;
+    let before = get_gateway_snapshot(&profile, dir.path())?;
+
+    create_route(&profile, &route("sonnet"))?;
+    let target = GatewayRouteTarget {
+        id: "primary".to_owned(),
+        provider: "anthropic".to_owned(),
+        upstream_model: None,
+        weight: 1,
+    };
+    set_route_targets(dir.path(), "sonnet", None, &[target])?;
+    std::fs::write(
+        &profile,
+        std::fs::read_to_string(&profile)?.replace("8080", "9090"),
+    )?;
+
+    restore_gateway_snapshot(&profile, dir.path(), &before)?;
+    assert_eq!(get_gateway_snapshot(&profile, dir.path())?, before);
+    assert!(!dir.path().join("gateway/targets.yaml").exists());
+    assert!(std::fs::read_to_string(&profile)?.contains("9090"));
+    Ok(())
+}
+
+#[test]
+fn restoring_an_empty_gateway_removes_the_block() -> anyhow::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let profile = dir.path().join("profile.yaml");
+    std::fs::write(&profile, "name: test\n")?;
+    let empty = get_gateway_snapshot(&profile, dir.path())?;
+    assert!(empty.gateway_yaml.is_empty());
+
+    std::fs::write(&profile, PROFILE)?;
+    restore_gateway_snapshot(&profile, dir.path(), &empty)?;
+    assert!(!std::fs::read_to_string(&profile)?.contains("gateway"));
+    Ok(())
+}
+
+fn entries(dir: &std::path::Path) -> anyhow::Result<Vec<String>> {
+    let mut names = std::fs::read_dir(dir)?
+        .map(|e| Ok(e?.file_name().to_string_lossy().into_owned()))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    names.sort();
+    Ok(names)
+}
+
+#[test]
+fn a_staged_edit_reaches_the_live_files_only_when_landed() -> anyhow::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let profile = dir.path().join("profile.yaml");
+    std::fs::write(&profile, PROFILE)?;
+    let before = get_gateway_snapshot(&profile, dir.path())?;
+    let target = GatewayRouteTarget {
+        id: "primary".to_owned(),
+        provider: "anthropic".to_owned(),
+        upstream_model: None,
+        weight: 1,
+    };
+
+    let abandoned = StagedGatewayFiles::stage(&profile, dir.path())?;
+    create_route(abandoned.profile_path(), &route("sonnet"))?;
+    drop(abandoned);
+    assert_eq!(get_gateway_snapshot(&profile, dir.path())?, before);
+    assert_eq!(entries(dir.path())?, vec!["profile.yaml"]);
+
+    let staged = StagedGatewayFiles::stage(&profile, dir.path())?;
+    create_route(staged.profile_path(), &route("sonnet"))?;
+    set_route_targets(staged.services_path(), "sonnet", None, &[target])?;
+    let edited = get_gateway_snapshot(staged.profile_path(), staged.services_path())?;
+    assert_eq!(get_gateway_snapshot(&profile, dir.path())?, before);
+
+    staged.land()?;
+    assert_eq!(get_gateway_snapshot(&profile, dir.path())?, edited);
+    assert_eq!(entries(dir.path())?, vec!["gateway", "profile.yaml"]);
+    Ok(())
+}
+
+#[test]
+fn diff_pairs_replaced_lines_and_keeps_context() {
+    use DiffKind::{Added, Changed, Removed, Same};
+    assert_eq!(kinds("a\nb\nc", "a\nb\nc"), vec![Same, Same, Same]);
+    assert_eq!(kinds("a\nb\nc", "a\nB\nc"), vec![Same, Changed, Same]);
+    assert_eq!(kinds("a\nc", "a\nb\nc"), vec![Same, Added, Same]);
+    assert_eq!(kinds("a\nb\nc", "a\nc"), vec![Same, Removed, Same]);
+    assert_eq!(kinds("", "x\ny"), vec![Added, Added]);
+
+    let rows = side_by_side("a\nb\nc", "a\nB\nc");
+    assert_eq!(rows[1].old.as_deref(), Some("b"));
+    assert_eq!(rows[1].new.as_deref(), Some("B"));
+    assert_eq!((rows[2].old_no, rows[2].new_no), (Some(3), Some(3)));
+}
+
+#[test]
+fn diff_of_uneven_replacement_leaves_one_side_blank() {
+    let rows = side_by_side("a\nx\nz", "a\ny1\ny2\nz");
+    assert_eq!(rows.len(), 4);
+    assert_eq!(rows[1].kind, DiffKind::Changed);
+    assert_eq!(rows[2].kind, DiffKind::Added);
+    assert_eq!(rows[2].old, None);
+    assert_eq!(rows[2].new_no, Some(3));
+}
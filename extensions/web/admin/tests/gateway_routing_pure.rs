@@ -0,0 +1,251 @@
+//! Gateway target selection without a gateway: the weighted split, fallback
+//! order, retry choice, health from recent failures, and the `targets.yaml`
+//! sidecar.
+
+#![allow(
+    clippy::expect_used,
+    clippy::panic,
+    clippy::needless_pass_by_value,
+    clippy::redundant_clone,
+    reason = "test code: panics are the assertion mechanism and clones keep fixtures readable"
+)]
+
+use chrono::{Duration, Utc};
+use systemprompt::api::services::gateway::protocol::outbound::UpstreamError;
+use systemprompt::models::profile::GatewayRoute;
+use systemprompt_web_admin::gateway_routing::{
+    UnhealthyUpstreams, choose_target, current_target, failover_target, is_retryable,
+    route_for_target, target_is_healthy,
+};
+use systemprompt_web_admin::repositories::analytics::upstream_health::UpstreamFailureRow;
+use systemprompt_web_admin::repositories::config::gateway::{
+    TargetHealthSettings, load_route_targets, set_route_targets, validate_targets,
+};
+use systemprompt_web_admin::types::GatewayRouteTarget;
+
+fn target(id: &str, provider: &str, model: &str, weight: u32) -> GatewayRouteTarget {
+    GatewayRouteTarget {
+        id: id.to_owned(),
+        provider: provider.to_owned(),
+        upstream_model: Some(model.to_owned()),
+        weight,
+    }
+}
+
+fn canary() -> Vec<GatewayRouteTarget> {
+    vec![
+        target("primary", "anthropic", "sonnet-a", 90),
+        target("canary", "anthropic", "sonnet-b", 10),
+        target("backup", "bedrock", "sonnet-a", 0),
+    ]
+}
+
+fn chosen(targets: &[GatewayRouteTarget], down: &[&str], roll: u64) -> String {
+    choose_target(targets, |t| !down.contains(&t.id.as_str()), roll)
+        .expect("a target")
+        .id
+        .clone()
+}
+
+#[test]
+fn weights_split_the_roll_space() {
+    let targets = canary();
+    assert_eq!(chosen(&targets, &[], 0), "primary");
+    assert_eq!(chosen(&targets, &[], 89), "primary");
+    assert_eq!(chosen(&targets, &[], 90), "canary");
+    assert_eq!(chosen(&targets, &[], 99), "canary");
+    assert_eq!(chosen(&targets, &[], 100), "primary");
+}
+
+#[test]
+fn zero_weight_targets_never_take_normal_traffic() {
+    let targets = canary();
+    assert!((0..100).all(|roll| chosen(&targets, &[], roll) != "backup"));
+}
+
+#[test]
+fn an_unhealthy_weighted_target_hands_its_share_to_the_rest() {
+    let targets = canary();
+    assert!((0..100).all(|roll| chosen(&targets, &["primary"], roll) == "canary"));
+}
+
+#[test]
+fn fallback_follows_list_order_once_weighted_targets_are_down() {
+    let targets = canary();
+    assert_eq!(chosen(&targets, &["primary", "canary"], 7), "backup");
+}
+
+#[test]
+fn all_down_still_sends_to_the_first_target() {
+    let targets = canary();
+    assert_eq!(
+        chosen(&targets, &["primary", "canary", "backup"], 7),
+        "primary"
+    );
+    assert!(choose_target(&[], |_| true, 0).is_none());
+}
+
+#[test]
+fn health_needs_the_threshold_and_expires_after_cooldown() {
+    let now = Utc::now();
+    let settings = TargetHealthSettings::default();
+    let row = |model: &str, failures: i64, ago: i64| UpstreamFailureRow {
+        provider: "anthropic".to_owned(),
+        model: model.to_owned(),
+        failures,
+        last_failure_at: now - Duration::seconds(ago),
+    };
+    let unhealthy = UnhealthyUpstreams::from_failures(
+        &[row("hot", 5, 10), row("few", 2, 10), row("cooled", 9, 120)],
+        settings,
+        now,
+    );
+    assert!(!unhealthy.is_healthy("anthropic", "hot"));
+    assert!(unhealthy.is_healthy("anthropic", "few"));
+    assert!(unhealthy.is_healthy("anthropic", "cooled"));
+    assert!(unhealthy.is_healthy("bedrock", "hot"));
+}
+
+#[test]
+fn a_retry_goes_to_the_next_healthy_target_and_never_back() {
+    let targets = canary();
+    let retry = |failed: usize, down: &[&str]| {
+        failover_target(&targets, &targets[failed], |t| {
+            !down.contains(&t.id.as_str())
+        })
+        .map(|t| t.id.clone())
+    };
+    assert_eq!(retry(0, &[]).as_deref(), Some("canary"));
+    assert_eq!(retry(1, &[]).as_deref(), Some("primary"));
+    assert_eq!(retry(0, &["canary"]).as_deref(), Some("backup"));
+    assert_eq!(retry(0, &["canary", "backup"]), None);
+}
+
+#[test]
+fn only_errors_another_upstream_could_avoid_are_retried() {
+    let status = |status: u16| {
+        anyhow::Error::new(UpstreamError::Status {
+            provider: "anthropic".to_owned(),
+            status,
+            message: "upstream said no".to_owned(),
+            body: Vec::new().into(),
+            retry_after: None,
+            request_id: None,
+        })
+    };
+    assert!(is_retryable(&status(429)));
+    assert!(is_retryable(&status(503)));
+    assert!(!is_retryable(&status(400)));
+    assert!(!is_retryable(&status(401)));
+    assert!(!is_retryable(&anyhow::anyhow!("build failed")));
+}
+
+#[test]
+fn a_target_without_a_model_is_judged_on_the_requested_one() {
+    let now = Utc::now();
+    let unhealthy = UnhealthyUpstreams::from_failures(
+        &[UpstreamFailureRow {
+            provider: "anthropic".to_owned(),
+            model: "sonnet".to_owned(),
+            failures: 9,
+            last_failure_at: now,
+        }],
+        TargetHealthSettings::default(),
+        now,
+    );
+    let inherit = GatewayRouteTarget {
+        upstream_model: None,
+        ..target("inherit", "anthropic", "", 1)
+    };
+    assert!(!target_is_healthy(&unhealthy, &inherit, "sonnet"));
+    assert!(target_is_healthy(&unhealthy, &inherit, "haiku"));
+    assert!(target_is_healthy(
+        &unhealthy,
+        &target("pinned", "anthropic", "haiku", 1),
+        "sonnet"
+    ));
+}
+
+#[test]
+fn a_swapped_route_names_its_target_and_drops_foreign_headers() {
+    let matched: GatewayRoute = serde_json::from_value(serde_json::json!({
+        "id": "claude",
+        "model_pattern": "claude-*",
+        "provider": "anthropic",
+        "extra_headers": {"anthropic-beta": "x"},
+    }))
+    .expect("route");
+    let targets = canary();
+
+    let same_vendor = route_for_target(&matched, &targets[1]);
+    assert_eq!(same_vendor.upstream_model.as_deref(), Some("sonnet-b"));
+    assert_eq!(same_vendor.extra_headers.len(), 1);
+    assert_eq!(
+        current_target(&targets, &same_vendor).map(|t| t.id.as_str()),
+        Some("canary")
+    );
+
+    let other_vendor = route_for_target(&matched, &targets[2]);
+    assert_eq!(other_vendor.provider.as_str(), "bedrock");
+    assert!(other_vendor.extra_headers.is_empty());
+    assert_eq!(
+        current_target(&targets, &other_vendor).map(|t| t.id.as_str()),
+        Some("backup")
+    );
+    assert_eq!(current_target(&targets, &matched), None);
+}
+
+#[test]
+fn validation_rejects_lists_the_selector_cannot_serve() {
+    assert!(validate_targets(&[]).is_ok());
+    assert!(validate_targets(&canary()).is_ok());
+    assert!(validate_targets(&[target("a", "anthropic", "m", 0)]).is_err());
+    assert!(validate_targets(&[target("", "anthropic", "m", 1)]).is_err());
+    assert!(validate_targets(&[target("a", " ", "m", 1)]).is_err());
+    assert!(
+        validate_targets(&[
+            target("a", "anthropic", "m", 1),
+            target("a", "bedrock", "m", 1)
+        ])
+        .is_err()
+    );
+    assert!(
+        validate_targets(&[
+            target("a", "anthropic", "m", 1),
+            target("b", "anthropic", "m", 1)
+        ])
+        .is_err()
+    );
+}
+
+#[test]
+fn sidecar_round_trips_renames_and_drops_entries() -> anyhow::Result<()> {
+    let dir = tempfile::tempdir()?;
+    assert!(load_route_targets(dir.path())?.routes.is_empty());
+
+    set_route_targets(dir.path(), "sonnet", None, &canary())?;
+    let loaded = load_route_targets(dir.path())?;
+    assert_eq!(loaded.targets_for("sonnet"), canary().as_slice());
+    assert_eq!(loaded.health, TargetHealthSettings::default());
+
+    set_route_targets(dir.path(), "sonnet-v2", Some("sonnet"), &canary())?;
+    let loaded = load_route_targets(dir.path())?;
+    assert!(loaded.targets_for("sonnet").is_empty());
+    assert_eq!(loaded.targets_for("sonnet-v2").len(), 3);
+
+    set_route_targets(dir.path(), "sonnet-v2", None, &[])?;
+    assert!(load_route_targets(dir.path())?.routes.is_empty());
+    Ok(())
+}
+
+#[test]
+fn sidecar_rejects_unknown_keys() -> anyhow::Result<()> {
+    let dir = tempfile::tempdir()?;
+    std::fs::create_dir_all(dir.path().join("gateway"))?;
+    std::fs::write(
+        dir.path().join("gateway/targets.yaml"),
+        "routes:\n  r:\n    - { id: a, provider: p, weight: 1, priority: 2 }\n",
+    )?;
+    assert!(load_route_targets(dir.path()).is_err());
+    Ok(())
+}
@@ -0,0 +1,209 @@
+//! Prometheus exposition for `/metrics`: series built from audit tallies and
+//! this process's own observations, in the text format scrapers parse.
+
+#![allow(
+    clippy::expect_used,
+    clippy::panic,
+    clippy::needless_pass_by_value,
+    clippy::redundant_clone,
+    reason = "test code: panics are the assertion mechanism and clones keep fixtures readable"
+)]
+
+use std::time::Duration;
+
+use chrono::Utc;
+use systemprompt::identifiers::{JobName, ScheduledJobId};
+use systemprompt_web_admin::metrics::exposition::{Exposition, MetricKind};
+use systemprompt_web_admin::metrics::{MetricsSnapshot, render, runtime};
+use systemprompt_web_admin::repositories::config::metrics::load_metrics_endpoint_config;
+use systemprompt_web_admin::repositories::metrics::{
+    DecisionTally, GatewayRequestTally, InFlightTally, SafetyFindingTally,
+};
+use systemprompt_web_admin::types::JobSummary;
+
+fn tally(status: &str, bucket: Option<i32>, requests: i64, latency_ms: i64) -> GatewayRequestTally {
+    GatewayRequestTally {
+        provider: Some("anthropic".to_owned()),
+        model: Some("claude-sonnet".to_owned()),
+        route: Some("default".to_owned()),
+        status: status.to_owned(),
+        bucket,
+        requests,
+        latency_ms,
+        input_tokens: 100 * requests,
+        output_tokens: 10 * requests,
+        cache_read_tokens: 0,
+        cost_microdollars: 1_500 * requests,
+    }
+}
+
+fn job(name: &str, status: Option<&str>) -> JobSummary {
+    JobSummary {
+        id: ScheduledJobId::new(name),
+        job_name: JobName::new(name),
+        schedule: "0 * * * * *".to_owned(),
+        enabled: true,
+        last_run: status.map(|_| Utc::now()),
+        next_run: None,
+        last_status: status.map(str::to_owned),
+        last_error: None,
+        run_count: 7,
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+    }
+}
+
+fn snapshot() -> MetricsSnapshot {
+    MetricsSnapshot {
+        requests: vec![
+            tally("completed", Some(0), 2, 150),
+            tally("completed", Some(3), 1, 900),
+            tally("completed", Some(10), 1, 200_000),
+            tally("failed", None, 4, 0),
+        ],
+        in_flight: vec![InFlightTally {
+            provider: Some("anthropic".to_owned()),
+            model: Some("claude-sonnet".to_owned()),
+            route: Some("default".to_owned()),
+            requests: 5,
+        }],
+        decisions: vec![DecisionTally {
+            policy: "tool_allowlist".to_owned(),
+            decision: "deny".to_owned(),
+            decisions: 3,
+        }],
+        findings: vec![SafetyFindingTally {
+            scanner: "secrets".to_owned(),
+            category: "api_key".to_owned(),
+            severity: "high".to_owned(),
+            phase: "request".to_owned(),
+            findings: 2,
+        }],
+        jobs: vec![
+            job("otlp_trace_export", Some("failed")),
+            job("never_run", None),
+        ],
+    }
+}
+
+fn line<'a>(body: &'a str, prefix: &str) -> &'a str {
+    body.lines()
+        .find(|l| l.starts_with(prefix))
+        .unwrap_or_else(|| panic!("no line starting {prefix}\n{body}"))
+}
+
+const SERIES: &str = r#"provider="anthropic",model="claude-sonnet",route="default""#;
+
+#[test]
+fn gateway_tallies_become_counters_and_a_cumulative_histogram() {
+    let body = render(&snapshot());
+
+    let completed = format!(r#"{{{SERIES},status="completed"}}"#);
+    assert_eq!(
+        line(
+            &body,
+            &format!("systemprompt_gateway_requests_total{completed}")
+        ),
+        format!("systemprompt_gateway_requests_total{completed} 4")
+    );
+    assert!(body.contains(&format!(
+        r#"systemprompt_gateway_requests_total{{{SERIES},status="failed"}} 4"#
+    )));
+    assert!(body.contains(&format!(
+        "systemprompt_gateway_requests_in_flight{{{SERIES}}} 5\n"
+    )));
+    assert!(body.contains("# TYPE systemprompt_gateway_requests_in_flight gauge"));
+
+    let bucket = |le: &str| {
+        format!(
+            r#"systemprompt_gateway_request_duration_seconds_bucket{{{SERIES},status="completed",le="{le}"}}"#
+        )
+    };
+    assert!(body.contains(&format!("{} 2\n", bucket("0.1"))));
+    assert!(body.contains(&format!("{} 2\n", bucket("0.5"))));
+    assert!(body.contains(&format!("{} 3\n", bucket("1"))));
+    assert!(body.contains(&format!("{} 3\n", bucket("120"))));
+    assert!(body.contains(&format!("{} 4\n", bucket("+Inf"))));
+    assert!(body.contains(&format!(
+        "systemprompt_gateway_request_duration_seconds_sum{completed} 201.05\n"
+    )));
+    assert!(
+        !body.contains(r#"duration_seconds_count{provider="anthropic",model="claude-sonnet",route="default",status="failed"}"#),
+        "requests without a latency stay out of the histogram"
+    );
+
+    assert!(body.contains(&format!(
+        r#"systemprompt_gateway_tokens_total{{{SERIES},status="completed",type="input"}} 400"#
+    )));
+    assert!(body.contains(&format!(
+        "systemprompt_gateway_cost_usd_total{completed} 0.006\n"
+    )));
+    assert_eq!(
+        body.matches("# TYPE systemprompt_gateway_requests_total counter")
+            .count(),
+        1
+    );
+}
+
+#[test]
+fn governance_safety_and_jobs_are_labelled_by_their_dimensions() {
+    runtime::record_job_run("otlp_trace_export", Duration::from_millis(1_500), false);
+    let body = render(&snapshot());
+
+    assert!(body.contains(
+        r#"systemprompt_governance_decisions_total{policy="tool_allowlist",decision="deny"} 3"#
+    ));
+    assert!(body.contains(
+        r#"systemprompt_safety_findings_total{scanner="secrets",category="api_key",severity="high",phase="request"} 2"#
+    ));
+    assert!(body.contains(r#"systemprompt_job_last_run_failed{job="otlp_trace_export"} 1"#));
+    assert!(!body.contains(r#"systemprompt_job_last_run_failed{job="never_run"}"#));
+    assert!(body.contains(r#"systemprompt_job_runs_total{job="never_run"} 7"#));
+    assert!(
+        body.contains(r#"systemprompt_job_last_duration_seconds{job="otlp_trace_export"} 1.5"#)
+    );
+    assert!(body.contains("# TYPE systemprompt_audit_bus_lag_seconds gauge"));
+    assert!(body.contains("systemprompt_audit_bus_subscribers 0\n"));
+}
+
+#[test]
+fn audit_bus_observations_accumulate() {
+    let before = runtime::audit_bus_observation();
+    runtime::record_audit_notification(Some(Duration::from_millis(250)));
+    runtime::record_audit_notification(None);
+    runtime::record_sse_dropped(5);
+    let after = runtime::audit_bus_observation();
+    assert_eq!(after.notifications - before.notifications, 2);
+    assert_eq!(after.sse_dropped - before.sse_dropped, 5);
+    assert_eq!(after.last_lag, Duration::from_millis(250));
+}
+
+#[test]
+fn label_values_and_help_are_escaped() {
+    let mut out = Exposition::new();
+    out.family("m", MetricKind::Gauge, "line one\nline two");
+    out.sample("m", &[("route", "a\"b\\c\nd")], 1.0);
+    out.sample("m", &[], 2.0);
+    assert_eq!(
+        out.finish(),
+        "# HELP m line one\\nline two\n# TYPE m gauge\nm{route=\"a\\\"b\\\\c\\nd\"} 1\nm 2\n"
+    );
+}
+
+#[test]
+fn endpoint_is_off_unless_the_file_enables_it() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let config = load_metrics_endpoint_config(dir.path()).expect("missing file");
+    assert!(!config.enabled);
+    assert_eq!(config.refresh_secs, 15);
+
+    std::fs::create_dir_all(dir.path().join("telemetry")).expect("mkdir");
+    let file = dir.path().join("telemetry/metrics.yaml");
+    std::fs::write(&file, "enabled: true\nrefresh_secs: 30\n").expect("write");
+    let config = load_metrics_endpoint_config(dir.path()).expect("valid file");
+    assert!(config.enabled);
+    assert_eq!(config.refresh_secs, 30);
+
+    std::fs::write(&file, "enabled: true\nbearer: x\n").expect("write");
+    assert!(load_metrics_endpoint_config(dir.path()).is_err());
+}
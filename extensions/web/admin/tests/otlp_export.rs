@@ -0,0 +1,301 @@
+//! OTLP trace export: spans built from a window of governed activity, and
+//! delivery to a local stand-in collector that fails on cue.
+
+#![allow(
+    clippy::expect_used,
+    clippy::panic,
+    clippy::needless_pass_by_value,
+    clippy::redundant_clone,
+    reason = "test code: panics are the assertion mechanism and clones keep fixtures readable"
+)]
+
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+
+use axum::Router;
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::routing::post;
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use systemprompt::identifiers::{AgentId, SessionId, UserId};
+use systemprompt_web_admin::otlp::wire::{AnyValue, ExportTraceRequest, OtlpSpan, STATUS_ERROR};
+use systemprompt_web_admin::otlp::{build_spans, send_spans, trace_id_for_session};
+use systemprompt_web_admin::repositories::config::otlp::OtlpExportConfig;
+use systemprompt_web_admin::repositories::traces::{
+    ExportDecision, ExportRequest, ExportToolEvent, TraceExportWindow,
+};
+
+fn at(secs: i64) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2026, 3, 1, 12, 0, 0)
+        .single()
+        .expect("time")
+        + Duration::seconds(secs)
+}
+
+fn request(id: &str, session: &str, status: &str) -> ExportRequest {
+    ExportRequest {
+        id: id.to_owned(),
+        session_id: SessionId::new(session),
+        user_id: UserId::new("user-1"),
+        provider: Some("anthropic".to_owned()),
+        model: Some("claude-sonnet".to_owned()),
+        requested_model: Some("sonnet".to_owned()),
+        provider_request_id: Some("msg_1".to_owned()),
+        status: status.to_owned(),
+        error_message: (status != "completed").then(|| "upstream 529".to_owned()),
+        input_tokens: Some(1_200),
+        output_tokens: Some(300),
+        cache_read_tokens: None,
+        cost_microdollars: 4_500,
+        cache_hit: false,
+        is_streaming: true,
+        max_tokens: Some(1_024),
+        temperature: None,
+        started_at: at(0),
+        ended_at: at(3),
+    }
+}
+
+fn tool_event(id: &str, event_type: &str, tool: &str, secs: i64) -> ExportToolEvent {
+    ExportToolEvent {
+        id: id.to_owned(),
+        session_id: SessionId::new("sess-1"),
+        user_id: UserId::new("user-1"),
+        event_type: event_type.to_owned(),
+        tool_name: Some(tool.to_owned()),
+        plugin_id: None,
+        created_at: at(secs),
+    }
+}
+
+fn decision(id: &str, tool: &str, verdict: &str, secs: i64) -> ExportDecision {
+    ExportDecision {
+        id: id.to_owned(),
+        session_id: SessionId::new("sess-1"),
+        user_id: UserId::new("user-1"),
+        tool_name: tool.to_owned(),
+        agent_id: Some(AgentId::new("researcher")),
+        decision: verdict.to_owned(),
+        policy: "tool_allowlist".to_owned(),
+        reason: "listed".to_owned(),
+        created_at: at(secs),
+    }
+}
+
+fn window() -> TraceExportWindow {
+    TraceExportWindow {
+        requests: vec![request("req-1", "sess-1", "completed")],
+        tool_events: vec![
+            tool_event("ev-1", "PreToolUse", "Bash", 5),
+            tool_event("ev-2", "PostToolUse", "Bash", 7),
+        ],
+        decisions: vec![
+            decision("gd-1", "Bash", "allow", 4),
+            decision("gd-2", "WebFetch", "deny", 8),
+        ],
+    }
+}
+
+fn named<'a>(spans: &'a [OtlpSpan], name: &str) -> &'a OtlpSpan {
+    spans
+        .iter()
+        .find(|s| s.name == name)
+        .unwrap_or_else(|| panic!("no span named {name}"))
+}
+
+fn string(span: &OtlpSpan, key: &str) -> Option<String> {
+    match span.attribute(key) {
+        Some(AnyValue::String(s) | AnyValue::Int(s)) => Some(s.clone()),
+        _ => None,
+    }
+}
+
+#[test]
+fn session_becomes_one_trace_with_genai_spans_and_decision_events() {
+    let spans = build_spans(&window(), at(60));
+    assert_eq!(spans.len(), 3, "root, chat, and one paired tool call");
+    let trace = trace_id_for_session(&SessionId::new("sess-1"));
+    assert_eq!(trace.len(), 32);
+    assert!(
+        spans
+            .iter()
+            .all(|s| s.trace_id == trace && s.span_id.len() == 16)
+    );
+
+    let root = named(&spans, "invoke_agent researcher");
+    assert!(root.parent_span_id.is_empty());
+    assert_eq!(
+        string(root, "gen_ai.conversation.id").as_deref(),
+        Some("sess-1")
+    );
+    assert_eq!(
+        root.events.len(),
+        1,
+        "the deny never ran, so it sits on the root"
+    );
+    assert_eq!(root.events[0].name, "governance.decision");
+
+    let chat = named(&spans, "chat claude-sonnet");
+    assert_eq!(chat.parent_span_id, root.span_id);
+    assert_eq!(
+        string(chat, "gen_ai.usage.input_tokens").as_deref(),
+        Some("1200")
+    );
+    assert_eq!(
+        string(chat, "gen_ai.usage.output_tokens").as_deref(),
+        Some("300")
+    );
+    assert_eq!(
+        string(chat, "gen_ai.request.model").as_deref(),
+        Some("sonnet")
+    );
+    assert_eq!(
+        string(chat, "gen_ai.provider.name").as_deref(),
+        Some("anthropic")
+    );
+    assert_eq!(
+        chat.attribute("systemprompt.cost.usd"),
+        Some(&AnyValue::Double(0.0045))
+    );
+
+    let tool = named(&spans, "execute_tool Bash");
+    assert_eq!(
+        tool.start_time_unix_nano,
+        at(5).timestamp_nanos_opt().expect("ns").to_string()
+    );
+    assert_eq!(
+        tool.end_time_unix_nano,
+        at(7).timestamp_nanos_opt().expect("ns").to_string()
+    );
+    assert_eq!(
+        tool.events.len(),
+        1,
+        "the allow governs the Bash call after it"
+    );
+}
+
+#[test]
+fn ids_are_stable_across_reruns_and_failures_mark_the_trace() {
+    assert_eq!(
+        build_spans(&window(), at(60)),
+        build_spans(&window(), at(60))
+    );
+
+    let failing = TraceExportWindow {
+        requests: vec![request("req-9", "sess-2", "failed")],
+        ..TraceExportWindow::default()
+    };
+    let spans = build_spans(&failing, at(60));
+    let chat = named(&spans, "chat claude-sonnet");
+    assert_eq!(chat.status.code, STATUS_ERROR);
+    assert_eq!(chat.status.message, "upstream 529");
+    assert_eq!(string(chat, "error.type").as_deref(), Some("failed"));
+    assert_eq!(named(&spans, "invoke_agent").status.code, STATUS_ERROR);
+
+    assert!(build_spans(&TraceExportWindow::default(), at(60)).is_empty());
+}
+
+#[derive(Clone, Default)]
+struct Collector {
+    failures_left: Arc<AtomicU32>,
+    failure: u16,
+    attempts: Arc<AtomicU32>,
+    received: Arc<Mutex<Vec<ExportTraceRequest>>>,
+}
+
+async fn receive(State(c): State<Collector>, body: axum::body::Bytes) -> StatusCode {
+    c.attempts.fetch_add(1, Ordering::SeqCst);
+    let failing = c
+        .failures_left
+        .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+        .is_ok();
+    if failing {
+        return StatusCode::from_u16(c.failure).expect("status");
+    }
+    let request: ExportTraceRequest = serde_json::from_slice(&body).expect("otlp json");
+    c.received.lock().expect("lock").push(request);
+    StatusCode::OK
+}
+
+async fn start_collector(failures: u32, status: u16) -> (Collector, String) {
+    let collector = Collector {
+        failures_left: Arc::new(AtomicU32::new(failures)),
+        failure: status,
+        ..Collector::default()
+    };
+    let app = Router::new()
+        .route("/v1/traces", post(receive))
+        .with_state(collector.clone());
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("bind");
+    let addr = listener.local_addr().expect("addr");
+    tokio::spawn(async move { axum::serve(listener, app).await });
+    (collector, format!("http://{addr}"))
+}
+
+fn config(endpoint: String) -> OtlpExportConfig {
+    OtlpExportConfig {
+        enabled: true,
+        endpoint,
+        batch_size: 2,
+        max_retries: 2,
+        timeout_ms: 2_000,
+        ..OtlpExportConfig::default()
+    }
+}
+
+#[tokio::test]
+async fn batches_are_retried_through_a_transient_collector_failure() {
+    let (collector, endpoint) = start_collector(1, 503).await;
+    let spans = build_spans(&window(), at(60));
+    let sent = send_spans(&config(endpoint), spans.clone())
+        .await
+        .expect("export");
+    assert_eq!(sent, 3);
+    assert_eq!(
+        collector.attempts.load(Ordering::SeqCst),
+        3,
+        "one retry, two batches"
+    );
+
+    let received = collector.received.lock().expect("lock").clone();
+    assert_eq!(received.len(), 2);
+    let resource = &received[0].resource_spans[0].resource;
+    assert!(
+        resource
+            .attributes
+            .iter()
+            .any(|kv| kv.key == "service.name")
+    );
+    let delivered: Vec<OtlpSpan> = received
+        .into_iter()
+        .flat_map(|r| r.resource_spans)
+        .flat_map(|r| r.scope_spans)
+        .flat_map(|s| s.spans)
+        .collect();
+    assert_eq!(delivered, spans);
+}
+
+#[tokio::test]
+async fn a_rejected_batch_fails_without_retrying() {
+    let (collector, endpoint) = start_collector(5, 400).await;
+    let result = send_spans(&config(endpoint), build_spans(&window(), at(60))).await;
+    assert!(result.is_err());
+    assert_eq!(collector.attempts.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn traces_url_appends_the_signal_path_once() {
+    let with = |endpoint: &str| config(endpoint.to_owned()).traces_url();
+    assert_eq!(
+        with("http://collector:4318"),
+        "http://collector:4318/v1/traces"
+    );
+    assert_eq!(
+        with("http://collector:4318/"),
+        "http://collector:4318/v1/traces"
+    );
+    assert_eq!(with("http://c/v1/traces"), "http://c/v1/traces");
+    assert!(config("collector:4318".to_owned()).validate().is_err());
+}
@@ -0,0 +1,95 @@
+//! Data retention: the policy file, legal holds, cutoffs and batching.
+
+#![allow(
+    clippy::expect_used,
+    clippy::panic,
+    clippy::needless_pass_by_value,
+    clippy::redundant_clone,
+    reason = "test code: panics are the assertion mechanism and clones keep fixtures readable"
+)]
+
+use chrono::{DateTime, Utc};
+use systemprompt_web_admin::repositories::config::retention::{
+    RetentionConfig, load_retention_config,
+};
+use systemprompt_web_admin::retention::{DataClass, LegalHolds, cutoff, wants_another_batch};
+
+fn parse(yaml: &str) -> RetentionConfig {
+    serde_yaml::from_str(yaml).expect("yaml")
+}
+
+#[test]
+fn missing_file_purges_nothing_and_policies_parse_by_class_name() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let config = load_retention_config(dir.path()).expect("missing file");
+    assert!(config.policies.is_empty());
+    assert!(config.legal_holds.is_empty());
+
+    std::fs::create_dir_all(dir.path().join("governance")).expect("mkdir");
+    std::fs::write(
+        dir.path().join("governance/retention.yaml"),
+        "policies:\n  ai_request_bodies: 30\n  ai_requests: 730\n  link_clicks: 180\n  mcp_tool_captures: 90\n",
+    )
+    .expect("write");
+    let config = load_retention_config(dir.path()).expect("valid file");
+    assert_eq!(
+        config.policies.into_iter().collect::<Vec<_>>(),
+        [
+            (DataClass::AiRequestBodies, 30),
+            (DataClass::AiRequests, 730),
+            (DataClass::LinkClicks, 180),
+            (DataClass::McpToolCaptures, 90),
+        ]
+    );
+}
+
+#[test]
+fn invalid_policies_and_holds_are_rejected() {
+    assert!(serde_yaml::from_str::<RetentionConfig>("policies:\n  chat_logs: 30\n").is_err());
+    assert!(parse("policies:\n  ai_requests: 30\n").validate().is_err());
+    assert!(parse("policies:\n  ai_requests: 90\n").validate().is_ok());
+    assert!(parse("policies:\n  transcripts: 0\n").validate().is_err());
+    assert!(parse("batch_size: 0\n").validate().is_err());
+    assert!(
+        parse("legal_holds:\n  - user_id: u1\n    session_id: s1\n    reason: both\n")
+            .validate()
+            .is_err()
+    );
+    assert!(
+        parse("legal_holds:\n  - reason: nobody\n")
+            .validate()
+            .is_err()
+    );
+    assert!(
+        parse("legal_holds:\n  - user_id: u1\n    reason: '  '\n")
+            .validate()
+            .is_err()
+    );
+}
+
+#[test]
+fn legal_holds_flatten_to_sorted_unique_ids() {
+    let config = parse(
+        "legal_holds:\n  - user_id: u2\n    reason: matter A\n  - session_id: s1\n    reason: incident\n  - user_id: u1\n    reason: matter B\n  - user_id: u2\n    reason: matter C\n",
+    );
+    config.validate().expect("valid holds");
+    let holds = LegalHolds::from_config(&config.legal_holds);
+    assert_eq!(holds.users, ["u1", "u2"]);
+    assert_eq!(holds.sessions, ["s1"]);
+}
+
+#[test]
+fn cutoff_counts_whole_days_back_and_batches_stop_when_short_or_capped() {
+    let now: DateTime<Utc> = "2026-10-19T03:30:00Z".parse().expect("timestamp");
+    assert_eq!(cutoff(now, 30).to_rfc3339(), "2026-09-19T03:30:00+00:00");
+
+    assert!(wants_another_batch(1_000, 1_000, 1, 100));
+    assert!(!wants_another_batch(999, 1_000, 1, 100));
+    assert!(!wants_another_batch(1_000, 1_000, 100, 100));
+    assert_eq!(DataClass::ALL.len(), 8);
+    assert!(
+        DataClass::ALL
+            .iter()
+            .all(|c| c.min_days() >= 1 && !c.description().is_empty())
+    );
+}
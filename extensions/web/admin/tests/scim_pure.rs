@@ -0,0 +1,173 @@
+//! SCIM provisioning logic that needs no database: the filter subset,
+//! `PatchOp` interpretation across identity-provider dialects, and the
+//! group → role/department map.
+
+#![allow(
+    clippy::expect_used,
+    clippy::panic,
+    clippy::needless_pass_by_value,
+    clippy::redundant_clone,
+    reason = "test code: panics are the assertion mechanism and clones keep fixtures readable"
+)]
+
+use axum::http::{StatusCode, header};
+use axum::response::IntoResponse;
+use serde_json::json;
+use systemprompt_web_admin::repositories::config::scim::{ScimConfig, load_scim_config};
+use systemprompt_web_admin::test_support::{
+    ScimError, interpret_group_patch, interpret_user_patch, parse_filter,
+};
+use systemprompt_web_admin::types::departments::DEFAULT_DEPARTMENT;
+use systemprompt_web_admin::types::scim::{ScimMemberChange, ScimPatchRequest, ScimUserInput};
+
+fn ops(body: serde_json::Value) -> ScimPatchRequest {
+    serde_json::from_value(body).expect("patch body parses")
+}
+
+fn config(yaml: &str) -> ScimConfig {
+    serde_yaml::from_str(yaml).expect("config parses")
+}
+
+#[test]
+fn filter_accepts_the_lookup_idps_send() {
+    let f = parse_filter(r#"userName eq "Ada@Example.com""#).expect("parses");
+    assert_eq!(f.attribute, "username");
+    assert_eq!(f.value, "Ada@Example.com");
+    let f = parse_filter(r#"displayName EQ "Platform Admins""#).expect("parses");
+    assert_eq!(f.value, "Platform Admins");
+}
+
+#[test]
+fn filter_rejects_anything_richer_than_equality() {
+    assert!(parse_filter(r#"userName sw "ada""#).is_err());
+    assert!(parse_filter(r#"userName eq "a" and active eq "true""#).is_err());
+    assert!(parse_filter("userName eq ada").is_err());
+    assert!(parse_filter("").is_err());
+}
+
+#[test]
+fn errors_answer_with_the_scim_media_type() {
+    let rejected = parse_filter(r#"userName sw "ada""#).expect_err("unsupported operator");
+    let response = ScimError::from(rejected).into_response();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(
+        response.headers()[header::CONTENT_TYPE],
+        "application/scim+json"
+    );
+}
+
+#[test]
+fn user_patch_reads_entra_and_okta_dialects_alike() {
+    let entra = ops(json!({"Operations": [
+        {"op": "Replace", "path": "active", "value": "False"}
+    ]}));
+    let okta = ops(json!({"Operations": [
+        {"op": "replace", "value": {"active": false}}
+    ]}));
+    let a = interpret_user_patch(&entra.operations).expect("entra");
+    let b = interpret_user_patch(&okta.operations).expect("okta");
+    assert_eq!(a.active, Some(false));
+    assert_eq!(a, b);
+}
+
+#[test]
+fn user_patch_picks_up_email_and_name_paths() {
+    let body = ops(json!({"Operations": [
+        {"op": "replace", "path": "emails[type eq \"work\"].value", "value": "ada@example.com"},
+        {"op": "add", "path": "name.formatted", "value": "Ada Lovelace"},
+        {"op": "replace", "path": "title", "value": "ignored"}
+    ]}));
+    let patch = interpret_user_patch(&body.operations).expect("patch");
+    assert_eq!(patch.email.as_deref(), Some("ada@example.com"));
+    assert_eq!(patch.display_name.as_deref(), Some("Ada Lovelace"));
+    assert_eq!(patch.active, None);
+}
+
+#[test]
+fn user_patch_refuses_remove() {
+    let body = ops(json!({"Operations": [{"op": "remove", "path": "displayName"}]}));
+    assert!(interpret_user_patch(&body.operations).is_err());
+}
+
+#[test]
+fn group_patch_keeps_member_changes_in_order() {
+    let body = ops(json!({"Operations": [
+        {"op": "replace", "path": "members", "value": [{"value": "u1"}]},
+        {"op": "add", "path": "members", "value": [{"value": "u2"}, {"value": "u3"}]},
+        {"op": "remove", "path": "members[value eq \"u1\"]"},
+        {"op": "replace", "value": {"displayName": "Engineering"}}
+    ]}));
+    let patch = interpret_group_patch(&body.operations).expect("patch");
+    assert_eq!(
+        patch.members,
+        vec![
+            ScimMemberChange::Replace(vec!["u1".into()]),
+            ScimMemberChange::Add(vec!["u2".into(), "u3".into()]),
+            ScimMemberChange::Remove(vec!["u1".into()]),
+        ]
+    );
+    assert_eq!(patch.display_name.as_deref(), Some("Engineering"));
+}
+
+#[test]
+fn group_patch_remove_without_value_clears_members() {
+    let body = ops(json!({"Operations": [{"op": "remove", "path": "members"}]}));
+    let patch = interpret_group_patch(&body.operations).expect("patch");
+    assert_eq!(patch.members, vec![ScimMemberChange::Replace(Vec::new())]);
+}
+
+#[test]
+fn assignment_unions_roles_and_takes_first_department() {
+    let cfg = config(
+        r"
+enabled: true
+default_roles: [user]
+group_mappings:
+  - group: Engineering
+    roles: [developer]
+    department: Engineering
+  - group: Platform Admins
+    roles: [admin, developer]
+    department: Platform
+",
+    );
+    let a = cfg.assignment_for(&["Platform Admins".into(), "Engineering".into()]);
+    assert_eq!(a.roles, vec!["admin", "developer", "user"]);
+    assert_eq!(a.department, "Engineering");
+
+    let none = cfg.assignment_for(&["Sales".into()]);
+    assert_eq!(none.roles, vec!["user"]);
+    assert_eq!(none.department, DEFAULT_DEPARTMENT);
+}
+
+#[test]
+fn config_rejects_unknown_keys() {
+    assert!(serde_yaml::from_str::<ScimConfig>("enabled: true\ngroups: []\n").is_err());
+}
+
+#[test]
+fn missing_config_file_means_disabled() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let cfg = load_scim_config(dir.path()).expect("loads");
+    assert!(!cfg.enabled);
+    assert_eq!(cfg.default_roles, vec!["user"]);
+}
+
+#[test]
+fn user_input_keys_on_the_primary_email() {
+    let input: ScimUserInput = serde_json::from_value(json!({
+        "userName": "ada.upn@corp.example",
+        "name": {"givenName": "Ada", "familyName": "Lovelace"},
+        "emails": [
+            {"value": "other@example.com"},
+            {"value": " Ada@Example.com ", "primary": true}
+        ]
+    }))
+    .expect("parses");
+    assert_eq!(input.email(), "ada@example.com");
+    assert_eq!(
+        input.resolved_display_name().as_deref(),
+        Some("Ada Lovelace")
+    );
+    assert!(input.active);
+}
@@ -113,6 +113,10 @@ fn error_messages_name_the_failing_stage() {
         SecretCryptoError::MasterKeyMissing.to_string(),
         "Master key not configured"
     );
+    assert_eq!(
+        SecretCryptoError::MasterKeyUnknown("mk-0123".into()).to_string(),
+        "Master key mk-0123 is not configured or has been retired"
+    );
     assert_eq!(
         SecretCryptoError::InvalidKeyMaterial.to_string(),
         "Invalid key material"
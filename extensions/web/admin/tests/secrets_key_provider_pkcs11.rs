@@ -0,0 +1,160 @@
+//! The PKCS#11 provider against a `SoftHSM` token: wrapping and unwrapping
+//! inside the token, the key id, and moving to a new HSM key.
+//!
+//! Runs when `SOFTHSM2_TEST_MODULE` names the module, as CI sets it, and skips
+//! otherwise. The test initialises a fresh token in a free slot, so only a
+//! writable token directory (`SOFTHSM2_CONF`) needs preparing:
+//!
+//! ```sh
+//! mkdir -p /tmp/softhsm/tokens
+//! echo 'directories.tokendir = /tmp/softhsm/tokens' > /tmp/softhsm/softhsm2.conf
+//! SOFTHSM2_CONF=/tmp/softhsm/softhsm2.conf \
+//!   SOFTHSM2_TEST_MODULE=/usr/lib/softhsm/libsofthsm2.so \
+//!   cargo test -p systemprompt-web-admin --test secrets_key_provider_pkcs11
+//! ```
+
+#![allow(
+    clippy::expect_used,
+    clippy::panic,
+    reason = "test code: panics are the assertion mechanism"
+)]
+#![allow(clippy::print_stderr, reason = "a skipped run says why on stderr")]
+
+use std::sync::Arc;
+
+use cryptoki::context::{CInitializeArgs, CInitializeFlags, Pkcs11};
+use cryptoki::error::{Error, RvError};
+use cryptoki::mechanism::Mechanism;
+use cryptoki::object::{Attribute, KeyType, ObjectClass};
+use cryptoki::session::UserType;
+use cryptoki::types::AuthPin;
+use systemprompt_web_admin::key_provider::{KeyProvider, Pkcs11Key, ProviderSpec};
+use systemprompt_web_admin::repositories::secrets::secret_crypto::generate_dek;
+use systemprompt_web_admin::repositories::secrets::secret_keyring::MasterKeyring;
+
+const PIN: &str = "1234";
+
+// Initialises a new token and keeps the context: the module stays initialised
+// for the provider, which loads it again.
+fn fresh_token(module: &str, label: &str) -> Pkcs11 {
+    let context = Pkcs11::new(module).expect("load the module");
+    match context.initialize(CInitializeArgs::new(CInitializeFlags::OS_LOCKING_OK)) {
+        Ok(()) | Err(Error::Pkcs11(RvError::CryptokiAlreadyInitialized, _)) => {},
+        Err(e) => panic!("initialise the module: {e}"),
+    }
+    let slot = context
+        .get_all_slots()
+        .expect("slots")
+        .into_iter()
+        .find(|slot| {
+            context
+                .get_token_info(*slot)
+                .is_ok_and(|info| !info.token_initialized())
+        })
+        .expect("a free slot");
+    context
+        .init_token(slot, &AuthPin::new("0000".into()), label)
+        .expect("init token");
+    let session = context.open_rw_session(slot).expect("session");
+    session
+        .login(UserType::So, Some(&AuthPin::new("0000".into())))
+        .expect("SO login");
+    session
+        .init_pin(&AuthPin::new(PIN.into()))
+        .expect("init PIN");
+    context
+}
+
+fn generate_key(context: &Pkcs11, token: &str, label: &str) {
+    let slot = context
+        .get_slots_with_token()
+        .expect("slots")
+        .into_iter()
+        .find(|slot| {
+            context
+                .get_token_info(*slot)
+                .is_ok_and(|info| info.label().trim_end() == token)
+        })
+        .expect("the test token");
+    let session = context.open_rw_session(slot).expect("session");
+    match session.login(UserType::User, Some(&AuthPin::new(PIN.into()))) {
+        Ok(()) | Err(Error::Pkcs11(RvError::UserAlreadyLoggedIn, _)) => {},
+        Err(e) => panic!("user login: {e}"),
+    }
+    session
+        .generate_key(
+            &Mechanism::AesKeyGen,
+            &[
+                Attribute::Class(ObjectClass::SECRET_KEY),
+                Attribute::KeyType(KeyType::AES),
+                Attribute::ValueLen(32.into()),
+                Attribute::Token(true),
+                Attribute::Sensitive(true),
+                Attribute::Extractable(false),
+                Attribute::Encrypt(true),
+                Attribute::Decrypt(true),
+                Attribute::Label(label.as_bytes().to_vec()),
+                Attribute::Id(label.as_bytes().to_vec()),
+            ],
+        )
+        .expect("generate the AES key");
+}
+
+#[tokio::test]
+async fn a_pkcs11_key_wraps_inside_the_token_and_stays_readable_after_a_move() {
+    let Ok(module) = std::env::var("SOFTHSM2_TEST_MODULE") else {
+        eprintln!("skipping: SOFTHSM2_TEST_MODULE is not set");
+        return;
+    };
+    let token = format!("sp-test-{}", std::process::id());
+    let context = fresh_token(&module, &token);
+    generate_key(&context, &token, "sp-master-old");
+
+    let old = Pkcs11Key::new(
+        module.clone(),
+        token.clone(),
+        PIN.to_owned(),
+        "sp-master-old".to_owned(),
+    )
+    .expect("open the token");
+    let fingerprint = old
+        .key_id()
+        .strip_prefix(&format!("pkcs11:{token}/sp-master-old#"))
+        .expect("key id names the token and label");
+    assert!(!fingerprint.is_empty() && hex::decode(fingerprint).is_ok());
+
+    let dek = generate_dek();
+    let wrapped = old.wrap(&dek).await.expect("wrap");
+    assert_eq!(wrapped.key_id, old.key_id());
+    assert_eq!(wrapped.nonce.len(), 12);
+    assert_eq!(wrapped.ciphertext.len(), 32 + 16);
+    let opened = old
+        .unwrap(&wrapped.nonce, &wrapped.ciphertext)
+        .await
+        .expect("unwrap");
+    assert_eq!(opened, dek);
+
+    generate_key(&context, &token, "sp-master-new");
+    let new = Pkcs11Key::new(
+        module.clone(),
+        token.clone(),
+        PIN.to_owned(),
+        "sp-master-new".to_owned(),
+    )
+    .expect("open the new key");
+    assert_ne!(new.key_id(), old.key_id());
+    let previous = ProviderSpec::Pkcs11 {
+        module,
+        token_label: token,
+        key_label: "sp-master-old".to_owned(),
+        pin_setting: "OLD_HSM_PIN".to_owned(),
+    }
+    .build(&|name| (name == "OLD_HSM_PIN").then(|| PIN.to_owned()))
+    .expect("the previous key");
+    let keyring = MasterKeyring::from_providers(Arc::new(new), [previous]);
+    let reopened = keyring
+        .unwrap_dek(Some(&wrapped.key_id), &wrapped.nonce, &wrapped.ciphertext)
+        .await
+        .expect("unwrap under the previous HSM key");
+    assert_eq!(reopened, dek);
+}
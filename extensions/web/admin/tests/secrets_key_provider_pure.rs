@@ -0,0 +1,353 @@
+//! Key providers: the keyring file and the previous providers it lists, a
+//! Vault Transit stand-in that keeps the master key on its side of the wire,
+//! and provider selection from settings. PKCS#11 is covered against `SoftHSM`
+//! in `secrets_key_provider_pkcs11`.
+
+#![allow(
+    clippy::expect_used,
+    clippy::panic,
+    reason = "test code: panics are the assertion mechanism"
+)]
+
+use std::collections::HashMap;
+use std::io::Write;
+use std::os::unix::fs::PermissionsExt;
+use std::sync::{Arc, Mutex};
+
+use axum::extract::{Path, State};
+use axum::http::{HeaderMap, StatusCode};
+use axum::routing::post;
+use axum::{Json, Router};
+use serde_json::{Value, json};
+use systemprompt_web_admin::key_provider::{
+    KeyProvider, LocalKey, ProviderSpec, VaultTransit, configured_providers, load_keyring_file,
+};
+use systemprompt_web_admin::repositories::secrets::secret_crypto::{
+    SecretCryptoError, generate_dek,
+};
+use systemprompt_web_admin::repositories::secrets::secret_keyring::{MasterKeyring, master_key_id};
+
+const OLD: [u8; 32] = [7u8; 32];
+const NEW: [u8; 32] = [8u8; 32];
+const TOKEN: &str = "test-token";
+
+fn keyring_file(contents: &str, mode: u32) -> tempfile::NamedTempFile {
+    let mut file = tempfile::NamedTempFile::new().expect("temp file");
+    file.write_all(contents.as_bytes()).expect("write keyring");
+    std::fs::set_permissions(file.path(), std::fs::Permissions::from_mode(mode))
+        .expect("chmod keyring");
+    file
+}
+
+fn settings(pairs: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+    let map: HashMap<String, String> = pairs
+        .iter()
+        .map(|(k, v)| ((*k).to_owned(), (*v).to_owned()))
+        .collect();
+    move |name| map.get(name).cloned()
+}
+
+#[test]
+fn a_keyring_file_yields_the_current_and_previous_keys() {
+    let file = keyring_file(
+        &format!(
+            "current: {}\nprevious:\n  - {}\n",
+            hex::encode(NEW),
+            hex::encode(OLD)
+        ),
+        0o600,
+    );
+    let keyring = load_keyring_file(file.path()).expect("load");
+    assert_eq!(keyring.current, Some(NEW));
+    assert_eq!(keyring.previous, vec![OLD]);
+    assert!(keyring.previous_providers.is_empty());
+}
+
+#[test]
+fn a_keyring_file_lists_previous_providers_beside_local_keys() {
+    let file = keyring_file(
+        &format!(
+            "previous:\n  - {}\n  - provider: vault_transit\n    addr: https://vault-a:8200\n    \
+             key: dek\n  - provider: pkcs11\n    module: /lib/hsm.so\n    token_label: sp\n    \
+             key_label: sp-master\n    pin_setting: OLD_PIN\n",
+            hex::encode(OLD)
+        ),
+        0o600,
+    );
+    let keyring = load_keyring_file(file.path()).expect("load");
+    assert_eq!(keyring.current, None);
+    assert_eq!(keyring.previous, vec![OLD]);
+    assert_eq!(
+        keyring.previous_providers,
+        vec![
+            ProviderSpec::VaultTransit {
+                addr: "https://vault-a:8200".to_owned(),
+                mount: None,
+                key: "dek".to_owned(),
+                namespace: None,
+                token_setting: "VAULT_TOKEN".to_owned(),
+            },
+            ProviderSpec::Pkcs11 {
+                module: "/lib/hsm.so".to_owned(),
+                token_label: "sp".to_owned(),
+                key_label: "sp-master".to_owned(),
+                pin_setting: "OLD_PIN".to_owned(),
+            },
+        ]
+    );
+}
+
+#[test]
+fn a_malformed_previous_provider_is_named_by_position() {
+    let file = keyring_file(
+        "previous:\n  - provider: vault_transit\n    addr: https://vault-a:8200\n",
+        0o600,
+    );
+    assert!(matches!(
+        load_keyring_file(file.path()),
+        Err(SecretCryptoError::KeyProvider(msg)) if msg.contains("previous[0]") && msg.contains("key")
+    ));
+}
+
+#[test]
+fn a_keyring_file_others_can_read_is_refused() {
+    let file = keyring_file(&format!("current: {}\n", hex::encode(NEW)), 0o644);
+    assert!(matches!(
+        load_keyring_file(file.path()),
+        Err(SecretCryptoError::KeyProvider(msg)) if msg.contains("group or others")
+    ));
+}
+
+#[test]
+fn a_keyring_file_with_an_unknown_field_is_refused() {
+    let file = keyring_file(&format!("curent: {}\n", hex::encode(NEW)), 0o600);
+    assert!(matches!(
+        load_keyring_file(file.path()),
+        Err(SecretCryptoError::KeyProvider(_))
+    ));
+}
+
+#[test]
+fn the_env_provider_is_the_default_and_needs_a_master_key() {
+    let providers =
+        configured_providers(&settings(&[("ENCRYPTION_MASTER_KEY", &hex::encode(NEW))]))
+            .expect("env provider");
+    assert_eq!(providers.current.key_id(), master_key_id(&NEW));
+    assert!(matches!(
+        configured_providers(&settings(&[])),
+        Err(SecretCryptoError::MasterKeyMissing)
+    ));
+}
+
+#[test]
+fn moving_to_a_file_keyring_keeps_the_env_key_for_unwrapping() {
+    let file = keyring_file(&format!("current: {}\n", hex::encode(NEW)), 0o600);
+    let path = file.path().to_string_lossy().into_owned();
+    let providers = configured_providers(&settings(&[
+        ("ENCRYPTION_KEY_PROVIDER", "file"),
+        ("ENCRYPTION_KEYRING_FILE", &path),
+        ("ENCRYPTION_MASTER_KEY", &hex::encode(OLD)),
+    ]))
+    .expect("file provider");
+    assert_eq!(providers.current.key_id(), master_key_id(&NEW));
+    let previous: Vec<&str> = providers.previous.iter().map(|p| p.key_id()).collect();
+    assert_eq!(previous, vec![master_key_id(&OLD)]);
+
+    let bare = keyring_file(&format!("previous:\n  - {}\n", hex::encode(OLD)), 0o600);
+    let path = bare.path().to_string_lossy().into_owned();
+    let missing = configured_providers(&settings(&[
+        ("ENCRYPTION_KEY_PROVIDER", "file"),
+        ("ENCRYPTION_KEYRING_FILE", &path),
+    ]));
+    assert!(matches!(missing, Err(SecretCryptoError::KeyProvider(m)) if m.contains("current")));
+}
+
+#[test]
+fn an_unknown_provider_or_a_missing_setting_is_named() {
+    let unknown = configured_providers(&settings(&[("ENCRYPTION_KEY_PROVIDER", "kms")]));
+    assert!(matches!(unknown, Err(SecretCryptoError::KeyProvider(m)) if m.contains("kms")));
+    let missing = configured_providers(&settings(&[
+        ("ENCRYPTION_KEY_PROVIDER", "vault_transit"),
+        ("VAULT_ADDR", "http://127.0.0.1:8200"),
+        ("VAULT_TRANSIT_KEY", "dek"),
+    ]));
+    assert!(matches!(missing, Err(SecretCryptoError::KeyProvider(m)) if m.contains("VAULT_TOKEN")));
+}
+
+// Stands in for Transit: plaintexts stay server-side, ciphertexts are handles.
+#[derive(Default)]
+struct Transit {
+    sealed: Mutex<Vec<String>>,
+}
+
+async fn transit(
+    State(transit): State<Arc<Transit>>,
+    Path((op, key)): Path<(String, String)>,
+    headers: HeaderMap,
+    Json(body): Json<Value>,
+) -> Result<Json<Value>, StatusCode> {
+    if headers.get("x-vault-token").and_then(|v| v.to_str().ok()) != Some(TOKEN) {
+        return Err(StatusCode::FORBIDDEN);
+    }
+    match op.as_str() {
+        "encrypt" => {
+            let plaintext = body["plaintext"].as_str().ok_or(StatusCode::BAD_REQUEST)?;
+            let handle = {
+                let mut sealed = transit.sealed.lock().expect("lock");
+                sealed.push(plaintext.to_owned());
+                sealed.len() - 1
+            };
+            let ciphertext = format!("vault:v1:{key}:{handle}");
+            Ok(Json(json!({ "data": { "ciphertext": ciphertext } })))
+        },
+        "decrypt" => {
+            let handle = body["ciphertext"]
+                .as_str()
+                .and_then(|c| c.strip_prefix(&format!("vault:v1:{key}:")))
+                .and_then(|i| i.parse::<usize>().ok())
+                .ok_or(StatusCode::BAD_REQUEST)?;
+            let plaintext = transit.sealed.lock().expect("lock").get(handle).cloned();
+            let plaintext = plaintext.ok_or(StatusCode::BAD_REQUEST)?;
+            Ok(Json(json!({ "data": { "plaintext": plaintext } })))
+        },
+        _ => Err(StatusCode::NOT_FOUND),
+    }
+}
+
+async fn spawn_transit() -> String {
+    let app = Router::new()
+        .route("/v1/transit/{op}/{key}", post(transit))
+        .with_state(Arc::new(Transit::default()));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("bind");
+    let addr = listener.local_addr().expect("addr");
+    tokio::spawn(async move { axum::serve(listener, app).await });
+    format!("http://{addr}")
+}
+
+fn vault(addr: &str, token: &str) -> VaultTransit {
+    VaultTransit::new(addr, token.to_owned(), "transit", "dek", None).expect("client")
+}
+
+#[tokio::test]
+async fn a_transit_key_wraps_and_unwraps_without_the_key_in_process() {
+    let addr = spawn_transit().await;
+    let provider = vault(&addr, TOKEN);
+    assert_eq!(
+        provider.key_id(),
+        format!("vault-transit:{addr}/transit/dek")
+    );
+
+    let dek = generate_dek();
+    let wrapped = provider.wrap(&dek).await.expect("wrap");
+    assert!(wrapped.nonce.is_empty());
+    assert!(wrapped.ciphertext.starts_with(b"vault:v1:"));
+    let opened = provider
+        .unwrap(&wrapped.nonce, &wrapped.ciphertext)
+        .await
+        .expect("unwrap");
+    assert_eq!(opened, dek);
+}
+
+#[tokio::test]
+async fn a_keyring_moves_from_a_local_key_to_transit() {
+    let addr = spawn_transit().await;
+    let dek = generate_dek();
+    let legacy = MasterKeyring::new(OLD, [])
+        .wrap_dek(&dek)
+        .await
+        .expect("wrap");
+
+    let local: Arc<dyn KeyProvider> = Arc::new(LocalKey::new(OLD));
+    let keyring = MasterKeyring::from_providers(Arc::new(vault(&addr, TOKEN)), [local]);
+    let opened = keyring
+        .unwrap_dek(Some(&legacy.key_id), &legacy.nonce, &legacy.ciphertext)
+        .await
+        .expect("unwrap under the local key");
+    let rewrapped = keyring.wrap_dek(&opened).await.expect("rewrap");
+    assert_eq!(
+        rewrapped.key_id,
+        format!("vault-transit:{addr}/transit/dek")
+    );
+    let reopened = keyring
+        .unwrap_dek(
+            Some(&rewrapped.key_id),
+            &rewrapped.nonce,
+            &rewrapped.ciphertext,
+        )
+        .await
+        .expect("unwrap under transit");
+    assert_eq!(reopened, dek);
+}
+
+#[tokio::test]
+async fn a_refused_transit_call_is_a_provider_error_without_the_token() {
+    let addr = spawn_transit().await;
+    let result = vault(&addr, "wrong-token").wrap(&generate_dek()).await;
+    match result {
+        Err(SecretCryptoError::KeyProvider(msg)) => {
+            assert!(msg.contains("403"), "{msg}");
+            assert!(!msg.contains("wrong-token"), "{msg}");
+        },
+        other => panic!("expected a provider error, got {other:?}"),
+    }
+    assert!(!format!("{:?}", vault(&addr, "wrong-token")).contains("wrong-token"));
+}
+
+#[test]
+fn equally_named_transit_keys_on_two_clusters_have_distinct_ids() {
+    let a = VaultTransit::new(
+        "https://vault-a:8200/",
+        "t".to_owned(),
+        "transit",
+        "dek",
+        None,
+    )
+    .expect("client");
+    let b = VaultTransit::new(
+        "https://vault-b:8200",
+        "t".to_owned(),
+        "transit",
+        "dek",
+        Some("team".to_owned()),
+    )
+    .expect("client");
+    assert_eq!(a.key_id(), "vault-transit:https://vault-a:8200/transit/dek");
+    assert_eq!(
+        b.key_id(),
+        "vault-transit:https://vault-b:8200/team/transit/dek"
+    );
+}
+
+#[tokio::test]
+async fn a_previous_transit_key_in_the_keyring_file_still_unwraps() {
+    let addr = spawn_transit().await;
+    let wrapped = vault(&addr, TOKEN)
+        .wrap(&generate_dek())
+        .await
+        .expect("wrap under the old cluster");
+
+    let file = keyring_file(
+        &format!(
+            "previous:\n  - provider: vault_transit\n    addr: {addr}\n    key: dek\n    \
+             token_setting: OLD_VAULT_TOKEN\n"
+        ),
+        0o600,
+    );
+    let path = file.path().to_string_lossy().into_owned();
+    let providers = configured_providers(&settings(&[
+        ("ENCRYPTION_MASTER_KEY", &hex::encode(NEW)),
+        ("ENCRYPTION_KEYRING_FILE", &path),
+        ("OLD_VAULT_TOKEN", TOKEN),
+    ]))
+    .expect("env provider with a previous Transit key");
+    let previous: Vec<&str> = providers.previous.iter().map(|p| p.key_id()).collect();
+    assert_eq!(previous, vec![wrapped.key_id.as_str()]);
+
+    let keyring = MasterKeyring::from_providers(providers.current, providers.previous);
+    keyring
+        .unwrap_dek(Some(&wrapped.key_id), &wrapped.nonce, &wrapped.ciphertext)
+        .await
+        .expect("unwrap under the previous Transit key");
+}
@@ -0,0 +1,110 @@
+#![allow(
+    clippy::expect_used,
+    clippy::panic,
+    reason = "test code: panics are the assertion mechanism"
+)]
+
+use systemprompt_web_admin::repositories::secrets::secret_crypto::{
+    SecretCryptoError, encrypt, generate_dek, generate_nonce,
+};
+use systemprompt_web_admin::repositories::secrets::secret_keyring::{MasterKeyring, master_key_id};
+
+const OLD: [u8; 32] = [7u8; 32];
+const NEW: [u8; 32] = [8u8; 32];
+
+#[test]
+fn a_key_id_is_stable_and_distinct_per_key() {
+    assert_eq!(master_key_id(&OLD), master_key_id(&OLD));
+    assert_ne!(master_key_id(&OLD), master_key_id(&NEW));
+    assert!(master_key_id(&OLD).starts_with("mk-"));
+    assert_eq!(master_key_id(&OLD).len(), "mk-".len() + 16);
+}
+
+#[tokio::test]
+async fn a_wrapped_dek_names_the_current_key_and_opens_again() {
+    let keyring = MasterKeyring::new(NEW, [OLD]);
+    let dek = generate_dek();
+    let wrapped = keyring.wrap_dek(&dek).await.expect("wrap");
+    assert_eq!(wrapped.key_id, master_key_id(&NEW));
+    let opened = keyring
+        .unwrap_dek(Some(&wrapped.key_id), &wrapped.nonce, &wrapped.ciphertext)
+        .await
+        .expect("unwrap");
+    assert_eq!(opened, dek);
+}
+
+#[tokio::test]
+async fn a_dek_under_a_previous_key_still_opens() {
+    let dek = generate_dek();
+    let wrapped = MasterKeyring::new(OLD, [])
+        .wrap_dek(&dek)
+        .await
+        .expect("wrap");
+    let rotated = MasterKeyring::new(NEW, [OLD]);
+    let opened = rotated
+        .unwrap_dek(Some(&wrapped.key_id), &wrapped.nonce, &wrapped.ciphertext)
+        .await
+        .expect("unwrap under the previous key");
+    assert_eq!(opened, dek);
+}
+
+#[tokio::test]
+async fn an_unversioned_dek_opens_under_whichever_key_sealed_it() {
+    let dek = generate_dek();
+    let nonce = generate_nonce();
+    let sealed = encrypt(&OLD, &nonce, &dek).expect("seal");
+    let keyring = MasterKeyring::new(NEW, [OLD]);
+    assert_eq!(
+        keyring
+            .unwrap_dek(None, &nonce, &sealed)
+            .await
+            .expect("unwrap"),
+        dek
+    );
+    assert!(matches!(
+        MasterKeyring::new(NEW, [])
+            .unwrap_dek(None, &nonce, &sealed)
+            .await,
+        Err(SecretCryptoError::DecryptionFailed(_))
+    ));
+}
+
+#[tokio::test]
+async fn a_key_that_is_not_configured_is_named_in_the_error() {
+    let wrapped = MasterKeyring::new(OLD, [])
+        .wrap_dek(&generate_dek())
+        .await
+        .expect("wrap");
+    let result = MasterKeyring::new(NEW, [])
+        .unwrap_dek(Some(&wrapped.key_id), &wrapped.nonce, &wrapped.ciphertext)
+        .await;
+    match result {
+        Err(SecretCryptoError::MasterKeyUnknown(id)) => assert_eq!(id, master_key_id(&OLD)),
+        other => panic!("expected MasterKeyUnknown, got {other:?}"),
+    }
+}
+
+#[test]
+fn previous_keys_drop_duplicates_and_the_current_key() {
+    let keyring = MasterKeyring::new(NEW, [OLD, NEW, OLD]);
+    let previous: Vec<&str> = keyring.previous_ids().collect();
+    assert_eq!(previous, vec![master_key_id(&OLD)]);
+}
+
+#[test]
+fn without_drops_retired_previous_keys_but_never_the_current_one() {
+    let retired = vec![master_key_id(&OLD), master_key_id(&NEW)];
+    let keyring = MasterKeyring::new(NEW, [OLD]).without(&retired);
+    assert_eq!(keyring.previous_ids().count(), 0);
+    assert_eq!(keyring.current_id(), master_key_id(&NEW));
+}
+
+#[test]
+fn debug_output_carries_key_ids_but_no_key_material() {
+    let rendered = format!("{:?}", MasterKeyring::new(NEW, [OLD]));
+    assert!(rendered.contains(&master_key_id(&NEW)));
+    assert!(
+        !rendered.contains("8, 8, 8"),
+        "key bytes leaked: {rendered}"
+    );
+}
@@ -0,0 +1,186 @@
+//! Secret lifecycle judgement: when a secret counts as expiring, expired or
+//! unused, when a resolution counts as unusual, and how the thresholds file
+//! loads.
+
+#![allow(
+    clippy::expect_used,
+    clippy::panic,
+    reason = "test code: panics are the assertion mechanism"
+)]
+
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use systemprompt::identifiers::UserId;
+use systemprompt_web_admin::repositories::config::secret_lifecycle::load_secret_lifecycle_policy;
+use systemprompt_web_admin::repositories::secrets::secret_lifecycle::{
+    ResolutionBaseline, SecretAlertKind, SecretAttentionRow,
+};
+use systemprompt_web_admin::secret_lifecycle::{
+    LifecyclePolicy, classify, describe, detect_anomalies,
+};
+
+fn now() -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2026, 10, 15, 12, 0, 0)
+        .single()
+        .expect("valid")
+}
+
+fn secret(expires_in_days: Option<i64>, last_used_days_ago: i64) -> SecretAttentionRow {
+    SecretAttentionRow {
+        id: "env-1".to_owned(),
+        user_id: UserId::new("u1"),
+        plugin_id: "plug".to_owned(),
+        var_name: "API_KEY".to_owned(),
+        owner_id: None,
+        expires_at: expires_in_days.map(|d| now() + Duration::days(d)),
+        last_used_at: now() - Duration::days(last_used_days_ago),
+    }
+}
+
+#[test]
+fn a_secret_without_expiry_in_regular_use_needs_nothing() {
+    let kinds = classify(&secret(None, 1), now(), &LifecyclePolicy::default());
+    assert!(kinds.is_empty());
+}
+
+#[test]
+fn expiry_inside_the_warning_window_is_expiring() {
+    let policy = LifecyclePolicy::default();
+    assert_eq!(
+        classify(&secret(Some(3), 1), now(), &policy),
+        vec![SecretAlertKind::Expiring]
+    );
+    assert!(classify(&secret(Some(30), 1), now(), &policy).is_empty());
+}
+
+#[test]
+fn expiry_in_the_past_is_expired_not_expiring() {
+    let kinds = classify(&secret(Some(-2), 1), now(), &LifecyclePolicy::default());
+    assert_eq!(kinds, vec![SecretAlertKind::Expired]);
+}
+
+#[test]
+fn a_secret_not_resolved_for_the_unused_window_is_unused() {
+    let policy = LifecyclePolicy::default();
+    assert_eq!(
+        classify(&secret(None, 120), now(), &policy),
+        vec![SecretAlertKind::Unused]
+    );
+    assert_eq!(
+        classify(&secret(Some(-1), 120), now(), &policy),
+        vec![SecretAlertKind::Expired, SecretAlertKind::Unused]
+    );
+}
+
+#[test]
+fn descriptions_name_the_days() {
+    assert_eq!(
+        describe(&secret(Some(5), 1), SecretAlertKind::Expiring, now()),
+        "Expires in 5 days"
+    );
+    assert_eq!(
+        describe(&secret(Some(-3), 1), SecretAlertKind::Expired, now()),
+        "Expired 3 days ago"
+    );
+    assert_eq!(
+        describe(&secret(None, 100), SecretAlertKind::Unused, now()),
+        "Not resolved for 100 days"
+    );
+}
+
+#[test]
+fn a_new_plugin_is_unusual_only_with_established_history() {
+    let policy = LifecyclePolicy::default();
+    let established = ResolutionBaseline {
+        user_30d: 12,
+        ..ResolutionBaseline::default()
+    };
+    assert_eq!(
+        detect_anomalies(&established, &policy),
+        vec![SecretAlertKind::UnusualPlugin]
+    );
+
+    let new_user = ResolutionBaseline {
+        user_30d: 2,
+        ..ResolutionBaseline::default()
+    };
+    assert!(detect_anomalies(&new_user, &policy).is_empty());
+
+    let known_plugin = ResolutionBaseline {
+        user_30d: 12,
+        plugin_30d: 4,
+        ..ResolutionBaseline::default()
+    };
+    assert!(detect_anomalies(&known_plugin, &policy).is_empty());
+}
+
+#[test]
+fn a_burst_far_above_the_usual_rate_is_unusual() {
+    let policy = LifecyclePolicy::default();
+    let burst = ResolutionBaseline {
+        user_30d: 200,
+        plugin_30d: 150,
+        plugin_last_hour: 40,
+        plugin_prior_7d: 168,
+    };
+    assert_eq!(
+        detect_anomalies(&burst, &policy),
+        vec![SecretAlertKind::UnusualRate]
+    );
+}
+
+#[test]
+fn a_busy_plugin_at_its_usual_rate_is_not_unusual() {
+    let policy = LifecyclePolicy::default();
+    let steady = ResolutionBaseline {
+        user_30d: 20_000,
+        plugin_30d: 20_000,
+        plugin_last_hour: 30,
+        plugin_prior_7d: 30 * 168,
+    };
+    assert!(detect_anomalies(&steady, &policy).is_empty());
+}
+
+#[test]
+fn a_small_burst_below_the_floor_is_not_unusual() {
+    let policy = LifecyclePolicy::default();
+    let small = ResolutionBaseline {
+        user_30d: 10,
+        plugin_30d: 10,
+        plugin_last_hour: 5,
+        plugin_prior_7d: 0,
+    };
+    assert!(detect_anomalies(&small, &policy).is_empty());
+}
+
+#[test]
+fn thresholds_file_is_optional_but_validated() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    assert_eq!(
+        load_secret_lifecycle_policy(dir.path()).expect("missing file"),
+        LifecyclePolicy::default()
+    );
+
+    std::fs::create_dir_all(dir.path().join("governance")).expect("mkdir");
+    let file = dir.path().join("governance/secret_lifecycle.yaml");
+    std::fs::write(
+        &file,
+        "expiry_warning_days: 30
+unused_after_days: 45
+",
+    )
+    .expect("write");
+    let policy = load_secret_lifecycle_policy(dir.path()).expect("valid file");
+    assert_eq!(policy.expiry_warning_days, 30);
+    assert_eq!(policy.unused_after_days, 45);
+    assert_eq!(policy.min_history, LifecyclePolicy::default().min_history);
+
+    for bad in [
+        "unused_after_days: 0\n",
+        "expiry_warning_days: -1\n",
+        "rate_multiplier: 0.5\n",
+        "unused_days: 30\n",
+    ] {
+        std::fs::write(&file, bad).expect("write");
+        assert!(load_secret_lifecycle_policy(dir.path()).is_err(), "{bad}");
+    }
+}
@@ -127,4 +127,4 @@ impl Job for BundleAdminCssJob {
     }
 }
 
-systemprompt::traits::submit_job!(&BundleAdminCssJob);
+systemprompt::traits::submit_job!(&crate::registry::Observed(BundleAdminCssJob));
@@ -133,4 +133,4 @@ impl Job for ContentAnalyticsAggregationJob {
     }
 }
 
-systemprompt::traits::submit_job!(&ContentAnalyticsAggregationJob);
+systemprompt::traits::submit_job!(&crate::registry::Observed(ContentAnalyticsAggregationJob));
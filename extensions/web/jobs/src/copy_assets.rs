@@ -131,4 +131,4 @@ impl Job for CopyExtensionAssetsJob {
     }
 }
 
-systemprompt::traits::submit_job!(&CopyExtensionAssetsJob);
+systemprompt::traits::submit_job!(&crate::registry::Observed(CopyExtensionAssetsJob));
@@ -0,0 +1,84 @@
+//! `cost_anomaly_scan` job: flags hours of anomalous spend per user,
+//! department and model, and refreshes month-end forecasts per department,
+//! under the thresholds in `services/finops/cost_watch.yaml`.
+//!
+//! Each run re-examines the last few closed hours; an hour already flagged is
+//! not flagged again, so the audit bus hears about each spike once.
+
+use std::sync::Arc;
+
+use chrono::Utc;
+use systemprompt::database::DbPool;
+use systemprompt::models::AppPaths;
+use systemprompt::traits::{Job, JobContext, JobResult};
+
+use crate::error::JobError;
+use systemprompt_web_admin::cost_watch::{refresh_forecasts, scan_anomalies};
+use systemprompt_web_admin::repositories::config::cost_watch::load_cost_watch_config;
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CostAnomalyScanJob;
+
+#[async_trait::async_trait]
+impl Job for CostAnomalyScanJob {
+    fn name(&self) -> &'static str {
+        "cost_anomaly_scan"
+    }
+
+    fn tags(&self) -> Vec<&'static str> {
+        vec![crate::registry::JOB_TAG]
+    }
+
+    fn description(&self) -> &'static str {
+        "Flags anomalous hourly spend per user, department and model, and forecasts month-end spend"
+    }
+
+    fn schedule(&self) -> &'static str {
+        "0 5 * * * *"
+    }
+
+    async fn execute(
+        &self,
+        ctx: &JobContext,
+    ) -> Result<JobResult, systemprompt::traits::ProviderError> {
+        Ok(execute_inner(ctx).await?)
+    }
+}
+
+async fn execute_inner(ctx: &JobContext) -> Result<JobResult, JobError> {
+    let start = std::time::Instant::now();
+
+    let paths = ctx
+        .app_paths::<Arc<AppPaths>>()
+        .ok_or(JobError::MissingContext("AppPaths"))?;
+    let config = load_cost_watch_config(paths.system().services())?;
+    if !config.enabled {
+        return Ok(JobResult::success().with_message("Cost watch disabled"));
+    }
+
+    let db = ctx
+        .db_pool::<DbPool>()
+        .ok_or(JobError::MissingContext("DbPool"))?;
+    let pool = db
+        .write_pool()
+        .ok_or(JobError::MissingContext("write PgPool"))?;
+
+    let now = Utc::now();
+    let report = scan_anomalies(&pool, &config, now).await?;
+    let departments = refresh_forecasts(&pool, &config, now).await?;
+    let duration_ms = u64::try_from(start.elapsed().as_millis()).unwrap_or(u64::MAX);
+    tracing::info!(
+        hours = report.hours_examined,
+        flagged = report.flagged,
+        recorded = report.recorded,
+        departments,
+        duration_ms,
+        "Cost anomaly scan completed"
+    );
+
+    Ok(JobResult::success()
+        .with_stats(u64::try_from(report.recorded).unwrap_or(u64::MAX), 0)
+        .with_duration(duration_ms))
+}
+
+systemprompt::traits::submit_job!(&crate::registry::Observed(CostAnomalyScanJob));
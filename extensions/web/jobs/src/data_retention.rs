@@ -0,0 +1,98 @@
+//! `data_retention` job: purges each class of governed data that has
+//! outlived its policy in `services/governance/retention.yaml`, skipping
+//! users and sessions under legal hold.
+//!
+//! Work per class is capped by the file's batch settings; a backlog larger
+//! than one run allows is worked down over the following nights. A class
+//! that fails is recorded on `/admin/governance/retention` and the others
+//! still run.
+
+use std::sync::Arc;
+
+use chrono::Utc;
+use systemprompt::database::DbPool;
+use systemprompt::models::AppPaths;
+use systemprompt::traits::{Job, JobContext, JobResult};
+
+use crate::error::JobError;
+use systemprompt_web_admin::repositories::config::retention::load_retention_config;
+use systemprompt_web_admin::retention::enforce;
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DataRetentionJob;
+
+#[async_trait::async_trait]
+impl Job for DataRetentionJob {
+    fn name(&self) -> &'static str {
+        "data_retention"
+    }
+
+    fn tags(&self) -> Vec<&'static str> {
+        vec![crate::registry::JOB_TAG]
+    }
+
+    fn description(&self) -> &'static str {
+        "Purges request bodies, hook events, transcripts, decisions and link clicks past their retention policy"
+    }
+
+    fn schedule(&self) -> &'static str {
+        "0 30 3 * * *"
+    }
+
+    async fn execute(
+        &self,
+        ctx: &JobContext,
+    ) -> Result<JobResult, systemprompt::traits::ProviderError> {
+        Ok(execute_inner(ctx).await?)
+    }
+}
+
+async fn execute_inner(ctx: &JobContext) -> Result<JobResult, JobError> {
+    let start = std::time::Instant::now();
+
+    let paths = ctx
+        .app_paths::<Arc<AppPaths>>()
+        .ok_or(JobError::MissingContext("AppPaths"))?;
+    let config = load_retention_config(paths.system().services())?;
+    if !config.enabled || config.policies.is_empty() {
+        return Ok(JobResult::success().with_message("No retention policy to enforce"));
+    }
+
+    let db = ctx
+        .db_pool::<DbPool>()
+        .ok_or(JobError::MissingContext("DbPool"))?;
+    let pool = db
+        .write_pool()
+        .ok_or(JobError::MissingContext("write PgPool"))?;
+
+    let outcomes = enforce(&pool, &config, Utc::now()).await?;
+    let mut purged = 0u64;
+    let mut failed = 0u64;
+    for outcome in &outcomes {
+        purged += outcome.purged;
+        match &outcome.error {
+            None => tracing::info!(
+                class = outcome.class.as_str(),
+                cutoff = %outcome.cutoff,
+                purged = outcome.purged,
+                "Retention purge completed"
+            ),
+            Some(error) => {
+                failed += 1;
+                tracing::warn!(
+                    class = outcome.class.as_str(),
+                    purged = outcome.purged,
+                    error = %error,
+                    "Retention purge failed"
+                );
+            },
+        }
+    }
+    let duration_ms = u64::try_from(start.elapsed().as_millis()).unwrap_or(u64::MAX);
+
+    Ok(JobResult::success()
+        .with_stats(purged, failed)
+        .with_duration(duration_ms))
+}
+
+systemprompt::traits::submit_job!(&crate::registry::Observed(DataRetentionJob));
@@ -0,0 +1,88 @@
+//! `digest_reports` job: sends each subscription in
+//! `services/reports/digests.yaml` its latest daily or weekly digest, by
+//! email and webhook.
+//!
+//! Runs hourly so a period is sent soon after it closes; the delivery ledger
+//! keeps every other run from sending it again, and retries one that failed.
+
+use std::sync::Arc;
+
+use chrono::Utc;
+use systemprompt::database::DbPool;
+use systemprompt::models::AppPaths;
+use systemprompt::traits::{Job, JobContext, JobResult};
+
+use crate::error::JobError;
+use systemprompt_web_admin::digests::{mail_transport, send_due_digests};
+use systemprompt_web_admin::repositories::config::digests::load_digest_config;
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DigestReportsJob;
+
+#[async_trait::async_trait]
+impl Job for DigestReportsJob {
+    fn name(&self) -> &'static str {
+        "digest_reports"
+    }
+
+    fn tags(&self) -> Vec<&'static str> {
+        vec![crate::registry::JOB_TAG]
+    }
+
+    fn description(&self) -> &'static str {
+        "Emails and posts daily and weekly digests of spend, denials, safety findings, evals and sessions"
+    }
+
+    fn schedule(&self) -> &'static str {
+        "0 15 * * * *"
+    }
+
+    async fn execute(
+        &self,
+        ctx: &JobContext,
+    ) -> Result<JobResult, systemprompt::traits::ProviderError> {
+        Ok(execute_inner(ctx).await?)
+    }
+}
+
+async fn execute_inner(ctx: &JobContext) -> Result<JobResult, JobError> {
+    let start = std::time::Instant::now();
+
+    let paths = ctx
+        .app_paths::<Arc<AppPaths>>()
+        .ok_or(JobError::MissingContext("AppPaths"))?;
+    let config = load_digest_config(paths.system().services())?;
+    if !config.enabled || config.subscriptions.is_empty() {
+        return Ok(JobResult::success().with_message("No digest subscriptions"));
+    }
+    let transport = mail_transport(
+        &config.mail,
+        paths.system().root(),
+        &paths.system().logs().join("digests"),
+    )?;
+
+    let db = ctx
+        .db_pool::<DbPool>()
+        .ok_or(JobError::MissingContext("DbPool"))?;
+    let pool = db
+        .write_pool()
+        .ok_or(JobError::MissingContext("write PgPool"))?;
+
+    let report = send_due_digests(&pool, &config, transport.as_ref(), Utc::now()).await;
+    let duration_ms = u64::try_from(start.elapsed().as_millis()).unwrap_or(u64::MAX);
+    tracing::info!(
+        delivered = report.delivered,
+        failed = report.failed,
+        duration_ms,
+        "Digest reports completed"
+    );
+
+    Ok(JobResult::success()
+        .with_stats(
+            u64::try_from(report.delivered).unwrap_or(u64::MAX),
+            u64::try_from(report.failed).unwrap_or(u64::MAX),
+        )
+        .with_duration(duration_ms))
+}
+
+systemprompt::traits::submit_job!(&crate::registry::Observed(DigestReportsJob));
@@ -158,4 +158,4 @@ async fn bootstrap_gateway_entities(db_pool: &DbPool) -> Result<usize, JobError>
         .map_err(|e| JobError::from(MarketplaceError::Internal(e.to_string())))
 }
 
-systemprompt::traits::submit_job!(&GovernanceBootstrapJob);
+systemprompt::traits::submit_job!(&crate::registry::Observed(GovernanceBootstrapJob));
@@ -174,4 +174,4 @@ async fn ingest_source(
     }
 }
 
-systemprompt::traits::submit_job!(&ContentIngestionJob);
+systemprompt::traits::submit_job!(&crate::registry::Observed(ContentIngestionJob));
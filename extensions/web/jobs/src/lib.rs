@@ -12,6 +12,17 @@
 //!   consumed by the SSR layer.
 //! - **Analytics / housekeeping** ([`ContentAnalyticsAggregationJob`],
 //!   [`SecretMigrationJob`]) — periodic rollups and one-shot migrations.
+//! - **Key management** ([`MasterKeyRotationJob`]) — re-wraps data encryption
+//!   keys after a master key change and retires the old key.
+//! - **Secret lifecycle** ([`SecretLifecycleJob`]) — flags plugin secrets that
+//!   are expiring, expired or unused.
+//! - **Cost watch** ([`CostAnomalyScanJob`]) — flags spend spikes and forecasts
+//!   month-end spend per department.
+//! - **Reports** ([`DigestReportsJob`]) — emails and posts daily and weekly
+//!   digests to their subscribers.
+//! - **Export** ([`OtlpTraceExportJob`], [`WarehouseExportJob`]) — ships
+//!   governed traces to an OpenTelemetry collector, and raw governed rows to a
+//!   data warehouse landing area.
 //!
 //! Errors normalise on [`JobError`]; the scheduler logs and surfaces them
 //! through `infra logs trace`.
@@ -22,14 +33,21 @@ mod registry;
 mod bundle_admin_css;
 mod content_analytics;
 mod copy_assets;
+mod cost_anomaly_scan;
+mod data_retention;
+mod digest_reports;
 mod governance_bootstrap;
 mod ingestion;
 mod llms_txt;
+mod master_key_rotation;
+mod otlp_export;
 mod prerender;
 mod publish;
 mod robots;
+mod secret_lifecycle;
 mod secret_migration;
 mod sitemap;
+mod warehouse_export;
 
 pub use error::JobError;
 pub use registry::{JOB_TAG, extension_jobs};
@@ -37,14 +55,21 @@ pub use registry::{JOB_TAG, extension_jobs};
 pub use bundle_admin_css::BundleAdminCssJob;
 pub use content_analytics::ContentAnalyticsAggregationJob;
 pub use copy_assets::CopyExtensionAssetsJob;
+pub use cost_anomaly_scan::CostAnomalyScanJob;
+pub use data_retention::DataRetentionJob;
+pub use digest_reports::DigestReportsJob;
 pub use governance_bootstrap::GovernanceBootstrapJob;
 pub use ingestion::ContentIngestionJob;
 pub use llms_txt::LlmsTxtGenerationJob;
+pub use master_key_rotation::MasterKeyRotationJob;
+pub use otlp_export::OtlpTraceExportJob;
 pub use prerender::ContentPrerenderJob;
 pub use publish::PublishPipelineJob;
 pub use robots::RobotsTxtGenerationJob;
+pub use secret_lifecycle::SecretLifecycleJob;
 pub use secret_migration::SecretMigrationJob;
 pub use sitemap::SitemapGenerationJob;
+pub use warehouse_export::WarehouseExportJob;
 
 /// The pure helpers behind the jobs above, re-exported for the external test
 /// workspace so their file-format and accounting behaviour can be asserted
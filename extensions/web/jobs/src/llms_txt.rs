@@ -60,7 +60,7 @@ async fn execute_inner(ctx: &JobContext) -> Result<JobResult, JobError> {
     Ok(JobResult::success().with_duration(duration_ms))
 }
 
-systemprompt::traits::submit_job!(&LlmsTxtGenerationJob);
+systemprompt::traits::submit_job!(&crate::registry::Observed(LlmsTxtGenerationJob));
 
 pub(crate) async fn generate_llms_txt(db_pool: DbPool, paths: &AppPaths) -> Result<(), JobError> {
     use systemprompt::models::Config;
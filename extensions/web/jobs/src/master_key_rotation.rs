@@ -0,0 +1,89 @@
+//! `master_key_rotation` job: re-wraps data encryption keys under the current
+//! master key and retires the keys it replaced.
+//!
+//! The work is in [`systemprompt_web_admin::master_key_rotation`]. A run is
+//! capped, and a rotation larger than one run allows is finished over the
+//! following hours.
+
+use systemprompt::database::DbPool;
+use systemprompt::traits::{Job, JobContext, JobResult};
+
+use crate::error::JobError;
+use systemprompt_web_admin::master_key_rotation::{RotationLimits, rotate};
+use systemprompt_web_admin::repositories::secrets::secret_crypto::SecretCryptoError;
+use systemprompt_web_admin::repositories::secrets::secret_keyring;
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MasterKeyRotationJob;
+
+#[async_trait::async_trait]
+impl Job for MasterKeyRotationJob {
+    fn name(&self) -> &'static str {
+        "master_key_rotation"
+    }
+
+    fn tags(&self) -> Vec<&'static str> {
+        vec![crate::registry::JOB_TAG]
+    }
+
+    fn description(&self) -> &'static str {
+        "Re-wraps data encryption keys under the current master key and retires replaced keys"
+    }
+
+    fn schedule(&self) -> &'static str {
+        "0 15 * * * *"
+    }
+
+    async fn execute(
+        &self,
+        ctx: &JobContext,
+    ) -> Result<JobResult, systemprompt::traits::ProviderError> {
+        Ok(execute_inner(ctx).await?)
+    }
+}
+
+async fn execute_inner(ctx: &JobContext) -> Result<JobResult, JobError> {
+    let start = std::time::Instant::now();
+
+    let keyring = match secret_keyring::load_master_keyring() {
+        Ok(keyring) => keyring,
+        Err(SecretCryptoError::MasterKeyMissing) => {
+            return Ok(JobResult::success().with_message("No master key configured"));
+        },
+        Err(e) => return Err(e.into()),
+    };
+
+    let db = ctx
+        .db_pool::<DbPool>()
+        .ok_or(JobError::MissingContext("DbPool"))?;
+    let pool = db
+        .write_pool()
+        .ok_or(JobError::MissingContext("write PgPool"))?;
+
+    let outcome = rotate(
+        &pool,
+        keyring,
+        &ctx.actor().user_id,
+        RotationLimits::default(),
+    )
+    .await?;
+    let duration_ms = u64::try_from(start.elapsed().as_millis()).unwrap_or(u64::MAX);
+
+    if outcome.rotation_id.is_some() || !outcome.retired.is_empty() {
+        tracing::info!(
+            rotation_id = outcome.rotation_id.as_deref().unwrap_or_default(),
+            rewrapped = outcome.rewrapped,
+            failed = outcome.failed,
+            remaining = outcome.remaining,
+            retired = outcome.retired.len(),
+            duration_ms,
+            "Master key rotation run completed"
+        );
+    }
+
+    Ok(JobResult::success()
+        .with_stats(outcome.rewrapped, outcome.failed)
+        .with_duration(duration_ms))
+}
+
+systemprompt::traits::submit_job!(&crate::registry::Observed(MasterKeyRotationJob));
@@ -0,0 +1,78 @@
+//! `otlp_trace_export` job: ships the next window of governed traces to the
+//! OTLP collector configured in `services/telemetry/otlp.yaml`.
+//!
+//! Does nothing while the exporter is disabled. A failed delivery fails the
+//! run and leaves the mark where it was, so the next run retries the window.
+
+use std::sync::Arc;
+
+use systemprompt::database::DbPool;
+use systemprompt::models::AppPaths;
+use systemprompt::traits::{Job, JobContext, JobResult};
+
+use crate::error::JobError;
+use systemprompt_web_admin::otlp::export_traces;
+use systemprompt_web_admin::repositories::config::otlp::load_otlp_export_config;
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OtlpTraceExportJob;
+
+#[async_trait::async_trait]
+impl Job for OtlpTraceExportJob {
+    fn name(&self) -> &'static str {
+        "otlp_trace_export"
+    }
+
+    fn tags(&self) -> Vec<&'static str> {
+        vec![crate::registry::JOB_TAG]
+    }
+
+    fn description(&self) -> &'static str {
+        "Exports governed traces (model requests, tool calls, policy decisions) to an OTLP collector"
+    }
+
+    fn schedule(&self) -> &'static str {
+        "0 * * * * *"
+    }
+
+    async fn execute(
+        &self,
+        ctx: &JobContext,
+    ) -> Result<JobResult, systemprompt::traits::ProviderError> {
+        Ok(execute_inner(ctx).await?)
+    }
+}
+
+async fn execute_inner(ctx: &JobContext) -> Result<JobResult, JobError> {
+    let start = std::time::Instant::now();
+
+    let paths = ctx
+        .app_paths::<Arc<AppPaths>>()
+        .ok_or(JobError::MissingContext("AppPaths"))?;
+    let config = load_otlp_export_config(paths.system().services())?;
+    if !config.enabled {
+        return Ok(JobResult::success().with_message("OTLP export disabled"));
+    }
+
+    let db = ctx
+        .db_pool::<DbPool>()
+        .ok_or(JobError::MissingContext("DbPool"))?;
+    let pool = db
+        .write_pool()
+        .ok_or(JobError::MissingContext("write PgPool"))?;
+
+    let report = export_traces(&pool, &config).await?;
+    let duration_ms = u64::try_from(start.elapsed().as_millis()).unwrap_or(u64::MAX);
+    tracing::info!(
+        spans = report.spans,
+        through = ?report.exported_through,
+        duration_ms,
+        "OTLP trace export completed"
+    );
+
+    Ok(JobResult::success()
+        .with_stats(u64::try_from(report.spans).unwrap_or(u64::MAX), 0)
+        .with_duration(duration_ms))
+}
+
+systemprompt::traits::submit_job!(&crate::registry::Observed(OtlpTraceExportJob));
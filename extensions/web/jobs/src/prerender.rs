@@ -61,4 +61,4 @@ async fn execute_inner(ctx: &JobContext) -> Result<JobResult, JobError> {
     Ok(JobResult::success().with_duration(duration_ms))
 }
 
-systemprompt::traits::submit_job!(&ContentPrerenderJob);
+systemprompt::traits::submit_job!(&crate::registry::Observed(ContentPrerenderJob));
@@ -289,4 +289,4 @@ impl PublishPipelineJob {
     }
 }
 
-systemprompt::traits::submit_job!(&PublishPipelineJob);
+systemprompt::traits::submit_job!(&crate::registry::Observed(PublishPipelineJob));
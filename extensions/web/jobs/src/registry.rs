@@ -6,10 +6,17 @@
 //! a second hand-written list that can drift. Ownership is expressed through
 //! [`JOB_TAG`]: every job in this crate tags itself with it, and only tagged
 //! jobs are reported as belonging to this extension.
+//!
+//! Each job is submitted wrapped in [`Observed`], which times every run and
+//! records it for the `/metrics` endpoint. The scheduler finds jobs both
+//! through this extension and straight from the inventory, so the wrapper
+//! has to sit on the inventory entry itself to see every run.
 
 use std::sync::Arc;
+use std::time::Instant;
 
 use systemprompt::traits::{Job, JobContext, JobResult, ProviderError};
+use systemprompt_web_admin::metrics::runtime::record_job_run;
 
 pub const JOB_TAG: &str = "web-extension";
 
@@ -56,3 +63,40 @@ impl Job for StaticJob {
         self.0.schedulable()
     }
 }
+
+pub(crate) struct Observed<J>(pub J);
+
+#[async_trait::async_trait]
+impl<J: Job> Job for Observed<J> {
+    fn name(&self) -> &'static str {
+        self.0.name()
+    }
+
+    fn description(&self) -> &'static str {
+        self.0.description()
+    }
+
+    fn schedule(&self) -> &'static str {
+        self.0.schedule()
+    }
+
+    fn tags(&self) -> Vec<&'static str> {
+        self.0.tags()
+    }
+
+    async fn execute(&self, ctx: &JobContext) -> Result<JobResult, ProviderError> {
+        let start = Instant::now();
+        let result = self.0.execute(ctx).await;
+        let success = result.as_ref().is_ok_and(|r| r.success);
+        record_job_run(self.0.name(), start.elapsed(), success);
+        result
+    }
+
+    fn enabled(&self) -> bool {
+        self.0.enabled()
+    }
+
+    fn schedulable(&self) -> bool {
+        self.0.schedulable()
+    }
+}
@@ -90,4 +90,4 @@ pub fn build_robots_txt_content(base_url: &str) -> Result<String, JobError> {
     Ok(content)
 }
 
-systemprompt::traits::submit_job!(&RobotsTxtGenerationJob);
+systemprompt::traits::submit_job!(&crate::registry::Observed(RobotsTxtGenerationJob));
@@ -0,0 +1,79 @@
+//! `secret_lifecycle` job: flags plugin secrets that have expired, are about
+//! to, or have gone unused.
+//!
+//! The work is in [`systemprompt_web_admin::secret_lifecycle`], with the
+//! thresholds from `services/governance/secret_lifecycle.yaml`. Alerts are
+//! recorded once, so each hourly run only adds what is new.
+
+use std::sync::Arc;
+
+use chrono::Utc;
+use systemprompt::database::DbPool;
+use systemprompt::models::AppPaths;
+use systemprompt::traits::{Job, JobContext, JobResult};
+
+use crate::error::JobError;
+use systemprompt_web_admin::repositories::config::secret_lifecycle::load_secret_lifecycle_policy;
+use systemprompt_web_admin::secret_lifecycle::scan;
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SecretLifecycleJob;
+
+#[async_trait::async_trait]
+impl Job for SecretLifecycleJob {
+    fn name(&self) -> &'static str {
+        "secret_lifecycle"
+    }
+
+    fn tags(&self) -> Vec<&'static str> {
+        vec![crate::registry::JOB_TAG]
+    }
+
+    fn description(&self) -> &'static str {
+        "Flags plugin secrets that are expiring, expired or unused"
+    }
+
+    fn schedule(&self) -> &'static str {
+        "0 25 * * * *"
+    }
+
+    async fn execute(
+        &self,
+        ctx: &JobContext,
+    ) -> Result<JobResult, systemprompt::traits::ProviderError> {
+        Ok(execute_inner(ctx).await?)
+    }
+}
+
+async fn execute_inner(ctx: &JobContext) -> Result<JobResult, JobError> {
+    let start = std::time::Instant::now();
+
+    let paths = ctx
+        .app_paths::<Arc<AppPaths>>()
+        .ok_or(JobError::MissingContext("AppPaths"))?;
+    let policy = load_secret_lifecycle_policy(paths.system().services())?;
+
+    let db = ctx
+        .db_pool::<DbPool>()
+        .ok_or(JobError::MissingContext("DbPool"))?;
+    let pool = db
+        .write_pool()
+        .ok_or(JobError::MissingContext("write PgPool"))?;
+
+    let report = scan(&pool, &policy, Utc::now()).await?;
+    let duration_ms = u64::try_from(start.elapsed().as_millis()).unwrap_or(u64::MAX);
+
+    tracing::info!(
+        examined = report.examined,
+        flagged = report.flagged,
+        recorded = report.recorded,
+        duration_ms,
+        "Secret lifecycle scan completed"
+    );
+
+    Ok(JobResult::success()
+        .with_stats(u64::try_from(report.recorded).unwrap_or(u64::MAX), 0)
+        .with_duration(duration_ms))
+}
+
+systemprompt::traits::submit_job!(&crate::registry::Observed(SecretLifecycleJob));
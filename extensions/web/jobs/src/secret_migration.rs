@@ -5,6 +5,7 @@ use systemprompt::identifiers::UserId;
 use systemprompt::traits::{Job, JobContext, JobResult};
 
 use crate::error::JobError;
+use systemprompt_web_admin::repositories::secrets::secret_keyring::{self, MasterKeyring};
 use systemprompt_web_admin::repositories::secrets::{secret_crypto, secret_keys, secret_migration};
 use systemprompt_web_shared::error::MarketplaceError;
 
@@ -39,7 +40,7 @@ impl Job for SecretMigrationJob {
 async fn execute_inner(ctx: &JobContext) -> Result<JobResult, JobError> {
     let start = std::time::Instant::now();
 
-    let Ok(master_key) = secret_crypto::load_master_key() else {
+    let Ok(keyring) = secret_keyring::load_master_keyring() else {
         return Ok(JobResult::success().with_stats(0, 0).with_duration(0));
     };
 
@@ -64,7 +65,7 @@ async fn execute_inner(ctx: &JobContext) -> Result<JobResult, JobError> {
             .with_duration(duration_ms));
     }
 
-    let (success_count, error_count) = migrate_secrets(&pool, &rows, &master_key, actor_user).await;
+    let (success_count, error_count) = migrate_secrets(&pool, &rows, &keyring, actor_user).await;
 
     let duration_ms = u64::try_from(start.elapsed().as_millis()).unwrap_or(u64::MAX);
 
@@ -84,14 +85,14 @@ async fn execute_inner(ctx: &JobContext) -> Result<JobResult, JobError> {
 async fn migrate_secrets(
     pool: &std::sync::Arc<sqlx::PgPool>,
     rows: &[secret_migration::UnencryptedSecret],
-    master_key: &[u8; 32],
+    keyring: &MasterKeyring,
     actor: &UserId,
 ) -> (u64, u64) {
     let mut success_count = 0u64;
     let mut error_count = 0u64;
 
     for row in rows {
-        let result = encrypt_and_store_secret(pool, row, master_key, actor).await;
+        let result = encrypt_and_store_secret(pool, row, keyring, actor).await;
 
         match result {
             Ok(()) => {
@@ -111,11 +112,11 @@ async fn migrate_secrets(
 async fn encrypt_and_store_secret(
     pool: &std::sync::Arc<sqlx::PgPool>,
     row: &secret_migration::UnencryptedSecret,
-    master_key: &[u8; 32],
+    keyring: &MasterKeyring,
     actor: &UserId,
 ) -> Result<(), JobError> {
     let dek =
-        secret_keys::get_or_create_user_dek(pool, &UserId::new(&row.user_id), master_key).await?;
+        secret_keys::get_or_create_user_dek(pool, &UserId::new(&row.user_id), keyring).await?;
 
     let nonce = secret_crypto::generate_nonce();
     let encrypted = secret_crypto::encrypt(&dek, &nonce, row.var_value.as_bytes())?;
@@ -141,4 +142,4 @@ async fn encrypt_and_store_secret(
     Ok(())
 }
 
-systemprompt::traits::submit_job!(&SecretMigrationJob);
+systemprompt::traits::submit_job!(&crate::registry::Observed(SecretMigrationJob));
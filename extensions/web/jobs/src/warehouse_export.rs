@@ -0,0 +1,85 @@
+//! `warehouse_export` job: writes the next windows of governed rows to the
+//! data warehouse landing area configured in
+//! `services/telemetry/warehouse.yaml`.
+//!
+//! Does nothing while the exporter is disabled. A table whose write fails is
+//! reported in the run's stats and retried from the same mark next run; the
+//! other tables still export.
+
+use std::sync::Arc;
+
+use systemprompt::database::DbPool;
+use systemprompt::models::AppPaths;
+use systemprompt::traits::{Job, JobContext, JobResult};
+
+use crate::error::JobError;
+use systemprompt_web_admin::repositories::config::warehouse::load_warehouse_export_config;
+use systemprompt_web_admin::warehouse::{export_warehouse, warehouse_store};
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WarehouseExportJob;
+
+#[async_trait::async_trait]
+impl Job for WarehouseExportJob {
+    fn name(&self) -> &'static str {
+        "warehouse_export"
+    }
+
+    fn tags(&self) -> Vec<&'static str> {
+        vec![crate::registry::JOB_TAG]
+    }
+
+    fn description(&self) -> &'static str {
+        "Exports requests, decisions, hook events, session analyses and eval results as Parquet or JSONL"
+    }
+
+    fn schedule(&self) -> &'static str {
+        "0 20 * * * *"
+    }
+
+    async fn execute(
+        &self,
+        ctx: &JobContext,
+    ) -> Result<JobResult, systemprompt::traits::ProviderError> {
+        Ok(execute_inner(ctx).await?)
+    }
+}
+
+async fn execute_inner(ctx: &JobContext) -> Result<JobResult, JobError> {
+    let start = std::time::Instant::now();
+
+    let paths = ctx
+        .app_paths::<Arc<AppPaths>>()
+        .ok_or(JobError::MissingContext("AppPaths"))?;
+    let config = load_warehouse_export_config(paths.system().services())?;
+    if !config.enabled {
+        return Ok(JobResult::success().with_message("Warehouse export disabled"));
+    }
+    let store = warehouse_store(&config.destination, paths.system().root())?;
+
+    let db = ctx
+        .db_pool::<DbPool>()
+        .ok_or(JobError::MissingContext("DbPool"))?;
+    let pool = db
+        .write_pool()
+        .ok_or(JobError::MissingContext("write PgPool"))?;
+
+    let report = export_warehouse(&pool, &config, store.as_ref()).await?;
+    let duration_ms = u64::try_from(start.elapsed().as_millis()).unwrap_or(u64::MAX);
+    tracing::info!(
+        rows = report.rows,
+        files = report.files,
+        failed = ?report.failed,
+        duration_ms,
+        "Warehouse export completed"
+    );
+
+    Ok(JobResult::success()
+        .with_stats(
+            u64::try_from(report.rows).unwrap_or(u64::MAX),
+            u64::try_from(report.failed.len()).unwrap_or(u64::MAX),
+        )
+        .with_duration(duration_ms))
+}
+
+systemprompt::traits::submit_job!(&crate::registry::Observed(WarehouseExportJob));
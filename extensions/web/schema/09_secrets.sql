@@ -10,22 +10,29 @@ CREATE TABLE IF NOT EXISTS plugin_env_vars (
     encrypted_value BYTEA,
     value_nonce BYTEA,
     key_version INTEGER NOT NULL DEFAULT 0,
+    owner_id TEXT,
+    expires_at TIMESTAMPTZ,
+    last_resolved_at TIMESTAMPTZ,
     created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
     updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
     UNIQUE(user_id, plugin_id, var_name)
 );
 CREATE INDEX IF NOT EXISTS idx_plugin_env_user_plugin ON plugin_env_vars(user_id, plugin_id);
+CREATE INDEX IF NOT EXISTS idx_plugin_env_vars_expires ON plugin_env_vars(expires_at)
+    WHERE expires_at IS NOT NULL;
 
 CREATE TABLE IF NOT EXISTS user_encryption_keys (
     id TEXT PRIMARY KEY,
     user_id TEXT NOT NULL UNIQUE,
     encrypted_dek BYTEA NOT NULL,
     dek_nonce BYTEA NOT NULL,
+    master_key_id TEXT,
     key_version INTEGER NOT NULL DEFAULT 1,
     created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
     rotated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
 );
 CREATE INDEX IF NOT EXISTS idx_user_encryption_keys_user_id ON user_encryption_keys(user_id);
+CREATE INDEX IF NOT EXISTS idx_user_encryption_keys_master_key ON user_encryption_keys(master_key_id);
 
 CREATE TABLE IF NOT EXISTS secret_audit_log (
     id TEXT PRIMARY KEY,
@@ -0,0 +1,33 @@
+-- SCIM 2.0 provisioning state.
+--
+-- The identity provider is the source of truth for every user it provisions.
+-- `scim_users` marks a `users` row as IdP-managed and carries the IdP's own
+-- identifier; roles and department for a managed user are derived from the
+-- groups below rather than edited by hand. Deprovisioning flips
+-- `users.status` and never deletes, so `user_activity` and `ai_requests` rows
+-- stay attributable to a real account.
+
+CREATE TABLE IF NOT EXISTS scim_users (
+    user_id TEXT PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    external_id TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_scim_users_external_id
+    ON scim_users(external_id) WHERE external_id IS NOT NULL;
+
+CREATE TABLE IF NOT EXISTS scim_groups (
+    id TEXT PRIMARY KEY,
+    display_name TEXT NOT NULL UNIQUE,
+    external_id TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE IF NOT EXISTS scim_group_members (
+    group_id TEXT NOT NULL REFERENCES scim_groups(id) ON DELETE CASCADE,
+    user_id TEXT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    added_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (group_id, user_id)
+);
+CREATE INDEX IF NOT EXISTS idx_scim_group_members_user ON scim_group_members(user_id);
@@ -0,0 +1,26 @@
+-- Revision history for the gateway configuration.
+--
+-- The gateway config is files, not rows: the profile YAML's `gateway` block
+-- and `services/gateway/targets.yaml`. Every change made through the admin
+-- API stores the complete state of both after the change, so any revision
+-- can be diffed against its predecessor or restored verbatim. `etag` is the
+-- digest of that state and is what `If-Match` is compared against. A change
+-- made outside the admin API (a hand edit, a deploy) is recorded with
+-- `action = 'external'` and no actor the next time the admin API writes.
+--
+-- `actor_id` is not a foreign key to `users`: erasing a user replaces it with
+-- their pseudonym, which names no account, and the revision is kept.
+
+CREATE TABLE IF NOT EXISTS gateway_config_revisions (
+    id BIGSERIAL PRIMARY KEY,
+    etag TEXT NOT NULL,
+    action TEXT NOT NULL,
+    summary TEXT NOT NULL DEFAULT '',
+    actor_id TEXT,
+    gateway_yaml TEXT NOT NULL,
+    targets_yaml TEXT NOT NULL,
+    restored_from BIGINT REFERENCES gateway_config_revisions(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+CREATE INDEX IF NOT EXISTS idx_gateway_config_revisions_created
+    ON gateway_config_revisions(created_at DESC);
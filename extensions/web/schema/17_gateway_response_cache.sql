@@ -0,0 +1,90 @@
+-- Gateway response cache.
+--
+-- Routes opt in through `services/gateway/cache.yaml`. An entry is one
+-- upstream reply, stored wire-neutral so a caller on any inbound protocol can
+-- be served from it. `cache_key` is the digest of the canonical request as
+-- sent upstream (plus provider, upstream model and, with per-user isolation,
+-- the user); `shape_key` is the same digest without the final message, and is
+-- what similarity matching compares within. `embedding` is only set on routes
+-- with a similarity threshold.
+--
+-- Every lookup on a cached route leaves one `gateway_cache_lookups` row:
+-- `outcome` is `exact`, `semantic` or `miss`. A miss names the request the
+-- adapter matched its body to. A hit is replayed under its own `replay_id`
+-- as the response id, and is tied to a request only by the trigger below,
+-- once core stores that reply on the request that served it. The trigger
+-- then stamps the `ai_requests` row: `cache_hit = TRUE` and zero cost, since
+-- nothing was sent upstream.
+
+CREATE TABLE IF NOT EXISTS gateway_cache_entries (
+    id BIGSERIAL PRIMARY KEY,
+    cache_key TEXT NOT NULL UNIQUE,
+    shape_key TEXT NOT NULL,
+    route_id TEXT NOT NULL,
+    scope_user_id TEXT REFERENCES users(id) ON DELETE CASCADE,
+    provider TEXT NOT NULL,
+    upstream_model TEXT NOT NULL,
+    embedding REAL[],
+    response JSONB NOT NULL,
+    source_ai_request_id TEXT REFERENCES ai_requests(id) ON DELETE SET NULL,
+    hit_count BIGINT NOT NULL DEFAULT 0,
+    last_hit_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    expires_at TIMESTAMPTZ NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_gateway_cache_entries_shape
+    ON gateway_cache_entries(shape_key, expires_at DESC);
+CREATE INDEX IF NOT EXISTS idx_gateway_cache_entries_expires
+    ON gateway_cache_entries(expires_at);
+
+CREATE TABLE IF NOT EXISTS gateway_cache_lookups (
+    id BIGSERIAL PRIMARY KEY,
+    ai_request_id TEXT REFERENCES ai_requests(id) ON DELETE CASCADE,
+    replay_id TEXT UNIQUE,
+    route_id TEXT NOT NULL,
+    outcome TEXT NOT NULL CHECK (outcome IN ('exact', 'semantic', 'miss')),
+    entry_id BIGINT REFERENCES gateway_cache_entries(id) ON DELETE SET NULL,
+    similarity REAL,
+    saved_microdollars BIGINT NOT NULL DEFAULT 0,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CHECK ((outcome = 'miss') = (replay_id IS NULL))
+);
+CREATE INDEX IF NOT EXISTS idx_gateway_cache_lookups_created
+    ON gateway_cache_lookups(created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_gateway_cache_lookups_request
+    ON gateway_cache_lookups(ai_request_id);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_gateway_cache_lookups_miss
+    ON gateway_cache_lookups(ai_request_id) WHERE outcome = 'miss';
+
+-- The replay id is the first `cache_` token in the stored reply: every wire
+-- puts the response id ahead of the content, except Gemini, whose
+-- `responseId` trails it and is kept in the tail of a truncated excerpt.
+CREATE OR REPLACE FUNCTION gateway_cache_bind_replay()
+RETURNS TRIGGER AS $$
+DECLARE
+    replay TEXT := substring(
+        COALESCE(NEW.response_body::text, NEW.response_excerpt)
+        FROM 'cache_[0-9a-f]{32}'
+    );
+    marked INT;
+BEGIN
+    IF replay IS NULL THEN
+        RETURN NEW;
+    END IF;
+    WITH bound AS (UPDATE gateway_cache_lookups
+                   SET ai_request_id = NEW.ai_request_id
+                   WHERE replay_id = replay AND ai_request_id IS NULL
+                   RETURNING ai_request_id),
+    hit AS (UPDATE ai_requests
+            SET cache_hit = TRUE, cost_microdollars = 0
+            WHERE id IN (SELECT ai_request_id FROM bound)
+            RETURNING id)
+    SELECT COUNT(*) INTO marked FROM hit;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE TRIGGER gateway_cache_bind_replay_trg
+    AFTER INSERT OR UPDATE OF response_body, response_excerpt ON ai_request_payloads
+    FOR EACH ROW
+    EXECUTE FUNCTION gateway_cache_bind_replay();
@@ -0,0 +1,25 @@
+-- High-water marks for exporters that ship governed activity to an outside
+-- system on a schedule.
+--
+-- One row per exporter. `exported_through` is the end of the last window the
+-- exporter delivered in full; the next run starts there, so a window that
+-- failed part-way is sent again from its start rather than skipped. The last
+-- failure is kept beside the mark so a stuck exporter is visible without
+-- reading job logs.
+
+CREATE TABLE IF NOT EXISTS export_cursors (
+    exporter TEXT PRIMARY KEY,
+    exported_through TIMESTAMPTZ NOT NULL,
+    items_exported BIGINT NOT NULL DEFAULT 0,
+    last_run_at TIMESTAMPTZ,
+    last_error TEXT,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- The warehouse export windows `ai_requests` on `completed_at` and
+-- `session_analyses` on `updated_at` (see `admin/src/warehouse`); without
+-- these each window is a scan of the whole table.
+CREATE INDEX IF NOT EXISTS idx_ai_requests_completed
+    ON ai_requests (completed_at) WHERE completed_at IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_session_analyses_updated
+    ON session_analyses (updated_at);
@@ -0,0 +1,67 @@
+-- Spend the `cost_anomaly_scan` job found out of line, and where each
+-- department's month is heading.
+--
+-- `cost_anomalies` holds one row per subject and hour that broke from its
+-- baseline. A subject is a user id, a department name or a model, per
+-- `scope`. The unique key makes a rescan of the same hour a no-op, so the job
+-- re-evaluates recent hours freely and the notify trigger fires once per
+-- anomaly. `baseline_microdollars` and `stddev_microdollars` describe the
+-- subject's hourly spend over the baseline window the hour was judged against.
+--
+-- `cost_forecasts` keeps the latest projection per department and month;
+-- each run replaces the current month's row.
+
+CREATE TABLE IF NOT EXISTS cost_anomalies (
+    id TEXT PRIMARY KEY,
+    scope TEXT NOT NULL CHECK (scope IN ('user', 'department', 'model')),
+    subject TEXT NOT NULL,
+    hour_start TIMESTAMPTZ NOT NULL,
+    spend_microdollars BIGINT NOT NULL,
+    requests BIGINT NOT NULL,
+    baseline_microdollars BIGINT NOT NULL,
+    stddev_microdollars BIGINT NOT NULL,
+    ratio DOUBLE PRECISION NOT NULL,
+    severity TEXT NOT NULL CHECK (severity IN ('warning', 'critical')),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (scope, subject, hour_start)
+);
+
+CREATE INDEX IF NOT EXISTS idx_cost_anomalies_hour ON cost_anomalies(hour_start DESC);
+
+CREATE TABLE IF NOT EXISTS cost_forecasts (
+    department TEXT NOT NULL,
+    month_start DATE NOT NULL,
+    spent_microdollars BIGINT NOT NULL,
+    daily_rate_microdollars BIGINT NOT NULL,
+    forecast_microdollars BIGINT NOT NULL,
+    previous_month_microdollars BIGINT NOT NULL DEFAULT 0,
+    computed_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (department, month_start)
+);
+
+CREATE OR REPLACE FUNCTION audit_event_notify_cost_anomaly()
+RETURNS TRIGGER AS $$
+BEGIN
+    BEGIN
+        PERFORM pg_notify('audit_events', json_build_object(
+            'table',              'cost_anomalies',
+            'id',                 NEW.id,
+            'scope',              NEW.scope,
+            'subject',            NEW.subject,
+            'hour_start',         NEW.hour_start,
+            'spend_microdollars', NEW.spend_microdollars,
+            'ratio',              NEW.ratio,
+            'severity',           NEW.severity,
+            'created_at',         NEW.created_at
+        )::text);
+    EXCEPTION WHEN OTHERS THEN
+        RAISE WARNING 'audit_event_notify_cost_anomaly failed: % (id=%)', SQLERRM, NEW.id;
+    END;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE TRIGGER audit_event_notify_cost_anomaly_trg
+    AFTER INSERT ON cost_anomalies
+    FOR EACH ROW
+    EXECUTE FUNCTION audit_event_notify_cost_anomaly();
@@ -0,0 +1,90 @@
+-- Monthly chargeback statements and closed periods.
+--
+-- An open month's statement is computed live from ai_requests. Closing a
+-- month snapshots its lines here, and from then on the statement is read
+-- from the snapshot: later pricing fixes, department moves, cost-center
+-- edits or retention purges no longer change what was invoiced.
+--
+-- Closing writes every line first and the period row last, in one
+-- transaction. The guard below rejects a line for a month whose period row
+-- already exists and any update or delete of either table, so a closed month
+-- cannot be reopened or edited short of dropping the trigger.
+--
+-- The one exception is an erasure. A transaction that has set
+-- `systemprompt.erasure_pseudonym` may replace a line's user id, or the
+-- period's `closed_by`, with that pseudonym and change nothing else, so the
+-- raw id of an erased user does not survive in a closed month while its
+-- figures stay as invoiced.
+
+CREATE TABLE IF NOT EXISTS chargeback_lines (
+    month_start DATE NOT NULL CHECK (EXTRACT(DAY FROM month_start) = 1),
+    department TEXT NOT NULL,
+    cost_center TEXT NOT NULL DEFAULT '',
+    user_id TEXT NOT NULL,
+    provider TEXT NOT NULL,
+    model TEXT NOT NULL,
+    requests BIGINT NOT NULL,
+    input_tokens BIGINT NOT NULL,
+    output_tokens BIGINT NOT NULL,
+    gross_microdollars BIGINT NOT NULL,
+    credit_microdollars BIGINT NOT NULL,
+    PRIMARY KEY (month_start, department, cost_center, user_id, provider, model)
+);
+
+CREATE TABLE IF NOT EXISTS chargeback_periods (
+    month_start DATE PRIMARY KEY CHECK (EXTRACT(DAY FROM month_start) = 1),
+    closed_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    closed_by TEXT NOT NULL,
+    gross_microdollars BIGINT NOT NULL,
+    credit_microdollars BIGINT NOT NULL
+);
+
+CREATE OR REPLACE FUNCTION chargeback_closed_guard()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP = 'INSERT' AND TG_TABLE_NAME = 'chargeback_lines' THEN
+        IF EXISTS (SELECT 1 FROM chargeback_periods WHERE month_start = NEW.month_start) THEN
+            RAISE EXCEPTION 'chargeback period % is closed', NEW.month_start;
+        END IF;
+        RETURN NEW;
+    END IF;
+    -- PL/pgSQL does not short-circuit AND, so each table's fields are only
+    -- named inside the branch for that table.
+    IF TG_OP = 'UPDATE'
+        AND COALESCE(current_setting('systemprompt.erasure_pseudonym', true), '') <> ''
+    THEN
+        IF TG_TABLE_NAME = 'chargeback_lines' THEN
+            IF NEW.user_id = current_setting('systemprompt.erasure_pseudonym', true)
+                AND NEW.user_id <> OLD.user_id
+                AND (NEW.month_start, NEW.department, NEW.cost_center, NEW.provider, NEW.model,
+                     NEW.requests, NEW.input_tokens, NEW.output_tokens,
+                     NEW.gross_microdollars, NEW.credit_microdollars)
+                    IS NOT DISTINCT FROM
+                    (OLD.month_start, OLD.department, OLD.cost_center, OLD.provider, OLD.model,
+                     OLD.requests, OLD.input_tokens, OLD.output_tokens,
+                     OLD.gross_microdollars, OLD.credit_microdollars)
+            THEN
+                RETURN NEW;
+            END IF;
+        ELSIF NEW.closed_by = current_setting('systemprompt.erasure_pseudonym', true)
+            AND NEW.closed_by <> OLD.closed_by
+            AND (NEW.month_start, NEW.closed_at, NEW.gross_microdollars, NEW.credit_microdollars)
+                IS NOT DISTINCT FROM
+                (OLD.month_start, OLD.closed_at, OLD.gross_microdollars, OLD.credit_microdollars)
+        THEN
+            RETURN NEW;
+        END IF;
+    END IF;
+    RAISE EXCEPTION 'closed chargeback periods are immutable (% on %)', TG_OP, TG_TABLE_NAME;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE TRIGGER chargeback_lines_guard_trg
+    BEFORE INSERT OR UPDATE OR DELETE ON chargeback_lines
+    FOR EACH ROW
+    EXECUTE FUNCTION chargeback_closed_guard();
+
+CREATE OR REPLACE TRIGGER chargeback_periods_guard_trg
+    BEFORE UPDATE OR DELETE ON chargeback_periods
+    FOR EACH ROW
+    EXECUTE FUNCTION chargeback_closed_guard();
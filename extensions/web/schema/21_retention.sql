@@ -0,0 +1,16 @@
+-- What the `data_retention` job last purged, per data class.
+--
+-- One row per class named in `services/governance/retention.yaml`.
+-- `last_cutoff` is the age boundary the last run enforced: everything in the
+-- class older than it, bar rows under legal hold, is gone or stripped.
+-- `total_purged` accumulates across runs so the retention page can show what
+-- a policy has removed since it was introduced, which the DPO asks for.
+
+CREATE TABLE IF NOT EXISTS retention_purges (
+    data_class TEXT PRIMARY KEY,
+    last_run_at TIMESTAMPTZ NOT NULL,
+    last_cutoff TIMESTAMPTZ NOT NULL,
+    last_purged BIGINT NOT NULL DEFAULT 0,
+    total_purged BIGINT NOT NULL DEFAULT 0,
+    last_error TEXT
+);
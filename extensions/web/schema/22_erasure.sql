@@ -0,0 +1,18 @@
+-- Certificates issued when a user's data is erased on request.
+--
+-- One row per completed erasure. The erased user id is never stored:
+-- `subject_hash` is the same keyed hash that replaced the id on the rows kept
+-- for audit, so a certificate can be matched to those rows but not back to
+-- the person. `tables` lists every table touched with what was done to it
+-- (`deleted`, `pseudonymized` or `retained`) and how many rows, as the
+-- evidence handed to the data subject or the regulator.
+
+CREATE TABLE IF NOT EXISTS erasure_certificates (
+    id TEXT PRIMARY KEY,
+    subject_hash TEXT NOT NULL,
+    requested_by TEXT NOT NULL,
+    reason TEXT NOT NULL,
+    tables JSONB NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+CREATE INDEX IF NOT EXISTS idx_erasure_certificates_created ON erasure_certificates(created_at DESC);
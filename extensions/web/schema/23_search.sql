@@ -0,0 +1,37 @@
+-- Full-text indexes behind `/admin/search`.
+--
+-- Expression indexes on the rows themselves rather than a copy: nothing has
+-- to keep a search table in step, and whatever retention or an erasure
+-- removes stops being searchable in the same statement. The `simple`
+-- configuration does no stemming or stop-word removal, because the question
+-- is usually "who pasted this exact string", not a topic search.
+--
+-- A tsvector cannot exceed 1 MB, and an index over an unbounded body would
+-- make the INSERT of one oversized message or transcript fail. Every indexed
+-- body therefore goes through `search_tsvector`, which reads only its first
+-- 100,000 characters; at worst a lexeme costs about five bytes per character,
+-- so that stays well under the limit. A match past that point is not found.
+-- The search query calls the same functions so the planner uses the indexes.
+--
+-- Transcripts are indexed on the text of their string values; the search
+-- narrows to the matching turns after the index has picked the transcripts.
+
+CREATE OR REPLACE FUNCTION search_tsvector(body TEXT)
+RETURNS tsvector AS $$
+    SELECT to_tsvector('simple', left(COALESCE(body, ''), 100000))
+$$ LANGUAGE sql IMMUTABLE PARALLEL SAFE;
+
+CREATE OR REPLACE FUNCTION transcript_search_text(transcript JSONB)
+RETURNS TEXT AS $$
+    SELECT string_agg(s #>> '{}', ' ')
+    FROM jsonb_path_query(transcript, 'strict $.** ? (@.type() == "string")') s
+$$ LANGUAGE sql IMMUTABLE PARALLEL SAFE;
+
+CREATE INDEX IF NOT EXISTS idx_session_transcripts_search
+    ON session_transcripts USING GIN (search_tsvector(transcript_search_text(transcript)));
+
+CREATE INDEX IF NOT EXISTS idx_ai_request_messages_search
+    ON ai_request_messages USING GIN (search_tsvector(content));
+
+CREATE INDEX IF NOT EXISTS idx_session_analyses_title_fts
+    ON session_analyses USING GIN (to_tsvector('simple', title));
@@ -0,0 +1,19 @@
+-- What the `digest_reports` job has delivered, per subscription, period and
+-- channel.
+--
+-- Subscriptions live in `services/reports/digests.yaml`; a row here is the
+-- job's memory of one digest. A period is sent once: the job skips any
+-- (subscription, period, channel) with `delivered_at` set, and retries a
+-- failed one on each run until `attempts` reaches the configured limit, so a
+-- mail server outage delays a digest rather than losing or repeating it.
+
+CREATE TABLE IF NOT EXISTS digest_deliveries (
+    subscription_id TEXT NOT NULL,
+    period_start TIMESTAMPTZ NOT NULL,
+    channel TEXT NOT NULL CHECK (channel IN ('email', 'webhook')),
+    attempts INTEGER NOT NULL DEFAULT 0,
+    delivered_at TIMESTAMPTZ,
+    last_error TEXT,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (subscription_id, period_start, channel)
+);
@@ -0,0 +1,8 @@
+-- Index behind `/admin/analytics/cohorts`.
+--
+-- The cohort page reads every session analysed in a date range, across all
+-- users; the existing `(user_id, created_at)` index only serves one user's
+-- history, so without this a range is a scan of the whole table.
+
+CREATE INDEX IF NOT EXISTS idx_session_analyses_created
+    ON session_analyses (created_at DESC);
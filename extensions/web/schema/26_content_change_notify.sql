@@ -0,0 +1,28 @@
+-- Content-change NOTIFY trigger
+--
+-- Fires `pg_notify('content_changes', 'markdown_content')` once per statement
+-- that inserts, updates or deletes `markdown_content` rows. The bundled MCP
+-- server LISTENs on the channel and tells its connected clients their
+-- resource list changed, so content ingestion reaches MCP clients without
+-- them polling. Statement level rather than row level: an ingestion run
+-- touches many rows, and clients only need to hear that something changed.
+--
+-- As with the audit-event triggers, a notification failure is downgraded to a
+-- WARNING so it can never roll back the ingestion write itself.
+
+CREATE OR REPLACE FUNCTION content_change_notify()
+RETURNS TRIGGER AS $$
+BEGIN
+    BEGIN
+        PERFORM pg_notify('content_changes', TG_TABLE_NAME);
+    EXCEPTION WHEN OTHERS THEN
+        RAISE WARNING 'content_change_notify failed: % (table=%)', SQLERRM, TG_TABLE_NAME;
+    END;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE TRIGGER content_change_notify_trg
+    AFTER INSERT OR UPDATE OR DELETE ON markdown_content
+    FOR EACH STATEMENT
+    EXECUTE FUNCTION content_change_notify();
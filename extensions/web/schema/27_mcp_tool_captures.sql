@@ -0,0 +1,34 @@
+-- What each MCP tool call was asked and answered, for investigations.
+--
+-- One row per call a bundled or proxied MCP server ran, written after the
+-- call returns. `arguments` and `result_excerpt` have been through the
+-- credential redactor and cut to the server's `tool_capture` limits before
+-- they reach this table; `redactions` counts the credentials replaced and
+-- `truncated` says whether either was cut. A server or tool opted out of
+-- capture still gets its row, with both left NULL, so the call keeps its
+-- place on the trace.
+--
+-- `session_id` and `trace_id` come from the caller's request context, which
+-- is how the Trace Explorer places the call among the session's governance
+-- decisions and model requests. Rows expire under the `mcp_tool_captures`
+-- class in services/governance/retention.yaml, counted from `ended_at`.
+
+CREATE TABLE IF NOT EXISTS mcp_tool_captures (
+    id TEXT PRIMARY KEY,
+    user_id TEXT NOT NULL,
+    session_id TEXT NOT NULL,
+    trace_id TEXT NOT NULL,
+    server TEXT NOT NULL,
+    tool_name TEXT NOT NULL,
+    status TEXT NOT NULL CHECK (status IN ('ok', 'error')),
+    arguments TEXT,
+    result_excerpt TEXT,
+    truncated BOOLEAN NOT NULL DEFAULT FALSE,
+    redactions INTEGER NOT NULL DEFAULT 0,
+    started_at TIMESTAMPTZ NOT NULL,
+    ended_at TIMESTAMPTZ NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_mcp_tool_captures_session ON mcp_tool_captures(session_id, started_at);
+CREATE INDEX IF NOT EXISTS idx_mcp_tool_captures_trace ON mcp_tool_captures(trace_id);
+CREATE INDEX IF NOT EXISTS idx_mcp_tool_captures_user ON mcp_tool_captures(user_id, started_at DESC);
+CREATE INDEX IF NOT EXISTS idx_mcp_tool_captures_ended ON mcp_tool_captures(ended_at);
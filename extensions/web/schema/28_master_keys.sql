@@ -0,0 +1,88 @@
+-- Master key registry and rotation audit
+--
+-- `master_keys` records every master key the instance has wrapped data
+-- encryption keys under, by fingerprint id. `user_encryption_keys.master_key_id`
+-- names the key that wrapped each DEK. When the configured key changes, the
+-- `master_key_rotation` job re-wraps every DEK under the new one in batches,
+-- counting progress in `master_key_rotations`, and retires the old keys once
+-- no DEK depends on them.
+--
+-- `master_key_events` is the operation's audit trail. Each row is also sent
+-- on the `audit_events` NOTIFY channel, best-effort, as the triggers in
+-- 14_audit_event_notify.sql do for their tables.
+
+CREATE TABLE IF NOT EXISTS master_keys (
+    key_id TEXT PRIMARY KEY,
+    status TEXT NOT NULL DEFAULT 'active' CHECK (status IN ('active', 'retired')),
+    first_seen_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    retired_at TIMESTAMPTZ
+);
+
+CREATE TABLE IF NOT EXISTS master_key_rotations (
+    id TEXT PRIMARY KEY,
+    target_key_id TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'running' CHECK (status IN ('running', 'completed')),
+    total_deks INTEGER NOT NULL,
+    rewrapped INTEGER NOT NULL DEFAULT 0,
+    failed INTEGER NOT NULL DEFAULT 0,
+    last_error TEXT,
+    started_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    completed_at TIMESTAMPTZ
+);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_master_key_rotations_running
+    ON master_key_rotations(target_key_id) WHERE status = 'running';
+
+CREATE TABLE IF NOT EXISTS master_key_events (
+    id TEXT PRIMARY KEY,
+    key_id TEXT NOT NULL,
+    rotation_id TEXT,
+    event TEXT NOT NULL CHECK (event IN (
+        'registered', 'rotation_started', 'batch_rewrapped', 'rewrap_failed',
+        'rotation_completed', 'retired', 'rotation_overdue'
+    )),
+    detail JSONB NOT NULL DEFAULT '{}'::jsonb,
+    actor_id TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+CREATE INDEX IF NOT EXISTS idx_master_key_events_key_created ON master_key_events(key_id, created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_master_key_events_rotation ON master_key_events(rotation_id);
+
+CREATE OR REPLACE FUNCTION audit_event_notify_master_key_events()
+RETURNS TRIGGER AS $$
+DECLARE
+    sev     TEXT;
+    payload TEXT;
+BEGIN
+    BEGIN
+        IF NEW.event = 'rewrap_failed' THEN
+            sev := 'error';
+        ELSIF NEW.event = 'rotation_overdue' THEN
+            sev := 'warn';
+        ELSE
+            sev := 'info';
+        END IF;
+
+        payload := json_build_object(
+            'table',       'master_key_events',
+            'id',          NEW.id,
+            'key_id',      NEW.key_id,
+            'rotation_id', NEW.rotation_id,
+            'event',       NEW.event,
+            'severity',    sev,
+            'created_at',  NEW.created_at
+        )::text;
+
+        PERFORM pg_notify('audit_events', payload);
+    EXCEPTION WHEN OTHERS THEN
+        RAISE WARNING 'audit_event_notify_master_key_events failed: % (id=%, key=%)',
+            SQLERRM, NEW.id, NEW.key_id;
+    END;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE TRIGGER audit_event_notify_master_key_events_trg
+    AFTER INSERT ON master_key_events
+    FOR EACH ROW
+    EXECUTE FUNCTION audit_event_notify_master_key_events();
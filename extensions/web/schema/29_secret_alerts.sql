@@ -0,0 +1,72 @@
+-- Secret lifecycle alerts
+--
+-- `plugin_env_vars` carries each secret's owner, expiry and the last time a
+-- plugin resolved it. The hourly `secret_lifecycle` job, and the resolve path
+-- itself, record what needs a person's attention here:
+--
+-- * `expiring` / `expired` — the secret's `expires_at` is near or past;
+-- * `unused` — no plugin has resolved it for the configured number of days,
+--   so it is a candidate for removal;
+-- * `unusual_plugin` / `unusual_rate` — a resolution came from a plugin the
+--   user has not resolved from before, or far above their usual rate.
+--
+-- `dedupe_key` makes a repeat of the same finding a no-op, so the job can
+-- rescan freely and the notify trigger fires once per alert. Each row is also
+-- sent on the `audit_events` NOTIFY channel, best-effort, as the triggers in
+-- 14_audit_event_notify.sql do for their tables.
+
+CREATE TABLE IF NOT EXISTS secret_alerts (
+    id TEXT PRIMARY KEY,
+    user_id TEXT NOT NULL,
+    plugin_id TEXT NOT NULL,
+    var_name TEXT NOT NULL,
+    owner_id TEXT,
+    kind TEXT NOT NULL CHECK (kind IN (
+        'expiring', 'expired', 'unused', 'unusual_plugin', 'unusual_rate'
+    )),
+    dedupe_key TEXT NOT NULL UNIQUE,
+    detail JSONB NOT NULL DEFAULT '{}'::jsonb,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+CREATE INDEX IF NOT EXISTS idx_secret_alerts_user_created ON secret_alerts(user_id, created_at DESC);
+
+CREATE OR REPLACE FUNCTION audit_event_notify_secret_alerts()
+RETURNS TRIGGER AS $$
+DECLARE
+    sev     TEXT;
+    payload TEXT;
+BEGIN
+    BEGIN
+        IF NEW.kind = 'expired' THEN
+            sev := 'error';
+        ELSIF NEW.kind = 'unused' THEN
+            sev := 'info';
+        ELSE
+            sev := 'warn';
+        END IF;
+
+        payload := json_build_object(
+            'table',      'secret_alerts',
+            'id',         NEW.id,
+            'user_id',    NEW.user_id,
+            'plugin_id',  NEW.plugin_id,
+            'var_name',   NEW.var_name,
+            'owner_id',   NEW.owner_id,
+            'event',      NEW.kind,
+            'severity',   sev,
+            'created_at', NEW.created_at
+        )::text;
+
+        PERFORM pg_notify('audit_events', payload);
+    EXCEPTION WHEN OTHERS THEN
+        RAISE WARNING 'audit_event_notify_secret_alerts failed: % (id=%, kind=%)',
+            SQLERRM, NEW.id, NEW.kind;
+    END;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE TRIGGER audit_event_notify_secret_alerts_trg
+    AFTER INSERT ON secret_alerts
+    FOR EACH ROW
+    EXECUTE FUNCTION audit_event_notify_secret_alerts();
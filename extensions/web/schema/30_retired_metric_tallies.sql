@@ -0,0 +1,43 @@
+-- Retired metric tallies
+--
+-- The `/metrics` counters are aggregates over `ai_requests`,
+-- `governance_decisions` and `ai_safety_findings`. Retention deletes old rows
+-- from those tables, which on its own would make every counter drop. The
+-- purge statements instead fold what they delete into these tables, in the
+-- same statement, and the metrics queries add them back, so the exposed
+-- totals only ever grow.
+--
+-- Label columns are NOT NULL so they can form the primary key: an absent
+-- provider, model or route is stored as '' and an absent latency bucket as
+-- -1, and the metrics queries map both back to NULL.
+
+CREATE TABLE IF NOT EXISTS retired_gateway_request_tallies (
+    provider TEXT NOT NULL DEFAULT '',
+    model TEXT NOT NULL DEFAULT '',
+    route TEXT NOT NULL DEFAULT '',
+    status TEXT NOT NULL,
+    bucket INTEGER NOT NULL DEFAULT -1,
+    requests BIGINT NOT NULL DEFAULT 0,
+    latency_ms BIGINT NOT NULL DEFAULT 0,
+    input_tokens BIGINT NOT NULL DEFAULT 0,
+    output_tokens BIGINT NOT NULL DEFAULT 0,
+    cache_read_tokens BIGINT NOT NULL DEFAULT 0,
+    cost_microdollars BIGINT NOT NULL DEFAULT 0,
+    PRIMARY KEY (provider, model, route, status, bucket)
+);
+
+CREATE TABLE IF NOT EXISTS retired_governance_decision_tallies (
+    policy TEXT NOT NULL,
+    decision TEXT NOT NULL,
+    decisions BIGINT NOT NULL DEFAULT 0,
+    PRIMARY KEY (policy, decision)
+);
+
+CREATE TABLE IF NOT EXISTS retired_safety_finding_tallies (
+    scanner TEXT NOT NULL,
+    category TEXT NOT NULL,
+    severity TEXT NOT NULL,
+    phase TEXT NOT NULL,
+    findings BIGINT NOT NULL DEFAULT 0,
+    PRIMARY KEY (scanner, category, severity, phase)
+);
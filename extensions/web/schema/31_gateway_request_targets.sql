@@ -0,0 +1,84 @@
+-- Which gateway target answered which request, and the upstream failures
+-- the gateway retried past.
+--
+-- The target is chosen, and on an upstream failure retried, inside the
+-- outbound adapter, which never learns the request id. It records the
+-- upstream's response id against the target instead; core later stores the
+-- reply, carrying that id, on the request's payload row, and the trigger
+-- below moves the target onto `gateway_request_targets` for that request. A
+-- pending row is consumed once bound, and rows left unbound (a reply core
+-- never stored) are swept by the adapter after an hour.
+
+CREATE TABLE IF NOT EXISTS gateway_target_responses (
+    response_id TEXT PRIMARY KEY,
+    route_id TEXT NOT NULL,
+    target_id TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+CREATE INDEX IF NOT EXISTS idx_gateway_target_responses_created
+    ON gateway_target_responses(created_at);
+
+-- The target that served each request on a route with targets. The
+-- `ai_requests` row keeps the provider and model the selector chose, which a
+-- retry onto another target no longer matches.
+CREATE TABLE IF NOT EXISTS gateway_request_targets (
+    ai_request_id TEXT PRIMARY KEY REFERENCES ai_requests(id) ON DELETE CASCADE,
+    route_id TEXT NOT NULL,
+    target_id TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+CREATE INDEX IF NOT EXISTS idx_gateway_request_targets_target
+    ON gateway_request_targets(route_id, target_id);
+
+-- A retryable failure on one target that another target then answered, or
+-- the retry's own failure. Core records only the error it is handed, the
+-- first target's, so these are the failures target health would otherwise
+-- never see. Rows older than a day are swept by the adapter.
+CREATE TABLE IF NOT EXISTS gateway_target_failures (
+    id BIGSERIAL PRIMARY KEY,
+    provider TEXT NOT NULL,
+    model TEXT NOT NULL,
+    error_message TEXT NOT NULL,
+    failed_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+CREATE INDEX IF NOT EXISTS idx_gateway_target_failures_failed
+    ON gateway_target_failures(failed_at);
+
+-- The response id as the caller's wire rendered it: the top-level `id` on
+-- every wire but Gemini's `responseId`, and in a streamed or truncated reply
+-- the first such key, which is the opening event's.
+CREATE OR REPLACE FUNCTION gateway_stored_response_id(body JSONB, excerpt TEXT)
+RETURNS TEXT AS $$
+    SELECT COALESCE(
+        body->>'responseId',
+        body->>'id',
+        substring(excerpt FROM '"responseId"\s*:\s*"([^"]+)"'),
+        substring(excerpt FROM '"id"\s*:\s*"([^"]+)"')
+    );
+$$ LANGUAGE sql IMMUTABLE;
+
+CREATE OR REPLACE FUNCTION gateway_target_bind_response()
+RETURNS TRIGGER AS $$
+DECLARE
+    response TEXT := gateway_stored_response_id(NEW.response_body, NEW.response_excerpt);
+    marked INT;
+BEGIN
+    IF response IS NULL THEN
+        RETURN NEW;
+    END IF;
+    WITH bound AS (DELETE FROM gateway_target_responses
+                   WHERE response_id = response
+                   RETURNING route_id, target_id),
+    stamped AS (INSERT INTO gateway_request_targets (ai_request_id, route_id, target_id)
+                SELECT NEW.ai_request_id, route_id, target_id FROM bound
+                ON CONFLICT (ai_request_id) DO NOTHING
+                RETURNING ai_request_id)
+    SELECT COUNT(*) INTO marked FROM stamped;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE TRIGGER gateway_target_bind_response_trg
+    AFTER INSERT OR UPDATE OF response_body, response_excerpt ON ai_request_payloads
+    FOR EACH ROW
+    EXECUTE FUNCTION gateway_target_bind_response();
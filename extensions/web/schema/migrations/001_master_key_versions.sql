@@ -0,0 +1,9 @@
+-- Record which master key wrapped each data encryption key.
+--
+-- DEKs issued before master keys were versioned keep a NULL id. They are
+-- unwrapped by trying each configured key, and the `master_key_rotation` job
+-- re-wraps them under the current key like any other DEK that is not on it.
+
+ALTER TABLE user_encryption_keys ADD COLUMN IF NOT EXISTS master_key_id TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_user_encryption_keys_master_key ON user_encryption_keys(master_key_id);
@@ -0,0 +1,22 @@
+-- Give each plugin secret an owner, an expiry, and a last-resolved time.
+--
+-- Existing secrets get no owner and no expiry. Their last-resolved time is
+-- taken from the newest `accessed` row in the secret audit log for the same
+-- user and plugin, since a resolution reads every secret of the plugin.
+
+ALTER TABLE plugin_env_vars ADD COLUMN IF NOT EXISTS owner_id TEXT;
+ALTER TABLE plugin_env_vars ADD COLUMN IF NOT EXISTS expires_at TIMESTAMPTZ;
+ALTER TABLE plugin_env_vars ADD COLUMN IF NOT EXISTS last_resolved_at TIMESTAMPTZ;
+
+UPDATE plugin_env_vars v SET last_resolved_at = a.last_accessed
+FROM (
+    SELECT user_id, plugin_id, MAX(created_at) AS last_accessed
+    FROM secret_audit_log
+    WHERE action = 'accessed'
+    GROUP BY user_id, plugin_id
+) a
+WHERE v.user_id = a.user_id AND v.plugin_id = a.plugin_id
+  AND v.is_secret AND v.last_resolved_at IS NULL;
+
+CREATE INDEX IF NOT EXISTS idx_plugin_env_vars_expires ON plugin_env_vars(expires_at)
+    WHERE expires_at IS NOT NULL;
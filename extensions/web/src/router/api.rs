@@ -1,4 +1,4 @@
-//! JSON API, webhook, and secrets routes.
+//! JSON API, webhook, secrets, and metrics routes.
 
 use std::sync::Arc;
 
@@ -32,3 +32,11 @@ pub(crate) fn build(db: &DbHandles, session_service: &Arc<SessionCreationService
 pub(crate) fn share(db: &DbHandles) -> Router {
     admin::share_manifest_router(Arc::clone(&db.read))
 }
+
+pub(crate) fn scim(db: &DbHandles) -> Router {
+    admin::scim_router(Arc::clone(&db.write))
+}
+
+pub(crate) fn metrics(db: &DbHandles) -> Router {
+    admin::metrics_router(Arc::clone(&db.write))
+}
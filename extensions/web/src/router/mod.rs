@@ -11,6 +11,8 @@ mod admin_ssr;
 mod api;
 mod pools;
 
+use std::sync::Arc;
+
 use axum::Router;
 
 use systemprompt::extension::prelude::{ExtensionContext, ExtensionRouter};
@@ -20,12 +22,18 @@ use pools::DbHandles;
 pub(crate) fn build(ctx: &dyn ExtensionContext) -> Option<ExtensionRouter> {
     let db = DbHandles::from_context(ctx)?;
     let session_service = pools::build_session_service(&db)?;
+    crate::admin::gateway_routing::install(Arc::clone(&db.read));
+    crate::admin::gateway_cache::install(Arc::clone(&db.write));
 
     let api_router = api::build(&db, &session_service);
     let share_api = api::share(&db);
+    let scim_api = api::scim(&db);
+    let metrics_api = api::metrics(&db);
 
     let mut combined = Router::new()
         .merge(share_api)
+        .merge(scim_api)
+        .merge(metrics_api)
         .nest("/api/public", api_router);
 
     match admin_ssr::build(&db) {
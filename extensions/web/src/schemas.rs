@@ -14,6 +14,29 @@ pub(crate) const SCHEMA_MANAGEMENT: &str = include_str!("../schema/12_management
 pub(crate) const SCHEMA_WEB_SIDE_TABLES: &str = include_str!("../schema/13_web_side_tables.sql");
 pub(crate) const SCHEMA_AUDIT_EVENT_NOTIFY: &str =
     include_str!("../schema/14_audit_event_notify.sql");
+pub(crate) const SCHEMA_SCIM: &str = include_str!("../schema/15_scim.sql");
+pub(crate) const SCHEMA_GATEWAY_REVISIONS: &str =
+    include_str!("../schema/16_gateway_config_revisions.sql");
+pub(crate) const SCHEMA_GATEWAY_RESPONSE_CACHE: &str =
+    include_str!("../schema/17_gateway_response_cache.sql");
+pub(crate) const SCHEMA_EXPORT_CURSORS: &str = include_str!("../schema/18_export_cursors.sql");
+pub(crate) const SCHEMA_COST_WATCH: &str = include_str!("../schema/19_cost_watch.sql");
+pub(crate) const SCHEMA_CHARGEBACK: &str = include_str!("../schema/20_chargeback.sql");
+pub(crate) const SCHEMA_RETENTION: &str = include_str!("../schema/21_retention.sql");
+pub(crate) const SCHEMA_ERASURE: &str = include_str!("../schema/22_erasure.sql");
+pub(crate) const SCHEMA_SEARCH: &str = include_str!("../schema/23_search.sql");
+pub(crate) const SCHEMA_DIGESTS: &str = include_str!("../schema/24_digests.sql");
+pub(crate) const SCHEMA_COHORTS: &str = include_str!("../schema/25_cohorts.sql");
+pub(crate) const SCHEMA_CONTENT_CHANGE_NOTIFY: &str =
+    include_str!("../schema/26_content_change_notify.sql");
+pub(crate) const SCHEMA_MCP_TOOL_CAPTURES: &str =
+    include_str!("../schema/27_mcp_tool_captures.sql");
+pub(crate) const SCHEMA_MASTER_KEYS: &str = include_str!("../schema/28_master_keys.sql");
+pub(crate) const SCHEMA_SECRET_ALERTS: &str = include_str!("../schema/29_secret_alerts.sql");
+pub(crate) const SCHEMA_RETIRED_METRIC_TALLIES: &str =
+    include_str!("../schema/30_retired_metric_tallies.sql");
+pub(crate) const SCHEMA_GATEWAY_REQUEST_TARGETS: &str =
+    include_str!("../schema/31_gateway_request_targets.sql");
 
 pub fn schema_definitions() -> Vec<SchemaDefinition> {
     vec![
@@ -24,9 +47,26 @@ pub fn schema_definitions() -> Vec<SchemaDefinition> {
         SchemaDefinition::new("", SCHEMA_MANAGEMENT),
         SchemaDefinition::new("", SCHEMA_WEB_SIDE_TABLES),
         SchemaDefinition::new("", SCHEMA_AUDIT_EVENT_NOTIFY),
+        SchemaDefinition::new("", SCHEMA_SCIM),
+        SchemaDefinition::new("", SCHEMA_GATEWAY_REVISIONS),
+        SchemaDefinition::new("", SCHEMA_GATEWAY_RESPONSE_CACHE),
+        SchemaDefinition::new("", SCHEMA_EXPORT_CURSORS),
+        SchemaDefinition::new("", SCHEMA_COST_WATCH),
+        SchemaDefinition::new("", SCHEMA_CHARGEBACK),
+        SchemaDefinition::new("", SCHEMA_RETENTION),
+        SchemaDefinition::new("", SCHEMA_ERASURE),
+        SchemaDefinition::new("", SCHEMA_SEARCH),
+        SchemaDefinition::new("", SCHEMA_DIGESTS),
+        SchemaDefinition::new("", SCHEMA_COHORTS),
+        SchemaDefinition::new("", SCHEMA_CONTENT_CHANGE_NOTIFY),
+        SchemaDefinition::new("", SCHEMA_MCP_TOOL_CAPTURES),
+        SchemaDefinition::new("", SCHEMA_MASTER_KEYS),
+        SchemaDefinition::new("", SCHEMA_SECRET_ALERTS),
+        SchemaDefinition::new("", SCHEMA_RETIRED_METRIC_TALLIES),
+        SchemaDefinition::new("", SCHEMA_GATEWAY_REQUEST_TARGETS),
     ]
 }
 
-pub const fn migrations() -> Vec<Migration> {
+pub fn migrations() -> Vec<Migration> {
     extension_migrations!()
 }
@@ -0,0 +1,29 @@
+import { apiFetch } from '../services/api.js';
+import { showToast } from '../services/toast.js';
+import { showConfirmDialog } from '../services/confirm.js';
+import { on } from '../services/events.js';
+
+const close = async (month, label, btn) => {
+  btn.disabled = true;
+  try {
+    await apiFetch(`/chargeback/${month}/close`, { method: 'POST' });
+    showToast(`${label} closed`, 'success');
+    window.location.assign(`/admin/chargeback?month=${month}`);
+  } catch {
+    btn.disabled = false;
+  }
+};
+
+on('click', '[data-action="close-period"]', (e, btn) => {
+  const { month, monthLabel } = btn.dataset;
+  if (!month) return;
+  showConfirmDialog(
+    `Close ${monthLabel}?`,
+    'Every department\'s statement for the month is frozen as it stands now. A closed month cannot be reopened or edited.',
+    'Close month',
+    () => close(month, monthLabel, btn),
+    { btnClass: 'btn-primary' }
+  );
+});
+
+on('click', '[data-action="print-statement"]', () => window.print());
@@ -0,0 +1,37 @@
+import { apiFetch } from '../services/api.js';
+import { showToast } from '../services/toast.js';
+import { showConfirmDialog } from '../services/confirm.js';
+import { on } from '../services/events.js';
+
+const root = () => document.querySelector('[data-page="gateway-history"]');
+
+const rollback = async (id, etag, btn) => {
+  btn.disabled = true;
+  try {
+    await apiFetch(`/gateway/revisions/${id}/rollback`, {
+      method: 'POST',
+      headers: { 'Content-Type': 'application/json', 'If-Match': `"${etag}"` }
+    });
+    showToast(`Rolled back to revision #${id}`, 'success');
+    window.location.assign('/admin/gateway/history');
+  } catch {
+    btn.disabled = false;
+  }
+};
+
+on('click', '[data-action="rollback"]', (e, btn) => {
+  const id = btn.dataset.revisionId;
+  const etag = root()?.dataset.etag;
+  if (!id) return;
+  if (!etag) {
+    showToast('The current configuration version is unknown; reload the page and try again', 'error');
+    return;
+  }
+  showConfirmDialog(
+    `Roll back to revision #${id}?`,
+    'The gateway routes and targets are restored to this revision. The current state stays in the history.',
+    'Roll back',
+    () => rollback(id, etag, btn),
+    { btnClass: 'btn-primary' }
+  );
+});
@@ -43,3 +43,82 @@ on('click', '[data-action="enable-model"]', async (e, btn) => {
     btn.disabled = false;
   }
 });
+
+const targetsModal = () => document.getElementById('targets-modal');
+let editing = null;
+
+const showTargetsError = (message) => {
+  const err = document.getElementById('targets-error');
+  err.textContent = message;
+  err.hidden = !message;
+};
+
+const addTargetRow = (target = {}) => {
+  const row = document.getElementById('targets-row-template').content.firstElementChild.cloneNode(true);
+  row.querySelector('[data-field="id"]').value = target.id || '';
+  row.querySelector('[data-field="provider"]').value = target.provider || '';
+  row.querySelector('[data-field="upstream_model"]').value = target.upstream_model || '';
+  row.querySelector('[data-field="weight"]').value = String(target.weight ?? 0);
+  document.getElementById('targets-rows').append(row);
+};
+
+const readTargetRows = () => [...document.querySelectorAll('#targets-rows tr')].map((row) => {
+  const value = (field) => row.querySelector(`[data-field="${field}"]`).value.trim();
+  const upstream = value('upstream_model');
+  return {
+    id: value('id'),
+    provider: value('provider'),
+    ...(upstream ? { upstream_model: upstream } : {}),
+    weight: Number.parseInt(value('weight'), 10) || 0
+  };
+});
+
+const closeTargets = () => {
+  editing = null;
+  document.getElementById('targets-modal-overlay').hidden = true;
+  targetsModal().hidden = true;
+};
+
+on('click', '[data-action="edit-targets"]', async (e, btn) => {
+  const routeId = btn.dataset.routeId;
+  if (!routeId) return;
+  const config = await apiFetch('/gateway').catch(() => null);
+  const index = config ? config.routes.findIndex((r) => r.id === routeId) : -1;
+  if (index < 0) return;
+  editing = { index, route: config.routes[index], etag: config.etag };
+  document.getElementById('targets-modal-route').textContent = routeId;
+  document.getElementById('targets-rows').replaceChildren();
+  for (const target of editing.route.targets || []) addTargetRow(target);
+  showTargetsError('');
+  document.getElementById('targets-modal-overlay').hidden = false;
+  targetsModal().hidden = false;
+  targetsModal().focus();
+});
+
+on('click', '[data-action="add-target"]', () => {
+  addTargetRow({ provider: editing?.route.provider, weight: 0 });
+});
+
+on('click', '[data-action="remove-target"]', (e, btn) => {
+  btn.closest('tr')?.remove();
+});
+
+on('click', '[data-action="close-targets"]', closeTargets);
+
+on('click', '[data-action="save-targets"]', async (e, btn) => {
+  if (!editing) return;
+  showTargetsError('');
+  btn.disabled = true;
+  try {
+    await apiFetch(`/gateway/routes/${editing.index}`, {
+      method: 'PATCH',
+      headers: { 'Content-Type': 'application/json', 'If-Match': `"${editing.etag}"` },
+      body: JSON.stringify({ ...editing.route, targets: readTargetRows() })
+    });
+    showToast('Route targets saved — the gateway picks them up within seconds', 'success');
+    window.location.reload();
+  } catch (err) {
+    showTargetsError(err.message || 'Failed to save targets');
+    btn.disabled = false;
+  }
+});
@@ -1,5 +1,6 @@
 import { apiFetch } from '../services/api.js';
 import { showToast } from '../services/toast.js';
+import { showConfirmDialog, showPromptDialog } from '../services/confirm.js';
 
 const form = document.getElementById('user-edit-form');
 if (form) {
@@ -34,3 +35,39 @@ if (form) {
     }
   });
 }
+
+const eraseBtn = document.getElementById('user-erase-btn');
+if (eraseBtn) {
+  const erase = (userId, reason, dryRun) => apiFetch('/users/' + encodeURIComponent(userId) + '/erasure', {
+    method: 'POST',
+    body: JSON.stringify({ reason, dry_run: dryRun }),
+  });
+  const total = (report, action) => report.tables
+    .filter((t) => t.action === action)
+    .reduce((sum, t) => sum + t.rows, 0);
+
+  eraseBtn.addEventListener('click', () => {
+    const userId = eraseBtn.dataset.userId;
+    if (!userId) return;
+    showPromptDialog('Erase user', 'Reason for the erasure certificate (for example the request ticket).', '', async (reason) => {
+      let preview;
+      try {
+        preview = await erase(userId, reason, true);
+      } catch (err) {
+        showToast(err.message || 'Failed to preview erasure', 'error');
+        return;
+      }
+      const message = total(preview, 'deleted') + ' rows will be deleted and '
+        + total(preview, 'pseudonymized') + ' pseudonymized. This cannot be undone.';
+      showConfirmDialog('Erase this user permanently?', message, 'Erase', async () => {
+        try {
+          const report = await erase(userId, reason, false);
+          showToast('User erased. Certificate ' + report.certificate_id, 'success');
+          setTimeout(() => { window.location.href = '/admin/access/users'; }, 1200);
+        } catch (err) {
+          showToast(err.message || 'Failed to erase user', 'error');
+        }
+      });
+    });
+  });
+}
@@ -72,6 +72,16 @@ fn client_errors() -> Vec<(AdminError, StatusCode, &'static str)> {
             StatusCode::CONFLICT,
             "already exists",
         ),
+        (
+            AdminError::PreconditionFailed("changed since it was loaded".to_owned()),
+            StatusCode::PRECONDITION_FAILED,
+            "changed since it was loaded",
+        ),
+        (
+            AdminError::PreconditionRequired("send If-Match".to_owned()),
+            StatusCode::PRECONDITION_REQUIRED,
+            "send If-Match",
+        ),
         (
             AdminError::RateLimited("slow down".to_owned()),
             StatusCode::TOO_MANY_REQUESTS,
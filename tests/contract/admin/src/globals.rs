@@ -90,8 +90,8 @@ fn fixture_secrets() -> String {
     )
 }
 
-// A fixed 32-byte key, hex-encoded. `secret_crypto::load_master_key` refuses
-// anything that is not exactly that, and without it every secrets route
+// A fixed 32-byte key, hex-encoded. `secret_keyring::load_master_keyring`
+// refuses anything that is not exactly that, and without it every secrets route
 // answers 500 before reaching the code the cases are about.
 const MASTER_KEY_HEX: &str = "00112233445566778899aabbccddeeff00112233445566778899aabbccddeeff";
 
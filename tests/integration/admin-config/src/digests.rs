@@ -0,0 +1,121 @@
+//! `digests` — a scoped digest counts spend, models and denials through the
+//! dashboard's repositories, and a run whose database fails still reports
+//! every subscription instead of aborting.
+
+use std::time::Duration;
+
+use sqlx::PgPool;
+use sqlx::postgres::PgPoolOptions;
+use systemprompt_web_admin::digests::{
+    DigestCadence, DigestPeriod, FileMailSink, build_digest, send_due_digests,
+};
+use systemprompt_web_admin::repositories::config::digests::{DigestConfig, DigestSubscription};
+
+use crate::fixtures::{RequestSeed, at, insert_request, insert_user, unique};
+use crate::tempdb::TempDb;
+
+fn subscription(id: &str, department: Option<&str>) -> DigestSubscription {
+    DigestSubscription {
+        id: id.to_owned(),
+        name: String::new(),
+        cadence: DigestCadence::Daily,
+        department: department.map(str::to_owned),
+        user_id: None,
+        email: vec!["digest@example.test".to_owned()],
+        webhook: None,
+    }
+}
+
+async fn insert_member(pool: &PgPool, user: &str, department: &str) {
+    insert_user(pool, user).await;
+    sqlx::query("INSERT INTO user_profile_ext (user_id, department) VALUES ($1, $2)")
+        .bind(user)
+        .bind(department)
+        .execute(pool)
+        .await
+        .expect("insert user profile");
+}
+
+async fn insert_denial(pool: &PgPool, user: &str, policy: &str) {
+    sqlx::query(
+        "INSERT INTO governance_decisions (id, user_id, session_id, tool_name, decision, policy,
+             reason, actor_kind, actor_id, context_id, created_at)
+         VALUES ($1, $2, 's', 'Bash', 'deny', $3, 'blocked', 'user', $2, 'c', $4)",
+    )
+    .bind(unique("gd"))
+    .bind(user)
+    .bind(policy)
+    .bind(at(2026, 3, 1, 12))
+    .execute(pool)
+    .await
+    .expect("insert governance decision");
+}
+
+#[tokio::test]
+async fn a_department_digest_counts_only_that_department() {
+    let Some(db) = TempDb::create().await else {
+        return;
+    };
+    let (eng, ops) = (unique("eng"), unique("ops"));
+    let department = unique("dept");
+    insert_member(&db.pool, &eng, &department).await;
+    insert_member(&db.pool, &ops, "elsewhere").await;
+    let noon = at(2026, 3, 1, 12);
+    for _ in 0..2 {
+        let id = unique("req");
+        insert_request(&db.pool, &RequestSeed::new(&id, &eng, noon)).await;
+    }
+    let expensive = unique("req");
+    insert_request(
+        &db.pool,
+        &RequestSeed {
+            model: Some("other-model"),
+            cost_microdollars: 50_000,
+            ..RequestSeed::new(&expensive, &ops, noon)
+        },
+    )
+    .await;
+    let (eng_policy, ops_policy) = (unique("p"), unique("p"));
+    insert_denial(&db.pool, &eng, &eng_policy).await;
+    insert_denial(&db.pool, &ops, &ops_policy).await;
+    let period = DigestPeriod {
+        start: at(2026, 3, 1, 7),
+        end: at(2026, 3, 2, 7),
+    };
+
+    let digest = build_digest(&db.pool, &subscription("eng", Some(&department)), period)
+        .await
+        .expect("build digest");
+
+    assert_eq!(digest.spend.requests, 2);
+    assert_eq!(digest.spend.spend_microdollars, 2_000);
+    assert_eq!(digest.spend.users, 1);
+    let models: Vec<&str> = digest.top_models.iter().map(|m| m.model.as_str()).collect();
+    assert_eq!(models, ["claude-sonnet-4-5-20250929"]);
+    assert_eq!(digest.denials, 1);
+    let denied: Vec<&str> = digest
+        .denied_policies
+        .iter()
+        .map(|p| p.policy.as_str())
+        .collect();
+    assert_eq!(denied, [eng_policy.as_str()]);
+
+    db.cleanup().await;
+}
+
+#[tokio::test]
+async fn a_run_against_an_unreachable_database_counts_every_subscription_as_failed() {
+    let pool = PgPoolOptions::new()
+        .acquire_timeout(Duration::from_millis(500))
+        .connect_lazy("postgres://nobody@127.0.0.1:1/none")
+        .expect("lazy pool");
+    let config = DigestConfig {
+        subscriptions: vec![subscription("first", None), subscription("second", None)],
+        ..DigestConfig::default()
+    };
+    let sink = FileMailSink::new(std::env::temp_dir().join(unique("digests")));
+
+    let report = send_due_digests(&pool, &config, &sink, at(2026, 3, 2, 12)).await;
+
+    assert_eq!((report.delivered, report.failed), (0, 2));
+}
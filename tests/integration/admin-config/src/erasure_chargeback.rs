@@ -0,0 +1,189 @@
+//! `erasure` — an erased user's closed chargeback lines take the pseudonym,
+//! while the guard on closed periods still refuses every other edit, and so
+//! does every record naming them as the one who acted.
+
+use sqlx::PgPool;
+use systemprompt_web_admin::erasure::ErasureAction;
+use systemprompt_web_admin::repositories::erasure::{NewErasureCertificate, erase_user_rows};
+
+use crate::fixtures::{insert_user, unique, user_id};
+use crate::tempdb::TempDb;
+
+async fn close_month_with_line(pool: &PgPool, user: &str) {
+    sqlx::query(
+        "INSERT INTO chargeback_lines (month_start, department, cost_center, user_id, provider,
+             model, requests, input_tokens, output_tokens, gross_microdollars, credit_microdollars)
+         VALUES ('2026-01-01', 'eng', '', $1, 'anthropic', 'm', 3, 10, 20, 500, 0)",
+    )
+    .bind(user)
+    .execute(pool)
+    .await
+    .expect("insert chargeback line");
+    sqlx::query(
+        "INSERT INTO chargeback_periods (month_start, closed_by, gross_microdollars,
+             credit_microdollars)
+         VALUES ('2026-01-01', 'admin', 500, 0)",
+    )
+    .execute(pool)
+    .await
+    .expect("close the month");
+}
+
+async fn line_owner_and_gross(pool: &PgPool) -> (String, i64) {
+    sqlx::query_as("SELECT user_id, gross_microdollars FROM chargeback_lines")
+        .fetch_one(pool)
+        .await
+        .expect("read the chargeback line")
+}
+
+#[tokio::test]
+async fn an_erasure_pseudonymizes_closed_chargeback_lines() {
+    let Some(db) = TempDb::create().await else {
+        return;
+    };
+    let (user, admin) = (unique("u"), unique("admin"));
+    insert_user(&db.pool, &user).await;
+    insert_user(&db.pool, &admin).await;
+    close_month_with_line(&db.pool, &user).await;
+    let pseudonym = unique("erased");
+    let requested_by = user_id(&admin);
+    let certificate = NewErasureCertificate {
+        id: &unique("cert"),
+        subject_hash: &pseudonym,
+        requested_by: &requested_by,
+        reason: "request",
+    };
+
+    let tables = erase_user_rows(&db.pool, &user_id(&user), &pseudonym, Some(certificate))
+        .await
+        .expect("erase");
+
+    let chargeback = tables
+        .iter()
+        .find(|t| t.table == "chargeback_lines")
+        .expect("chargeback lines are listed");
+    assert_eq!(chargeback.action, ErasureAction::Pseudonymized);
+    assert_eq!(chargeback.rows, 1);
+    assert_eq!(line_owner_and_gross(&db.pool).await, (pseudonym, 500));
+
+    db.cleanup().await;
+}
+
+#[tokio::test]
+async fn a_closed_line_still_refuses_any_other_update() {
+    let Some(db) = TempDb::create().await else {
+        return;
+    };
+    let user = unique("u");
+    insert_user(&db.pool, &user).await;
+    close_month_with_line(&db.pool, &user).await;
+
+    let unnamed = sqlx::query("UPDATE chargeback_lines SET user_id = 'someone-else'")
+        .execute(db.pool.as_ref())
+        .await;
+    assert!(unnamed.is_err(), "a rename without a pseudonym is refused");
+
+    let mut tx = db.pool.begin().await.expect("begin");
+    sqlx::query("SELECT set_config('systemprompt.erasure_pseudonym', 'erased-x', true)")
+        .execute(&mut *tx)
+        .await
+        .expect("name the pseudonym");
+    let edited =
+        sqlx::query("UPDATE chargeback_lines SET user_id = 'erased-x', gross_microdollars = 0")
+            .execute(&mut *tx)
+            .await;
+    assert!(
+        edited.is_err(),
+        "figures cannot change alongside the pseudonym"
+    );
+    tx.rollback().await.expect("rollback");
+
+    assert_eq!(line_owner_and_gross(&db.pool).await, (user, 500));
+
+    db.cleanup().await;
+}
+
+async fn seed_actor_records(pool: &PgPool, user: &str) {
+    for sql in [
+        "INSERT INTO chargeback_periods (month_start, closed_by, gross_microdollars,
+             credit_microdollars)
+         VALUES ('2026-02-01', $1, 0, 0)",
+        "INSERT INTO cost_anomalies (id, scope, subject, hour_start, spend_microdollars,
+             requests, baseline_microdollars, stddev_microdollars, ratio, severity)
+         VALUES ('anomaly-' || $1, 'user', $1, '2026-02-01', 900, 3, 100, 10, 9.0, 'critical')",
+        "INSERT INTO gateway_config_revisions (etag, action, actor_id, gateway_yaml, targets_yaml)
+         VALUES ('etag', 'update', $1, '', '')",
+        "INSERT INTO erasure_certificates (id, subject_hash, requested_by, reason, tables)
+         VALUES ('cert-' || $1, 'erased-earlier', $1, 'request', '[]')",
+        "INSERT INTO master_key_events (id, key_id, event, detail, actor_id)
+         VALUES ('event-' || $1, 'local:k', 'rewrap_failed',
+                 jsonb_build_object('user_id', $1::text, 'error', 'x'), 'system')",
+    ] {
+        sqlx::query(sql)
+            .bind(user)
+            .execute(pool)
+            .await
+            .expect("seed a record naming the user");
+    }
+}
+
+async fn records_naming(pool: &PgPool, id: &str) -> i64 {
+    sqlx::query_scalar(
+        "SELECT (SELECT COUNT(*) FROM chargeback_periods WHERE closed_by = $1)
+              + (SELECT COUNT(*) FROM cost_anomalies WHERE subject = $1)
+              + (SELECT COUNT(*) FROM gateway_config_revisions WHERE actor_id = $1)
+              + (SELECT COUNT(*) FROM erasure_certificates WHERE requested_by = $1)
+              + (SELECT COUNT(*) FROM master_key_events WHERE detail->>'user_id' = $1)",
+    )
+    .bind(id)
+    .fetch_one(pool)
+    .await
+    .expect("count records naming the id")
+}
+
+#[tokio::test]
+async fn an_erasure_pseudonymizes_records_naming_the_user_as_actor() {
+    let Some(db) = TempDb::create().await else {
+        return;
+    };
+    let user = unique("u");
+    insert_user(&db.pool, &user).await;
+    seed_actor_records(&db.pool, &user).await;
+    let pseudonym = unique("erased");
+
+    let tables = erase_user_rows(&db.pool, &user_id(&user), &pseudonym, None)
+        .await
+        .expect("preview");
+    for name in [
+        "chargeback_periods",
+        "cost_anomalies",
+        "gateway_config_revisions",
+        "erasure_certificates",
+        "master_key_events",
+    ] {
+        let line = tables
+            .iter()
+            .find(|t| t.table == name)
+            .unwrap_or_else(|| panic!("{name} is listed"));
+        assert_eq!(
+            (line.action, line.rows),
+            (ErasureAction::Pseudonymized, 1),
+            "{name}"
+        );
+    }
+
+    let certificate = NewErasureCertificate {
+        id: &unique("cert"),
+        subject_hash: &pseudonym,
+        requested_by: &user_id("admin"),
+        reason: "request",
+    };
+    erase_user_rows(&db.pool, &user_id(&user), &pseudonym, Some(certificate))
+        .await
+        .expect("erase");
+
+    assert_eq!(records_naming(&db.pool, &user).await, 0);
+    assert_eq!(records_naming(&db.pool, &pseudonym).await, 5);
+
+    db.cleanup().await;
+}
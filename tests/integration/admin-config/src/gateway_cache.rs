@@ -0,0 +1,144 @@
+//! `gateway_cache` — recovering the request a prepared body belongs to, when
+//! the outbound adapter sees only the body, and tying a hit to the request
+//! that served its replay.
+
+use chrono::Utc;
+use systemprompt::identifiers::AiRequestId;
+use systemprompt_web_admin::gateway_cache::sole_pending_request;
+use systemprompt_web_admin::repositories::gateway_cache::{
+    NewCacheLookup, insert_cache_lookup, list_pending_requests_by_digest,
+};
+
+use crate::fixtures::{RequestSeed, insert_request, insert_user, unique};
+use crate::tempdb::TempDb;
+
+async fn insert_pending(pool: &sqlx::PgPool, id: &str, user: &str, digest: &str) {
+    let mut seed = RequestSeed::new(id, user, Utc::now());
+    seed.status = "pending";
+    insert_request(pool, &seed).await;
+    sqlx::query(
+        "INSERT INTO ai_request_payloads (ai_request_id, prepared_body_sha256) VALUES ($1, $2)",
+    )
+    .bind(id)
+    .bind(digest)
+    .execute(pool)
+    .await
+    .expect("insert payload");
+}
+
+#[tokio::test]
+async fn identical_bodies_from_two_users_are_not_attributed_to_either() {
+    let Some(db) = TempDb::create().await else {
+        return;
+    };
+    let (alice, bob) = (unique("alice"), unique("bob"));
+    insert_user(&db.pool, &alice).await;
+    insert_user(&db.pool, &bob).await;
+    let digest = unique("digest");
+    insert_pending(&db.pool, &unique("req"), &alice, &digest).await;
+    insert_pending(&db.pool, &unique("req"), &bob, &digest).await;
+
+    let candidates = list_pending_requests_by_digest(&db.pool, &digest)
+        .await
+        .expect("list pending");
+    assert_eq!(candidates.len(), 2);
+    assert!(sole_pending_request(candidates).is_none());
+
+    db.cleanup().await;
+}
+
+#[tokio::test]
+async fn a_claimed_request_is_no_longer_a_candidate() {
+    let Some(db) = TempDb::create().await else {
+        return;
+    };
+    let user = unique("u");
+    insert_user(&db.pool, &user).await;
+    let digest = unique("digest");
+    let (first, second) = (unique("req"), unique("req"));
+    insert_pending(&db.pool, &first, &user, &digest).await;
+    insert_pending(&db.pool, &second, &user, &digest).await;
+
+    let candidates = list_pending_requests_by_digest(&db.pool, &digest)
+        .await
+        .expect("list pending");
+    let pending = sole_pending_request(candidates).expect("one caller");
+    assert_eq!(pending.ai_request_id.as_str(), first);
+
+    let lookup = NewCacheLookup {
+        ai_request_id: Some(&AiRequestId::new(first.clone())),
+        replay_id: None,
+        route_id: "route",
+        outcome: "miss",
+        entry_id: None,
+        similarity: None,
+    };
+    insert_cache_lookup(&db.pool, lookup).await.expect("claim");
+    let remaining = list_pending_requests_by_digest(&db.pool, &digest)
+        .await
+        .expect("list pending");
+    let ids: Vec<&str> = remaining.iter().map(|c| c.ai_request_id.as_str()).collect();
+    assert_eq!(ids, vec![second.as_str()]);
+
+    db.cleanup().await;
+}
+
+#[tokio::test]
+async fn a_hit_marks_the_request_whose_reply_carries_its_replay_id() {
+    let Some(db) = TempDb::create().await else {
+        return;
+    };
+    let user = unique("u");
+    insert_user(&db.pool, &user).await;
+    let digest = unique("digest");
+    let (older, served) = (unique("req"), unique("req"));
+    insert_pending(&db.pool, &older, &user, &digest).await;
+    insert_pending(&db.pool, &served, &user, &digest).await;
+
+    let replay_id = format!("cache_{}", uuid::Uuid::new_v4().simple());
+    let lookup = NewCacheLookup {
+        ai_request_id: None,
+        replay_id: Some(&replay_id),
+        route_id: "route",
+        outcome: "exact",
+        entry_id: None,
+        similarity: None,
+    };
+    insert_cache_lookup(&db.pool, lookup).await.expect("hit");
+    sqlx::query(
+        "UPDATE ai_requests SET status = 'completed', cost_microdollars = 500 WHERE id = $1",
+    )
+    .bind(&served)
+    .execute(&*db.pool)
+    .await
+    .expect("complete");
+    sqlx::query("UPDATE ai_request_payloads SET response_body = $2 WHERE ai_request_id = $1")
+        .bind(&served)
+        .bind(serde_json::json!({ "id": replay_id, "content": [] }))
+        .execute(&*db.pool)
+        .await
+        .expect("store reply");
+
+    let rows: Vec<(String, bool, i64)> = sqlx::query_as(
+        "SELECT id, cache_hit, cost_microdollars FROM ai_requests WHERE id = ANY($1) ORDER BY id",
+    )
+    .bind(vec![older.clone(), served.clone()])
+    .fetch_all(&*db.pool)
+    .await
+    .expect("requests");
+    for (id, cache_hit, cost) in rows {
+        assert_eq!(cache_hit, id == served, "{id}");
+        if id == served {
+            assert_eq!(cost, 0);
+        }
+    }
+    let bound: Option<String> =
+        sqlx::query_scalar("SELECT ai_request_id FROM gateway_cache_lookups WHERE replay_id = $1")
+            .bind(&replay_id)
+            .fetch_one(&*db.pool)
+            .await
+            .expect("lookup");
+    assert_eq!(bound.as_deref(), Some(served.as_str()));
+
+    db.cleanup().await;
+}
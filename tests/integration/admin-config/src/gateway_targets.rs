@@ -0,0 +1,98 @@
+//! `gateway_targets` — naming the target that served a request, from the
+//! response id the outbound adapter recorded, and counting the failures it
+//! retried past in target health.
+
+use chrono::{Duration, Utc};
+use systemprompt_web_admin::repositories::analytics::upstream_health::list_upstream_failures;
+use systemprompt_web_admin::repositories::gateway_targets::{
+    insert_target_failure, insert_target_response,
+};
+
+use crate::fixtures::{RequestSeed, insert_request, insert_user, unique};
+use crate::tempdb::TempDb;
+
+#[tokio::test]
+async fn a_stored_reply_names_the_target_that_sent_it() {
+    let Some(db) = TempDb::create().await else {
+        return;
+    };
+    let user = unique("u");
+    insert_user(&db.pool, &user).await;
+    let (buffered, streamed) = (unique("req"), unique("req"));
+    for id in [&buffered, &streamed] {
+        insert_request(&db.pool, &RequestSeed::new(id, &user, Utc::now())).await;
+    }
+    let (msg, chunk) = (unique("msg"), unique("chatcmpl"));
+    insert_target_response(&db.pool, &msg, "claude", "canary")
+        .await
+        .expect("record buffered");
+    insert_target_response(&db.pool, &chunk, "claude", "backup")
+        .await
+        .expect("record streamed");
+
+    sqlx::query("INSERT INTO ai_request_payloads (ai_request_id, response_body) VALUES ($1, $2)")
+        .bind(&buffered)
+        .bind(serde_json::json!({ "id": msg, "content": [] }))
+        .execute(&*db.pool)
+        .await
+        .expect("store buffered reply");
+    let excerpt = format!(
+        "data: {{\"id\":\"{chunk}\",\"object\":\"chat.completion.chunk\"}}\n\n... data: [DONE]"
+    );
+    sqlx::query(
+        "INSERT INTO ai_request_payloads (ai_request_id, response_excerpt) VALUES ($1, $2)",
+    )
+    .bind(&streamed)
+    .bind(&excerpt)
+    .execute(&*db.pool)
+    .await
+    .expect("store streamed reply");
+
+    let rows: Vec<(String, String)> = sqlx::query_as(
+        "SELECT ai_request_id, target_id FROM gateway_request_targets
+         WHERE ai_request_id = ANY($1)",
+    )
+    .bind(vec![buffered.clone(), streamed.clone()])
+    .fetch_all(&*db.pool)
+    .await
+    .expect("requests");
+    assert_eq!(rows.len(), 2);
+    for (id, target) in rows {
+        let expected = if id == buffered { "canary" } else { "backup" };
+        assert_eq!(target, expected, "{id}");
+    }
+    let left: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM gateway_target_responses WHERE response_id = ANY($1)",
+    )
+    .bind(vec![msg, chunk])
+    .fetch_one(&*db.pool)
+    .await
+    .expect("bindings");
+    assert_eq!(left, 0);
+
+    db.cleanup().await;
+}
+
+#[tokio::test]
+async fn a_failure_retried_past_counts_toward_health() {
+    let Some(db) = TempDb::create().await else {
+        return;
+    };
+    let provider = unique("provider");
+    for _ in 0..2 {
+        insert_target_failure(&db.pool, &provider, "sonnet", "provider returned 503: busy")
+            .await
+            .expect("record failure");
+    }
+
+    let rows = list_upstream_failures(&db.pool, Utc::now() - Duration::minutes(5))
+        .await
+        .expect("failures");
+    let row = rows
+        .iter()
+        .find(|r| r.provider == provider)
+        .expect("the retried failures");
+    assert_eq!((row.model.as_str(), row.failures), ("sonnet", 2));
+
+    db.cleanup().await;
+}
@@ -1,7 +1,10 @@
 //! Integration coverage for `systemprompt-web-admin`'s repositories against a
 //! live Postgres: the configured-policy surface (`config`), the marketplace's
-//! catalog, usage and environment records, encrypted secret storage, and the
-//! scheduled-job list.
+//! catalog, usage and environment records, encrypted secret storage and master
+//! key rotation, SCIM user creation, full-text search, erasure of closed
+//! chargeback lines, scoped digests, retention purges under legal hold, the
+//! `/metrics` tallies, the scheduled-job list, gateway caching and target
+//! records, and the warehouse export's windows.
 //!
 //! Every test runs against its OWN throwaway database created on the server
 //! named by `DATABASE_URL`, with the real extension schema installed, so the
@@ -20,8 +23,16 @@ mod config_gateway_acl;
 #[cfg(test)]
 mod config_roles;
 #[cfg(test)]
+mod digests;
+#[cfg(test)]
+mod erasure_chargeback;
+#[cfg(test)]
 mod fixtures;
 #[cfg(test)]
+mod gateway_cache;
+#[cfg(test)]
+mod gateway_targets;
+#[cfg(test)]
 mod jobs_repo;
 #[cfg(test)]
 mod marketplace_catalog;
@@ -30,10 +41,24 @@ mod marketplace_env;
 #[cfg(test)]
 mod marketplace_usage;
 #[cfg(test)]
+mod metrics_tallies;
+#[cfg(test)]
+mod retention;
+#[cfg(test)]
+mod scim_users;
+#[cfg(test)]
+mod search;
+#[cfg(test)]
 mod secrets_keys;
 #[cfg(test)]
+mod secrets_lifecycle;
+#[cfg(test)]
 mod secrets_migration;
 #[cfg(test)]
 mod secrets_resolve;
 #[cfg(test)]
+mod secrets_rotation;
+#[cfg(test)]
 mod tempdb;
+#[cfg(test)]
+mod warehouse_rows;
@@ -0,0 +1,136 @@
+//! `metrics` — the `/metrics` tallies leave pending requests to the in-flight
+//! gauge and keep counting what the retention purge deletes.
+
+use sqlx::PgPool;
+use systemprompt_web_admin::metrics::LATENCY_BUCKETS_MS;
+use systemprompt_web_admin::repositories::metrics::{
+    list_decision_tallies, list_gateway_request_tallies, list_in_flight_requests,
+    list_safety_finding_tallies,
+};
+use systemprompt_web_admin::repositories::retention::purge::{
+    delete_expired_ai_requests, delete_expired_governance_decisions,
+};
+use systemprompt_web_admin::retention::LegalHolds;
+
+use crate::fixtures::{RequestSeed, at, insert_request, insert_user, unique};
+use crate::tempdb::TempDb;
+
+async fn insert_finding(pool: &PgPool, request: &str, scanner: &str) {
+    sqlx::query(
+        "INSERT INTO ai_safety_findings (id, ai_request_id, phase, severity, category, scanner)
+         VALUES ($1, $2, 'request', 'high', 'api_key', $3)",
+    )
+    .bind(unique("finding"))
+    .bind(request)
+    .bind(scanner)
+    .execute(pool)
+    .await
+    .expect("insert safety finding");
+}
+
+async fn insert_decision(pool: &PgPool, user: &str, policy: &str) {
+    sqlx::query(
+        "INSERT INTO governance_decisions
+            (id, user_id, session_id, tool_name, decision, policy, reason,
+             actor_kind, actor_id, context_id, created_at)
+         VALUES ($1, $2, $1, 'Bash', 'deny', $3, 'blocked', 'user', $2, $1, $4)",
+    )
+    .bind(unique("decision"))
+    .bind(user)
+    .bind(policy)
+    .bind(at(2025, 1, 1, 12))
+    .execute(pool)
+    .await
+    .expect("insert governance decision");
+}
+
+// Why: (completed requests, their input tokens, in-flight requests, findings,
+// decisions) for the labels this test minted, so the migrations' seed rows
+// cannot leak into the comparison.
+async fn totals(pool: &PgPool, model: &str, scanner: &str, policy: &str) -> [i64; 5] {
+    let requests = list_gateway_request_tallies(pool, &LATENCY_BUCKETS_MS)
+        .await
+        .expect("request tallies");
+    let mine = || {
+        requests
+            .iter()
+            .filter(|t| t.model.as_deref() == Some(model))
+    };
+    assert!(mine().all(|t| t.status == "completed"), "{requests:?}");
+    let in_flight = list_in_flight_requests(pool).await.expect("in flight");
+    let findings = list_safety_finding_tallies(pool).await.expect("findings");
+    let decisions = list_decision_tallies(pool).await.expect("decisions");
+    [
+        mine().map(|t| t.requests).sum(),
+        mine().map(|t| t.input_tokens).sum(),
+        in_flight
+            .iter()
+            .filter(|t| t.model.as_deref() == Some(model))
+            .map(|t| t.requests)
+            .sum(),
+        findings
+            .iter()
+            .filter(|t| t.scanner == scanner)
+            .map(|t| t.findings)
+            .sum(),
+        decisions
+            .iter()
+            .filter(|t| t.policy == policy)
+            .map(|t| t.decisions)
+            .sum(),
+    ]
+}
+
+#[tokio::test]
+async fn pending_requests_are_in_flight_and_purged_rows_keep_counting() {
+    let Some(db) = TempDb::create().await else {
+        return;
+    };
+    let user = unique("u");
+    insert_user(&db.pool, &user).await;
+    let model = unique("model");
+    let (scanner, policy) = (unique("scanner"), unique("policy"));
+
+    let completed = unique("req");
+    insert_request(
+        &db.pool,
+        &RequestSeed {
+            model: Some(&model),
+            ..RequestSeed::new(&completed, &user, at(2025, 1, 1, 12))
+        },
+    )
+    .await;
+    let pending = unique("req");
+    insert_request(
+        &db.pool,
+        &RequestSeed {
+            model: Some(&model),
+            status: "pending",
+            ..RequestSeed::new(&pending, &user, at(2025, 1, 1, 12))
+        },
+    )
+    .await;
+    insert_finding(&db.pool, &completed, &scanner).await;
+    insert_decision(&db.pool, &user, &policy).await;
+
+    let before = totals(&db.pool, &model, &scanner, &policy).await;
+    assert_eq!(before, [1, 100, 1, 1, 1]);
+
+    let holds = LegalHolds::default();
+    let cutoff = at(2026, 1, 1, 0);
+    delete_expired_ai_requests(&db.pool, cutoff, &holds, 10_000)
+        .await
+        .expect("purge requests");
+    delete_expired_governance_decisions(&db.pool, cutoff, &holds, 10_000)
+        .await
+        .expect("purge decisions");
+
+    let after = totals(&db.pool, &model, &scanner, &policy).await;
+    assert_eq!(
+        after,
+        [1, 100, 0, 1, 1],
+        "counters hold across the purge; the purged pending request leaves the gauge"
+    );
+
+    db.cleanup().await;
+}
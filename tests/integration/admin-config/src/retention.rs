@@ -0,0 +1,157 @@
+//! `retention` — rows without a session are purged on their user alone, so an
+//! unrelated session hold neither shields them nor hides them from the
+//! backlog, while a held session's rows stay. MCP tool captures expire on
+//! when the call ended, under the same holds.
+
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use systemprompt_web_admin::repositories::retention::backlog::{
+    get_ai_requests_backlog, get_mcp_tool_captures_backlog,
+};
+use systemprompt_web_admin::repositories::retention::purge::{
+    delete_expired_ai_requests, delete_expired_hook_events, delete_expired_mcp_tool_captures,
+};
+use systemprompt_web_admin::retention::LegalHolds;
+
+use crate::fixtures::{RequestSeed, at, count_rows, insert_request, insert_user, unique};
+use crate::tempdb::TempDb;
+
+async fn insert_hook_event(pool: &PgPool, user: &str, session: &str) -> String {
+    let id = unique("evt");
+    sqlx::query(
+        "INSERT INTO plugin_usage_events (id, user_id, session_id, event_type, created_at)
+         VALUES ($1, $2, $3, 'PostToolUse', $4)",
+    )
+    .bind(&id)
+    .bind(user)
+    .bind(session)
+    .bind(at(2025, 1, 1, 12))
+    .execute(pool)
+    .await
+    .expect("insert hook event");
+    id
+}
+
+async fn insert_tool_capture(
+    pool: &PgPool,
+    user: &str,
+    session: &str,
+    ended_at: DateTime<Utc>,
+) -> String {
+    let id = unique("cap");
+    sqlx::query(
+        "INSERT INTO mcp_tool_captures
+             (id, user_id, session_id, trace_id, server, tool_name, status, arguments,
+              started_at, ended_at)
+         VALUES ($1, $2, $3, $4, 'systemprompt', 'list_skills', 'ok', '{}', $5, $5)",
+    )
+    .bind(&id)
+    .bind(user)
+    .bind(session)
+    .bind(unique("trace"))
+    .bind(ended_at)
+    .execute(pool)
+    .await
+    .expect("insert tool capture");
+    id
+}
+
+fn session_hold(session: &str) -> LegalHolds {
+    LegalHolds {
+        users: Vec::new(),
+        sessions: vec![session.to_owned()],
+    }
+}
+
+#[tokio::test]
+async fn a_request_without_a_session_is_purged_past_an_unrelated_hold() {
+    let Some(db) = TempDb::create().await else {
+        return;
+    };
+    let user = unique("u");
+    insert_user(&db.pool, &user).await;
+    let request = unique("req");
+    insert_request(
+        &db.pool,
+        &RequestSeed::new(&request, &user, at(2025, 1, 1, 12)),
+    )
+    .await;
+    let holds = session_hold(&unique("held-session"));
+    let cutoff = at(2026, 1, 1, 0);
+
+    let backlog = get_ai_requests_backlog(&db.pool, cutoff, cutoff, &holds)
+        .await
+        .expect("backlog");
+    assert!(backlog.due_now >= 1, "{backlog:?}");
+    let purged = delete_expired_ai_requests(&db.pool, cutoff, &holds, 10_000)
+        .await
+        .expect("purge");
+
+    assert!(purged >= 1);
+    let left = count_rows(
+        &db.pool,
+        "SELECT COUNT(*) FROM ai_requests WHERE id = $1",
+        &request,
+    )
+    .await;
+    assert_eq!(left, 0);
+
+    db.cleanup().await;
+}
+
+#[tokio::test]
+async fn a_held_session_keeps_its_hook_events_and_others_go() {
+    let Some(db) = TempDb::create().await else {
+        return;
+    };
+    let user = unique("u");
+    insert_user(&db.pool, &user).await;
+    let held_session = unique("held-session");
+    let kept = insert_hook_event(&db.pool, &user, &held_session).await;
+    let gone = insert_hook_event(&db.pool, &user, &unique("session")).await;
+
+    delete_expired_hook_events(
+        &db.pool,
+        at(2026, 1, 1, 0),
+        &session_hold(&held_session),
+        10_000,
+    )
+    .await
+    .expect("purge");
+
+    let count = "SELECT COUNT(*) FROM plugin_usage_events WHERE id = $1";
+    assert_eq!(count_rows(&db.pool, count, &kept).await, 1);
+    assert_eq!(count_rows(&db.pool, count, &gone).await, 0);
+
+    db.cleanup().await;
+}
+
+#[tokio::test]
+async fn expired_tool_captures_go_unless_their_session_is_held() {
+    let Some(db) = TempDb::create().await else {
+        return;
+    };
+    let user = unique("u");
+    let held_session = unique("held-session");
+    let held = insert_tool_capture(&db.pool, &user, &held_session, at(2025, 1, 1, 12)).await;
+    let expired = insert_tool_capture(&db.pool, &user, &unique("s"), at(2025, 1, 1, 12)).await;
+    let fresh = insert_tool_capture(&db.pool, &user, &unique("s"), at(2026, 2, 1, 12)).await;
+    let holds = session_hold(&held_session);
+    let cutoff = at(2026, 1, 1, 0);
+
+    let backlog = get_mcp_tool_captures_backlog(&db.pool, cutoff, cutoff, &holds)
+        .await
+        .expect("backlog");
+    assert_eq!((backlog.due_now, backlog.held), (1, 1), "{backlog:?}");
+    let purged = delete_expired_mcp_tool_captures(&db.pool, cutoff, &holds, 10_000)
+        .await
+        .expect("purge");
+
+    assert_eq!(purged, 1);
+    let count = "SELECT COUNT(*) FROM mcp_tool_captures WHERE id = $1";
+    assert_eq!(count_rows(&db.pool, count, &held).await, 1);
+    assert_eq!(count_rows(&db.pool, count, &expired).await, 0);
+    assert_eq!(count_rows(&db.pool, count, &fresh).await, 1);
+
+    db.cleanup().await;
+}
@@ -0,0 +1,43 @@
+//! `scim` — provisioning never takes over an account the platform already
+//! holds, so a suspended user stays suspended.
+
+use systemprompt_web_admin::repositories::scim::users::{ScimUserWrite, create_scim_user};
+
+use crate::fixtures::{insert_user, unique, user_id};
+use crate::tempdb::TempDb;
+
+#[tokio::test]
+async fn creating_over_an_existing_email_fails_and_keeps_its_status() {
+    let Some(db) = TempDb::create().await else {
+        return;
+    };
+    let existing = unique("u");
+    insert_user(&db.pool, &existing).await;
+    sqlx::query("UPDATE users SET status = 'suspended' WHERE id = $1")
+        .bind(&existing)
+        .execute(db.pool.as_ref())
+        .await
+        .expect("suspend");
+    let email = format!("{existing}@example.test");
+    let write = ScimUserWrite {
+        email: &email,
+        display_name: None,
+        external_id: None,
+        active: true,
+    };
+
+    let created = create_scim_user(&db.pool, &user_id(&unique("new")), &write).await;
+
+    let Err(sqlx::Error::Database(err)) = created else {
+        panic!("expected a unique violation, got {created:?}");
+    };
+    assert_eq!(err.code().as_deref(), Some("23505"));
+    let status: String = sqlx::query_scalar("SELECT status FROM users WHERE id = $1")
+        .bind(&existing)
+        .fetch_one(db.pool.as_ref())
+        .await
+        .expect("read status");
+    assert_eq!(status, "suspended");
+
+    db.cleanup().await;
+}
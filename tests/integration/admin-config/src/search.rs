@@ -0,0 +1,92 @@
+//! `search` — full-text search over request messages and transcripts whose
+//! bodies are too large for a single tsvector.
+
+use chrono::Utc;
+use systemprompt_web_admin::repositories::search::{SearchFilter, list_search_hits};
+use systemprompt_web_admin::search::SearchSource;
+
+use crate::fixtures::{RequestSeed, insert_request, insert_user, unique};
+use crate::tempdb::TempDb;
+
+// Distinct words whose tsvector passes the 1 MB limit, after a term to find.
+fn oversized_body(term: &str) -> String {
+    let words: Vec<String> = (0..400_000).map(|n| format!("w{n}")).collect();
+    format!("{term} {}", words.join(" "))
+}
+
+fn filter(query: &str, source: SearchSource) -> SearchFilter {
+    SearchFilter {
+        query: query.to_owned(),
+        source: Some(source),
+        ..SearchFilter::default()
+    }
+}
+
+#[tokio::test]
+async fn an_oversized_message_is_stored_and_found_by_its_opening_text() {
+    let Some(db) = TempDb::create().await else {
+        return;
+    };
+    let user = unique("u");
+    insert_user(&db.pool, &user).await;
+    let request = unique("req");
+    insert_request(&db.pool, &RequestSeed::new(&request, &user, Utc::now())).await;
+    let term = unique("needle");
+
+    sqlx::query(
+        "INSERT INTO ai_request_messages (request_id, role, content, sequence_number)
+         VALUES ($1, 'user', $2, 0)",
+    )
+    .bind(&request)
+    .bind(oversized_body(&term))
+    .execute(db.pool.as_ref())
+    .await
+    .expect("an oversized message is stored");
+
+    let hits = list_search_hits(&db.pool, &filter(&term, SearchSource::Request), 10, 0)
+        .await
+        .expect("search");
+    let ids: Vec<_> = hits
+        .iter()
+        .filter_map(|h| h.ai_request_id.as_ref())
+        .collect();
+    assert_eq!(ids.len(), 1);
+    assert_eq!(ids[0].as_str(), request);
+
+    db.cleanup().await;
+}
+
+#[tokio::test]
+async fn an_oversized_transcript_is_stored_and_found_by_its_opening_turn() {
+    let Some(db) = TempDb::create().await else {
+        return;
+    };
+    let user = unique("u");
+    insert_user(&db.pool, &user).await;
+    let session = unique("sess");
+    let term = unique("needle");
+    let transcript = serde_json::json!([{ "role": "user", "content": oversized_body(&term) }]);
+
+    sqlx::query(
+        "INSERT INTO session_transcripts (id, user_id, session_id, transcript)
+         VALUES ($1, $2, $3, $4)",
+    )
+    .bind(unique("t"))
+    .bind(&user)
+    .bind(&session)
+    .bind(&transcript)
+    .execute(db.pool.as_ref())
+    .await
+    .expect("an oversized transcript is stored");
+
+    let hits = list_search_hits(&db.pool, &filter(&term, SearchSource::Transcript), 10, 0)
+        .await
+        .expect("search");
+    assert_eq!(hits.len(), 1);
+    assert_eq!(
+        hits[0].session_id.as_ref().map(|s| s.as_str()),
+        Some(session.as_str())
+    );
+
+    db.cleanup().await;
+}
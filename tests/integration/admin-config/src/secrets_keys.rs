@@ -5,6 +5,7 @@
 //! so these tests exercise the storage paths without mutating process state.
 
 use systemprompt_web_admin::repositories::secrets::secret_crypto::{encrypt, generate_nonce};
+use systemprompt_web_admin::repositories::secrets::secret_keyring::MasterKeyring;
 use systemprompt_web_admin::repositories::secrets::secret_keys::{
     get_or_create_user_dek, rotate_user_dek,
 };
@@ -16,9 +17,13 @@ use crate::tempdb::TempDb;
 
 const MASTER_KEY: [u8; 32] = [7u8; 32];
 
+fn keyring() -> MasterKeyring {
+    MasterKeyring::new(MASTER_KEY, [])
+}
+
 // Stores `value` as a sealed secret the way the handler path would.
 async fn store_secret(pool: &sqlx::PgPool, user: &str, plugin: &str, name: &str, value: &str) {
-    let dek = get_or_create_user_dek(pool, &user_id(user), &MASTER_KEY)
+    let dek = get_or_create_user_dek(pool, &user_id(user), &keyring())
         .await
         .expect("issue dek");
     let nonce = generate_nonce();
@@ -44,10 +49,10 @@ async fn get_or_create_user_dek_issues_one_key_and_reuses_it() {
     let user = unique("u");
     insert_user(&db.pool, &user).await;
 
-    let first = get_or_create_user_dek(&db.pool, &user_id(&user), &MASTER_KEY)
+    let first = get_or_create_user_dek(&db.pool, &user_id(&user), &keyring())
         .await
         .expect("issue dek");
-    let second = get_or_create_user_dek(&db.pool, &user_id(&user), &MASTER_KEY)
+    let second = get_or_create_user_dek(&db.pool, &user_id(&user), &keyring())
         .await
         .expect("read dek back");
 
@@ -74,10 +79,10 @@ async fn get_or_create_user_dek_issues_distinct_keys_per_user() {
     insert_user(&db.pool, &one).await;
     insert_user(&db.pool, &two).await;
 
-    let key_one = get_or_create_user_dek(&db.pool, &user_id(&one), &MASTER_KEY)
+    let key_one = get_or_create_user_dek(&db.pool, &user_id(&one), &keyring())
         .await
         .expect("issue first dek");
-    let key_two = get_or_create_user_dek(&db.pool, &user_id(&two), &MASTER_KEY)
+    let key_two = get_or_create_user_dek(&db.pool, &user_id(&two), &keyring())
         .await
         .expect("issue second dek");
 
@@ -93,11 +98,16 @@ async fn get_or_create_user_dek_fails_under_the_wrong_master_key() {
     };
     let user = unique("u");
     insert_user(&db.pool, &user).await;
-    get_or_create_user_dek(&db.pool, &user_id(&user), &MASTER_KEY)
+    get_or_create_user_dek(&db.pool, &user_id(&user), &keyring())
         .await
         .expect("issue dek");
 
-    let result = get_or_create_user_dek(&db.pool, &user_id(&user), &[9u8; 32]).await;
+    let result = get_or_create_user_dek(
+        &db.pool,
+        &user_id(&user),
+        &MasterKeyring::new([9u8; 32], []),
+    )
+    .await;
 
     assert!(
         result.is_err(),
@@ -116,11 +126,11 @@ async fn rotate_user_dek_re_seals_every_secret_under_the_new_key() {
     insert_user(&db.pool, &user).await;
     store_secret(&db.pool, &user, "plug", "API_TOKEN", "s3cret").await;
 
-    rotate_user_dek(&db.pool, &user_id(&user), &MASTER_KEY)
+    rotate_user_dek(&db.pool, &user_id(&user), &keyring())
         .await
         .expect("rotate dek");
 
-    let resolved = resolve_secrets_for_plugin(&db.pool, &user_id(&user), "plug", &MASTER_KEY)
+    let resolved = resolve_secrets_for_plugin(&db.pool, &user_id(&user), "plug", &keyring())
         .await
         .expect("resolve after rotation");
     assert_eq!(
@@ -140,7 +150,7 @@ async fn rotate_user_dek_bumps_the_key_version() {
     insert_user(&db.pool, &user).await;
     store_secret(&db.pool, &user, "plug", "API_TOKEN", "s3cret").await;
 
-    rotate_user_dek(&db.pool, &user_id(&user), &MASTER_KEY)
+    rotate_user_dek(&db.pool, &user_id(&user), &keyring())
         .await
         .expect("rotate dek");
 
@@ -165,7 +175,7 @@ async fn rotate_user_dek_issues_a_key_for_a_user_who_never_had_one() {
     let user = unique("u");
     insert_user(&db.pool, &user).await;
 
-    rotate_user_dek(&db.pool, &user_id(&user), &MASTER_KEY)
+    rotate_user_dek(&db.pool, &user_id(&user), &keyring())
         .await
         .expect("rotate without prior key");
 
@@ -0,0 +1,242 @@
+//! `secret_lifecycle` — secret metadata and the owner it may name, the scan
+//! that flags expiring and unused secrets, and the anomaly check made on each
+//! resolution.
+
+use chrono::{Duration, Utc};
+use systemprompt_web_admin::error::AdminError;
+use systemprompt_web_admin::repositories::secrets::secret_audit::insert_audit_entry;
+use systemprompt_web_admin::repositories::secrets::secret_crypto::{encrypt, generate_nonce};
+use systemprompt_web_admin::repositories::secrets::secret_keyring::MasterKeyring;
+use systemprompt_web_admin::repositories::secrets::secret_keys::get_or_create_user_dek;
+use systemprompt_web_admin::repositories::secrets::secret_lifecycle::{
+    SecretAlertKind, SecretMetadata, get_resolution_baseline, set_secret_metadata,
+};
+use systemprompt_web_admin::repositories::secrets::secret_resolve::resolve_secrets_for_plugin;
+use systemprompt_web_admin::secret_lifecycle::{
+    LifecyclePolicy, attention_for_user, check_resolution, scan,
+};
+
+use crate::fixtures::{count_rows, insert_secret_env_var, insert_user, unique, user_id};
+use crate::tempdb::TempDb;
+
+#[tokio::test]
+async fn set_secret_metadata_updates_the_secret_and_audits_it() {
+    let Some(db) = TempDb::create().await else {
+        return;
+    };
+    let user = unique("u");
+    insert_user(&db.pool, &user).await;
+    insert_secret_env_var(&db.pool, &user, "plug", "API_KEY", "").await;
+
+    let expires_at = Utc::now() + Duration::days(30);
+    let metadata = SecretMetadata {
+        owner_id: Some("platform-team".to_owned()),
+        expires_at: Some(expires_at),
+    };
+    let updated = set_secret_metadata(&db.pool, &user_id(&user), "plug", "API_KEY", &metadata)
+        .await
+        .expect("set metadata");
+    assert!(updated);
+
+    let (owner, stored): (Option<String>, Option<chrono::DateTime<Utc>>) = sqlx::query_as(
+        "SELECT owner_id, expires_at FROM plugin_env_vars WHERE user_id = $1 AND var_name = 'API_KEY'",
+    )
+    .bind(&user)
+    .fetch_one(db.pool.as_ref())
+    .await
+    .expect("read metadata");
+    assert_eq!(owner.as_deref(), Some("platform-team"));
+    assert_eq!(stored.map(|t| t.timestamp()), Some(expires_at.timestamp()));
+    let audited = count_rows(
+        &db.pool,
+        "SELECT COUNT(*) FROM secret_audit_log WHERE user_id = $1 AND action = 'updated' AND var_name = 'API_KEY'",
+        &user,
+    )
+    .await;
+    assert_eq!(audited, 1);
+
+    let missing = set_secret_metadata(&db.pool, &user_id(&user), "plug", "NOPE", &metadata)
+        .await
+        .expect("set metadata on a missing secret");
+    assert!(!missing);
+
+    db.cleanup().await;
+}
+
+#[tokio::test]
+async fn a_secret_owner_must_be_an_existing_user() {
+    let Some(db) = TempDb::create().await else {
+        return;
+    };
+    let (user, owner) = (unique("u"), unique("owner"));
+    insert_user(&db.pool, &user).await;
+    insert_user(&db.pool, &owner).await;
+    insert_secret_env_var(&db.pool, &user, "plug", "API_KEY", "").await;
+    let named = |owner_id: &str| SecretMetadata {
+        owner_id: Some(owner_id.to_owned()),
+        expires_at: None,
+    };
+
+    let unknown = systemprompt_web_admin::test_support::set_secret_metadata(
+        &db.pool,
+        &user_id(&user),
+        "plug",
+        "API_KEY",
+        &named("nobody-by-that-id"),
+    )
+    .await;
+    assert!(
+        matches!(unknown, Err(AdminError::BadRequest(_))),
+        "{unknown:?}"
+    );
+    systemprompt_web_admin::test_support::set_secret_metadata(
+        &db.pool,
+        &user_id(&user),
+        "plug",
+        "API_KEY",
+        &named(&owner),
+    )
+    .await
+    .expect("an existing user may own the secret");
+
+    db.cleanup().await;
+}
+
+#[tokio::test]
+async fn scan_flags_expiring_and_unused_secrets_once() {
+    let Some(db) = TempDb::create().await else {
+        return;
+    };
+    let user = unique("u");
+    insert_user(&db.pool, &user).await;
+    insert_secret_env_var(&db.pool, &user, "plug", "SOON", "").await;
+    insert_secret_env_var(&db.pool, &user, "plug", "IDLE", "").await;
+    insert_secret_env_var(&db.pool, &user, "plug", "FINE", "").await;
+    let metadata = SecretMetadata {
+        owner_id: None,
+        expires_at: Some(Utc::now() + Duration::days(3)),
+    };
+    set_secret_metadata(&db.pool, &user_id(&user), "plug", "SOON", &metadata)
+        .await
+        .expect("set expiry");
+    sqlx::query(
+        "UPDATE plugin_env_vars SET created_at = NOW() - INTERVAL '200 days'
+         WHERE user_id = $1 AND var_name = 'IDLE'",
+    )
+    .bind(&user)
+    .execute(db.pool.as_ref())
+    .await
+    .expect("age secret");
+
+    let policy = LifecyclePolicy::default();
+    let first = scan(&db.pool, &policy, Utc::now()).await.expect("scan");
+    assert_eq!(first.recorded, 2);
+    let kinds: Vec<(String, String)> = sqlx::query_as(
+        "SELECT var_name, kind FROM secret_alerts WHERE user_id = $1 ORDER BY var_name",
+    )
+    .bind(&user)
+    .fetch_all(db.pool.as_ref())
+    .await
+    .expect("list alerts");
+    assert_eq!(
+        kinds,
+        vec![
+            ("IDLE".to_owned(), "unused".to_owned()),
+            ("SOON".to_owned(), "expiring".to_owned()),
+        ]
+    );
+
+    let second = scan(&db.pool, &policy, Utc::now()).await.expect("rescan");
+    assert_eq!(second.flagged, 2);
+    assert_eq!(second.recorded, 0);
+
+    let attention = attention_for_user(&db.pool, &user_id(&user), &policy, Utc::now())
+        .await
+        .expect("attention");
+    let names: Vec<&str> = attention.iter().map(|a| a.var_name.as_str()).collect();
+    assert_eq!(names, vec!["SOON", "IDLE"]);
+
+    db.cleanup().await;
+}
+
+#[tokio::test]
+async fn resolving_stamps_the_secrets_it_read() {
+    let Some(db) = TempDb::create().await else {
+        return;
+    };
+    let user = unique("u");
+    insert_user(&db.pool, &user).await;
+    let keyring = MasterKeyring::new([7u8; 32], []);
+    let dek = get_or_create_user_dek(&db.pool, &user_id(&user), &keyring)
+        .await
+        .expect("issue dek");
+    let nonce = generate_nonce();
+    let sealed = encrypt(&dek, &nonce, b"value").expect("seal value");
+    let read = insert_secret_env_var(&db.pool, &user, "plug", "API_KEY", "").await;
+    sqlx::query(
+        "UPDATE plugin_env_vars SET encrypted_value = $1, value_nonce = $2, key_version = 1
+         WHERE id = $3",
+    )
+    .bind(sealed)
+    .bind(nonce.to_vec())
+    .bind(&read)
+    .execute(db.pool.as_ref())
+    .await
+    .expect("store sealed value");
+    let other = insert_secret_env_var(&db.pool, &user, "other", "API_KEY", "").await;
+
+    resolve_secrets_for_plugin(&db.pool, &user_id(&user), "plug", &keyring)
+        .await
+        .expect("resolve");
+
+    let stamped =
+        "SELECT COUNT(*) FROM plugin_env_vars WHERE id = $1 AND last_resolved_at IS NOT NULL";
+    assert_eq!(count_rows(&db.pool, stamped, &read).await, 1);
+    assert_eq!(count_rows(&db.pool, stamped, &other).await, 0);
+
+    db.cleanup().await;
+}
+
+#[tokio::test]
+async fn a_resolution_from_a_new_plugin_is_flagged_once_a_day() {
+    let Some(db) = TempDb::create().await else {
+        return;
+    };
+    let user = unique("u");
+    insert_user(&db.pool, &user).await;
+    for _ in 0..6 {
+        insert_audit_entry(&db.pool, &user_id(&user), "usual", "accessed")
+            .await
+            .expect("seed history");
+    }
+
+    let now = Utc::now();
+    let baseline = get_resolution_baseline(&db.pool, &user_id(&user), "fresh", now)
+        .await
+        .expect("baseline");
+    assert_eq!(baseline.user_30d, 6);
+    assert_eq!(baseline.plugin_30d, 0);
+
+    let policy = LifecyclePolicy::default();
+    let kinds = check_resolution(&db.pool, &user_id(&user), "fresh", &policy, now)
+        .await
+        .expect("check");
+    assert_eq!(kinds, vec![SecretAlertKind::UnusualPlugin]);
+    check_resolution(&db.pool, &user_id(&user), "fresh", &policy, now)
+        .await
+        .expect("check again");
+    let alerts = count_rows(
+        &db.pool,
+        "SELECT COUNT(*) FROM secret_alerts WHERE user_id = $1 AND kind = 'unusual_plugin'",
+        &user,
+    )
+    .await;
+    assert_eq!(alerts, 1);
+
+    let usual = check_resolution(&db.pool, &user_id(&user), "usual", &policy, now)
+        .await
+        .expect("check usual plugin");
+    assert!(usual.is_empty());
+
+    db.cleanup().await;
+}
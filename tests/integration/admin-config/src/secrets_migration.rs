@@ -2,6 +2,7 @@
 //! plaintext secrets, driven by the migration job.
 
 use systemprompt_web_admin::repositories::secrets::secret_crypto::{encrypt, generate_nonce};
+use systemprompt_web_admin::repositories::secrets::secret_keyring::MasterKeyring;
 use systemprompt_web_admin::repositories::secrets::secret_keys::get_or_create_user_dek;
 use systemprompt_web_admin::repositories::secrets::secret_migration::{
     get_key_version, insert_migration_audit, list_unencrypted_secrets, update_encrypted_value,
@@ -12,9 +13,13 @@ use crate::tempdb::TempDb;
 
 const MASTER_KEY: [u8; 32] = [7u8; 32];
 
+fn keyring() -> MasterKeyring {
+    MasterKeyring::new(MASTER_KEY, [])
+}
+
 // Stores `value` as a sealed secret the way the handler path would.
 async fn store_secret(pool: &sqlx::PgPool, user: &str, plugin: &str, name: &str, value: &str) {
-    let dek = get_or_create_user_dek(pool, &user_id(user), &MASTER_KEY)
+    let dek = get_or_create_user_dek(pool, &user_id(user), &keyring())
         .await
         .expect("issue dek");
     let nonce = generate_nonce();
@@ -113,7 +118,7 @@ async fn update_encrypted_value_clears_the_plaintext_column() {
     let user = unique("u");
     insert_user(&db.pool, &user).await;
     let id = insert_secret_env_var(&db.pool, &user, "plug", "LEGACY", "plaintext").await;
-    let dek = get_or_create_user_dek(&db.pool, &user_id(&user), &MASTER_KEY)
+    let dek = get_or_create_user_dek(&db.pool, &user_id(&user), &keyring())
         .await
         .expect("issue dek");
     let nonce = generate_nonce();
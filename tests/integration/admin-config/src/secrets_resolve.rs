@@ -5,6 +5,7 @@ use systemprompt_web_admin::repositories::secrets::secret_audit::{
     insert_audit_entry, list_audit_log,
 };
 use systemprompt_web_admin::repositories::secrets::secret_crypto::{encrypt, generate_nonce};
+use systemprompt_web_admin::repositories::secrets::secret_keyring::MasterKeyring;
 use systemprompt_web_admin::repositories::secrets::secret_keys::get_or_create_user_dek;
 use systemprompt_web_admin::repositories::secrets::secret_resolve::{
     create_resolution_token, resolve_secrets_for_plugin, validate_and_consume_token,
@@ -15,9 +16,13 @@ use crate::tempdb::TempDb;
 
 const MASTER_KEY: [u8; 32] = [7u8; 32];
 
+fn keyring() -> MasterKeyring {
+    MasterKeyring::new(MASTER_KEY, [])
+}
+
 // Stores `value` as a sealed secret the way the handler path would.
 async fn store_secret(pool: &sqlx::PgPool, user: &str, plugin: &str, name: &str, value: &str) {
-    let dek = get_or_create_user_dek(pool, &user_id(user), &MASTER_KEY)
+    let dek = get_or_create_user_dek(pool, &user_id(user), &keyring())
         .await
         .expect("issue dek");
     let nonce = generate_nonce();
@@ -192,7 +197,7 @@ async fn resolve_secrets_for_plugin_returns_only_that_plugins_secrets() {
     store_secret(&db.pool, &user, "plug-a", "A_TOKEN", "alpha").await;
     store_secret(&db.pool, &user, "plug-b", "B_TOKEN", "beta").await;
 
-    let resolved = resolve_secrets_for_plugin(&db.pool, &user_id(&user), "plug-a", &MASTER_KEY)
+    let resolved = resolve_secrets_for_plugin(&db.pool, &user_id(&user), "plug-a", &keyring())
         .await
         .expect("resolve secrets");
 
@@ -211,7 +216,7 @@ async fn resolve_secrets_for_plugin_audits_the_access() {
     insert_user(&db.pool, &user).await;
     store_secret(&db.pool, &user, "plug", "A_TOKEN", "alpha").await;
 
-    resolve_secrets_for_plugin(&db.pool, &user_id(&user), "plug", &MASTER_KEY)
+    resolve_secrets_for_plugin(&db.pool, &user_id(&user), "plug", &keyring())
         .await
         .expect("resolve secrets");
 
@@ -236,7 +241,7 @@ async fn resolve_secrets_for_plugin_skips_unsealed_rows() {
     insert_user(&db.pool, &user).await;
     insert_secret_env_var(&db.pool, &user, "plug", "LEGACY", "plaintext").await;
 
-    let resolved = resolve_secrets_for_plugin(&db.pool, &user_id(&user), "plug", &MASTER_KEY)
+    let resolved = resolve_secrets_for_plugin(&db.pool, &user_id(&user), "plug", &keyring())
         .await
         .expect("resolve secrets");
 
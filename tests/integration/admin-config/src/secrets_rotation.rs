@@ -0,0 +1,275 @@
+//! `master_key_rotation` — moving every data encryption key onto the current
+//! master key in resumable batches, retiring the keys it replaced, and the
+//! audit trail the operation leaves.
+
+use std::collections::HashMap;
+
+use systemprompt::identifiers::UserId;
+use systemprompt_web_admin::master_key_rotation::{RotationLimits, rotate};
+use systemprompt_web_admin::repositories::secrets::secret_crypto::{encrypt, generate_nonce};
+use systemprompt_web_admin::repositories::secrets::secret_keyring::{MasterKeyring, master_key_id};
+use systemprompt_web_admin::repositories::secrets::secret_keys::get_or_create_user_dek;
+use systemprompt_web_admin::repositories::secrets::secret_resolve::resolve_secrets_for_plugin;
+
+use crate::fixtures::{insert_secret_env_var, insert_user, unique, user_id};
+use crate::tempdb::TempDb;
+
+const OLD_KEY: [u8; 32] = [7u8; 32];
+const NEW_KEY: [u8; 32] = [8u8; 32];
+const STRAY_KEY: [u8; 32] = [9u8; 32];
+
+fn before() -> MasterKeyring {
+    MasterKeyring::new(OLD_KEY, [])
+}
+
+fn after() -> MasterKeyring {
+    MasterKeyring::new(NEW_KEY, [OLD_KEY])
+}
+
+fn actor() -> UserId {
+    UserId::new("system".to_owned())
+}
+
+// Seeds a user with one secret sealed under a DEK wrapped by `keyring`.
+async fn seed_user(pool: &sqlx::PgPool, keyring: &MasterKeyring) -> String {
+    let user = unique("u");
+    insert_user(pool, &user).await;
+    let dek = get_or_create_user_dek(pool, &user_id(&user), keyring)
+        .await
+        .expect("issue dek");
+    let nonce = generate_nonce();
+    let sealed = encrypt(&dek, &nonce, b"s3cret").expect("seal value");
+    let id = insert_secret_env_var(pool, &user, "plug", "API_TOKEN", "").await;
+    sqlx::query(
+        "UPDATE plugin_env_vars SET encrypted_value = $1, value_nonce = $2, key_version = 1
+         WHERE id = $3",
+    )
+    .bind(sealed)
+    .bind(nonce.to_vec())
+    .bind(&id)
+    .execute(pool)
+    .await
+    .expect("store sealed value");
+    user
+}
+
+async fn resolves_under(pool: &sqlx::PgPool, user: &str, keyring: &MasterKeyring) -> bool {
+    resolve_secrets_for_plugin(pool, &user_id(user), "plug", keyring)
+        .await
+        .is_ok_and(|secrets: HashMap<String, String>| {
+            secrets.get("API_TOKEN").map(String::as_str) == Some("s3cret")
+        })
+}
+
+async fn events(pool: &sqlx::PgPool) -> Vec<(String, String)> {
+    sqlx::query_as::<_, (String, String)>(
+        "SELECT key_id, event FROM master_key_events ORDER BY created_at, event",
+    )
+    .fetch_all(pool)
+    .await
+    .expect("list master key events")
+}
+
+fn limits(batch_size: i64, max_batches: usize) -> RotationLimits {
+    RotationLimits {
+        batch_size,
+        max_batches,
+    }
+}
+
+#[tokio::test]
+async fn rotate_rewraps_in_batches_and_resumes_on_the_next_run() {
+    let Some(db) = TempDb::create().await else {
+        return;
+    };
+    let mut users = Vec::new();
+    for _ in 0..3 {
+        users.push(seed_user(&db.pool, &before()).await);
+    }
+
+    let first = rotate(&db.pool, after(), &actor(), limits(1, 2))
+        .await
+        .expect("first run");
+    assert_eq!(first.rewrapped, 2);
+    assert_eq!(first.remaining, 1);
+    assert!(first.retired.is_empty(), "old key is still in use");
+
+    let second = rotate(&db.pool, after(), &actor(), limits(1, 2))
+        .await
+        .expect("second run");
+    assert_eq!(
+        second.rotation_id, first.rotation_id,
+        "same rotation resumed"
+    );
+    assert_eq!(second.rewrapped, 1);
+    assert_eq!(second.remaining, 0);
+    assert_eq!(second.retired, vec![master_key_id(&OLD_KEY)]);
+
+    let new_only = MasterKeyring::new(NEW_KEY, []);
+    for user in &users {
+        assert!(resolves_under(&db.pool, user, &new_only).await);
+    }
+
+    db.cleanup().await;
+}
+
+#[tokio::test]
+async fn a_dek_wrapped_before_keys_were_versioned_is_rewrapped() {
+    let Some(db) = TempDb::create().await else {
+        return;
+    };
+    let user = seed_user(&db.pool, &before()).await;
+    sqlx::query("UPDATE user_encryption_keys SET master_key_id = NULL WHERE user_id = $1")
+        .bind(&user)
+        .execute(&*db.pool)
+        .await
+        .expect("clear key id");
+    assert!(resolves_under(&db.pool, &user, &before()).await);
+
+    let outcome = rotate(&db.pool, after(), &actor(), RotationLimits::default())
+        .await
+        .expect("rotate");
+
+    assert_eq!(outcome.rewrapped, 1);
+    assert!(resolves_under(&db.pool, &user, &MasterKeyring::new(NEW_KEY, [])).await);
+
+    db.cleanup().await;
+}
+
+#[tokio::test]
+async fn a_dek_no_configured_key_opens_holds_retirement_back() {
+    let Some(db) = TempDb::create().await else {
+        return;
+    };
+    seed_user(&db.pool, &before()).await;
+    let stray = seed_user(&db.pool, &MasterKeyring::new(STRAY_KEY, [])).await;
+
+    let stalled = rotate(&db.pool, after(), &actor(), RotationLimits::default())
+        .await
+        .expect("rotate");
+    assert_eq!(stalled.rewrapped, 1);
+    assert_eq!(stalled.failed, 1);
+    assert_eq!(stalled.remaining, 1);
+    assert!(stalled.retired.is_empty());
+    assert!(
+        events(&db.pool)
+            .await
+            .iter()
+            .any(|(_, event)| event == "rewrap_failed")
+    );
+    let (detail, dek_id): (serde_json::Value, String) = sqlx::query_as(
+        "SELECT e.detail, k.id FROM master_key_events e, user_encryption_keys k
+         WHERE e.event = 'rewrap_failed' AND k.user_id = $1",
+    )
+    .bind(&stray)
+    .fetch_one(&*db.pool)
+    .await
+    .expect("read the failure event");
+    assert_eq!(detail["dek_id"], dek_id.as_str());
+    assert!(
+        !detail.to_string().contains(&stray),
+        "the audit trail names the DEK, not its user: {detail}"
+    );
+
+    let fixed = MasterKeyring::new(NEW_KEY, [OLD_KEY, STRAY_KEY]);
+    let finished = rotate(&db.pool, fixed, &actor(), RotationLimits::default())
+        .await
+        .expect("rotate with the missing key");
+    assert_eq!(finished.rotation_id, stalled.rotation_id);
+    assert_eq!(finished.remaining, 0);
+    assert_eq!(finished.retired.len(), 2);
+
+    db.cleanup().await;
+}
+
+#[tokio::test]
+async fn a_retired_key_is_not_used_even_while_still_configured() {
+    let Some(db) = TempDb::create().await else {
+        return;
+    };
+    seed_user(&db.pool, &before()).await;
+    rotate(&db.pool, after(), &actor(), RotationLimits::default())
+        .await
+        .expect("complete rotation");
+
+    let late = seed_user(&db.pool, &before()).await;
+    let outcome = rotate(&db.pool, after(), &actor(), RotationLimits::default())
+        .await
+        .expect("rotate again");
+
+    assert_eq!(outcome.failed, 1, "the retired key must not open the DEK");
+    assert!(!resolves_under(&db.pool, &late, &MasterKeyring::new(NEW_KEY, [])).await);
+
+    db.cleanup().await;
+}
+
+#[tokio::test]
+async fn the_rotation_is_audited_from_start_to_retirement() {
+    let Some(db) = TempDb::create().await else {
+        return;
+    };
+    seed_user(&db.pool, &before()).await;
+
+    rotate(&db.pool, after(), &actor(), RotationLimits::default())
+        .await
+        .expect("rotate");
+
+    let new_id = master_key_id(&NEW_KEY);
+    let old_id = master_key_id(&OLD_KEY);
+    let events = events(&db.pool).await;
+    for expected in [
+        (new_id.as_str(), "registered"),
+        (old_id.as_str(), "registered"),
+        (new_id.as_str(), "rotation_started"),
+        (new_id.as_str(), "batch_rewrapped"),
+        (new_id.as_str(), "rotation_completed"),
+        (old_id.as_str(), "retired"),
+    ] {
+        assert!(
+            events
+                .iter()
+                .any(|(k, e)| (k.as_str(), e.as_str()) == expected),
+            "missing {expected:?} in {events:?}"
+        );
+    }
+    let status = sqlx::query_scalar::<_, String>(
+        "SELECT status FROM master_key_rotations WHERE target_key_id = $1",
+    )
+    .bind(&new_id)
+    .fetch_one(&*db.pool)
+    .await
+    .expect("read rotation");
+    assert_eq!(status, "completed");
+
+    db.cleanup().await;
+}
+
+#[tokio::test]
+async fn a_current_key_older_than_a_year_is_flagged_overdue_once() {
+    let Some(db) = TempDb::create().await else {
+        return;
+    };
+    let fresh = rotate(&db.pool, before(), &actor(), RotationLimits::default())
+        .await
+        .expect("register key");
+    assert!(!fresh.flagged_overdue);
+
+    sqlx::query(
+        "UPDATE master_keys SET first_seen_at = NOW() - INTERVAL '400 days' WHERE key_id = $1",
+    )
+    .bind(master_key_id(&OLD_KEY))
+    .execute(&*db.pool)
+    .await
+    .expect("age the key");
+
+    let first = rotate(&db.pool, before(), &actor(), RotationLimits::default())
+        .await
+        .expect("first check");
+    let second = rotate(&db.pool, before(), &actor(), RotationLimits::default())
+        .await
+        .expect("second check");
+    assert!(first.flagged_overdue);
+    assert!(!second.flagged_overdue, "flagged once, not on every run");
+
+    db.cleanup().await;
+}
@@ -0,0 +1,106 @@
+//! `warehouse` — which rows a window reads: requests once they complete,
+//! session analyses again after every rewrite.
+
+use chrono::{Duration, DurationRound, Utc};
+use systemprompt_web_admin::repositories::warehouse::list_warehouse_rows;
+use systemprompt_web_admin::warehouse::WarehouseTable;
+
+use crate::fixtures::{RequestSeed, insert_request, insert_user, unique};
+use crate::tempdb::TempDb;
+
+fn ids(rows: &[serde_json::Value], key: &str) -> Vec<String> {
+    rows.iter()
+        .filter_map(|r| r.get(key).and_then(|v| v.as_str()).map(ToOwned::to_owned))
+        .collect()
+}
+
+#[tokio::test]
+async fn a_request_is_read_in_the_window_it_completed_in() {
+    let Some(db) = TempDb::create().await else {
+        return;
+    };
+    let user = unique("u");
+    insert_user(&db.pool, &user).await;
+    let start = Utc::now().duration_trunc(Duration::hours(1)).expect("hour") - Duration::days(1);
+    let (pending, done) = (unique("req"), unique("req"));
+    let mut seed = RequestSeed::new(&pending, &user, start);
+    seed.status = "pending";
+    insert_request(&db.pool, &seed).await;
+    insert_request(&db.pool, &RequestSeed::new(&done, &user, start)).await;
+    sqlx::query("UPDATE ai_requests SET completed_at = $2 WHERE id = $1")
+        .bind(&done)
+        .bind(start + Duration::minutes(30))
+        .execute(&*db.pool)
+        .await
+        .expect("complete");
+
+    let created_window = list_warehouse_rows(
+        &db.pool,
+        WarehouseTable::AiRequests,
+        start,
+        start + Duration::minutes(20),
+    )
+    .await
+    .expect("rows");
+    assert!(ids(&created_window, "id").is_empty());
+    let completed_window = list_warehouse_rows(
+        &db.pool,
+        WarehouseTable::AiRequests,
+        start + Duration::minutes(20),
+        start + Duration::hours(1),
+    )
+    .await
+    .expect("rows");
+    assert_eq!(ids(&completed_window, "id"), vec![done]);
+
+    db.cleanup().await;
+}
+
+#[tokio::test]
+async fn a_reanalysed_session_is_read_again_in_the_window_of_the_rewrite() {
+    let Some(db) = TempDb::create().await else {
+        return;
+    };
+    let user = unique("u");
+    let session = unique("sess");
+    let start = Utc::now().duration_trunc(Duration::hours(1)).expect("hour") - Duration::days(1);
+    sqlx::query(
+        "INSERT INTO session_analyses (session_id, user_id, title, created_at, updated_at)
+         VALUES ($1, $2, 'first', $3, $3)",
+    )
+    .bind(&session)
+    .bind(&user)
+    .bind(start)
+    .execute(&*db.pool)
+    .await
+    .expect("analyse");
+    let first = list_warehouse_rows(
+        &db.pool,
+        WarehouseTable::SessionAnalyses,
+        start,
+        start + Duration::hours(1),
+    )
+    .await
+    .expect("rows");
+    assert_eq!(ids(&first, "session_id"), vec![session.clone()]);
+
+    sqlx::query(
+        "UPDATE session_analyses SET title = 'second', updated_at = $2 WHERE session_id = $1",
+    )
+    .bind(&session)
+    .bind(start + Duration::hours(2))
+    .execute(&*db.pool)
+    .await
+    .expect("reanalyse");
+    let rewrite = list_warehouse_rows(
+        &db.pool,
+        WarehouseTable::SessionAnalyses,
+        start + Duration::hours(1),
+        start + Duration::hours(3),
+    )
+    .await
+    .expect("rows");
+    assert_eq!(ids(&rewrite, "title"), vec!["second".to_owned()]);
+
+    db.cleanup().await;
+}
@@ -4,6 +4,7 @@
 use systemprompt_web_admin::repositories::analytics::request_stats::{
     LATENCY_BIN_EDGES_MS, get_request_stats, list_latency_histogram, list_request_timeseries,
 };
+use systemprompt_web_admin::util::activity_scope::ActivityScope;
 
 use crate::fixtures::{
     DecisionSpec, RequestSpec, insert_decision, insert_request, insert_session, insert_user,
@@ -18,7 +19,7 @@ async fn get_request_stats_is_all_zeroes_in_an_empty_window() {
         return;
     };
 
-    let stats = get_request_stats(&db.pool, narrow_window())
+    let stats = get_request_stats(&db.pool, narrow_window(), ActivityScope::default())
         .await
         .expect("query succeeds");
 
@@ -53,7 +54,7 @@ async fn get_request_stats_derives_error_and_deny_rates() {
     denial.decision = "deny";
     insert_decision(&db.pool, &denial).await;
 
-    let stats = get_request_stats(&db.pool, narrow_window())
+    let stats = get_request_stats(&db.pool, narrow_window(), ActivityScope::default())
         .await
         .expect("query succeeds");
 
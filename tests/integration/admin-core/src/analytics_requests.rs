@@ -10,6 +10,7 @@ use systemprompt_web_admin::repositories::analytics::requests::{
     list_requests_by_model, list_requests_by_provider, list_requests_by_status,
     list_requests_paged,
 };
+use systemprompt_web_admin::util::activity_scope::ActivityScope;
 
 use crate::fixtures::{
     DecisionSpec, EventSpec, RequestSpec, insert_decision, insert_event, insert_request,
@@ -331,7 +332,7 @@ async fn list_requests_by_model_rolls_up_the_windows_traffic() {
         insert_request(&db.pool, &spec).await;
     }
 
-    let rows = list_requests_by_model(&db.pool, narrow_window())
+    let rows = list_requests_by_model(&db.pool, narrow_window(), ActivityScope::default())
         .await
         .expect("query succeeds");
 
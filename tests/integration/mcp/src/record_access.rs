@@ -2,7 +2,7 @@
 //! `entity_type`/`entity_name` by action.
 
 use systemprompt::identifiers::UserId;
-use systemprompt_mcp_shared::record_mcp_access;
+use systemprompt_mcp_shared::{McpAccess, record_mcp_access};
 
 use crate::common::TempDb;
 
@@ -17,8 +17,7 @@ async fn used_action_records_tool_scoped_row() {
     record_mcp_access(
         &db.pool,
         &UserId::new("user-1"),
-        "systemprompt",
-        "list_skills",
+        McpAccess::new("systemprompt", "list_skills"),
         "used",
     )
     .await;
@@ -45,8 +44,7 @@ async fn authenticated_action_records_server_scoped_row() {
     record_mcp_access(
         &db.pool,
         &UserId::new("user-2"),
-        "systemprompt",
-        "list_skills",
+        McpAccess::new("systemprompt", "list_skills"),
         "authenticated",
     )
     .await;
@@ -3,7 +3,7 @@
 //! row, no panic) otherwise — never attributing a rejection to an arbitrary
 //! user.
 
-use systemprompt_mcp_shared::{MAX_REASON_LEN, record_mcp_access_rejected};
+use systemprompt_mcp_shared::{MAX_REASON_LEN, McpAccess, record_mcp_access_rejected};
 
 use crate::common::TempDb;
 
@@ -18,7 +18,7 @@ async fn drops_row_when_no_anonymous_principal_exists() {
     };
     // No users at all — the anonymous lookup returns None.
 
-    record_mcp_access_rejected(&db.pool, SERVER, TOOL, "scope denied").await;
+    record_mcp_access_rejected(&db.pool, McpAccess::new(SERVER, TOOL), "scope denied").await;
 
     let rows = db.mcp_rows(SERVER).await;
     assert!(
@@ -38,7 +38,7 @@ async fn does_not_fall_back_to_an_arbitrary_user() {
     // A real user exists, but NOT an anonymous one — the row must still drop.
     db.insert_user("real-user", "person@example.com").await;
 
-    record_mcp_access_rejected(&db.pool, SERVER, TOOL, "blocklist hit").await;
+    record_mcp_access_rejected(&db.pool, McpAccess::new(SERVER, TOOL), "blocklist hit").await;
 
     let rows = db.mcp_rows(SERVER).await;
     assert!(
@@ -57,7 +57,7 @@ async fn attributes_row_to_anonymous_principal_when_present() {
     };
     db.insert_user("anon-1", "fp_abc@anonymous.local").await;
 
-    record_mcp_access_rejected(&db.pool, SERVER, TOOL, "scope denied").await;
+    record_mcp_access_rejected(&db.pool, McpAccess::new(SERVER, TOOL), "scope denied").await;
 
     let rows = db.mcp_rows(SERVER).await;
     assert_eq!(rows.len(), 1, "expected one attributed rejection row");
@@ -79,7 +79,7 @@ async fn long_reason_is_truncated_in_the_description() {
     db.insert_user("anon-2", "fp_def@anonymous.local").await;
 
     let reason = "x".repeat(MAX_REASON_LEN + 40);
-    record_mcp_access_rejected(&db.pool, SERVER, TOOL, &reason).await;
+    record_mcp_access_rejected(&db.pool, McpAccess::new(SERVER, TOOL), &reason).await;
 
     let rows = db.mcp_rows(SERVER).await;
     assert_eq!(rows.len(), 1);
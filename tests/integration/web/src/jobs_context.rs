@@ -34,9 +34,9 @@ use crate::tempdb::TempDb;
 // organisation. Asserting the total pins that no stage is silently dropped.
 const PIPELINE_STAGES: u64 = 11;
 
-// A 32-byte key, hex-encoded, so `load_master_key` accepts it. Under nextest
-// each test is its own process and sets this before spawning anything, so
-// there is no concurrent reader of the environment.
+// A 32-byte key, hex-encoded, so `load_master_keyring` accepts it. Under
+// nextest each test is its own process and sets this before spawning anything,
+// so there is no concurrent reader of the environment.
 fn set_master_key() {
     unsafe {
         std::env::set_var(
@@ -245,7 +245,7 @@ impl Harness {
         let path = self.paths.system().content_config();
         std::fs::create_dir_all(path.parent().expect("content config has a parent"))
             .expect("create the content config directory");
-        std::fs::write(&path, yaml).expect("write the content config");
+        std::fs::write(path, yaml).expect("write the content config");
     }
 
     async fn cleanup(self) {
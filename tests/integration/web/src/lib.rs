@@ -3,7 +3,10 @@
 //! repositories (content CRUD and orphan pruning, campaign-link lookup, click
 //! tracking and its counters, content search) and the service layer over them,
 //! markdown ingestion from a real directory tree, the content-analytics job's
-//! rollups, and construction of the two bundled MCP servers.
+//! rollups, construction of the two bundled MCP servers, the published content
+//! the `systemprompt` server offers as resources, the skills and content it
+//! lets each caller see, the personal access tokens its `--stdio` mode authenticates
+//! with, and the tool-call captures the MCP servers put on the Trace Explorer.
 //!
 //! Every test runs against its OWN throwaway database created on the server
 //! named by `DATABASE_URL`, with the real extension schema installed, so the
@@ -30,12 +33,24 @@ mod link_analytics_repository;
 #[cfg(test)]
 mod link_repository;
 #[cfg(test)]
+mod mcp_capture;
+#[cfg(test)]
 mod mcp_cli;
 #[cfg(test)]
+mod mcp_content;
+#[cfg(test)]
+mod mcp_content_access;
+#[cfg(test)]
 mod mcp_dispatch;
 #[cfg(test)]
+mod mcp_policy;
+#[cfg(test)]
 mod mcp_server;
 #[cfg(test)]
+mod mcp_skill_access;
+#[cfg(test)]
+mod mcp_stdio;
+#[cfg(test)]
 mod search_repository;
 #[cfg(test)]
 mod site_docs_db;
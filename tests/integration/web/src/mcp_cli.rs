@@ -5,8 +5,9 @@
 //! written into a tempdir makes every branch of `cli::execute` and the handler
 //! above it reachable without running the real CLI against the machine's
 //! profile: the spawn failure, the argument-parse failure, the non-zero exit,
-//! and both artifact arms (stdout that deserialises into a `CliArtifact` and
-//! stdout that does not).
+//! both artifact arms (stdout that deserialises into a `CliArtifact` and
+//! stdout that does not), and the limits a call runs under: the timeout, the
+//! client's cancellation, and stderr forwarded as progress.
 //!
 //! The environment is process-global, so these tests rely on nextest's
 //! process-per-test execution: each one owns its process and cannot race
@@ -17,23 +18,28 @@ use std::sync::Arc;
 
 use rmcp::model::CallToolRequestParams;
 use sqlx::PgPool;
-use systemprompt::database::Database;
+use systemprompt::database::{Database, DbPool};
 use systemprompt::identifiers::{AgentName, ContextId, SessionId, TraceId};
 use systemprompt::mcp::repository::ToolUsageRepository;
 use systemprompt::mcp::{McpArtifactRepository, McpToolExecutor};
 use systemprompt::models::artifacts::{CliArtifact, TextArtifact};
 use systemprompt::models::execution::context::RequestContext as SysRequestContext;
 use systemprompt_mcp_agent::filter_hallucinated_args;
+use systemprompt_mcp_agent::policy::CommandTimeouts;
+use systemprompt_mcp_agent::server::tool::CallControl;
 
 use crate::tempdb::TempDb;
 
-fn executor(pool: &Arc<PgPool>) -> McpToolExecutor {
-    let db_pool = Arc::new(Database::from_pools(
+fn database(pool: &Arc<PgPool>) -> DbPool {
+    Arc::new(Database::from_pools(
         Arc::clone(pool),
         Some(Arc::clone(pool)),
-    ));
-    let usage = Arc::new(ToolUsageRepository::new(&db_pool).expect("tool usage repository"));
-    let artifacts = Arc::new(McpArtifactRepository::new(&db_pool).expect("artifact repository"));
+    ))
+}
+
+fn executor(db_pool: &DbPool) -> McpToolExecutor {
+    let usage = Arc::new(ToolUsageRepository::new(db_pool).expect("tool usage repository"));
+    let artifacts = Arc::new(McpArtifactRepository::new(db_pool).expect("artifact repository"));
     McpToolExecutor::new(usage, artifacts, "systemprompt")
 }
 
@@ -76,15 +82,26 @@ fn call(command: &str) -> CallToolRequestParams {
 }
 
 async fn run(db: &TempDb, command: &str) -> Result<rmcp::model::CallToolResult, rmcp::ErrorData> {
-    let executor = executor(&db.pool);
+    run_with(db, command, &CallControl::default()).await
+}
+
+async fn run_with(
+    db: &TempDb,
+    command: &str,
+    control: &CallControl,
+) -> Result<rmcp::model::CallToolResult, rmcp::ErrorData> {
+    let db_pool = database(&db.pool);
+    let executor = executor(&db_pool);
     let request = call(command);
     let profile = client();
     systemprompt_mcp_agent::server::tool::dispatch_tool(
         &systemprompt_mcp_agent::server::tool::Dispatch {
+            db_pool: &db_pool,
             executor: &executor,
             request: &request,
             request_context: &request_context(),
             client: &profile,
+            control,
         },
         "systemprompt",
         "test-bearer-token",
@@ -366,3 +383,126 @@ async fn without_the_env_override_the_path_comes_from_the_profile() {
 
     db.cleanup().await;
 }
+
+fn one_second_limit() -> CallControl {
+    CallControl {
+        timeouts: CommandTimeouts {
+            default_secs: 1,
+            overrides: Vec::new(),
+        },
+        ..CallControl::default()
+    }
+}
+
+// A process that was killed is either gone or a zombie awaiting its reaper.
+fn is_running(pid: &str) -> bool {
+    std::fs::read_to_string(format!("/proc/{pid}/stat")).is_ok_and(|stat| {
+        !stat
+            .split_whitespace()
+            .nth(2)
+            .is_some_and(|state| state == "Z")
+    })
+}
+
+#[tokio::test]
+async fn a_command_past_its_timeout_is_stopped_with_everything_it_started() {
+    let Some(db) = TempDb::create().await else {
+        return;
+    };
+    let dir = tempfile::tempdir().expect("tempdir");
+    let pid_file = dir.path().join("grandchild.pid");
+    fake_cli(
+        &dir,
+        &format!(
+            "sleep 30 &\necho $! > {}\necho 'still waiting on the registry' >&2\nsleep 30",
+            pid_file.display()
+        ),
+    );
+
+    let started = std::time::Instant::now();
+    let error = run_with(&db, "build core", &one_second_limit())
+        .await
+        .expect_err("a command past its limit fails");
+
+    assert!(started.elapsed() < std::time::Duration::from_secs(10));
+    assert!(
+        error.message.contains("timed out after 1s"),
+        "the limit is named: {}",
+        error.message
+    );
+    let artifact = error.data.expect("the timeout carries an artifact");
+    let body = artifact
+        .pointer("/content")
+        .and_then(|v| v.as_str())
+        .expect("the artifact is a text artifact");
+    assert!(
+        body.contains("still waiting on the registry"),
+        "the last stderr lines are kept: {body}"
+    );
+    let grandchild = std::fs::read_to_string(&pid_file).expect("the script recorded its child");
+    assert!(
+        !is_running(grandchild.trim()),
+        "the background process the command started is killed with it"
+    );
+
+    db.cleanup().await;
+}
+
+#[tokio::test]
+async fn a_cancelled_call_stops_the_command() {
+    let Some(db) = TempDb::create().await else {
+        return;
+    };
+    let dir = tempfile::tempdir().expect("tempdir");
+    fake_cli(&dir, "sleep 30");
+    let control = CallControl::default();
+    let cancel = control.cancel.clone();
+    std::thread::spawn(move || {
+        std::thread::sleep(std::time::Duration::from_millis(300));
+        cancel.cancel();
+    });
+
+    let started = std::time::Instant::now();
+    let error = run_with(&db, "infra logs stream", &control)
+        .await
+        .expect_err("a cancelled call fails");
+
+    assert!(started.elapsed() < std::time::Duration::from_secs(10));
+    assert!(
+        error.message.contains("cancelled by the client"),
+        "{}",
+        error.message
+    );
+
+    db.cleanup().await;
+}
+
+#[tokio::test]
+async fn stderr_lines_are_forwarded_as_progress_while_the_command_runs() {
+    let Some(db) = TempDb::create().await else {
+        return;
+    };
+    let dir = tempfile::tempdir().expect("tempdir");
+    fake_cli(
+        &dir,
+        "echo 'compiling 1/2' >&2\necho 'compiling 2/2' >&2\necho done",
+    );
+    let (sender, mut lines) = tokio::sync::mpsc::unbounded_channel();
+    let control = CallControl {
+        progress: Some(sender),
+        ..CallControl::default()
+    };
+
+    run_with(&db, "build core", &control)
+        .await
+        .expect("a zero-exit CLI call succeeds");
+    drop(control);
+
+    let mut forwarded = Vec::new();
+    while let Some(line) = lines.recv().await {
+        forwarded.push(line);
+    }
+    assert_eq!(forwarded, vec!["compiling 1/2", "compiling 2/2"]);
+
+    db.cleanup().await;
+}
@@ -0,0 +1,122 @@
+//! The `systemprompt` MCP server's content resources against a real schema.
+//!
+//! Only public rows from the offered sources whose publish date has passed are
+//! listed or read, and the `markdown_content` trigger announces every write on
+//! the `content_changes` channel the server listens on.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use sqlx::PgPool;
+use sqlx::postgres::PgListener;
+use systemprompt::identifiers::SourceId;
+use systemprompt_mcp_agent::repositories::content::{
+    RESOURCE_SOURCES, find_published_content, list_published_content,
+};
+use systemprompt_web_content::repository::ContentRepository;
+
+use crate::fixtures::content_params;
+use crate::tempdb::TempDb;
+
+async fn seed(pool: &Arc<PgPool>, source: &str, slug: &str) {
+    ContentRepository::new(Arc::clone(pool))
+        .create(&content_params(slug, &SourceId::new(source.to_owned())))
+        .await
+        .expect("seed a content row");
+}
+
+async fn seed_fixture(pool: &Arc<PgPool>) {
+    seed(pool, "documentation", "getting-started").await;
+    seed(pool, "blog", "launch").await;
+    seed(pool, "guides", "not-offered").await;
+    seed(pool, "documentation", "private").await;
+    seed(pool, "blog", "scheduled").await;
+    sqlx::query("UPDATE markdown_content SET public = false WHERE slug = 'private'")
+        .execute(&**pool)
+        .await
+        .expect("hide a row");
+    sqlx::query(
+        "UPDATE markdown_content SET published_at = NOW() + INTERVAL '1 day' \
+         WHERE slug = 'scheduled'",
+    )
+    .execute(&**pool)
+    .await
+    .expect("schedule a row");
+}
+
+#[tokio::test]
+async fn only_published_rows_from_offered_sources_are_listed() {
+    let Some(db) = TempDb::create().await else {
+        return;
+    };
+    seed_fixture(&db.pool).await;
+
+    let rows = list_published_content(&db.pool, &RESOURCE_SOURCES)
+        .await
+        .expect("list content");
+
+    let listed: Vec<(&str, &str)> = rows
+        .iter()
+        .map(|row| (row.source_id.as_str(), row.slug.as_str()))
+        .collect();
+    assert_eq!(
+        listed,
+        vec![("blog", "launch"), ("documentation", "getting-started")]
+    );
+    assert_eq!(rows[0].title, "Title for launch");
+
+    db.cleanup().await;
+}
+
+#[tokio::test]
+async fn reading_returns_the_body_of_a_published_row_only() {
+    let Some(db) = TempDb::create().await else {
+        return;
+    };
+    seed_fixture(&db.pool).await;
+
+    let found = find_published_content(&db.pool, &SourceId::new("blog".to_owned()), "launch")
+        .await
+        .expect("read content")
+        .expect("a published row is found");
+    assert_eq!(found.body, "Body for launch");
+
+    for (source, slug) in [
+        ("documentation", "private"),
+        ("blog", "scheduled"),
+        ("documentation", "launch"),
+        ("blog", "missing"),
+    ] {
+        let found = find_published_content(&db.pool, &SourceId::new(source.to_owned()), slug)
+            .await
+            .expect("read content");
+        assert!(found.is_none(), "{source}/{slug} must not be readable");
+    }
+
+    db.cleanup().await;
+}
+
+#[tokio::test]
+async fn writing_content_notifies_the_content_changes_channel() {
+    let Some(db) = TempDb::create().await else {
+        return;
+    };
+    let mut listener = PgListener::connect_with(&db.pool)
+        .await
+        .expect("connect a listener");
+    listener
+        .listen("content_changes")
+        .await
+        .expect("listen on content_changes");
+
+    seed(&db.pool, "blog", "launch").await;
+
+    let notification = tokio::time::timeout(Duration::from_secs(10), listener.recv())
+        .await
+        .expect("a notification arrives")
+        .expect("receive the notification");
+    assert_eq!(notification.payload(), "markdown_content");
+
+    drop(listener);
+    db.cleanup().await;
+}
@@ -0,0 +1,122 @@
+//! Which content resources the `systemprompt` server lets a caller see.
+//!
+//! An item with no rules of its own takes the grant of the marketplaces the
+//! server ships, an item with rules keyed on its URI owns its decision, and
+//! outside any marketplace only an item granted on its own is visible.
+
+use std::collections::{BTreeMap, HashSet};
+use std::sync::Arc;
+
+use sqlx::PgPool;
+use systemprompt::database::{Database, DbPool};
+use systemprompt::identifiers::{MarketplaceId, UserId};
+use systemprompt::models::services::MarketplaceAccess;
+use systemprompt_mcp_agent::catalog::MarketplaceSkills;
+use systemprompt_mcp_agent::server::content_access::visible_content;
+use uuid::Uuid;
+
+use crate::tempdb::TempDb;
+
+const OPEN: &str = "content://documentation/getting-started";
+const ADMIN_ONLY: &str = "content://documentation/runbook";
+const GRANTED: &str = "content://blog/launch";
+
+fn database(pool: &Arc<PgPool>) -> DbPool {
+    Arc::new(Database::from_pools(
+        Arc::clone(pool),
+        Some(Arc::clone(pool)),
+    ))
+}
+
+fn open_marketplace() -> MarketplaceSkills {
+    MarketplaceSkills {
+        id: MarketplaceId::new("demo"),
+        access: MarketplaceAccess {
+            default_included: true,
+            roles: vec!["user".to_owned()],
+            attributes: BTreeMap::new(),
+            justification: None,
+        },
+        skills: Vec::new(),
+    }
+}
+
+async fn seed_user(pool: &PgPool) -> Uuid {
+    let id = Uuid::new_v4();
+    sqlx::query(
+        "INSERT INTO users (id, name, email, status, roles) VALUES ($1, $1, $1 || '@example.com', \
+         'active', ARRAY['user'])",
+    )
+    .bind(id.to_string())
+    .execute(pool)
+    .await
+    .expect("seed a user");
+    id
+}
+
+async fn add_rule(pool: &PgPool, uri: &str, rule_type: &str, rule_value: &str) {
+    sqlx::query(
+        "INSERT INTO access_control_entities (entity_type, entity_id, default_included, source)
+         VALUES ('skill', $1, false, 'test-fixture') ON CONFLICT DO NOTHING",
+    )
+    .bind(uri)
+    .execute(pool)
+    .await
+    .expect("insert the content entity");
+    sqlx::query(
+        "INSERT INTO access_control_rules (id, entity_type, entity_id, rule_type, rule_value, access)
+         VALUES ($1, 'skill', $2, $3, $4, 'allow')",
+    )
+    .bind(Uuid::new_v4().to_string())
+    .bind(uri)
+    .bind(rule_type)
+    .bind(rule_value)
+    .execute(pool)
+    .await
+    .expect("insert the allow rule");
+}
+
+fn uris() -> Vec<String> {
+    [OPEN, ADMIN_ONLY, GRANTED].map(str::to_owned).to_vec()
+}
+
+fn set(items: &[&str]) -> HashSet<String> {
+    items.iter().map(|&uri| uri.to_owned()).collect()
+}
+
+#[tokio::test]
+async fn content_takes_the_marketplace_grant_unless_it_has_rules_of_its_own() {
+    let Some(db) = TempDb::create().await else {
+        return;
+    };
+    let user = seed_user(&db.pool).await;
+    add_rule(&db.pool, ADMIN_ONLY, "role", "admin").await;
+    add_rule(&db.pool, GRANTED, "user", &user.to_string()).await;
+    let db_pool = database(&db.pool);
+    let user = UserId::new(user.to_string());
+
+    let visible = visible_content(&db_pool, &user, &[open_marketplace()], &uris())
+        .await
+        .expect("judge the content");
+    assert_eq!(visible, set(&[OPEN, GRANTED]));
+
+    db.cleanup().await;
+}
+
+#[tokio::test]
+async fn outside_any_marketplace_only_content_granted_on_its_own_is_visible() {
+    let Some(db) = TempDb::create().await else {
+        return;
+    };
+    let user = seed_user(&db.pool).await;
+    add_rule(&db.pool, GRANTED, "user", &user.to_string()).await;
+    let db_pool = database(&db.pool);
+    let user = UserId::new(user.to_string());
+
+    let visible = visible_content(&db_pool, &user, &[], &uris())
+        .await
+        .expect("judge the content");
+    assert_eq!(visible, set(&[GRANTED]));
+
+    db.cleanup().await;
+}
@@ -8,27 +8,32 @@
 //! is reachable here, including the unknown-tool arm.
 //!
 //! A live pool is needed because `McpToolExecutor` records every call through
-//! a `ToolUsageRepository` and persists the artifact it returns.
+//! a `ToolUsageRepository` and persists the artifact it returns, and because
+//! the typed tools query it directly.
 
 use std::sync::Arc;
 
 use rmcp::model::CallToolRequestParams;
 use sqlx::PgPool;
-use systemprompt::database::Database;
+use systemprompt::database::{Database, DbPool};
 use systemprompt::identifiers::{AgentName, ContextId, SessionId, TraceId};
 use systemprompt::mcp::repository::ToolUsageRepository;
 use systemprompt::mcp::{McpArtifactRepository, McpToolExecutor};
 use systemprompt::models::execution::context::RequestContext as SysRequestContext;
+use systemprompt_mcp_agent::server::tool::CallControl;
 
 use crate::tempdb::TempDb;
 
-fn executor(pool: &Arc<PgPool>, server_name: &str) -> McpToolExecutor {
-    let db_pool = Arc::new(Database::from_pools(
+fn database(pool: &Arc<PgPool>) -> DbPool {
+    Arc::new(Database::from_pools(
         Arc::clone(pool),
         Some(Arc::clone(pool)),
-    ));
-    let usage = Arc::new(ToolUsageRepository::new(&db_pool).expect("tool usage repository"));
-    let artifacts = Arc::new(McpArtifactRepository::new(&db_pool).expect("artifact repository"));
+    ))
+}
+
+fn executor(db_pool: &DbPool, server_name: &str) -> McpToolExecutor {
+    let usage = Arc::new(ToolUsageRepository::new(db_pool).expect("tool usage repository"));
+    let artifacts = Arc::new(McpArtifactRepository::new(db_pool).expect("artifact repository"));
     McpToolExecutor::new(usage, artifacts, server_name)
 }
 
@@ -54,16 +59,19 @@ async fn an_unknown_systemprompt_tool_points_the_caller_at_the_cli_skill() {
     let Some(db) = TempDb::create().await else {
         return;
     };
-    let executor = executor(&db.pool, "systemprompt");
+    let db_pool = database(&db.pool);
+    let executor = executor(&db_pool, "systemprompt");
 
     let request = call("not_a_tool", serde_json::json!({}));
     let profile = client();
     let error = systemprompt_mcp_agent::server::tool::dispatch_tool(
         &systemprompt_mcp_agent::server::tool::Dispatch {
+            db_pool: &db_pool,
             executor: &executor,
             request: &request,
             request_context: &request_context(),
             client: &profile,
+            control: &CallControl::default(),
         },
         "not_a_tool",
         "unused-token",
@@ -80,6 +88,105 @@ async fn an_unknown_systemprompt_tool_points_the_caller_at_the_cli_skill() {
     db.cleanup().await;
 }
 
+async fn dispatch_typed(
+    db: &TempDb,
+    tool: &'static str,
+    arguments: serde_json::Value,
+) -> Result<rmcp::model::CallToolResult, rmcp::ErrorData> {
+    let db_pool = database(&db.pool);
+    let executor = executor(&db_pool, "systemprompt");
+    let request = call(tool, arguments);
+    let profile = client();
+    systemprompt_mcp_agent::server::tool::dispatch_tool(
+        &systemprompt_mcp_agent::server::tool::Dispatch {
+            db_pool: &db_pool,
+            executor: &executor,
+            request: &request,
+            request_context: &request_context(),
+            client: &profile,
+            control: &CallControl::default(),
+        },
+        tool,
+        "unused-token",
+    )
+    .await
+}
+
+async fn insert_decision(pool: &PgPool, id: &str, decision: &str) {
+    sqlx::query(
+        "INSERT INTO governance_decisions (
+             id, user_id, session_id, context_id, tool_name, decision, policy, reason,
+             actor_kind, actor_id)
+         VALUES ($1, 'typed-user', 'typed-session', 'typed-context', 'mcp__systemprompt__x',
+                 $2, 'scope_check', 'typed tool fixture', 'user', 'typed-user')",
+    )
+    .bind(id)
+    .bind(decision)
+    .execute(pool)
+    .await
+    .expect("insert governance decision");
+}
+
+#[tokio::test]
+async fn the_typed_decision_lookup_answers_with_a_filtered_table() {
+    let Some(db) = TempDb::create().await else {
+        return;
+    };
+    insert_decision(&db.pool, "typed-allow", "allow").await;
+    insert_decision(&db.pool, "typed-deny", "deny").await;
+
+    let result = dispatch_typed(
+        &db,
+        "list_governance_decisions",
+        serde_json::json!({ "decision": "deny", "session_id": "typed-session" }),
+    )
+    .await
+    .expect("the lookup succeeds");
+
+    let items = result
+        .structured_content
+        .as_ref()
+        .and_then(|v| v.pointer("/items"))
+        .and_then(|v| v.as_array())
+        .expect("the artifact is a table");
+    assert_eq!(items.len(), 1, "only the deny matches: {items:?}");
+    assert_eq!(items[0]["decision_id"], "typed-deny");
+    assert_eq!(items[0]["policy"], "scope_check");
+
+    db.cleanup().await;
+}
+
+#[tokio::test]
+async fn the_typed_tools_reject_arguments_outside_their_schema() {
+    let Some(db) = TempDb::create().await else {
+        return;
+    };
+
+    let error = dispatch_typed(
+        &db,
+        "list_governance_decisions",
+        serde_json::json!({ "decision": "maybe" }),
+    )
+    .await
+    .expect_err("an outcome other than allow or deny is refused");
+    assert!(!error.message.is_empty());
+
+    let error = dispatch_typed(
+        &db,
+        "inspect_session",
+        serde_json::json!({ "session_id": "no-such-session" }),
+    )
+    .await
+    .expect_err("a session with no footprint is reported, not rendered empty");
+    assert!(
+        error.message.contains("no-such-session"),
+        "the refusal names the session: {}",
+        error.message
+    );
+
+    db.cleanup().await;
+}
+
 // `SystempromptToolHandler::handle` is deliberately not driven here: it shells
 // out to the real `systemprompt` binary with the caller's bearer token, so a
 // test that reached it would be running the CLI against whatever profile the
@@ -0,0 +1,183 @@
+//! The `systemprompt` server's command policy, driven through `authorize`:
+//! the seam `call_tool` uses between authentication and dispatch, taking the
+//! policy explicitly so no profile is needed to find the rules file.
+//!
+//! A denied call must be refused before anything runs and leave a rejection
+//! row naming the rule that decided it; an allowed call hands that rule back
+//! for the `used` row.
+
+use std::sync::Arc;
+
+use rmcp::model::CallToolRequestParams;
+use sqlx::PgPool;
+use systemprompt::database::{Database, DbPool};
+use systemprompt::identifiers::{AgentName, ContextId, SessionId, TraceId};
+use systemprompt::models::auth::{AuthenticatedUser, Permission};
+use systemprompt::models::execution::context::RequestContext as SysRequestContext;
+use systemprompt_mcp_agent::policy::CommandPolicy;
+use systemprompt_mcp_agent::server::tool::authorize;
+use uuid::Uuid;
+
+use crate::tempdb::TempDb;
+
+const SERVER: &str = "systemprompt";
+
+fn policy() -> CommandPolicy {
+    serde_json::from_value(serde_json::json!({
+        "rules": [
+            { "id": "admin", "roles": ["admin"], "allow": ["*"] },
+            {
+                "id": "operator-read-only",
+                "roles": ["user"],
+                "deny": ["admin *"],
+                "allow": ["core skills list", "list_skills"],
+            },
+        ],
+    }))
+    .expect("the fixture policy deserializes")
+}
+
+fn database(pool: &Arc<PgPool>) -> DbPool {
+    Arc::new(Database::from_pools(
+        Arc::clone(pool),
+        Some(Arc::clone(pool)),
+    ))
+}
+
+fn caller(permission: Permission) -> SysRequestContext {
+    let user = AuthenticatedUser::new_with_roles(
+        Uuid::new_v4(),
+        "operator".to_owned(),
+        "operator@example.com".to_owned(),
+        vec![permission],
+        Vec::new(),
+    );
+    SysRequestContext::new(
+        SessionId::new("policy-session"),
+        TraceId::new("policy-trace"),
+        ContextId::new("00000000-0000-4000-8000-0000000070c1"),
+        AgentName::new("policy-agent"),
+    )
+    .with_user(user)
+}
+
+fn cli(command: &str) -> CallToolRequestParams {
+    let arguments = serde_json::json!({ "command": command });
+    CallToolRequestParams::new(SERVER).with_arguments(
+        arguments
+            .as_object()
+            .expect("tool arguments are a JSON object")
+            .clone(),
+    )
+}
+
+async fn seed_anonymous_principal(pool: &PgPool) {
+    sqlx::query("INSERT INTO users (id, name, email) VALUES ('anonymous', 'anonymous', 'guest@anonymous.local')")
+        .execute(pool)
+        .await
+        .expect("seed the anonymous principal");
+}
+
+async fn rejection_rules(pool: &PgPool) -> Vec<(String, Option<String>)> {
+    sqlx::query_as::<_, (String, Option<String>)>(
+        r"SELECT description, metadata->>'rule'
+          FROM user_activity
+          WHERE category = 'mcp_access' AND entity_name = $1",
+    )
+    .bind(SERVER)
+    .fetch_all(pool)
+    .await
+    .expect("query recorded activity")
+}
+
+#[tokio::test]
+async fn a_denied_command_is_refused_and_audited_with_its_rule() {
+    let Some(db) = TempDb::create().await else {
+        return;
+    };
+    seed_anonymous_principal(&db.pool).await;
+    let db_pool = database(&db.pool);
+
+    let error = authorize(
+        &db_pool,
+        SERVER,
+        &policy(),
+        &cli("admin users delete someone --yes"),
+        &caller(Permission::User),
+    )
+    .await
+    .expect_err("the user rule denies admin commands");
+
+    assert!(
+        error
+            .message
+            .contains("rule 'operator-read-only' denies 'admin users delete someone'"),
+        "the refusal names the rule and the judged path: {}",
+        error.message
+    );
+    let rows = rejection_rules(&db.pool).await;
+    assert_eq!(rows.len(), 1, "exactly one rejection row: {rows:?}");
+    assert_eq!(rows[0].1.as_deref(), Some("operator-read-only"));
+
+    db.cleanup().await;
+}
+
+#[tokio::test]
+async fn an_allowed_call_returns_the_rule_without_a_rejection_row() {
+    let Some(db) = TempDb::create().await else {
+        return;
+    };
+    seed_anonymous_principal(&db.pool).await;
+    let db_pool = database(&db.pool);
+    let user = caller(Permission::User);
+
+    let cli_decision = authorize(
+        &db_pool,
+        SERVER,
+        &policy(),
+        &cli("core skills list --json"),
+        &user,
+    )
+    .await
+    .expect("a read-only command is allowed");
+    let typed = CallToolRequestParams::new("list_skills");
+    let typed_decision = authorize(&db_pool, SERVER, &policy(), &typed, &user)
+        .await
+        .expect("a typed tool is judged by its name");
+
+    assert_eq!(cli_decision.rule.as_deref(), Some("operator-read-only"));
+    assert_eq!(typed_decision.rule.as_deref(), Some("operator-read-only"));
+    assert!(rejection_rules(&db.pool).await.is_empty());
+
+    db.cleanup().await;
+}
+
+#[tokio::test]
+async fn a_caller_no_rule_applies_to_is_denied_by_default() {
+    let Some(db) = TempDb::create().await else {
+        return;
+    };
+    seed_anonymous_principal(&db.pool).await;
+    let db_pool = database(&db.pool);
+
+    let error = authorize(
+        &db_pool,
+        SERVER,
+        &policy(),
+        &cli("core skills list"),
+        &caller(Permission::Anonymous),
+    )
+    .await
+    .expect_err("no rule covers anonymous callers");
+
+    assert!(
+        error
+            .message
+            .contains("no command rule allows 'core skills list'")
+    );
+    let rows = rejection_rules(&db.pool).await;
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].1, None, "the default deny has no rule to record");
+
+    db.cleanup().await;
+}
@@ -47,7 +47,7 @@ async fn new_builds_its_repositories_from_the_pool() {
 }
 
 #[tokio::test]
-async fn get_info_advertises_both_tools_and_resources() {
+async fn get_info_advertises_tools_prompts_and_resources() {
     let Some(db) = TempDb::create().await else {
         return;
     };
@@ -58,7 +58,17 @@ async fn get_info_advertises_both_tools_and_resources() {
     assert!(info.capabilities.tools.is_some(), "it serves tools");
     assert!(
         info.capabilities.resources.is_some(),
-        "it serves the artifact-viewer resource"
+        "it serves the artifact viewer and published content"
+    );
+    let prompts = info
+        .capabilities
+        .prompts
+        .expect("it serves skills as prompts");
+    assert_eq!(prompts.list_changed, Some(true));
+    assert_eq!(
+        info.capabilities.resources.and_then(|r| r.list_changed),
+        Some(true),
+        "content ingestion is announced"
     );
 
     db.cleanup().await;
@@ -123,17 +133,27 @@ async fn a_different_service_id_only_changes_the_server_name() {
 }
 
 #[tokio::test]
-async fn the_server_exposes_exactly_one_cli_tool() {
+async fn the_server_lists_the_typed_tools_before_the_cli_tool() {
     let Some(db) = TempDb::create().await else {
         return;
     };
     let _built = server(&db.pool);
 
     let listed = tools::list_tools();
+    let cli = listed
+        .last()
+        .expect("the server lists at least the CLI tool");
 
-    assert_eq!(listed.len(), 1, "the CLI tool is the whole tool surface");
-    assert_eq!(listed[0].name.as_ref(), tools::SERVER_NAME);
-    assert_eq!(listed[0].title.as_deref(), Some("SystemPrompt CLI"));
+    assert!(
+        listed.len() > 1,
+        "typed tools are listed alongside the CLI tool"
+    );
+    assert_eq!(
+        cli.name.as_ref(),
+        tools::SERVER_NAME,
+        "the CLI tool comes last"
+    );
+    assert_eq!(cli.title.as_deref(), Some("SystemPrompt CLI"));
 
     db.cleanup().await;
 }
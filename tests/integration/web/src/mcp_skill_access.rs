@@ -0,0 +1,164 @@
+//! The `list_skills` and `show_skill` tools answer with the same skills the
+//! prompt list offers a caller. A skill the access rules withhold from a user's
+//! prompts must not be listed or readable through the typed tools either.
+
+use std::path::Path;
+use std::sync::Arc;
+
+use sqlx::PgPool;
+use systemprompt::database::{Database, DbPool};
+use systemprompt::identifiers::{
+    AgentName, ContextId, McpExecutionId, SessionId, SkillId, TraceId, UserId,
+};
+use systemprompt::mcp::McpToolHandler;
+use systemprompt::models::auth::{AuthenticatedUser, Permission};
+use systemprompt::models::execution::context::RequestContext as SysRequestContext;
+use systemprompt_mcp_agent::catalog::{SkillCatalog, list_skills_in};
+use systemprompt_mcp_agent::server::skill_access::visible_skills;
+use systemprompt_mcp_agent::server::typed::{ListSkillsHandler, ShowSkillHandler};
+use systemprompt_mcp_agent::tools::typed::{ListSkillsInput, ShowSkillInput};
+use uuid::Uuid;
+
+use crate::tempdb::TempDb;
+
+const OPEN: &str = "open";
+const RESTRICTED: &str = "restricted";
+
+fn write_skill(root: &Path, id: &str) {
+    let dir = root.join(id);
+    std::fs::create_dir_all(&dir).expect("create the skill directory");
+    std::fs::write(
+        dir.join("config.yaml"),
+        format!("id: {id}\nname: {id}\ndescription: The {id} skill\n"),
+    )
+    .expect("write the skill config");
+}
+
+fn catalog(root: &Path) -> SkillCatalog {
+    write_skill(root, OPEN);
+    write_skill(root, RESTRICTED);
+    SkillCatalog {
+        root: root.to_path_buf(),
+        skills: list_skills_in(root).expect("list the fixture skills"),
+        marketplaces: Vec::new(),
+    }
+}
+
+fn database(pool: &Arc<PgPool>) -> DbPool {
+    Arc::new(Database::from_pools(
+        Arc::clone(pool),
+        Some(Arc::clone(pool)),
+    ))
+}
+
+async fn seed_user(pool: &PgPool) -> Uuid {
+    let id = Uuid::new_v4();
+    sqlx::query(
+        "INSERT INTO users (id, name, email, status, roles) VALUES ($1, $1, $1 || '@example.com', \
+         'active', ARRAY['user'])",
+    )
+    .bind(id.to_string())
+    .execute(pool)
+    .await
+    .expect("seed a user");
+    id
+}
+
+async fn allow_skill(pool: &PgPool, skill: &str, user: Uuid) {
+    sqlx::query(
+        "INSERT INTO access_control_entities (entity_type, entity_id, default_included, source)
+         VALUES ('skill', $1, false, 'test-fixture')",
+    )
+    .bind(skill)
+    .execute(pool)
+    .await
+    .expect("insert the skill entity");
+    sqlx::query(
+        "INSERT INTO access_control_rules (id, entity_type, entity_id, rule_type, rule_value, access)
+         VALUES ($1, 'skill', $2, 'user', $3, 'allow')",
+    )
+    .bind(Uuid::new_v4().to_string())
+    .bind(skill)
+    .bind(user.to_string())
+    .execute(pool)
+    .await
+    .expect("insert the allow rule");
+}
+
+fn caller(user: Uuid) -> SysRequestContext {
+    let user = AuthenticatedUser::new_with_roles(
+        user,
+        "operator".to_owned(),
+        "operator@example.com".to_owned(),
+        vec![Permission::User],
+        vec!["user".to_owned()],
+    );
+    SysRequestContext::new(
+        SessionId::new("skill-access-session"),
+        TraceId::new("skill-access-trace"),
+        ContextId::new("00000000-0000-4000-8000-0000000071c1"),
+        AgentName::new("skill-access-agent"),
+    )
+    .with_user(user)
+}
+
+#[tokio::test]
+async fn a_skill_withheld_from_prompts_is_not_shown_by_the_typed_tools() {
+    let Some(db) = TempDb::create().await else {
+        return;
+    };
+    let root = tempfile::tempdir().expect("create a skills root");
+    let catalog = catalog(root.path());
+    let user = seed_user(&db.pool).await;
+    allow_skill(&db.pool, OPEN, user).await;
+    let db_pool = database(&db.pool);
+
+    let prompts = visible_skills(&db_pool, &UserId::new(user.to_string()), &catalog)
+        .await
+        .expect("judge the prompt list");
+    let offered: Vec<_> = prompts.iter().map(|skill| skill.id.as_str()).collect();
+    assert_eq!(offered, [OPEN], "only the allowed skill is a prompt");
+
+    let ctx = caller(user);
+    let exec = McpExecutionId::generate();
+    let show = ShowSkillHandler {
+        db_pool: Arc::clone(&db_pool),
+        catalog: catalog.clone(),
+    };
+    let denied = show
+        .handle(
+            ShowSkillInput {
+                skill_id: SkillId::new(RESTRICTED),
+            },
+            &ctx,
+            &exec,
+        )
+        .await
+        .expect_err("a withheld skill is not shown");
+    assert!(denied.message.contains("not found"), "{}", denied.message);
+    show.handle(
+        ShowSkillInput {
+            skill_id: SkillId::new(OPEN),
+        },
+        &ctx,
+        &exec,
+    )
+    .await
+    .expect("an allowed skill is shown");
+
+    let list = ListSkillsHandler { db_pool, catalog };
+    let (_, summary) = list
+        .handle(
+            ListSkillsInput {
+                enabled_only: false,
+                tag: None,
+            },
+            &ctx,
+            &exec,
+        )
+        .await
+        .expect("list the skills");
+    assert_eq!(summary, "1 skill.");
+
+    db.cleanup().await;
+}
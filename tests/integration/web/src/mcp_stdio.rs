@@ -0,0 +1,85 @@
+//! `--stdio` authenticates with a personal access token resolved against the
+//! real key table. A token that names no live key of an active user is
+//! refused before the server serves anything.
+
+use std::sync::Arc;
+
+use sqlx::PgPool;
+use systemprompt::identifiers::{McpServerId, UserId};
+use systemprompt_mcp_agent::error::SystempromptToolError;
+use systemprompt_mcp_agent::stdio::PatCredential;
+use systemprompt_web_admin::repositories::access_tokens::{issue_api_key, revoke_api_key};
+
+use crate::tempdb::TempDb;
+
+async fn seed_user(pool: &PgPool, status: &str) -> UserId {
+    let id = uuid::Uuid::new_v4().to_string();
+    sqlx::query(
+        "INSERT INTO users (id, name, email, status, roles) VALUES ($1, $1, $1 || '@example.com', \
+         $2, ARRAY['user'])",
+    )
+    .bind(&id)
+    .bind(status)
+    .execute(pool)
+    .await
+    .expect("seed a user");
+    UserId::new(id)
+}
+
+async fn refused(pool: &Arc<PgPool>, token: &str) -> bool {
+    matches!(
+        PatCredential::resolve(
+            Arc::clone(pool),
+            token.to_owned(),
+            McpServerId::new("systemprompt"),
+        )
+        .await,
+        Err(SystempromptToolError::Unauthorized(_))
+    )
+}
+
+#[tokio::test]
+async fn a_token_that_names_no_key_is_refused() {
+    let Some(db) = TempDb::create().await else {
+        return;
+    };
+
+    assert!(refused(&db.pool, "").await);
+    assert!(refused(&db.pool, "not-a-token").await);
+    assert!(refused(&db.pool, "sp-live-unknown.secret").await);
+
+    db.cleanup().await;
+}
+
+#[tokio::test]
+async fn a_revoked_key_is_refused() {
+    let Some(db) = TempDb::create().await else {
+        return;
+    };
+    let owner = seed_user(&db.pool, "active").await;
+    let issued = issue_api_key(&db.pool, &owner, "stdio", None)
+        .await
+        .expect("issue a key");
+    revoke_api_key(&db.pool, &owner, &issued.id)
+        .await
+        .expect("revoke the key");
+
+    assert!(refused(&db.pool, &issued.secret).await);
+
+    db.cleanup().await;
+}
+
+#[tokio::test]
+async fn a_key_whose_owner_is_inactive_is_refused() {
+    let Some(db) = TempDb::create().await else {
+        return;
+    };
+    let owner = seed_user(&db.pool, "suspended").await;
+    let issued = issue_api_key(&db.pool, &owner, "stdio", None)
+        .await
+        .expect("issue a key");
+
+    assert!(refused(&db.pool, &issued.secret).await);
+
+    db.cleanup().await;
+}
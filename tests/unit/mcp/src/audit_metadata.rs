@@ -2,7 +2,8 @@
 //! its JSON shape is a storage contract: `tool_name` and `server` always
 //! present, and `reason` omitted entirely on the success path rather than
 //! written as an explicit null (which would make "no reason" and "reason was
-//! null" indistinguishable to queries over the JSONB column).
+//! null" indistinguishable to queries over the JSONB column). `rule` follows
+//! the same rule: present only when a command policy decided the call.
 
 use systemprompt_mcp_shared::AuditMetadata;
 
@@ -12,6 +13,7 @@ fn reason_is_omitted_when_absent() {
         tool_name: "search_project_context".to_owned(),
         server: "salesforce".to_owned(),
         reason: None,
+        rule: None,
     };
     let value = serde_json::to_value(&metadata).expect("serializes");
     let object = value.as_object().expect("serializes to an object");
@@ -36,6 +38,7 @@ fn reason_is_written_verbatim_when_present() {
         tool_name: "upload_document".to_owned(),
         server: "salesforce".to_owned(),
         reason: Some("requires the admin role".to_owned()),
+        rule: None,
     };
     let value = serde_json::to_value(&metadata).expect("serializes");
     let object = value.as_object().expect("serializes to an object");
@@ -45,3 +48,21 @@ fn reason_is_written_verbatim_when_present() {
     );
     assert_eq!(object.len(), 3);
 }
+
+#[test]
+fn rule_is_written_only_when_a_command_policy_decided() {
+    let metadata = AuditMetadata {
+        tool_name: "systemprompt".to_owned(),
+        server: "systemprompt".to_owned(),
+        reason: None,
+        rule: Some("operator-read-only".to_owned()),
+    };
+    let value = serde_json::to_value(&metadata).expect("serializes");
+    let object = value.as_object().expect("serializes to an object");
+    assert_eq!(
+        object.get("rule").and_then(|v| v.as_str()),
+        Some("operator-read-only")
+    );
+    assert!(!object.contains_key("reason"));
+    assert_eq!(object.len(), 3);
+}
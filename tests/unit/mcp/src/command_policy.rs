@@ -0,0 +1,173 @@
+//! The command policy decides which CLI subcommands and typed tools each role
+//! may run on the `systemprompt` server. Patterns are word-wise, rules are
+//! first-match in file order, and anything unmatched is denied.
+
+use std::path::PathBuf;
+
+use systemprompt_mcp_agent::policy::{
+    CommandPolicy, CommandTimeouts, DEFAULT_TIMEOUT_SECS, TimeoutOverride, command_path,
+    load_settings_from, pattern_matches,
+};
+
+fn words(s: &str) -> Vec<String> {
+    s.split_whitespace().map(str::to_owned).collect()
+}
+
+fn roles(names: &[&str]) -> Vec<String> {
+    names.iter().map(|&n| n.to_owned()).collect()
+}
+
+fn shipped_policy() -> CommandPolicy {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("../../../services/mcp/systemprompt-commands.yaml");
+    load_settings_from(&path)
+        .expect("the shipped command settings parse")
+        .command_policy
+}
+
+#[test]
+fn a_trailing_star_covers_the_command_and_everything_under_it() {
+    assert!(pattern_matches("admin *", &words("admin")));
+    assert!(pattern_matches("admin *", &words("admin users delete")));
+    assert!(!pattern_matches("admin *", &words("core skills list")));
+    assert!(pattern_matches("*", &[]));
+}
+
+#[test]
+fn an_inner_star_matches_exactly_one_word() {
+    assert!(pattern_matches("core * list", &words("core skills list")));
+    assert!(!pattern_matches("core * list", &words("core list")));
+    assert!(!pattern_matches(
+        "core * list",
+        &words("core skills list extra")
+    ));
+    assert!(!pattern_matches(
+        "core skills list",
+        &words("core skills list extra")
+    ));
+}
+
+#[test]
+fn the_command_path_stops_at_the_first_flag() {
+    let args = words("core skills show foo --json --limit 5");
+    assert_eq!(command_path(&args), words("core skills show foo"));
+    assert!(command_path(&words("--profile prod admin users")).is_empty());
+}
+
+#[test]
+fn deny_wins_over_allow_within_a_rule_and_the_first_rule_decides() {
+    let policy = shipped_policy();
+
+    let denied = policy.evaluate(&roles(&["user"]), &words("admin users list"));
+    assert!(!denied.allowed);
+    assert_eq!(denied.rule.as_deref(), Some("operator-read-only"));
+    assert_eq!(denied.pattern.as_deref(), Some("admin *"));
+
+    let allowed = policy.evaluate(&roles(&["user", "admin"]), &words("admin users list"));
+    assert!(allowed.allowed, "the admin rule comes first");
+    assert_eq!(allowed.rule.as_deref(), Some("admin"));
+}
+
+#[test]
+fn users_get_read_only_commands_and_typed_tools_by_name() {
+    let policy = shipped_policy();
+    let user = roles(&["user"]);
+
+    assert!(policy.evaluate(&user, &words("core skills list")).allowed);
+    assert!(
+        policy
+            .evaluate(&user, &words("core skills show systemprompt_cli"))
+            .allowed
+    );
+    assert!(policy.evaluate(&user, &words("list_skills")).allowed);
+    assert!(
+        !policy
+            .evaluate(&user, &words("list_governance_decisions"))
+            .allowed
+    );
+    assert!(
+        !policy
+            .evaluate(&user, &words("core skills delete x"))
+            .allowed
+    );
+}
+
+#[test]
+fn no_matching_rule_is_a_deny_without_a_rule_name() {
+    let decision = shipped_policy().evaluate(&roles(&["anonymous"]), &words("core skills list"));
+    assert!(!decision.allowed);
+    assert_eq!(decision.rule, None);
+    assert_eq!(
+        decision.describe(&words("core skills list")),
+        "no command rule allows 'core skills list'"
+    );
+}
+
+#[test]
+fn a_missing_policy_file_falls_back_to_admins_only() {
+    let dir = tempfile::tempdir().unwrap();
+    let settings = load_settings_from(&dir.path().join("absent.yaml")).unwrap();
+    let policy = settings.command_policy;
+
+    assert!(
+        policy
+            .evaluate(&roles(&["admin"]), &words("cloud deploy"))
+            .allowed
+    );
+    assert!(
+        !policy
+            .evaluate(&roles(&["user"]), &words("core skills list"))
+            .allowed
+    );
+}
+
+#[test]
+fn an_unparseable_policy_file_is_an_error_not_a_fallback() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("policy.yaml");
+    std::fs::write(&path, "command_policy: [unterminated").unwrap();
+
+    assert!(load_settings_from(&path).is_err());
+}
+
+#[test]
+fn the_first_matching_timeout_override_wins_over_the_default() {
+    let timeouts = CommandTimeouts {
+        default_secs: 60,
+        overrides: vec![
+            TimeoutOverride {
+                pattern: "build *".to_owned(),
+                secs: 1800,
+            },
+            TimeoutOverride {
+                pattern: "*".to_owned(),
+                secs: 5,
+            },
+        ],
+    };
+
+    assert_eq!(timeouts.for_path(&words("build core")).as_secs(), 1800);
+    assert_eq!(timeouts.for_path(&words("core skills list")).as_secs(), 5);
+    assert_eq!(
+        CommandTimeouts::default()
+            .for_path(&words("core skills list"))
+            .as_secs(),
+        DEFAULT_TIMEOUT_SECS
+    );
+}
+
+#[test]
+fn a_settings_file_without_timeouts_gets_the_default_limit() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("policy.yaml");
+    std::fs::write(
+        &path,
+        "command_policy:\n  rules:\n  - id: admin\n    roles: [admin]\n    allow: ['*']\n",
+    )
+    .unwrap();
+
+    let settings = load_settings_from(&path).unwrap();
+
+    assert_eq!(settings.command_timeouts.default_secs, DEFAULT_TIMEOUT_SECS);
+    assert!(settings.command_timeouts.overrides.is_empty());
+}
@@ -2,16 +2,41 @@
 //! - `systemprompt-mcp-agent`'s `filter_hallucinated_args` (CLI arg scrubbing)
 //! - `systemprompt-mcp-shared`'s `truncate_on_char_boundary` (rejection-reason
 //!   truncation with UTF-8 safety) and `AuditMetadata`'s stored JSON shape
-//! - `systemprompt-mcp-agent`'s `systemprompt` tool contract (single tool, its
+//! - `systemprompt-mcp-agent`'s `systemprompt` tool contract (the CLI tool, its
 //!   input/output schema) and its error type's code / status / retryability
+//! - `systemprompt-mcp-agent`'s typed tools (required arguments, clamped
+//!   windows) and the skill catalog's directory confinement
+//! - `systemprompt-mcp-agent`'s command policy (pattern matching, rule order,
+//!   and the shipped `systemprompt-commands.yaml`)
+//! - `systemprompt-mcp-agent`'s skill prompts (marketplace membership, prompt
+//!   messages) and content resource URIs
+//! - `systemprompt-mcp-agent`'s stdio request parts and token scopes
+//! - `systemprompt-mcp-proxy`'s upstream config, per-role tool visibility and
+//!   the names proxied calls are governed under
+//! - `systemprompt-mcp-shared`'s tool-call capture (redaction, size limits and
+//!   the per-server opt-out)
 
 #[cfg(test)]
 mod audit_metadata;
 #[cfg(test)]
+mod command_policy;
+#[cfg(test)]
 mod filter_hallucinated_args;
 #[cfg(test)]
+mod proxy_config;
+#[cfg(test)]
+mod skill_catalog;
+#[cfg(test)]
+mod skill_prompts;
+#[cfg(test)]
+mod stdio_auth;
+#[cfg(test)]
 mod systemprompt_error;
 #[cfg(test)]
 mod systemprompt_tools;
 #[cfg(test)]
+mod tool_capture;
+#[cfg(test)]
 mod truncate_on_char_boundary;
+#[cfg(test)]
+mod typed_tools;
@@ -0,0 +1,135 @@
+//! The governing proxy finds its upstream by service id, hides tools per role,
+//! and governs each call under the name the `PreToolUse` hook would see.
+
+use std::io::Write;
+use std::path::PathBuf;
+
+use systemprompt::identifiers::PolicyId;
+use systemprompt::security::policy::{ChainEntryOutcome, ChainEntryResult};
+use systemprompt_mcp_proxy::config::{UpstreamTransport, load_upstream_from};
+use systemprompt_mcp_proxy::server::{denying_policy, governed_tool_name};
+
+const FIXTURE: &str = "\
+mcp_proxies:
+  github:
+    transport:
+      type: stdio
+      command: npx
+      args: [-y, '@modelcontextprotocol/server-github']
+      env:
+        LOG_LEVEL: warn
+    tool_roles:
+      push_files: [admin]
+  linear:
+    transport:
+      type: http
+      url: https://mcp.linear.app/mcp
+      bearer_token_env: LINEAR_API_KEY
+";
+
+fn fixture(text: &str) -> tempfile::NamedTempFile {
+    let mut file = tempfile::NamedTempFile::new().unwrap();
+    file.write_all(text.as_bytes()).unwrap();
+    file
+}
+
+fn roles(names: &[&str]) -> Vec<String> {
+    names.iter().map(|&n| n.to_owned()).collect()
+}
+
+fn entry(policy: &str, result: ChainEntryResult) -> ChainEntryOutcome {
+    ChainEntryOutcome {
+        policy_id: PolicyId::new(policy),
+        result,
+        detail: String::new(),
+        duration_ms: 0.0,
+    }
+}
+
+#[test]
+fn each_service_id_selects_its_own_upstream() {
+    let file = fixture(FIXTURE);
+
+    let github = load_upstream_from(file.path(), "github").unwrap();
+    let UpstreamTransport::Stdio { command, args, env } = github.transport else {
+        panic!("github is a stdio upstream");
+    };
+    assert_eq!(command, "npx");
+    assert_eq!(args, ["-y", "@modelcontextprotocol/server-github"]);
+    assert_eq!(env.get("LOG_LEVEL").map(String::as_str), Some("warn"));
+
+    let linear = load_upstream_from(file.path(), "linear").unwrap();
+    let UpstreamTransport::Http {
+        url,
+        bearer_token_env,
+    } = linear.transport
+    else {
+        panic!("linear is an http upstream");
+    };
+    assert_eq!(url, "https://mcp.linear.app/mcp");
+    assert_eq!(bearer_token_env.as_deref(), Some("LINEAR_API_KEY"));
+}
+
+#[test]
+fn an_unregistered_service_id_is_an_error_naming_it() {
+    let file = fixture(FIXTURE);
+    let err = load_upstream_from(file.path(), "jira").unwrap_err();
+    assert!(err.to_string().contains("'jira'"), "{err}");
+}
+
+#[test]
+fn a_misspelled_key_is_rejected_rather_than_ignored() {
+    let file = fixture(
+        "mcp_proxies:\n  github:\n    transport: {type: stdio, command: npx}\n    tool_role: {push_files: [admin]}\n",
+    );
+    let err = load_upstream_from(file.path(), "github").unwrap_err();
+    assert!(err.to_string().contains("tool_role"), "{err}");
+}
+
+#[test]
+fn a_listed_tool_is_visible_only_to_its_roles() {
+    let file = fixture(FIXTURE);
+    let github = load_upstream_from(file.path(), "github").unwrap();
+
+    assert!(github.tool_visible("push_files", &roles(&["admin"])));
+    assert!(github.tool_visible("push_files", &roles(&["user", "admin"])));
+    assert!(!github.tool_visible("push_files", &roles(&["user"])));
+    assert!(!github.tool_visible("push_files", &[]));
+}
+
+#[test]
+fn an_unlisted_tool_is_visible_to_every_caller() {
+    let file = fixture(FIXTURE);
+    let github = load_upstream_from(file.path(), "github").unwrap();
+    assert!(github.tool_visible("list_issues", &roles(&["user"])));
+}
+
+#[test]
+fn the_shipped_proxies_file_parses() {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../../services/mcp/proxies.yaml");
+    let err = load_upstream_from(&path, "github").unwrap_err();
+    assert!(err.to_string().contains("No upstream"), "{err}");
+}
+
+#[test]
+fn proxied_tools_are_governed_under_the_hook_naming() {
+    assert_eq!(
+        governed_tool_name("github", "push_files"),
+        "mcp__github__push_files"
+    );
+}
+
+#[test]
+fn the_first_failing_policy_is_the_one_that_denied() {
+    let chain = [
+        entry("secret_scan", ChainEntryResult::Pass),
+        entry("scope_check", ChainEntryResult::Disabled),
+        entry("tool_blocklist", ChainEntryResult::Fail),
+        entry("rate_limit", ChainEntryResult::Fail),
+    ];
+    assert_eq!(denying_policy(&chain).as_deref(), Some("tool_blocklist"));
+    assert_eq!(
+        denying_policy(&[entry("secret_scan", ChainEntryResult::Pass)]),
+        None
+    );
+}
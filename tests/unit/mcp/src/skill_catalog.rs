@@ -0,0 +1,72 @@
+//! `show_skill` turns a model-supplied id into a path under the skills
+//! directory, so the catalog must refuse ids that would leave it, and list
+//! only directories that carry a parseable config.
+
+use systemprompt_mcp_agent::catalog::{find_skill_in, list_skills_in};
+
+fn write_skill(root: &std::path::Path, id: &str, config: &str, body: Option<&str>) {
+    let dir = root.join(id);
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("config.yaml"), config).unwrap();
+    if let Some(body) = body {
+        std::fs::write(dir.join("SKILL.md"), body).unwrap();
+    }
+}
+
+fn fixture() -> tempfile::TempDir {
+    let root = tempfile::tempdir().unwrap();
+    write_skill(
+        root.path(),
+        "b_skill",
+        "id: b_skill\nname: B\ndescription: second\nfile: SKILL.md\ntags: [governance]\n",
+        Some("---\ntitle: B\n---\n# B body\n"),
+    );
+    write_skill(
+        root.path(),
+        "a_skill",
+        "id: a_skill\nname: A\ndescription: first\nenabled: false\n",
+        None,
+    );
+    write_skill(root.path(), "broken", "name: [unterminated", None);
+    std::fs::create_dir_all(root.path().join("no_config")).unwrap();
+    root
+}
+
+#[test]
+fn lists_parseable_skills_sorted_by_id() {
+    let root = fixture();
+    let skills = list_skills_in(root.path()).unwrap();
+    let ids: Vec<&str> = skills.iter().map(|s| s.id.as_str()).collect();
+    assert_eq!(ids, vec!["a_skill", "b_skill"]);
+    assert!(!skills[0].config.enabled);
+    assert_eq!(skills[1].config.tags, vec!["governance"]);
+}
+
+#[test]
+fn a_missing_skills_directory_lists_nothing() {
+    let root = tempfile::tempdir().unwrap();
+    assert!(list_skills_in(&root.path().join("absent")).unwrap().is_empty());
+}
+
+#[test]
+fn shows_the_instruction_body_without_frontmatter() {
+    let root = fixture();
+    let (skill, body) = find_skill_in(root.path(), "b_skill").unwrap().unwrap();
+    assert_eq!(skill.config.name, "B");
+    assert_eq!(body.trim(), "# B body");
+
+    let (_, body) = find_skill_in(root.path(), "a_skill").unwrap().unwrap();
+    assert!(body.is_empty(), "a skill without its content file has no body");
+}
+
+#[test]
+fn ids_that_would_leave_the_skills_directory_are_not_found() {
+    let root = fixture();
+    for id in ["", "..", "../b_skill", "b_skill/../b_skill", ".hidden", "a\\b"] {
+        assert!(
+            find_skill_in(root.path(), id).unwrap().is_none(),
+            "{id:?} must not resolve"
+        );
+    }
+    assert!(find_skill_in(root.path(), "missing").unwrap().is_none());
+}
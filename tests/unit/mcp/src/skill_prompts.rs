@@ -0,0 +1,118 @@
+//! Skills reach MCP clients as prompts and published content as resources.
+//! A marketplace's member list decides which access rule a skill inherits,
+//! the prompt carries the skill's instructions and the caller's task, and only
+//! `content://` URIs for the offered sources are read from the database.
+
+use systemprompt::identifiers::SourceId;
+use systemprompt::models::services::MarketplaceConfig;
+use systemprompt_mcp_agent::catalog::{list_skills_in, marketplace_members, skills_fingerprint_in};
+use systemprompt_mcp_agent::server::prompts::{TASK_ARGUMENT, skill_messages, skill_prompt};
+use systemprompt_mcp_agent::server::resources::{content_uri, parse_content_uri};
+
+fn marketplace(skills: serde_json::Value) -> MarketplaceConfig {
+    serde_json::from_value(serde_json::json!({
+        "id": "engineering",
+        "name": "Engineering",
+        "description": "",
+        "version": "1.0.0",
+        "author": {"name": "a", "email": "a@example.com"},
+        "license": "MIT",
+        "skills": skills,
+    }))
+    .unwrap()
+}
+
+fn ids(ids: &[&str]) -> Vec<String> {
+    ids.iter().map(|&id| id.to_owned()).collect()
+}
+
+#[test]
+fn an_explicit_marketplace_ships_its_include_list_less_its_exclude_list() {
+    let config = marketplace(serde_json::json!({
+        "include": ["a", "b", "absent"],
+        "exclude": ["b"],
+    }));
+    assert_eq!(
+        marketplace_members(&config, &ids(&["a", "b", "c"])),
+        ids(&["a"])
+    );
+}
+
+#[test]
+fn an_instance_marketplace_ships_every_skill_not_excluded() {
+    let config = marketplace(serde_json::json!({"source": "instance", "exclude": ["c"]}));
+    assert_eq!(
+        marketplace_members(&config, &ids(&["a", "b", "c"])),
+        ids(&["a", "b"])
+    );
+}
+
+#[test]
+fn a_prompt_carries_the_skill_instructions_then_the_task() {
+    let root = tempfile::tempdir().unwrap();
+    let dir = root.path().join("review");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(
+        dir.join("config.yaml"),
+        "id: review\nname: Code review\ndescription: Review a diff\n",
+    )
+    .unwrap();
+    let skill = list_skills_in(root.path()).unwrap().remove(0);
+
+    let prompt = skill_prompt(&skill);
+    assert_eq!(prompt.name, "review");
+    assert_eq!(prompt.title.as_deref(), Some("Code review"));
+    let arguments = prompt.arguments.unwrap();
+    assert_eq!(arguments[0].name, TASK_ARGUMENT);
+    assert_eq!(arguments[0].required, Some(false));
+
+    let result = skill_messages(&skill, "\nRead every hunk.\n", Some(" the auth change "));
+    let texts: Vec<&str> = result
+        .messages
+        .iter()
+        .map(|m| m.content.as_text().unwrap().text.as_str())
+        .collect();
+    assert_eq!(texts, vec!["Read every hunk.", "Task: the auth change"]);
+
+    let result = skill_messages(&skill, "Read every hunk.", Some("  "));
+    assert_eq!(result.messages.len(), 1, "a blank task adds no message");
+}
+
+#[test]
+fn content_uris_round_trip_for_offered_sources_only() {
+    let uri = content_uri(
+        &SourceId::new("documentation".to_owned()),
+        "getting-started",
+    );
+    assert_eq!(uri, "content://documentation/getting-started");
+    let (source_id, slug) = parse_content_uri(&uri).unwrap();
+    assert_eq!(
+        (source_id.as_str(), slug),
+        ("documentation", "getting-started")
+    );
+    let (source_id, slug) = parse_content_uri("content://blog/launch").unwrap();
+    assert_eq!((source_id.as_str(), slug), ("blog", "launch"));
+
+    for uri in [
+        "content://drafts/launch",
+        "content://blog/",
+        "content://blog/a/b",
+        "content://blog",
+        "ui://systemprompt/artifact-viewer",
+    ] {
+        assert!(parse_content_uri(uri).is_none(), "{uri:?} must not parse");
+    }
+}
+
+#[test]
+fn the_skills_fingerprint_changes_when_a_skill_changes() {
+    let root = tempfile::tempdir().unwrap();
+    let dir = root.path().join("review");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("config.yaml"), "id: review\n").unwrap();
+    let before = skills_fingerprint_in(root.path()).unwrap();
+    assert_eq!(before, skills_fingerprint_in(root.path()).unwrap());
+
+    std::fs::write(dir.join("SKILL.md"), "# Review\n").unwrap();
+    assert_ne!(before, skills_fingerprint_in(root.path()).unwrap());
+}
@@ -0,0 +1,62 @@
+//! Over stdio there is no HTTP request, so the server attaches the request
+//! parts the RBAC middleware reads: a bearer when the token resolves, and the
+//! request context the gateway would otherwise have set. The JWT's scopes are
+//! the owner's roles that name a permission.
+
+use axum::http::header::AUTHORIZATION;
+use systemprompt::identifiers::SessionId;
+use systemprompt::models::auth::Permission;
+use systemprompt::models::execution::context::RequestContext;
+use systemprompt_mcp_agent::stdio::{STDIO_AGENT, permissions, request_parts};
+
+#[test]
+fn a_resolved_token_travels_as_a_bearer_with_the_session_context() {
+    let session = SessionId::new("stdio-session".to_owned());
+    let parts = request_parts(Some("minted.jwt"), &session);
+
+    assert_eq!(
+        parts.headers.get(AUTHORIZATION).unwrap(),
+        "Bearer minted.jwt"
+    );
+    let context = parts.extensions.get::<RequestContext>().unwrap();
+    assert_eq!(context.session_id(), &session);
+    assert_eq!(context.agent_name().as_str(), STDIO_AGENT);
+}
+
+#[test]
+fn an_unresolved_token_sends_no_bearer_so_the_request_is_rejected() {
+    let parts = request_parts(None, &SessionId::new("stdio-session".to_owned()));
+
+    assert!(parts.headers.get(AUTHORIZATION).is_none());
+    assert!(
+        parts.extensions.get::<RequestContext>().is_some(),
+        "the rejection is still audited against the session's context"
+    );
+}
+
+#[test]
+fn each_request_gets_its_own_trace() {
+    let session = SessionId::new("stdio-session".to_owned());
+    let first = request_parts(None, &session);
+    let second = request_parts(None, &session);
+
+    let trace = |parts: &axum::http::request::Parts| {
+        parts
+            .extensions
+            .get::<RequestContext>()
+            .unwrap()
+            .trace_id()
+            .clone()
+    };
+    assert_ne!(trace(&first), trace(&second));
+}
+
+#[test]
+fn only_roles_that_name_a_permission_become_scopes() {
+    let roles = ["admin", "user", "finance"].map(str::to_owned);
+    assert_eq!(
+        permissions(&roles),
+        vec![Permission::Admin, Permission::User]
+    );
+    assert!(permissions(&[]).is_empty());
+}
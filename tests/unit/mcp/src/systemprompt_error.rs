@@ -3,7 +3,8 @@
 //! is the stable string clients match on, `status` is the HTTP code the MCP
 //! transport returns, and `is_retryable` decides whether the caller may try
 //! again. The distinction that matters is that a failed CLI command is the
-//! caller's fault (400, not retryable) while an IO fault is transient.
+//! caller's fault (400, not retryable) while an IO fault is transient, and a
+//! stdio token that no longer resolves is a 401.
 
 use axum::http::StatusCode;
 use std::io;
@@ -18,6 +19,7 @@ fn variants() -> Vec<SystempromptToolError> {
         SystempromptToolError::Serialization(
             serde_json::from_str::<serde_json::Value>("{").expect_err("invalid json"),
         ),
+        SystempromptToolError::Unauthorized("token revoked".to_owned()),
         SystempromptToolError::Internal("pool exhausted".to_owned()),
     ]
 }
@@ -33,6 +35,7 @@ fn every_variant_has_a_distinct_screaming_snake_code() {
             "NOT_FOUND",
             "IO_ERROR",
             "SERIALIZATION_ERROR",
+            "UNAUTHORIZED",
             "INTERNAL_ERROR",
         ]
     );
@@ -52,6 +55,7 @@ fn a_bad_command_is_the_callers_fault_and_everything_else_is_ours() {
             StatusCode::NOT_FOUND,
             StatusCode::INTERNAL_SERVER_ERROR,
             StatusCode::INTERNAL_SERVER_ERROR,
+            StatusCode::UNAUTHORIZED,
             StatusCode::INTERNAL_SERVER_ERROR,
         ]
     );
@@ -64,7 +68,7 @@ fn only_io_faults_are_worth_retrying() {
         .map(ExtensionError::is_retryable)
         .collect();
 
-    assert_eq!(retryable, vec![false, false, true, false, false]);
+    assert_eq!(retryable, vec![false, false, true, false, false, false]);
 
     for error in variants() {
         assert!(
@@ -1,26 +1,36 @@
-//! The `systemprompt` MCP server exposes exactly one tool, and its schema is
-//! the whole contract: the model has to learn from the description alone that
-//! the `systemprompt` prefix must be omitted, and `command` has to be the one
-//! required argument or a call with no command reaches the CLI. The output
-//! schema is the shared `ToolResponse<CliArtifact>` shape, so the client can
-//! render the artifact rather than a blob of stdout.
+//! The `systemprompt` MCP server exposes a CLI tool under the server's own
+//! name, and its schema is the whole contract: the model has to learn from
+//! the description alone that the `systemprompt` prefix must be omitted, and
+//! `command` has to be the one required argument or a call with no command
+//! reaches the CLI. The output schema is the shared `ToolResponse<CliArtifact>`
+//! shape, so the client can render the artifact rather than a blob of stdout.
 
+use rmcp::model::Tool;
 use systemprompt_mcp_agent::tools::{
     CliInput, CliOutput, SERVER_NAME, input_schema, list_tools, output_schema,
 };
 
+fn cli_tool(tools: &[Tool]) -> &Tool {
+    tools
+        .iter()
+        .find(|t| t.name.as_ref() == SERVER_NAME)
+        .expect("the CLI tool is listed under the server name")
+}
+
 #[test]
-fn exactly_one_tool_is_exposed_under_the_server_name() {
+fn the_cli_tool_is_exposed_under_the_server_name() {
     let tools = list_tools();
-    assert_eq!(tools.len(), 1);
-    assert_eq!(tools[0].name.as_ref(), SERVER_NAME);
+    assert_eq!(
+        tools.iter().filter(|t| t.name.as_ref() == SERVER_NAME).count(),
+        1
+    );
     assert_eq!(SERVER_NAME, "systemprompt");
 }
 
 #[test]
 fn the_tool_carries_a_title_description_output_schema_and_ui_meta() {
     let tools = list_tools();
-    let tool = &tools[0];
+    let tool = cli_tool(&tools);
 
     assert_eq!(tool.title.as_deref(), Some("SystemPrompt CLI"));
     let description = tool.description.as_deref().expect("description is set");
@@ -54,7 +64,7 @@ fn command_is_the_single_required_input() {
 #[test]
 fn the_listed_input_schema_is_the_one_the_tool_advertises() {
     let tools = list_tools();
-    let listed = serde_json::Value::Object((*tools[0].input_schema).clone());
+    let listed = serde_json::Value::Object((*cli_tool(&tools).input_schema).clone());
 
     assert_eq!(listed, input_schema());
     assert!(
@@ -0,0 +1,90 @@
+//! The typed tools replace free-text CLI commands for the common reads, so
+//! their schemas are the contract: the lookups that need an id require it,
+//! the filters are all optional, the governance outcome is a closed enum a
+//! model cannot misspell, and every window and row cap is clamped.
+
+use std::collections::BTreeSet;
+
+use chrono::{Duration, TimeZone, Utc};
+use systemprompt_mcp_agent::tools::list_tools;
+use systemprompt_mcp_agent::tools::typed::{
+    GovernanceDecisionsInput, MAX_LIMIT, MAX_SINCE_HOURS, row_limit, window_start,
+};
+
+fn required(tool: &str) -> Vec<String> {
+    let tools = list_tools();
+    let tool = tools
+        .iter()
+        .find(|t| t.name.as_ref() == tool)
+        .unwrap_or_else(|| panic!("{tool} is listed"));
+    tool.input_schema
+        .get("required")
+        .and_then(|v| v.as_array())
+        .map(|a| {
+            a.iter()
+                .filter_map(|v| v.as_str().map(str::to_owned))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[test]
+fn every_typed_tool_is_listed_before_the_cli_tool() {
+    let names: Vec<String> = list_tools().iter().map(|t| t.name.to_string()).collect();
+    let typed = [
+        "list_skills",
+        "show_skill",
+        "list_agents",
+        "list_inference_requests",
+        "list_governance_decisions",
+        "inspect_session",
+    ];
+    assert_eq!(&names[..typed.len()], typed.as_slice());
+    assert_eq!(names.last().map(String::as_str), Some("systemprompt"));
+    assert_eq!(
+        names.iter().collect::<BTreeSet<_>>().len(),
+        names.len(),
+        "tool names are unique"
+    );
+}
+
+#[test]
+fn only_the_single_item_lookups_require_an_argument() {
+    assert_eq!(required("show_skill"), vec!["skill_id"]);
+    assert_eq!(required("inspect_session"), vec!["session_id"]);
+    for tool in [
+        "list_skills",
+        "list_agents",
+        "list_inference_requests",
+        "list_governance_decisions",
+    ] {
+        assert!(required(tool).is_empty(), "{tool} takes only filters");
+    }
+}
+
+#[test]
+fn the_governance_outcome_is_a_closed_enum() {
+    let input: GovernanceDecisionsInput =
+        serde_json::from_value(serde_json::json!({ "decision": "deny" })).expect("deny parses");
+    assert_eq!(input.decision.map(|d| d.as_str()), Some("deny"));
+    assert!(
+        serde_json::from_value::<GovernanceDecisionsInput>(
+            serde_json::json!({ "decision": "denied" })
+        )
+        .is_err()
+    );
+}
+
+#[test]
+fn windows_and_row_caps_are_clamped() {
+    let now = Utc.with_ymd_and_hms(2026, 10, 19, 12, 0, 0).unwrap();
+    assert_eq!(window_start(None, now), now - Duration::hours(24));
+    assert_eq!(window_start(Some(0), now), now - Duration::hours(1));
+    assert_eq!(
+        window_start(Some(u32::MAX), now),
+        now - Duration::hours(i64::from(MAX_SINCE_HOURS))
+    );
+    assert_eq!(row_limit(None), 25);
+    assert_eq!(row_limit(Some(0)), 1);
+    assert_eq!(row_limit(Some(10_000)), i64::from(MAX_LIMIT));
+}
@@ -16,12 +16,19 @@ fn all_jobs_registered() {
         "content_analytics_aggregation",
         "content_prerender",
         "copy_extension_assets",
+        "cost_anomaly_scan",
+        "data_retention",
+        "digest_reports",
         "governance_bootstrap",
         "llms_txt_generation",
+        "master_key_rotation",
+        "otlp_trace_export",
         "publish_pipeline",
         "robots_txt_generation",
+        "secret_lifecycle",
         "secret_migration",
         "sitemap_generation",
+        "warehouse_export",
     ]
     .into();
     assert_eq!(names, expected);